@@ -0,0 +1,40 @@
+//! Scalar and SIMD throughput of the similarity scan's inner kernel on
+//! 384-dim vectors.
+//!
+//! Run with: `cargo bench --features simd,test-util --bench simd`
+
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use vipune::fixtures::{dot_and_norms, dot_and_norms_scalar, sample_vector};
+
+const ROWS: usize = 10_000;
+const DIMS: usize = 384;
+
+fn dot_and_norms_384_dims(c: &mut Criterion) {
+    let rows: Vec<Vec<f32>> = (0..ROWS as u64)
+        .map(|i| sample_vector(DIMS, i + 1))
+        .collect();
+    let query = sample_vector(DIMS, 99_999);
+
+    let mut group = c.benchmark_group("dot_and_norms_384_dims");
+    group.throughput(Throughput::Elements(ROWS as u64));
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            for row in &rows {
+                black_box(dot_and_norms_scalar(black_box(&query), black_box(row)));
+            }
+        })
+    });
+    group.bench_function("simd", |b| {
+        b.iter(|| {
+            for row in &rows {
+                black_box(dot_and_norms(black_box(&query), black_box(row)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dot_and_norms_384_dims);
+criterion_main!(benches);
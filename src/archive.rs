@@ -0,0 +1,371 @@
+//! Portable archives of a whole store, for `vipune archive`.
+//!
+//! An archive is a zstd-compressed tar file with two entries:
+//!
+//! - `manifest.json`: archive format version, vipune version, schema version,
+//!   embedding model ID, creation time, memory count, and the size and
+//!   SHA-256 of every other entry
+//! - `memories.db`: a compacted SQLite snapshot of the database, taken with
+//!   `VACUUM INTO` so it is consistent even while other processes write
+//!
+//! Import reads the manifest first and refuses unsupported formats, newer
+//! schemas and (without `force`) a different embedding model or a non-empty
+//! store. The snapshot is checksummed and opened (migrating older schemas and
+//! checking the FTS5 index) before it replaces the store's contents.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::sqlite::{Database, FtsCheck, SCHEMA_VERSION};
+
+/// Archive layout version written to and accepted from manifests.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Name of the manifest entry, always the first in the archive.
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Name of the database snapshot entry.
+const DATABASE_NAME: &str = "memories.db";
+
+/// Largest manifest accepted on import, so a corrupted archive cannot make us
+/// read an arbitrary amount into memory.
+const MAX_MANIFEST_BYTES: u64 = 1024 * 1024;
+
+/// Archive manifest, stored as `manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Archive layout version ([`FORMAT_VERSION`]).
+    pub format_version: u32,
+    /// vipune version that wrote the archive.
+    pub vipune_version: String,
+    /// Schema version of the database snapshot.
+    pub schema_version: u32,
+    /// Embedding model that produced the stored embeddings.
+    pub model_id: String,
+    /// When the archive was written (RFC3339).
+    pub created_at: String,
+    /// Memories in the snapshot (chunks not counted separately).
+    pub memories: usize,
+    /// Every entry other than the manifest, with its checksum.
+    pub files: Vec<ManifestFile>,
+}
+
+/// One checksummed archive entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Entry name inside the archive.
+    pub name: String,
+    /// Size in bytes.
+    pub size: u64,
+    /// Lowercase hex SHA-256 of the contents.
+    pub sha256: String,
+}
+
+/// Write the whole store to a new archive at `path`.
+///
+/// The archive is written next to `path` first and renamed into place, so an
+/// interrupted export never leaves a truncated archive behind.
+///
+/// # Errors
+///
+/// Returns error if the snapshot or the archive cannot be written.
+pub fn export_archive(store: &MemoryStore, path: &Path) -> Result<Manifest, Error> {
+    let snapshot = TempPath::new();
+    store.db.snapshot_to(&snapshot.0)?;
+    let (size, sha256) = checksum(&mut File::open(&snapshot.0)?, &mut io::sink())?;
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        vipune_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: store.db.schema_version()?,
+        model_id: store.model_id.clone(),
+        created_at: Utc::now().to_rfc3339(),
+        memories: store.db.count_all()?,
+        files: vec![ManifestFile {
+            name: DATABASE_NAME.to_string(),
+            size,
+            sha256,
+        }],
+    };
+
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let result = write_archive(&partial, &manifest, &snapshot.0);
+    if let Err(e) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, path)?;
+    log::debug!(
+        "Archived {} memories to {}",
+        manifest.memories,
+        path.display()
+    );
+    Ok(manifest)
+}
+
+/// Replace the store's contents with the archive at `path`.
+///
+/// Without `force`, refuses a store that already holds memories and an
+/// archive made with another embedding model.
+///
+/// # Errors
+///
+/// Returns `Error::Archive` if the archive is malformed, fails a checksum or
+/// is incompatible, and other errors if the store cannot be replaced.
+pub fn import_archive(
+    store: &mut MemoryStore,
+    path: &Path,
+    force: bool,
+) -> Result<Manifest, Error> {
+    let mut archive = tar::Archive::new(zstd::Decoder::new(File::open(path)?)?);
+    let mut entries = archive.entries()?;
+
+    let manifest = match entries.next() {
+        Some(entry) => read_manifest(entry?)?,
+        None => return Err(Error::Archive("archive is empty".to_string())),
+    };
+    check_compatible(store, &manifest, force)?;
+
+    let snapshot = TempPath::new();
+    let mut verified = false;
+    for entry in entries {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let expected = manifest
+            .files
+            .iter()
+            .find(|file| file.name == name)
+            .ok_or_else(|| Error::Archive(format!("unexpected entry '{}'", name)))?;
+        if name != DATABASE_NAME {
+            return Err(Error::Archive(format!("unsupported entry '{}'", name)));
+        }
+
+        let (size, sha256) = checksum(&mut entry, &mut File::create(&snapshot.0)?)?;
+        if size != expected.size || sha256 != expected.sha256 {
+            return Err(Error::Archive(format!("checksum mismatch for '{}'", name)));
+        }
+        verified = true;
+    }
+    if !verified {
+        return Err(Error::Archive(format!("missing entry '{}'", DATABASE_NAME)));
+    }
+
+    // Opening migrates an older schema and rebuilds a drifted FTS5 index
+    let check = FtsCheck {
+        integrity_check: true,
+        auto_repair: true,
+    };
+    drop(Database::open_with(
+        &snapshot.0,
+        check,
+        store.config.fts_tokenizer,
+    )?);
+    store.db.restore_from(&snapshot.0)?;
+    log::debug!(
+        "Restored {} memories from {}",
+        manifest.memories,
+        path.display()
+    );
+    Ok(manifest)
+}
+
+fn write_archive(path: &Path, manifest: &Manifest, snapshot: &Path) -> Result<(), Error> {
+    let mut builder = tar::Builder::new(zstd::Encoder::new(File::create(path)?, 0)?);
+
+    let json = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, json.as_slice())?;
+    builder.append_path_with_name(snapshot, DATABASE_NAME)?;
+
+    builder.into_inner()?.finish()?.sync_all()?;
+    Ok(())
+}
+
+fn read_manifest(entry: tar::Entry<'_, impl Read>) -> Result<Manifest, Error> {
+    if entry.path()?.as_os_str() != MANIFEST_NAME {
+        return Err(Error::Archive(format!(
+            "first entry must be {}",
+            MANIFEST_NAME
+        )));
+    }
+    let mut json = String::new();
+    entry.take(MAX_MANIFEST_BYTES).read_to_string(&mut json)?;
+    serde_json::from_str(&json).map_err(|e| Error::Archive(format!("bad manifest: {}", e)))
+}
+
+/// Check that `manifest` describes an archive this store can take.
+fn check_compatible(store: &MemoryStore, manifest: &Manifest, force: bool) -> Result<(), Error> {
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(Error::Archive(format!(
+            "unsupported format version {} (expected {})",
+            manifest.format_version, FORMAT_VERSION
+        )));
+    }
+    if manifest.schema_version > SCHEMA_VERSION {
+        return Err(Error::Archive(format!(
+            "schema version {} is newer than this vipune supports ({}); upgrade vipune",
+            manifest.schema_version, SCHEMA_VERSION
+        )));
+    }
+    if force {
+        return Ok(());
+    }
+    if manifest.model_id != store.model_id {
+        return Err(Error::Archive(format!(
+            "embeddings were made with {} but this store uses {} (use --force to import anyway)",
+            manifest.model_id, store.model_id
+        )));
+    }
+    let existing = store.db.count_all()?;
+    if existing > 0 {
+        return Err(Error::InvalidInput(format!(
+            "Database already holds {} memories (use --force to replace them)",
+            existing
+        )));
+    }
+    Ok(())
+}
+
+/// Copy `reader` to `writer`, returning the byte count and hex SHA-256.
+fn checksum(reader: &mut impl Read, writer: &mut impl Write) -> Result<(u64, String), Error> {
+    let mut hasher = hmac_sha256::Hash::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        size += read as u64;
+    }
+    writer.flush()?;
+    let digest = hasher.finalize();
+    Ok((size, digest.iter().map(|b| format!("{:02x}", b)).collect()))
+}
+
+/// Database file in the temp directory, removed (with its journal files) on drop.
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new() -> Self {
+        Self(std::env::temp_dir().join(format!("vipune-archive-{}.db", Uuid::new_v4())))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            let mut path = self.0.clone().into_os_string();
+            path.push(suffix);
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::embedding::EMBEDDING_DIMS;
+    use tempfile::TempDir;
+
+    fn store(dir: &TempDir, name: &str) -> MemoryStore {
+        MemoryStore::open_without_embedder(&dir.path().join(name), Config::default()).unwrap()
+    }
+
+    #[test]
+    fn test_export_then_import_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let source = store(&dir, "source.db");
+        let embedding = vec![0.1; EMBEDDING_DIMS];
+        source
+            .db
+            .insert("proj", "Alice works at Microsoft", &embedding, None)
+            .unwrap();
+        source
+            .db
+            .insert("other", "Bob likes tea", &embedding, None)
+            .unwrap();
+
+        let path = dir.path().join("store.vipune");
+        let manifest = export_archive(&source, &path).unwrap();
+        assert_eq!(manifest.memories, 2);
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+        assert!(!dir.path().join("store.vipune.partial").exists());
+
+        let mut target = store(&dir, "target.db");
+        let imported = import_archive(&mut target, &path, false).unwrap();
+        assert_eq!(imported.files[0].sha256, manifest.files[0].sha256);
+        assert_eq!(target.db.count_project("proj").unwrap(), 1);
+        assert_eq!(target.db.search_bm25("tea", "other", 10).unwrap().len(), 1);
+
+        // A second import would overwrite memories, so it needs --force
+        assert!(matches!(
+            import_archive(&mut target, &path, false),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(import_archive(&mut target, &path, true).is_ok());
+        assert_eq!(target.db.count_all().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_import_rejects_corrupted_and_foreign_archives() {
+        let dir = TempDir::new().unwrap();
+        let source = store(&dir, "source.db");
+        let path = dir.path().join("store.vipune");
+        let manifest = export_archive(&source, &path).unwrap();
+        let snapshot = dir.path().join("snapshot.db");
+        source.db.snapshot_to(&snapshot).unwrap();
+
+        let rewrite = |manifest: &Manifest| {
+            let out = dir.path().join("bad.vipune");
+            write_archive(&out, manifest, &snapshot).unwrap();
+            out
+        };
+        let mut target = store(&dir, "target.db");
+
+        let tampered = Manifest {
+            files: vec![ManifestFile {
+                sha256: "0".repeat(64),
+                ..manifest.files[0].clone()
+            }],
+            ..manifest.clone()
+        };
+        let err = import_archive(&mut target, &rewrite(&tampered), false).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+
+        let newer = Manifest {
+            schema_version: SCHEMA_VERSION + 1,
+            ..manifest.clone()
+        };
+        assert!(matches!(
+            import_archive(&mut target, &rewrite(&newer), true),
+            Err(Error::Archive(_))
+        ));
+
+        let other_model = Manifest {
+            model_id: "other/model".to_string(),
+            ..manifest.clone()
+        };
+        assert!(import_archive(&mut target, &rewrite(&other_model), false).is_err());
+        assert!(import_archive(&mut target, &rewrite(&other_model), true).is_ok());
+
+        fs::write(dir.path().join("junk.vipune"), b"not an archive").unwrap();
+        assert!(import_archive(&mut target, &dir.path().join("junk.vipune"), true).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Cancellation and timeouts of long store operations.
+//!
+//! A [`CancellationToken`] lets a host abort a search from another thread
+//! (see [`MemoryStore::query_with_cancel`](crate::MemoryStore::query_with_cancel)),
+//! and `limits.timeout_ms` bounds every embedding run and search. Both are
+//! checked between the steps of an operation, and a model run in progress is
+//! terminated by a watchdog thread, so a hung ONNX run or a huge input does
+//! not block the caller indefinitely.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::errors::Error;
+
+/// How often a watchdog checks whether a model run must be terminated.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Handle for aborting an operation from another thread.
+///
+/// Clones share their state: cancelling one cancels all of them.
+///
+/// # Example
+///
+/// ```
+/// use vipune::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let handle = token.clone();
+/// std::thread::spawn(move || handle.cancel()).join().unwrap();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// A token that has not been cancelled.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask operations using this token to stop; they fail with
+    /// `Error::Cancelled`.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`CancellationToken::cancel`] was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// When an operation must stop: once its token is cancelled or its
+/// deadline has passed. The default never interrupts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Interrupt {
+    token: Option<CancellationToken>,
+    deadline: Option<(Instant, u64)>,
+}
+
+impl Interrupt {
+    /// Interrupt on `token`, or once `timeout_ms` have passed from now.
+    pub(crate) fn new(token: Option<CancellationToken>, timeout_ms: Option<u64>) -> Self {
+        Self {
+            token,
+            deadline: timeout_ms.map(|ms| (Instant::now() + Duration::from_millis(ms), ms)),
+        }
+    }
+
+    /// Whether this can interrupt anything.
+    pub(crate) fn is_set(&self) -> bool {
+        self.token.is_some() || self.deadline.is_some()
+    }
+
+    /// Fail if the operation must stop.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Cancelled` once the token is cancelled, or
+    /// `Error::Timeout` once the deadline has passed.
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        if self
+            .token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(Error::Cancelled);
+        }
+        match self.deadline {
+            Some((deadline, ms)) if Instant::now() >= deadline => Err(Error::Timeout(ms)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Call `run`, calling `terminate` from a watchdog thread if the
+    /// operation must stop before `run` returns.
+    pub(crate) fn watch<T>(&self, terminate: impl Fn() + Sync, run: impl FnOnce() -> T) -> T {
+        if !self.is_set() {
+            return run();
+        }
+        let (done, finished) = mpsc::channel::<()>();
+        let terminate = &terminate;
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(POLL_INTERVAL) {
+                    if self.check().is_err() {
+                        terminate();
+                        break;
+                    }
+                }
+            });
+            let result = run();
+            drop(done);
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reports_cancellation_and_timeout() {
+        assert!(Interrupt::default().check().is_ok());
+        assert!(!Interrupt::default().is_set());
+
+        let token = CancellationToken::new();
+        let interrupt = Interrupt::new(Some(token.clone()), None);
+        assert!(interrupt.check().is_ok());
+        token.cancel();
+        assert!(matches!(interrupt.check(), Err(Error::Cancelled)));
+
+        let expired = Interrupt::new(None, Some(0));
+        assert!(matches!(expired.check(), Err(Error::Timeout(0))));
+    }
+
+    #[test]
+    fn test_watch_terminates_interrupted_runs() {
+        let terminated = AtomicBool::new(false);
+        let token = CancellationToken::new();
+        let interrupt = Interrupt::new(Some(token.clone()), None);
+        interrupt.watch(
+            || terminated.store(true, Ordering::Relaxed),
+            || {
+                token.cancel();
+                // Stand-in for a model run that stops once terminated
+                while !terminated.load(Ordering::Relaxed) {
+                    thread::sleep(POLL_INTERVAL);
+                }
+            },
+        );
+        assert!(terminated.load(Ordering::Relaxed));
+
+        // Runs that finish in time are left alone
+        let terminated = AtomicBool::new(false);
+        let value = Interrupt::new(None, Some(60_000))
+            .watch(|| terminated.store(true, Ordering::Relaxed), || 7);
+        assert_eq!(value, 7);
+        assert!(!terminated.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,85 @@
+//! Splitting long content into overlapping word-window chunks.
+//!
+//! Chunks are slices of the original text, so whitespace and line breaks
+//! inside a chunk are preserved.
+
+/// Split `text` into windows of at most `chunk_size` words, with consecutive
+/// windows sharing `overlap` words.
+///
+/// Returns an empty vector when the text fits in a single chunk (nothing to
+/// split). `overlap` must be less than `chunk_size` (enforced by config
+/// validation); larger values are clamped.
+pub fn split_into_chunks(text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+    let spans = word_spans(text);
+    if chunk_size == 0 || spans.len() <= chunk_size {
+        return Vec::new();
+    }
+
+    let step = chunk_size - overlap.min(chunk_size - 1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + chunk_size).min(spans.len());
+        chunks.push(text[spans[start].0..spans[end - 1].1].to_string());
+        if end == spans.len() {
+            break;
+        }
+        start += step;
+    }
+    chunks
+}
+
+/// Byte ranges of whitespace-separated words.
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_text_is_not_split() {
+        assert!(split_into_chunks("one two three", 3, 1).is_empty());
+        assert!(split_into_chunks("", 3, 1).is_empty());
+    }
+
+    #[test]
+    fn test_chunks_overlap() {
+        let chunks = split_into_chunks("a b c d e f g", 3, 1);
+        assert_eq!(chunks, vec!["a b c", "c d e", "e f g"]);
+    }
+
+    #[test]
+    fn test_last_chunk_may_be_shorter() {
+        let chunks = split_into_chunks("a b c d e", 3, 0);
+        assert_eq!(chunks, vec!["a b c", "d e"]);
+    }
+
+    #[test]
+    fn test_chunks_preserve_original_text() {
+        let chunks = split_into_chunks("first  line\nsecond line\n\nthird", 3, 1);
+        assert_eq!(chunks, vec!["first  line\nsecond", "second line\n\nthird"]);
+    }
+
+    #[test]
+    fn test_overlap_is_clamped() {
+        let chunks = split_into_chunks("a b c d", 2, 5);
+        assert_eq!(chunks, vec!["a b", "b c", "c d"]);
+    }
+}
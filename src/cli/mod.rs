@@ -0,0 +1,131 @@
+//! Global flags of the vipune CLI and dispatch of the parsed command.
+
+use clap::{CommandFactory, Parser};
+use std::process::ExitCode;
+
+use crate::commands::{self, Commands};
+use crate::config;
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::output::Printer;
+use crate::project::detect_project_with;
+
+/// vipune - A minimal memory layer for AI agents
+#[derive(Parser)]
+#[command(name = "vipune", about = "Minimal memory layer for AI agents", long_about = None)]
+pub struct Cli {
+    /// Output as JSON (default: human-readable)
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Print only memory IDs and requested data, no status messages
+    /// (exit codes still report the outcome)
+    #[arg(short = 'q', long, global = true)]
+    pub quiet: bool,
+
+    /// Never color output (also disabled by NO_COLOR or when not a terminal)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Show timestamps in human output as relative times ("3 days ago")
+    #[arg(long, global = true)]
+    pub relative_times: bool,
+
+    /// Fail searches, and model runs outside them, that take longer than
+    /// this many milliseconds (default: the configured `limits.timeout_ms`)
+    #[arg(long, global = true, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout_ms: Option<u64>,
+
+    /// Project identifier (auto-detected from git if omitted)
+    #[arg(long, short = 'p', global = true)]
+    pub project: Option<String>,
+
+    /// Override database path (":memory:" for a throwaway in-memory database)
+    #[arg(long, global = true)]
+    pub db_path: Option<String>,
+
+    /// Use the settings of this config file profile ([profiles.<name>];
+    /// default: VIPUNE_PROFILE)
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Add memories as this owner and see only theirs and shared ones
+    /// (default: the configured `owner`)
+    #[arg(long, global = true)]
+    pub owner: Option<String>,
+
+    /// Log diagnostics to stderr (-v info, -vv debug, -vvv trace; default: VIPUNE_LOG or warn)
+    #[arg(short = 'v', long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+/// Run a parsed command, loading the config and opening the store it needs.
+pub fn run(cli: &Cli, out: &Printer) -> Result<ExitCode, Error> {
+    // Generated artifacts need neither config nor database, and listing
+    // profiles reads the config file itself
+    match &cli.command {
+        Commands::Completions { shell } => {
+            return commands::print_completions(*shell, &mut Cli::command());
+        }
+        Commands::Manpage => return commands::print_manpage(Cli::command()),
+        Commands::Schema { response } => return commands::print_schema(*response),
+        Commands::Config { action } => {
+            return commands::handle_config(action, cli.profile.as_deref(), out);
+        }
+        _ => {}
+    }
+
+    let mut config = config::Config::load_profile(cli.profile.as_deref())?;
+    config.ensure_directories()?;
+
+    if let Some(db_path) = &cli.db_path {
+        config.database_path = db_path.clone().into();
+    }
+    if cli.command.allows_secrets() {
+        config.secret_scan = false;
+    }
+    if let Some(owner) = &cli.owner {
+        config.owner = Some(owner.clone());
+    }
+    if let Some(timeout_ms) = cli.timeout_ms {
+        config.limits.timeout_ms = Some(timeout_ms);
+    }
+    if let Some(visibility) = cli.command.visibility() {
+        config.visibility = visibility;
+    }
+
+    let project_id =
+        detect_project_with(cli.project.as_deref(), config.monorepo_mode).into_string();
+
+    let mut store = if cli.command.switches_model() {
+        MemoryStore::open_for_reembed(
+            &config.database_path,
+            &config.embedding_model,
+            config.clone(),
+        )?
+    } else if cli.command.needs_embedder() {
+        MemoryStore::new(
+            &config.database_path,
+            &config.embedding_model,
+            config.clone(),
+        )?
+    } else {
+        MemoryStore::open_without_embedder(&config.database_path, config.clone())?
+    };
+    store.set_owner(config.owner.clone(), config.visibility)?;
+
+    commands::execute(
+        &cli.command,
+        &mut store,
+        project_id,
+        cli.project.as_deref(),
+        &config,
+        out,
+    )
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,358 @@
+//! Parsing of maintenance and inspection commands.
+
+use clap::Parser;
+
+use crate::cli::Cli;
+use crate::commands::{Commands, ReviewArgs, SearchArgs};
+
+#[test]
+fn test_cli_parse_reindex() {
+    let cli = Cli::parse_from(["vipune", "reindex", "--tokenizer", "unicode61"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Reindex {
+            tokenizer: Some(crate::sqlite::FtsTokenizer::Unicode61)
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "reindex"]);
+    assert!(matches!(cli.command, Commands::Reindex { tokenizer: None }));
+    assert!(Cli::try_parse_from(["vipune", "reindex", "--tokenizer", "snowball"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_clusters() {
+    let cli = Cli::parse_from(["vipune", "clusters", "--k", "4", "--format", "csv"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Clusters {
+            k: 4,
+            format: Some(crate::commands::ClusterFormat::Csv)
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+    let cli = Cli::parse_from(["vipune", "clusters"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Clusters {
+            k: 10,
+            format: None
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parse_trash() {
+    let cli = Cli::parse_from(["vipune", "trash", "restore", "abc"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Trash {
+            action: crate::commands::TrashAction::Restore { ref id }
+        } if id == "abc"
+    ));
+    assert!(!cli.command.needs_embedder());
+    assert!(Cli::try_parse_from(["vipune", "trash", "restore"]).is_err());
+    let cli = Cli::parse_from(["vipune", "trash", "empty"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Trash {
+            action: crate::commands::TrashAction::Empty
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parse_synonym() {
+    let cli = Cli::parse_from(["vipune", "synonym", "add", "k8s", "kubernetes", "kube"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Synonym {
+            action: crate::commands::SynonymAction::Add { ref term, ref synonyms }
+        } if term == "k8s" && synonyms == &["kubernetes", "kube"]
+    ));
+    assert!(!cli.command.needs_embedder());
+    assert!(Cli::try_parse_from(["vipune", "synonym", "add", "k8s"]).is_err());
+
+    let cli = Cli::parse_from(["vipune", "synonym", "remove", "k8s"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Synonym {
+            action: crate::commands::SynonymAction::Remove { synonym: None, .. }
+        }
+    ));
+
+    let cli = Cli::parse_from(["vipune", "search", "k8s", "--expand-synonyms"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            expand_synonyms: true,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_cli_parse_quantize() {
+    let cli = Cli::parse_from(["vipune", "quantize", "--format", "int8"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Quantize {
+            format: Some(crate::sqlite::EmbeddingFormat::Int8)
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "quantize"]);
+    assert!(matches!(cli.command, Commands::Quantize { format: None }));
+    assert!(Cli::try_parse_from(["vipune", "quantize", "--format", "f16"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_review() {
+    let cli = Cli::parse_from(["vipune", "review", "--days", "30"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Review(ReviewArgs {
+            days: Some(30),
+            limit: 20,
+            ..
+        })
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from([
+        "vipune", "review", "--keep", "a", "--keep", "b", "--delete", "c",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Review(ReviewArgs { ref keep, ref delete, .. }) if keep.len() == 2 && delete == &["c"]
+    ));
+}
+
+#[test]
+fn test_cli_parse_reembed() {
+    let cli = Cli::parse_from(["vipune", "reembed", "--mismatched-only"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Reembed {
+            mismatched_only: true
+        }
+    ));
+    assert!(cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "reembed"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Reembed {
+            mismatched_only: false
+        }
+    ));
+    assert!(cli.command.switches_model());
+    assert!(
+        !Cli::parse_from(["vipune", "reembed", "--mismatched-only"])
+            .command
+            .switches_model()
+    );
+}
+
+#[test]
+fn test_cli_parse_verify_embeddings() {
+    let cli = Cli::parse_from(["vipune", "verify-embeddings"]);
+    assert!(matches!(
+        cli.command,
+        Commands::VerifyEmbeddings {
+            sample: 100,
+            seed: None,
+            ..
+        }
+    ));
+    assert!(cli.command.needs_embedder());
+
+    let cli = Cli::parse_from([
+        "vipune",
+        "verify-embeddings",
+        "--sample",
+        "10",
+        "--seed",
+        "42",
+        "--tolerance",
+        "0.01",
+    ]);
+    let Commands::VerifyEmbeddings {
+        sample,
+        seed,
+        tolerance,
+    } = cli.command
+    else {
+        panic!("expected verify-embeddings");
+    };
+    assert_eq!((sample, seed, tolerance), (10, Some(42), 0.01));
+}
+
+#[test]
+fn test_cli_parse_stats() {
+    let cli = Cli::parse_from(["vipune", "stats"]);
+    assert!(matches!(cli.command, Commands::Stats { embeddings: false }));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "stats", "--embeddings"]);
+    assert!(matches!(cli.command, Commands::Stats { embeddings: true }));
+}
+
+#[test]
+fn test_cli_parse_meta_set() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "--project",
+        "x",
+        "meta",
+        "set",
+        "status=done",
+        "n=3",
+        "--tag",
+        "old",
+        "--dry-run",
+    ]);
+    assert_eq!(cli.project.as_deref(), Some("x"));
+    match &cli.command {
+        Commands::Meta {
+            action:
+                crate::commands::MetaAction::Set {
+                    assignments,
+                    tags,
+                    dry_run,
+                    ..
+                },
+        } => {
+            assert_eq!(assignments, &["status=done", "n=3"]);
+            assert_eq!(tags, &["old"]);
+            assert!(*dry_run);
+        }
+        _ => panic!("expected meta set"),
+    }
+    assert!(!cli.command.needs_embedder());
+    assert!(Cli::try_parse_from(["vipune", "meta", "set", "--tag", "old"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_project_normalize() {
+    let cli = Cli::parse_from(["vipune", "project", "normalize", "--dry-run"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Project {
+            action: crate::commands::ProjectAction::Normalize { dry_run: true }
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    // The subcommand and the global --project flag coexist
+    let cli = Cli::parse_from(["vipune", "--project", "x", "project", "normalize"]);
+    assert_eq!(cli.project.as_deref(), Some("x"));
+}
+
+#[test]
+fn test_cli_parse_project_prune() {
+    let cli = Cli::parse_from(["vipune", "project", "prune", "--dry-run"]);
+    match &cli.command {
+        Commands::Project {
+            action:
+                crate::commands::ProjectAction::Prune {
+                    older_than,
+                    empty_only,
+                    yes,
+                    dry_run,
+                },
+        } => {
+            assert_eq!(older_than, "180d");
+            assert!(!empty_only);
+            assert!(!yes);
+            assert!(*dry_run);
+        }
+        _ => panic!("expected project prune"),
+    }
+    assert!(!cli.command.needs_embedder());
+
+    // Deleting needs --yes (or --force); without it the prune is a dry run
+    for flag in ["--yes", "-y", "--force"] {
+        let cli = Cli::parse_from(["vipune", "project", "prune", flag]);
+        assert!(matches!(
+            cli.command,
+            Commands::Project {
+                action: crate::commands::ProjectAction::Prune { yes: true, .. }
+            }
+        ));
+    }
+    assert!(Cli::try_parse_from(["vipune", "project", "prune", "--yes", "--dry-run"]).is_err());
+
+    assert!(Cli::try_parse_from(["vipune", "project", "prune", "--empty-only"]).is_ok());
+    assert!(
+        Cli::try_parse_from([
+            "vipune",
+            "project",
+            "prune",
+            "--empty-only",
+            "--older-than",
+            "30d"
+        ])
+        .is_err()
+    );
+}
+
+#[test]
+fn test_cli_parse_embedding() {
+    let cli = Cli::parse_from(["vipune", "embedding", "show", "abc", "--values", "4"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Embedding {
+            action: crate::commands::EmbeddingAction::Show { values: 4, .. }
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "embedding", "compare", "a", "b"]);
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "embedding", "query", "where is alice", "a"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Embedding {
+            action: crate::commands::EmbeddingAction::Query { ref text, .. }
+        } if text == "where is alice"
+    ));
+    assert!(cli.command.needs_embedder());
+    assert!(Cli::try_parse_from(["vipune", "embedding", "compare", "a"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_model_download() {
+    let cli = Cli::parse_from(["vipune", "model", "download", "--model", "org/model"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Model {
+            action: crate::commands::ModelAction::Download { model: Some(ref m) }
+        } if m == "org/model"
+    ));
+}
+
+#[test]
+fn test_cli_parse_health() {
+    let cli = Cli::parse_from(["vipune", "health", "--json"]);
+    assert!(cli.json);
+    assert!(matches!(cli.command, Commands::Health));
+    assert!(cli.command.needs_embedder());
+}
+
+#[test]
+fn test_cli_parse_doctor() {
+    let cli = Cli::parse_from(["vipune", "doctor", "--scan-embeddings", "--dry-run"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Doctor {
+            scan_embeddings: true,
+            dry_run: true
+        }
+    ));
+    assert!(cli.command.needs_embedder());
+    assert!(Cli::try_parse_from(["vipune", "doctor", "--dry-run"]).is_err());
+}
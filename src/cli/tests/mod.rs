@@ -0,0 +1,134 @@
+//! Tests for CLI parsing, one file per command group.
+
+mod maintenance;
+mod records;
+mod search;
+mod transfer;
+
+use clap::{CommandFactory, Parser};
+
+use crate::cli::Cli;
+use crate::commands::Commands;
+
+#[test]
+fn test_cli_parse_with_json() {
+    let cli = Cli::parse_from(["vipune", "--json", "add", "test"]);
+    assert!(cli.json);
+}
+
+#[test]
+fn test_cli_parse_quiet_and_no_color() {
+    let cli = Cli::parse_from(["vipune", "add", "test", "-q", "--no-color"]);
+    assert!(cli.quiet && cli.no_color);
+
+    let cli = Cli::parse_from(["vipune", "--quiet", "list"]);
+    assert!(cli.quiet && !cli.no_color);
+}
+
+#[test]
+fn test_cli_parse_with_project() {
+    let cli = Cli::parse_from(["vipune", "-p", "my-project", "add", "test"]);
+    assert_eq!(cli.project, Some("my-project".to_string()));
+}
+
+#[test]
+fn test_cli_origin_from_environment() {
+    use crate::commands::cli_origin;
+    use crate::sqlite::Origin;
+    assert_eq!(cli_origin(None, false).unwrap(), Origin::Cli);
+    assert_eq!(cli_origin(None, true).unwrap(), Origin::Hook);
+    assert_eq!(cli_origin(Some("mcp"), true).unwrap(), Origin::Mcp);
+    assert!(cli_origin(Some("email"), false).is_err());
+}
+
+#[test]
+fn test_cli_parse_version() {
+    let cli = Cli::parse_from(["vipune", "version"]);
+    matches!(cli.command, Commands::Version);
+}
+
+#[test]
+fn test_cli_parse_profile() {
+    let cli = Cli::parse_from(["vipune", "--profile", "work", "search", "deploys"]);
+    assert_eq!(cli.profile.as_deref(), Some("work"));
+
+    let cli = Cli::parse_from(["vipune", "config", "profiles", "--profile", "personal"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Config {
+            action: crate::commands::ConfigAction::Profiles
+        }
+    ));
+    assert_eq!(cli.profile.as_deref(), Some("personal"));
+    assert!(!cli.command.needs_embedder());
+}
+
+#[test]
+fn test_needs_embedder() {
+    let needs = |args: &[&str]| Cli::parse_from(args).command.needs_embedder();
+    assert!(needs(&["vipune", "add", "text"]));
+    assert!(needs(&["vipune", "search", "query"]));
+    assert!(needs(&["vipune", "update", "id", "text"]));
+    assert!(!needs(&["vipune", "get", "id"]));
+    assert!(!needs(&["vipune", "list"]));
+    assert!(!needs(&["vipune", "delete", "id"]));
+    assert!(!needs(&["vipune", "prune"]));
+    assert!(!needs(&["vipune", "model", "download"]));
+    assert!(!needs(&["vipune", "audit", "--since", "7d"]));
+    assert!(!needs(&["vipune", "version"]));
+}
+
+#[test]
+fn test_cli_parse_timeout() {
+    let cli = Cli::parse_from(["vipune", "search", "deploys", "--timeout-ms", "5000"]);
+    assert_eq!(cli.timeout_ms, Some(5000));
+    assert!(Cli::try_parse_from(["vipune", "--timeout-ms", "0", "list"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_with_db_path() {
+    let cli = Cli::parse_from(["vipune", "--db-path", "/custom/path.db", "add", "test"]);
+    assert_eq!(cli.db_path, Some("/custom/path.db".to_string()));
+}
+
+#[test]
+fn test_cli_parse_verbosity() {
+    let cli = Cli::parse_from(["vipune", "list"]);
+    assert_eq!(cli.verbose, 0);
+    let cli = Cli::parse_from(["vipune", "-vv", "list"]);
+    assert_eq!(cli.verbose, 2);
+    let cli = Cli::parse_from(["vipune", "search", "query", "-v"]);
+    assert_eq!(cli.verbose, 1);
+}
+
+#[test]
+fn test_cli_parse_completions() {
+    let cli = Cli::parse_from(["vipune", "completions", "zsh"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Completions {
+            shell: clap_complete::Shell::Zsh
+        }
+    ));
+    assert!(Cli::try_parse_from(["vipune", "completions", "tcsh"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_schema() {
+    let cli = Cli::parse_from(["vipune", "schema", "search"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Schema {
+            response: Some(crate::commands::SchemaKind::Search)
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+    let cli = Cli::parse_from(["vipune", "schema"]);
+    assert!(matches!(cli.command, Commands::Schema { response: None }));
+    assert!(Cli::try_parse_from(["vipune", "schema", "stats"]).is_err());
+}
+
+#[test]
+fn test_cli_definition_is_valid() {
+    Cli::command().debug_assert();
+}
@@ -0,0 +1,254 @@
+//! Parsing of the single-memory commands (add, get, list, update, ...).
+
+use clap::Parser;
+
+use crate::cli::Cli;
+use crate::commands::{Commands, SearchArgs};
+
+#[test]
+fn test_cli_parse_add() {
+    let cli = Cli::parse_from(["vipune", "add", "test content"]);
+    assert!(!cli.json);
+    assert!(cli.project.is_none());
+    assert!(cli.db_path.is_none());
+    matches!(cli.command, Commands::Add { .. });
+}
+
+#[test]
+fn test_cli_parse_get() {
+    let cli = Cli::parse_from(["vipune", "get", "memory-id"]);
+    matches!(cli.command, Commands::Get { id } if id == "memory-id");
+}
+
+#[test]
+fn test_cli_parse_history() {
+    let cli = Cli::parse_from(["vipune", "history", "memory-id", "--diff"]);
+    assert!(matches!(
+        cli.command,
+        Commands::History { ref id, diff: true } if id == "memory-id"
+    ));
+    let cli = Cli::parse_from(["vipune", "history", "memory-id"]);
+    assert!(matches!(cli.command, Commands::History { diff: false, .. }));
+}
+
+#[test]
+fn test_cli_parse_find() {
+    let cli = Cli::parse_from(["vipune", "find", "--exact", "Alice works at Microsoft"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Find { ref exact, icase: false } if exact == "Alice works at Microsoft"
+    ));
+    let cli = Cli::parse_from(["vipune", "find", "--icase", "--exact", "alice"]);
+    assert!(matches!(cli.command, Commands::Find { icase: true, .. }));
+    assert!(Cli::try_parse_from(["vipune", "find", "--icase"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_list() {
+    let cli = Cli::parse_from(["vipune", "list"]);
+    matches!(cli.command, Commands::List { .. });
+}
+
+#[test]
+fn test_cli_parse_list_time_range_and_relative_times() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "list",
+        "--since",
+        "2w",
+        "--before",
+        "yesterday",
+        "--relative-times",
+    ]);
+    assert!(cli.relative_times);
+    assert_eq!(cli.timeout_ms, None);
+    assert!(matches!(
+        cli.command,
+        Commands::List { since, before, .. }
+            if since.as_deref() == Some("2w") && before.as_deref() == Some("yesterday")
+    ));
+}
+
+#[test]
+fn test_cli_parse_delete() {
+    let cli = Cli::parse_from(["vipune", "delete", "memory-id"]);
+    matches!(cli.command, Commands::Delete { id } if id == "memory-id");
+}
+
+#[test]
+fn test_cli_parse_update() {
+    let cli = Cli::parse_from(["vipune", "update", "memory-id", "new content"]);
+    matches!(
+        cli.command,
+        Commands::Update { id, text, .. } if id == "memory-id" && text.as_deref() == Some("new content")
+    );
+}
+
+#[test]
+fn test_cli_parse_update_metadata_only_and_touch() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "update",
+        "memory-id",
+        "--metadata-only",
+        "-m",
+        r#"{"topic":"db"}"#,
+    ]);
+    assert!(matches!(
+        &cli.command,
+        Commands::Update { text: None, metadata: Some(m), metadata_only: true, .. }
+            if m == r#"{"topic":"db"}"#
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "update", "memory-id", "--touch"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Update {
+            text: None,
+            touch: true,
+            ..
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "update", "memory-id", "text", "-m", "null"]);
+    assert!(cli.command.needs_embedder());
+
+    for args in [
+        &["vipune", "update", "memory-id"][..],
+        &["vipune", "update", "memory-id", "--metadata-only"],
+        &["vipune", "update", "memory-id", "text", "--touch"],
+        &[
+            "vipune",
+            "update",
+            "memory-id",
+            "text",
+            "--metadata-only",
+            "-m",
+            "{}",
+        ],
+    ] {
+        assert!(Cli::try_parse_from(args).is_err(), "{args:?}");
+    }
+}
+
+#[test]
+fn test_cli_parse_origin_filters() {
+    let cli = Cli::parse_from(["vipune", "list", "--origin", "import:notes.md"]);
+    assert!(matches!(
+        cli.command,
+        Commands::List { origin: Some(crate::sqlite::Origin::Import { source }), .. }
+            if source == "notes.md"
+    ));
+    let cli = Cli::parse_from(["vipune", "search", "q", "--origin", "mcp"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            origin: Some(crate::sqlite::Origin::Mcp),
+            ..
+        })
+    ));
+    assert!(Cli::try_parse_from(["vipune", "list", "--origin", "email"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_edit() {
+    let cli = Cli::parse_from(["vipune", "edit", "memory-id", "--yes"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Edit { ref id, yes: true, force: false, .. } if id == "memory-id"
+    ));
+    assert!(cli.command.needs_embedder());
+}
+
+#[test]
+fn test_cli_parse_owner_and_visibility() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "add",
+        "note",
+        "--visibility",
+        "private",
+        "--owner",
+        "alice",
+    ]);
+    assert_eq!(cli.owner.as_deref(), Some("alice"));
+    assert_eq!(
+        cli.command.visibility(),
+        Some(crate::sqlite::Visibility::Private)
+    );
+    assert!(Cli::try_parse_from(["vipune", "add", "note", "--visibility", "public"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_append() {
+    let cli = Cli::parse_from(["vipune", "append", "id-1", "more detail", "--allow-secrets"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Append { ref id, ref text, .. } if id == "id-1" && text == "more detail"
+    ));
+    assert!(cli.command.allows_secrets());
+    assert!(cli.command.needs_embedder());
+}
+
+#[test]
+fn test_cli_parse_kind() {
+    let cli = Cli::parse_from(["vipune", "add", "use postgres", "--kind", "decision"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Add {
+            kind: Some(crate::sqlite::MemoryKind::Decision),
+            ..
+        }
+    ));
+
+    let cli = Cli::parse_from(["vipune", "list", "--kind", "task"]);
+    assert!(matches!(
+        cli.command,
+        Commands::List {
+            kind: Some(crate::sqlite::MemoryKind::Task),
+            ..
+        }
+    ));
+
+    assert!(Cli::try_parse_from(["vipune", "add", "x", "--kind", "rumor"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_add_dry_run() {
+    let cli = Cli::parse_from(["vipune", "add", "x", "--dry-run"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Add {
+            dry_run: true,
+            force: false,
+            ..
+        }
+    ));
+    assert!(cli.command.needs_embedder());
+    assert!(Cli::try_parse_from(["vipune", "add", "x", "--dry-run", "--force"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_add_title() {
+    let cli = Cli::parse_from(["vipune", "add", "x", "--title", "Release schedule"]);
+    match cli.command {
+        Commands::Add { title, .. } => assert_eq!(title.as_deref(), Some("Release schedule")),
+        _ => panic!("expected add"),
+    }
+}
+
+#[test]
+fn test_cli_parse_allow_secrets() {
+    let cli = Cli::parse_from(["vipune", "add", "x", "--allow-secrets"]);
+    assert!(cli.command.allows_secrets());
+    let cli = Cli::parse_from(["vipune", "update", "id", "x", "--allow-secrets"]);
+    assert!(cli.command.allows_secrets());
+    let cli = Cli::parse_from(["vipune", "edit", "id", "--allow-secrets"]);
+    assert!(cli.command.allows_secrets());
+
+    let cli = Cli::parse_from(["vipune", "add", "x"]);
+    assert!(!cli.command.allows_secrets());
+    assert!(Cli::try_parse_from(["vipune", "search", "x", "--allow-secrets"]).is_err());
+}
@@ -0,0 +1,455 @@
+//! Parsing of `vipune search` and `vipune context`.
+
+use clap::Parser;
+
+use crate::cli::Cli;
+use crate::commands::{Commands, SearchArgs};
+use crate::rrf::FusionStrategy;
+
+#[test]
+fn test_cli_parse_search() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--limit", "10"]);
+    matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            query,
+            limit: 10,
+            ..
+        }) if query.as_deref() == Some("query")
+    );
+}
+
+#[test]
+fn test_cli_parse_search_query_syntax() {
+    let cli = Cli::parse_from(["vipune", "search", "auth", "--hybrid", "--prefix"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            prefix: true,
+            query_syntax: false,
+            ..
+        })
+    ));
+    let cli = Cli::parse_from(["vipune", "search", "auth*", "--hybrid", "--query-syntax"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            query_syntax: true,
+            ..
+        })
+    ));
+    // Keyword syntax needs keyword search, and one way to read it
+    assert!(Cli::try_parse_from(["vipune", "search", "auth", "--prefix"]).is_err());
+    assert!(
+        Cli::try_parse_from([
+            "vipune",
+            "search",
+            "auth",
+            "--hybrid",
+            "--prefix",
+            "--query-syntax"
+        ])
+        .is_err()
+    );
+}
+
+#[test]
+fn test_cli_parse_search_details() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--details"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { details: true, .. })
+    ));
+    let cli = Cli::parse_from(["vipune", "search", "query"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { details: false, .. })
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_exclusions() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "search",
+        "database tuning",
+        "--not",
+        "postgres",
+        "--not",
+        "connection pool",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { ref exclude, .. })
+            if exclude == &["postgres".to_string(), "connection pool".to_string()]
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_tags_and_since() {
+    let cli = Cli::parse_from([
+        "vipune", "search", "deploys", "--tag", "rust", "--tag", "db", "--since", "7d",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { ref tags, ref since, .. })
+            if tags == &["rust".to_string(), "db".to_string()]
+                && since.as_deref() == Some("7d")
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_include_inactive() {
+    for flag in ["--include-inactive", "--include-archived"] {
+        let cli = Cli::parse_from(["vipune", "search", "deploys", flag]);
+        assert!(matches!(
+            cli.command,
+            Commands::Search(SearchArgs {
+                include_inactive: true,
+                ..
+            })
+        ));
+    }
+}
+
+#[test]
+fn test_cli_parse_search_rerank() {
+    let cli = Cli::parse_from(["vipune", "search", "deploys", "--hybrid", "--rerank"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            hybrid: true,
+            rerank: true,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_late_interaction() {
+    let cli = Cli::parse_from(["vipune", "search", "deploys", "--late-interaction"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            late_interaction: true,
+            rerank: false,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_min_seq() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "search",
+        "query",
+        "--min-seq",
+        "12",
+        "--wait-ms",
+        "500",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            min_seq: Some(12),
+            wait_ms: 500,
+            ..
+        })
+    ));
+    assert!(Cli::try_parse_from(["vipune", "search", "query", "--wait-ms", "500"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_search_group_by_project() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "search",
+        "query",
+        "--all-projects",
+        "--group-by",
+        "project",
+        "--per-project",
+        "2",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            all_projects: true,
+            group_by: Some(crate::commands::GroupBy::Project),
+            per_project: 2,
+            ..
+        })
+    ));
+    let cli = Cli::parse_from(["vipune", "search", "query", "--all-projects"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            group_by: None,
+            per_project: 3,
+            ..
+        })
+    ));
+    assert!(Cli::try_parse_from(["vipune", "search", "query", "--group-by", "project"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_fields() {
+    let cli = Cli::parse_from(["vipune", "list", "--fields", "id"]);
+    assert!(matches!(
+        cli.command,
+        Commands::List { fields: Some(ref fields), .. } if fields == "id"
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "search", "q", "--fields", "id,score,updated_at"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { fields: Some(ref fields), .. }) if fields == "id,score,updated_at"
+    ));
+}
+
+#[test]
+fn test_cli_parse_via_daemon() {
+    let cli = Cli::parse_from(["vipune", "daemon"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Daemon { metrics_file: None }
+    ));
+    assert!(cli.command.needs_embedder());
+    let cli = Cli::parse_from(["vipune", "daemon", "--metrics-file", "v.prom"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Daemon {
+            metrics_file: Some(_)
+        }
+    ));
+
+    let cli = Cli::parse_from(["vipune", "add", "text", "--via-daemon"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Add {
+            via_daemon: true,
+            ..
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "search", "query", "--via-daemon"]);
+    assert!(!cli.command.needs_embedder());
+
+    for args in [
+        &["vipune", "add", "text", "--via-daemon", "--dry-run"][..],
+        &["vipune", "add", "text", "--via-daemon", "--allow-secrets"],
+        &["vipune", "search", "q", "--via-daemon", "--all-projects"],
+        &[
+            "vipune",
+            "search",
+            "--queries-file",
+            "q.txt",
+            "--via-daemon",
+        ],
+    ] {
+        assert!(Cli::try_parse_from(args).is_err(), "{args:?}");
+    }
+}
+
+#[test]
+fn test_cli_parse_context() {
+    let cli = Cli::parse_from(["vipune", "context", "fix the login bug"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Context {
+            budget: 2000,
+            limit: 20,
+            recency: None,
+            ..
+        }
+    ));
+    assert!(cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "context", "task", "--budget", "500", "-l", "50"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Context {
+            ref task,
+            budget: 500,
+            limit: 50,
+            ..
+        } if task == "task"
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_with_diversity() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--diversity", "0.3"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { diversity, .. }) if diversity == 0.3
+    ));
+
+    let cli = Cli::parse_from(["vipune", "search", "query"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { diversity, .. }) if diversity == 0.0
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_with_fusion() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "search",
+        "query",
+        "--hybrid",
+        "--fusion",
+        "weighted:0.7",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            fusion: Some(FusionStrategy::Weighted { semantic_weight }),
+            ..
+        }) if semantic_weight == 0.7
+    ));
+
+    // Fusion only applies to hybrid search
+    assert!(Cli::try_parse_from(["vipune", "search", "query", "--fusion", "zscore"]).is_err());
+    assert!(
+        Cli::try_parse_from(["vipune", "search", "query", "--hybrid", "--fusion", "mean"]).is_err()
+    );
+}
+
+#[test]
+fn test_cli_parse_search_as_of() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--as-of", "2024-06-01"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { as_of: Some(ref as_of), .. }) if as_of == "2024-06-01"
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_with_filters_and_explain() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "search",
+        "query",
+        "--filter",
+        "topic=auth",
+        "--filter",
+        "priority=2",
+        "--namespace",
+        "docs",
+        "--explain",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { filters, namespace: Some(namespace), explain: true, .. })
+            if filters == ["topic=auth", "priority=2"] && namespace == "docs"
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_with_rank() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--rank", "frecency"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            rank: Some(crate::search_options::Rank::Frecency),
+            ..
+        })
+    ));
+    assert!(Cli::try_parse_from(["vipune", "search", "query", "--rank", "newest"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_search_with_min_score() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--min-score", "0.6"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs { min_score: Some(score), .. }) if score == 0.6
+    ));
+}
+
+#[test]
+fn test_cli_parse_search_queries_file() {
+    let cli = Cli::parse_from(["vipune", "search", "--queries-file", "q.txt", "--hybrid"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            query: None,
+            queries_file: Some(ref path),
+            hybrid: true,
+            ..
+        }) if path == std::path::Path::new("q.txt")
+    ));
+    assert!(Cli::try_parse_from(["vipune", "search"]).is_err());
+    assert!(Cli::try_parse_from(["vipune", "search", "q", "--queries-file", "q.txt"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_search_with_recency() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--recency", "0.5"]);
+    matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            query,
+            recency: Some(0.5),
+            ..
+        }) if query.as_deref() == Some("query")
+    );
+}
+
+#[test]
+fn test_cli_parse_search_without_recency() {
+    let cli = Cli::parse_from(["vipune", "search", "query"]);
+    matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            query,
+            recency: None,
+            ..
+        }) if query.as_deref() == Some("query")
+    );
+}
+
+#[test]
+fn test_cli_parse_search_with_hybrid() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--hybrid"]);
+    matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            query,
+            hybrid: true,
+            ..
+        }) if query.as_deref() == Some("query")
+    );
+}
+
+#[test]
+fn test_cli_parse_search_without_hybrid() {
+    let cli = Cli::parse_from(["vipune", "search", "query"]);
+    matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            query,
+            hybrid: false,
+            ..
+        }) if query.as_deref() == Some("query")
+    );
+}
+
+#[test]
+fn test_cli_parse_search_with_hybrid_and_recency() {
+    let cli = Cli::parse_from(["vipune", "search", "query", "--hybrid", "--recency", "0.5"]);
+    matches!(
+        cli.command,
+        Commands::Search(SearchArgs {
+            query,
+            hybrid: true,
+            recency: Some(0.5),
+            ..
+        }) if query.as_deref() == Some("query")
+    );
+}
@@ -0,0 +1,298 @@
+//! Parsing of import, export, watch, archive, sync and fixture commands.
+
+use clap::Parser;
+
+use crate::cli::Cli;
+use crate::commands::Commands;
+
+#[test]
+fn test_cli_parse_import_with_mappings() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "import",
+        "memories.jsonl",
+        "--map",
+        "alice=owner/repo",
+        "--map",
+        "bob=other",
+        "--project",
+        "shared",
+    ]);
+    assert_eq!(cli.project, Some("shared".to_string()));
+    assert!(matches!(
+        cli.command,
+        Commands::Import { ref map, .. } if map.len() == 2
+    ));
+}
+
+#[test]
+fn test_cli_parse_import_exclusive() {
+    let cli = Cli::parse_from(["vipune", "import", "memories.jsonl", "--exclusive"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Import {
+            exclusive: true,
+            resume: false,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parse_import_bulk() {
+    let cli = Cli::parse_from(["vipune", "import", "memories.jsonl", "--bulk"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Import {
+            bulk: true,
+            exclusive: false,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parse_import_on_duplicate() {
+    let cli = Cli::parse_from([
+        "vipune",
+        "import",
+        "memories.jsonl",
+        "--on-duplicate",
+        "keep-both",
+        "--threshold",
+        "0.95",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Import {
+            on_duplicate: crate::import_options::DuplicatePolicy::KeepBoth,
+            threshold: Some(t),
+            ..
+        } if t == 0.95
+    ));
+    let cli = Cli::parse_from(["vipune", "import", "memories.jsonl"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Import {
+            on_duplicate: crate::import_options::DuplicatePolicy::Skip,
+            threshold: None,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parse_export() {
+    let cli = Cli::parse_from(["vipune", "export", "out.jsonl", "--all"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Export {
+            path: Some(_),
+            all: true,
+            no_embeddings: false,
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "export", "--no-embeddings"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Export {
+            path: None,
+            all: false,
+            no_embeddings: true,
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parse_export_context() {
+    let cli = Cli::parse_from(["vipune", "export-context", "CLAUDE.md", "--top", "5"]);
+    assert!(matches!(
+        cli.command,
+        Commands::ExportContext {
+            path: Some(_),
+            format: crate::commands::ContextFormat::ClaudeMd,
+            top: 5,
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "export-context", "--format", "agents-md"]);
+    assert!(matches!(
+        cli.command,
+        Commands::ExportContext {
+            path: None,
+            format: crate::commands::ContextFormat::ClaudeMd,
+            top: 50,
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parse_import_markdown() {
+    let cli = Cli::parse_from(["vipune", "import", "notes.jsonl"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Import {
+            format: crate::commands::ImportFormat::Json,
+            split: crate::notes::SplitMode::Heading,
+            ..
+        }
+    ));
+
+    let cli = Cli::parse_from([
+        "vipune",
+        "import",
+        "./notes",
+        "--format",
+        "markdown",
+        "--split",
+        "paragraph",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Import {
+            format: crate::commands::ImportFormat::Markdown,
+            split: crate::notes::SplitMode::Paragraph,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parse_import_conversations() {
+    let cli = Cli::parse_from(["vipune", "import", "conversations.json"]);
+    let Commands::Import { roles, .. } = cli.command else {
+        panic!("expected import");
+    };
+    assert_eq!(roles, crate::conversations::ChatRole::DEFAULT);
+
+    let cli = Cli::parse_from([
+        "vipune",
+        "import",
+        "./export",
+        "--format",
+        "claude-export",
+        "--roles",
+        "user,system",
+    ]);
+    let Commands::Import { format, roles, .. } = cli.command else {
+        panic!("expected import");
+    };
+    assert_eq!(format, crate::commands::ImportFormat::ClaudeExport);
+    assert_eq!(
+        roles,
+        [
+            crate::conversations::ChatRole::User,
+            crate::conversations::ChatRole::System
+        ]
+    );
+    assert!(Cli::try_parse_from(["vipune", "import", "x", "--format", "chatgpt-export"]).is_ok());
+}
+
+#[test]
+fn test_cli_parse_fixture_generate() {
+    let cli = Cli::parse_from([
+        "vipune", "fixture", "generate", "--count", "50", "--seed", "7",
+    ]);
+    assert!(matches!(
+        cli.command,
+        Commands::Fixture {
+            action: crate::commands::FixtureAction::Generate {
+                count: 50,
+                seed: 7,
+                clusters: 10,
+                ..
+            }
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+}
+
+#[test]
+fn test_cli_parse_archive() {
+    let cli = Cli::parse_from(["vipune", "archive", "export", "store.vipune"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Archive {
+            action: crate::commands::ArchiveAction::Export { .. }
+        }
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "archive", "import", "store.vipune", "--force"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Archive {
+            action: crate::commands::ArchiveAction::Import { force: true, .. }
+        }
+    ));
+    assert!(Cli::try_parse_from(["vipune", "archive", "import"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_sync() {
+    let cli = Cli::parse_from(["vipune", "sync", "push", "/mnt/desktop/memories.db"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Sync {
+            action: crate::commands::SyncAction::Push { ref peer }
+        } if peer == "/mnt/desktop/memories.db"
+    ));
+    assert!(!cli.command.needs_embedder());
+
+    let cli = Cli::parse_from(["vipune", "sync", "pull", "other.db"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Sync {
+            action: crate::commands::SyncAction::Pull { .. }
+        }
+    ));
+    assert!(Cli::try_parse_from(["vipune", "sync", "pull"]).is_err());
+}
+
+#[test]
+fn test_cli_parse_watch() {
+    let cli = Cli::parse_from(["vipune", "watch", "notes", "--interval-ms", "250", "--once"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Watch {
+            interval_ms: 250,
+            once: true,
+            ..
+        }
+    ));
+
+    let cli = Cli::parse_from(["vipune", "watch", "log.jsonl"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Watch {
+            interval_ms: 1000,
+            once: false,
+            ..
+        }
+    ));
+
+    let cli = Cli::parse_from(["vipune", "watch", "log.jsonl", "--metrics-file", "v.prom"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Watch {
+            metrics_file: Some(ref path),
+            ..
+        } if path.as_path() == std::path::Path::new("v.prom")
+    ));
+}
+
+#[test]
+fn test_cli_parse_import_resume_with_progress() {
+    let cli = Cli::parse_from(["vipune", "import", "dump.jsonl", "--resume", "--progress"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Import {
+            resume: true,
+            progress: true,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,303 @@
+//! Remote store client over the daemon's unix socket (feature `client`).
+//!
+//! [`MemoryApi`] is the part of the [`MemoryStore`] API a running
+//! `vipune daemon` answers: adds, searches, gets, updates, deletes and
+//! listings. Store errors come back as the same [`Error`] variants, so code
+//! written against it
+//! switches between an embedded store and [`RemoteStore`], which sends the
+//! same operations to the daemon (see [`crate::daemon`] for the protocol),
+//! by changing the line that opens the store:
+//!
+//! ```no_run
+//! use vipune::client::{MemoryApi, RemoteStore};
+//! use vipune::{MemoryKind, SearchOptions};
+//!
+//! let mut store = RemoteStore::for_database("/home/user/.vipune/memories.db".as_ref())?;
+//! // let mut store = vipune::MemoryStore::new(path, model_id, config)?;
+//! store.add_with_kind("owner/repo", "Alice works at Microsoft", None, MemoryKind::Fact, false)?;
+//! let hits = store.query("owner/repo", "where does alice work", SearchOptions::default())?;
+//! # Ok::<(), vipune::Error>(())
+//! ```
+
+use std::path::Path;
+
+use crate::daemon::{self, DaemonClient, DaemonRequest, DaemonResponse};
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::memory_types::AddResult;
+use crate::search_options::SearchOptions;
+use crate::sqlite::{Memory, MemoryKind};
+
+/// Memory operations answered by an embedded [`MemoryStore`] or a daemon
+/// through [`RemoteStore`].
+pub trait MemoryApi {
+    /// Add a memory of `kind`, as [`MemoryStore::add_with_kind`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the content is rejected or the add fails.
+    fn add_with_kind(
+        &mut self,
+        project_id: &str,
+        content: &str,
+        metadata: Option<&str>,
+        kind: MemoryKind,
+        force: bool,
+    ) -> Result<AddResult, Error>;
+
+    /// Search a project, as [`MemoryStore::query`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query or options are invalid or the search
+    /// fails.
+    fn query(
+        &mut self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<Memory>, Error>;
+
+    /// Get a memory by ID, as [`MemoryStore::get`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the lookup fails.
+    fn get(&mut self, id: &str) -> Result<Option<Memory>, Error>;
+
+    /// Replace a memory's content, as [`MemoryStore::update`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if the memory doesn't exist, or error if
+    /// the content is rejected or the update fails.
+    fn update(&mut self, id: &str, content: &str) -> Result<(), Error>;
+
+    /// Delete a memory, as [`MemoryStore::delete`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the delete fails.
+    fn delete(&mut self, id: &str) -> Result<bool, Error>;
+
+    /// List a project's memories, newest first, as [`MemoryStore::list`]
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the limit is invalid or the listing fails.
+    fn list(&mut self, project_id: &str, limit: usize) -> Result<Vec<Memory>, Error>;
+}
+
+impl MemoryApi for MemoryStore {
+    fn add_with_kind(
+        &mut self,
+        project_id: &str,
+        content: &str,
+        metadata: Option<&str>,
+        kind: MemoryKind,
+        force: bool,
+    ) -> Result<AddResult, Error> {
+        MemoryStore::add_with_kind(self, project_id, content, metadata, kind, force)
+    }
+
+    fn query(
+        &mut self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<Memory>, Error> {
+        MemoryStore::query(self, project_id, query, options)
+    }
+
+    fn get(&mut self, id: &str) -> Result<Option<Memory>, Error> {
+        MemoryStore::get(self, id)
+    }
+
+    fn update(&mut self, id: &str, content: &str) -> Result<(), Error> {
+        MemoryStore::update(self, id, content)
+    }
+
+    fn delete(&mut self, id: &str) -> Result<bool, Error> {
+        MemoryStore::delete(self, id)
+    }
+
+    fn list(&mut self, project_id: &str, limit: usize) -> Result<Vec<Memory>, Error> {
+        MemoryStore::list(self, project_id, limit)
+    }
+}
+
+/// A store served by a running `vipune daemon`.
+///
+/// Memories are written with the daemon's owner, visibility and origin, and
+/// searches and listings run as its owner, as for `vipune add --via-daemon`.
+/// A refused
+/// request fails with `Error::DaemonBusy` and can be retried after its
+/// `retry_after_ms`.
+pub struct RemoteStore {
+    client: DaemonClient,
+}
+
+impl RemoteStore {
+    /// Connect to the daemon listening on `socket`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Daemon` if no daemon is listening there.
+    pub fn connect(socket: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            client: DaemonClient::connect(socket)?,
+        })
+    }
+
+    /// Connect to the daemon serving the database at `database_path`, on
+    /// its default socket ([`daemon::socket_path`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Daemon` if no daemon is listening there.
+    pub fn for_database(database_path: &Path) -> Result<Self, Error> {
+        Self::connect(&daemon::socket_path(database_path))
+    }
+
+    /// Check that the daemon is still answering.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the connection fails or the daemon answers anything
+    /// but a pong.
+    pub fn ping(&mut self) -> Result<(), Error> {
+        match self.client.request(&DaemonRequest::Ping)? {
+            DaemonResponse::Pong => Ok(()),
+            other => Err(Error::Daemon(format!("unexpected response: {:?}", other))),
+        }
+    }
+}
+
+impl MemoryApi for RemoteStore {
+    fn add_with_kind(
+        &mut self,
+        project_id: &str,
+        content: &str,
+        metadata: Option<&str>,
+        kind: MemoryKind,
+        force: bool,
+    ) -> Result<AddResult, Error> {
+        self.client
+            .request(&DaemonRequest::Add {
+                project_id: project_id.to_string(),
+                content: content.to_string(),
+                metadata: metadata.map(str::to_string),
+                kind,
+                title: None,
+                force,
+                origin: None,
+                owner: None,
+                visibility: None,
+            })?
+            .into_add_result()
+    }
+
+    fn query(
+        &mut self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<Memory>, Error> {
+        self.client
+            .request(&DaemonRequest::Search {
+                project_id: project_id.to_string(),
+                query: query.to_string(),
+                options,
+                owner: None,
+            })?
+            .into_memories()
+    }
+
+    fn get(&mut self, id: &str) -> Result<Option<Memory>, Error> {
+        self.client
+            .request(&DaemonRequest::Get { id: id.to_string() })?
+            .into_memory()
+    }
+
+    fn update(&mut self, id: &str, content: &str) -> Result<(), Error> {
+        self.client
+            .request(&DaemonRequest::Update {
+                id: id.to_string(),
+                content: content.to_string(),
+            })?
+            .into_updated()
+    }
+
+    fn delete(&mut self, id: &str) -> Result<bool, Error> {
+        self.client
+            .request(&DaemonRequest::Delete { id: id.to_string() })?
+            .into_deleted()
+    }
+
+    fn list(&mut self, project_id: &str, limit: usize) -> Result<Vec<Memory>, Error> {
+        self.client
+            .request(&DaemonRequest::List {
+                project_id: project_id.to_string(),
+                limit,
+                owner: None,
+            })?
+            .into_memories()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    /// Run the operations that need no embedding model on `store`, whose
+    /// project `p` holds the single memory `id`.
+    fn exercise(store: &mut dyn MemoryApi, id: &str) {
+        assert_eq!(store.get(id).unwrap().unwrap().content, "kept");
+        assert!(store.get("missing").unwrap().is_none());
+        assert_eq!(store.list("p", 10).unwrap().len(), 1);
+        // Store errors come back as the same variants
+        assert!(matches!(
+            store.query("p", "  ", SearchOptions::default()),
+            Err(Error::EmptyInput)
+        ));
+        assert!(matches!(store.update(id, "  "), Err(Error::EmptyInput)));
+        assert!(matches!(store.update(id, "new"), Err(Error::Config(_))));
+        assert!(matches!(store.list("p", 0), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_embedded_and_remote_stores_answer_alike() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let database = dir.path().join("memories.db");
+        let mut embedded =
+            MemoryStore::open_without_embedder(&database, Config::default()).unwrap();
+        let id = embedded.db.insert("p", "kept", &[0.1; 384], None).unwrap();
+        let listener = daemon::bind(&daemon::socket_path(&database)).unwrap();
+        let served = database.clone();
+        // Serves until the test process exits
+        std::thread::spawn(move || {
+            let mut store = MemoryStore::open_without_embedder(&served, Config::default())?;
+            daemon::serve(&mut store, &listener)
+        });
+
+        let mut remote = RemoteStore::for_database(&database).unwrap();
+        remote.ping().unwrap();
+        exercise(&mut embedded, &id);
+        exercise(&mut remote, &id);
+
+        assert!(remote.delete(&id).unwrap());
+        assert!(!remote.delete(&id).unwrap());
+        assert!(embedded.get(&id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_connect_without_daemon_fails() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(matches!(
+            RemoteStore::for_database(&dir.path().join("memories.db")),
+            Err(Error::Daemon(_))
+        ));
+    }
+}
@@ -0,0 +1,78 @@
+//! Handlers for whole-store archives (`vipune archive ...`).
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use crate::archive::{Manifest, export_archive, import_archive};
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::output::*;
+
+/// Archive actions.
+#[derive(clap::Subcommand)]
+pub enum ArchiveAction {
+    /// Write every project's memories, embeddings and audit log to a single
+    /// compressed archive
+    Export {
+        /// Archive file to write (e.g. store.vipune)
+        path: PathBuf,
+    },
+    /// Replace the database with the contents of an archive
+    Import {
+        /// Archive file written by `vipune archive export`
+        path: PathBuf,
+
+        /// Replace a database that already holds memories, and accept an
+        /// archive made with another embedding model
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+pub(super) fn handle_archive(
+    store: &mut MemoryStore,
+    action: &ArchiveAction,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let (status, path, manifest) = match action {
+        ArchiveAction::Export { path } => ("exported", path, export_archive(store, path)?),
+        ArchiveAction::Import { path, force } => {
+            ("imported", path, import_archive(store, path, *force)?)
+        }
+    };
+
+    if out.quiet() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    if out.json() {
+        print_json(&ArchiveResponse {
+            status: status.to_string(),
+            path: path.display().to_string(),
+            memories: manifest.memories,
+            schema_version: manifest.schema_version,
+            model_id: manifest.model_id,
+            created_at: manifest.created_at,
+        });
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let Manifest {
+        memories,
+        model_id,
+        created_at,
+        ..
+    } = manifest;
+    match action {
+        ArchiveAction::Export { .. } => {
+            println!("Archived {} memories to {}", memories, path.display())
+        }
+        ArchiveAction::Import { .. } => println!(
+            "Imported {} memories from {} (archived {}, model {})",
+            memories,
+            path.display(),
+            created_at,
+            model_id
+        ),
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,487 @@
+//! Subcommands and flags of the vipune CLI.
+
+use std::path::PathBuf;
+
+use crate::conversations::ChatRole;
+use crate::import_options::DuplicatePolicy;
+use crate::memory::store::DEFAULT_DRIFT_TOLERANCE;
+use crate::notes::SplitMode;
+use crate::sqlite::{EmbeddingFormat, FtsTokenizer, MemoryKind, Origin, Visibility};
+
+use super::{
+    ArchiveAction, ConfigAction, EmbeddingAction, FixtureAction, MetaAction, ModelAction,
+    ProjectAction, ReviewArgs, SearchArgs, SyncAction, SynonymAction, TrashAction,
+};
+
+/// Commands supported by vipune CLI.
+#[derive(clap::Subcommand)]
+#[allow(clippy::large_enum_variant)] // Parsed once per run; `Search` has many flags
+pub enum Commands {
+    Add {
+        /// Memory text content
+        text: String,
+
+        /// Optional JSON metadata
+        #[arg(short = 'm', long)]
+        metadata: Option<String>,
+
+        /// Bypass conflict detection and store the memory unconditionally.
+        #[arg(long)]
+        force: bool,
+
+        /// Memory kind: fact, preference, decision, task or other (default: other)
+        #[arg(long)]
+        kind: Option<MemoryKind>,
+
+        /// Title shown by `list` (default: the first sentence of the content)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Report whether the memory would be added or conflict, without storing it
+        #[arg(long, conflicts_with = "force")]
+        dry_run: bool,
+
+        /// Store the content even if `secret_scan` flags it as a secret
+        #[arg(long)]
+        allow_secrets: bool,
+
+        /// Who besides the owner may see the memory: private or shared
+        /// (default: the configured `visibility`)
+        #[arg(long)]
+        visibility: Option<Visibility>,
+
+        /// Send the memory to the running `vipune daemon` instead of loading
+        /// the model in this process
+        #[arg(long, conflicts_with_all = ["dry_run", "allow_secrets"])]
+        via_daemon: bool,
+    },
+    Search(SearchArgs),
+    /// Pack the memories most relevant to a task into a prompt block
+    Context {
+        /// Task description to retrieve memories for
+        task: String,
+
+        /// Token budget for the block (estimated at 4 characters per token)
+        #[arg(long, default_value = "2000")]
+        budget: usize,
+
+        /// Maximum number of candidate memories to consider (default: 20)
+        #[arg(short = 'l', long, default_value = "20")]
+        limit: usize,
+
+        /// Recency weight for ranking candidates (0.0 to 1.0)
+        #[arg(long)]
+        recency: Option<f64>,
+    },
+    Get {
+        /// Memory ID
+        id: String,
+    },
+    /// Print the IDs of memories whose content is exactly the given text,
+    /// without loading the model
+    Find {
+        /// Content to look up
+        #[arg(long, value_name = "TEXT")]
+        exact: String,
+
+        /// Ignore case when comparing content
+        #[arg(long)]
+        icase: bool,
+    },
+    List {
+        /// Maximum number of results (default: 10)
+        #[arg(short = 'l', long, default_value = "10")]
+        limit: usize,
+
+        /// Only list memories of this kind (fact, preference, decision, task, other)
+        #[arg(long)]
+        kind: Option<MemoryKind>,
+
+        /// Only list memories written through this entry path (cli, mcp, api,
+        /// hook, or import[:source])
+        #[arg(long)]
+        origin: Option<Origin>,
+
+        /// Only list memories updated at or after this time (RFC3339,
+        /// YYYY-MM-DD, "yesterday" or a time ago like "2w" or "3 days ago")
+        #[arg(long, value_name = "TIME")]
+        since: Option<String>,
+
+        /// Only list memories last updated before this time (same forms as
+        /// --since)
+        #[arg(long, value_name = "TIME")]
+        before: Option<String>,
+
+        /// Only show these comma-separated fields, in this order (see `search --fields`)
+        #[arg(long, value_name = "FIELDS")]
+        fields: Option<String>,
+    },
+    Delete {
+        /// Memory ID
+        id: String,
+    },
+    Update {
+        /// Memory ID
+        id: String,
+        /// New content (re-embedded)
+        #[arg(required_unless_present_any = ["metadata_only", "touch"])]
+        text: Option<String>,
+
+        /// Replace the metadata with this JSON object (`null` clears it)
+        #[arg(short = 'm', long, value_name = "JSON")]
+        metadata: Option<String>,
+
+        /// Only replace the metadata, keeping the content and embedding
+        #[arg(long, requires = "metadata", conflicts_with = "text")]
+        metadata_only: bool,
+
+        /// Only bump the memory's update time, e.g. to confirm it is still current
+        #[arg(long, conflicts_with_all = ["text", "metadata"])]
+        touch: bool,
+
+        /// Store the content even if `secret_scan` flags it as a secret
+        #[arg(long)]
+        allow_secrets: bool,
+
+        /// Print a word diff of the old and new content
+        #[arg(long, requires = "text")]
+        diff: bool,
+    },
+    /// Append a timestamped addition to a memory's content and re-embed it
+    Append {
+        /// Memory ID
+        id: String,
+        /// Text to add to the content
+        text: String,
+
+        /// Store the text even if `secret_scan` flags it as a secret
+        #[arg(long)]
+        allow_secrets: bool,
+    },
+    /// Show the earlier versions of a memory's content, oldest first
+    History {
+        /// Memory ID
+        id: String,
+
+        /// Show each version as a word diff of the one before it
+        #[arg(long)]
+        diff: bool,
+    },
+    /// Edit a memory's content and metadata in $VISUAL or $EDITOR
+    Edit {
+        /// Memory ID
+        id: String,
+
+        /// Save without asking for confirmation after showing the diff
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Bypass conflict detection and save the edit unconditionally.
+        #[arg(long)]
+        force: bool,
+
+        /// Save the content even if `secret_scan` flags it as a secret
+        #[arg(long)]
+        allow_secrets: bool,
+    },
+    Import {
+        /// Path to a JSON array or JSON Lines file of memories, a notes
+        /// directory with --format markdown, or a conversation export
+        path: PathBuf,
+
+        /// Source format
+        #[arg(long, value_enum, default_value_t = ImportFormat::Json)]
+        format: ImportFormat,
+
+        /// How markdown notes are split into memories
+        #[arg(long, value_enum, default_value_t = SplitMode::Heading)]
+        split: SplitMode,
+
+        /// Message roles imported from a conversation export (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',', default_values_t = ChatRole::DEFAULT)]
+        roles: Vec<ChatRole>,
+
+        /// Rename a source project during import (repeatable, e.g. --map alice=owner/repo)
+        #[arg(long = "map", value_name = "OLD=NEW")]
+        map: Vec<String>,
+
+        /// Continue an interrupted import of the same file where it left off
+        #[arg(long)]
+        resume: bool,
+
+        /// Hold the database write lock for the whole import (other writers
+        /// wait, and a failure rolls back every imported memory)
+        #[arg(long)]
+        exclusive: bool,
+
+        /// Write-optimized import for large files: like --exclusive, and
+        /// defers full-text indexing and disk syncs until it finishes
+        #[arg(long)]
+        bulk: bool,
+
+        /// What to do with records similar to existing memories
+        #[arg(long, value_enum, default_value_t = DuplicatePolicy::Skip)]
+        on_duplicate: DuplicatePolicy,
+
+        /// Similarity at which a record counts as a duplicate (default:
+        /// similarity_threshold)
+        #[arg(long)]
+        threshold: Option<f64>,
+
+        /// Show a progress bar on stderr
+        #[arg(long)]
+        progress: bool,
+    },
+    /// Export memories as JSON Lines that `vipune import` reads back
+    Export {
+        /// Output file (default: stdout)
+        path: Option<PathBuf>,
+
+        /// Export every project instead of the current one
+        #[arg(long)]
+        all: bool,
+
+        /// Leave out embeddings, so an import re-embeds every memory
+        #[arg(long)]
+        no_embeddings: bool,
+    },
+    /// Write pinned and high-importance memories into an agent context file
+    /// such as CLAUDE.md or AGENTS.md
+    ExportContext {
+        /// Context file to create or update in place (default: print the section to stdout)
+        path: Option<PathBuf>,
+
+        /// Section format
+        #[arg(long, value_enum, default_value_t = ContextFormat::ClaudeMd)]
+        format: ContextFormat,
+
+        /// Maximum number of memories in the section (default: 50)
+        #[arg(long, default_value = "50")]
+        top: usize,
+    },
+    Watch {
+        /// JSONL file to tail, or directory of markdown notes
+        path: PathBuf,
+
+        /// Poll interval in milliseconds (default: 1000)
+        #[arg(long, default_value = "1000")]
+        interval_ms: u64,
+
+        /// Ingest current contents once and exit instead of watching
+        #[arg(long)]
+        once: bool,
+
+        /// Rewrite this file with Prometheus metrics after every poll (for
+        /// the node_exporter textfile collector, e.g. vipune.prom)
+        #[arg(long, value_name = "PATH")]
+        metrics_file: Option<PathBuf>,
+    },
+    Prune,
+    /// Keep the model loaded and answer `--via-daemon` adds and searches on
+    /// a unix socket next to the database
+    Daemon {
+        /// Rewrite this file with Prometheus metrics after every request
+        /// (for the node_exporter textfile collector, e.g. vipune.prom)
+        #[arg(long, value_name = "PATH")]
+        metrics_file: Option<PathBuf>,
+    },
+    /// List idle, low-importance memories and apply keep, archive or delete
+    /// decisions on them
+    Review(ReviewArgs),
+    Audit {
+        /// Only show entries at or after this time (RFC3339, YYYY-MM-DD,
+        /// "yesterday" or a time ago like "24h" or "3 days ago")
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Maximum number of entries (default: 100)
+        #[arg(short = 'l', long, default_value = "100")]
+        limit: usize,
+    },
+    Model {
+        #[command(subcommand)]
+        action: ModelAction,
+    },
+    /// Move the whole store between machines as one portable archive
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveAction,
+    },
+    /// Inspect stored embeddings to debug retrieval quality
+    Embedding {
+        #[command(subcommand)]
+        action: EmbeddingAction,
+    },
+    /// Exchange changed memories with another store (e.g. a laptop's and a
+    /// desktop's)
+    Sync {
+        #[command(subcommand)]
+        action: SyncAction,
+    },
+    /// Inspect the configuration (profiles)
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Generate synthetic memories for benchmarks and tests
+    Fixture {
+        #[command(subcommand)]
+        action: FixtureAction,
+    },
+    /// Update the metadata of many memories at once
+    Meta {
+        #[command(subcommand)]
+        action: MetaAction,
+    },
+    /// Maintain stored project IDs
+    Project {
+        #[command(subcommand)]
+        action: ProjectAction,
+    },
+    /// Manage the project's search synonyms (e.g. k8s = kubernetes)
+    Synonym {
+        #[command(subcommand)]
+        action: SynonymAction,
+    },
+    /// List, restore or empty deleted memories (with trash_retention_days set)
+    Trash {
+        #[command(subcommand)]
+        action: TrashAction,
+    },
+    /// Rebuild the full-text index, optionally switching its tokenizer
+    Reindex {
+        /// Tokenizer: porter (English stemming), unicode61 (no stemming) or
+        /// trigram (CJK) (default: fts_tokenizer from the config)
+        #[arg(long, value_name = "TOKENIZER")]
+        tokenizer: Option<FtsTokenizer>,
+    },
+    /// Embed stored memories again with the current model
+    Reembed {
+        /// Only re-embed memories whose stored embedding has the wrong
+        /// dimensions (these are skipped by search)
+        #[arg(long)]
+        mismatched_only: bool,
+    },
+    /// Re-embed a random sample of memories and report how far their stored
+    /// embeddings deviate, to detect model drift or a corrupted model cache
+    VerifyEmbeddings {
+        /// Number of memories to sample across all projects
+        #[arg(long, value_name = "N", default_value_t = 100)]
+        sample: usize,
+        /// Seed picking the sample; reuse a reported seed to verify the same
+        /// memories again (default: random)
+        #[arg(long, value_name = "N")]
+        seed: Option<u64>,
+        /// Cosine deviation (1 - similarity) above which an embedding counts
+        /// as drifted
+        #[arg(long, value_name = "X", default_value_t = DEFAULT_DRIFT_TOLERANCE)]
+        tolerance: f64,
+    },
+    /// Convert stored embeddings to another storage format
+    Quantize {
+        /// Storage format: int8 (quantized, about a quarter of the size) or
+        /// f32 (default: embedding_format from the config)
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<EmbeddingFormat>,
+    },
+    /// Show memory statistics for the project
+    Stats {
+        /// Also report how coherent the project's embeddings are, flagging outliers
+        #[arg(long)]
+        embeddings: bool,
+    },
+    /// Group the project's memories by embedding similarity (k-means), for
+    /// visualizing the memory space
+    Clusters {
+        /// Number of clusters
+        #[arg(long, default_value_t = 10)]
+        k: usize,
+
+        /// Output format (default: a human-readable summary, or JSON with --json)
+        #[arg(long, value_enum)]
+        format: Option<ClusterFormat>,
+    },
+    /// Self-test the embedding model and database (exit 1 if unhealthy)
+    Health,
+    /// Run the health check and repair what it can (exit 1 if problems remain)
+    Doctor {
+        /// Scan every stored embedding for NaN or infinite values (which make
+        /// searches fail) and embed the contaminated memories again
+        #[arg(long)]
+        scan_embeddings: bool,
+        /// Only report contaminated embeddings, don't re-embed them
+        #[arg(long, requires = "scan_embeddings")]
+        dry_run: bool,
+    },
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Target shell
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Print the roff manpage to stdout
+    Manpage,
+    /// Print the JSON Schema of `--json` responses to stdout
+    Schema {
+        /// Response to describe (every response when omitted)
+        #[arg(value_enum)]
+        response: Option<SchemaKind>,
+    },
+    Version,
+}
+
+/// Source format of `vipune import`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    /// JSON array or JSON Lines of memory records
+    Json,
+    /// Directory (or file) of markdown and plain-text notes
+    Markdown,
+    /// ChatGPT data export (`conversations.json` or the unpacked export)
+    ChatgptExport,
+    /// Claude data export (`conversations.json` or the unpacked export)
+    ClaudeExport,
+}
+
+/// Response of `--json` output described by `vipune schema`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SchemaKind {
+    /// Memory added (`add`)
+    Add,
+    /// Add refused because similar memories exist
+    Conflicts,
+    /// Ranked search results
+    Search,
+    /// Listed memories
+    List,
+    /// A single memory (`get`)
+    Get,
+    /// Import summary
+    Import,
+    /// Any failed command
+    Error,
+}
+
+/// Output format of `vipune clusters`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ClusterFormat {
+    /// Clusters with their terms, and the cluster of every memory
+    Json,
+    /// One `id,cluster,similarity` row per memory, with a header
+    Csv,
+}
+
+/// How `vipune search --all-projects` groups its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    /// One group per project, with per-project top results and hit counts
+    Project,
+}
+
+/// Format of the section written by `vipune export-context`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ContextFormat {
+    /// Markdown section for CLAUDE.md or AGENTS.md
+    #[value(alias = "agents-md")]
+    ClaudeMd,
+}
@@ -0,0 +1,64 @@
+//! Handlers for the daemon (`vipune daemon`) and for sending requests to it
+//! (`--via-daemon`).
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use crate::config::Config;
+use crate::daemon::{self, DaemonRequest, DaemonResponse};
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::metrics::MetricsExporter;
+use crate::output::*;
+
+/// Load the model and answer requests on the database's socket until
+/// stopped, publishing metrics to `metrics_file` if given.
+#[cfg(unix)]
+pub(super) fn handle_daemon(
+    store: &mut MemoryStore,
+    config: &Config,
+    metrics_file: Option<&Path>,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let path = daemon::socket_path(&config.database_path);
+    let listener = daemon::bind(&path)?;
+    store.warm_up()?;
+    if !out.json() && !out.quiet() {
+        println!("Listening on {} (Ctrl-C to stop)", path.display());
+    }
+    let exporter = metrics_file.map(MetricsExporter::new);
+    daemon::serve_with_limits(store, &listener, &config.daemon, exporter.as_ref())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(not(unix))]
+pub(super) fn handle_daemon(
+    _store: &mut MemoryStore,
+    _config: &Config,
+    _metrics_file: Option<&Path>,
+    _out: &Printer,
+) -> Result<ExitCode, Error> {
+    Err(unsupported())
+}
+
+/// Send `request` to the daemon serving the configured database.
+#[cfg(unix)]
+pub(super) fn daemon_request(
+    config: &Config,
+    request: &DaemonRequest,
+) -> Result<DaemonResponse, Error> {
+    daemon::DaemonClient::connect(&daemon::socket_path(&config.database_path))?.request(request)
+}
+
+#[cfg(not(unix))]
+pub(super) fn daemon_request(
+    _config: &Config,
+    _request: &DaemonRequest,
+) -> Result<DaemonResponse, Error> {
+    Err(unsupported())
+}
+
+#[cfg(not(unix))]
+fn unsupported() -> Error {
+    Error::Daemon("the daemon needs unix sockets, which this platform lacks".to_string())
+}
@@ -0,0 +1,152 @@
+//! Printing of found memories, shared by the search and record handlers.
+
+use crate::list_options::{Field, SelectedFields, select_fields};
+use crate::memory::MemoryStore;
+use crate::output::*;
+use crate::sqlite::{Memory, MemoryKind};
+
+pub(super) fn search_result_item(store: &MemoryStore, m: Memory) -> SearchResultItem {
+    SearchResultItem {
+        token_count: store.count_tokens(&m.content).ok(),
+        tags: m.tags(),
+        id: m.id,
+        project_id: m.project_id,
+        content: m.content,
+        similarity: m.similarity.unwrap_or(0.0),
+        kind: m.kind,
+        metadata: m.metadata,
+        snippet: m.snippet,
+        explanation: m.explanation,
+        created_at: m.created_at,
+        updated_at: m.updated_at,
+    }
+}
+
+/// JSON items for found memories: every field, or only `fields`.
+pub(super) fn search_items(
+    store: &MemoryStore,
+    memories: Vec<Memory>,
+    fields: Option<&[Field]>,
+) -> Vec<Selectable<SearchResultItem>> {
+    memories
+        .into_iter()
+        .map(|m| match fields {
+            Some(fields) => Selectable::Selected(selected(store, &m, fields)),
+            None => Selectable::All(search_result_item(store, m)),
+        })
+        .collect()
+}
+
+/// `fields` of `memory`, counting tokens only if they were asked for.
+pub(super) fn selected(store: &MemoryStore, memory: &Memory, fields: &[Field]) -> SelectedFields {
+    let token_count = fields
+        .contains(&Field::TokenCount)
+        .then(|| store.count_tokens(&memory.content).ok())
+        .flatten();
+    select_fields(memory, fields, token_count)
+}
+
+/// Print a found memory in full (with its project, update time, tags and
+/// metadata if `details` is set), or only `fields`.
+pub(super) fn print_result(
+    store: &MemoryStore,
+    memory: &Memory,
+    fields: Option<&[Field]>,
+    details: bool,
+    out: &Printer,
+) {
+    match fields {
+        Some(fields) => print_selected(&selected(store, memory, fields), out),
+        None => print_search_result(memory, details, out),
+    }
+}
+
+/// Print selected fields on one line, separated by tabs (`-` for fields
+/// without a value).
+pub(super) fn print_selected(selected: &SelectedFields, out: &Printer) {
+    let values: Vec<String> = selected
+        .entries()
+        .iter()
+        .map(|(field, value)| match value {
+            serde_json::Value::Null => "-".to_string(),
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Number(score) if *field == Field::Score => {
+                out.score(score.as_f64().unwrap_or(0.0), 2)
+            }
+            value => value.to_string(),
+        })
+        .collect();
+    println!("{}", values.join("\t"));
+}
+
+pub(super) fn print_search_result(memory: &Memory, details: bool, out: &Printer) {
+    if out.quiet() {
+        println!("{}", memory.id);
+        return;
+    }
+    println!(
+        "{} [score: {}]{}\n  {}",
+        memory.id,
+        out.score(memory.similarity.unwrap_or(0.0), 2),
+        kind_tag(memory.kind),
+        memory.content
+    );
+    if details {
+        let mut parts = vec![
+            format!("project: {}", memory.project_id),
+            format!("updated: {}", out.time(&memory.updated_at)),
+        ];
+        let tags = memory.tags();
+        if !tags.is_empty() {
+            parts.push(format!("tags: {}", tags.join(", ")));
+        }
+        println!("  {}", parts.join(" | "));
+        if let Some(metadata) = &memory.metadata {
+            println!("  metadata: {}", metadata);
+        }
+    }
+    if let Some(snippet) = &memory.snippet {
+        println!("  > {}", snippet);
+    }
+    if let Some(explanation) = &memory.explanation {
+        let mut parts = Vec::new();
+        if let Some(semantic) = explanation.semantic {
+            parts.push(format!("semantic {:.3}", semantic));
+        }
+        if let Some(rank) = explanation.bm25_rank {
+            parts.push(format!("bm25 rank {}", rank));
+        }
+        if let Some(fused) = explanation.fused {
+            parts.push(format!("fused {:.4}", fused));
+        }
+        if let Some(decay) = explanation.recency_decay {
+            parts.push(format!("recency decay {:.3}", decay));
+        }
+        if let Some(exclusion) = explanation.exclusion {
+            parts.push(format!("exclusion {:.3}", exclusion));
+        }
+        if let Some(frecency) = explanation.frecency {
+            parts.push(format!("frecency {:.3}", frecency));
+        }
+        if let Some(late_interaction) = explanation.late_interaction {
+            parts.push(format!("late interaction {:.3}", late_interaction));
+        }
+        if let Some(rerank) = explanation.rerank {
+            parts.push(format!("rerank {:.3}", rerank));
+        }
+        if let Some(scanned) = explanation.scanned {
+            parts.push(format!("{} rows scanned", scanned));
+        }
+        println!("  ({})", parts.join(", "));
+    }
+    println!();
+}
+
+/// ` [kind]` suffix for human output; empty for the default kind.
+pub(super) fn kind_tag(kind: MemoryKind) -> String {
+    if kind == MemoryKind::Other {
+        String::new()
+    } else {
+        format!(" [{}]", kind)
+    }
+}
@@ -0,0 +1,298 @@
+//! Handler for `vipune edit`: edit a memory in the user's text editor.
+//!
+//! The memory is written to a temporary file as a JSON metadata header and the
+//! content, separated by a `---` line. After the editor exits, the changes are
+//! shown as a line diff and saved (with conflict detection) once confirmed.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process::{Command, ExitCode};
+
+use crate::diff;
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::memory_types::UpdateResult;
+use crate::output::*;
+use crate::sqlite::Memory;
+
+use super::records::print_conflicts;
+
+/// Line separating the metadata header from the content.
+const SEPARATOR: &str = "---";
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Memory fields as parsed back from the edited file.
+#[derive(Debug, PartialEq)]
+struct Edited {
+    content: String,
+    metadata: Option<serde_json::Value>,
+}
+
+pub(super) fn handle_edit(
+    store: &mut MemoryStore,
+    id: &str,
+    yes: bool,
+    force: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let memory = store
+        .get(id)?
+        .ok_or_else(|| Error::NotFound("memory not found".to_string()))?;
+    let edited = parse(&run_editor(id, &render(&memory))?)?;
+
+    let old_content = memory.content.trim_end();
+    let content_changed = edited.content != old_content;
+    let old_metadata = memory
+        .metadata
+        .as_deref()
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok());
+    let metadata_changed = edited.metadata != old_metadata;
+
+    if !content_changed && !metadata_changed {
+        if out.json() {
+            print_json(&UpdateResponse {
+                status: "unchanged".to_string(),
+                id: id.to_string(),
+                seq: store.write_seq()?,
+            });
+        } else {
+            out.status(format_args!("No changes to memory: {}", id));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // The diff and prompt go to stderr so that stdout only carries the result
+    if metadata_changed {
+        eprintln!("- metadata: {}", metadata_line(old_metadata.as_ref()));
+        eprintln!("+ metadata: {}", metadata_line(edited.metadata.as_ref()));
+    }
+    if content_changed {
+        for line in diff::lines(old_content, &edited.content) {
+            eprintln!("{}", line);
+        }
+    }
+    if !yes && !confirm("Save changes?")? {
+        eprintln!("Edit discarded");
+        return Ok(ExitCode::from(1));
+    }
+
+    if content_changed {
+        if let UpdateResult::Conflicts {
+            proposed,
+            conflicts,
+        } = store.update_with_conflict(id, &edited.content, force)?
+        {
+            print_conflicts(proposed, conflicts, "save", out);
+            return Ok(ExitCode::from(2));
+        }
+    }
+    if metadata_changed {
+        let metadata = edited
+            .metadata
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        store.update_metadata(id, metadata.as_deref())?;
+    }
+
+    if out.json() {
+        print_json(&UpdateResponse {
+            status: "updated".to_string(),
+            id: id.to_string(),
+            seq: store.write_seq()?,
+        });
+    } else {
+        out.status(format_args!("Updated memory: {}", id));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Render a memory as the editable file contents.
+fn render(memory: &Memory) -> String {
+    let metadata = match memory.metadata.as_deref() {
+        Some(raw) => match serde_json::from_str::<serde_json::Value>(raw) {
+            Ok(value) => serde_json::to_string_pretty(&value).unwrap_or_else(|_| raw.to_string()),
+            Err(_) => raw.to_string(),
+        },
+        None => String::new(),
+    };
+    format!(
+        "# Editing memory {} ({})\n\
+         # Metadata is a JSON object above the '{}' line (leave empty for none);\n\
+         # the content follows it. Lines starting with '#' in the header are ignored.\n\
+         {}\n{}\n{}\n",
+        memory.id,
+        memory.kind,
+        SEPARATOR,
+        metadata,
+        SEPARATOR,
+        memory.content.trim_end()
+    )
+}
+
+/// Parse an edited file back into content and metadata.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if the separator line is missing or the
+/// metadata header is not a JSON object.
+fn parse(text: &str) -> Result<Edited, Error> {
+    let mut header = Vec::new();
+    let mut lines = text.lines();
+    loop {
+        match lines.next() {
+            Some(line) if line.trim_end() == SEPARATOR => break,
+            Some(line) if line.trim_start().starts_with('#') => {}
+            Some(line) => header.push(line),
+            None => {
+                return Err(Error::InvalidInput(format!(
+                    "missing '{}' line between metadata and content",
+                    SEPARATOR
+                )));
+            }
+        }
+    }
+
+    let header = header.join("\n");
+    let metadata = match header.trim() {
+        "" | "null" => None,
+        raw => match serde_json::from_str::<serde_json::Value>(raw) {
+            Ok(value @ serde_json::Value::Object(_)) => Some(value),
+            Ok(_) => {
+                return Err(Error::InvalidInput(
+                    "metadata must be a JSON object".to_string(),
+                ));
+            }
+            Err(e) => {
+                return Err(Error::InvalidInput(format!("invalid metadata JSON: {}", e)));
+            }
+        },
+    };
+
+    let content = lines.collect::<Vec<_>>().join("\n");
+    Ok(Edited {
+        content: content.trim_end().to_string(),
+        metadata,
+    })
+}
+
+fn metadata_line(metadata: Option<&serde_json::Value>) -> String {
+    metadata.map_or_else(|| "(none)".to_string(), |m| m.to_string())
+}
+
+/// Write `contents` to a temporary file, open it in the editor and return
+/// the saved text. The file is removed afterwards.
+fn run_editor(id: &str, contents: &str) -> Result<String, Error> {
+    let path = std::env::temp_dir().join(format!("vipune-edit-{}-{}.txt", std::process::id(), id));
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    file.write_all(contents.as_bytes())?;
+    drop(file);
+
+    let result = launch_editor(&path).and_then(|_| Ok(fs::read_to_string(&path)?));
+    let _ = fs::remove_file(&path);
+    result
+}
+
+/// Run `$VISUAL`, `$EDITOR` or `vi` on `path` and wait for it to exit.
+fn launch_editor(path: &Path) -> Result<(), Error> {
+    let editor = editor_command();
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|e| Error::InvalidInput(format!("failed to run editor '{}': {}", editor, e)))?;
+    if !status.success() {
+        return Err(Error::InvalidInput(format!(
+            "editor '{}' exited with {}",
+            editor, status
+        )));
+    }
+    Ok(())
+}
+
+/// Editor command line from `$VISUAL` or `$EDITOR`, defaulting to `vi`.
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Ask a yes/no question on stderr; anything but `y`/`yes` is a no.
+fn confirm(question: &str) -> Result<bool, Error> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::{MemoryKind, Origin};
+
+    fn memory(content: &str, metadata: Option<&str>) -> Memory {
+        Memory {
+            id: "mem-1".to_string(),
+            project_id: "proj".to_string(),
+            content: content.to_string(),
+            metadata: metadata.map(str::to_string),
+            kind: MemoryKind::Fact,
+            origin: Origin::default(),
+            title: None,
+            similarity: None,
+            snippet: None,
+            explanation: None,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_parse_roundtrip() {
+        let original = memory("# Heading\nbody line", Some(r#"{"topic": "auth"}"#));
+        let parsed = parse(&render(&original)).unwrap();
+        assert_eq!(parsed.content, "# Heading\nbody line");
+        assert_eq!(parsed.metadata, Some(serde_json::json!({"topic": "auth"})));
+
+        let parsed = parse(&render(&memory("plain", None))).unwrap();
+        assert_eq!(parsed.content, "plain");
+        assert_eq!(parsed.metadata, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_header() {
+        assert!(matches!(
+            parse("no separator here"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            parse("[1, 2]\n---\ncontent"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            parse("{not json\n---\ncontent"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_keeps_separator_lines_in_content() {
+        let parsed = parse("# comment\n\n---\nabove\n---\nbelow\n\n").unwrap();
+        assert_eq!(parsed.content, "above\n---\nbelow");
+        assert_eq!(parsed.metadata, None);
+    }
+}
@@ -0,0 +1,108 @@
+//! Handlers for inspecting stored embeddings (`vipune embedding ...`).
+
+use std::process::ExitCode;
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::output::*;
+
+/// Embedding inspection actions.
+#[derive(clap::Subcommand)]
+pub enum EmbeddingAction {
+    /// Show a memory's embedding: model, dimensions, norm and first values
+    Show {
+        /// Memory ID
+        id: String,
+
+        /// Number of leading values to print
+        #[arg(long, default_value_t = 8)]
+        values: usize,
+    },
+    /// Cosine similarity between the embeddings of two memories
+    Compare {
+        /// First memory ID
+        id_a: String,
+        /// Second memory ID
+        id_b: String,
+    },
+    /// Cosine similarity between a query and a memory, as semantic search
+    /// scores it
+    Query {
+        /// Search query
+        text: String,
+        /// Memory ID
+        id: String,
+    },
+}
+
+impl EmbeddingAction {
+    /// Whether the action embeds text and therefore needs the model.
+    pub(super) fn needs_embedder(&self) -> bool {
+        matches!(self, EmbeddingAction::Query { .. })
+    }
+}
+
+pub(super) fn handle_embedding(
+    store: &mut MemoryStore,
+    action: &EmbeddingAction,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let similarity = match action {
+        EmbeddingAction::Show { id, values } => {
+            let info = store.embedding_info(id, *values)?;
+            if out.json() {
+                print_json(&EmbeddingShowResponse {
+                    id: info.id,
+                    model_id: info.model_id,
+                    dims: info.dims,
+                    norm: info.norm,
+                    values: info.values,
+                });
+            } else if !out.quiet() {
+                let values: Vec<String> = info.values.iter().map(|v| format!("{:.4}", v)).collect();
+                let more = if info.values.len() < info.dims {
+                    ", ..."
+                } else {
+                    ""
+                };
+                println!("ID: {}", info.id);
+                println!("Model: {}", info.model_id);
+                println!("Dimensions: {}", info.dims);
+                println!("Norm: {:.4}", info.norm);
+                println!("Values: [{}{}]", values.join(", "), more);
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        EmbeddingAction::Compare { id_a, id_b } => {
+            let similarity = store.embedding_similarity(id_a, id_b)?;
+            if out.json() {
+                print_json(&EmbeddingCompareResponse {
+                    id_a: id_a.clone(),
+                    id_b: id_b.clone(),
+                    similarity,
+                });
+                return Ok(ExitCode::SUCCESS);
+            }
+            similarity
+        }
+        EmbeddingAction::Query { text, id } => {
+            let similarity = store.query_similarity(text, id)?;
+            if out.json() {
+                print_json(&EmbeddingQueryResponse {
+                    query: text.clone(),
+                    id: id.clone(),
+                    similarity,
+                });
+                return Ok(ExitCode::SUCCESS);
+            }
+            similarity
+        }
+    };
+
+    if out.quiet() {
+        println!("{:.4}", similarity);
+    } else {
+        println!("Cosine similarity: {:.4}", similarity);
+    }
+    Ok(ExitCode::SUCCESS)
+}
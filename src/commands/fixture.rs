@@ -0,0 +1,80 @@
+//! Handlers for synthetic test stores (`vipune fixture ...`).
+
+use std::process::ExitCode;
+
+use crate::errors::Error;
+use crate::fixtures::{self, FixtureOptions};
+use crate::memory::MemoryStore;
+use crate::output::*;
+
+/// Synthetic store actions.
+#[derive(clap::Subcommand)]
+pub enum FixtureAction {
+    /// Fill an empty project with deterministic synthetic memories (lorem-like
+    /// content, fake clustered embeddings) for benchmarks and tests
+    Generate {
+        /// Memories to generate
+        #[arg(long, default_value = "1000")]
+        count: usize,
+
+        /// Seed of the generator; the same seed gives the same memories
+        #[arg(long, default_value = "42")]
+        seed: u64,
+
+        /// Cluster centers the embeddings are grouped around
+        #[arg(long, default_value = "10")]
+        clusters: usize,
+
+        /// How far embeddings scatter around their cluster center (0.0 to 1.0)
+        #[arg(long, default_value = "0.3")]
+        spread: f32,
+    },
+}
+
+pub(super) fn handle_fixture(
+    store: &MemoryStore,
+    project_id: &str,
+    action: &FixtureAction,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let FixtureAction::Generate {
+        count,
+        seed,
+        clusters,
+        spread,
+    } = action;
+    let options = FixtureOptions::default()
+        .with_count(*count)
+        .with_seed(*seed)
+        .with_clusters(*clusters)
+        .with_spread(*spread);
+    options.validate()?;
+
+    // Never mix synthetic memories into real ones
+    let existing = store.db.count_project(project_id)?;
+    if existing > 0 {
+        return Err(Error::InvalidInput(format!(
+            "Project '{}' already has {} memories; generate fixtures into an empty project \
+             (--project) or database (--db-path)",
+            project_id, existing
+        )));
+    }
+    let ids = fixtures::populate(&store.db, project_id, &options)?;
+
+    if out.json() {
+        print_json(&FixtureResponse {
+            status: "generated".to_string(),
+            project_id: project_id.to_string(),
+            count: ids.len(),
+            seed: *seed,
+        });
+    } else {
+        out.status(format!(
+            "Generated {} memories in project '{}' (seed {})",
+            ids.len(),
+            project_id,
+            seed
+        ));
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,215 @@
+//! Shell completion, manpage and JSON Schema generation (`vipune completions`,
+//! `vipune manpage`, `vipune schema`).
+//!
+//! Artifacts are rendered from the clap definition and the output structs at
+//! runtime, so they always match the installed binary and nothing generated
+//! is checked into the repo.
+
+use std::io::Write;
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+use clap_complete::{Generator, Shell};
+use schemars::JsonSchema;
+use schemars::generate::SchemaSettings;
+use serde_json::{Map, Value};
+
+use super::SchemaKind;
+use crate::errors::Error;
+use crate::output::{
+    AddResponse, ConflictsResponse, ErrorResponse, GetResponse, ImportResponse, ListResponse,
+    SearchResponse,
+};
+
+/// Write a completion script for `shell` to `out`.
+///
+/// # Errors
+///
+/// Returns error if writing fails (e.g. stdout closed by `| head`).
+pub fn write_completions(
+    shell: Shell,
+    cmd: &mut clap::Command,
+    out: &mut dyn Write,
+) -> Result<(), Error> {
+    let name = cmd.get_name().to_string();
+    cmd.set_bin_name(name);
+    cmd.build();
+    shell.try_generate(cmd, out)?;
+    Ok(())
+}
+
+/// Write a roff manpage for `cmd` to `out`.
+///
+/// # Errors
+///
+/// Returns error if writing fails.
+pub fn write_manpage(cmd: clap::Command, out: &mut dyn Write) -> Result<(), Error> {
+    clap_mangen::Man::new(cmd).render(out)?;
+    Ok(())
+}
+
+/// Name of a response schema, as used in its `$id` and by `vipune schema`.
+fn schema_name(kind: SchemaKind) -> &'static str {
+    match kind {
+        SchemaKind::Add => "add",
+        SchemaKind::Conflicts => "conflicts",
+        SchemaKind::Search => "search",
+        SchemaKind::List => "list",
+        SchemaKind::Get => "get",
+        SchemaKind::Import => "import",
+        SchemaKind::Error => "error",
+    }
+}
+
+/// JSON Schema (draft 2020-12) of the `--json` response `kind`.
+///
+/// The `$id` carries the crate version, so a schema changes identity
+/// whenever a release may have changed the output.
+pub fn response_schema(kind: SchemaKind) -> Value {
+    fn generate<T: JsonSchema>() -> schemars::Schema {
+        SchemaSettings::draft2020_12()
+            .for_serialize()
+            .into_generator()
+            .into_root_schema_for::<T>()
+    }
+
+    let mut schema = match kind {
+        SchemaKind::Add => generate::<AddResponse>(),
+        SchemaKind::Conflicts => generate::<ConflictsResponse>(),
+        SchemaKind::Search => generate::<SearchResponse>(),
+        SchemaKind::List => generate::<ListResponse>(),
+        SchemaKind::Get => generate::<GetResponse>(),
+        SchemaKind::Import => generate::<ImportResponse>(),
+        SchemaKind::Error => generate::<ErrorResponse>(),
+    };
+    schema.insert(
+        "$id".to_string(),
+        Value::String(format!(
+            "urn:vipune:{}:schema:{}",
+            env!("CARGO_PKG_VERSION"),
+            schema_name(kind)
+        )),
+    );
+    schema.to_value()
+}
+
+/// Write the schema of `kind` to `out`, or of every response keyed by name
+/// with the crate version when `kind` is `None`.
+///
+/// # Errors
+///
+/// Returns error if writing fails.
+pub fn write_schema(kind: Option<SchemaKind>, out: &mut dyn Write) -> Result<(), Error> {
+    let document = match kind {
+        Some(kind) => response_schema(kind),
+        None => {
+            let schemas: Map<String, Value> = SchemaKind::value_variants()
+                .iter()
+                .map(|&kind| (schema_name(kind).to_string(), response_schema(kind)))
+                .collect();
+            serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "schemas": schemas,
+            })
+        }
+    };
+    serde_json::to_writer_pretty(&mut *out, &document)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Print a completion script to stdout.
+///
+/// # Errors
+///
+/// Returns error if writing to stdout fails.
+pub fn print_completions(shell: Shell, cmd: &mut clap::Command) -> Result<ExitCode, Error> {
+    write_completions(shell, cmd, &mut std::io::stdout().lock())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print the manpage to stdout.
+///
+/// # Errors
+///
+/// Returns error if writing to stdout fails.
+pub fn print_manpage(cmd: clap::Command) -> Result<ExitCode, Error> {
+    write_manpage(cmd, &mut std::io::stdout().lock())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print one or every response schema to stdout.
+///
+/// # Errors
+///
+/// Returns error if writing to stdout fails.
+pub fn print_schema(kind: Option<SchemaKind>) -> Result<ExitCode, Error> {
+    write_schema(kind, &mut std::io::stdout().lock())?;
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command() -> clap::Command {
+        clap::Command::new("vipune")
+            .about("Minimal memory layer for AI agents")
+            .subcommand(clap::Command::new("search").arg(clap::arg!(--limit <N>)))
+    }
+
+    #[test]
+    fn test_completions_cover_subcommands() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let mut out = Vec::new();
+            write_completions(shell, &mut command(), &mut out).unwrap();
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains("search"), "{shell} script lacks subcommand");
+        }
+    }
+
+    #[test]
+    fn test_manpage_renders_roff() {
+        let mut out = Vec::new();
+        write_manpage(command(), &mut out).unwrap();
+        let page = String::from_utf8(out).unwrap();
+        assert!(page.starts_with(".ie"));
+        assert!(page.contains("vipune"));
+    }
+
+    #[test]
+    fn test_response_schemas_describe_output_fields() {
+        let schema = response_schema(SchemaKind::Get);
+        assert_eq!(
+            schema["$id"],
+            format!("urn:vipune:{}:schema:get", env!("CARGO_PKG_VERSION"))
+        );
+        let properties = schema["properties"].as_object().unwrap();
+        for field in ["id", "content", "kind", "origin", "created_at"] {
+            assert!(properties.contains_key(field), "get schema lacks {field}");
+        }
+        assert_eq!(schema["$defs"]["Origin"]["type"], "string");
+
+        let search = response_schema(SchemaKind::Search);
+        assert_eq!(search["properties"]["results"]["type"], "array");
+        assert!(search["$defs"]["SearchResultItem"].is_object());
+        assert!(
+            search["$defs"]["MemoryKind"]["oneOf"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|variant| variant["const"] == "decision")
+        );
+    }
+
+    #[test]
+    fn test_schema_document_covers_every_response() {
+        let mut out = Vec::new();
+        write_schema(None, &mut out).unwrap();
+        let document: Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(document["version"], env!("CARGO_PKG_VERSION"));
+        let schemas = document["schemas"].as_object().unwrap();
+        assert_eq!(schemas.len(), SchemaKind::value_variants().len());
+        assert_eq!(schemas["error"]["required"], serde_json::json!(["error"]));
+    }
+}
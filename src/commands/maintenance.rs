@@ -0,0 +1,370 @@
+//! Handlers for lifecycle and upkeep commands (prune, audit, health, doctor,
+//! reindex, reembed, verify, quantize).
+
+use std::process::ExitCode;
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::memory_types::HealthReport;
+use crate::normalize::TextNormalization;
+use crate::output::*;
+use crate::sqlite::{EmbeddingFormat, FtsTokenizer};
+use crate::timeparse;
+
+pub(super) fn handle_prune(store: &mut MemoryStore, out: &Printer) -> Result<ExitCode, Error> {
+    let stats = store.prune()?;
+    if out.json() {
+        print_json(&PruneResponse {
+            status: "pruned".to_string(),
+            removed: stats.removed,
+            projects: stats.projects,
+            archived: stats.archived,
+            trash_purged: stats.trash_purged,
+            deactivated: stats.deactivated,
+        });
+    } else if !out.quiet() {
+        let action = if stats.archived {
+            "Archived"
+        } else {
+            "Deleted"
+        };
+        println!(
+            "{} {} expired memories across {} projects",
+            action, stats.removed, stats.projects
+        );
+        if stats.trash_purged > 0 {
+            println!(
+                "Emptied {} expired memories from the trash",
+                stats.trash_purged
+            );
+        }
+        if stats.deactivated > 0 {
+            println!(
+                "Marked {} idle memories inactive (search with --include-inactive)",
+                stats.deactivated
+            );
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_audit(
+    store: &mut MemoryStore,
+    project_id: &str,
+    since: Option<&str>,
+    limit: usize,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let since = since.map(|s| parse_time("--since", s)).transpose()?;
+    let entries = store.audit_log(project_id, since, limit)?;
+    if out.json() {
+        print_json(&AuditResponse { entries });
+    } else if entries.is_empty() {
+        out.status("No audit entries found");
+    } else {
+        for entry in entries {
+            out.result(
+                &entry.memory_id,
+                format_args!(
+                    "{} {:<6} {} {}",
+                    out.time(&entry.timestamp),
+                    entry.operation,
+                    entry.memory_id,
+                    &entry.payload_hash[..12]
+                ),
+            );
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_health(store: &mut MemoryStore, out: &Printer) -> Result<ExitCode, Error> {
+    let report = store.health_check()?;
+    if out.json() {
+        print_json(&report);
+    } else if !out.quiet() {
+        print_health(&report);
+    }
+    Ok(if report.healthy {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+/// Run the health check and, with `scan_embeddings`, look for stored
+/// embeddings holding NaN or infinite values, re-embedding them unless
+/// `dry_run` (or the model itself is unhealthy).
+///
+/// Exits with status 1 if the store is unhealthy or contaminated rows remain.
+pub(super) fn handle_doctor(
+    store: &mut MemoryStore,
+    scan_embeddings: bool,
+    dry_run: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let health = store.health_check()?;
+    let embeddings = if scan_embeddings {
+        Some(store.scan_embeddings(!dry_run && health.healthy)?)
+    } else {
+        None
+    };
+    let remaining = embeddings
+        .as_ref()
+        .map_or(0, |scan| scan.contaminated.len() - scan.reembedded);
+    let healthy = health.healthy && remaining == 0;
+    if out.json() {
+        print_json(&DoctorResponse { health, embeddings });
+    } else if !out.quiet() {
+        print_health(&health);
+        if let Some(scan) = &embeddings {
+            match (scan.contaminated.len(), scan.reembedded) {
+                (0, _) => println!("Embeddings: no NaN or infinite values"),
+                (found, 0) => {
+                    println!("Embeddings: {} with NaN or infinite values", found);
+                    for id in &scan.contaminated {
+                        println!("  {}", id);
+                    }
+                }
+                (found, _) => println!(
+                    "Embeddings: re-embedded {} with NaN or infinite values",
+                    found
+                ),
+            }
+        }
+    }
+    Ok(if healthy {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+fn print_health(report: &HealthReport) {
+    let status = if report.healthy {
+        "healthy"
+    } else {
+        "unhealthy"
+    };
+    println!("Status: {}", status);
+    println!("Model: {}", report.model_id);
+    println!(
+        "Tokenizer: {}",
+        if report.tokenizer_loaded {
+            "loaded"
+        } else {
+            "not loaded"
+        }
+    );
+    match report.embed_ms {
+        Some(ms) => println!("Embedding: {} dims in {:.1} ms", report.dims, ms),
+        None => println!("Embedding: unavailable"),
+    }
+    println!("Schema version: {}", report.schema_version);
+    if report.fts_enabled {
+        println!(
+            "FTS index: {}/{} rows{}",
+            report.fts_rows,
+            report.memory_rows,
+            if report.fts_in_sync {
+                ""
+            } else {
+                " (out of sync)"
+            }
+        );
+    } else {
+        println!("FTS index: disabled ({} rows)", report.memory_rows);
+    }
+    if let Some(error) = &report.error {
+        println!("Error: {}", error);
+    }
+}
+
+pub(super) fn handle_reindex(
+    store: &MemoryStore,
+    tokenizer: FtsTokenizer,
+    normalization: TextNormalization,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let previous = store.db.fts_tokenizer()?;
+    let indexed = store.db.reindex_fts(tokenizer, normalization)?;
+    if out.json() {
+        print_json(&ReindexResponse {
+            status: "reindexed".to_string(),
+            tokenizer: tokenizer.to_string(),
+            previous: previous.map(|t| t.to_string()),
+            normalization: normalization.to_string(),
+            indexed,
+        });
+    } else if !out.quiet() {
+        match previous {
+            Some(previous) if previous != tokenizer => println!(
+                "Reindexed {} rows, switching the tokenizer from {} to {}",
+                indexed, previous, tokenizer
+            ),
+            _ => println!(
+                "Reindexed {} rows with the {} tokenizer",
+                indexed, tokenizer
+            ),
+        }
+        if !normalization.is_identity() {
+            println!("Normalization: {}", normalization);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_reembed(
+    store: &mut MemoryStore,
+    mismatched_only: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let reembedded = store.reembed(mismatched_only)?;
+    if out.json() {
+        print_json(&ReembedResponse {
+            status: "reembedded".to_string(),
+            mismatched_only,
+            reembedded,
+        });
+    } else if !out.quiet() {
+        match (reembedded, mismatched_only) {
+            (0, true) => println!("No memories with mismatched embedding dimensions"),
+            (_, true) => println!(
+                "Re-embedded {} memories with mismatched dimensions",
+                reembedded
+            ),
+            _ => println!("Re-embedded {} memories", reembedded),
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Exits with status 1 if any sampled embedding drifted past `tolerance`.
+pub(super) fn handle_verify_embeddings(
+    store: &mut MemoryStore,
+    sample: usize,
+    seed: Option<u64>,
+    tolerance: f64,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let report = store.verify_embeddings(sample, seed, tolerance)?;
+    let drifted = !report.drifted.is_empty();
+    if out.json() {
+        print_json(&report);
+    } else if !out.quiet() {
+        println!(
+            "Model: {} (revision {})",
+            report.model_id,
+            report.model_revision.as_deref().unwrap_or("unknown")
+        );
+        println!("Seed: {}", report.seed);
+        println!(
+            "Sampled: {} memories ({} with mismatched dimensions skipped)",
+            report.sampled, report.mismatched
+        );
+        println!(
+            "Deviation: max {:.6}, mean {:.6}",
+            report.max_deviation, report.mean_deviation
+        );
+        if report.revisions.len() > 1 || drifted {
+            println!("By stored revision:");
+            for revision in &report.revisions {
+                // Short commit hashes, as git shows them
+                let name = match revision.revision.as_deref() {
+                    Some(commit) => &commit[..commit.len().min(12)],
+                    None => "unrecorded",
+                };
+                println!(
+                    "  {:<12} {:>5} sampled, max {:.6}",
+                    name, revision.sampled, revision.max_deviation
+                );
+            }
+        }
+        if drifted {
+            println!(
+                "{} embeddings drifted past {} (re-embed with vipune reembed):",
+                report.drifted.len(),
+                report.tolerance
+            );
+            for embedding in &report.drifted {
+                println!("  {} {:.6}", embedding.id, embedding.deviation);
+            }
+        }
+    }
+    Ok(if drifted {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+pub(super) fn handle_quantize(
+    store: &MemoryStore,
+    format: EmbeddingFormat,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let converted = store.db.convert_embeddings(format)?;
+    if out.json() {
+        print_json(&QuantizeResponse {
+            status: "converted".to_string(),
+            format: format.to_string(),
+            converted,
+        });
+    } else if !out.quiet() {
+        if converted == 0 {
+            println!("All embeddings are already stored as {}", format);
+        } else {
+            println!("Converted {} embeddings to {}", converted, format);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parse the value of a time `flag` with [`timeparse::parse`]: an RFC3339
+/// timestamp, a date, `today`/`yesterday` or a time ago ("24h", "2w ago").
+pub(super) fn parse_time(flag: &str, value: &str) -> Result<DateTime<Utc>, Error> {
+    timeparse::parse(value).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "Invalid {} value '{}' (expected RFC3339, YYYY-MM-DD, \"yesterday\" or a time ago like \"24h\" or \"2 weeks ago\")",
+            flag, value
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since_rfc3339() {
+        let since = parse_time("--since", "2024-06-01T12:00:00+02:00").unwrap();
+        assert_eq!(since.to_rfc3339(), "2024-06-01T10:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_time_date_is_midnight_utc() {
+        let as_of = parse_time("--as-of", "2024-06-01").unwrap();
+        assert_eq!(as_of.to_rfc3339(), "2024-06-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_since_relative() {
+        let since = parse_time("--since", "24h").unwrap();
+        let expected = Utc::now() - chrono::Duration::hours(24);
+        assert!((since - expected).num_seconds().abs() < 5);
+    }
+
+    #[test]
+    fn test_parse_since_invalid() {
+        assert!(matches!(
+            parse_time("--since", "never"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            parse_time("--as-of", "2024-13-01"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
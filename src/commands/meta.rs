@@ -0,0 +1,151 @@
+//! Handlers for bulk metadata updates (`vipune meta ...`).
+
+use std::process::ExitCode;
+
+use serde_json::{Map, Value};
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::output::*;
+use crate::search_options::{MetadataFilter, SearchOptions};
+use crate::sqlite::{MemoryKind, Origin};
+
+/// Metadata maintenance actions.
+#[derive(clap::Subcommand)]
+pub enum MetaAction {
+    /// Set metadata keys on every memory of the project matching the
+    /// filters (a JSON merge patch)
+    Set {
+        /// Keys to set; VALUE is parsed as JSON if it is valid JSON (e.g.
+        /// 3, true, ["a","b"]) and taken as a string otherwise, and null
+        /// removes the key
+        #[arg(required = true, value_name = "KEY=VALUE")]
+        assignments: Vec<String>,
+
+        /// Only update memories whose metadata KEY equals VALUE, or with
+        /// KEY<VALUE, KEY<=VALUE, KEY>VALUE, KEY>=VALUE compares as a number (repeatable)
+        #[arg(long = "filter", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
+
+        /// Only update memories whose "namespace" metadata equals this value
+        #[arg(long)]
+        namespace: Option<String>,
+
+        /// Only update memories whose "tags" metadata includes this tag (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Only update memories of this kind (fact, preference, decision, task, other)
+        #[arg(long)]
+        kind: Option<MemoryKind>,
+
+        /// Only update memories written through this entry path (cli, mcp,
+        /// api, hook, or import[:source])
+        #[arg(long)]
+        origin: Option<Origin>,
+
+        /// Report what would be updated without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub(super) fn handle_meta(
+    store: &MemoryStore,
+    project_id: &str,
+    action: &MetaAction,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let MetaAction::Set {
+        assignments,
+        filters,
+        namespace,
+        tags,
+        kind,
+        origin,
+        dry_run,
+    } = action;
+
+    let mut patch = Map::new();
+    for assignment in assignments {
+        let (key, value) = parse_assignment(assignment)?;
+        patch.insert(key, value);
+    }
+    let mut options = SearchOptions::default();
+    for spec in filters {
+        options = options.with_metadata_filter(MetadataFilter::parse(spec)?);
+    }
+    if let Some(namespace) = namespace {
+        options = options.with_namespace(namespace);
+    }
+    for tag in tags {
+        options = options.with_tag(tag);
+    }
+    if let Some(kind) = kind {
+        options = options.with_kind(*kind);
+    }
+    if let Some(origin) = origin {
+        options = options.with_origin(origin.clone());
+    }
+    options.validate()?;
+
+    let update = store.update_metadata_where(
+        project_id,
+        &options.search_filter(),
+        &Value::Object(patch),
+        *dry_run,
+    )?;
+    if out.json() {
+        print_json(&MetaSetResponse {
+            status: if *dry_run { "dry_run" } else { "updated" }.to_string(),
+            matched: update.matched,
+            updated: update.updated,
+        });
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let verb = if *dry_run { "Would update" } else { "Updated" };
+    out.status(format!(
+        "{} metadata of {} of {} matching memories",
+        verb,
+        update.updated.len(),
+        update.matched
+    ));
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Split `key=value`, parsing the value as JSON if it is valid JSON and
+/// taking it as a string otherwise.
+fn parse_assignment(assignment: &str) -> Result<(String, Value), Error> {
+    let invalid = |reason: &str| {
+        Error::InvalidInput(format!("Invalid assignment '{}' ({})", assignment, reason))
+    };
+    let (key, value) = assignment
+        .split_once('=')
+        .ok_or_else(|| invalid("expected key=value"))?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(invalid("key must be non-empty"));
+    }
+    let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+    Ok((key.to_string(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_assignment() {
+        let parse = |s: &str| parse_assignment(s).unwrap();
+        assert_eq!(parse("status=done"), ("status".to_string(), json!("done")));
+        assert_eq!(parse(" n = 3"), ("n".to_string(), json!(3)));
+        assert_eq!(parse(r#"tags=["a","b"]"#).1, json!(["a", "b"]));
+        assert_eq!(parse("owner=null").1, Value::Null);
+        assert_eq!(parse("note=a=b").1, json!("a=b"));
+        assert_eq!(parse("empty=").1, json!(""));
+        assert!(parse_assignment("status").is_err());
+        assert!(parse_assignment("=done").is_err());
+    }
+}
@@ -0,0 +1,396 @@
+//! Command handlers for vipune CLI.
+
+mod archive;
+mod args;
+mod daemon;
+mod display;
+mod edit;
+mod embedding;
+mod fixture;
+mod generate;
+mod maintenance;
+mod meta;
+mod model;
+mod profiles;
+mod project;
+mod records;
+mod review;
+mod search;
+mod stats;
+mod sync;
+mod synonym;
+mod transfer;
+mod trash;
+
+use crate::config;
+use crate::errors::Error;
+use crate::hooks::HOOK_EVENT_VAR;
+use crate::list_options::{Field, ListOptions};
+use crate::memory::MemoryStore;
+use crate::output::*;
+use crate::sqlite::{Origin, Visibility};
+pub use archive::ArchiveAction;
+use archive::handle_archive;
+pub use args::{ClusterFormat, Commands, ContextFormat, GroupBy, ImportFormat, SchemaKind};
+use daemon::handle_daemon;
+use edit::handle_edit;
+pub use embedding::EmbeddingAction;
+use embedding::handle_embedding;
+pub use fixture::FixtureAction;
+use fixture::handle_fixture;
+pub use generate::{print_completions, print_manpage, print_schema};
+use maintenance::{
+    handle_audit, handle_doctor, handle_health, handle_prune, handle_quantize, handle_reembed,
+    handle_reindex, handle_verify_embeddings, parse_time,
+};
+pub use meta::MetaAction;
+use meta::handle_meta;
+pub use model::ModelAction;
+use model::handle_model;
+pub use profiles::{ConfigAction, handle_config};
+pub use project::ProjectAction;
+use project::handle_project;
+use records::{
+    handle_add, handle_add_preview, handle_append, handle_delete, handle_find, handle_get,
+    handle_history, handle_list, handle_update,
+};
+pub use review::ReviewArgs;
+use review::handle_review;
+pub use search::SearchArgs;
+use search::{handle_context, handle_search};
+use stats::{handle_clusters, handle_stats};
+use std::process::ExitCode;
+pub use sync::SyncAction;
+use sync::handle_sync;
+pub use synonym::SynonymAction;
+use synonym::handle_synonym;
+use transfer::{ImportContext, handle_export, handle_export_context, handle_import, handle_watch};
+pub use trash::TrashAction;
+use trash::handle_trash;
+
+impl Commands {
+    /// Whether this command generates embeddings and therefore needs the model.
+    pub fn needs_embedder(&self) -> bool {
+        if let Commands::Embedding { action } = self {
+            return action.needs_embedder();
+        }
+        !matches!(
+            self,
+            Commands::Add {
+                via_daemon: true,
+                ..
+            } | Commands::Search(SearchArgs {
+                via_daemon: true,
+                ..
+            }) | Commands::Update { text: None, .. }
+                | Commands::Get { .. }
+                | Commands::History { .. }
+                | Commands::Find { .. }
+                | Commands::List { .. }
+                | Commands::Delete { .. }
+                | Commands::Export { .. }
+                | Commands::ExportContext { .. }
+                | Commands::Reindex { .. }
+                | Commands::Quantize { .. }
+                | Commands::Stats { .. }
+                | Commands::Clusters { .. }
+                | Commands::Prune
+                | Commands::Review { .. }
+                | Commands::Audit { .. }
+                | Commands::Model { .. }
+                | Commands::Fixture { .. }
+                | Commands::Meta { .. }
+                | Commands::Project { .. }
+                | Commands::Synonym { .. }
+                | Commands::Trash { .. }
+                | Commands::Archive { .. }
+                | Commands::Sync { .. }
+                | Commands::Config { .. }
+                | Commands::Completions { .. }
+                | Commands::Manpage
+                | Commands::Schema { .. }
+                | Commands::Version
+        )
+    }
+
+    /// Whether this command re-embeds every memory and may therefore open a
+    /// store whose embeddings come from another model.
+    pub fn switches_model(&self) -> bool {
+        matches!(
+            self,
+            Commands::Reembed {
+                mismatched_only: false
+            }
+        )
+    }
+
+    /// Whether the command was told to store content that looks like a
+    /// secret, overriding `secret_scan`.
+    pub fn allows_secrets(&self) -> bool {
+        matches!(
+            self,
+            Commands::Add {
+                allow_secrets: true,
+                ..
+            } | Commands::Update {
+                allow_secrets: true,
+                ..
+            } | Commands::Append {
+                allow_secrets: true,
+                ..
+            } | Commands::Edit {
+                allow_secrets: true,
+                ..
+            }
+        )
+    }
+
+    /// Visibility the command was told to add memories with, overriding the
+    /// configured one.
+    pub fn visibility(&self) -> Option<Visibility> {
+        match self {
+            Commands::Add { visibility, .. } => *visibility,
+            _ => None,
+        }
+    }
+}
+
+/// Origin recorded on the memories this invocation adds: `VIPUNE_ORIGIN` if
+/// set (e.g. `mcp` for an MCP server that runs the CLI), `hook` inside a hook
+/// command, otherwise `cli`.
+pub fn cli_origin(origin_var: Option<&str>, in_hook: bool) -> Result<Origin, Error> {
+    match origin_var {
+        Some(origin) => origin.parse(),
+        None if in_hook => Ok(Origin::Hook),
+        None => Ok(Origin::Cli),
+    }
+}
+
+/// Execute a CLI command.
+pub fn execute(
+    command: &Commands,
+    store: &mut MemoryStore,
+    project_id: String,
+    project_override: Option<&str>,
+    config: &config::Config,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    store.set_origin(cli_origin(
+        std::env::var("VIPUNE_ORIGIN").ok().as_deref(),
+        std::env::var_os(HOOK_EVENT_VAR).is_some(),
+    )?);
+    match command {
+        Commands::Add { text, dry_run, .. } if *dry_run => {
+            handle_add_preview(store, &project_id, text, out)
+        }
+        Commands::Add {
+            text,
+            metadata,
+            force,
+            kind,
+            title,
+            via_daemon,
+            ..
+        } => handle_add(
+            store,
+            &project_id,
+            text,
+            config
+                .project
+                .apply_defaults(metadata.as_deref())
+                .as_deref(),
+            kind.or(config.project.default_kind).unwrap_or_default(),
+            title.as_deref(),
+            *force,
+            *via_daemon,
+            config,
+            out,
+        ),
+        Commands::Search(args) => handle_search(store, &project_id, args, config, out),
+        Commands::Context {
+            task,
+            budget,
+            limit,
+            recency,
+        } => handle_context(
+            store,
+            &project_id,
+            task,
+            *budget,
+            *limit,
+            *recency,
+            config,
+            out,
+        ),
+        Commands::Get { id } => handle_get(store, id, out),
+        Commands::History { id, diff } => handle_history(store, id, *diff, out),
+        Commands::Find { exact, icase } => handle_find(store, &project_id, exact, *icase, out),
+        Commands::List {
+            limit,
+            kind,
+            origin,
+            since,
+            before,
+            fields,
+        } => {
+            let mut options = ListOptions::default().with_limit(*limit);
+            if let Some(kind) = kind {
+                options = options.with_kind(*kind);
+            }
+            if let Some(origin) = origin {
+                options = options.with_origin(origin.clone());
+            }
+            if let Some(since) = since {
+                options = options.with_since(parse_time("--since", since)?);
+            }
+            if let Some(before) = before {
+                options = options.with_before(parse_time("--before", before)?);
+            }
+            if let Some(fields) = fields {
+                options = options.with_fields(Field::parse_list(fields)?);
+            }
+            handle_list(store, &project_id, &options, out)
+        }
+        Commands::Delete { id } => {
+            handle_delete(store, id, config.trash_retention_days.is_some(), out)
+        }
+        Commands::Update {
+            id,
+            text,
+            metadata,
+            touch,
+            diff,
+            ..
+        } => handle_update(
+            store,
+            id,
+            text.as_deref(),
+            metadata.as_deref(),
+            *touch,
+            *diff,
+            out,
+        ),
+        Commands::Append { id, text, .. } => handle_append(store, id, text, out),
+        Commands::Edit { id, yes, force, .. } => handle_edit(store, id, *yes, *force, out),
+        Commands::Import {
+            path,
+            format,
+            split,
+            roles,
+            map,
+            resume,
+            exclusive,
+            bulk,
+            on_duplicate,
+            threshold,
+            progress,
+        } => handle_import(
+            store,
+            &project_id,
+            project_override,
+            &ImportContext {
+                path,
+                format: *format,
+                split: *split,
+                roles,
+                map,
+                resume: *resume,
+                exclusive: *exclusive,
+                bulk: *bulk,
+                on_duplicate: *on_duplicate,
+                threshold: *threshold,
+                progress: *progress,
+            },
+            out,
+        ),
+        Commands::Export {
+            path,
+            all,
+            no_embeddings,
+        } => handle_export(
+            store,
+            &project_id,
+            path.as_deref(),
+            *all,
+            !*no_embeddings,
+            out,
+        ),
+        Commands::ExportContext { path, format, top } => {
+            handle_export_context(store, &project_id, path.as_deref(), *format, *top, out)
+        }
+        Commands::Watch {
+            path,
+            interval_ms,
+            once,
+            metrics_file,
+        } => handle_watch(
+            store,
+            &project_id,
+            path,
+            *interval_ms,
+            *once,
+            metrics_file.as_deref(),
+            out,
+        ),
+        Commands::Daemon { metrics_file } => {
+            handle_daemon(store, config, metrics_file.as_deref(), out)
+        }
+        Commands::Prune => handle_prune(store, out),
+        Commands::Review(args) => handle_review(store, &project_id, args, &config.review, out),
+        Commands::Audit { since, limit } => {
+            handle_audit(store, &project_id, since.as_deref(), *limit, out)
+        }
+        Commands::Model { action } => {
+            handle_model(action, &config.embedding_model, &config.download, out)
+        }
+        Commands::Fixture { action } => handle_fixture(store, &project_id, action, out),
+        Commands::Meta { action } => handle_meta(store, &project_id, action, out),
+        Commands::Project { action } => handle_project(store, action, out),
+        Commands::Synonym { action } => handle_synonym(store, &project_id, action, out),
+        Commands::Trash { action } => handle_trash(store, &project_id, action, out),
+        Commands::Archive { action } => handle_archive(store, action, out),
+        Commands::Sync { action } => handle_sync(store, action, out),
+        Commands::Embedding { action } => handle_embedding(store, action, out),
+        Commands::Reindex { tokenizer } => handle_reindex(
+            store,
+            tokenizer.unwrap_or(config.fts_tokenizer),
+            config.normalization,
+            out,
+        ),
+        Commands::Reembed { mismatched_only } => handle_reembed(store, *mismatched_only, out),
+        Commands::VerifyEmbeddings {
+            sample,
+            seed,
+            tolerance,
+        } => handle_verify_embeddings(store, *sample, *seed, *tolerance, out),
+        Commands::Quantize { format } => {
+            handle_quantize(store, format.unwrap_or(config.embedding_format), out)
+        }
+        Commands::Stats { embeddings } => handle_stats(store, &project_id, *embeddings, out),
+        Commands::Clusters { k, format } => handle_clusters(store, &project_id, *k, *format, out),
+        Commands::Health => handle_health(store, out),
+        Commands::Doctor {
+            scan_embeddings,
+            dry_run,
+        } => handle_doctor(store, *scan_embeddings, *dry_run, out),
+        Commands::Completions { .. }
+        | Commands::Manpage
+        | Commands::Schema { .. }
+        | Commands::Config { .. } => {
+            unreachable!("handled before the store is opened")
+        }
+        Commands::Version => handle_version(out),
+    }
+}
+
+fn handle_version(out: &Printer) -> Result<ExitCode, Error> {
+    if out.json() {
+        print_json(&serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "name": env!("CARGO_PKG_NAME")
+        }));
+    } else {
+        println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,46 @@
+//! Handlers for embedding model management (`vipune model ...`).
+
+use std::process::ExitCode;
+
+use crate::config::DownloadConfig;
+use crate::errors::Error;
+use crate::model_files::download_model_with;
+use crate::output::*;
+
+/// Model management actions.
+#[derive(clap::Subcommand)]
+pub enum ModelAction {
+    /// Pre-fetch the ONNX model and tokenizer into the local cache
+    Download {
+        /// HuggingFace model ID (default: configured embedding model)
+        #[arg(long)]
+        model: Option<String>,
+    },
+}
+
+pub(super) fn handle_model(
+    action: &ModelAction,
+    default_model: &str,
+    retry: &DownloadConfig,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    match action {
+        ModelAction::Download { model } => {
+            let model_id = model.as_deref().unwrap_or(default_model);
+            let files = download_model_with(model_id, retry)?;
+            if out.json() {
+                print_json(&ModelDownloadResponse {
+                    status: "downloaded".to_string(),
+                    model: model_id.to_string(),
+                    model_path: files.model.display().to_string(),
+                    tokenizer_path: files.tokenizer.display().to_string(),
+                });
+            } else if !out.quiet() {
+                println!("Downloaded model: {}", model_id);
+                println!("Model: {}", files.model.display());
+                println!("Tokenizer: {}", files.tokenizer.display());
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Handlers for inspecting configuration profiles (`vipune config ...`).
+
+use std::process::ExitCode;
+
+use crate::config::Config;
+use crate::errors::Error;
+use crate::output::*;
+
+/// Configuration actions.
+#[derive(clap::Subcommand)]
+pub enum ConfigAction {
+    /// List the profiles defined in the config file (`[profiles.<name>]`),
+    /// with the database and model each one uses
+    Profiles,
+}
+
+/// Run a config action. Needs neither the database nor the model, so it
+/// works before a profile's database exists.
+pub fn handle_config(
+    action: &ConfigAction,
+    profile: Option<&str>,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    match action {
+        ConfigAction::Profiles => {
+            let selected = Config::selected_profile(profile);
+            let mut profiles = Vec::new();
+            for name in Config::profile_names()? {
+                let config = Config::load_profile(Some(&name))?;
+                profiles.push(ProfileResponse {
+                    active: selected.as_deref() == Some(name.as_str()),
+                    name,
+                    database_path: config.database_path.display().to_string(),
+                    embedding_model: config.embedding_model,
+                });
+            }
+            if out.json() {
+                print_json(&ProfilesResponse {
+                    active: selected,
+                    profiles,
+                });
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            if out.quiet() {
+                for profile in &profiles {
+                    println!("{}", profile.name);
+                }
+            } else if profiles.is_empty() {
+                println!(
+                    "No profiles defined (add [profiles.<name>] tables to {})",
+                    crate::config::config_path().display()
+                );
+            } else {
+                for profile in &profiles {
+                    println!(
+                        "{} {}: {} ({})",
+                        if profile.active { "*" } else { " " },
+                        profile.name,
+                        profile.database_path,
+                        profile.embedding_model
+                    );
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Handlers for project ID maintenance (`vipune project ...`).
+
+use std::process::ExitCode;
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::output::*;
+
+use super::maintenance::parse_time;
+
+/// Project maintenance actions.
+#[derive(clap::Subcommand)]
+pub enum ProjectAction {
+    /// Rename stored project IDs to their normalized form (lowercase, no
+    /// `.git` suffix), merging projects that differ only in spelling
+    Normalize {
+        /// Report what would be renamed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Delete projects without live memories, or whose memories have not
+    /// been updated or accessed for a while, including their archive and
+    /// trash (only reported without --yes)
+    Prune {
+        /// Delete projects inactive for this long (e.g. "180d" or "6 months"),
+        /// or since a time (RFC3339, YYYY-MM-DD or "yesterday")
+        #[arg(
+            long,
+            value_name = "AGE",
+            default_value = "180d",
+            conflicts_with = "empty_only"
+        )]
+        older_than: String,
+
+        /// Only delete projects that have no live memories
+        #[arg(long)]
+        empty_only: bool,
+
+        /// Delete the projects instead of only reporting them
+        #[arg(short = 'y', long, alias = "force")]
+        yes: bool,
+
+        /// Report what would be deleted without changing anything (the
+        /// default)
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+}
+
+pub(super) fn handle_project(
+    store: &MemoryStore,
+    action: &ProjectAction,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    match action {
+        ProjectAction::Normalize { dry_run } => {
+            let renames = store.normalize_projects(*dry_run)?;
+            if out.json() {
+                print_json(&ProjectNormalizeResponse {
+                    status: if *dry_run { "dry_run" } else { "normalized" }.to_string(),
+                    renames,
+                });
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            if out.quiet() {
+                return Ok(ExitCode::SUCCESS);
+            }
+            if renames.is_empty() {
+                println!("All project IDs are normalized");
+                return Ok(ExitCode::SUCCESS);
+            }
+            let verb = if *dry_run { "Would rename" } else { "Renamed" };
+            for rename in &renames {
+                let merge = if rename.merged { " (merged)" } else { "" };
+                println!(
+                    "{} {} -> {}: {} memories{}",
+                    verb, rename.from, rename.to, rename.memories, merge
+                );
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        ProjectAction::Prune {
+            older_than,
+            empty_only,
+            yes,
+            ..
+        } => {
+            let dry_run = !*yes;
+            let cutoff = if *empty_only {
+                None
+            } else {
+                Some(parse_time("--older-than", older_than)?)
+            };
+            let projects = store.prune_projects(cutoff, dry_run)?;
+            if out.json() {
+                print_json(&ProjectPruneResponse {
+                    status: if dry_run { "dry_run" } else { "pruned" }.to_string(),
+                    projects,
+                });
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            if out.quiet() {
+                return Ok(ExitCode::SUCCESS);
+            }
+            if projects.is_empty() {
+                println!("No projects to prune");
+                return Ok(ExitCode::SUCCESS);
+            }
+            let verb = if dry_run { "Would delete" } else { "Deleted" };
+            for project in &projects {
+                let activity = match &project.last_activity {
+                    Some(last) => format!("last active {}", last),
+                    None => "empty".to_string(),
+                };
+                println!(
+                    "{} {}: {} memories, {} archived, {} in trash ({})",
+                    verb,
+                    project.project_id,
+                    project.memories,
+                    project.archived,
+                    project.trashed,
+                    activity
+                );
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
@@ -0,0 +1,456 @@
+//! Handlers for single-memory commands (add, get, history, list, find,
+//! delete, update, append).
+
+use std::process::ExitCode;
+
+use crate::config;
+use crate::daemon::DaemonRequest;
+use crate::diff;
+use crate::embedding::MAX_TOKENS;
+use crate::errors::Error;
+use crate::list_options::{ListOptions, SelectedFields};
+use crate::memory::MemoryStore;
+use crate::memory_types::{AddPreview, AddResult, ConflictMemory};
+use crate::output::*;
+use crate::sqlite::{Memory, MemoryKind};
+
+use super::daemon::daemon_request;
+use super::display::{kind_tag, print_selected, selected};
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn handle_add(
+    store: &mut MemoryStore,
+    project_id: &str,
+    text: &str,
+    metadata: Option<&str>,
+    kind: MemoryKind,
+    title: Option<&str>,
+    force: bool,
+    via_daemon: bool,
+    config: &config::Config,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    if title.is_some_and(|title| title.trim().is_empty()) {
+        return Err(Error::InvalidInput("Title cannot be empty".to_string()));
+    }
+    let result = if via_daemon {
+        let request = DaemonRequest::Add {
+            project_id: project_id.to_string(),
+            content: text.to_string(),
+            metadata: metadata.map(str::to_string),
+            kind,
+            title: title.map(str::to_string),
+            force,
+            origin: Some(store.origin().clone()),
+            owner: store.owner().map(str::to_string),
+            visibility: store.owner().map(|_| store.visibility()),
+        };
+        daemon_request(config, &request)?.into_add_result()?
+    } else {
+        let result = store.add_with_kind(project_id, text, metadata, kind, force)?;
+        if let AddResult::Added { id } = &result
+            && title.is_some()
+        {
+            store.set_title(id, title)?;
+        }
+        result
+    };
+    match result {
+        AddResult::Added { id } => {
+            if out.json() {
+                print_json(&AddResponse {
+                    status: "added".to_string(),
+                    id,
+                    seq: store.write_seq()?,
+                });
+            } else {
+                out.result(&id, format_args!("Added memory: {}", id));
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        AddResult::Conflicts {
+            proposed,
+            conflicts,
+        } => {
+            print_conflicts(proposed, conflicts, "add", out);
+            Ok(ExitCode::from(2))
+        }
+    }
+}
+
+pub(super) fn handle_add_preview(
+    store: &mut MemoryStore,
+    project_id: &str,
+    text: &str,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    match store.preview_add(project_id, text)? {
+        AddPreview::WouldAdd => {
+            if out.json() {
+                print_json(&AddPreviewResponse {
+                    status: "would_add".to_string(),
+                });
+            } else {
+                out.status("Would add memory (no conflicts)");
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        AddPreview::Conflicts {
+            proposed,
+            conflicts,
+        } => {
+            print_conflicts(proposed, conflicts, "add", out);
+            Ok(ExitCode::from(2))
+        }
+    }
+}
+
+/// Report conflicts found for `proposed` content; `action` names what
+/// `--force` does ("add", "save"). Quiet output lists the conflicting IDs.
+pub(super) fn print_conflicts(
+    proposed: String,
+    conflicts: Vec<ConflictMemory>,
+    action: &str,
+    out: &Printer,
+) {
+    if out.json() {
+        let conflict_responses: Vec<ConflictMemoryResponse> = conflicts
+            .into_iter()
+            .map(|c| ConflictMemoryResponse {
+                id: c.id,
+                content: c.content,
+                similarity: c.similarity,
+                overlap: c.overlap,
+                confidence: c.confidence,
+                metadata: c.metadata,
+                created_at: c.created_at,
+                updated_at: c.updated_at,
+            })
+            .collect();
+        print_json(&ConflictsResponse {
+            status: "conflicts".to_string(),
+            proposed,
+            conflicts: conflict_responses,
+        });
+    } else if out.quiet() {
+        for conflict in conflicts {
+            println!("{}", conflict.id);
+        }
+    } else {
+        println!(
+            "{}: {} similar memory/memories found",
+            out.warning("Conflicts detected"),
+            conflicts.len()
+        );
+        println!("Proposed: {}", proposed);
+        println!("Use --force to {} anyway", action);
+        for conflict in conflicts {
+            println!(
+                "  {} (confidence: {}, similarity: {}, overlap: {}, updated: {})",
+                conflict.id,
+                out.score(conflict.confidence, 3),
+                out.score(conflict.similarity, 3),
+                out.score(conflict.overlap, 3),
+                out.time(&conflict.updated_at)
+            );
+            let changes = diff::words(&conflict.content, &proposed);
+            println!("    {}", out.word_diff(&changes));
+            if let Some(metadata) = &conflict.metadata {
+                println!("    metadata: {}", metadata);
+            }
+        }
+    }
+}
+
+pub(super) fn handle_get(
+    store: &mut MemoryStore,
+    id: &str,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let memory = store
+        .get(id)?
+        .ok_or_else(|| Error::NotFound("memory not found".to_string()))?;
+    if out.json() {
+        print_json(&GetResponse {
+            token_count: store.count_tokens(&memory.content).ok(),
+            id: memory.id.clone(),
+            content: memory.content.clone(),
+            title: memory.title,
+            project_id: memory.project_id,
+            metadata: memory.metadata,
+            kind: memory.kind,
+            origin: memory.origin.clone(),
+            created_at: memory.created_at,
+            updated_at: memory.updated_at,
+        });
+    } else if out.quiet() {
+        println!("{}", memory.content);
+    } else {
+        println!("ID: {}", memory.id);
+        if let Some(title) = &memory.title {
+            println!("Title: {}", title);
+        }
+        println!("Content: {}", memory.content);
+        println!("Project: {}", memory.project_id);
+        println!("Kind: {}", memory.kind);
+        println!("Origin: {}", memory.origin);
+        if let Ok(tokens) = store.count_tokens(&memory.content) {
+            if tokens > MAX_TOKENS {
+                println!(
+                    "Tokens: {} (only the first {} are embedded)",
+                    tokens, MAX_TOKENS
+                );
+            } else {
+                println!("Tokens: {}", tokens);
+            }
+        }
+        if let Some(meta) = &memory.metadata {
+            println!("Metadata: {}", meta);
+        }
+        println!("Created: {}", out.time(&memory.created_at));
+        println!("Updated: {}", out.time(&memory.updated_at));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_history(
+    store: &mut MemoryStore,
+    id: &str,
+    show_diff: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let memory = store
+        .get(id)?
+        .ok_or_else(|| Error::NotFound("memory not found".to_string()))?;
+    let mut versions: Vec<HistoryVersion> = store
+        .history(id)?
+        .into_iter()
+        .map(|version| HistoryVersion {
+            content: version.content,
+            metadata: version.metadata,
+            updated_at: version.updated_at,
+            replaced_at: Some(version.replaced_at),
+            diff: None,
+        })
+        .collect();
+    versions.push(HistoryVersion {
+        content: memory.content,
+        metadata: memory.metadata,
+        updated_at: memory.updated_at,
+        replaced_at: None,
+        diff: None,
+    });
+
+    let changes: Vec<Option<Vec<diff::Change>>> = (0..versions.len())
+        .map(|i| {
+            (show_diff && i > 0)
+                .then(|| diff::words(&versions[i - 1].content, &versions[i].content))
+        })
+        .collect();
+    if out.json() {
+        for (version, changes) in versions.iter_mut().zip(&changes) {
+            version.diff = changes.as_deref().map(diff::render_words);
+        }
+        print_json(&HistoryResponse {
+            id: memory.id,
+            versions,
+        });
+        return Ok(ExitCode::SUCCESS);
+    }
+    for (i, (version, changes)) in versions.iter().zip(&changes).enumerate() {
+        match version.replaced_at {
+            Some(_) => println!("[{}] {}", i + 1, out.time(&version.updated_at)),
+            None => println!("[current] {}", out.time(&version.updated_at)),
+        }
+        match changes {
+            Some(changes) => println!("    {}", out.word_diff(changes)),
+            None => println!("    {}", version.content),
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_list(
+    store: &mut MemoryStore,
+    project_id: &str,
+    options: &ListOptions,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let memories = store.list_with(project_id, options)?;
+    if let Some(fields) = &options.fields {
+        if out.json() {
+            let items: Vec<SelectedFields> = memories
+                .iter()
+                .map(|m| selected(store, m, fields))
+                .collect();
+            print_json(&ListResponse { memories: items });
+        } else {
+            for memory in &memories {
+                print_selected(&selected(store, memory, fields), out);
+            }
+        }
+    } else {
+        print_memories(store, memories, out);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print memories as `list` does: their ID and title, or as a JSON list.
+pub(super) fn print_memories(store: &MemoryStore, memories: Vec<Memory>, out: &Printer) {
+    if out.json() {
+        let items: Vec<ListItem> = memories
+            .into_iter()
+            .map(|m| ListItem {
+                token_count: store.count_tokens(&m.content).ok(),
+                id: m.id,
+                title: m.title,
+                content: m.content,
+                kind: m.kind,
+                created_at: m.created_at,
+            })
+            .collect();
+        print_json(&ListResponse { memories: items });
+    } else {
+        for memory in memories {
+            out.result(
+                &memory.id,
+                format_args!(
+                    "{}{}: {}",
+                    memory.id,
+                    kind_tag(memory.kind),
+                    memory.title.as_deref().unwrap_or(&memory.content)
+                ),
+            );
+        }
+    }
+}
+
+/// Print the memories whose content is `content` (up to case with
+/// `ignore_case`) as `list` does; none found is an error.
+pub(super) fn handle_find(
+    store: &mut MemoryStore,
+    project_id: &str,
+    content: &str,
+    ignore_case: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let memories = store.find_exact(project_id, content, ignore_case)?;
+    if memories.is_empty() {
+        return Err(Error::NotFound("no memory has this content".to_string()));
+    }
+    print_memories(store, memories, out);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Delete a memory, or move it to the trash when `trash` is set.
+pub(super) fn handle_delete(
+    store: &mut MemoryStore,
+    id: &str,
+    trash: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let deleted = store.delete(id)?;
+    if deleted {
+        if out.json() {
+            print_json(&DeleteResponse {
+                status: if trash { "trashed" } else { "deleted" }.to_string(),
+                id: id.to_string(),
+                seq: store.write_seq()?,
+            });
+        } else if trash {
+            out.status(format_args!(
+                "Moved memory to trash: {} (undo with `vipune trash restore {}`)",
+                id, id
+            ));
+        } else {
+            out.status(format_args!("Deleted memory: {}", id));
+        }
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Err(Error::NotFound("memory not found".to_string()))
+    }
+}
+
+/// Update content (re-embedding it), metadata, both, or with `touch` just
+/// the update time.
+pub(super) fn handle_update(
+    store: &mut MemoryStore,
+    id: &str,
+    text: Option<&str>,
+    metadata: Option<&str>,
+    touch: bool,
+    diff: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let metadata = metadata.map(parse_metadata_arg).transpose()?;
+    if touch {
+        store.touch(id)?;
+    }
+    let mut changes = None;
+    if let Some(text) = text {
+        if diff {
+            let old = store
+                .get(id)?
+                .ok_or_else(|| Error::NotFound("memory not found".to_string()))?;
+            changes = Some(diff::words(&old.content, text));
+        }
+        store.update(id, text)?;
+    }
+    if let Some(metadata) = &metadata {
+        store.update_metadata(id, metadata.as_deref())?;
+    }
+    let status = if touch { "touched" } else { "updated" };
+    if out.json() {
+        print_json(&UpdateResponse {
+            status: status.to_string(),
+            id: id.to_string(),
+            seq: store.write_seq()?,
+        });
+    } else if touch {
+        out.status(format_args!("Touched memory: {}", id));
+    } else if text.is_none() {
+        out.status(format_args!("Updated metadata of memory: {}", id));
+    } else {
+        out.status(format_args!("Updated memory: {}", id));
+        if let Some(changes) = changes.filter(|changes| diff::has_changes(changes)) {
+            out.status(format_args!("    {}", out.word_diff(&changes)));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_append(
+    store: &mut MemoryStore,
+    id: &str,
+    text: &str,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let content = store.append(id, text)?;
+    if out.json() {
+        print_json(&UpdateResponse {
+            status: "appended".to_string(),
+            id: id.to_string(),
+            seq: store.write_seq()?,
+        });
+    } else {
+        out.status(format_args!("Appended to memory: {}", id));
+        out.status(format_args!(
+            "    {}",
+            content.rsplit("\n\n").next().unwrap_or_default()
+        ));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Metadata given to `update --metadata`: a JSON object, or `null` (`None`)
+/// to clear it.
+pub(super) fn parse_metadata_arg(raw: &str) -> Result<Option<String>, Error> {
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(serde_json::Value::Null) => Ok(None),
+        Ok(serde_json::Value::Object(_)) => Ok(Some(raw.trim().to_string())),
+        Ok(_) => Err(Error::InvalidInput(
+            "metadata must be a JSON object or null".to_string(),
+        )),
+        Err(e) => Err(Error::InvalidInput(format!("invalid metadata JSON: {}", e))),
+    }
+}
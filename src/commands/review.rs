@@ -0,0 +1,170 @@
+//! Handler for the review queue (`vipune review`).
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::config::ReviewConfig;
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::memory_types::{ReviewAction, ReviewDecision};
+use crate::output::*;
+
+/// Flags of `vipune review`.
+#[derive(clap::Args)]
+pub struct ReviewArgs {
+    /// Days without an update, access or review before a memory is listed
+    /// (default: review.stale_days, 90)
+    #[arg(long)]
+    pub days: Option<u32>,
+
+    /// Highest decayed importance listed (default: review.max_importance, 3.0)
+    #[arg(long, value_name = "IMPORTANCE")]
+    pub max_importance: Option<f64>,
+
+    /// Maximum number of memories listed (default: 20)
+    #[arg(short = 'l', long, default_value = "20")]
+    pub limit: usize,
+
+    /// Keep this memory, restarting its idle time (repeatable)
+    #[arg(long, value_name = "ID")]
+    pub keep: Vec<String>,
+
+    /// Move this memory to the archive table (repeatable)
+    #[arg(long, value_name = "ID")]
+    pub archive: Vec<String>,
+
+    /// Delete this memory (repeatable)
+    #[arg(long, value_name = "ID")]
+    pub delete: Vec<String>,
+
+    /// Apply decisions from a JSON file ("-" for stdin):
+    /// [{"id": "...", "action": "keep" | "archive" | "delete"}, ...]
+    #[arg(long, value_name = "PATH")]
+    pub decisions: Option<PathBuf>,
+}
+
+pub(super) fn handle_review(
+    store: &MemoryStore,
+    project_id: &str,
+    opts: &ReviewArgs,
+    config: &ReviewConfig,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let mut decisions = match &opts.decisions {
+        Some(path) => read_decisions(path)?,
+        None => Vec::new(),
+    };
+    for (ids, action) in [
+        (&opts.keep, ReviewAction::Keep),
+        (&opts.archive, ReviewAction::Archive),
+        (&opts.delete, ReviewAction::Delete),
+    ] {
+        decisions.extend(ids.iter().map(|id| ReviewDecision {
+            id: id.clone(),
+            action,
+        }));
+    }
+    if !decisions.is_empty() {
+        return apply_decisions(store, &decisions, out);
+    }
+
+    let mut review = *config;
+    if let Some(days) = opts.days {
+        review.stale_days = days;
+    }
+    if let Some(max_importance) = opts.max_importance {
+        review.max_importance = max_importance;
+    }
+    review.validate()?;
+
+    let candidates = store.review_queue(project_id, &review, opts.limit)?;
+    if out.json() {
+        print_json(&ReviewQueueResponse {
+            project_id: project_id.to_string(),
+            stale_days: review.stale_days,
+            max_importance: review.max_importance,
+            candidates,
+        });
+    } else if candidates.is_empty() {
+        out.status("No memories due for review");
+    } else {
+        for candidate in &candidates {
+            let importance = match candidate.importance {
+                Some(importance) => format!(
+                    "importance {} -> {:.1}",
+                    importance, candidate.decayed_importance
+                ),
+                None => "no importance".to_string(),
+            };
+            out.result(
+                &candidate.id,
+                format_args!(
+                    "{} [idle {:.0}d, {}]: {}",
+                    candidate.id, candidate.idle_days, importance, candidate.content
+                ),
+            );
+        }
+        out.status(format_args!(
+            "\nDecide with --keep, --archive or --delete <id> (repeatable)"
+        ));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Apply decisions, exiting with status 1 if any ID matched no memory.
+fn apply_decisions(
+    store: &MemoryStore,
+    decisions: &[ReviewDecision],
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let stats = store.apply_review(decisions)?;
+    let complete = stats.not_found.is_empty();
+    if out.json() {
+        print_json(&ReviewResponse {
+            status: "reviewed".to_string(),
+            kept: stats.kept,
+            archived: stats.archived,
+            deleted: stats.deleted,
+            not_found: stats.not_found,
+        });
+    } else {
+        out.status(format_args!(
+            "Kept {}, archived {}, deleted {}",
+            stats.kept, stats.archived, stats.deleted
+        ));
+        for id in &stats.not_found {
+            eprintln!("Not found: {}", id);
+        }
+    }
+    Ok(if complete {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+/// Decisions from a JSON array of `{"id": ..., "action": ...}` objects in
+/// `path` (`-` for stdin).
+fn read_decisions(path: &Path) -> Result<Vec<ReviewDecision>, Error> {
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map(|_| text)
+    } else {
+        std::fs::read_to_string(path)
+    }
+    .map_err(|e| {
+        Error::InvalidInput(format!(
+            "Cannot read decisions file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    serde_json::from_str(&text).map_err(|e| {
+        Error::InvalidInput(format!(
+            "Invalid review decisions (expected a JSON array of {{\"id\", \"action\"}} with \
+             action keep, archive or delete): {}",
+            e
+        ))
+    })
+}
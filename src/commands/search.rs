@@ -0,0 +1,404 @@
+//! Handlers for semantic and hybrid search (`vipune search`, `vipune context`).
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use crate::config;
+use crate::daemon::DaemonRequest;
+use crate::errors::Error;
+use crate::list_options::Field;
+use crate::memory::MemoryStore;
+use crate::output::*;
+use crate::rrf::FusionStrategy;
+use crate::search_options::{
+    FieldWeights, MetadataFilter, QuerySyntax, Rank, SearchOptions, SearchStrategy,
+};
+use crate::sqlite::{MemoryKind, Origin};
+use crate::temporal;
+
+use super::GroupBy;
+use super::daemon::daemon_request;
+use super::display::{print_result, search_items};
+use super::maintenance::parse_time;
+
+/// Flags of `vipune search`.
+#[derive(clap::Args)]
+#[command(about = None, long_about = None)] // Keep the doc comment out of `--help`
+pub struct SearchArgs {
+    /// Search query text
+    #[arg(required_unless_present = "queries_file")]
+    pub query: Option<String>,
+
+    /// Run every query in this file (one per line; blank lines and lines
+    /// starting with '#' are skipped), embedding them in one batch
+    #[arg(long, value_name = "PATH", conflicts_with = "query")]
+    pub queries_file: Option<PathBuf>,
+
+    /// Maximum number of results (default: 5)
+    #[arg(short = 'l', long, default_value = "5")]
+    pub limit: usize,
+
+    /// Recency weight for search results (0.0 to 1.0)
+    #[arg(long)]
+    pub recency: Option<f64>,
+
+    /// Use hybrid search (semantic + BM25 with RRF fusion)
+    #[arg(long)]
+    pub hybrid: bool,
+
+    /// How --hybrid combines rankings: rrf, minmax, zscore or weighted[:W] (default: rrf)
+    #[arg(long, value_name = "STRATEGY", requires = "hybrid")]
+    pub fusion: Option<FusionStrategy>,
+
+    /// Let every query word also match the words it begins ("auth"
+    /// matches "authentication") in keyword search
+    #[arg(long, requires = "hybrid", conflicts_with = "query_syntax")]
+    pub prefix: bool,
+
+    /// Read keyword search syntax in the query: `auth*` matches by prefix,
+    /// and `content:WORD` or `metadata:WORD` limits a word to the content
+    /// or the title and tags metadata
+    #[arg(long, requires = "hybrid")]
+    pub query_syntax: bool,
+
+    /// Diversify results with MMR (0.0 = off, 1.0 = max diversity)
+    #[arg(long, default_value = "0.0")]
+    pub diversity: f64,
+
+    /// Only return memories with cosine similarity to the query of at least this (0.0 to 1.0)
+    #[arg(long, value_name = "SCORE")]
+    pub min_score: Option<f64>,
+
+    /// Only return memories whose metadata KEY equals VALUE, or with
+    /// KEY<VALUE, KEY<=VALUE, KEY>VALUE, KEY>=VALUE compares as a number (repeatable)
+    #[arg(long = "filter", value_name = "KEY=VALUE")]
+    pub filters: Vec<String>,
+
+    /// Only return memories whose "namespace" metadata equals this value
+    #[arg(long)]
+    pub namespace: Option<String>,
+
+    /// Only return memories whose "tags" metadata includes this tag (repeatable)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Only return memories of this kind (fact, preference, decision, task, other)
+    #[arg(long)]
+    pub kind: Option<MemoryKind>,
+
+    /// Only return memories written through this entry path (cli, mcp, api,
+    /// hook, or import[:source])
+    #[arg(long)]
+    pub origin: Option<Origin>,
+
+    /// Only return memories updated at or after this time (RFC3339,
+    /// YYYY-MM-DD, "yesterday" or a time ago like "7d" or "2 weeks ago")
+    #[arg(long, value_name = "TIME")]
+    pub since: Option<String>,
+
+    /// Steer away from memories about this term or concept (repeatable)
+    #[arg(long = "not", value_name = "TEXT")]
+    pub exclude: Vec<String>,
+
+    /// Search as of a past time (RFC3339, YYYY-MM-DD, "yesterday" or a time
+    /// ago like "7d"), ignoring memories added or updated after it
+    #[arg(long, value_name = "TIME")]
+    pub as_of: Option<String>,
+
+    /// Also search memories marked inactive by `prune` after
+    /// `inactive_after_days` without use
+    #[arg(long, alias = "include-archived")]
+    pub include_inactive: bool,
+
+    /// Rerank the top candidates with the `[rerank]` cross-encoder
+    /// (needs vipune built with the `rerank` feature)
+    #[arg(long)]
+    pub rerank: bool,
+
+    /// Rescore the top candidates by late interaction with their stored
+    /// token embeddings (needs vipune built with the `late-interaction`
+    /// feature and `[late_interaction] enabled` when memories are added)
+    #[arg(long)]
+    pub late_interaction: bool,
+
+    /// Include a score breakdown for each result
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Also print each result's project, update time, tags and metadata
+    #[arg(long)]
+    pub details: bool,
+
+    /// Result ranking: score, or frecency to boost often and recently used memories (default: score)
+    #[arg(long, value_name = "RANK")]
+    pub rank: Option<Rank>,
+
+    /// Also blend stored synonyms of the query words into the semantic
+    /// query (keyword search always matches them)
+    #[arg(long)]
+    pub expand_synonyms: bool,
+
+    /// BM25 weight of keyword matches in the title and tags metadata,
+    /// relative to 1.0 for the content (default: 2.0)
+    #[arg(long, value_name = "WEIGHT", requires = "hybrid")]
+    pub metadata_weight: Option<f64>,
+
+    /// Fail unless the store includes the write that reported this `seq`
+    /// (see the JSON output of add, update and delete)
+    #[arg(long, value_name = "SEQ")]
+    pub min_seq: Option<i64>,
+
+    /// With --min-seq, wait up to this many milliseconds for the write
+    /// to become visible before failing (default: 0)
+    #[arg(long, value_name = "MS", default_value_t = 0, requires = "min_seq")]
+    pub wait_ms: u64,
+
+    /// Search every project in the database, not just the current one
+    #[arg(long, conflicts_with = "queries_file")]
+    pub all_projects: bool,
+
+    /// Run the search in the running `vipune daemon` instead of loading
+    /// the model in this process
+    #[arg(long, conflicts_with_all = ["queries_file", "all_projects"])]
+    pub via_daemon: bool,
+
+    /// With --all-projects, bucket results per project
+    #[arg(long, value_enum, value_name = "FIELD", requires = "all_projects")]
+    pub group_by: Option<GroupBy>,
+
+    /// With --group-by, results shown per project (default: 3)
+    #[arg(long, value_name = "N", default_value_t = 3, requires = "group_by")]
+    pub per_project: usize,
+
+    /// Only show these comma-separated fields, in this order (id, project_id,
+    /// content, score, kind, metadata, snippet, explanation, token_count,
+    /// created_at, updated_at)
+    #[arg(long, value_name = "FIELDS")]
+    pub fields: Option<String>,
+}
+
+pub(super) fn handle_search(
+    store: &mut MemoryStore,
+    project_id: &str,
+    opts: &SearchArgs,
+    config: &config::Config,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let recency_weight = opts.recency.unwrap_or(config.recency_weight);
+    temporal::validate_recency_weight(recency_weight)?;
+    let strategy = if opts.hybrid {
+        SearchStrategy::Hybrid
+    } else {
+        SearchStrategy::Semantic
+    };
+    let mut options = SearchOptions::default()
+        .with_limit(opts.limit)
+        .with_recency_weight(recency_weight)
+        .with_strategy(strategy)
+        .with_diversity(opts.diversity)
+        .with_explain(opts.explain)
+        .with_synonym_embeddings(opts.expand_synonyms)
+        .with_include_inactive(opts.include_inactive)
+        .with_rerank(opts.rerank)
+        .with_late_interaction(opts.late_interaction);
+    for spec in &opts.filters {
+        options = options.with_metadata_filter(MetadataFilter::parse(spec)?);
+    }
+    let namespace = opts
+        .namespace
+        .as_ref()
+        .or(config.project.namespace.as_ref());
+    if let Some(namespace) = namespace {
+        options = options.with_namespace(namespace);
+    }
+    for tag in &opts.tags {
+        options = options.with_tag(tag);
+    }
+    if let Some(min_score) = opts.min_score {
+        options = options.with_min_similarity(min_score);
+    }
+    if let Some(kind) = opts.kind {
+        options = options.with_kind(kind);
+    }
+    if let Some(origin) = &opts.origin {
+        options = options.with_origin(origin.clone());
+    }
+    if let Some(fusion) = opts.fusion {
+        options = options.with_fusion(fusion);
+    }
+    if opts.prefix {
+        options = options.with_query_syntax(QuerySyntax::Prefix);
+    } else if opts.query_syntax {
+        options = options.with_query_syntax(QuerySyntax::Fts);
+    }
+    for term in &opts.exclude {
+        options = options.with_exclude(term);
+    }
+    if let Some(as_of) = &opts.as_of {
+        options = options.with_as_of(parse_time("--as-of", as_of)?);
+    }
+    if let Some(since) = &opts.since {
+        options = options.with_since(parse_time("--since", since)?);
+    }
+    if let Some(rank) = opts.rank {
+        options = options.with_rank(rank);
+    }
+    if let Some(metadata_weight) = opts.metadata_weight {
+        let content_weight = FieldWeights::default().content;
+        options = options.with_field_weights(content_weight, metadata_weight);
+    }
+    let fields = opts.fields.as_deref().map(Field::parse_list).transpose()?;
+    let fields = fields.as_deref();
+    if let Some(min_seq) = opts.min_seq {
+        store.wait_for_seq(min_seq, Duration::from_millis(opts.wait_ms))?;
+    }
+
+    if let Some(path) = &opts.queries_file {
+        let queries = read_queries(path)?;
+        let refs: Vec<&str> = queries.iter().map(String::as_str).collect();
+        let results = store.search_batch(project_id, &refs, options)?;
+        if out.json() {
+            let queries = queries
+                .into_iter()
+                .zip(results)
+                .map(|(query, memories)| BatchSearchItem {
+                    query,
+                    results: search_items(store, memories, fields),
+                })
+                .collect();
+            print_json(&BatchSearchResponse { queries });
+        } else {
+            for (query, memories) in queries.iter().zip(results) {
+                out.status(format_args!("Query: {}\n", query));
+                for memory in memories {
+                    print_result(store, &memory, fields, opts.details, out);
+                }
+            }
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let query = opts.query.as_deref().unwrap_or_default();
+    if opts.all_projects {
+        return print_all_projects_search(store, query, options, opts, fields, out);
+    }
+    let memories = if opts.via_daemon {
+        let request = DaemonRequest::Search {
+            project_id: project_id.to_string(),
+            query: query.to_string(),
+            options,
+            owner: store.owner().map(str::to_string),
+        };
+        daemon_request(config, &request)?.into_memories()?
+    } else {
+        store.query(project_id, query, options)?
+    };
+    if out.json() {
+        let results = search_items(store, memories, fields);
+        print_json(&SearchResponse { results });
+    } else {
+        for memory in memories {
+            print_result(store, &memory, fields, opts.details, out);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Search every project and print the hits, grouped per project with
+/// `--group-by project`.
+pub(super) fn print_all_projects_search(
+    store: &mut MemoryStore,
+    query: &str,
+    options: SearchOptions,
+    opts: &SearchArgs,
+    fields: Option<&[Field]>,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let limit = options.limit;
+    let results = store.query_all_projects(query, options)?;
+
+    let Some(GroupBy::Project) = opts.group_by else {
+        let memories = results.top(limit);
+        if out.json() {
+            let results = search_items(store, memories, fields);
+            print_json(&SearchResponse { results });
+        } else {
+            for memory in memories {
+                if fields.is_none() {
+                    out.status(format_args!("[{}]", memory.project_id));
+                }
+                print_result(store, &memory, fields, opts.details, out);
+            }
+        }
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let groups = results.grouped(opts.per_project);
+    if out.json() {
+        let groups = groups
+            .into_iter()
+            .map(|group| SearchGroup {
+                project_id: group.project_id,
+                count: group.count,
+                results: search_items(store, group.memories, fields),
+            })
+            .collect();
+        print_json(&GroupedSearchResponse { groups });
+    } else {
+        for group in groups {
+            out.status(format_args!(
+                "Project: {} ({} hits)\n",
+                group.project_id, group.count
+            ));
+            for memory in group.memories {
+                print_result(store, &memory, fields, opts.details, out);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Queries from a `--queries-file`: one per line, skipping blank lines and
+/// `#` comments.
+pub(super) fn read_queries(path: &Path) -> Result<Vec<String>, Error> {
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        Error::InvalidInput(format!(
+            "Cannot read queries file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn handle_context(
+    store: &mut MemoryStore,
+    project_id: &str,
+    task: &str,
+    budget: usize,
+    limit: usize,
+    recency: Option<f64>,
+    config: &config::Config,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let recency_weight = recency.unwrap_or(config.recency_weight);
+    temporal::validate_recency_weight(recency_weight)?;
+    let options = SearchOptions::default()
+        .with_limit(limit)
+        .with_recency_weight(recency_weight)
+        .with_strategy(SearchStrategy::Hybrid);
+    let pack = store.context(project_id, task, budget, options)?;
+    if out.json() {
+        print_json(&pack);
+    } else {
+        print!("{}", pack.text);
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,184 @@
+//! Handlers for store statistics (stats, clusters).
+
+use std::process::ExitCode;
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::memory_types::EmbeddingStats;
+use crate::output::*;
+
+use super::ClusterFormat;
+
+/// Most central memories shown under each cluster in human output.
+const CLUSTER_EXAMPLES: usize = 3;
+
+pub(super) fn handle_clusters(
+    store: &MemoryStore,
+    project_id: &str,
+    k: usize,
+    format: Option<ClusterFormat>,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let clustering = store.clusters(project_id, k)?;
+    match format {
+        Some(ClusterFormat::Json) => print_json(&clustering),
+        Some(ClusterFormat::Csv) => {
+            println!("id,cluster,similarity");
+            for assignment in &clustering.assignments {
+                println!(
+                    "{},{},{:.4}",
+                    assignment.id, assignment.cluster, assignment.similarity
+                );
+            }
+        }
+        None if out.json() => print_json(&clustering),
+        None if clustering.clusters.is_empty() => out.status("No memories to cluster"),
+        None => {
+            for cluster in &clustering.clusters {
+                out.status(format_args!(
+                    "Cluster {} ({} memories, cohesion {:.2}): {}",
+                    cluster.cluster,
+                    cluster.size,
+                    cluster.cohesion,
+                    cluster.terms.join(", ")
+                ));
+                let mut members: Vec<_> = clustering
+                    .assignments
+                    .iter()
+                    .filter(|a| a.cluster == cluster.cluster)
+                    .collect();
+                members.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+                for member in members.into_iter().take(CLUSTER_EXAMPLES) {
+                    out.result(
+                        &member.id,
+                        format_args!("  {}: {}", member.id, member.content),
+                    );
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_stats(
+    store: &MemoryStore,
+    project_id: &str,
+    embeddings: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let stats = store.project_stats(project_id, embeddings)?;
+    if out.json() {
+        print_json(&stats);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    println!("Project: {}", stats.project_id);
+    println!("Memories: {}", stats.memories);
+    if !stats.kinds.is_empty() {
+        let kinds: Vec<String> = stats
+            .kinds
+            .iter()
+            .map(|k| format!("{} {}", k.kind, k.count))
+            .collect();
+        println!("Kinds: {}", kinds.join(", "));
+    }
+    if !stats.languages.is_empty() {
+        let languages: Vec<String> = stats
+            .languages
+            .iter()
+            .map(|l| format!("{} {}", l.language, l.count))
+            .collect();
+        println!("Languages: {}", languages.join(", "));
+    }
+    if let (Some(oldest), Some(newest)) = (&stats.oldest, &stats.newest) {
+        println!("Oldest: {}", out.time(oldest));
+        println!("Newest: {}", out.time(newest));
+    }
+    if let Some(tokens) = &stats.tokens {
+        println!(
+            "Tokens: {} total, longest {}, {} over the {}-token limit",
+            tokens.total, tokens.max, tokens.over_limit, tokens.limit
+        );
+    }
+    if let Some(compression) = &stats.compression {
+        println!(
+            "Compressed: {} rows, {} bytes saved ({} -> {} bytes)",
+            compression.compressed,
+            compression.saved_bytes,
+            compression.original_bytes,
+            compression.stored_bytes
+        );
+    }
+    let store_meta = &stats.store;
+    let mut store_line = match (&store_meta.embedding_model, store_meta.embedding_dims) {
+        (Some(model), Some(dims)) => format!("Store: model {} ({} dims)", model, dims),
+        (Some(model), None) => format!("Store: model {}", model),
+        _ => "Store: no model recorded".to_string(),
+    };
+    store_line.push_str(&format!(", schema {}", store_meta.schema_version));
+    if let Some(version) = &store_meta.crate_version {
+        store_line.push_str(&format!(", vipune {}", version));
+    }
+    if let Some(created_at) = &store_meta.created_at {
+        store_line.push_str(&format!(", created {}", out.time(created_at)));
+    }
+    println!("{}", store_line);
+    if let Some(report) = &stats.embeddings {
+        print_embedding_stats(report);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Width of the longest histogram bar.
+const HISTOGRAM_WIDTH: usize = 40;
+
+fn print_embedding_stats(report: &EmbeddingStats) {
+    println!();
+    println!("Embeddings: {}", report.count);
+    if report.count == 0 {
+        return;
+    }
+    println!("Centroid norm: {:.3}", report.centroid_norm);
+    if let Some(similarity) = report.mean_pairwise_similarity {
+        println!("Mean pairwise similarity: {:.3}", similarity);
+    }
+    println!(
+        "Similarity to centroid: mean {:.3}, stddev {:.3}",
+        report.mean_centroid_similarity, report.stddev_centroid_similarity
+    );
+
+    let largest = report.histogram.iter().map(|b| b.count).max().unwrap_or(0);
+    for bucket in &report.histogram {
+        let bar = (bucket.count * HISTOGRAM_WIDTH)
+            .checked_div(largest)
+            .unwrap_or(0);
+        println!(
+            "  {:.1}-{:.1} {:>6} {}",
+            bucket.min,
+            bucket.max,
+            bucket.count,
+            "#".repeat(bar)
+        );
+    }
+
+    let outliers: usize = report
+        .outlier_clusters
+        .iter()
+        .map(|c| c.members.len())
+        .sum();
+    println!(
+        "Outliers (similarity < {:.3}): {} in {} clusters",
+        report.outlier_threshold,
+        outliers,
+        report.outlier_clusters.len()
+    );
+    for (i, cluster) in report.outlier_clusters.iter().enumerate() {
+        println!("  Cluster {} ({} memories):", i + 1, cluster.members.len());
+        for outlier in &cluster.members {
+            println!(
+                "    {} ({:.3}) {}",
+                outlier.id, outlier.centroid_similarity, outlier.content
+            );
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Handlers for syncing with another store (`vipune sync ...`).
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::output::*;
+
+/// Sync actions.
+#[derive(clap::Subcommand)]
+pub enum SyncAction {
+    /// Send the memories changed since the last push to another database
+    /// (created if it does not exist)
+    Push {
+        /// Path of the other database (e.g. on a shared or mounted drive)
+        peer: String,
+    },
+    /// Apply the memories changed in another database since the last pull
+    Pull {
+        /// Path of the other database
+        peer: String,
+    },
+}
+
+pub(super) fn handle_sync(
+    store: &MemoryStore,
+    action: &SyncAction,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let (status, peer) = match action {
+        SyncAction::Push { peer } => ("pushed", peer),
+        SyncAction::Pull { peer } => ("pulled", peer),
+    };
+    if peer.contains("://") {
+        return Err(Error::InvalidInput(format!(
+            "Cannot sync with {}: only database paths are supported, not URLs",
+            peer
+        )));
+    }
+    let path = Path::new(peer);
+    let stats = match action {
+        SyncAction::Push { .. } => store.sync_push(path)?,
+        SyncAction::Pull { .. } => store.sync_pull(path)?,
+    };
+
+    if out.json() {
+        print_json(&SyncResponse {
+            status: status.to_string(),
+            peer: peer.clone(),
+            changes: stats.changes,
+            added: stats.added,
+            updated: stats.updated,
+            merged: stats.merged,
+            deleted: stats.deleted,
+            skipped: stats.skipped,
+        });
+    } else {
+        let direction = match action {
+            SyncAction::Push { .. } => "to",
+            SyncAction::Pull { .. } => "from",
+        };
+        out.status(format_args!(
+            "Synced {} changes {} {}: {} added, {} updated, {} merged, {} deleted, {} unchanged",
+            stats.changes,
+            direction,
+            peer,
+            stats.added,
+            stats.updated,
+            stats.merged,
+            stats.deleted,
+            stats.skipped
+        ));
+    }
+    Ok(ExitCode::SUCCESS)
+}
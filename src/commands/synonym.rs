@@ -0,0 +1,87 @@
+//! Handlers for search synonyms (`vipune synonym ...`).
+
+use std::process::ExitCode;
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::output::*;
+
+/// Synonym maintenance actions.
+#[derive(clap::Subcommand)]
+pub enum SynonymAction {
+    /// Let searches for TERM also match each SYNONYM, and the other way round
+    Add {
+        /// Single-word term, e.g. an abbreviation
+        term: String,
+        /// Words or phrases meaning the same
+        #[arg(required = true)]
+        synonyms: Vec<String>,
+    },
+    /// Remove a synonym of TERM, or all of them
+    Remove {
+        /// Term to remove synonyms of
+        term: String,
+        /// Synonym to remove (default: every synonym of the term)
+        synonym: Option<String>,
+    },
+    /// List the project's synonyms
+    List,
+}
+
+pub(super) fn handle_synonym(
+    store: &MemoryStore,
+    project_id: &str,
+    action: &SynonymAction,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    match action {
+        SynonymAction::Add { term, synonyms } => {
+            let synonyms: Vec<&str> = synonyms.iter().map(String::as_str).collect();
+            let added = store.add_synonyms(project_id, term, &synonyms)?;
+            print_change(out, ("added", "Added"), term, added);
+        }
+        SynonymAction::Remove { term, synonym } => {
+            let removed = store.remove_synonyms(project_id, term, synonym.as_deref())?;
+            print_change(out, ("removed", "Removed"), term, removed);
+        }
+        SynonymAction::List => {
+            let mut entries: Vec<SynonymEntry> = Vec::new();
+            for (term, synonym) in store.synonyms(project_id)? {
+                match entries.last_mut() {
+                    Some(entry) if entry.term == term => entry.synonyms.push(synonym),
+                    _ => entries.push(SynonymEntry {
+                        term,
+                        synonyms: vec![synonym],
+                    }),
+                }
+            }
+            if out.json() {
+                print_json(&SynonymListResponse {
+                    project_id: project_id.to_string(),
+                    synonyms: entries,
+                });
+            } else if entries.is_empty() {
+                out.status("No synonyms");
+            } else {
+                for entry in &entries {
+                    println!("{}: {}", entry.term, entry.synonyms.join(", "));
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Report `changed` pairs of `term`, with `(status, verb)` naming the change.
+fn print_change(out: &Printer, (status, verb): (&str, &str), term: &str, changed: usize) {
+    let term = term.trim().to_lowercase();
+    if out.json() {
+        print_json(&SynonymChangeResponse {
+            status: status.to_string(),
+            term,
+            changed,
+        });
+    } else {
+        out.status(format!("{} {} synonyms of {}", verb, changed, term));
+    }
+}
@@ -0,0 +1,297 @@
+//! Handlers for moving memories in and out of the store (import, export,
+//! export-context, watch).
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use crate::context_file::{render_section, write_section};
+use crate::conversations::{ChatRole, ConversationFormat};
+use crate::errors::Error;
+use crate::export::export_to_json;
+use crate::import::{ImportSource, ImportStats, import};
+use crate::import_options::{DuplicatePolicy, ImportOptions};
+use crate::memory::MemoryStore;
+use crate::metrics::MetricsExporter;
+use crate::notes::SplitMode;
+use crate::output::*;
+use crate::watch::Watcher;
+
+use super::{ContextFormat, ImportFormat};
+
+/// Arguments of `vipune import`.
+pub(super) struct ImportContext<'a> {
+    pub(super) path: &'a Path,
+    pub(super) format: ImportFormat,
+    pub(super) split: SplitMode,
+    pub(super) roles: &'a [ChatRole],
+    pub(super) map: &'a [String],
+    pub(super) resume: bool,
+    pub(super) exclusive: bool,
+    pub(super) bulk: bool,
+    pub(super) on_duplicate: DuplicatePolicy,
+    pub(super) threshold: Option<f64>,
+    pub(super) progress: bool,
+}
+
+pub(super) fn handle_import(
+    store: &mut MemoryStore,
+    project_id: &str,
+    project_override: Option<&str>,
+    opts: &ImportContext,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let mut options = ImportOptions::default();
+    for spec in opts.map {
+        let (old, new) = ImportOptions::parse_mapping(spec)?;
+        options = options.with_mapping(&old, &new);
+    }
+    if let Some(project) = project_override.filter(|p| !p.trim().is_empty()) {
+        options = options.with_project(project);
+    }
+
+    options = options
+        .with_resume(opts.resume)
+        .with_exclusive(opts.exclusive)
+        .with_bulk(opts.bulk)
+        .with_on_duplicate(opts.on_duplicate);
+    if let Some(threshold) = opts.threshold {
+        options = options.with_threshold(threshold);
+    }
+
+    let mut bar = ProgressBar::default();
+    let mut on_progress = |processed: usize, stats: &ImportStats| {
+        if opts.progress {
+            bar.draw(processed, stats.total);
+        }
+    };
+    let source = match opts.format {
+        ImportFormat::Json => ImportSource::Json(opts.path.to_path_buf()),
+        ImportFormat::Markdown => ImportSource::Markdown {
+            path: opts.path.to_path_buf(),
+            split: opts.split,
+        },
+        ImportFormat::ChatgptExport | ImportFormat::ClaudeExport => ImportSource::Conversations {
+            path: opts.path.to_path_buf(),
+            format: if opts.format == ImportFormat::ChatgptExport {
+                ConversationFormat::ChatGpt
+            } else {
+                ConversationFormat::Claude
+            },
+            roles: opts.roles.to_vec(),
+        },
+    };
+    let result = import(store, &source, project_id, &options, &mut on_progress);
+    if opts.progress {
+        bar.finish();
+    }
+    let stats = result?;
+    if out.json() {
+        print_json(&ImportResponse {
+            status: "imported".to_string(),
+            total: stats.total,
+            imported: stats.imported,
+            skipped_duplicates: stats.skipped_duplicates,
+            overwritten: stats.overwritten,
+            merged: stats.merged,
+            kept_duplicates: stats.kept_duplicates,
+            skipped_invalid: stats.skipped_invalid,
+            skipped_resumed: stats.skipped_resumed,
+            reused_embeddings: stats.reused_embeddings,
+        });
+    } else if !out.quiet() {
+        let mut extra = String::new();
+        if stats.overwritten > 0 {
+            extra.push_str(&format!(", {} overwritten", stats.overwritten));
+        }
+        if stats.merged > 0 {
+            extra.push_str(&format!(", {} merged", stats.merged));
+        }
+        if stats.kept_duplicates > 0 {
+            extra.push_str(&format!(", {} kept as duplicates", stats.kept_duplicates));
+        }
+        if stats.skipped_resumed > 0 {
+            extra.push_str(&format!(", {} already imported", stats.skipped_resumed));
+        }
+        if stats.reused_embeddings > 0 {
+            extra.push_str(&format!(", {} embeddings reused", stats.reused_embeddings));
+        }
+        println!(
+            "Imported {} of {} memories ({} duplicates skipped, {} invalid skipped{})",
+            stats.imported, stats.total, stats.skipped_duplicates, stats.skipped_invalid, extra
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_export(
+    store: &MemoryStore,
+    project_id: &str,
+    path: Option<&Path>,
+    all: bool,
+    include_embeddings: bool,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let projects = if all {
+        store.db.project_ids()?
+    } else {
+        vec![project_id.to_string()]
+    };
+
+    // Without a path the records themselves are the output
+    let Some(path) = path else {
+        let mut writer = BufWriter::new(io::stdout().lock());
+        export_to_json(store, &projects, include_embeddings, &mut writer)?;
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    let exported = export_to_json(store, &projects, include_embeddings, &mut writer)?;
+    if out.json() {
+        print_json(&ExportResponse {
+            status: "exported".to_string(),
+            exported,
+            projects: projects.len(),
+            path: path.display().to_string(),
+        });
+    } else {
+        out.status(format_args!(
+            "Exported {} memories to {}",
+            exported,
+            path.display()
+        ));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub(super) fn handle_export_context(
+    store: &MemoryStore,
+    project_id: &str,
+    path: Option<&Path>,
+    format: ContextFormat,
+    top: usize,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let memories = store.db.pinned_memories(project_id, top)?;
+    let section = match format {
+        ContextFormat::ClaudeMd => render_section(project_id, &memories),
+    };
+
+    // Without a path the section itself is the output
+    let Some(path) = path else {
+        print!("{}", section);
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let changed = write_section(path, project_id, &section)?;
+    if out.json() {
+        print_json(&ExportContextResponse {
+            status: if changed { "exported" } else { "unchanged" }.to_string(),
+            memories: memories.len(),
+            path: path.display().to_string(),
+        });
+    } else if changed {
+        out.status(format_args!(
+            "Wrote {} memories to {}",
+            memories.len(),
+            path.display()
+        ));
+    } else {
+        out.status(format_args!("{} is up to date", path.display()));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Single-line progress bar redrawn in place on stderr.
+#[derive(Default)]
+struct ProgressBar {
+    /// Last drawn percentage, to avoid redrawing on every record.
+    last_percent: Option<usize>,
+}
+
+impl ProgressBar {
+    const WIDTH: usize = 30;
+
+    fn draw(&mut self, processed: usize, total: usize) {
+        let percent = (processed * 100).checked_div(total).unwrap_or(100);
+        if self.last_percent == Some(percent) {
+            return;
+        }
+        self.last_percent = Some(percent);
+        let filled = percent * Self::WIDTH / 100;
+        eprint!(
+            "\rImporting [{}{}] {}/{} ({}%)",
+            "#".repeat(filled),
+            " ".repeat(Self::WIDTH - filled),
+            processed,
+            total,
+            percent
+        );
+    }
+
+    fn finish(&self) {
+        if self.last_percent.is_some() {
+            eprintln!();
+        }
+    }
+}
+
+pub(super) fn handle_watch(
+    store: &mut MemoryStore,
+    project_id: &str,
+    path: &Path,
+    interval_ms: u64,
+    once: bool,
+    metrics_file: Option<&Path>,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    let mut watcher = Watcher::new(path)?;
+    let exporter = metrics_file.map(MetricsExporter::new);
+    let mut total_ingested = 0;
+    let mut total_updated = 0;
+    let mut total_skipped = 0;
+
+    if !out.json() && !out.quiet() && !once {
+        println!("Watching {} (Ctrl-C to stop)", path.display());
+    }
+
+    loop {
+        let stats = watcher.poll(store, project_id)?;
+        total_ingested += stats.ingested;
+        total_updated += stats.updated;
+        total_skipped += stats.skipped;
+        if let Some(exporter) = &exporter {
+            exporter.export(store.metrics())?;
+        }
+
+        if stats.ingested + stats.updated + stats.skipped > 0 || once {
+            if out.json() {
+                print_json_line(&WatchProgressResponse {
+                    ingested: stats.ingested,
+                    updated: stats.updated,
+                    skipped: stats.skipped,
+                    total_ingested,
+                    total_updated,
+                    total_skipped,
+                });
+            } else {
+                out.status(format_args!(
+                    "Ingested {}, updated {}, skipped {} (total: {} ingested, {} updated, {} skipped)",
+                    stats.ingested,
+                    stats.updated,
+                    stats.skipped,
+                    total_ingested,
+                    total_updated,
+                    total_skipped
+                ));
+            }
+        }
+
+        if once {
+            return Ok(ExitCode::SUCCESS);
+        }
+        std::thread::sleep(Duration::from_millis(interval_ms.max(1)));
+    }
+}
@@ -0,0 +1,92 @@
+//! Handlers for the trash of deleted memories (`vipune trash ...`).
+
+use std::process::ExitCode;
+
+use super::display::kind_tag;
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::output::*;
+
+/// Trash actions.
+#[derive(clap::Subcommand)]
+pub enum TrashAction {
+    /// List the project's deleted memories, most recently deleted first
+    List,
+    /// Move a deleted memory back into the store
+    Restore {
+        /// Memory ID
+        id: String,
+    },
+    /// Permanently remove the project's deleted memories
+    Empty,
+}
+
+pub(super) fn handle_trash(
+    store: &MemoryStore,
+    project_id: &str,
+    action: &TrashAction,
+    out: &Printer,
+) -> Result<ExitCode, Error> {
+    match action {
+        TrashAction::List => {
+            let trashed = store.trashed(project_id)?;
+            if out.json() {
+                let memories = trashed
+                    .into_iter()
+                    .map(|t| TrashItem {
+                        id: t.memory.id,
+                        content: t.memory.content,
+                        kind: t.memory.kind,
+                        created_at: t.memory.created_at,
+                        deleted_at: t.deleted_at,
+                    })
+                    .collect();
+                print_json(&TrashListResponse { memories });
+            } else if trashed.is_empty() {
+                out.status("Trash is empty");
+            } else {
+                for t in trashed {
+                    out.result(
+                        &t.memory.id,
+                        format_args!(
+                            "{} [deleted {}]{}: {}",
+                            t.memory.id,
+                            out.time(&t.deleted_at),
+                            kind_tag(t.memory.kind),
+                            t.memory.content
+                        ),
+                    );
+                }
+            }
+        }
+        TrashAction::Restore { id } => {
+            if !store.restore(id)? {
+                return Err(Error::NotFound("memory not found in trash".to_string()));
+            }
+            if out.json() {
+                print_json(&RestoreResponse {
+                    status: "restored".to_string(),
+                    id: id.clone(),
+                    seq: store.write_seq()?,
+                });
+            } else {
+                out.status(format_args!("Restored memory: {}", id));
+            }
+        }
+        TrashAction::Empty => {
+            let removed = store.empty_trash(project_id)?;
+            if out.json() {
+                print_json(&TrashEmptyResponse {
+                    status: "emptied".to_string(),
+                    removed,
+                });
+            } else {
+                out.status(format_args!(
+                    "Permanently removed {} memories from the trash",
+                    removed
+                ));
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,103 @@
+//! Chunking configuration for long memories (`[chunking]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Rules for splitting long content into overlapping chunks on add.
+///
+/// The embedding model only sees the first 512 tokens of its input, so the
+/// tail of a long memory is otherwise unsearchable. With chunking enabled,
+/// content longer than `chunk_size` words is stored as a parent memory plus
+/// linked chunk memories; search matches against the chunks and returns the
+/// parent with the best-matching chunk as a snippet.
+///
+/// ```toml
+/// [chunking]
+/// enabled = true
+/// chunk_size = 300
+/// overlap = 50
+/// ```
+///
+/// Sizes are in words (roughly 1.3 tokens each for English text).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChunkingConfig {
+    /// Split long content into chunks on add and update.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Maximum words per chunk.
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+
+    /// Words shared between consecutive chunks.
+    #[serde(default = "default_overlap")]
+    pub overlap: usize,
+}
+
+fn default_chunk_size() -> usize {
+    300
+}
+
+fn default_overlap() -> usize {
+    50
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chunk_size: default_chunk_size(),
+            overlap: default_overlap(),
+        }
+    }
+}
+
+impl ChunkingConfig {
+    /// Check that chunk sizes are usable.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.chunk_size == 0 {
+            return Err(Error::Config(
+                "Invalid chunking.chunk_size: must be greater than 0".to_string(),
+            ));
+        }
+        if self.overlap >= self.chunk_size {
+            return Err(Error::Config(format!(
+                "Invalid chunking.overlap: {} (must be less than chunk_size {})",
+                self.overlap, self.chunk_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_when_section_is_partial() {
+        let config: ChunkingConfig = toml::from_str("enabled = true").unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.chunk_size, 300);
+        assert_eq!(config.overlap, 50);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_overlap_not_below_size() {
+        let config = ChunkingConfig {
+            enabled: true,
+            chunk_size: 10,
+            overlap: 10,
+        };
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+
+        let config = ChunkingConfig {
+            chunk_size: 0,
+            overlap: 0,
+            ..ChunkingConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! Content compression configuration (`[compression]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Transparent zstd compression of long memory content.
+///
+/// Imported documents and logs can be many kilobytes each. With compression
+/// enabled, content of at least `min_bytes` bytes is stored zstd-compressed
+/// (unless that would not make it smaller) and decompressed on read; the
+/// full-text index still holds the uncompressed text.
+///
+/// ```toml
+/// [compression]
+/// enabled = true
+/// min_bytes = 4096
+/// ```
+///
+/// Only content written while enabled is compressed; existing rows are
+/// compressed when they are next updated.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CompressionConfig {
+    /// Compress long content on add and update.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Smallest content size, in bytes of UTF-8, that is compressed.
+    #[serde(default = "default_min_bytes")]
+    pub min_bytes: usize,
+}
+
+fn default_min_bytes() -> usize {
+    4096
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_bytes: default_min_bytes(),
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Size from which content is compressed, or `None` when disabled.
+    pub fn threshold(&self) -> Option<usize> {
+        self.enabled.then_some(self.min_bytes)
+    }
+
+    /// Check that the threshold is usable.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.min_bytes == 0 {
+            return Err(Error::Config(
+                "Invalid compression.min_bytes: must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_only_when_enabled() {
+        let config: CompressionConfig = toml::from_str("min_bytes = 100").unwrap();
+        assert_eq!(config.threshold(), None);
+
+        let config: CompressionConfig = toml::from_str("enabled = true").unwrap();
+        assert_eq!(config.threshold(), Some(4096));
+        assert!(config.validate().is_ok());
+
+        let config: CompressionConfig = toml::from_str("enabled = true\nmin_bytes = 0").unwrap();
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,154 @@
+//! Conflict detection configuration (`[conflicts]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// How add and update decide that new content conflicts with a stored
+/// memory.
+///
+/// Each candidate gets a conflict confidence: the weighted mean of its
+/// embedding similarity and its term overlap (Jaccard index of the words of
+/// both texts). A candidate conflicts when its confidence reaches
+/// `similarity_threshold`. With the default weights the confidence is the
+/// similarity alone, so conflicts are the same as without this section.
+///
+/// Similarity alone flags related but distinct facts that use similar
+/// vocabulary, and misses small edits of a memory the model embeds a bit
+/// further away; weighting in the overlap corrects both. With an
+/// `overlap_weight`, candidates down to `min_similarity` are scored, so
+/// overlap can raise a candidate above the threshold.
+///
+/// ```toml
+/// similarity_threshold = 0.8
+///
+/// [conflicts]
+/// similarity_weight = 0.7
+/// overlap_weight = 0.3
+/// min_similarity = 0.6
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ConflictsConfig {
+    /// Weight of embedding similarity in the confidence.
+    #[serde(default = "default_similarity_weight")]
+    pub similarity_weight: f64,
+
+    /// Weight of term overlap in the confidence.
+    #[serde(default)]
+    pub overlap_weight: f64,
+
+    /// Lowest similarity of a candidate scored when `overlap_weight` is set.
+    #[serde(default = "default_min_similarity")]
+    pub min_similarity: f64,
+}
+
+fn default_similarity_weight() -> f64 {
+    1.0
+}
+
+fn default_min_similarity() -> f64 {
+    0.6
+}
+
+impl Default for ConflictsConfig {
+    fn default() -> Self {
+        Self {
+            similarity_weight: default_similarity_weight(),
+            overlap_weight: 0.0,
+            min_similarity: default_min_similarity(),
+        }
+    }
+}
+
+impl ConflictsConfig {
+    /// Conflict confidence (0.0 to 1.0) of a candidate with `similarity` and
+    /// term `overlap`.
+    pub fn confidence(&self, similarity: f64, overlap: f64) -> f64 {
+        let total = self.similarity_weight + self.overlap_weight;
+        ((self.similarity_weight * similarity + self.overlap_weight * overlap) / total)
+            .clamp(0.0, 1.0)
+    }
+
+    /// Lowest similarity of a candidate worth scoring against `threshold`.
+    pub fn candidate_floor(&self, threshold: f64) -> f64 {
+        if self.overlap_weight > 0.0 {
+            self.min_similarity.min(threshold)
+        } else {
+            threshold
+        }
+    }
+
+    /// Check that the weights are usable and `min_similarity` is in range.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        for (name, weight) in [
+            ("similarity_weight", self.similarity_weight),
+            ("overlap_weight", self.overlap_weight),
+        ] {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(Error::Config(format!(
+                    "Invalid conflicts.{}: {} (must be a finite number >= 0)",
+                    name, weight
+                )));
+            }
+        }
+        if self.similarity_weight + self.overlap_weight <= 0.0 {
+            return Err(Error::Config(
+                "Invalid conflicts weights: at least one must be greater than 0".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.min_similarity) {
+            return Err(Error::Config(format!(
+                "Invalid conflicts.min_similarity: {} (must be between 0.0 and 1.0)",
+                self.min_similarity
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_confidence_is_similarity() {
+        let config = ConflictsConfig::default();
+        assert_eq!(config.confidence(0.9, 0.1), 0.9);
+        assert_eq!(config.candidate_floor(0.85), 0.85);
+    }
+
+    #[test]
+    fn test_weighted_confidence() {
+        let config: ConflictsConfig =
+            toml::from_str("similarity_weight = 3.0\noverlap_weight = 1.0").unwrap();
+        assert!((config.confidence(0.8, 0.4) - 0.7).abs() < 1e-9);
+        assert_eq!(config.confidence(1.5, 1.0), 1.0);
+        assert_eq!(config.candidate_floor(0.85), 0.6);
+        assert_eq!(config.candidate_floor(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_validate_rejects_unusable_weights() {
+        assert!(ConflictsConfig::default().validate().is_ok());
+        for config in [
+            ConflictsConfig {
+                overlap_weight: -1.0,
+                ..ConflictsConfig::default()
+            },
+            ConflictsConfig {
+                similarity_weight: 0.0,
+                ..ConflictsConfig::default()
+            },
+            ConflictsConfig {
+                similarity_weight: f64::NAN,
+                ..ConflictsConfig::default()
+            },
+            ConflictsConfig {
+                min_similarity: 1.5,
+                ..ConflictsConfig::default()
+            },
+        ] {
+            assert!(matches!(config.validate(), Err(Error::Config(_))));
+        }
+    }
+}
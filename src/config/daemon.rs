@@ -0,0 +1,94 @@
+//! Backpressure of `vipune daemon` (`[daemon]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Bounds on the work a daemon accepts at once, so a runaway client cannot
+/// queue unbounded work behind its single model and database connection.
+///
+/// Requests over `max_in_flight`, and connections over `max_connections`,
+/// are answered with a `busy` response instead of waiting:
+///
+/// ```toml
+/// [daemon]
+/// max_in_flight = 16
+/// max_connections = 32
+/// retry_after_ms = 100
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct DaemonConfig {
+    /// Most requests accepted at once: the one being answered plus those
+    /// queued behind it.
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: usize,
+
+    /// Most client connections kept open at once.
+    #[serde(default = "default_max_connections")]
+    pub max_connections: usize,
+
+    /// Delay suggested to rejected clients before they retry, in
+    /// milliseconds.
+    #[serde(default = "default_retry_after_ms")]
+    pub retry_after_ms: u64,
+}
+
+fn default_max_in_flight() -> usize {
+    16
+}
+
+fn default_max_connections() -> usize {
+    32
+}
+
+fn default_retry_after_ms() -> u64 {
+    100
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight: default_max_in_flight(),
+            max_connections: default_max_connections(),
+            retry_after_ms: default_retry_after_ms(),
+        }
+    }
+}
+
+impl DaemonConfig {
+    /// Check that both bounds are greater than zero.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        for (name, value) in [
+            ("max_in_flight", self.max_in_flight),
+            ("max_connections", self.max_connections),
+        ] {
+            if value == 0 {
+                return Err(Error::Config(format!(
+                    "Invalid daemon.{}: must be greater than 0",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_when_section_is_partial() {
+        let config: DaemonConfig = toml::from_str("max_in_flight = 4").unwrap();
+        assert_eq!(config.max_in_flight, 4);
+        assert_eq!(config.max_connections, 32);
+        assert_eq!(config.retry_after_ms, 100);
+        assert!(config.validate().is_ok());
+
+        let config = DaemonConfig {
+            max_connections: 0,
+            ..DaemonConfig::default()
+        };
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,124 @@
+//! Model download retry configuration (`[download]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Most retries a config may set.
+pub const MAX_DOWNLOAD_RETRIES: u32 = 10;
+
+/// Retries of model downloads that fail on a transient network error.
+///
+/// Connection failures, DNS errors, timeouts and HTTP 408, 429 and 5xx
+/// responses are retried with exponential backoff: the first retry waits
+/// `initial_backoff_ms`, each following one twice as long, up to
+/// `max_backoff_ms`. Other failures (e.g. HTTP 401 or 404) are not retried.
+///
+/// ```toml
+/// [download]
+/// retries = 5
+/// initial_backoff_ms = 1000
+/// max_backoff_ms = 30000
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct DownloadConfig {
+    /// Retries after the first attempt (0 disables retrying).
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+
+    /// Delay before the first retry, in milliseconds.
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+
+    /// Longest delay between two attempts, in milliseconds.
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_max_backoff_ms() -> u64 {
+    8_000
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            retries: default_retries(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+        }
+    }
+}
+
+impl DownloadConfig {
+    /// Delay before retry number `retry` (counting from 0).
+    pub fn backoff(&self, retry: u32) -> std::time::Duration {
+        let factor = 1u64.checked_shl(retry).unwrap_or(u64::MAX);
+        let ms = self
+            .initial_backoff_ms
+            .saturating_mul(factor)
+            .min(self.max_backoff_ms);
+        std::time::Duration::from_millis(ms)
+    }
+
+    /// Check that the retry count and backoff bounds are usable.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.retries > MAX_DOWNLOAD_RETRIES {
+            return Err(Error::Config(format!(
+                "Invalid download.retries: {} (max {MAX_DOWNLOAD_RETRIES})",
+                self.retries
+            )));
+        }
+        if self.max_backoff_ms < self.initial_backoff_ms {
+            return Err(Error::Config(format!(
+                "Invalid download.max_backoff_ms: {} (must be at least initial_backoff_ms {})",
+                self.max_backoff_ms, self.initial_backoff_ms
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let config = DownloadConfig::default();
+        let delays: Vec<_> = (0..6).map(|retry| config.backoff(retry)).collect();
+        assert_eq!(
+            delays,
+            [500, 1000, 2000, 4000, 8000, 8000].map(Duration::from_millis)
+        );
+        assert_eq!(config.backoff(200), Duration::from_millis(8000));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_bounds() {
+        let config: DownloadConfig = toml::from_str("retries = 0").unwrap();
+        assert_eq!(config.initial_backoff_ms, 500);
+        assert!(config.validate().is_ok());
+
+        let config = DownloadConfig {
+            retries: MAX_DOWNLOAD_RETRIES + 1,
+            ..DownloadConfig::default()
+        };
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+
+        let config = DownloadConfig {
+            initial_backoff_ms: 2000,
+            max_backoff_ms: 1000,
+            ..DownloadConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -32,6 +32,29 @@ fn parse_env_float(name: &str, value: &str) -> Result<f64, Error> {
         .map_err(|e| Error::Config(format!("Invalid {name} value: {e}")))
 }
 
+/// Parse environment variable as a positive integer.
+fn parse_env_positive(name: &str, value: &str) -> Result<u64, Error> {
+    if value.trim().is_empty() {
+        return Err(Error::Config(format!("{name} cannot be empty")));
+    }
+    value
+        .trim()
+        .parse()
+        .map_err(|e| Error::Config(format!("Invalid {name} value: {e}")))
+}
+
+/// Parse environment variable as a boolean (`true`/`false`, `1`/`0`).
+fn parse_env_bool(name: &str, value: &str) -> Result<bool, Error> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        "" => Err(Error::Config(format!("{name} cannot be empty"))),
+        other => Err(Error::Config(format!(
+            "Invalid {name} value: {other} (expected true or false)"
+        ))),
+    }
+}
+
 /// Apply VIPUNE_DATABASE_PATH environment variable override.
 pub fn apply_database_path_override(database_path: &mut PathBuf) -> Result<(), Error> {
     if let Ok(val) = std::env::var("VIPUNE_DATABASE_PATH") {
@@ -72,6 +95,38 @@ pub fn apply_recency_weight_override(recency_weight: &mut f64) -> Result<(), Err
     Ok(())
 }
 
+/// Apply VIPUNE_MAX_MEMORIES_PER_PROJECT environment variable override.
+pub fn apply_max_memories_override(max_memories: &mut Option<usize>) -> Result<(), Error> {
+    if let Ok(val) = std::env::var("VIPUNE_MAX_MEMORIES_PER_PROJECT") {
+        *max_memories = Some(parse_env_positive("VIPUNE_MAX_MEMORIES_PER_PROJECT", &val)? as usize);
+    }
+    Ok(())
+}
+
+/// Apply VIPUNE_MAX_TOTAL_DB_SIZE_MB environment variable override.
+pub fn apply_max_db_size_override(max_size_mb: &mut Option<u64>) -> Result<(), Error> {
+    if let Ok(val) = std::env::var("VIPUNE_MAX_TOTAL_DB_SIZE_MB") {
+        *max_size_mb = Some(parse_env_positive("VIPUNE_MAX_TOTAL_DB_SIZE_MB", &val)?);
+    }
+    Ok(())
+}
+
+/// Apply VIPUNE_EVICT_ON_QUOTA environment variable override.
+pub fn apply_evict_on_quota_override(evict_on_quota: &mut bool) -> Result<(), Error> {
+    if let Ok(val) = std::env::var("VIPUNE_EVICT_ON_QUOTA") {
+        *evict_on_quota = parse_env_bool("VIPUNE_EVICT_ON_QUOTA", &val)?;
+    }
+    Ok(())
+}
+
+/// Apply VIPUNE_CHUNKING environment variable override.
+pub fn apply_chunking_override(enabled: &mut bool) -> Result<(), Error> {
+    if let Ok(val) = std::env::var("VIPUNE_CHUNKING") {
+        *enabled = parse_env_bool("VIPUNE_CHUNKING", &val)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,4 +160,28 @@ mod tests {
         let result = parse_env_float("TEST_FLOAT", "0.5");
         assert_eq!(result.unwrap(), 0.5);
     }
+
+    #[test]
+    fn test_parse_env_positive() {
+        assert_eq!(parse_env_positive("TEST_INT", " 42 ").unwrap(), 42);
+        assert!(matches!(
+            parse_env_positive("TEST_INT", "-1"),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(
+            parse_env_positive("TEST_INT", ""),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_env_bool() {
+        assert!(parse_env_bool("TEST_BOOL", "true").unwrap());
+        assert!(parse_env_bool("TEST_BOOL", "1").unwrap());
+        assert!(!parse_env_bool("TEST_BOOL", "FALSE").unwrap());
+        assert!(matches!(
+            parse_env_bool("TEST_BOOL", "maybe"),
+            Err(Error::Config(_))
+        ));
+    }
 }
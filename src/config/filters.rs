@@ -0,0 +1,84 @@
+//! Content filter configuration (`[filters]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// A built-in content filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterKind {
+    /// Trim, strip trailing whitespace and collapse blank lines and spaces.
+    NormalizeWhitespace,
+    /// Replace email addresses with `[email]`.
+    RedactEmails,
+    /// Replace phone numbers with `[phone]`.
+    RedactPhones,
+    /// Truncate content to `max_length` bytes.
+    MaxLength,
+}
+
+/// Filters applied, in order, to content before it is added or updated.
+///
+/// Filtered content is what gets validated, scanned for secrets, embedded and
+/// stored; the original is not kept. The pipeline is empty by default.
+///
+/// ```toml
+/// [filters]
+/// pipeline = ["normalize_whitespace", "redact_emails", "redact_phones", "max_length"]
+/// max_length = 4000
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+pub struct FiltersConfig {
+    /// Built-in filters to run, in order.
+    #[serde(default)]
+    pub pipeline: Vec<FilterKind>,
+
+    /// Bytes kept by the `max_length` filter.
+    #[serde(default = "default_max_length")]
+    pub max_length: usize,
+}
+
+fn default_max_length() -> usize {
+    10_000
+}
+
+impl FiltersConfig {
+    /// Check that the `max_length` filter keeps something.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.max_length == 0 && self.pipeline.contains(&FilterKind::MaxLength) {
+            return Err(Error::Config(
+                "Invalid filters.max_length: must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pipeline_in_order() {
+        let config: FiltersConfig =
+            toml::from_str(r#"pipeline = ["max_length", "normalize_whitespace"]"#).unwrap();
+        assert_eq!(
+            config.pipeline,
+            [FilterKind::MaxLength, FilterKind::NormalizeWhitespace]
+        );
+        assert_eq!(config.max_length, 10_000);
+        assert!(config.validate().is_ok());
+
+        assert!(toml::from_str::<FiltersConfig>(r#"pipeline = ["uppercase"]"#).is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_max_length() {
+        let config = FiltersConfig {
+            pipeline: vec![FilterKind::MaxLength],
+            max_length: 0,
+        };
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,134 @@
+//! Mutation hook configuration (`[hooks]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Commands run after memories are added, updated or deleted.
+///
+/// Each command runs through the shell (`sh -c`, or `cmd /C` on Windows)
+/// with a JSON payload on stdin, e.g. to mirror changes into a chat channel
+/// or log pipeline:
+///
+/// ```toml
+/// [hooks]
+/// on_add = "curl -s -X POST -H 'Content-Type: application/json' -d @- https://hooks.example.com/vipune"
+/// on_delete = "logger -t vipune"
+/// timeout_ms = 5000
+/// on_failure = "warn"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HooksConfig {
+    /// Command run after a memory is added.
+    #[serde(default)]
+    pub on_add: Option<String>,
+
+    /// Command run after a memory's content or metadata is updated.
+    #[serde(default)]
+    pub on_update: Option<String>,
+
+    /// Command run after a memory is deleted.
+    #[serde(default)]
+    pub on_delete: Option<String>,
+
+    /// Milliseconds a hook may run before it is killed.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// What a failing hook (non-zero exit, timeout, spawn error) does.
+    #[serde(default)]
+    pub on_failure: HookFailurePolicy,
+}
+
+/// Handling of a hook that fails.
+///
+/// The mutation itself is never rolled back: hooks run after it is stored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookFailurePolicy {
+    /// Log a warning and carry on.
+    #[default]
+    Warn,
+    /// Return `Error::Hook` from the operation that triggered the hook.
+    Fail,
+    /// Carry on silently (failures are only logged at debug level).
+    Ignore,
+}
+
+fn default_timeout_ms() -> u64 {
+    5000
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_add: None,
+            on_update: None,
+            on_delete: None,
+            timeout_ms: default_timeout_ms(),
+            on_failure: HookFailurePolicy::default(),
+        }
+    }
+}
+
+impl HooksConfig {
+    /// Check that the timeout is usable and no command is blank.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.timeout_ms == 0 {
+            return Err(Error::Config(
+                "Invalid hooks.timeout_ms: must be greater than 0".to_string(),
+            ));
+        }
+        for (name, command) in [
+            ("on_add", &self.on_add),
+            ("on_update", &self.on_update),
+            ("on_delete", &self.on_delete),
+        ] {
+            if command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+                return Err(Error::Config(format!(
+                    "Invalid hooks.{}: command must not be empty",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hooks_section() {
+        let config: HooksConfig = toml::from_str(
+            r#"
+            on_add = "logger -t vipune"
+            on_failure = "fail"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.on_add.as_deref(), Some("logger -t vipune"));
+        assert_eq!(config.on_update, None);
+        assert_eq!(config.timeout_ms, 5000);
+        assert_eq!(config.on_failure, HookFailurePolicy::Fail);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_timeout_and_blank_command() {
+        let config = HooksConfig {
+            timeout_ms: 0,
+            ..HooksConfig::default()
+        };
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+
+        let config = HooksConfig {
+            on_delete: Some("  ".to_string()),
+            ..HooksConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        assert!(toml::from_str::<HooksConfig>(r#"on_failure = "retry""#).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Late-interaction scoring configuration (`[late_interaction]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Most token embeddings a config may have stored per memory.
+pub const MAX_LATE_INTERACTION_TOKENS: usize = 128;
+
+/// Most candidates a config may have rescored per search.
+pub const MAX_LATE_INTERACTION_CANDIDATES: usize = 200;
+
+/// Token embeddings stored next to each memory's sentence embedding, and
+/// the MaxSim rescoring of searches that use them (`search
+/// --late-interaction`,
+/// [`SearchOptions::late_interaction`](crate::SearchOptions::late_interaction)).
+///
+/// Needs vipune built with the `late-interaction` feature. With `enabled`,
+/// adds, updates, imports and re-embeds store an evenly spaced sample of
+/// `tokens` token embeddings per memory, int8-quantized (about 388 bytes
+/// each, so the default sample is roughly 12 KB per memory).
+///
+/// ```toml
+/// [late_interaction]
+/// enabled = true
+/// tokens = 32
+/// candidates = 50
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LateInteractionConfig {
+    /// Store token embeddings on add, update, import and re-embed.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Token embeddings kept per memory.
+    #[serde(default = "default_tokens")]
+    pub tokens: usize,
+
+    /// Top first-stage candidates rescored (at least the search limit).
+    #[serde(default = "default_candidates")]
+    pub candidates: usize,
+}
+
+fn default_tokens() -> usize {
+    32
+}
+
+fn default_candidates() -> usize {
+    50
+}
+
+impl Default for LateInteractionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tokens: default_tokens(),
+            candidates: default_candidates(),
+        }
+    }
+}
+
+impl LateInteractionConfig {
+    /// Check that `tokens` and `candidates` are in range and that the
+    /// feature is compiled in when storage is enabled.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if !(1..=MAX_LATE_INTERACTION_TOKENS).contains(&self.tokens) {
+            return Err(Error::Config(format!(
+                "Invalid late_interaction.tokens: {} (must be between 1 and {})",
+                self.tokens, MAX_LATE_INTERACTION_TOKENS
+            )));
+        }
+        if !(1..=MAX_LATE_INTERACTION_CANDIDATES).contains(&self.candidates) {
+            return Err(Error::Config(format!(
+                "Invalid late_interaction.candidates: {} (must be between 1 and {})",
+                self.candidates, MAX_LATE_INTERACTION_CANDIDATES
+            )));
+        }
+        if self.enabled && !cfg!(feature = "late-interaction") {
+            return Err(Error::Config(
+                "late_interaction.enabled needs vipune built with the `late-interaction` feature"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_late_interaction_config_defaults_and_validation() {
+        let config: LateInteractionConfig = toml::from_str("").unwrap();
+        assert_eq!(config, LateInteractionConfig::default());
+        assert!(config.validate().is_ok());
+
+        for bad in [
+            "tokens = 0",
+            "tokens = 129",
+            "candidates = 0",
+            "candidates = 201",
+        ] {
+            let config: LateInteractionConfig = toml::from_str(bad).unwrap();
+            assert!(matches!(config.validate(), Err(Error::Config(_))), "{bad}");
+        }
+
+        let config: LateInteractionConfig = toml::from_str("enabled = true").unwrap();
+        assert_eq!(
+            config.validate().is_ok(),
+            cfg!(feature = "late-interaction")
+        );
+    }
+}
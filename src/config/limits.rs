@@ -0,0 +1,136 @@
+//! Input validation limits (`[limits]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::memory::store::{
+    BATCH_QUERIES_CAP, INPUT_LENGTH_CAP, MAX_BATCH_QUERIES, MAX_INPUT_LENGTH, MAX_SEARCH_LIMIT,
+    SEARCH_LIMIT_CAP,
+};
+
+/// Bounds on memory content, queries and result counts.
+///
+/// Embedded consumers storing short notes can tighten them; power users can
+/// raise them up to fixed caps ([`INPUT_LENGTH_CAP`], [`SEARCH_LIMIT_CAP`],
+/// [`BATCH_QUERIES_CAP`]):
+///
+/// ```toml
+/// [limits]
+/// max_input_length = 4000
+/// max_search_limit = 100
+/// max_batch_queries = 8
+/// timeout_ms = 30000
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct LimitsConfig {
+    /// Longest accepted memory content or query, in bytes.
+    #[serde(default = "default_max_input_length")]
+    pub max_input_length: usize,
+
+    /// Largest accepted result limit for searches and listings.
+    #[serde(default = "default_max_search_limit")]
+    pub max_search_limit: usize,
+
+    /// Most queries accepted in one batch search.
+    #[serde(default = "default_max_batch_queries")]
+    pub max_batch_queries: usize,
+
+    /// Longest a search, or a model run outside one, may take before it
+    /// fails with `Error::Timeout`, in milliseconds (`None`: no limit).
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+fn default_max_input_length() -> usize {
+    MAX_INPUT_LENGTH
+}
+
+fn default_max_search_limit() -> usize {
+    MAX_SEARCH_LIMIT
+}
+
+fn default_max_batch_queries() -> usize {
+    MAX_BATCH_QUERIES
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_input_length: default_max_input_length(),
+            max_search_limit: default_max_search_limit(),
+            max_batch_queries: default_max_batch_queries(),
+            timeout_ms: None,
+        }
+    }
+}
+
+impl LimitsConfig {
+    /// Check that every limit is between 1 and its cap, and that a timeout
+    /// is not zero.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.timeout_ms == Some(0) {
+            return Err(Error::Config(
+                "Invalid limits.timeout_ms: must be greater than 0".to_string(),
+            ));
+        }
+        for (name, value, cap) in [
+            ("max_input_length", self.max_input_length, INPUT_LENGTH_CAP),
+            ("max_search_limit", self.max_search_limit, SEARCH_LIMIT_CAP),
+            (
+                "max_batch_queries",
+                self.max_batch_queries,
+                BATCH_QUERIES_CAP,
+            ),
+        ] {
+            if value == 0 || value > cap {
+                return Err(Error::Config(format!(
+                    "Invalid limits.{}: {} (must be between 1 and {})",
+                    name, value, cap
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_when_section_is_partial() {
+        let config: LimitsConfig = toml::from_str("max_search_limit = 100").unwrap();
+        assert_eq!(config.max_search_limit, 100);
+        assert_eq!(config.max_input_length, MAX_INPUT_LENGTH);
+        assert_eq!(config.max_batch_queries, MAX_BATCH_QUERIES);
+        assert_eq!(config.timeout_ms, None);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_and_values_over_cap() {
+        let config = LimitsConfig {
+            max_input_length: 0,
+            ..LimitsConfig::default()
+        };
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+
+        let config = LimitsConfig {
+            max_search_limit: SEARCH_LIMIT_CAP + 1,
+            ..LimitsConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = LimitsConfig {
+            max_batch_queries: BATCH_QUERIES_CAP,
+            ..LimitsConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let config = LimitsConfig {
+            timeout_ms: Some(0),
+            ..LimitsConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
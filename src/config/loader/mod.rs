@@ -0,0 +1,324 @@
+//! Configuration file loading and parsing.
+
+use super::chunking::ChunkingConfig;
+use super::compression::CompressionConfig;
+use super::conflicts::ConflictsConfig;
+use super::daemon::DaemonConfig;
+use super::download::DownloadConfig;
+use super::filters::FiltersConfig;
+use super::hooks::HooksConfig;
+use super::late_interaction::LateInteractionConfig;
+use super::limits::LimitsConfig;
+use super::metadata_schema::MetadataSchemaConfig;
+use super::rerank::RerankConfig;
+use super::retention::RetentionConfig;
+use super::review::ReviewConfig;
+use crate::errors::Error;
+use crate::normalize::TextNormalization;
+use crate::project::MonorepoMode;
+use crate::sqlite::{EmbeddingFormat, FtsTokenizer, Visibility};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Configuration loaded from TOML file.
+#[derive(Debug, Deserialize)]
+pub struct ConfigFile {
+    /// Path to the SQLite database file.
+    #[serde(default)]
+    pub database_path: PathBuf,
+
+    /// HuggingFace embedding model identifier.
+    #[serde(default)]
+    pub embedding_model: String,
+
+    /// Directory for caching ONNX models.
+    #[serde(default)]
+    pub model_cache: PathBuf,
+
+    /// Minimum similarity threshold for search results.
+    #[serde(default = "default_threshold")]
+    pub similarity_threshold: f64,
+
+    /// Recency weight for search ranking.
+    #[serde(default = "default_recency_weight")]
+    pub recency_weight: f64,
+
+    /// Maximum number of memories per project.
+    #[serde(default)]
+    pub max_memories_per_project: Option<usize>,
+
+    /// Maximum database size in megabytes.
+    #[serde(default)]
+    pub max_total_db_size_mb: Option<u64>,
+
+    /// Evict oldest memories instead of rejecting adds over quota.
+    #[serde(default)]
+    pub evict_on_quota: bool,
+
+    /// Load model files from the local cache only.
+    #[serde(default)]
+    pub offline: bool,
+
+    /// Model download retries (`[download]` section).
+    #[serde(default)]
+    pub download: DownloadConfig,
+
+    /// Days deleted memories stay restorable in the trash.
+    #[serde(default)]
+    pub trash_retention_days: Option<u32>,
+
+    /// Days without an update or access before pruning marks a memory
+    /// inactive.
+    #[serde(default)]
+    pub inactive_after_days: Option<u32>,
+
+    /// Retention rules (`[retention]` section).
+    #[serde(default)]
+    pub retention: RetentionConfig,
+
+    /// Review queue (`[review]` section).
+    #[serde(default)]
+    pub review: ReviewConfig,
+
+    /// Conflict confidence weights (`[conflicts]` section).
+    #[serde(default)]
+    pub conflicts: ConflictsConfig,
+
+    /// Reranking cross-encoder (`[rerank]` section).
+    #[serde(default)]
+    pub rerank: RerankConfig,
+
+    /// Late-interaction token embeddings (`[late_interaction]` section).
+    #[serde(default)]
+    pub late_interaction: LateInteractionConfig,
+
+    /// Chunking rules (`[chunking]` section).
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+
+    /// Content compression (`[compression]` section).
+    #[serde(default)]
+    pub compression: CompressionConfig,
+
+    /// Mutation hooks (`[hooks]` section).
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Input validation limits (`[limits]` section).
+    #[serde(default)]
+    pub limits: LimitsConfig,
+
+    /// Daemon backpressure (`[daemon]` section).
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+
+    /// Metadata schema (`[metadata_schema]` section).
+    #[serde(default)]
+    pub metadata_schema: MetadataSchemaConfig,
+
+    /// Maintain the FTS5 index for keyword and hybrid search.
+    #[serde(default = "default_enable_fts")]
+    pub enable_fts: bool,
+
+    /// Rebuild a drifted FTS5 index on open.
+    #[serde(default = "default_fts_auto_repair")]
+    pub fts_auto_repair: bool,
+
+    /// Run the FTS5 integrity check on open.
+    #[serde(default)]
+    pub fts_integrity_check: bool,
+
+    /// Full-text index tokenizer of new databases.
+    #[serde(default)]
+    pub fts_tokenizer: FtsTokenizer,
+
+    /// Text normalization (`[normalization]` section).
+    #[serde(default)]
+    pub normalization: TextNormalization,
+
+    /// Detect the language of added and updated memories.
+    #[serde(default)]
+    pub detect_language: bool,
+
+    /// Reject content that looks like a credential.
+    #[serde(default)]
+    pub secret_scan: bool,
+
+    /// Owner recorded on added memories.
+    #[serde(default)]
+    pub owner: Option<String>,
+
+    /// Visibility of added memories.
+    #[serde(default)]
+    pub visibility: Visibility,
+
+    /// Storage format of new embeddings.
+    #[serde(default)]
+    pub embedding_format: EmbeddingFormat,
+
+    /// Derive memory IDs from project and content.
+    #[serde(default)]
+    pub stable_ids: bool,
+
+    /// Project scope within a monorepo.
+    #[serde(default)]
+    pub monorepo_mode: MonorepoMode,
+
+    /// Content filter pipeline (`[filters]` section).
+    #[serde(default)]
+    pub filters: FiltersConfig,
+
+    /// Path of the sqlite-vec loadable extension.
+    #[serde(default)]
+    pub sqlite_vec_path: Option<PathBuf>,
+}
+
+#[allow(dead_code)]
+fn default_threshold() -> f64 {
+    0.85
+}
+
+#[allow(dead_code)]
+fn default_recency_weight() -> f64 {
+    0.3
+}
+
+#[allow(dead_code)]
+fn default_enable_fts() -> bool {
+    true
+}
+
+#[allow(dead_code)]
+fn default_fts_auto_repair() -> bool {
+    true
+}
+
+/// Table of named profiles in the config file (`[profiles.<name>]`).
+const PROFILES_KEY: &str = "profiles";
+
+/// Path of the config file (`vipune/config.toml` in the user's config
+/// directory).
+pub fn config_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let config_dir = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+    config_dir.join("vipune/config.toml")
+}
+
+/// Load configuration from TOML file, with the settings of `profile` (a
+/// `[profiles.<name>]` table) laid over the top-level ones.
+///
+/// # Errors
+///
+/// Returns `Error::Config` if the file cannot be read or parsed, or the
+/// profile is not defined in it.
+pub fn load_from_file(profile: Option<&str>) -> Result<Option<ConfigFile>, Error> {
+    let config_path = config_path();
+    let Some(table) = read_table(&config_path)? else {
+        return match profile {
+            Some(profile) => Err(unknown_profile(profile, &[])),
+            None => Ok(None),
+        };
+    };
+    parse_config(table, profile).map(Some)
+}
+
+/// Names of the profiles defined in the config file, sorted.
+///
+/// # Errors
+///
+/// Returns `Error::Config` if the file cannot be read or parsed.
+pub fn profile_names() -> Result<Vec<String>, Error> {
+    let Some(table) = read_table(&config_path())? else {
+        return Ok(Vec::new());
+    };
+    Ok(profiles_of(&table).into_iter().collect())
+}
+
+fn read_table(config_path: &std::path::Path) -> Result<Option<toml::Table>, Error> {
+    if !config_path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(config_path).map_err(|e| {
+        Error::Config(format!(
+            "Failed to read config file {}: {e}",
+            config_path.display()
+        ))
+    })?;
+    let table = content.parse::<toml::Table>().map_err(|e| {
+        Error::Config(format!(
+            "Failed to parse config file {}: {e}",
+            config_path.display()
+        ))
+    })?;
+    Ok(Some(table))
+}
+
+/// The config file's settings for `profile`: the top-level table with the
+/// profile's table merged over it (nested tables key by key).
+fn parse_config(mut table: toml::Table, profile: Option<&str>) -> Result<ConfigFile, Error> {
+    let names = profiles_of(&table);
+    let profiles = table.remove(PROFILES_KEY);
+    if let Some(profile) = profile {
+        let overlay = match profiles {
+            Some(toml::Value::Table(mut profiles)) => profiles.remove(profile),
+            _ => None,
+        };
+        match overlay {
+            Some(toml::Value::Table(overlay)) => merge_tables(&mut table, overlay),
+            _ => {
+                let names: Vec<String> = names.into_iter().collect();
+                return Err(unknown_profile(profile, &names));
+            }
+        }
+    }
+    toml::Value::Table(table)
+        .try_into()
+        .map_err(|e: toml::de::Error| {
+            Error::Config(format!(
+                "Failed to parse config file {}: {e}",
+                config_path().display()
+            ))
+        })
+}
+
+fn profiles_of(table: &toml::Table) -> std::collections::BTreeSet<String> {
+    match table.get(PROFILES_KEY) {
+        Some(toml::Value::Table(profiles)) => profiles
+            .iter()
+            .filter(|(_, value)| value.is_table())
+            .map(|(name, _)| name.clone())
+            .collect(),
+        _ => Default::default(),
+    }
+}
+
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn unknown_profile(profile: &str, names: &[String]) -> Error {
+    let defined = if names.is_empty() {
+        "none are defined".to_string()
+    } else {
+        format!("defined: {}", names.join(", "))
+    };
+    Error::Config(format!(
+        "Unknown profile '{}' ({}; add a [profiles.{}] table to {})",
+        profile,
+        defined,
+        profile,
+        config_path().display()
+    ))
+}
+
+#[cfg(test)]
+mod tests;
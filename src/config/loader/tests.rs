@@ -0,0 +1,196 @@
+//! Tests for reading config files and profiles.
+
+use super::*;
+
+#[test]
+fn test_malformed_toml() {
+    let content = r#"
+This is not valid TOML
+ [[unclosed bracket
+ "#;
+
+    let result: Result<ConfigFile, _> = toml::from_str(content);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_empty_config_file() {
+    let content = "";
+
+    let result: Result<ConfigFile, _> = toml::from_str(content);
+    assert!(result.is_ok());
+
+    let config = result.unwrap();
+    assert!(config.database_path.as_os_str().is_empty());
+    assert!(config.embedding_model.is_empty());
+    assert!(config.model_cache.as_os_str().is_empty());
+    assert_eq!(config.similarity_threshold, 0.85);
+}
+
+#[test]
+fn test_profile_overrides_top_level_settings() {
+    let content = r#"
+        database_path = "/data/default.db"
+        similarity_threshold = 0.9
+
+        [retention]
+        default_ttl = "90d"
+        archive_instead_of_delete = true
+
+        [profiles.work]
+        database_path = "/data/work.db"
+        embedding_model = "BAAI/bge-base-en-v1.5"
+
+        [profiles.work.retention]
+        default_ttl = "30d"
+
+        [profiles.personal]
+        database_path = "/data/personal.db"
+    "#;
+    let table = || content.parse::<toml::Table>().unwrap();
+
+    let base = parse_config(table(), None).unwrap();
+    assert_eq!(base.database_path, PathBuf::from("/data/default.db"));
+    assert!(base.embedding_model.is_empty());
+
+    let work = parse_config(table(), Some("work")).unwrap();
+    assert_eq!(work.database_path, PathBuf::from("/data/work.db"));
+    assert_eq!(work.embedding_model, "BAAI/bge-base-en-v1.5");
+    assert_eq!(work.similarity_threshold, 0.9);
+    assert_eq!(work.retention.default_ttl.as_deref(), Some("30d"));
+    assert!(work.retention.archive_instead_of_delete);
+
+    assert_eq!(
+        profiles_of(&table()).into_iter().collect::<Vec<_>>(),
+        vec!["personal", "work"]
+    );
+    let Err(Error::Config(msg)) = parse_config(table(), Some("home")) else {
+        panic!("expected an unknown profile error");
+    };
+    assert!(msg.contains("defined: personal, work"));
+}
+
+#[test]
+fn test_config_file_missing_recency_weight() {
+    let content = ""; // No recency_weight field
+
+    let result: Result<ConfigFile, _> = toml::from_str(content);
+    assert!(result.is_ok());
+
+    let config = result.unwrap();
+    assert_eq!(config.recency_weight, 0.3); // Should use default, not f64::default() (0.0)
+}
+
+#[test]
+fn test_config_file_partial_toml() {
+    let content = r#"
+        database_path = "/test/db.db"
+    "#;
+
+    let result: Result<ConfigFile, _> = toml::from_str(content);
+    assert!(result.is_ok());
+
+    let config = result.unwrap();
+    assert_eq!(config.database_path, PathBuf::from("/test/db.db"));
+    assert_eq!(config.recency_weight, 0.3); // Missing field uses default 0.3
+    assert_eq!(config.similarity_threshold, 0.85); // Missing field uses default 0.85
+}
+
+#[test]
+fn test_config_file_quota_fields() {
+    let content = r#"
+        max_memories_per_project = 500
+        max_total_db_size_mb = 64
+        evict_on_quota = true
+    "#;
+
+    let config: ConfigFile = toml::from_str(content).unwrap();
+    assert_eq!(config.max_memories_per_project, Some(500));
+    assert_eq!(config.max_total_db_size_mb, Some(64));
+    assert!(config.evict_on_quota);
+
+    let config: ConfigFile = toml::from_str("").unwrap();
+    assert_eq!(config.max_memories_per_project, None);
+    assert!(!config.evict_on_quota);
+}
+
+#[test]
+fn test_config_file_retention_section() {
+    let content = r#"
+        similarity_threshold = 0.9
+
+        [retention]
+        default_ttl = "90d"
+
+        [retention.projects."owner/repo"]
+        ttl = "7d"
+    "#;
+
+    let config: ConfigFile = toml::from_str(content).unwrap();
+    assert_eq!(config.similarity_threshold, 0.9);
+    assert_eq!(config.retention.default_ttl.as_deref(), Some("90d"));
+    assert_eq!(
+        config.retention.projects["owner/repo"].ttl.as_deref(),
+        Some("7d")
+    );
+}
+
+#[test]
+fn test_config_file_trash_retention() {
+    let config: ConfigFile = toml::from_str("").unwrap();
+    assert_eq!(config.trash_retention_days, None);
+    let config: ConfigFile = toml::from_str("trash_retention_days = 30").unwrap();
+    assert_eq!(config.trash_retention_days, Some(30));
+}
+
+#[test]
+fn test_config_file_fts_fields() {
+    let config: ConfigFile = toml::from_str("").unwrap();
+    assert!(config.enable_fts);
+    assert!(config.fts_auto_repair);
+    assert!(!config.fts_integrity_check);
+
+    let content = r#"
+        enable_fts = false
+        fts_auto_repair = false
+        fts_integrity_check = true
+    "#;
+    let config: ConfigFile = toml::from_str(content).unwrap();
+    assert!(!config.enable_fts);
+    assert!(!config.fts_auto_repair);
+    assert!(config.fts_integrity_check);
+}
+
+#[test]
+fn test_config_file_language_fields() {
+    let config: ConfigFile = toml::from_str("").unwrap();
+    assert_eq!(config.fts_tokenizer, FtsTokenizer::Porter);
+    assert!(!config.detect_language);
+
+    let content = r#"
+        fts_tokenizer = "trigram"
+        detect_language = true
+    "#;
+    let config: ConfigFile = toml::from_str(content).unwrap();
+    assert_eq!(config.fts_tokenizer, FtsTokenizer::Trigram);
+    assert!(config.detect_language);
+
+    assert!(toml::from_str::<ConfigFile>(r#"fts_tokenizer = "snowball""#).is_err());
+}
+
+#[test]
+fn test_config_file_embedding_format() {
+    let config: ConfigFile = toml::from_str("").unwrap();
+    assert_eq!(config.embedding_format, EmbeddingFormat::F32);
+
+    let config: ConfigFile = toml::from_str(r#"embedding_format = "int8""#).unwrap();
+    assert_eq!(config.embedding_format, EmbeddingFormat::Int8);
+
+    assert!(toml::from_str::<ConfigFile>(r#"embedding_format = "f16""#).is_err());
+    assert!(!config.stable_ids);
+    assert!(
+        toml::from_str::<ConfigFile>("stable_ids = true")
+            .unwrap()
+            .stable_ids
+    );
+}
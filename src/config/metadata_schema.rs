@@ -0,0 +1,302 @@
+//! Metadata schema configuration (`[metadata_schema]` section).
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::errors::Error;
+use crate::search_options::{FilterOp, MetadataFilter, parse_number};
+
+/// Expected type of a metadata field.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawFieldType")]
+pub enum FieldType {
+    /// Any JSON string.
+    String,
+    /// A JSON number.
+    Number,
+    /// `true` or `false`.
+    Bool,
+    /// One of the listed strings.
+    Enum(Vec<String>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawFieldType {
+    Name(String),
+    Values(Vec<String>),
+}
+
+impl TryFrom<RawFieldType> for FieldType {
+    type Error = String;
+
+    fn try_from(raw: RawFieldType) -> Result<Self, Self::Error> {
+        match raw {
+            RawFieldType::Name(name) => match name.as_str() {
+                "string" => Ok(FieldType::String),
+                "number" => Ok(FieldType::Number),
+                "bool" => Ok(FieldType::Bool),
+                other => Err(format!(
+                    "unknown metadata type '{}' (expected string, number, bool or a list of allowed values)",
+                    other
+                )),
+            },
+            RawFieldType::Values(values) => Ok(FieldType::Enum(values)),
+        }
+    }
+}
+
+impl FieldType {
+    /// Whether a JSON value (not an array) has this type.
+    fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (FieldType::String, Value::String(_))
+            | (FieldType::Number, Value::Number(_))
+            | (FieldType::Bool, Value::Bool(_)) => true,
+            (FieldType::Enum(values), Value::String(s)) => values.contains(s),
+            _ => false,
+        }
+    }
+
+    /// Whether a filter value written on the command line has this type.
+    fn accepts_filter_value(&self, value: &str) -> bool {
+        match self {
+            FieldType::String => true,
+            FieldType::Number => parse_number(value).is_some(),
+            FieldType::Bool => matches!(value, "true" | "false"),
+            FieldType::Enum(values) => values.iter().any(|v| v == value),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            FieldType::String => "a string".to_string(),
+            FieldType::Number => "a number".to_string(),
+            FieldType::Bool => "true or false".to_string(),
+            FieldType::Enum(values) => format!("one of {}", values.join(", ")),
+        }
+    }
+}
+
+/// Expected metadata keys and their types.
+///
+/// Added and updated memories (including imported ones) must have metadata
+/// that fits: a declared key holds a value of its type, or an array of such
+/// values. `search --filter` values are checked the same way, so a typo in
+/// a number or enum value is an error rather than an empty result, and
+/// range comparisons are only allowed on numbers. With `strict`, keys not
+/// declared here are rejected too; otherwise they are left unchecked. The
+/// schema is empty by default.
+///
+/// ```toml
+/// [metadata_schema]
+/// strict = true
+///
+/// [metadata_schema.fields]
+/// importance = "number"
+/// pinned = "bool"
+/// tags = "string"
+/// status = ["open", "done"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+pub struct MetadataSchemaConfig {
+    /// Reject metadata keys that are not declared in `fields`.
+    #[serde(default)]
+    pub strict: bool,
+
+    /// Declared keys and their types.
+    #[serde(default)]
+    pub fields: BTreeMap<String, FieldType>,
+}
+
+impl MetadataSchemaConfig {
+    /// Whether there is anything to check.
+    fn is_empty(&self) -> bool {
+        !self.strict && self.fields.is_empty()
+    }
+
+    /// Check memory `metadata` (a JSON string) against the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` naming the first key that does not fit,
+    /// or if the metadata is not a JSON object.
+    pub fn check(&self, metadata: Option<&str>) -> Result<(), Error> {
+        let Some(metadata) = metadata.filter(|_| !self.is_empty()) else {
+            return Ok(());
+        };
+        let Ok(Value::Object(object)) = serde_json::from_str::<Value>(metadata) else {
+            return Err(Error::InvalidInput(
+                "metadata must be a JSON object to be checked against metadata_schema".to_string(),
+            ));
+        };
+        for (key, value) in &object {
+            let Some(field) = self.field(key)? else {
+                continue;
+            };
+            let fits = match value {
+                Value::Array(values) => values.iter().all(|v| field.accepts(v)),
+                value => field.accepts(value),
+            };
+            if !fits {
+                return Err(Error::InvalidInput(format!(
+                    "Metadata field '{}' must be {} (got {})",
+                    key,
+                    field.describe(),
+                    value
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check search filters against the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if a filter value does not have its
+    /// key's type, a range comparison is on a key that is not a number, or
+    /// (with `strict`) a key is not declared.
+    pub fn check_filters(&self, filters: &[MetadataFilter]) -> Result<(), Error> {
+        for filter in filters {
+            let Some(field) = self.field(&filter.key)? else {
+                continue;
+            };
+            if filter.op != FilterOp::Eq && *field != FieldType::Number {
+                return Err(Error::InvalidInput(format!(
+                    "Invalid filter on '{}': {} needs a number field, and it is {}",
+                    filter.key,
+                    filter.op.as_str(),
+                    field.describe()
+                )));
+            }
+            if !field.accepts_filter_value(&filter.value) {
+                return Err(Error::InvalidInput(format!(
+                    "Invalid filter on '{}': value must be {} (got '{}')",
+                    filter.key,
+                    field.describe(),
+                    filter.value
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The declared type of `key`, or `None` if it is undeclared and the
+    /// schema is not strict.
+    fn field(&self, key: &str) -> Result<Option<&FieldType>, Error> {
+        match self.fields.get(key) {
+            Some(field) => Ok(Some(field)),
+            None if self.strict => Err(Error::InvalidInput(format!(
+                "Metadata field '{}' is not declared in metadata_schema",
+                key
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Check that declared keys and enum value lists are non-empty.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        for (key, field) in &self.fields {
+            if key.trim().is_empty() {
+                return Err(Error::Config(
+                    "Invalid metadata_schema.fields: keys must not be empty".to_string(),
+                ));
+            }
+            if matches!(field, FieldType::Enum(values) if values.is_empty()) {
+                return Err(Error::Config(format!(
+                    "Invalid metadata_schema.fields.{}: the list of allowed values is empty",
+                    key
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(strict: bool) -> MetadataSchemaConfig {
+        let mut schema: MetadataSchemaConfig = toml::from_str(
+            r#"
+            [fields]
+            importance = "number"
+            pinned = "bool"
+            tags = "string"
+            status = ["open", "done"]
+            "#,
+        )
+        .unwrap();
+        schema.strict = strict;
+        schema
+    }
+
+    #[test]
+    fn test_parse_field_types() {
+        let schema = sample(false);
+        assert_eq!(schema.fields["importance"], FieldType::Number);
+        assert_eq!(
+            schema.fields["status"],
+            FieldType::Enum(vec!["open".to_string(), "done".to_string()])
+        );
+        assert!(schema.validate().is_ok());
+        assert!(toml::from_str::<MetadataSchemaConfig>("[fields]\na = \"date\"").is_err());
+        let empty: MetadataSchemaConfig = toml::from_str("[fields]\na = []").unwrap();
+        assert!(matches!(empty.validate(), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_check_metadata() {
+        let schema = sample(false);
+        assert!(
+            schema
+                .check(Some(
+                    r#"{"importance": 2.5, "pinned": true, "tags": ["a", "b"], "status": "open", "other": 1}"#
+                ))
+                .is_ok()
+        );
+        assert!(schema.check(None).is_ok());
+        for bad in [
+            r#"{"importance": "high"}"#,
+            r#"{"pinned": "yes"}"#,
+            r#"{"tags": ["a", 1]}"#,
+            r#"{"status": "closed"}"#,
+            "[1]",
+        ] {
+            assert!(
+                matches!(schema.check(Some(bad)), Err(Error::InvalidInput(_))),
+                "{bad}"
+            );
+        }
+
+        let strict = sample(true);
+        assert!(strict.check(Some(r#"{"other": 1}"#)).is_err());
+        assert!(strict.check(Some(r#"{"importance": 1}"#)).is_ok());
+        // Nothing is checked without a schema
+        assert!(MetadataSchemaConfig::default().check(Some("[1]")).is_ok());
+    }
+
+    #[test]
+    fn test_check_filters() {
+        let schema = sample(false);
+        let check = |spec: &str| schema.check_filters(&[MetadataFilter::parse(spec).unwrap()]);
+        assert!(check("importance>=2").is_ok());
+        assert!(check("importance=2").is_ok());
+        assert!(check("importance=high").is_err());
+        assert!(check("pinned=true").is_ok());
+        assert!(check("pinned=yes").is_err());
+        assert!(check("status=done").is_ok());
+        assert!(check("status=closed").is_err());
+        assert!(check("status>1").is_err());
+        assert!(check("other>1").is_ok());
+        assert!(
+            sample(true)
+                .check_filters(&[MetadataFilter::parse("other=1").unwrap()])
+                .is_err()
+        );
+    }
+}
@@ -1,21 +1,50 @@
 //! Configuration system for vipune.
 
+pub mod chunking;
+pub mod compression;
+pub mod conflicts;
+pub mod daemon;
+pub mod download;
 mod env_parser;
+pub mod filters;
+pub mod hooks;
+pub mod late_interaction;
+pub mod limits;
 mod loader;
+pub mod metadata_schema;
 mod overrides;
 mod paths;
+pub mod project;
+pub mod rerank;
+pub mod retention;
+pub mod review;
 mod validation;
 
 #[cfg(test)]
 mod tests_utils;
-#[cfg(test)]
-use tests_utils::ENV_MUTEX;
 
 use crate::errors::Error;
+use crate::normalize::TextNormalization;
+use crate::project::MonorepoMode;
+use crate::sqlite::{EmbeddingFormat, FtsTokenizer, Visibility};
 use serde::Deserialize;
 use std::path::PathBuf;
 
-pub use loader::ConfigFile;
+pub use chunking::ChunkingConfig;
+pub use compression::CompressionConfig;
+pub use conflicts::ConflictsConfig;
+pub use daemon::DaemonConfig;
+pub use download::DownloadConfig;
+pub use filters::{FilterKind, FiltersConfig};
+pub use hooks::{HookFailurePolicy, HooksConfig};
+pub use late_interaction::LateInteractionConfig;
+pub use limits::LimitsConfig;
+pub use loader::{ConfigFile, config_path};
+pub use metadata_schema::MetadataSchemaConfig;
+pub use project::ProjectConfig;
+pub use rerank::RerankConfig;
+pub use retention::RetentionConfig;
+pub use review::ReviewConfig;
 
 /// Configuration values with priority: defaults < config file < env vars.
 ///
@@ -51,6 +80,159 @@ pub struct Config {
     /// Weight applied to recency in search ranking (0.0 = ignore time, 1.0 = prioritize recent).
     #[serde(default)]
     pub recency_weight: f64,
+
+    /// Maximum number of memories per project (`None` = unlimited).
+    #[serde(default)]
+    pub max_memories_per_project: Option<usize>,
+
+    /// Maximum size of the database file in megabytes (`None` = unlimited).
+    #[serde(default)]
+    pub max_total_db_size_mb: Option<u64>,
+
+    /// Evict the oldest memories when a quota is reached instead of rejecting the add.
+    #[serde(default)]
+    pub evict_on_quota: bool,
+
+    /// Never download model files; load them from the local cache only.
+    #[serde(default)]
+    pub offline: bool,
+
+    /// Retries of model downloads on transient network errors.
+    #[serde(default)]
+    pub download: DownloadConfig,
+
+    /// Days deleted memories stay in the trash, restorable with
+    /// `vipune trash restore`, before pruning removes them for good. Unset
+    /// (the default), deletes are permanent.
+    #[serde(default)]
+    pub trash_retention_days: Option<u32>,
+
+    /// Days without an update or access after which pruning marks a memory
+    /// inactive. Searches skip inactive memories unless asked to include
+    /// them, so large stores keep scanning only recently used ones. Unset
+    /// (the default), every memory is searched.
+    #[serde(default)]
+    pub inactive_after_days: Option<u32>,
+
+    /// Retention rules enforced by `vipune prune`.
+    #[serde(default)]
+    pub retention: RetentionConfig,
+
+    /// Which idle, unimportant memories `vipune review` lists.
+    #[serde(default)]
+    pub review: ReviewConfig,
+
+    /// How similarity and term overlap combine into conflict confidence.
+    #[serde(default)]
+    pub conflicts: ConflictsConfig,
+
+    /// Cross-encoder used by searches with reranking on.
+    #[serde(default)]
+    pub rerank: RerankConfig,
+
+    /// Token embeddings for late-interaction (MaxSim) rescoring.
+    #[serde(default)]
+    pub late_interaction: LateInteractionConfig,
+
+    /// Splitting of long content into searchable chunks.
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+
+    /// Zstd compression of long content.
+    #[serde(default)]
+    pub compression: CompressionConfig,
+
+    /// Commands run after memories are added, updated or deleted.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Bounds on content length, result limits, batch sizes and the time a
+    /// search or model run may take.
+    #[serde(default)]
+    pub limits: LimitsConfig,
+
+    /// Bounds on the requests and connections `vipune daemon` accepts at
+    /// once.
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+
+    /// Expected metadata keys and types, checked on add, update and search.
+    #[serde(default)]
+    pub metadata_schema: MetadataSchemaConfig,
+
+    /// Keep an FTS5 full-text index for keyword and hybrid search. When
+    /// false, the index and its triggers are dropped, BM25 search returns
+    /// `Error::FtsDisabled` and hybrid search ranks by similarity only.
+    #[serde(default)]
+    pub enable_fts: bool,
+
+    /// Rebuild the FTS5 index on open when it has drifted from the memories table.
+    #[serde(default)]
+    pub fts_auto_repair: bool,
+
+    /// Also run the (slower) FTS5 integrity check on open, not just a row count comparison.
+    #[serde(default)]
+    pub fts_integrity_check: bool,
+
+    /// Tokenizer of the full-text index of a newly created database
+    /// (`vipune reindex` switches an existing one).
+    #[serde(default)]
+    pub fts_tokenizer: FtsTokenizer,
+
+    /// Unicode normalization of the full-text index of a newly created
+    /// database and of keyword queries (`vipune reindex` applies it to an
+    /// existing one).
+    #[serde(default)]
+    pub normalization: TextNormalization,
+
+    /// Detect and store the language of each memory added or updated.
+    #[serde(default)]
+    pub detect_language: bool,
+
+    /// Reject added and updated content that looks like a credential.
+    #[serde(default)]
+    pub secret_scan: bool,
+
+    /// Owner recorded on added memories. Searches and lists return only this
+    /// owner's memories and shared ones; without an owner nothing is left
+    /// out.
+    #[serde(default)]
+    pub owner: Option<String>,
+
+    /// Visibility of added memories: `shared` (the default) or `private`,
+    /// which needs an `owner`.
+    #[serde(default)]
+    pub visibility: Visibility,
+
+    /// Storage format of new embeddings: full-precision `f32` or quantized
+    /// `int8` (`vipune quantize` converts existing ones).
+    #[serde(default)]
+    pub embedding_format: EmbeddingFormat,
+
+    /// Derive new memories' IDs from their project and content instead of
+    /// drawing them at random, so adding the same content again updates the
+    /// existing memory and re-imports are idempotent.
+    #[serde(default)]
+    pub stable_ids: bool,
+
+    /// Whether detected project IDs identify the repository (`root`) or the
+    /// package the current directory is in (`subdir`).
+    #[serde(default)]
+    pub monorepo_mode: MonorepoMode,
+
+    /// Content filters run on add and update, in order.
+    #[serde(default)]
+    pub filters: FiltersConfig,
+
+    /// sqlite-vec loadable extension to run semantic searches through a
+    /// native vector index (needs the `sqlite-vec` build feature; searches
+    /// fall back to scanning if it cannot be loaded).
+    #[serde(default)]
+    pub sqlite_vec_path: Option<PathBuf>,
+
+    /// Overrides and add defaults from the repository's `.vipune.toml`.
+    #[serde(default)]
+    pub project: ProjectConfig,
 }
 
 impl Default for Config {
@@ -69,14 +251,68 @@ impl Default for Config {
             model_cache: vipune_dir.join("models"),
             similarity_threshold: 0.85,
             recency_weight: 0.3,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
+            evict_on_quota: false,
+            offline: false,
+            download: DownloadConfig::default(),
+            trash_retention_days: None,
+            inactive_after_days: None,
+            retention: RetentionConfig::default(),
+            review: ReviewConfig::default(),
+            conflicts: ConflictsConfig::default(),
+            rerank: RerankConfig::default(),
+            late_interaction: LateInteractionConfig::default(),
+            chunking: ChunkingConfig::default(),
+            compression: CompressionConfig::default(),
+            hooks: HooksConfig::default(),
+            limits: LimitsConfig::default(),
+            daemon: DaemonConfig::default(),
+            metadata_schema: MetadataSchemaConfig::default(),
+            enable_fts: true,
+            fts_auto_repair: true,
+            fts_integrity_check: false,
+            fts_tokenizer: FtsTokenizer::default(),
+            normalization: TextNormalization::default(),
+            detect_language: false,
+            secret_scan: false,
+            owner: None,
+            visibility: Visibility::default(),
+            embedding_format: EmbeddingFormat::default(),
+            stable_ids: false,
+            monorepo_mode: MonorepoMode::default(),
+            filters: FiltersConfig::default(),
+            sqlite_vec_path: None,
+            project: ProjectConfig::default(),
         }
     }
 }
 
+/// Environment variable naming the config profile to use when none is given.
+pub const PROFILE_ENV: &str = "VIPUNE_PROFILE";
+
 impl Config {
     /// Load configuration with defaults, file values, and environment overrides.
+    ///
+    /// The `.vipune.toml` of the current git repository, if any, overrides
+    /// the config file; environment variables override both. Uses the
+    /// profile named by `VIPUNE_PROFILE`, if set.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
     pub fn load() -> Result<Self, Error> {
-        let file_config = loader::load_from_file()?;
+        Self::load_profile(None)
+    }
+
+    /// Like [`Config::load`], with the settings of a profile (a
+    /// `[profiles.<name>]` table of the config file, e.g. with its own
+    /// `database_path` and `embedding_model`) laid over the top-level ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the profile (`profile`, or else
+    /// `VIPUNE_PROFILE`) is not defined, or the configuration is invalid.
+    pub fn load_profile(profile: Option<&str>) -> Result<Self, Error> {
+        let profile = Self::selected_profile(profile);
+        let file_config = loader::load_from_file(profile.as_deref())?;
 
         let mut config = Config::default();
 
@@ -86,6 +322,12 @@ impl Config {
             config.merge_from_file(file);
         }
 
+        if let Some(root) = crate::project::find_git_root() {
+            if let Some(project) = ProjectConfig::load(&root)? {
+                config.merge_from_project(project);
+            }
+        }
+
         overrides::apply_env_overrides(
             &mut config.database_path,
             &mut config.embedding_model,
@@ -93,12 +335,37 @@ impl Config {
             &mut config.similarity_threshold,
             &mut config.recency_weight,
         )?;
+        overrides::apply_quota_env_overrides(
+            &mut config.max_memories_per_project,
+            &mut config.max_total_db_size_mb,
+            &mut config.evict_on_quota,
+        )?;
+        overrides::apply_chunking_env_overrides(&mut config.chunking.enabled)?;
 
         config.validate()?;
 
         Ok(config)
     }
 
+    /// The profile to use: `profile` if given, else `VIPUNE_PROFILE` if set
+    /// and not empty.
+    pub fn selected_profile(profile: Option<&str>) -> Option<String> {
+        profile.map(str::to_string).or_else(|| {
+            std::env::var(PROFILE_ENV)
+                .ok()
+                .filter(|name| !name.trim().is_empty())
+        })
+    }
+
+    /// Names of the profiles defined in the config file, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the config file cannot be read or parsed.
+    pub fn profile_names() -> Result<Vec<String>, Error> {
+        loader::profile_names()
+    }
+
     /// Merge configuration from a file into this config.
     fn merge_from_file(&mut self, file: ConfigFile) {
         if !file.database_path.as_os_str().is_empty() {
@@ -112,18 +379,49 @@ impl Config {
         }
         self.similarity_threshold = file.similarity_threshold;
         self.recency_weight = file.recency_weight;
+        self.max_memories_per_project = file.max_memories_per_project;
+        self.max_total_db_size_mb = file.max_total_db_size_mb;
+        self.evict_on_quota = file.evict_on_quota;
+        self.offline = file.offline;
+        self.download = file.download;
+        self.trash_retention_days = file.trash_retention_days;
+        self.inactive_after_days = file.inactive_after_days;
+        self.retention = file.retention;
+        self.review = file.review;
+        self.conflicts = file.conflicts;
+        self.rerank = file.rerank;
+        self.late_interaction = file.late_interaction;
+        self.chunking = file.chunking;
+        self.compression = file.compression;
+        self.hooks = file.hooks;
+        self.limits = file.limits;
+        self.daemon = file.daemon;
+        self.metadata_schema = file.metadata_schema;
+        self.enable_fts = file.enable_fts;
+        self.fts_auto_repair = file.fts_auto_repair;
+        self.fts_integrity_check = file.fts_integrity_check;
+        self.fts_tokenizer = file.fts_tokenizer;
+        self.normalization = file.normalization;
+        self.detect_language = file.detect_language;
+        self.secret_scan = file.secret_scan;
+        self.owner = file.owner;
+        self.visibility = file.visibility;
+        self.embedding_format = file.embedding_format;
+        self.stable_ids = file.stable_ids;
+        self.monorepo_mode = file.monorepo_mode;
+        self.filters = file.filters;
+        self.sqlite_vec_path = file.sqlite_vec_path;
     }
 
-    /// Validate configuration values.
-    fn validate(&self) -> Result<(), Error> {
-        let validator = validation::ConfigValidator {
-            database_path: self.database_path.clone(),
-            embedding_model: self.embedding_model.clone(),
-            similarity_threshold: self.similarity_threshold,
-            recency_weight: self.recency_weight,
-        };
-
-        validator.validate()
+    /// Merge the overrides of a project config file into this config.
+    fn merge_from_project(&mut self, project: ProjectConfig) {
+        if let Some(threshold) = project.similarity_threshold {
+            self.similarity_threshold = threshold;
+        }
+        if let Some(weight) = project.recency_weight {
+            self.recency_weight = weight;
+        }
+        self.project = project;
     }
 
     /// Ensure parent directories for database and cache paths exist.
@@ -153,57 +451,4 @@ impl Config {
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    fn cleanup_env_vars() {
-        let vars = [
-            "VIPUNE_DATABASE_PATH",
-            "VIPUNE_EMBEDDING_MODEL",
-            "VIPUNE_MODEL_CACHE",
-            "VIPUNE_SIMILARITY_THRESHOLD",
-            "VIPUNE_RECENCY_WEIGHT",
-        ];
-        for var in vars {
-            unsafe {
-                std::env::remove_var(var);
-            }
-        }
-    }
-
-    #[test]
-    fn test_default_config() {
-        let config = Config::default();
-
-        assert!(config.database_path.ends_with(".vipune/memories.db"));
-        assert_eq!(config.embedding_model, "BAAI/bge-small-en-v1.5");
-        assert!(config.model_cache.ends_with(".vipune/models"));
-        assert_eq!(config.similarity_threshold, 0.85);
-        assert_eq!(config.recency_weight, 0.3);
-    }
-
-    #[test]
-    fn test_config_load_without_file() {
-        let _guard = ENV_MUTEX.lock().unwrap();
-        cleanup_env_vars();
-
-        let config = Config::load().unwrap();
-
-        assert!(config.database_path.ends_with(".vipune/memories.db"));
-        assert_eq!(config.embedding_model, "BAAI/bge-small-en-v1.5");
-        assert_eq!(config.similarity_threshold, 0.85);
-    }
-
-    #[test]
-    fn test_config_file_overrides_defaults() {
-        let _guard = ENV_MUTEX.lock().unwrap();
-        cleanup_env_vars();
-
-        let config = Config::load().unwrap();
-
-        assert!(config.database_path.ends_with(".vipune/memories.db"));
-        assert_eq!(config.embedding_model, "BAAI/bge-small-en-v1.5");
-        assert!(config.model_cache.ends_with(".vipune/models"));
-        assert_eq!(config.similarity_threshold, 0.85);
-    }
-}
+mod tests;
@@ -24,6 +24,23 @@ pub fn apply_env_overrides(
     Ok(())
 }
 
+/// Apply environment variable overrides to quota configuration.
+pub fn apply_quota_env_overrides(
+    max_memories_per_project: &mut Option<usize>,
+    max_total_db_size_mb: &mut Option<u64>,
+    evict_on_quota: &mut bool,
+) -> Result<(), Error> {
+    env_parser::apply_max_memories_override(max_memories_per_project)?;
+    env_parser::apply_max_db_size_override(max_total_db_size_mb)?;
+    env_parser::apply_evict_on_quota_override(evict_on_quota)?;
+    Ok(())
+}
+
+/// Apply environment variable overrides to chunking configuration.
+pub fn apply_chunking_env_overrides(enabled: &mut bool) -> Result<(), Error> {
+    env_parser::apply_chunking_override(enabled)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -35,6 +52,10 @@ mod tests {
             "VIPUNE_MODEL_CACHE",
             "VIPUNE_SIMILARITY_THRESHOLD",
             "VIPUNE_RECENCY_WEIGHT",
+            "VIPUNE_MAX_MEMORIES_PER_PROJECT",
+            "VIPUNE_MAX_TOTAL_DB_SIZE_MB",
+            "VIPUNE_EVICT_ON_QUOTA",
+            "VIPUNE_CHUNKING",
         ];
         for var in vars {
             unsafe {
@@ -218,4 +239,65 @@ mod tests {
 
         cleanup_env_vars();
     }
+
+    #[test]
+    fn test_quota_env_var_overrides() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        cleanup_env_vars();
+
+        unsafe {
+            std::env::set_var("VIPUNE_MAX_MEMORIES_PER_PROJECT", "100");
+            std::env::set_var("VIPUNE_MAX_TOTAL_DB_SIZE_MB", "50");
+            std::env::set_var("VIPUNE_EVICT_ON_QUOTA", "true");
+        }
+
+        let mut max_memories = None;
+        let mut max_size = None;
+        let mut evict = false;
+
+        apply_quota_env_overrides(&mut max_memories, &mut max_size, &mut evict).unwrap();
+
+        assert_eq!(max_memories, Some(100));
+        assert_eq!(max_size, Some(50));
+        assert!(evict);
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    fn test_invalid_quota_env_var() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        cleanup_env_vars();
+
+        unsafe {
+            std::env::set_var("VIPUNE_MAX_MEMORIES_PER_PROJECT", "lots");
+        }
+
+        let mut max_memories = None;
+        let mut max_size = None;
+        let mut evict = false;
+
+        let result = apply_quota_env_overrides(&mut max_memories, &mut max_size, &mut evict);
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        cleanup_env_vars();
+    }
+
+    #[test]
+    fn test_chunking_env_var_override() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        cleanup_env_vars();
+
+        let mut enabled = false;
+        apply_chunking_env_overrides(&mut enabled).unwrap();
+        assert!(!enabled);
+
+        unsafe {
+            std::env::set_var("VIPUNE_CHUNKING", "true");
+        }
+        apply_chunking_env_overrides(&mut enabled).unwrap();
+        assert!(enabled);
+
+        cleanup_env_vars();
+    }
 }
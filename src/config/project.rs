@@ -0,0 +1,196 @@
+//! Per-repository overrides (`.vipune.toml` at the git repository root).
+
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::errors::Error;
+use crate::search_options::{NAMESPACE_KEY, TAGS_KEY};
+use crate::sqlite::MemoryKind;
+
+/// Name of the project config file, looked up at the git repository root.
+pub const PROJECT_CONFIG_FILE: &str = ".vipune.toml";
+
+/// Settings a repository overrides for everyone working in it.
+///
+/// Read from `.vipune.toml` at the root of the git repository the current
+/// directory is in (found as by `detect_project`). Values override the user
+/// config file; environment variables still override both.
+///
+/// ```toml
+/// similarity_threshold = 0.9
+/// recency_weight = 0.1
+/// namespace = "backend"
+/// default_tags = ["backend", "api"]
+/// default_kind = "decision"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ProjectConfig {
+    /// Overrides `similarity_threshold`.
+    #[serde(default)]
+    pub similarity_threshold: Option<f64>,
+
+    /// Overrides `recency_weight`.
+    #[serde(default)]
+    pub recency_weight: Option<f64>,
+
+    /// `namespace` metadata given to added memories, and the namespace
+    /// searches are restricted to unless `--namespace` is passed.
+    #[serde(default)]
+    pub namespace: Option<String>,
+
+    /// Tags added to the `tags` metadata of added memories.
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+
+    /// Kind of added memories without `--kind`.
+    #[serde(default)]
+    pub default_kind: Option<MemoryKind>,
+}
+
+impl ProjectConfig {
+    /// Read `.vipune.toml` from `root`, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the file cannot be read or parsed, or
+    /// holds an invalid value.
+    pub fn load(root: &Path) -> Result<Option<Self>, Error> {
+        let path = root.join(PROJECT_CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read project config file {}: {e}",
+                path.display()
+            ))
+        })?;
+        let config: ProjectConfig = toml::from_str(&content).map_err(|e| {
+            Error::Config(format!(
+                "Failed to parse project config file {}: {e}",
+                path.display()
+            ))
+        })?;
+        config.validate()?;
+        log::debug!("Loaded project config {}", path.display());
+        Ok(Some(config))
+    }
+
+    /// Metadata JSON for a memory added with `metadata`, given this
+    /// project's namespace and default tags.
+    ///
+    /// An explicit `namespace` is kept, and default tags missing from a
+    /// `tags` array are appended to it. Metadata that is not a JSON object
+    /// is returned unchanged (and rejected on add).
+    pub fn apply_defaults(&self, metadata: Option<&str>) -> Option<String> {
+        if self.namespace.is_none() && self.default_tags.is_empty() {
+            return metadata.map(str::to_string);
+        }
+        let mut object = match metadata.map(serde_json::from_str::<Value>) {
+            None => Map::new(),
+            Some(Ok(Value::Object(object))) => object,
+            Some(_) => return metadata.map(str::to_string),
+        };
+        if let Some(namespace) = &self.namespace {
+            object
+                .entry(NAMESPACE_KEY)
+                .or_insert_with(|| Value::String(namespace.clone()));
+        }
+        if !self.default_tags.is_empty() {
+            let tags = object
+                .entry(TAGS_KEY)
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(tags) = tags {
+                for tag in &self.default_tags {
+                    let tag = Value::String(tag.clone());
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+            }
+        }
+        Some(Value::Object(object).to_string())
+    }
+
+    /// Check the namespace and tags; thresholds are checked with the rest
+    /// of the config.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self
+            .namespace
+            .as_deref()
+            .is_some_and(|n| n.trim().is_empty())
+        {
+            return Err(Error::Config(
+                "Invalid namespace in .vipune.toml: must not be empty".to_string(),
+            ));
+        }
+        if self.default_tags.iter().any(|tag| tag.trim().is_empty()) {
+            return Err(Error::Config(
+                "Invalid default_tags in .vipune.toml: tags must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project(namespace: Option<&str>, tags: &[&str]) -> ProjectConfig {
+        ProjectConfig {
+            namespace: namespace.map(str::to_string),
+            default_tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..ProjectConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_load_from_repo_root() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(ProjectConfig::load(dir.path()).unwrap(), None);
+
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "similarity_threshold = 0.9\nnamespace = \"backend\"\ndefault_kind = \"decision\"\n",
+        )
+        .unwrap();
+        let config = ProjectConfig::load(dir.path()).unwrap().unwrap();
+        assert_eq!(config.similarity_threshold, Some(0.9));
+        assert_eq!(config.recency_weight, None);
+        assert_eq!(config.namespace.as_deref(), Some("backend"));
+        assert_eq!(config.default_kind, Some(MemoryKind::Decision));
+
+        std::fs::write(dir.path().join(PROJECT_CONFIG_FILE), "namespace = \" \"").unwrap();
+        assert!(matches!(
+            ProjectConfig::load(dir.path()),
+            Err(Error::Config(_))
+        ));
+        std::fs::write(dir.path().join(PROJECT_CONFIG_FILE), "default_kind = \"x\"").unwrap();
+        assert!(ProjectConfig::load(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_apply_defaults_to_metadata() {
+        let config = project(Some("backend"), &["api", "rust"]);
+        let applied = |metadata| -> Value {
+            serde_json::from_str(&config.apply_defaults(metadata).unwrap()).unwrap()
+        };
+        assert_eq!(
+            applied(None),
+            serde_json::json!({"namespace": "backend", "tags": ["api", "rust"]})
+        );
+        assert_eq!(
+            applied(Some(r#"{"namespace": "docs", "tags": ["rust", "db"]}"#)),
+            serde_json::json!({"namespace": "docs", "tags": ["rust", "db", "api"]})
+        );
+        assert_eq!(
+            config.apply_defaults(Some("not json")).as_deref(),
+            Some("not json")
+        );
+        assert_eq!(ProjectConfig::default().apply_defaults(None), None);
+    }
+}
@@ -0,0 +1,85 @@
+//! Cross-encoder reranking configuration (`[rerank]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Most candidates a config may have reranked per search.
+pub const MAX_RERANK_CANDIDATES: usize = 200;
+
+/// Cross-encoder used by searches with reranking on (`search --rerank`,
+/// [`SearchOptions::rerank`](crate::SearchOptions::rerank)).
+///
+/// Reranking needs vipune built with the `rerank` feature. The model is
+/// downloaded on first use like the embedding model; it must be a
+/// sequence-classification model with an ONNX export (`onnx/model.onnx` or
+/// `model.onnx`) returning one relevance logit per query-document pair.
+///
+/// ```toml
+/// [rerank]
+/// model = "BAAI/bge-reranker-base"
+/// candidates = 20
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RerankConfig {
+    /// HuggingFace ID of the cross-encoder.
+    #[serde(default = "default_model")]
+    pub model: String,
+
+    /// Top candidates reranked (at least the search limit). Each one costs
+    /// a model run over the query and its content.
+    #[serde(default = "default_candidates")]
+    pub candidates: usize,
+}
+
+fn default_model() -> String {
+    "BAAI/bge-reranker-base".to_string()
+}
+
+fn default_candidates() -> usize {
+    20
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            model: default_model(),
+            candidates: default_candidates(),
+        }
+    }
+}
+
+impl RerankConfig {
+    /// Check that a model is named and `candidates` is in range.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.model.trim().is_empty() {
+            return Err(Error::Config(
+                "Invalid rerank.model: must not be empty".to_string(),
+            ));
+        }
+        if !(1..=MAX_RERANK_CANDIDATES).contains(&self.candidates) {
+            return Err(Error::Config(format!(
+                "Invalid rerank.candidates: {} (must be between 1 and {})",
+                self.candidates, MAX_RERANK_CANDIDATES
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rerank_config_defaults_and_validation() {
+        let config: RerankConfig = toml::from_str("").unwrap();
+        assert_eq!(config, RerankConfig::default());
+        assert!(config.validate().is_ok());
+
+        for bad in ["candidates = 0", "candidates = 201", "model = \" \""] {
+            let config: RerankConfig = toml::from_str(bad).unwrap();
+            assert!(matches!(config.validate(), Err(Error::Config(_))), "{bad}");
+        }
+    }
+}
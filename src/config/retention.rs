@@ -0,0 +1,196 @@
+//! Retention policy configuration (`[retention]` section).
+
+use std::collections::HashMap;
+
+use chrono::Duration;
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Declarative lifecycle rules for stored memories.
+///
+/// Memories whose last update is older than the applicable TTL are removed by
+/// `MemoryStore::prune` (the `vipune prune` command).
+///
+/// ```toml
+/// [retention]
+/// default_ttl = "90d"
+/// archive_instead_of_delete = true
+/// prune_on_add = false
+///
+/// [retention.projects."owner/repo"]
+/// ttl = "30d"
+///
+/// [retention.projects."owner/keep-forever"]
+/// ttl = "never"
+/// ```
+///
+/// TTLs are a positive integer followed by a unit: `s`, `m`, `h`, `d` or `w`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RetentionConfig {
+    /// TTL applied to projects without an override (`None` = keep forever).
+    #[serde(default)]
+    pub default_ttl: Option<String>,
+
+    /// Move expired memories to the `memories_archive` table instead of deleting them.
+    #[serde(default)]
+    pub archive_instead_of_delete: bool,
+
+    /// Prune the target project opportunistically before each add.
+    #[serde(default)]
+    pub prune_on_add: bool,
+
+    /// Per-project overrides keyed by project ID.
+    #[serde(default)]
+    pub projects: HashMap<String, ProjectRetention>,
+}
+
+/// Retention override for a single project.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectRetention {
+    /// TTL for this project; `"never"` disables expiry.
+    #[serde(default)]
+    pub ttl: Option<String>,
+}
+
+impl RetentionConfig {
+    /// Resolve the TTL for a project (`None` = memories never expire).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the applicable TTL string is invalid.
+    pub fn ttl_for(&self, project_id: &str) -> Result<Option<Duration>, Error> {
+        let ttl = match self.projects.get(project_id).and_then(|p| p.ttl.as_deref()) {
+            Some(ttl) => Some(ttl),
+            None => self.default_ttl.as_deref(),
+        };
+        match ttl {
+            Some(ttl) => parse_ttl(ttl),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether any rule can expire memories.
+    pub fn is_enabled(&self) -> bool {
+        self.default_ttl.is_some() || self.projects.values().any(|p| p.ttl.is_some())
+    }
+
+    /// Check that every TTL in the section parses.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if let Some(ttl) = &self.default_ttl {
+            parse_ttl(ttl)?;
+        }
+        for project in self.projects.values() {
+            if let Some(ttl) = &project.ttl {
+                parse_ttl(ttl)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a TTL such as `"90d"` or `"12h"`; `"never"` yields `None`.
+///
+/// # Errors
+///
+/// Returns `Error::Config` for empty, zero, negative or unknown-unit values.
+pub fn parse_ttl(ttl: &str) -> Result<Option<Duration>, Error> {
+    let ttl = ttl.trim();
+    if ttl.eq_ignore_ascii_case("never") {
+        return Ok(None);
+    }
+
+    let invalid = || {
+        Error::Config(format!(
+            "Invalid retention TTL '{ttl}' (expected e.g. \"90d\", \"12h\" or \"never\")"
+        ))
+    };
+
+    let unit = ttl.chars().last().ok_or_else(invalid)?;
+    let amount: i64 = ttl[..ttl.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+    if amount <= 0 {
+        return Err(invalid());
+    }
+
+    let duration = match unit {
+        's' => Duration::try_seconds(amount),
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    };
+    duration.map(Some).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ttl_units() {
+        assert_eq!(parse_ttl("30s").unwrap(), Some(Duration::seconds(30)));
+        assert_eq!(parse_ttl("5m").unwrap(), Some(Duration::minutes(5)));
+        assert_eq!(parse_ttl("12h").unwrap(), Some(Duration::hours(12)));
+        assert_eq!(parse_ttl(" 90d ").unwrap(), Some(Duration::days(90)));
+        assert_eq!(parse_ttl("2w").unwrap(), Some(Duration::weeks(2)));
+        assert_eq!(parse_ttl("never").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_ttl_invalid() {
+        for ttl in ["", "d", "90", "0d", "-1d", "10y", "abc"] {
+            assert!(
+                matches!(parse_ttl(ttl), Err(Error::Config(_))),
+                "expected error for {ttl:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ttl_for_uses_project_override() {
+        let config: RetentionConfig = toml::from_str(
+            r#"
+            default_ttl = "90d"
+            archive_instead_of_delete = true
+
+            [projects."owner/repo"]
+            ttl = "30d"
+
+            [projects."owner/keep"]
+            ttl = "never"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.archive_instead_of_delete);
+        assert_eq!(
+            config.ttl_for("owner/repo").unwrap(),
+            Some(Duration::days(30))
+        );
+        assert_eq!(config.ttl_for("owner/keep").unwrap(), None);
+        assert_eq!(config.ttl_for("other").unwrap(), Some(Duration::days(90)));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_default_config_keeps_everything() {
+        let config = RetentionConfig::default();
+        assert!(!config.is_enabled());
+        assert_eq!(config.ttl_for("any").unwrap(), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_override() {
+        let mut config = RetentionConfig::default();
+        config.projects.insert(
+            "p".to_string(),
+            ProjectRetention {
+                ttl: Some("soon".to_string()),
+            },
+        );
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,116 @@
+//! Review queue configuration (`[review]` section).
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Which memories `vipune review` puts up for a keep, archive or delete
+/// decision.
+///
+/// A memory is idle since its last update, access or review, whichever is
+/// latest. Once idle for `stale_days`, it joins the queue if its importance
+/// (the numeric `importance` metadata, 0 when absent) has decayed to at most
+/// `max_importance`: importance halves every `half_life_days` of idleness,
+/// so even important memories come up for review eventually. Pinned
+/// memories never do.
+///
+/// ```toml
+/// [review]
+/// stale_days = 60
+/// max_importance = 2.0
+/// half_life_days = 365
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ReviewConfig {
+    /// Days without an update, access or review before a memory is reviewed.
+    #[serde(default = "default_stale_days")]
+    pub stale_days: u32,
+
+    /// Highest decayed importance a memory in the queue may have.
+    #[serde(default = "default_max_importance")]
+    pub max_importance: f64,
+
+    /// Idle days over which importance halves.
+    #[serde(default = "default_half_life_days")]
+    pub half_life_days: u32,
+}
+
+fn default_stale_days() -> u32 {
+    90
+}
+
+fn default_max_importance() -> f64 {
+    3.0
+}
+
+fn default_half_life_days() -> u32 {
+    180
+}
+
+impl Default for ReviewConfig {
+    fn default() -> Self {
+        Self {
+            stale_days: default_stale_days(),
+            max_importance: default_max_importance(),
+            half_life_days: default_half_life_days(),
+        }
+    }
+}
+
+impl ReviewConfig {
+    /// `importance` after `idle_days` of decay.
+    pub fn decayed_importance(&self, importance: f64, idle_days: f64) -> f64 {
+        importance * 0.5f64.powf(idle_days.max(0.0) / f64::from(self.half_life_days))
+    }
+
+    /// Check that the idle period, threshold and half-life are usable.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.stale_days == 0 {
+            return Err(Error::Config(
+                "Invalid review.stale_days: must be greater than 0".to_string(),
+            ));
+        }
+        if !self.max_importance.is_finite() {
+            return Err(Error::Config(format!(
+                "Invalid review.max_importance: {} (must be a finite number)",
+                self.max_importance
+            )));
+        }
+        if self.half_life_days == 0 {
+            return Err(Error::Config(
+                "Invalid review.half_life_days: must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_importance_halves_every_half_life() {
+        let config: ReviewConfig = toml::from_str("half_life_days = 30").unwrap();
+        assert_eq!(config.stale_days, 90);
+        assert_eq!(config.decayed_importance(8.0, 0.0), 8.0);
+        assert_eq!(config.decayed_importance(8.0, 30.0), 4.0);
+        assert_eq!(config.decayed_importance(8.0, 90.0), 1.0);
+        assert_eq!(config.decayed_importance(0.0, 90.0), 0.0);
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_periods() {
+        assert!(ReviewConfig::default().validate().is_ok());
+        let config = ReviewConfig {
+            stale_days: 0,
+            ..ReviewConfig::default()
+        };
+        assert!(matches!(config.validate(), Err(Error::Config(_))));
+        let config = ReviewConfig {
+            max_importance: f64::NAN,
+            ..ReviewConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Tests for config defaults and environment overrides.
+
+use super::tests_utils::ENV_MUTEX;
+use super::*;
+
+fn cleanup_env_vars() {
+    let vars = [
+        "VIPUNE_DATABASE_PATH",
+        "VIPUNE_EMBEDDING_MODEL",
+        "VIPUNE_MODEL_CACHE",
+        "VIPUNE_SIMILARITY_THRESHOLD",
+        "VIPUNE_RECENCY_WEIGHT",
+        "VIPUNE_MAX_MEMORIES_PER_PROJECT",
+        "VIPUNE_MAX_TOTAL_DB_SIZE_MB",
+        "VIPUNE_EVICT_ON_QUOTA",
+        "VIPUNE_CHUNKING",
+    ];
+    for var in vars {
+        unsafe {
+            std::env::remove_var(var);
+        }
+    }
+}
+
+#[test]
+fn test_default_config() {
+    let config = Config::default();
+
+    assert!(config.database_path.ends_with(".vipune/memories.db"));
+    assert_eq!(config.embedding_model, "BAAI/bge-small-en-v1.5");
+    assert!(config.model_cache.ends_with(".vipune/models"));
+    assert_eq!(config.similarity_threshold, 0.85);
+    assert_eq!(config.recency_weight, 0.3);
+    assert_eq!(config.max_memories_per_project, None);
+    assert_eq!(config.max_total_db_size_mb, None);
+    assert!(!config.evict_on_quota);
+}
+
+#[test]
+fn test_config_load_without_file() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    cleanup_env_vars();
+
+    let config = Config::load().unwrap();
+
+    assert!(config.database_path.ends_with(".vipune/memories.db"));
+    assert_eq!(config.embedding_model, "BAAI/bge-small-en-v1.5");
+    assert_eq!(config.similarity_threshold, 0.85);
+}
+
+#[test]
+fn test_config_file_overrides_defaults() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    cleanup_env_vars();
+
+    let config = Config::load().unwrap();
+
+    assert!(config.database_path.ends_with(".vipune/memories.db"));
+    assert_eq!(config.embedding_model, "BAAI/bge-small-en-v1.5");
+    assert!(config.model_cache.ends_with(".vipune/models"));
+    assert_eq!(config.similarity_threshold, 0.85);
+}
+
+#[test]
+fn test_project_config_overrides_thresholds() {
+    let mut config = Config::default();
+    config.merge_from_project(ProjectConfig {
+        recency_weight: Some(0.0),
+        namespace: Some("backend".to_string()),
+        ..ProjectConfig::default()
+    });
+
+    assert_eq!(config.similarity_threshold, 0.85);
+    assert_eq!(config.recency_weight, 0.0);
+    assert_eq!(config.project.namespace.as_deref(), Some("backend"));
+    assert!(config.validate().is_ok());
+
+    config.merge_from_project(ProjectConfig {
+        similarity_threshold: Some(1.5),
+        ..ProjectConfig::default()
+    });
+    assert!(config.validate().is_err());
+}
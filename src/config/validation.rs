@@ -1,8 +1,11 @@
 //! Configuration validation logic.
 
 use crate::errors::Error;
+use crate::sqlite::Visibility;
 use std::path::PathBuf;
 
+use super::Config;
+
 /// Validates configuration values.
 pub struct ConfigValidator {
     /// Path to the SQLite database file.
@@ -13,6 +16,10 @@ pub struct ConfigValidator {
     pub similarity_threshold: f64,
     /// Recency weight for search ranking.
     pub recency_weight: f64,
+    /// Maximum number of memories per project.
+    pub max_memories_per_project: Option<usize>,
+    /// Maximum database size in megabytes.
+    pub max_total_db_size_mb: Option<u64>,
 }
 
 impl ConfigValidator {
@@ -23,6 +30,7 @@ impl ConfigValidator {
     /// - Recency weight is between 0.0 and 1.0
     /// - Embedding model is not empty
     /// - Database path is not empty
+    /// - Quota limits, when set, are greater than zero
     /// - No NaN or infinite values
     ///
     /// # Errors
@@ -33,6 +41,7 @@ impl ConfigValidator {
         self.validate_recency_weight()?;
         self.validate_embedding_model()?;
         self.validate_database_path()?;
+        self.validate_quotas()?;
 
         Ok(())
     }
@@ -86,6 +95,76 @@ impl ConfigValidator {
 
         Ok(())
     }
+
+    fn validate_quotas(&self) -> Result<(), Error> {
+        if self.max_memories_per_project == Some(0) {
+            return Err(Error::Config(
+                "Invalid max_memories_per_project: must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.max_total_db_size_mb == Some(0) {
+            return Err(Error::Config(
+                "Invalid max_total_db_size_mb: must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Validate configuration values.
+    pub(super) fn validate(&self) -> Result<(), Error> {
+        let validator = ConfigValidator {
+            database_path: self.database_path.clone(),
+            embedding_model: self.embedding_model.clone(),
+            similarity_threshold: self.similarity_threshold,
+            recency_weight: self.recency_weight,
+            max_memories_per_project: self.max_memories_per_project,
+            max_total_db_size_mb: self.max_total_db_size_mb,
+        };
+
+        validator.validate()?;
+        if self
+            .owner
+            .as_ref()
+            .is_some_and(|owner| owner.trim().is_empty())
+        {
+            return Err(Error::Config(
+                "Invalid owner: must not be empty".to_string(),
+            ));
+        }
+        if self.visibility == Visibility::Private && self.owner.is_none() {
+            return Err(Error::Config(
+                "Invalid visibility: private memories need an owner".to_string(),
+            ));
+        }
+        if self.trash_retention_days == Some(0) {
+            return Err(Error::Config(
+                "Invalid trash_retention_days: must be greater than 0".to_string(),
+            ));
+        }
+        if self.inactive_after_days == Some(0) {
+            return Err(Error::Config(
+                "Invalid inactive_after_days: must be greater than 0".to_string(),
+            ));
+        }
+        self.download.validate()?;
+        self.retention.validate()?;
+        self.review.validate()?;
+        self.conflicts.validate()?;
+        self.rerank.validate()?;
+        self.late_interaction.validate()?;
+        self.chunking.validate()?;
+        self.compression.validate()?;
+        self.limits.validate()?;
+        self.daemon.validate()?;
+        self.metadata_schema.validate()?;
+        self.filters.validate()?;
+        self.project.validate()?;
+        self.hooks.validate()
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +178,8 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: 1.5,
             recency_weight: 0.3,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
         };
 
         assert!(matches!(validator.validate(), Err(Error::Config(_))));
@@ -111,6 +192,8 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: 1.5,
             recency_weight: 0.3,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
         };
 
         assert!(matches!(validator.validate(), Err(Error::Config(_))));
@@ -123,6 +206,8 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: 0.0,
             recency_weight: 0.3,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
         };
         assert!(validator.validate().is_ok());
 
@@ -137,6 +222,8 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: f64::NAN,
             recency_weight: 0.3,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
         };
 
         assert!(matches!(validator.validate(), Err(Error::Config(_))));
@@ -149,6 +236,8 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: f64::INFINITY,
             recency_weight: 0.3,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
         };
 
         assert!(matches!(validator.validate(), Err(Error::Config(_))));
@@ -161,6 +250,8 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: 0.85,
             recency_weight: 1.5,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
         };
 
         assert!(matches!(validator.validate(), Err(Error::Config(_))));
@@ -173,6 +264,8 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: 0.85,
             recency_weight: 0.0,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
         };
         assert!(validator.validate().is_ok());
 
@@ -190,6 +283,8 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: 0.85,
             recency_weight: f64::NAN,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
         };
 
         assert!(matches!(validator.validate(), Err(Error::Config(_))));
@@ -202,8 +297,30 @@ mod tests {
             embedding_model: "test/model".to_string(),
             similarity_threshold: 0.85,
             recency_weight: f64::INFINITY,
+            max_memories_per_project: None,
+            max_total_db_size_mb: None,
+        };
+
+        assert!(matches!(validator.validate(), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_zero_quotas_rejected() {
+        let mut validator = ConfigValidator {
+            database_path: PathBuf::from("/test"),
+            embedding_model: "test/model".to_string(),
+            similarity_threshold: 0.85,
+            recency_weight: 0.3,
+            max_memories_per_project: Some(0),
+            max_total_db_size_mb: None,
         };
+        assert!(matches!(validator.validate(), Err(Error::Config(_))));
 
+        validator.max_memories_per_project = Some(10);
+        validator.max_total_db_size_mb = Some(0);
         assert!(matches!(validator.validate(), Err(Error::Config(_))));
+
+        validator.max_total_db_size_mb = Some(10);
+        assert!(validator.validate().is_ok());
     }
 }
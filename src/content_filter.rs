@@ -0,0 +1,204 @@
+//! Preprocessing of memory content before it is stored.
+//!
+//! A [`MemoryStore`](crate::MemoryStore) runs content through its filters,
+//! in order, on add and update (including imports and `watch`), before the
+//! content is validated, scanned for secrets and embedded. The `[filters]`
+//! config section selects built-in filters; library users can append their
+//! own with [`MemoryStore::add_content_filter`](crate::MemoryStore::add_content_filter).
+
+use std::sync::{Arc, LazyLock};
+
+use regex::Regex;
+
+use crate::config::{FilterKind, FiltersConfig};
+use crate::errors::Error;
+
+/// A transformation applied to content before it is stored.
+///
+/// Filters should be idempotent: content that has already been stored may
+/// pass through the pipeline again when it is updated or re-imported.
+pub trait ContentFilter: Send + Sync {
+    /// Short name used in log messages.
+    fn name(&self) -> &str;
+
+    /// Return the filtered content.
+    ///
+    /// # Errors
+    ///
+    /// An error rejects the add or update it occurred in.
+    fn apply(&self, content: &str) -> Result<String, Error>;
+}
+
+/// Trims the content, strips trailing whitespace from each line, collapses
+/// runs of spaces and tabs after a line's indentation into one space, and
+/// collapses consecutive blank lines into one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeWhitespace;
+
+impl ContentFilter for NormalizeWhitespace {
+    fn name(&self) -> &str {
+        "normalize_whitespace"
+    }
+
+    fn apply(&self, content: &str) -> Result<String, Error> {
+        let mut out = String::with_capacity(content.len());
+        let mut blank_run = false;
+        for line in content.trim().lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank_run = true;
+                continue;
+            }
+            if !out.is_empty() {
+                out.push_str(if blank_run { "\n\n" } else { "\n" });
+            }
+            blank_run = false;
+
+            let body = line.trim_start();
+            out.push_str(&line[..line.len() - body.len()]);
+            let mut words = body.split([' ', '\t']).filter(|word| !word.is_empty());
+            if let Some(first) = words.next() {
+                out.push_str(first);
+            }
+            for word in words {
+                out.push(' ');
+                out.push_str(word);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Email addresses.
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b")
+        .expect("valid email pattern")
+});
+
+/// Phone numbers with a `+` country code, a parenthesized area code, or in
+/// the `555-123-4567` form. Dates and version numbers don't match.
+static PHONE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:\+\d{1,3}[ .-]?(?:\(\d{1,4}\)[ .-]?)?\d{1,4}(?:[ .-]?\d{2,4}){2,4}|\(\d{3}\) ?\d{3}[ .-]\d{4}|\b\d{3}[.-]\d{3}[.-]\d{4})\b",
+    )
+    .expect("valid phone pattern")
+});
+
+/// Replaces email addresses with `[email]`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RedactEmails;
+
+impl ContentFilter for RedactEmails {
+    fn name(&self) -> &str {
+        "redact_emails"
+    }
+
+    fn apply(&self, content: &str) -> Result<String, Error> {
+        Ok(EMAIL.replace_all(content, "[email]").into_owned())
+    }
+}
+
+/// Replaces phone numbers with `[phone]`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RedactPhones;
+
+impl ContentFilter for RedactPhones {
+    fn name(&self) -> &str {
+        "redact_phones"
+    }
+
+    fn apply(&self, content: &str) -> Result<String, Error> {
+        Ok(PHONE.replace_all(content, "[phone]").into_owned())
+    }
+}
+
+/// Truncates content to at most this many bytes, at a character boundary.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxLength(pub usize);
+
+impl ContentFilter for MaxLength {
+    fn name(&self) -> &str {
+        "max_length"
+    }
+
+    fn apply(&self, content: &str) -> Result<String, Error> {
+        if content.len() <= self.0 {
+            return Ok(content.to_string());
+        }
+        let mut end = self.0;
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        Ok(content[..end].to_string())
+    }
+}
+
+/// The built-in filters selected by `config.pipeline`, in order.
+pub(crate) fn from_config(config: &FiltersConfig) -> Vec<Arc<dyn ContentFilter>> {
+    config
+        .pipeline
+        .iter()
+        .map(|kind| -> Arc<dyn ContentFilter> {
+            match kind {
+                FilterKind::NormalizeWhitespace => Arc::new(NormalizeWhitespace),
+                FilterKind::RedactEmails => Arc::new(RedactEmails),
+                FilterKind::RedactPhones => Arc::new(RedactPhones),
+                FilterKind::MaxLength => Arc::new(MaxLength(config.max_length)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_whitespace() {
+        let filter = NormalizeWhitespace;
+        let input = "  \n  fn main()  {\r\n\tlet  x =\t1;   \n\n\n\n}\n  ";
+        let output = filter.apply(input).unwrap();
+        assert_eq!(output, "fn main() {\n\tlet x = 1;\n\n}");
+        assert_eq!(filter.apply(&output).unwrap(), output);
+    }
+
+    #[test]
+    fn test_redact_emails() {
+        let output = RedactEmails
+            .apply("Mail alice.smith+work@example.co.uk or bob@corp.io, not @handle")
+            .unwrap();
+        assert_eq!(output, "Mail [email] or [email], not @handle");
+    }
+
+    #[test]
+    fn test_redact_phones() {
+        let output = RedactPhones
+            .apply("Call +1 555-123-4567, (555) 123-4567, 555.123.4567 or +44 20 7946 0958")
+            .unwrap();
+        assert_eq!(output, "Call [phone], [phone], [phone] or [phone]");
+
+        let kept = "Released 2024-01-15 as v1.20.3, ticket 12345";
+        assert_eq!(RedactPhones.apply(kept).unwrap(), kept);
+    }
+
+    #[test]
+    fn test_max_length_truncates_at_char_boundary() {
+        assert_eq!(MaxLength(5).apply("hello world").unwrap(), "hello");
+        assert_eq!(MaxLength(20).apply("short").unwrap(), "short");
+        // 'é' is two bytes; cutting inside it backs off to the boundary
+        assert_eq!(MaxLength(2).apply("aé").unwrap(), "a");
+    }
+
+    #[test]
+    fn test_from_config_keeps_order() {
+        let config = FiltersConfig {
+            pipeline: vec![FilterKind::MaxLength, FilterKind::RedactEmails],
+            max_length: 30,
+        };
+        let names: Vec<_> = from_config(&config)
+            .iter()
+            .map(|f| f.name().to_string())
+            .collect();
+        assert_eq!(names, ["max_length", "redact_emails"]);
+    }
+}
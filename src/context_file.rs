@@ -0,0 +1,176 @@
+//! Render pinned memories into agent context files such as CLAUDE.md or
+//! AGENTS.md, for `vipune export-context`.
+//!
+//! The memories are written as a markdown section between HTML comment
+//! markers naming the project:
+//!
+//! ```markdown
+//! <!-- vipune:begin project=owner/repo -->
+//! ## Project memory
+//!
+//! - **decision**: Use PostgreSQL for persistence <!-- vipune:id=... -->
+//! <!-- vipune:end project=owner/repo -->
+//! ```
+//!
+//! Re-running the export replaces only the text between the markers, so the
+//! rest of the file is left alone and an unchanged project leaves the file
+//! byte-for-byte identical. Each memory line ends with its ID, so it can be
+//! looked up with `vipune get`.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::errors::Error;
+use crate::sqlite::Memory;
+
+/// Markdown section holding `memories` of `project_id`, markers included,
+/// ending with a newline.
+pub fn render_section(project_id: &str, memories: &[Memory]) -> String {
+    let mut section = format!("{}\n## Project memory\n\n", begin_marker(project_id));
+    if memories.is_empty() {
+        section.push_str("_No pinned memories._\n");
+    }
+    for memory in memories {
+        let content = memory.content.trim().replace('\n', "\n  ");
+        section.push_str(&format!(
+            "- **{}**: {} <!-- vipune:id={} -->\n",
+            memory.kind, content, memory.id
+        ));
+    }
+    section.push_str(&end_marker(project_id));
+    section.push('\n');
+    section
+}
+
+/// `existing` with the project's section replaced by `section`, or with
+/// `section` appended if the file has none yet.
+pub fn splice_section(existing: &str, project_id: &str, section: &str) -> String {
+    let begin = begin_marker(project_id);
+    let end = end_marker(project_id);
+    if let Some(start) = existing.find(&begin) {
+        if let Some(offset) = existing[start..].find(&end) {
+            let mut stop = start + offset + end.len();
+            // The section brings its own trailing newline
+            if existing[stop..].starts_with('\n') {
+                stop += 1;
+            }
+            return format!("{}{}{}", &existing[..start], section, &existing[stop..]);
+        }
+    }
+
+    let separator = if existing.is_empty() || existing.ends_with("\n\n") {
+        ""
+    } else if existing.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{}{}{}", existing, separator, section)
+}
+
+/// Write `section` into the file at `path`, creating it if needed.
+///
+/// Returns `false` if the file already held exactly this section, in which
+/// case it is not rewritten.
+///
+/// # Errors
+///
+/// Returns error if the file cannot be read or written.
+pub fn write_section(path: &Path, project_id: &str, section: &str) -> Result<bool, Error> {
+    let existing = match fs::read_to_string(path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let updated = splice_section(&existing, project_id, section);
+    if updated == existing {
+        return Ok(false);
+    }
+    fs::write(path, updated)?;
+    Ok(true)
+}
+
+fn begin_marker(project_id: &str) -> String {
+    format!("<!-- vipune:begin project={} -->", project_id)
+}
+
+fn end_marker(project_id: &str) -> String {
+    format!("<!-- vipune:end project={} -->", project_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::{MemoryKind, Origin};
+
+    fn memory(id: &str, content: &str, kind: MemoryKind) -> Memory {
+        Memory {
+            id: id.to_string(),
+            project_id: "owner/repo".to_string(),
+            content: content.to_string(),
+            metadata: Some(r#"{"pinned":true}"#.to_string()),
+            kind,
+            origin: Origin::default(),
+            title: None,
+            similarity: None,
+            snippet: None,
+            explanation: None,
+            created_at: "2026-01-05T09:00:00+00:00".to_string(),
+            updated_at: "2026-01-05T09:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_section() {
+        let section = render_section(
+            "owner/repo",
+            &[
+                memory("a", "Use PostgreSQL", MemoryKind::Decision),
+                memory("b", "Line one\nLine two\n", MemoryKind::Fact),
+            ],
+        );
+        assert_eq!(
+            section,
+            "<!-- vipune:begin project=owner/repo -->\n\
+             ## Project memory\n\n\
+             - **decision**: Use PostgreSQL <!-- vipune:id=a -->\n\
+             - **fact**: Line one\n  Line two <!-- vipune:id=b -->\n\
+             <!-- vipune:end project=owner/repo -->\n"
+        );
+        assert!(render_section("p", &[]).contains("_No pinned memories._\n"));
+    }
+
+    #[test]
+    fn test_splice_appends_then_replaces_in_place() {
+        let first = render_section("owner/repo", &[memory("a", "old", MemoryKind::Fact)]);
+        let second = render_section("owner/repo", &[memory("a", "new", MemoryKind::Fact)]);
+
+        let file = splice_section("# Notes\nKeep this.", "owner/repo", &first);
+        assert_eq!(file, format!("# Notes\nKeep this.\n\n{}", first));
+
+        let file = format!("{}\n## After\n", file);
+        let updated = splice_section(&file, "owner/repo", &second);
+        assert_eq!(
+            updated,
+            format!("# Notes\nKeep this.\n\n{}\n## After\n", second)
+        );
+        assert_eq!(splice_section(&updated, "owner/repo", &second), updated);
+
+        // Another project's section is added alongside, not replaced
+        let other = render_section("other", &[]);
+        let both = splice_section(&updated, "other", &other);
+        assert!(both.contains(&second) && both.ends_with(&other));
+    }
+
+    #[test]
+    fn test_write_section_is_idempotent() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("CLAUDE.md");
+        let section = render_section("owner/repo", &[memory("a", "x", MemoryKind::Fact)]);
+
+        assert!(write_section(&path, "owner/repo", &section).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), section);
+        assert!(!write_section(&path, "owner/repo", &section).unwrap());
+    }
+}
@@ -0,0 +1,469 @@
+//! Import chat history from ChatGPT and Claude conversation exports.
+//!
+//! Both services export a `conversations.json` file holding every
+//! conversation. Each message of the chosen roles (user and assistant by
+//! default) becomes one memory, with metadata naming its conversation and
+//! role:
+//!
+//! ```json
+//! {"conversation_id": "6f1c...", "conversation": "Deploy checklist", "role": "assistant"}
+//! ```
+//!
+//! Messages keep their original timestamps and are identified by their
+//! message ID, so `--resume` works. Long messages are chunked like any other
+//! long memory. Only the text of a message is kept; images, attachments and
+//! tool payloads are dropped. For ChatGPT exports, only the branch of each
+//! conversation that was last shown is read, not abandoned regenerations.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::DateTime;
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::errors::Error;
+use crate::import::{ImportStats, JsonMemory, import_records};
+use crate::import_options::ImportOptions;
+use crate::memory::MemoryStore;
+
+/// File name of the conversations in an unpacked export.
+const EXPORT_FILE: &str = "conversations.json";
+
+/// Service a conversation export comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversationFormat {
+    /// ChatGPT data export (`conversations.json` with a message tree per
+    /// conversation).
+    ChatGpt,
+    /// Claude data export (`conversations.json` with a message list per
+    /// conversation).
+    Claude,
+}
+
+/// Author role of a chat message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChatRole {
+    /// Messages written by the person (`human` in Claude exports).
+    User,
+    /// Replies of the model.
+    Assistant,
+    /// System prompts and custom instructions.
+    System,
+    /// Tool and plugin output.
+    Tool,
+}
+
+impl ChatRole {
+    /// Roles imported unless others are chosen.
+    pub const DEFAULT: [ChatRole; 2] = [ChatRole::User, ChatRole::Assistant];
+
+    fn parse(role: &str) -> Option<Self> {
+        match role {
+            "user" | "human" => Some(ChatRole::User),
+            "assistant" => Some(ChatRole::Assistant),
+            "system" => Some(ChatRole::System),
+            "tool" => Some(ChatRole::Tool),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+            ChatRole::System => "system",
+            ChatRole::Tool => "tool",
+        }
+    }
+}
+
+/// A chat message, ready to be stored as a memory.
+#[derive(Debug, PartialEq)]
+pub struct ChatMessage {
+    /// ID of the conversation the message belongs to.
+    pub conversation_id: String,
+    /// Title of the conversation, if it has one.
+    pub conversation: Option<String>,
+    /// Message ID, if the export has one.
+    pub id: Option<String>,
+    /// Who wrote the message.
+    pub role: ChatRole,
+    /// When the message was sent, as RFC3339.
+    pub created_at: Option<String>,
+    /// Message text.
+    pub content: String,
+}
+
+/// Import the messages of `roles` from the conversation export at `path`.
+///
+/// `on_progress` is called after each message as for
+/// [`crate::import::import_from_json`].
+///
+/// # Errors
+///
+/// Returns error if the export cannot be read or parsed, or if embedding or
+/// database operations fail.
+pub fn import_from_conversations(
+    store: &mut MemoryStore,
+    path: &Path,
+    format: ConversationFormat,
+    roles: &[ChatRole],
+    default_project: &str,
+    options: &ImportOptions,
+    on_progress: &mut dyn FnMut(usize, &ImportStats),
+) -> Result<ImportStats, Error> {
+    let records = read_conversations(path, format, roles)?
+        .into_iter()
+        .map(|message| {
+            let mut metadata = serde_json::json!({
+                "conversation_id": message.conversation_id,
+                "role": message.role.as_str(),
+            });
+            if let Some(title) = message.conversation {
+                metadata["conversation"] = serde_json::Value::String(title);
+            }
+            JsonMemory {
+                id: message.id.map(serde_json::Value::String),
+                content: message.content,
+                project_id: None,
+                user_id: None,
+                metadata: Some(metadata),
+                created_at: message.created_at,
+                kind: None,
+                embedding: None,
+                model_id: None,
+            }
+        })
+        .collect();
+    import_records(store, records, path, default_project, options, on_progress)
+}
+
+/// Read the messages of `roles` from the export at `path`, conversation by
+/// conversation in export order.
+///
+/// `path` is the `conversations.json` file or the unpacked export directory
+/// holding it. Messages without text are left out.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if `path` does not exist, an I/O error if
+/// it cannot be read, and a JSON error if it is not an export of `format`.
+pub fn read_conversations(
+    path: &Path,
+    format: ConversationFormat,
+    roles: &[ChatRole],
+) -> Result<Vec<ChatMessage>, Error> {
+    let file = export_file(path)?;
+    let raw = std::fs::read_to_string(&file)?;
+    let messages = match format {
+        ConversationFormat::ChatGpt => parse_chatgpt(&raw)?,
+        ConversationFormat::Claude => parse_claude(&raw)?,
+    };
+    let messages: Vec<ChatMessage> = messages
+        .into_iter()
+        .filter(|m| roles.contains(&m.role) && !m.content.trim().is_empty())
+        .collect();
+    log::debug!(
+        "Read {} messages from conversations in {}",
+        messages.len(),
+        file.display()
+    );
+    Ok(messages)
+}
+
+/// The conversations file of the export at `path`.
+fn export_file(path: &Path) -> Result<PathBuf, Error> {
+    let file = if path.is_dir() {
+        path.join(EXPORT_FILE)
+    } else {
+        path.to_path_buf()
+    };
+    if !file.is_file() {
+        return Err(Error::InvalidInput(format!(
+            "Conversation export not found: {}",
+            file.display()
+        )));
+    }
+    Ok(file)
+}
+
+#[derive(Deserialize)]
+struct GptConversation {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    conversation_id: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    current_node: Option<String>,
+    mapping: HashMap<String, GptNode>,
+}
+
+#[derive(Deserialize)]
+struct GptNode {
+    #[serde(default)]
+    parent: Option<String>,
+    #[serde(default)]
+    message: Option<GptMessage>,
+}
+
+#[derive(Deserialize)]
+struct GptMessage {
+    #[serde(default)]
+    id: Option<String>,
+    author: GptAuthor,
+    #[serde(default)]
+    create_time: Option<f64>,
+    #[serde(default)]
+    content: Option<GptContent>,
+}
+
+#[derive(Deserialize)]
+struct GptAuthor {
+    role: String,
+}
+
+#[derive(Deserialize)]
+struct GptContent {
+    #[serde(default)]
+    parts: Vec<serde_json::Value>,
+}
+
+/// Messages of a ChatGPT export, every role included.
+fn parse_chatgpt(raw: &str) -> Result<Vec<ChatMessage>, Error> {
+    let conversations: Vec<GptConversation> = serde_json::from_str(raw)?;
+    let mut messages = Vec::new();
+    for (index, conversation) in conversations.into_iter().enumerate() {
+        let conversation_id = conversation
+            .conversation_id
+            .clone()
+            .or_else(|| conversation.id.clone())
+            .unwrap_or_else(|| format!("conversation-{}", index + 1));
+        for message in chatgpt_thread(conversation.mapping, conversation.current_node.as_deref()) {
+            let Some(role) = ChatRole::parse(&message.author.role) else {
+                continue;
+            };
+            // Parts that are not strings are images and other attachments
+            let content = message
+                .content
+                .map(|c| {
+                    c.parts
+                        .iter()
+                        .filter_map(|part| part.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            messages.push(ChatMessage {
+                conversation_id: conversation_id.clone(),
+                conversation: conversation.title.clone(),
+                id: message.id,
+                role,
+                created_at: message
+                    .create_time
+                    .and_then(|t| DateTime::from_timestamp_millis((t * 1000.0) as i64))
+                    .map(|t| t.to_rfc3339()),
+                content: content.trim().to_string(),
+            });
+        }
+    }
+    Ok(messages)
+}
+
+/// Messages of a conversation tree in order: the branch ending at
+/// `current_node`, or without one every message by creation time.
+fn chatgpt_thread(
+    mut mapping: HashMap<String, GptNode>,
+    current_node: Option<&str>,
+) -> Vec<GptMessage> {
+    let mut thread = Vec::new();
+    if let Some(current) = current_node.filter(|node| mapping.contains_key(*node)) {
+        let mut next = Some(current.to_string());
+        while let Some(node) = next.and_then(|id| mapping.remove(&id)) {
+            thread.extend(node.message);
+            next = node.parent;
+        }
+        thread.reverse();
+    } else {
+        thread.extend(mapping.into_values().filter_map(|node| node.message));
+        thread.sort_by(|a, b| {
+            a.create_time
+                .unwrap_or(0.0)
+                .total_cmp(&b.create_time.unwrap_or(0.0))
+        });
+    }
+    thread
+}
+
+#[derive(Deserialize)]
+struct ClaudeConversation {
+    #[serde(default)]
+    uuid: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    chat_messages: Vec<ClaudeMessage>,
+}
+
+#[derive(Deserialize)]
+struct ClaudeMessage {
+    #[serde(default)]
+    uuid: Option<String>,
+    sender: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    content: Vec<ClaudeContent>,
+}
+
+#[derive(Deserialize)]
+struct ClaudeContent {
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/// Messages of a Claude export, every role included.
+fn parse_claude(raw: &str) -> Result<Vec<ChatMessage>, Error> {
+    let conversations: Vec<ClaudeConversation> = serde_json::from_str(raw)?;
+    let mut messages = Vec::new();
+    for (index, conversation) in conversations.into_iter().enumerate() {
+        let conversation_id = conversation
+            .uuid
+            .unwrap_or_else(|| format!("conversation-{}", index + 1));
+        let title = conversation.name.filter(|name| !name.trim().is_empty());
+        for message in conversation.chat_messages {
+            let Some(role) = ChatRole::parse(&message.sender) else {
+                continue;
+            };
+            // Newer exports split the message into content blocks
+            let blocks: Vec<&str> = message
+                .content
+                .iter()
+                .filter(|block| block.kind == "text")
+                .filter_map(|block| block.text.as_deref())
+                .collect();
+            let content = if blocks.is_empty() {
+                message.text
+            } else {
+                blocks.join("\n\n")
+            };
+            messages.push(ChatMessage {
+                conversation_id: conversation_id.clone(),
+                conversation: title.clone(),
+                id: message.uuid,
+                role,
+                created_at: message.created_at,
+                content: content.trim().to_string(),
+            });
+        }
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CHATGPT: &str = r#"[{
+        "title": "Deploy checklist",
+        "conversation_id": "c1",
+        "current_node": "n4",
+        "mapping": {
+            "n0": {"parent": null, "message": null},
+            "n1": {"parent": "n0", "message": {"id": "m1", "author": {"role": "system"}, "create_time": null, "content": {"content_type": "text", "parts": [""]}}},
+            "n2": {"parent": "n1", "message": {"id": "m2", "author": {"role": "user"}, "create_time": 1717243200.5, "content": {"content_type": "text", "parts": ["How do we deploy?"]}}},
+            "n3": {"parent": "n2", "message": {"id": "m3", "author": {"role": "assistant"}, "create_time": 1717243201, "content": {"content_type": "text", "parts": ["Abandoned answer"]}}},
+            "n4": {"parent": "n2", "message": {"id": "m4", "author": {"role": "assistant"}, "create_time": 1717243202, "content": {"content_type": "multimodal_text", "parts": [{"asset_pointer": "file-1"}, "Run the release workflow."]}}}
+        }
+    }]"#;
+
+    const CLAUDE: &str = r#"[{
+        "uuid": "c2",
+        "name": "",
+        "chat_messages": [
+            {"uuid": "m1", "sender": "human", "text": "Which database?", "created_at": "2024-06-01T12:00:00.000000Z", "content": []},
+            {"uuid": "m2", "sender": "assistant", "text": "ignored", "created_at": "2024-06-01T12:00:05Z", "content": [{"type": "text", "text": "SQLite."}, {"type": "tool_use", "name": "search"}, {"type": "text", "text": "It needs no server."}]}
+        ]
+    }]"#;
+
+    #[test]
+    fn test_chatgpt_reads_the_current_branch() {
+        let messages = parse_chatgpt(CHATGPT).unwrap();
+        let summary: Vec<(ChatRole, &str)> = messages
+            .iter()
+            .map(|m| (m.role, m.content.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChatRole::System, ""),
+                (ChatRole::User, "How do we deploy?"),
+                (ChatRole::Assistant, "Run the release workflow."),
+            ]
+        );
+        assert_eq!(messages[1].conversation_id, "c1");
+        assert_eq!(
+            messages[1].conversation.as_deref(),
+            Some("Deploy checklist")
+        );
+        assert_eq!(messages[1].id.as_deref(), Some("m2"));
+        assert_eq!(
+            messages[1].created_at.as_deref(),
+            Some("2024-06-01T12:00:00.500+00:00")
+        );
+    }
+
+    #[test]
+    fn test_chatgpt_without_current_node_orders_by_time() {
+        let raw = CHATGPT.replace(r#""current_node": "n4","#, "");
+        let ids: Vec<String> = parse_chatgpt(&raw)
+            .unwrap()
+            .into_iter()
+            .filter_map(|m| m.id)
+            .collect();
+        assert_eq!(ids, ["m1", "m2", "m3", "m4"]);
+    }
+
+    #[test]
+    fn test_claude_joins_text_blocks() {
+        let messages = parse_claude(CLAUDE).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, ChatRole::User);
+        assert_eq!(messages[0].content, "Which database?");
+        assert_eq!(messages[0].conversation, None);
+        assert_eq!(messages[1].content, "SQLite.\n\nIt needs no server.");
+        assert_eq!(messages[1].conversation_id, "c2");
+    }
+
+    #[test]
+    fn test_read_conversations_filters_roles() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(EXPORT_FILE), CHATGPT).unwrap();
+
+        let messages =
+            read_conversations(dir.path(), ConversationFormat::ChatGpt, &ChatRole::DEFAULT)
+                .unwrap();
+        assert_eq!(messages.len(), 2);
+        let users = read_conversations(
+            &dir.path().join(EXPORT_FILE),
+            ConversationFormat::ChatGpt,
+            &[ChatRole::User],
+        )
+        .unwrap();
+        assert_eq!(users.len(), 1);
+
+        assert!(matches!(
+            read_conversations(Path::new("/nonexistent"), ConversationFormat::Claude, &[]),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(
+            read_conversations(dir.path(), ConversationFormat::Claude, &ChatRole::DEFAULT).is_err()
+        );
+    }
+}
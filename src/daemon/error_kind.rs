@@ -0,0 +1,116 @@
+//! Store errors carried over the protocol, so clients get back the
+//! [`Error`] variant the daemon's store returned.
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+
+/// The variant of a store error, with the fields needed to rebuild it.
+///
+/// Errors wrapping library types (SQLite, I/O, ONNX, ...) have no kind and
+/// reach the client as `Error::Daemon` with their message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ErrorKind {
+    Config {
+        detail: String,
+    },
+    InvalidInput {
+        detail: String,
+    },
+    EmptyInput,
+    InputTooLong {
+        max_length: usize,
+        actual_length: usize,
+    },
+    NotFound {
+        detail: String,
+    },
+    QuotaExceeded {
+        detail: String,
+    },
+    Storage {
+        detail: String,
+    },
+    Hook {
+        detail: String,
+    },
+    PotentialSecret {
+        detail: String,
+    },
+    StaleStore {
+        seq: i64,
+        min_seq: i64,
+    },
+    InvalidEmbedding {
+        detail: String,
+    },
+    FtsDisabled,
+    Cancelled,
+    Timeout {
+        ms: u64,
+    },
+    Validation {
+        detail: String,
+    },
+}
+
+impl ErrorKind {
+    /// The kind of `error`, if clients can rebuild it.
+    pub fn of(error: &Error) -> Option<Self> {
+        Some(match error {
+            Error::Config(d) => ErrorKind::Config { detail: d.clone() },
+            Error::InvalidInput(d) => ErrorKind::InvalidInput { detail: d.clone() },
+            Error::EmptyInput => ErrorKind::EmptyInput,
+            Error::InputTooLong {
+                max_length,
+                actual_length,
+            } => ErrorKind::InputTooLong {
+                max_length: *max_length,
+                actual_length: *actual_length,
+            },
+            Error::NotFound(d) => ErrorKind::NotFound { detail: d.clone() },
+            Error::QuotaExceeded(d) => ErrorKind::QuotaExceeded { detail: d.clone() },
+            Error::Storage(d) => ErrorKind::Storage { detail: d.clone() },
+            Error::Hook(d) => ErrorKind::Hook { detail: d.clone() },
+            Error::PotentialSecret(d) => ErrorKind::PotentialSecret { detail: d.clone() },
+            Error::StaleStore { seq, min_seq } => ErrorKind::StaleStore {
+                seq: *seq,
+                min_seq: *min_seq,
+            },
+            Error::InvalidEmbedding(d) => ErrorKind::InvalidEmbedding { detail: d.clone() },
+            Error::FtsDisabled => ErrorKind::FtsDisabled,
+            Error::Cancelled => ErrorKind::Cancelled,
+            Error::Timeout(ms) => ErrorKind::Timeout { ms: *ms },
+            Error::Validation(d) => ErrorKind::Validation { detail: d.clone() },
+            _ => return None,
+        })
+    }
+
+    /// The error this kind was taken from.
+    pub fn into_error(self) -> Error {
+        match self {
+            ErrorKind::Config { detail } => Error::Config(detail),
+            ErrorKind::InvalidInput { detail } => Error::InvalidInput(detail),
+            ErrorKind::EmptyInput => Error::EmptyInput,
+            ErrorKind::InputTooLong {
+                max_length,
+                actual_length,
+            } => Error::InputTooLong {
+                max_length,
+                actual_length,
+            },
+            ErrorKind::NotFound { detail } => Error::NotFound(detail),
+            ErrorKind::QuotaExceeded { detail } => Error::QuotaExceeded(detail),
+            ErrorKind::Storage { detail } => Error::Storage(detail),
+            ErrorKind::Hook { detail } => Error::Hook(detail),
+            ErrorKind::PotentialSecret { detail } => Error::PotentialSecret(detail),
+            ErrorKind::StaleStore { seq, min_seq } => Error::StaleStore { seq, min_seq },
+            ErrorKind::InvalidEmbedding { detail } => Error::InvalidEmbedding(detail),
+            ErrorKind::FtsDisabled => Error::FtsDisabled,
+            ErrorKind::Cancelled => Error::Cancelled,
+            ErrorKind::Timeout { ms } => Error::Timeout(ms),
+            ErrorKind::Validation { detail } => Error::Validation(detail),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Answering protocol requests with the daemon's store.
+
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::memory_types::AddResult;
+use crate::sqlite::{MemoryKind, Origin, Visibility};
+
+use super::{DaemonRequest, DaemonResponse};
+
+/// Answer one request with `store`.
+///
+/// Store errors become [`DaemonResponse::Error`], so a failed request
+/// leaves the daemon serving.
+pub fn handle_request(store: &mut MemoryStore, request: DaemonRequest) -> DaemonResponse {
+    let result = match request {
+        DaemonRequest::Ping => Ok(DaemonResponse::Pong),
+        DaemonRequest::Add {
+            project_id,
+            content,
+            metadata,
+            kind,
+            title,
+            force,
+            origin,
+            owner,
+            visibility,
+        } => as_owner(store, owner, visibility, |store| {
+            add(
+                store,
+                &project_id,
+                &content,
+                metadata.as_deref(),
+                kind,
+                title.as_deref(),
+                force,
+                origin,
+            )
+        }),
+        DaemonRequest::Search {
+            project_id,
+            query,
+            options,
+            owner,
+        } => as_owner(store, owner, None, |store| {
+            store
+                .query(&project_id, &query, options)
+                .map(|memories| DaemonResponse::Results { memories })
+        }),
+        DaemonRequest::Get { id } => store.get(&id).map(|memory| DaemonResponse::Found {
+            memory: memory.map(Box::new),
+        }),
+        DaemonRequest::Update { id, content } => store
+            .update(&id, &content)
+            .map(|()| DaemonResponse::Updated),
+        DaemonRequest::Delete { id } => store
+            .delete(&id)
+            .map(|deleted| DaemonResponse::Deleted { deleted }),
+        DaemonRequest::List {
+            project_id,
+            limit,
+            owner,
+        } => as_owner(store, owner, None, |store| {
+            store
+                .list(&project_id, limit)
+                .map(|memories| DaemonResponse::Results { memories })
+        }),
+    };
+    result.unwrap_or_else(|e| DaemonResponse::failed(&e))
+}
+
+/// Run `f` with the store's owner and visibility replaced by those given,
+/// then restore the daemon's own.
+fn as_owner<T>(
+    store: &mut MemoryStore,
+    owner: Option<String>,
+    visibility: Option<Visibility>,
+    f: impl FnOnce(&mut MemoryStore) -> Result<T, Error>,
+) -> Result<T, Error> {
+    if owner.is_none() && visibility.is_none() {
+        return f(store);
+    }
+    let default_owner = store.owner().map(str::to_string);
+    let default_visibility = store.visibility();
+    store.set_owner(
+        owner.or_else(|| default_owner.clone()),
+        visibility.unwrap_or(default_visibility),
+    )?;
+    let result = f(store);
+    store.set_owner(default_owner, default_visibility)?;
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add(
+    store: &mut MemoryStore,
+    project_id: &str,
+    content: &str,
+    metadata: Option<&str>,
+    kind: MemoryKind,
+    title: Option<&str>,
+    force: bool,
+    origin: Option<Origin>,
+) -> Result<DaemonResponse, Error> {
+    if title.is_some_and(|title| title.trim().is_empty()) {
+        return Err(Error::InvalidInput("Title cannot be empty".to_string()));
+    }
+    let default_origin = store.origin().clone();
+    if let Some(origin) = origin {
+        store.set_origin(origin);
+    }
+    let result = store.add_with_kind(project_id, content, metadata, kind, force);
+    store.set_origin(default_origin);
+    Ok(match result? {
+        AddResult::Added { id } => {
+            if title.is_some() {
+                store.set_title(&id, title)?;
+            }
+            DaemonResponse::Added { id }
+        }
+        AddResult::Conflicts {
+            proposed,
+            conflicts,
+        } => DaemonResponse::Conflicts {
+            proposed,
+            conflicts,
+        },
+    })
+}
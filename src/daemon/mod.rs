@@ -0,0 +1,49 @@
+//! Long-running daemon answering memory requests over a unix socket.
+//!
+//! Every `vipune` invocation loads the embedding model before it can embed
+//! anything, which dominates the run time of an `add` or `search`. A daemon
+//! (`vipune daemon`) loads it once and keeps one store open, so clients
+//! (`vipune add --via-daemon`, or [`DaemonClient`] in other programs) only
+//! pay for the embedding and the query.
+//!
+//! The protocol is JSON Lines: a client writes one [`DaemonRequest`] per
+//! line and reads one [`DaemonResponse`] line back, any number of times per
+//! connection. The daemon answers one request at a time, in arrival order,
+//! so writes are serialized through its single database connection.
+//!
+//! Work waiting for that connection is bounded by the `[daemon]` config: a
+//! request beyond `max_in_flight`, or a connection beyond `max_connections`,
+//! is answered at once with [`DaemonResponse::Busy`] (the daemon's HTTP 429),
+//! so a runaway client loop cannot build an unbounded backlog.
+//!
+//! ```json
+//! {"op": "add", "project_id": "owner/repo", "content": "Alice works at Microsoft"}
+//! {"status": "added", "id": "3f2c..."}
+//! ```
+
+use std::path::Path;
+
+mod error_kind;
+mod handlers;
+mod protocol;
+#[cfg(all(test, unix))]
+mod tests;
+#[cfg(unix)]
+mod unix;
+
+#[allow(unused_imports)] // Dead code justified: library API, unused by the CLI
+pub use error_kind::ErrorKind;
+pub use handlers::handle_request;
+pub use protocol::{DaemonRequest, DaemonResponse};
+#[cfg(unix)]
+#[allow(unused_imports)] // Dead code justified: library API, unused by the CLI
+pub use unix::{
+    DaemonClient, MAX_REQUEST_BYTES, MAX_RESPONSE_BYTES, bind, serve, serve_connection,
+    serve_with_limits,
+};
+
+/// Default socket of the daemon serving the database at `database_path`:
+/// the database path with a `.sock` extension.
+pub fn socket_path(database_path: &Path) -> std::path::PathBuf {
+    database_path.with_extension("sock")
+}
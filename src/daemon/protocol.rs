@@ -0,0 +1,206 @@
+//! Request and response types of the daemon's JSON Lines protocol.
+
+use serde::{Deserialize, Serialize};
+
+use super::error_kind::ErrorKind;
+use crate::errors::Error;
+use crate::memory_types::{AddResult, ConflictMemory};
+use crate::search_options::SearchOptions;
+use crate::sqlite::{Memory, MemoryKind, Origin, Visibility};
+
+/// A request to the daemon, written as one line of JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    /// Check that the daemon is up; answered with [`DaemonResponse::Pong`].
+    Ping,
+    /// Add a memory as [`MemoryStore::add_with_kind`](crate::MemoryStore::add_with_kind) does.
+    Add {
+        project_id: String,
+        content: String,
+        /// JSON metadata string.
+        #[serde(default)]
+        metadata: Option<String>,
+        #[serde(default)]
+        kind: MemoryKind,
+        /// Title instead of the one derived from the content.
+        #[serde(default)]
+        title: Option<String>,
+        /// Store the memory even if similar ones exist.
+        #[serde(default)]
+        force: bool,
+        /// Origin recorded on the memory (default: the daemon's own).
+        #[serde(default)]
+        origin: Option<Origin>,
+        /// Owner recorded on the memory (default: the daemon's own).
+        #[serde(default)]
+        owner: Option<String>,
+        /// Visibility of the memory (default: the daemon's own).
+        #[serde(default)]
+        visibility: Option<Visibility>,
+    },
+    /// Search a project as [`MemoryStore::query`](crate::MemoryStore::query) does.
+    Search {
+        project_id: String,
+        query: String,
+        #[serde(default)]
+        options: SearchOptions,
+        /// Search as this owner (default: the daemon's own).
+        #[serde(default)]
+        owner: Option<String>,
+    },
+    /// Get a memory as [`MemoryStore::get`](crate::MemoryStore::get) does.
+    Get { id: String },
+    /// Replace a memory's content as
+    /// [`MemoryStore::update`](crate::MemoryStore::update) does.
+    Update { id: String, content: String },
+    /// Delete a memory as [`MemoryStore::delete`](crate::MemoryStore::delete) does.
+    Delete { id: String },
+    /// List a project as [`MemoryStore::list`](crate::MemoryStore::list) does.
+    List {
+        project_id: String,
+        limit: usize,
+        /// List as this owner (default: the daemon's own).
+        #[serde(default)]
+        owner: Option<String>,
+    },
+}
+
+/// The daemon's answer to a [`DaemonRequest`], written as one line of JSON.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DaemonResponse {
+    /// The daemon is up.
+    Pong,
+    /// The memory was added.
+    Added { id: String },
+    /// The memory was not added because similar memories exist.
+    Conflicts {
+        proposed: String,
+        conflicts: Vec<ConflictMemory>,
+    },
+    /// Search results, best first, or a listing.
+    Results { memories: Vec<Memory> },
+    /// The memory asked for, if it exists.
+    Found { memory: Option<Box<Memory>> },
+    /// The memory was updated.
+    Updated,
+    /// Whether the memory existed and was deleted.
+    Deleted { deleted: bool },
+    /// The request failed; `message` is the error the store returned, and
+    /// `error` its variant when the client can rebuild it.
+    Error {
+        message: String,
+        #[serde(default, flatten, skip_serializing_if = "Option::is_none")]
+        error: Option<ErrorKind>,
+    },
+    /// The request was refused because the daemon is at its `[daemon]`
+    /// limits; it can be retried after `retry_after_ms`.
+    Busy {
+        message: String,
+        retry_after_ms: u64,
+    },
+}
+
+impl DaemonResponse {
+    /// Answer for a request that failed with `error`.
+    pub fn failed(error: &Error) -> Self {
+        DaemonResponse::Error {
+            message: error.to_string(),
+            error: ErrorKind::of(error),
+        }
+    }
+
+    /// The outcome of an add request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::DaemonBusy` if the daemon refused the request, the
+    /// store's error if it failed, and `Error::Daemon` if it was not an add.
+    pub fn into_add_result(self) -> Result<AddResult, Error> {
+        match self {
+            DaemonResponse::Added { id } => Ok(AddResult::Added { id }),
+            DaemonResponse::Conflicts {
+                proposed,
+                conflicts,
+            } => Ok(AddResult::Conflicts {
+                proposed,
+                conflicts,
+            }),
+            other => Err(other.unexpected()),
+        }
+    }
+
+    /// The results of a search request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::DaemonBusy` if the daemon refused the request, the
+    /// store's error if it failed, and `Error::Daemon` if it was not a
+    /// search or list.
+    pub fn into_memories(self) -> Result<Vec<Memory>, Error> {
+        match self {
+            DaemonResponse::Results { memories } => Ok(memories),
+            other => Err(other.unexpected()),
+        }
+    }
+
+    /// The memory of a get request.
+    ///
+    /// # Errors
+    ///
+    /// As [`DaemonResponse::into_memories`], for a get.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn into_memory(self) -> Result<Option<Memory>, Error> {
+        match self {
+            DaemonResponse::Found { memory } => Ok(memory.map(|memory| *memory)),
+            other => Err(other.unexpected()),
+        }
+    }
+
+    /// The outcome of an update request.
+    ///
+    /// # Errors
+    ///
+    /// As [`DaemonResponse::into_memories`], for an update.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn into_updated(self) -> Result<(), Error> {
+        match self {
+            DaemonResponse::Updated => Ok(()),
+            other => Err(other.unexpected()),
+        }
+    }
+
+    /// Whether a delete request deleted the memory.
+    ///
+    /// # Errors
+    ///
+    /// As [`DaemonResponse::into_memories`], for a delete.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn into_deleted(self) -> Result<bool, Error> {
+        match self {
+            DaemonResponse::Deleted { deleted } => Ok(deleted),
+            other => Err(other.unexpected()),
+        }
+    }
+
+    fn unexpected(self) -> Error {
+        match self {
+            DaemonResponse::Error {
+                error: Some(error), ..
+            } => error.into_error(),
+            DaemonResponse::Error {
+                message,
+                error: None,
+            } => Error::Daemon(message),
+            DaemonResponse::Busy {
+                message,
+                retry_after_ms,
+            } => Error::DaemonBusy {
+                message,
+                retry_after_ms,
+            },
+            other => Error::Daemon(format!("unexpected response: {:?}", other)),
+        }
+    }
+}
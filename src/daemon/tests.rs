@@ -0,0 +1,241 @@
+//! Tests for the protocol and the unix socket server.
+
+use super::*;
+use crate::config::Config;
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::search_options::SearchOptions;
+use crate::sqlite::{MemoryKind, Origin};
+use std::os::unix::net::UnixStream;
+
+fn spawn_daemon() -> (DaemonClient, std::thread::JoinHandle<Result<(), Error>>) {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("vipune.sock");
+    let listener = bind(&path).unwrap();
+    let server = std::thread::spawn(move || {
+        let _dir = dir;
+        let mut store = MemoryStore::new_in_memory("model", Config::default())?;
+        let (stream, _) = listener.accept()?;
+        serve_connection(&mut store, stream)
+    });
+    let client = DaemonClient::connect(&path).unwrap();
+    (client, server)
+}
+
+#[test]
+fn test_protocol_round_trip() {
+    let request: DaemonRequest =
+        serde_json::from_str(r#"{"op": "add", "project_id": "p", "content": "x"}"#).unwrap();
+    assert_eq!(
+        request,
+        DaemonRequest::Add {
+            project_id: "p".to_string(),
+            content: "x".to_string(),
+            metadata: None,
+            kind: MemoryKind::Other,
+            title: None,
+            force: false,
+            origin: None,
+            owner: None,
+            visibility: None,
+        }
+    );
+    let search = DaemonRequest::Search {
+        project_id: "p".to_string(),
+        query: "q".to_string(),
+        options: SearchOptions::default()
+            .with_limit(3)
+            .with_tag("rust")
+            .with_origin(Origin::Mcp),
+        owner: Some("alice".to_string()),
+    };
+    let line = serde_json::to_string(&search).unwrap();
+    assert_eq!(
+        serde_json::from_str::<DaemonRequest>(&line).unwrap(),
+        search
+    );
+
+    let added = serde_json::to_string(&DaemonResponse::Added {
+        id: "1".to_string(),
+    })
+    .unwrap();
+    assert_eq!(added, r#"{"status":"added","id":"1"}"#);
+
+    let failed = serde_json::to_string(&DaemonResponse::failed(&Error::NotFound(
+        "memory not found".to_string(),
+    )))
+    .unwrap();
+    assert_eq!(
+        failed,
+        r#"{"status":"error","message":"Memory not found: memory not found","kind":"not_found","detail":"memory not found"}"#
+    );
+    let response: DaemonResponse = serde_json::from_str(&failed).unwrap();
+    assert!(matches!(response.into_memory(), Err(Error::NotFound(_))));
+    // Errors without a kind, e.g. from older daemons, stay daemon errors
+    let response: DaemonResponse =
+        serde_json::from_str(r#"{"status":"error","message":"boom"}"#).unwrap();
+    assert!(matches!(response.into_updated(), Err(Error::Daemon(m)) if m == "boom"));
+}
+
+#[test]
+fn test_daemon_answers_requests_and_reports_errors() {
+    let (mut client, server) = spawn_daemon();
+    assert!(matches!(
+        client.request(&DaemonRequest::Ping).unwrap(),
+        DaemonResponse::Pong
+    ));
+    // Store errors are answered, and the connection stays usable
+    let empty = client
+        .request(&DaemonRequest::Add {
+            project_id: "p".to_string(),
+            content: "  ".to_string(),
+            metadata: None,
+            kind: MemoryKind::Other,
+            title: None,
+            force: true,
+            origin: None,
+            owner: None,
+            visibility: None,
+        })
+        .unwrap();
+    // Store errors come back as the variant the store returned
+    assert!(matches!(empty.into_add_result(), Err(Error::EmptyInput)));
+    assert!(matches!(
+        client
+            .request(&DaemonRequest::Ping)
+            .unwrap()
+            .into_memories(),
+        Err(Error::Daemon(_))
+    ));
+    drop(client);
+    server.join().unwrap().unwrap();
+}
+
+#[test]
+fn test_daemon_refuses_work_over_its_limits() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("vipune.sock");
+    let listener = bind(&path).unwrap();
+    let limits = crate::config::DaemonConfig {
+        max_connections: 1,
+        ..Default::default()
+    };
+    let metrics_file = dir.path().join("vipune.prom");
+    let exporter = crate::metrics::MetricsExporter::new(&metrics_file);
+    // Serves until the test process exits
+    std::thread::spawn(move || {
+        let mut store = MemoryStore::new_in_memory("model", Config::default())?;
+        serve_with_limits(&mut store, &listener, &limits, Some(&exporter))
+    });
+
+    let mut first = DaemonClient::connect(&path).unwrap();
+    assert!(matches!(
+        first.request(&DaemonRequest::Ping).unwrap(),
+        DaemonResponse::Pong
+    ));
+    let search = DaemonRequest::Search {
+        project_id: "p".to_string(),
+        query: "  ".to_string(),
+        options: SearchOptions::default(),
+        owner: None,
+    };
+    assert!(matches!(
+        first.request(&search).unwrap(),
+        DaemonResponse::Error { .. }
+    ));
+
+    let mut second = DaemonClient::connect(&path).unwrap();
+    let refused = second.request(&search).unwrap();
+    assert!(matches!(
+        refused,
+        DaemonResponse::Busy {
+            retry_after_ms: 100,
+            ..
+        }
+    ));
+    assert!(matches!(
+        refused.into_memories(),
+        Err(Error::DaemonBusy { .. })
+    ));
+    // Published on start and after each answered request
+    let metrics = std::fs::read_to_string(&metrics_file).unwrap();
+    assert!(metrics.contains("vipune_searches_total"));
+}
+
+#[test]
+fn test_requests_over_max_in_flight_are_busy() {
+    let limits = crate::config::DaemonConfig {
+        max_in_flight: 1,
+        ..Default::default()
+    };
+    let (jobs, _queue) = std::sync::mpsc::sync_channel(1);
+    let in_flight = std::sync::atomic::AtomicUsize::new(1);
+    assert!(matches!(
+        unix::submit(DaemonRequest::Ping, &jobs, &in_flight, &limits),
+        DaemonResponse::Busy { .. }
+    ));
+    assert_eq!(in_flight.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_bind_replaces_stale_sockets_only() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("vipune.sock");
+    drop(bind(&path).unwrap());
+    // The socket file outlives the listener
+    assert!(path.exists());
+    let _listener = bind(&path).unwrap();
+    assert!(matches!(bind(&path), Err(Error::Daemon(_))));
+
+    // Only the user may connect, and no staging directory is left behind
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_long_lines_are_refused() {
+    let mut reader = std::io::Cursor::new(b"12345\n123456\n".to_vec());
+    let mut line = String::new();
+    assert_eq!(
+        unix::read_line_capped(&mut reader, &mut line, 5).unwrap(),
+        6
+    );
+    assert_eq!(line, "12345\n");
+    line.clear();
+    assert!(matches!(
+        unix::read_line_capped(&mut reader, &mut line, 5),
+        Err(Error::Daemon(_))
+    ));
+
+    let mut store = MemoryStore::new_in_memory("model", Config::default()).unwrap();
+    let (mut client, server) = UnixStream::pair().unwrap();
+    let mut reply = std::io::BufReader::new(client.try_clone().unwrap());
+    let writer = std::thread::spawn(move || {
+        // The daemon stops reading once the line is over the limit
+        let _ = std::io::Write::write_all(&mut client, &vec![b'x'; MAX_REQUEST_BYTES + 1]);
+    });
+    assert!(matches!(
+        serve_connection(&mut store, server),
+        Err(Error::Daemon(message)) if message.contains("exceeds")
+    ));
+    let mut line = String::new();
+    std::io::BufRead::read_line(&mut reply, &mut line).unwrap();
+    assert!(line.contains("exceeds"));
+    writer.join().unwrap();
+}
+
+#[test]
+fn test_invalid_request_closes_the_connection() {
+    let mut store = MemoryStore::new_in_memory("model", Config::default()).unwrap();
+    let (mut client, server) = UnixStream::pair().unwrap();
+    std::io::Write::write_all(&mut client, b"{\"op\": \"explode\"}\n").unwrap();
+    assert!(matches!(
+        serve_connection(&mut store, server),
+        Err(Error::Daemon(_))
+    ));
+    let mut line = String::new();
+    std::io::BufRead::read_line(&mut std::io::BufReader::new(client), &mut line).unwrap();
+    assert!(line.contains(r#""status":"error""#));
+}
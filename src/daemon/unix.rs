@@ -0,0 +1,371 @@
+//! Unix socket server accepting connections, and the matching client.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread;
+use std::time::Duration;
+
+use super::{DaemonRequest, DaemonResponse, handle_request};
+use crate::config::DaemonConfig;
+use crate::errors::Error;
+use crate::memory::MemoryStore;
+use crate::metrics::MetricsExporter;
+
+/// How long the daemon waits for the next request on an idle
+/// connection before closing it, so one stuck client cannot block the
+/// others.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest request line the daemon reads, with room for content at
+/// `INPUT_LENGTH_CAP` after JSON escaping; a longer one is answered with an
+/// error and its connection closed, so a client cannot exhaust memory.
+pub const MAX_REQUEST_BYTES: usize = 32 * 1024 * 1024;
+
+/// Longest response line a [`DaemonClient`] reads.
+pub const MAX_RESPONSE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Listen on `path`, readable and writable by the current user only.
+///
+/// The socket is bound inside a fresh directory only the user can enter,
+/// restricted to mode 0600 there and only then linked to `path`, so it is
+/// never reachable by other users with the default permissions. A socket
+/// file left behind by a daemon that is no longer running is replaced.
+///
+/// # Errors
+///
+/// Returns `Error::Daemon` if another daemon is listening on `path`, and
+/// an I/O error if the socket cannot be created.
+pub fn bind(path: &Path) -> Result<UnixListener, Error> {
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(Error::Daemon(format!(
+                "A daemon is already listening on {}",
+                path.display()
+            )));
+        }
+        log::info!("Removing stale daemon socket {}", path.display());
+        std::fs::remove_file(path)?;
+    }
+    let private = path.with_file_name(format!(".vipune-{}.d", std::process::id()));
+    std::fs::DirBuilder::new().mode(0o700).create(&private)?;
+    let staged = private.join("s");
+    let bound = UnixListener::bind(&staged).and_then(|listener| {
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        // Unlike a rename, linking fails if another daemon took `path`
+        std::fs::hard_link(&staged, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&private);
+    Ok(bound?)
+}
+
+/// Read one line of at most `max` bytes, newline excluded, into `line`,
+/// returning the number of bytes read (0 at the end of the stream).
+///
+/// # Errors
+///
+/// Returns `Error::Daemon` if the line is longer than `max` bytes, and an
+/// I/O error if the read fails.
+pub(super) fn read_line_capped(
+    reader: &mut impl BufRead,
+    line: &mut String,
+    max: usize,
+) -> Result<usize, Error> {
+    let read = reader.take(max as u64 + 1).read_line(line)?;
+    if read > max && !line.ends_with('\n') {
+        return Err(Error::Daemon(format!("line exceeds {} bytes", max)));
+    }
+    Ok(read)
+}
+
+/// A request admitted by a connection thread, for the thread holding
+/// the store to answer.
+pub(super) struct Job {
+    request: DaemonRequest,
+    reply: mpsc::Sender<DaemonResponse>,
+}
+
+/// Answer connections on `listener` with `store` and the default
+/// `[daemon]` limits until the process is stopped.
+///
+/// # Errors
+///
+/// Returns error if the connection thread panics.
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+pub fn serve(store: &mut MemoryStore, listener: &UnixListener) -> Result<(), Error> {
+    serve_with_limits(store, listener, &DaemonConfig::default(), None)
+}
+
+/// Answer connections on `listener` with `store` until the process is
+/// stopped.
+///
+/// Each connection is read on its own thread; requests are answered one
+/// at a time, in arrival order, on the calling thread. A request beyond
+/// `limits.max_in_flight`, or a connection beyond
+/// `limits.max_connections`, is answered with [`DaemonResponse::Busy`].
+/// Pings are answered without waiting for the store, so health checks
+/// work under load. A connection that fails (e.g. a client sending
+/// invalid JSON or going idle) is logged and closed, and so is a failed
+/// accept (e.g. out of file descriptors); the daemon keeps serving.
+///
+/// With `exporter` set, the store's metrics are published on start and
+/// after every request the store answers.
+///
+/// # Errors
+///
+/// Returns error if the connection thread panics.
+pub fn serve_with_limits(
+    store: &mut MemoryStore,
+    listener: &UnixListener,
+    limits: &DaemonConfig,
+    exporter: Option<&MetricsExporter>,
+) -> Result<(), Error> {
+    export_metrics(store, exporter);
+    let (jobs, queue) = mpsc::sync_channel::<Job>(limits.max_in_flight);
+    let in_flight = AtomicUsize::new(0);
+    let connections = AtomicUsize::new(0);
+    let (in_flight, connections) = (&in_flight, &connections);
+    thread::scope(|scope| {
+        let acceptor = scope.spawn(move || -> Result<(), Error> {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        log::warn!("Failed to accept a daemon connection: {}", e);
+                        // Errors such as EMFILE repeat until a connection closes
+                        thread::sleep(Duration::from_millis(limits.retry_after_ms));
+                        continue;
+                    }
+                };
+                if connections.fetch_add(1, Ordering::SeqCst) >= limits.max_connections {
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                    reject(stream, limits);
+                    continue;
+                }
+                let jobs = jobs.clone();
+                scope.spawn(move || {
+                    let result = answer_lines(stream, |request| match request {
+                        DaemonRequest::Ping => DaemonResponse::Pong,
+                        request => submit(request, &jobs, in_flight, limits),
+                    });
+                    if let Err(e) = result {
+                        log::warn!("Closed daemon connection: {}", e);
+                    }
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+            Ok(())
+        });
+        for job in queue {
+            // The client may have gone; its answer is dropped then
+            let _ = job.reply.send(handle_request(store, job.request));
+            export_metrics(store, exporter);
+        }
+        acceptor.join().unwrap_or_else(|_| {
+            Err(Error::Daemon(
+                "the connection thread of the daemon panicked".to_string(),
+            ))
+        })
+    })
+}
+
+/// Publish the store's metrics with `exporter`, if any; a failed write is
+/// logged so it never stops the daemon.
+fn export_metrics(store: &MemoryStore, exporter: Option<&MetricsExporter>) {
+    let Some(exporter) = exporter else {
+        return;
+    };
+    if let Err(e) = exporter.export(store.metrics()) {
+        log::warn!(
+            "Failed to write metrics to {}: {}",
+            exporter.path().display(),
+            e
+        );
+    }
+}
+
+/// Queue `request` for the store and wait for its answer, or answer
+/// [`DaemonResponse::Busy`] if `limits.max_in_flight` requests are
+/// already admitted.
+pub(super) fn submit(
+    request: DaemonRequest,
+    jobs: &SyncSender<Job>,
+    in_flight: &AtomicUsize,
+    limits: &DaemonConfig,
+) -> DaemonResponse {
+    if in_flight.fetch_add(1, Ordering::SeqCst) >= limits.max_in_flight {
+        in_flight.fetch_sub(1, Ordering::SeqCst);
+        return busy(
+            format!("{} requests are already in flight", limits.max_in_flight),
+            limits,
+        );
+    }
+    let (reply, answer) = mpsc::channel();
+    let response = match jobs.try_send(Job { request, reply }) {
+        Ok(()) => answer.recv().unwrap_or_else(|_| stopped()),
+        Err(TrySendError::Full(_)) => busy("the request queue is full".to_string(), limits),
+        Err(TrySendError::Disconnected(_)) => stopped(),
+    };
+    in_flight.fetch_sub(1, Ordering::SeqCst);
+    response
+}
+
+fn busy(message: String, limits: &DaemonConfig) -> DaemonResponse {
+    DaemonResponse::Busy {
+        message,
+        retry_after_ms: limits.retry_after_ms,
+    }
+}
+
+fn stopped() -> DaemonResponse {
+    DaemonResponse::Error {
+        message: "the daemon is stopping".to_string(),
+        error: None,
+    }
+}
+
+/// Answer a connection over `limits.max_connections` with
+/// [`DaemonResponse::Busy`] and close it, without reading its request.
+fn reject(mut stream: UnixStream, limits: &DaemonConfig) {
+    let response = busy(
+        format!("{} connections are already open", limits.max_connections),
+        limits,
+    );
+    if let Ok(line) = serde_json::to_string(&response) {
+        // The client may have gone; nothing to report to then
+        let _ = writeln!(stream, "{}", line);
+    }
+}
+
+/// Answer the requests of one connection until the client closes it.
+///
+/// # Errors
+///
+/// Returns `Error::Daemon` for a line that is not a request, and an I/O
+/// error if the connection fails or stays idle for 30 seconds.
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+pub fn serve_connection(store: &mut MemoryStore, stream: UnixStream) -> Result<(), Error> {
+    answer_lines(stream, |request| handle_request(store, request))
+}
+
+/// Answer each request line of `stream` with `answer` until the client
+/// closes it (see [`serve_connection`]).
+fn answer_lines(
+    stream: UnixStream,
+    mut answer: impl FnMut(DaemonRequest) -> DaemonResponse,
+) -> Result<(), Error> {
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match read_line_capped(&mut reader, &mut line, MAX_REQUEST_BYTES) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(Error::Daemon(_)) => {
+                // The rest of the line cannot be told from the next request
+                let message = format!("request exceeds {} bytes", MAX_REQUEST_BYTES);
+                let response = DaemonResponse::Error {
+                    message: message.clone(),
+                    error: None,
+                };
+                writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+                return Err(Error::Daemon(message));
+            }
+            Err(e) => return Err(e),
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, result) = match serde_json::from_str::<DaemonRequest>(&line) {
+            Ok(request) => (answer(request), Ok(())),
+            Err(e) => {
+                let message = format!("invalid request: {}", e);
+                (
+                    DaemonResponse::Error {
+                        message: message.clone(),
+                        error: None,
+                    },
+                    Err(Error::Daemon(message)),
+                )
+            }
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        result?;
+    }
+    Ok(())
+}
+
+/// Connection to a running daemon.
+///
+/// # Example
+///
+/// ```no_run
+/// use vipune::daemon::{DaemonClient, DaemonRequest, socket_path};
+/// use vipune::{Config, SearchOptions};
+///
+/// let config = Config::default();
+/// let mut client = DaemonClient::connect(&socket_path(&config.database_path))?;
+/// let memories = client
+///     .request(&DaemonRequest::Search {
+///         project_id: "owner/repo".to_string(),
+///         query: "where does alice work".to_string(),
+///         options: SearchOptions::default(),
+///         owner: None,
+///     })?
+///     .into_memories()?;
+/// # Ok::<(), vipune::Error>(())
+/// ```
+pub struct DaemonClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl DaemonClient {
+    /// Connect to the daemon listening on `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Daemon` if no daemon is listening there.
+    pub fn connect(path: &Path) -> Result<Self, Error> {
+        let writer = UnixStream::connect(path).map_err(|e| {
+            Error::Daemon(format!(
+                "No daemon listening on {} ({}); start one with `vipune daemon`",
+                path.display(),
+                e
+            ))
+        })?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self { reader, writer })
+    }
+
+    /// Send `request` and wait for the daemon's response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the connection fails, and `Error::Daemon`
+    /// if the daemon closes it without answering.
+    pub fn request(&mut self, request: &DaemonRequest) -> Result<DaemonResponse, Error> {
+        let sent = writeln!(self.writer, "{}", serde_json::to_string(request)?);
+        // A daemon at its connection limit answers before reading the
+        // request and closes, which can fail the write; read its answer
+        // anyway
+        let mut line = String::new();
+        let read = read_line_capped(&mut self.reader, &mut line, MAX_RESPONSE_BYTES);
+        if matches!(read, Ok(0) | Err(Error::Io(_))) {
+            sent?;
+        }
+        if read? == 0 {
+            return Err(Error::Daemon(
+                "daemon closed the connection without answering".to_string(),
+            ));
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+}
@@ -0,0 +1,217 @@
+//! Diffs between two versions of memory content.
+//!
+//! Used wherever two similar texts are shown together, so what differs
+//! stands out instead of having to be spotted by comparing both: word diffs
+//! for add and update conflicts and `update --diff`, line diffs for
+//! `vipune edit`, and both for `vipune history --diff`. Both use the longest
+//! common subsequence, so unchanged words or lines between edits are kept in
+//! place. Its table grows with the product of the changed spans, so spans
+//! past [`MAX_TABLE_CELLS`] are shown as a whole-text replace instead.
+
+/// Largest LCS table (changed old items times changed new items) computed;
+/// about 32 MB on 64-bit targets.
+pub const MAX_TABLE_CELLS: usize = 4_000_000;
+
+/// Role of a run of words or a line in a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// In both texts.
+    Equal,
+    /// Only in the old text.
+    Delete,
+    /// Only in the new text.
+    Insert,
+}
+
+/// A run of consecutive words with the same [`ChangeKind`], joined by
+/// single spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub text: String,
+}
+
+/// Word diff turning `old` into `new`. Words are split on whitespace, so a
+/// change in spacing alone is not a difference.
+pub fn words(old: &str, new: &str) -> Vec<Change> {
+    let old: Vec<&str> = old.split_whitespace().collect();
+    let new: Vec<&str> = new.split_whitespace().collect();
+    let mut changes: Vec<Change> = Vec::new();
+    for (kind, word) in edit_script(&old, &new) {
+        match changes.last_mut() {
+            Some(last) if last.kind == kind => {
+                last.text.push(' ');
+                last.text.push_str(word);
+            }
+            _ => changes.push(Change {
+                kind,
+                text: word.to_string(),
+            }),
+        }
+    }
+    changes
+}
+
+/// Whether a word diff has more than unchanged words.
+pub fn has_changes(changes: &[Change]) -> bool {
+    changes.iter().any(|c| c.kind != ChangeKind::Equal)
+}
+
+/// A word diff as one line in `git diff --word-diff` style: deleted words
+/// in `[-...-]` and inserted words in `{+...+}`.
+pub fn render_words(changes: &[Change]) -> String {
+    changes
+        .iter()
+        .map(|change| match change.kind {
+            ChangeKind::Equal => change.text.clone(),
+            ChangeKind::Delete => format!("[-{}-]", change.text),
+            ChangeKind::Insert => format!("{{+{}+}}", change.text),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Line diff of `old` and `new`, each line prefixed with `  `, `- ` or `+ `.
+pub fn lines(old: &str, new: &str) -> Vec<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    edit_script(&old, &new)
+        .into_iter()
+        .map(|(kind, line)| {
+            let prefix = match kind {
+                ChangeKind::Equal => ' ',
+                ChangeKind::Delete => '-',
+                ChangeKind::Insert => '+',
+            };
+            format!("{} {}", prefix, line)
+        })
+        .collect()
+}
+
+/// Shortest edit script turning `old` into `new`, with deletions before
+/// insertions where both replace the same items. If the spans between the
+/// common prefix and suffix exceed [`MAX_TABLE_CELLS`], all of the old span
+/// is deleted and all of the new one inserted.
+fn edit_script<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(ChangeKind, &'a str)> {
+    // The common prefix and suffix don't need the quadratic table
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut script: Vec<_> = old[..prefix]
+        .iter()
+        .map(|item| (ChangeKind::Equal, *item))
+        .collect();
+    let cells = (old_mid.len() + 1).saturating_mul(new_mid.len() + 1);
+    let (i, j) = if cells > MAX_TABLE_CELLS {
+        (0, 0)
+    } else {
+        lcs_script(old_mid, new_mid, &mut script)
+    };
+    script.extend(old_mid[i..].iter().map(|item| (ChangeKind::Delete, *item)));
+    script.extend(new_mid[j..].iter().map(|item| (ChangeKind::Insert, *item)));
+    script.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|item| (ChangeKind::Equal, *item)),
+    );
+    script
+}
+
+/// Append the LCS edit script of `old` and `new` to `script` until either
+/// runs out, returning how far into each it got.
+fn lcs_script<'a>(
+    old: &[&'a str],
+    new: &[&'a str],
+    script: &mut Vec<(ChangeKind, &'a str)>,
+) -> (usize, usize) {
+    // lcs[i * width + j] = length of the LCS of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lcs = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            script.push((ChangeKind::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            script.push((ChangeKind::Delete, old[i]));
+            i += 1;
+        } else {
+            script.push((ChangeKind::Insert, new[j]));
+            j += 1;
+        }
+    }
+    (i, j)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        let changes = words(
+            "Alice works at Microsoft on Azure",
+            "Alice now works at Google on  Azure",
+        );
+        assert_eq!(
+            render_words(&changes),
+            "Alice {+now+} works at [-Microsoft-] {+Google+} on Azure"
+        );
+        assert!(has_changes(&changes));
+
+        assert_eq!(render_words(&words("a b", "a b c d")), "a b {+c d+}");
+        assert_eq!(render_words(&words("a b c", "")), "[-a b c-]");
+        assert_eq!(render_words(&words("x y", "y z")), "[-x-] y {+z+}");
+
+        let same = words("same  text\n", "same text");
+        assert!(!has_changes(&same));
+        assert_eq!(render_words(&same), "same text");
+        assert!(words("", "").is_empty());
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(
+            lines("a\nb\nc", "a\nx\nc\nd"),
+            vec!["  a", "- b", "+ x", "  c", "+ d"]
+        );
+        assert_eq!(lines("", "new"), vec!["+ new"]);
+        assert_eq!(lines("same", "same"), vec!["  same"]);
+    }
+
+    #[test]
+    fn test_large_changes_are_replaced_whole() {
+        let old: String = (0..3000).map(|i| format!("a{} ", i)).collect();
+        let new: String = (0..3000).map(|i| format!("b{} ", i)).collect();
+        let changes = words(&format!("keep {} end", old), &format!("keep {} end", new));
+        let kinds: Vec<ChangeKind> = changes.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ChangeKind::Equal,
+                ChangeKind::Delete,
+                ChangeKind::Insert,
+                ChangeKind::Equal
+            ]
+        );
+        assert_eq!(changes[1].text, old.trim_end());
+    }
+}
@@ -0,0 +1,494 @@
+//! Synchronous ONNX embedding engine for text-to-vector conversion.
+//!
+//! Uses bge-small-en-v1.5 model (384 dimensions) with mean pooling and L2 normalization.
+//! Queries and documents get the model's instruction prefixes (see
+//! [`model_registry`](crate::model_registry)) via `embed_query` and `embed_document`.
+
+use std::path::Path;
+
+use ort::inputs;
+use ort::session::Session;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::run_options::RunOptions;
+use ort::value::Tensor;
+use tokenizers::Tokenizer;
+
+use crate::cancel::Interrupt;
+use crate::config::DownloadConfig;
+use crate::errors::Error;
+use crate::model_files::{self, ModelFiles};
+use crate::model_registry::{self, ModelPrefixes};
+use tokenizers::TruncationParams;
+
+/// Embedding dimensions for bge-small-en-v1.5 model.
+///
+/// All generated embeddings are 384-dimensional vectors.
+pub const EMBEDDING_DIMS: usize = 384;
+
+/// Maximum number of tokens (special tokens included) the model embeds.
+///
+/// Longer texts are truncated before embedding, so text past this point is
+/// invisible to semantic search (keyword search and chunking still cover it).
+pub const MAX_TOKENS: usize = 512;
+
+/// Counts model tokens without loading the ONNX model.
+///
+/// Unlike the tokenizer used for embedding, counts are not capped at
+/// [`MAX_TOKENS`], so they show how much of a text would be truncated.
+pub struct TokenCounter {
+    tokenizer: Tokenizer,
+}
+
+impl TokenCounter {
+    /// Load the tokenizer from a `tokenizer.json` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tokenizer` if the file cannot be read or parsed.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let mut tokenizer = Tokenizer::from_file(path)?;
+        tokenizer.with_padding(None).with_truncation(None)?;
+        Ok(Self { tokenizer })
+    }
+
+    /// Number of tokens `text` encodes to, including special tokens.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tokenizer` if encoding fails.
+    pub fn count(&self, text: &str) -> Result<usize, Error> {
+        Ok(self.tokenizer.encode(text, true)?.len())
+    }
+}
+
+/// ONNX embedding engine for synchronous text-to-vector conversion.
+///
+/// Uses the bge-small-en-v1.5 model to generate 384-dimensional embeddings
+/// with mean pooling and L2 normalization. All methods are synchronous,
+/// matching vipune's no-async policy.
+///
+/// # Mutability Requirements
+///
+/// The `embed()` method requires `&mut self` because ONNX internally mutates
+/// state for tensor allocations during inference.
+pub struct EmbeddingEngine {
+    session: Session,
+    /// Run options of every model run, terminated when `interrupt` fires.
+    run_options: RunOptions,
+    interrupt: Interrupt,
+    tokenizer: Tokenizer,
+    counter: TokenCounter,
+    requires_token_type_ids: bool,
+    prefixes: ModelPrefixes,
+    revision: Option<String>,
+}
+
+impl EmbeddingEngine {
+    /// Load model from cache or download on first use.
+    ///
+    /// # Sync API Choice
+    ///
+    /// Uses `hf_hub::api::sync::Api` with ureq feature for blocking I/O.
+    /// This approach is fully synchronous, matching vipune's no-async policy.
+    /// Files are cached locally in HF Hub cache, only downloaded once.
+    ///
+    /// Honors `HF_HUB_OFFLINE`: when set, behaves like `with_offline(model_id, true)`.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn new(model_id: &str) -> Result<Self, Error> {
+        Self::with_offline(model_id, false)
+    }
+
+    /// Load model, optionally forbidding network access.
+    ///
+    /// In offline mode (or when `HF_HUB_OFFLINE` is set) files are read from
+    /// the local cache only. Query and document prefixes are looked up for
+    /// `model_id` in the model registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ModelNotCached` in offline mode if the files have not
+    /// been downloaded (see `vipune model download`).
+    pub fn with_offline(model_id: &str, offline: bool) -> Result<Self, Error> {
+        Self::with_download(model_id, offline, &DownloadConfig::default())
+    }
+
+    /// Load model like [`EmbeddingEngine::with_offline`], retrying transient
+    /// download errors as `retry` specifies.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ModelNotCached` in offline mode if the files have not
+    /// been downloaded, or `Error::ModelDownload` if a download failed.
+    pub fn with_download(
+        model_id: &str,
+        offline: bool,
+        retry: &DownloadConfig,
+    ) -> Result<Self, Error> {
+        let files = if offline || model_files::offline_from_env() {
+            model_files::cached_model_files(model_id)?
+        } else {
+            model_files::download_model_with(model_id, retry)?
+        };
+        Ok(Self::from_files(&files)?.with_prefixes(model_registry::prefixes_for(model_id)))
+    }
+
+    /// Load model from already resolved local files.
+    ///
+    /// The engine uses no query or document prefixes; see
+    /// [`EmbeddingEngine::with_prefixes`].
+    pub fn from_files(files: &ModelFiles) -> Result<Self, Error> {
+        let ModelFiles {
+            model: model_path,
+            tokenizer: tokenizer_path,
+        } = files;
+
+        let mut tokenizer = Tokenizer::from_file(tokenizer_path)?;
+        tokenizer
+            .with_padding(None)
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_TOKENS,
+                ..Default::default()
+            }))?;
+
+        let counter = TokenCounter::from_file(tokenizer_path)?;
+
+        let session = Session::builder()?
+            .with_optimization_level(GraphOptimizationLevel::Level1)?
+            .commit_from_file(model_path)?;
+
+        // Check if model requires token_type_ids input
+        let requires_token_type_ids = session
+            .inputs()
+            .iter()
+            .any(|input| input.name() == "token_type_ids");
+
+        Ok(EmbeddingEngine {
+            session,
+            run_options: RunOptions::new()?,
+            interrupt: Interrupt::default(),
+            tokenizer,
+            counter,
+            requires_token_type_ids,
+            prefixes: ModelPrefixes::NONE,
+            revision: files.revision(),
+        })
+    }
+
+    /// Use `prefixes` in `embed_query` and `embed_document`.
+    #[must_use]
+    pub fn with_prefixes(mut self, prefixes: ModelPrefixes) -> Self {
+        self.prefixes = prefixes;
+        self
+    }
+
+    /// Prefixes applied to queries and documents.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn prefixes(&self) -> ModelPrefixes {
+        self.prefixes
+    }
+
+    /// Stop the following model runs once `interrupt` fires, failing them
+    /// with `Error::Cancelled` or `Error::Timeout`.
+    pub(crate) fn set_interrupt(&mut self, interrupt: Interrupt) {
+        self.interrupt = interrupt;
+    }
+
+    /// Hub commit hash of the loaded model files, if known (see
+    /// [`ModelFiles::revision`]).
+    pub fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
+    }
+
+    /// Embed a search query, with the model's query prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`EmbeddingEngine::embed`].
+    pub fn embed_query(&mut self, text: &str) -> Result<Vec<f32>, Error> {
+        self.embed_prefixed(self.prefixes.query, text)
+    }
+
+    /// Embed a memory (or chunk) for storage, with the model's document prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`EmbeddingEngine::embed`].
+    pub fn embed_document(&mut self, text: &str) -> Result<Vec<f32>, Error> {
+        self.embed_prefixed(self.prefixes.document, text)
+    }
+
+    fn embed_prefixed(&mut self, prefix: &str, text: &str) -> Result<Vec<f32>, Error> {
+        if prefix.is_empty() || text.is_empty() {
+            self.embed(text)
+        } else {
+            self.embed(&format!("{}{}", prefix, text))
+        }
+    }
+
+    /// Number of tokens `text` encodes to, including special tokens and
+    /// without any prefix.
+    ///
+    /// Not capped: a result above [`MAX_TOKENS`] means `embed` would
+    /// truncate the text.
+    pub fn count_tokens(&self, text: &str) -> Result<usize, Error> {
+        self.counter.count(text)
+    }
+
+    /// Generate embedding for a single text, without any prefix.
+    ///
+    /// Returns exactly 384-dimensional f32 vector, L2-normalized.
+    ///
+    /// # Empty Input Handling
+    ///
+    /// Empty strings return a zero vector. This provides graceful handling
+    /// without requiring error recovery from callers.
+    ///
+    /// # Token Truncation
+    ///
+    /// Texts exceeding [`MAX_TOKENS`] tokens are truncated by the tokenizer;
+    /// use [`EmbeddingEngine::count_tokens`] to detect this.
+    pub fn embed(&mut self, text: &str) -> Result<Vec<f32>, Error> {
+        let mut embeddings = self.embed_batch(&[text])?;
+        Ok(embeddings.pop().expect("one embedding per input text"))
+    }
+
+    /// Embed several search queries in one model run, with the model's
+    /// query prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`EmbeddingEngine::embed_batch`].
+    pub fn embed_queries(&mut self, texts: &[&str]) -> Result<Vec<Vec<f32>>, Error> {
+        let prefix = self.prefixes.query;
+        if prefix.is_empty() {
+            return self.embed_batch(texts);
+        }
+        let prefixed: Vec<String> = texts
+            .iter()
+            .map(|text| {
+                if text.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", prefix, text)
+                }
+            })
+            .collect();
+        let prefixed: Vec<&str> = prefixed.iter().map(String::as_str).collect();
+        self.embed_batch(&prefixed)
+    }
+
+    /// Generate embeddings for several texts in one model run, without any
+    /// prefix.
+    ///
+    /// Texts are padded to the longest one; padding is masked out of the mean
+    /// pooling, so each embedding matches what [`EmbeddingEngine::embed`]
+    /// returns for the same text (up to floating-point noise). Empty texts
+    /// get zero vectors, as with `embed`.
+    pub fn embed_batch(&mut self, texts: &[&str]) -> Result<Vec<Vec<f32>>, Error> {
+        let mut embeddings = vec![vec![0.0f32; EMBEDDING_DIMS]; texts.len()];
+        self.forward(texts, |index, tokens, mask| {
+            let mut pooled = vec![0.0f32; EMBEDDING_DIMS];
+
+            for (chunk, &mask_value) in tokens.chunks(EMBEDDING_DIMS).zip(mask) {
+                let mask_value = mask_value as f32;
+                for (dim, pooled_value) in pooled.iter_mut().enumerate() {
+                    *pooled_value += chunk[dim] * mask_value;
+                }
+            }
+
+            let mask_sum: f32 = mask.iter().map(|&m| m as f32).sum::<f32>().max(1e-9);
+            for value in pooled.iter_mut() {
+                *value /= mask_sum;
+            }
+
+            embeddings[index] = l2_normalize(&pooled);
+        })?;
+        Ok(embeddings)
+    }
+
+    /// Token embeddings of a search query, with the model's query prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`EmbeddingEngine::embed`].
+    #[cfg(feature = "late-interaction")]
+    pub fn embed_query_tokens(&mut self, text: &str) -> Result<Vec<Vec<f32>>, Error> {
+        self.embed_tokens_prefixed(self.prefixes.query, text)
+    }
+
+    /// Token embeddings of a memory, with the model's document prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`EmbeddingEngine::embed`].
+    #[cfg(feature = "late-interaction")]
+    pub fn embed_document_tokens(&mut self, text: &str) -> Result<Vec<Vec<f32>>, Error> {
+        self.embed_tokens_prefixed(self.prefixes.document, text)
+    }
+
+    #[cfg(feature = "late-interaction")]
+    fn embed_tokens_prefixed(&mut self, prefix: &str, text: &str) -> Result<Vec<Vec<f32>>, Error> {
+        if prefix.is_empty() || text.is_empty() {
+            self.token_embeddings(text)
+        } else {
+            self.token_embeddings(&format!("{}{}", prefix, text))
+        }
+    }
+
+    /// L2-normalized embedding of every token of `text` (special tokens
+    /// included) before pooling, without any prefix, for late-interaction
+    /// scoring. Empty for empty text; truncated like [`EmbeddingEngine::embed`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`EmbeddingEngine::embed`].
+    #[cfg(feature = "late-interaction")]
+    pub fn token_embeddings(&mut self, text: &str) -> Result<Vec<Vec<f32>>, Error> {
+        let mut embeddings = Vec::new();
+        self.forward(&[text], |_, tokens, mask| {
+            embeddings = tokens
+                .chunks(EMBEDDING_DIMS)
+                .zip(mask)
+                .filter(|(_, mask_value)| **mask_value != 0)
+                .map(|(token, _)| l2_normalize(token))
+                .collect();
+        })?;
+        Ok(embeddings)
+    }
+
+    /// Run the model over `texts` padded to the longest one, calling `each`
+    /// with the index of every non-empty text, its `seq_len × EMBEDDING_DIMS`
+    /// token states and its attention mask (0 for padding).
+    fn forward(
+        &mut self,
+        texts: &[&str],
+        mut each: impl FnMut(usize, &[f32], &[i64]),
+    ) -> Result<(), Error> {
+        let mut indices = Vec::with_capacity(texts.len());
+        let mut encodings = Vec::with_capacity(texts.len());
+        for (index, text) in texts.iter().enumerate() {
+            if text.is_empty() {
+                continue;
+            }
+            let encoding = self.tokenizer.encode(*text, true)?;
+            if encoding.get_ids().is_empty() {
+                continue;
+            }
+            indices.push(index);
+            encodings.push(encoding);
+        }
+        if encodings.is_empty() {
+            return Ok(());
+        }
+        self.interrupt.check()?;
+
+        let batch_size = encodings.len();
+        let seq_len = encodings.iter().map(|e| e.len()).max().unwrap_or(0);
+        let pad_id = i64::from(self.tokenizer.token_to_id("[PAD]").unwrap_or(0));
+
+        let mut input_ids_vec: Vec<i64> = Vec::with_capacity(batch_size * seq_len);
+        let mut attention_mask_vec: Vec<i64> = Vec::with_capacity(batch_size * seq_len);
+        for encoding in &encodings {
+            let padding = seq_len - encoding.len();
+            input_ids_vec.extend(encoding.get_ids().iter().map(|&id| id as i64));
+            input_ids_vec.extend(std::iter::repeat_n(pad_id, padding));
+            attention_mask_vec.extend(encoding.get_attention_mask().iter().map(|&m| m as i64));
+            attention_mask_vec.extend(std::iter::repeat_n(0, padding));
+        }
+        let attention_mask = attention_mask_vec.clone();
+
+        let input_ids_tensor = Tensor::from_array(([batch_size, seq_len], input_ids_vec))?;
+        let attention_mask_tensor =
+            Tensor::from_array(([batch_size, seq_len], attention_mask_vec))?;
+
+        // Only include token_type_ids if the model requires it
+        let token_type_ids_tensor = if self.requires_token_type_ids {
+            let token_type_ids_vec: Vec<i64> = vec![0i64; batch_size * seq_len]; // Single sentences, all zeros
+            Some(Tensor::from_array((
+                [batch_size, seq_len],
+                token_type_ids_vec,
+            ))?)
+        } else {
+            None
+        };
+        let session = &mut self.session;
+        let run_options = &self.run_options;
+        let outputs = self.interrupt.watch(
+            || {
+                if let Err(e) = run_options.terminate() {
+                    log::warn!("Failed to terminate embedding run: {}", e);
+                }
+            },
+            || match token_type_ids_tensor {
+                Some(token_type_ids_tensor) => session.run_with_options(
+                    inputs![
+                        "input_ids" => input_ids_tensor,
+                        "attention_mask" => attention_mask_tensor,
+                        "token_type_ids" => token_type_ids_tensor
+                    ],
+                    run_options,
+                ),
+                None => session.run_with_options(
+                    inputs![
+                        "input_ids" => input_ids_tensor,
+                        "attention_mask" => attention_mask_tensor
+                    ],
+                    run_options,
+                ),
+            },
+        );
+        // A terminated run fails, and so would the next ones until the flag
+        // is reset
+        if let Err(e) = self.interrupt.check() {
+            run_options.unterminate()?;
+            return Err(e);
+        }
+        let outputs = outputs?;
+
+        let last_hidden_state = outputs
+            .get("last_hidden_state")
+            .or_else(|| outputs.get("token_embeddings"))
+            .ok_or_else(|| {
+                Error::Inference(
+                    "Output tensor 'last_hidden_state' or 'token_embeddings' not found".to_string(),
+                )
+            })?
+            .try_extract_tensor::<f32>()?;
+
+        let (shape, data) = last_hidden_state;
+        if shape.len() != 3 {
+            return Err(Error::Inference(format!(
+                "Expected 3D output (batch, seq_len, hidden), got {:?}",
+                shape
+            )));
+        }
+
+        let output_batch = shape[0] as usize;
+        let output_seq_len = shape[1] as usize;
+        let hidden_dim = shape[2] as usize;
+
+        if output_batch != batch_size || output_seq_len != seq_len || hidden_dim != EMBEDDING_DIMS {
+            return Err(Error::Inference(format!(
+                "Unexpected output shape: {:?}, batch={}, seq_len={}, hidden=384 expected",
+                shape, batch_size, seq_len
+            )));
+        }
+
+        for (row, &index) in indices.iter().enumerate() {
+            let mask = &attention_mask[row * seq_len..(row + 1) * seq_len];
+            let tokens = &data[row * seq_len * hidden_dim..(row + 1) * seq_len * hidden_dim];
+            each(index, tokens, mask);
+        }
+        Ok(())
+    }
+}
+
+fn l2_normalize(vec: &[f32]) -> Vec<f32> {
+    let norm: f32 = vec.iter().map(|&x| x * x).sum::<f32>().sqrt();
+    let norm = norm.max(1e-9);
+
+    vec.iter().map(|&x| x / norm).collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,197 @@
+//! Tests for normalization, token counting and the embedding engine.
+
+use super::*;
+
+#[test]
+fn test_embedding_dimensions() {
+    assert_eq!(EMBEDDING_DIMS, 384);
+}
+
+#[test]
+fn test_l2_normalize_unit_vector() {
+    let vec = vec![1.0, 0.0, 0.0];
+    let normalized = l2_normalize(&vec);
+
+    let norm: f32 = normalized.iter().map(|&x| x * x).sum::<f32>().sqrt();
+    assert!((norm - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn test_l2_normalize_zero_vector() {
+    let vec = vec![0.0, 0.0, 0.0];
+    let normalized = l2_normalize(&vec);
+
+    assert_eq!(normalized, vec![0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_l2_normalize_magnitude() {
+    let vec = vec![3.0, 4.0];
+    let normalized = l2_normalize(&vec);
+
+    let norm: f32 = normalized.iter().map(|&x| x * x).sum::<f32>().sqrt();
+    assert!((norm - 1.0).abs() < 0.01);
+}
+
+#[ignore]
+#[test]
+fn test_integration_whitespace_only() {
+    let mut engine = EmbeddingEngine::new("BAAI/bge-small-en-v1.5").expect("load model");
+    let embedding = engine.embed("   \t\n  ").expect("embed whitespace text");
+
+    // Whitespace-only input should produce a valid embedding
+    assert_eq!(embedding.len(), 384);
+    assert!(embedding.iter().all(|&x| x.is_finite()));
+}
+
+#[ignore]
+#[test]
+fn test_integration_simple_text() {
+    let mut engine = EmbeddingEngine::new("BAAI/bge-small-en-v1.5").expect("load model");
+    let embedding = engine.embed("hello world").expect("embed text");
+
+    assert_eq!(embedding.len(), 384);
+
+    let norm: f32 = embedding.iter().map(|&x| x * x).sum::<f32>().sqrt();
+    assert!(
+        (norm - 1.0).abs() < 0.01,
+        "Embedding should be L2-normalized"
+    );
+
+    assert!(embedding.iter().all(|&x| x.is_finite()));
+}
+
+#[ignore]
+#[test]
+fn test_integration_empty_string() {
+    let mut engine = EmbeddingEngine::new("BAAI/bge-small-en-v1.5").expect("load model");
+    let embedding = engine.embed("").expect("embed empty text");
+
+    assert_eq!(embedding.len(), 384);
+    assert_eq!(embedding, vec![0.0f32; 384]);
+}
+
+#[ignore]
+#[test]
+fn test_integration_long_text_truncation() {
+    let mut engine = EmbeddingEngine::new("BAAI/bge-small-en-v1.5").expect("load model");
+
+    let long_text = "This is a sentence. ".repeat(100);
+    assert!(engine.count_tokens(&long_text).unwrap() > MAX_TOKENS);
+    let embedding = engine.embed(&long_text).expect("embed long text");
+
+    assert_eq!(embedding.len(), 384);
+
+    let norm: f32 = embedding.iter().map(|&x| x * x).sum::<f32>().sqrt();
+    assert!((norm - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn test_token_counter_ignores_file_truncation() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("tokenizer.json");
+    std::fs::write(
+        &path,
+        r#"{
+            "version": "1.0",
+            "truncation": {"direction": "Right", "max_length": 2, "strategy": "LongestFirst", "stride": 0},
+            "padding": null,
+            "added_tokens": [],
+            "normalizer": null,
+            "pre_tokenizer": {"type": "Whitespace"},
+            "post_processor": null,
+            "decoder": null,
+            "model": {"type": "WordLevel", "vocab": {"[UNK]": 0, "a": 1, "b": 2}, "unk_token": "[UNK]"}
+        }"#,
+    )
+    .unwrap();
+
+    let counter = TokenCounter::from_file(&path).unwrap();
+    assert_eq!(counter.count("a b a b c").unwrap(), 5);
+    assert_eq!(counter.count("").unwrap(), 0);
+    assert!(TokenCounter::from_file(&dir.path().join("missing.json")).is_err());
+}
+
+#[ignore]
+#[test]
+fn test_integration_embed_batch_matches_embed() {
+    let mut engine = EmbeddingEngine::new("BAAI/bge-small-en-v1.5").expect("load model");
+    let texts = [
+        "short",
+        "",
+        "a considerably longer sentence that needs padding less",
+    ];
+    let batch = engine.embed_batch(&texts).expect("embed batch");
+    assert_eq!(batch.len(), 3);
+    assert_eq!(batch[1], vec![0.0f32; EMBEDDING_DIMS]);
+    for (text, embedding) in texts.iter().zip(&batch) {
+        let single = engine.embed(text).expect("embed");
+        for (a, b) in single.iter().zip(embedding) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
+}
+
+/// Mean reciprocal rank of each query's relevant passage.
+fn mean_reciprocal_rank(passages: &[Vec<f32>], queries: &[(Vec<f32>, usize)]) -> f64 {
+    let dot = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    let total: f64 = queries
+        .iter()
+        .map(|(query, relevant)| {
+            let score = dot(query, &passages[*relevant]);
+            let rank = 1 + passages.iter().filter(|p| dot(query, p) > score).count();
+            1.0 / rank as f64
+        })
+        .sum();
+    total / queries.len() as f64
+}
+
+#[ignore]
+#[test]
+fn test_integration_query_prefix_ranking() {
+    let passages = [
+        "The deploy pipeline runs on GitHub Actions and publishes to Fly.io",
+        "Alice prefers tabs over spaces in Python code",
+        "We chose PostgreSQL over MySQL for its JSONB support",
+        "API tokens expire after 24 hours and are refreshed by the client",
+        "The staging database is reset every Sunday night",
+        "Bob is responsible for the mobile app release schedule",
+    ];
+    let queries = [
+        ("how do we ship to production", 0),
+        ("indentation style", 1),
+        ("why postgres", 2),
+        ("how long do auth tokens last", 3),
+        ("when is staging data wiped", 4),
+        ("who owns mobile releases", 5),
+    ];
+
+    let mut engine = EmbeddingEngine::new("BAAI/bge-small-en-v1.5").expect("load model");
+    assert!(!engine.prefixes().query.is_empty());
+    let passages: Vec<Vec<f32>> = passages
+        .iter()
+        .map(|p| engine.embed_document(p).expect("embed passage"))
+        .collect();
+    let mut embed_queries = |prefixed: bool| -> Vec<(Vec<f32>, usize)> {
+        queries
+            .iter()
+            .map(|(q, relevant)| {
+                let embedding = if prefixed {
+                    engine.embed_query(q)
+                } else {
+                    engine.embed(q)
+                };
+                (embedding.expect("embed query"), *relevant)
+            })
+            .collect()
+    };
+    let plain = embed_queries(false);
+    let prefixed = embed_queries(true);
+
+    let plain_mrr = mean_reciprocal_rank(&passages, &plain);
+    let prefixed_mrr = mean_reciprocal_rank(&passages, &prefixed);
+    assert!(
+        prefixed_mrr >= plain_mrr,
+        "MRR without query prefix: {plain_mrr:.3}, with: {prefixed_mrr:.3}"
+    );
+}
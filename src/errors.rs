@@ -68,6 +68,83 @@ pub enum Error {
     #[error("Database error")]
     SqliteModule(String),
 
+    /// Model files are missing from the local cache and downloads are disabled.
+    #[error(
+        "Model '{0}' is not in the local cache and offline mode is enabled (run `vipune model download --model {0}`)"
+    )]
+    ModelNotCached(String),
+
+    /// A model file could not be downloaded (after retrying transient
+    /// network errors); see `Error::ModelNotCached` for offline mode.
+    #[error("Failed to download {file} of model '{model}' after {attempts} attempt(s): {reason}")]
+    ModelDownload {
+        model: String,
+        file: String,
+        attempts: u32,
+        /// HTTP status of the last response, if the server answered.
+        status: Option<u16>,
+        /// URL of the last request, if known.
+        url: Option<String>,
+        /// Whether the last failure was a network error that was retried.
+        transient: bool,
+        reason: String,
+    },
+
+    /// Storage quota reached and eviction is disabled.
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// Failure reported by a non-SQLite storage backend.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    /// A mutation hook failed and `hooks.on_failure` is `fail`.
+    #[error("Hook failed: {0}")]
+    Hook(String),
+
+    /// A store archive is malformed, corrupted or incompatible.
+    #[error("Invalid archive: {0}")]
+    Archive(String),
+
+    /// Content matched a secret detector while `secret_scan` is enabled.
+    #[error("Content looks like a secret (rule: {0}); not stored")]
+    PotentialSecret(String),
+
+    /// The store has not yet seen a write with the required sequence number.
+    #[error("Store is at write sequence {seq}, older than the required {min_seq}")]
+    StaleStore { seq: i64, min_seq: i64 },
+
+    /// An embedding holds NaN or infinite values (e.g. from a corrupted
+    /// model cache) and was not stored.
+    #[error("Invalid embedding: {0}")]
+    InvalidEmbedding(String),
+
+    /// A `vipune daemon` could not be reached, or failed a request.
+    #[error("Daemon error: {0}")]
+    Daemon(String),
+
+    /// A `vipune daemon` refused a request because `[daemon]` limits were
+    /// reached; retrying after `retry_after_ms` may succeed (like HTTP 429).
+    #[error("Daemon busy: {message} (retry after {retry_after_ms} ms)")]
+    DaemonBusy {
+        message: String,
+        retry_after_ms: u64,
+    },
+
+    /// Keyword search on a store opened with `enable_fts = false`.
+    #[error("Full-text search is disabled (enable_fts = false)")]
+    FtsDisabled,
+
+    /// An operation was aborted through its `CancellationToken`.
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    /// An embedding run or search took longer than `limits.timeout_ms`
+    /// (the value in milliseconds).
+    #[error("Operation timed out after {0} ms")]
+    Timeout(u64),
+
     /// Validation error (for parameter validation).
     #[error("Validation error: {0}")]
     Validation(String),
@@ -75,6 +152,11 @@ pub enum Error {
 
 impl From<crate::sqlite::Error> for Error {
     fn from(err: crate::sqlite::Error) -> Self {
+        match err {
+            crate::sqlite::Error::FtsDisabled => return Error::FtsDisabled,
+            crate::sqlite::Error::InvalidEmbedding(msg) => return Error::InvalidEmbedding(msg),
+            _ => {}
+        }
         // Convert specific SQLite errors to NotFound when applicable
         // Sanitize: don't leak memory IDs in error messages to library consumers
         let err_str = err.to_string();
@@ -0,0 +1,202 @@
+//! Export memories as JSON Lines in the format read by `vipune import`.
+//!
+//! Each line holds one memory with its `id`, `project_id`, `content`, `kind`,
+//! `metadata` and timestamps and, unless disabled, its stored `embedding`
+//! (base64 of little-endian `f32`s) and the `model_id` that produced it. An
+//! import into a store using the same model reuses the embeddings instead of
+//! running the model again.
+
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::errors::Error;
+use crate::import::records::encode_embedding;
+use crate::memory::MemoryStore;
+use crate::sqlite::{Memory, MemoryKind};
+
+/// A single exported memory.
+#[derive(Debug, Serialize)]
+struct ExportRecord<'a> {
+    id: &'a str,
+    project_id: &'a str,
+    content: &'a str,
+    kind: MemoryKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<serde_json::Value>,
+    created_at: &'a str,
+    updated_at: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    embedding: Option<String>,
+}
+
+impl<'a> ExportRecord<'a> {
+    fn new(memory: &'a Memory, embedding: Option<(&'a str, &[f32])>) -> Self {
+        // JSON metadata is exported as a value; anything else stays a string,
+        // which the importer stores verbatim
+        let metadata = memory.metadata.as_deref().map(|raw| {
+            serde_json::from_str(raw).unwrap_or_else(|_| serde_json::Value::String(raw.to_string()))
+        });
+        Self {
+            id: &memory.id,
+            project_id: &memory.project_id,
+            content: &memory.content,
+            kind: memory.kind,
+            metadata,
+            created_at: &memory.created_at,
+            updated_at: &memory.updated_at,
+            model_id: embedding.map(|(model_id, _)| model_id),
+            embedding: embedding.map(|(_, values)| encode_embedding(values)),
+        }
+    }
+}
+
+/// Write every memory of `projects` to `out`, one JSON object per line.
+///
+/// With `include_embeddings`, records carry their embedding and the store's
+/// model ID. Returns the number of memories written.
+///
+/// # Errors
+///
+/// Returns error if a database query or a write to `out` fails.
+pub fn export_to_json(
+    store: &MemoryStore,
+    projects: &[String],
+    include_embeddings: bool,
+    out: &mut dyn Write,
+) -> Result<usize, Error> {
+    let mut exported = 0;
+    for project_id in projects {
+        for (memory, embedding) in store.db.export_project(project_id)? {
+            let embedding = include_embeddings.then_some((store.model_id.as_str(), &embedding[..]));
+            serde_json::to_writer(&mut *out, &ExportRecord::new(&memory, embedding))?;
+            out.write_all(b"\n")?;
+            exported += 1;
+        }
+    }
+    out.flush()?;
+    log::debug!(
+        "Exported {} memories from {} projects",
+        exported,
+        projects.len()
+    );
+    Ok(exported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::fixtures::axis;
+    use crate::import::records::decode_embedding;
+    use crate::import::{JsonMemory, import_from_json};
+    use crate::import_options::ImportOptions;
+
+    /// Unit vector along `dim`, so no two test memories look alike.
+    fn store_with_memories() -> MemoryStore {
+        let store =
+            MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+        store
+            .db
+            .insert_with_kind(
+                "proj",
+                "Alice works at Microsoft",
+                &axis(0),
+                Some(r#"{"topic":"people"}"#),
+                MemoryKind::Fact,
+            )
+            .unwrap();
+        store
+            .db
+            .insert("proj", "raw metadata", &axis(1), Some("not json"))
+            .unwrap();
+        store
+    }
+
+    fn export(store: &MemoryStore, include_embeddings: bool) -> Vec<JsonMemory> {
+        let mut out = Vec::new();
+        let count =
+            export_to_json(store, &["proj".to_string()], include_embeddings, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let records: Vec<JsonMemory> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), count);
+        records
+    }
+
+    #[test]
+    fn test_export_roundtrips_through_import_format() {
+        let store = store_with_memories();
+        let records = export(&store, true);
+        assert_eq!(records.len(), 2);
+
+        let fact = records
+            .iter()
+            .find(|r| r.content == "Alice works at Microsoft")
+            .unwrap();
+        assert_eq!(fact.project_id.as_deref(), Some("proj"));
+        assert_eq!(fact.memory_kind(), MemoryKind::Fact);
+        assert_eq!(fact.metadata, Some(serde_json::json!({"topic": "people"})));
+        assert_eq!(fact.model_id.as_deref(), Some(store.model_id.as_str()));
+        assert_eq!(
+            decode_embedding(fact.embedding.as_deref().unwrap()).unwrap(),
+            axis(0)
+        );
+
+        let raw = records
+            .iter()
+            .find(|r| r.content == "raw metadata")
+            .unwrap();
+        assert_eq!(raw.metadata, Some(serde_json::json!("not json")));
+    }
+
+    #[test]
+    fn test_export_without_embeddings() {
+        let records = export(&store_with_memories(), false);
+        assert_eq!(records.len(), 2);
+        assert!(
+            records
+                .iter()
+                .all(|r| r.embedding.is_none() && r.model_id.is_none())
+        );
+    }
+
+    #[test]
+    fn test_import_of_export_reuses_embeddings() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("export.jsonl");
+        let mut out = std::fs::File::create(&path).unwrap();
+        export_to_json(
+            &store_with_memories(),
+            &["proj".to_string()],
+            true,
+            &mut out,
+        )
+        .unwrap();
+
+        // The target store cannot embed, so the import must reuse every embedding
+        let mut target =
+            MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+        let stats = import_from_json(
+            &mut target,
+            &path,
+            "default",
+            &ImportOptions::default(),
+            &mut |_, _| {},
+        )
+        .unwrap();
+        assert_eq!(stats.imported, 2);
+        assert_eq!(stats.reused_embeddings, 2);
+
+        let imported = target.db.export_project("proj").unwrap();
+        let (_, embedding) = imported
+            .iter()
+            .find(|(m, _)| m.content == "Alice works at Microsoft")
+            .unwrap();
+        assert_eq!(*embedding, axis(0));
+    }
+}
@@ -0,0 +1,473 @@
+//! Deterministic synthetic stores for benchmarks and integration tests.
+//!
+//! [`generate`] turns a seed into memories with lorem-like content and fake
+//! embeddings grouped around cluster centers, so searches, clustering and
+//! deduplication have known structure to find; [`populate`] writes them to a
+//! database. The same options always produce the same contents, embeddings,
+//! kinds and timestamps (memory IDs are still random UUIDs). Each memory's
+//! metadata records its cluster, e.g. `{"cluster":3}`.
+//!
+//! The CLI exposes this as `vipune fixture generate`; library users need the
+//! `test-util` feature.
+//!
+//! ```ignore
+//! use vipune::fixtures::{FixtureOptions, populate};
+//!
+//! let db = vipune::Database::open_in_memory()?;
+//! let ids = populate(&db, "bench", &FixtureOptions::default().with_count(100))?;
+//! assert_eq!(ids.len(), 100);
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::embedding::EMBEDDING_DIMS;
+use crate::errors::Error;
+use crate::sqlite::{Database, MemoryKind, NewMemory, Origin, Visibility};
+use crate::storage::StorageBackend;
+
+/// Most memories one call may generate.
+pub const MAX_FIXTURE_COUNT: usize = 1_000_000;
+
+/// Filler words of generated content.
+const LOREM: [&str; 32] = [
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+    "commodo",
+    "consequat",
+];
+
+/// Topic words; each cluster's content mixes a few of them into the filler,
+/// so keyword search sees the same structure as semantic search.
+const TOPICS: [&str; 32] = [
+    "database",
+    "deploy",
+    "kubernetes",
+    "billing",
+    "invoice",
+    "onboarding",
+    "latency",
+    "cache",
+    "migration",
+    "schema",
+    "frontend",
+    "backend",
+    "auth",
+    "token",
+    "release",
+    "pipeline",
+    "budget",
+    "roadmap",
+    "customer",
+    "support",
+    "metrics",
+    "alerting",
+    "backup",
+    "storage",
+    "search",
+    "index",
+    "queue",
+    "worker",
+    "payment",
+    "refund",
+    "design",
+    "review",
+];
+
+/// Start of the year over which creation times are spread.
+const EPOCH: &str = "2024-01-01T00:00:00Z";
+
+/// What [`generate`] produces.
+///
+/// Built with chained `with_*` calls on top of the defaults (1000 memories,
+/// seed 42, 10 clusters, spread 0.3).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureOptions {
+    /// Memories to generate.
+    pub count: usize,
+    /// Seed of the generator; the same seed gives the same memories.
+    pub seed: u64,
+    /// Cluster centers the embeddings are grouped around; with fewer
+    /// memories than clusters, each memory gets its own.
+    pub clusters: usize,
+    /// How far embeddings scatter around their center, from 0.0 (all equal
+    /// to it) to 1.0 (pure noise).
+    pub spread: f32,
+}
+
+impl Default for FixtureOptions {
+    fn default() -> Self {
+        Self {
+            count: 1000,
+            seed: 42,
+            clusters: 10,
+            spread: 0.3,
+        }
+    }
+}
+
+impl FixtureOptions {
+    /// Generate `count` memories.
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Seed the generator with `seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Group embeddings around `clusters` centers.
+    pub fn with_clusters(mut self, clusters: usize) -> Self {
+        self.clusters = clusters;
+        self
+    }
+
+    /// Scatter embeddings around their center by `spread`.
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Check that the counts and `spread` are in range.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` if `count` is 0 or above
+    /// [`MAX_FIXTURE_COUNT`], `clusters` is 0 or above it, or `spread` is
+    /// not within [0.0, 1.0].
+    pub fn validate(&self) -> Result<(), Error> {
+        if !(1..=MAX_FIXTURE_COUNT).contains(&self.count) {
+            return Err(Error::Validation(format!(
+                "Fixture count must be between 1 and {} (got {})",
+                MAX_FIXTURE_COUNT, self.count
+            )));
+        }
+        if !(1..=MAX_FIXTURE_COUNT).contains(&self.clusters) {
+            return Err(Error::Validation(format!(
+                "Fixture clusters must be between 1 and {} (got {})",
+                MAX_FIXTURE_COUNT, self.clusters
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.spread) {
+            return Err(Error::Validation(format!(
+                "Fixture spread must be between 0.0 and 1.0 (got {})",
+                self.spread
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A generated memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fixture {
+    /// Lorem-like content with its cluster's topic words.
+    pub content: String,
+    /// Unit-length embedding near its cluster's center.
+    pub embedding: Vec<f32>,
+    /// Cluster the memory belongs to (0-based).
+    pub cluster: usize,
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// Creation (and update) time, within 2024 (RFC3339).
+    pub created_at: String,
+}
+
+impl Fixture {
+    /// Metadata stored with the memory, recording its cluster.
+    pub fn metadata(&self) -> String {
+        serde_json::json!({ "cluster": self.cluster }).to_string()
+    }
+}
+
+/// Generate memories as `options` describe, the same ones for the same
+/// options.
+///
+/// # Errors
+///
+/// Returns `Error::Validation` if the options are out of range.
+pub fn generate(options: &FixtureOptions) -> Result<Vec<Fixture>, Error> {
+    options.validate()?;
+    let clusters = options.clusters.min(options.count);
+    let mut rng = Rng::new(options.seed);
+    let centers: Vec<Vec<f32>> = (0..clusters).map(|_| unit_vector(&mut rng)).collect();
+    let topics: Vec<[&str; 3]> = (0..clusters)
+        .map(|_| [0; 3].map(|_| TOPICS[rng.below(TOPICS.len())]))
+        .collect();
+    let epoch: DateTime<Utc> = EPOCH.parse().expect("EPOCH is valid RFC3339");
+    let minutes_per_year = 365 * 24 * 60;
+
+    Ok((0..options.count)
+        .map(|i| {
+            // Every cluster gets members even when they are few
+            let cluster = if i < clusters { i } else { rng.below(clusters) };
+            let noise = unit_vector(&mut rng);
+            let mut embedding: Vec<f32> = centers[cluster]
+                .iter()
+                .zip(&noise)
+                .map(|(c, n)| c * (1.0 - options.spread) + n * options.spread)
+                .collect();
+            normalize(&mut embedding);
+
+            let words = 8 + rng.below(9);
+            let mut content: Vec<&str> = (0..words)
+                .map(|_| {
+                    if rng.below(3) == 0 {
+                        topics[cluster][rng.below(3)]
+                    } else {
+                        LOREM[rng.below(LOREM.len())]
+                    }
+                })
+                .collect();
+            let first = capitalize(content.remove(0));
+            let content = format!("{} {}.", first, content.join(" "));
+
+            let kind = MemoryKind::ALL[rng.below(MemoryKind::ALL.len())];
+            let created_at = epoch + Duration::minutes(rng.below(minutes_per_year) as i64);
+            Fixture {
+                content,
+                embedding,
+                cluster,
+                kind,
+                created_at: created_at.to_rfc3339(),
+            }
+        })
+        .collect())
+}
+
+/// Generate memories as `options` describe and add them to `project_id` in
+/// one transaction, returning their IDs in generation order.
+///
+/// Fixtures are written as imported from `fixtures` (origin
+/// `import:fixtures`); nothing is embedded, so no model is needed.
+///
+/// # Errors
+///
+/// Returns `Error::Validation` if the options are out of range, or error if
+/// the database write fails.
+pub fn populate(
+    db: &Database,
+    project_id: &str,
+    options: &FixtureOptions,
+) -> Result<Vec<String>, Error> {
+    let fixtures = generate(options)?;
+    let origin = Origin::Import {
+        source: "fixtures".to_string(),
+    };
+    let tx = db.write_transaction()?;
+    let ids = fixtures
+        .iter()
+        .map(|fixture| {
+            let metadata = fixture.metadata();
+            StorageBackend::insert(
+                db,
+                &NewMemory {
+                    project_id,
+                    content: &fixture.content,
+                    embedding: &fixture.embedding,
+                    metadata: Some(&metadata),
+                    kind: fixture.kind,
+                    origin: &origin,
+                    created_at: &fixture.created_at,
+                    updated_at: &fixture.created_at,
+                    owner: None,
+                    visibility: Visibility::Shared,
+                },
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    tx.commit()?;
+    Ok(ids)
+}
+
+/// Embedding of unit length along axis `index`, orthogonal to every other
+/// axis; handy for placing memories at known similarities.
+///
+/// # Panics
+///
+/// Panics if `index` is not below `EMBEDDING_DIMS`.
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+pub fn axis(index: usize) -> Vec<f32> {
+    let mut vector = vec![0.0; EMBEDDING_DIMS];
+    vector[index] = 1.0;
+    vector
+}
+
+/// Deterministic pseudo-random vector of `len` values in (-1, 1], the same
+/// for the same seed.
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+pub fn sample_vector(len: usize, seed: u64) -> Vec<f32> {
+    let mut rng = Rng::new(seed);
+    (0..len).map(|_| (rng.unit() * 2.0 - 1.0) as f32).collect()
+}
+
+/// The scalar and SIMD kernels of the similarity scan, for `benches/simd.rs`.
+#[cfg(feature = "simd")]
+#[allow(unused_imports)] // Unused import justified: library API, unused by the CLI
+pub use crate::sqlite::{embedding::dot_and_norms_scalar, simd::dot_and_norms};
+
+/// SplitMix64 generator; small, fast and identical on every platform.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut x = self.0;
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^ (x >> 31)
+    }
+
+    /// Uniform in [0, n).
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Uniform in (0, 1].
+    fn unit(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal (Box-Muller).
+    fn normal(&mut self) -> f32 {
+        let (u, v) = (self.unit(), self.unit());
+        ((-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()) as f32
+    }
+}
+
+/// Random direction in embedding space, of unit length.
+fn unit_vector(rng: &mut Rng) -> Vec<f32> {
+    let mut vector: Vec<f32> = (0..EMBEDDING_DIMS).map(|_| rng.normal()).collect();
+    normalize(&mut vector);
+    vector
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn test_generate_is_deterministic_and_clustered() {
+        let options = FixtureOptions::default().with_count(200).with_clusters(4);
+        let fixtures = generate(&options).unwrap();
+        assert_eq!(fixtures, generate(&options).unwrap());
+        assert_ne!(fixtures, generate(&options.clone().with_seed(7)).unwrap());
+        assert_eq!(fixtures.len(), 200);
+        assert!((0..4).all(|c| fixtures.iter().any(|f| f.cluster == c)));
+
+        // Members of a cluster are closer to each other than to other clusters
+        let (same, other): (Vec<f32>, Vec<f32>) = fixtures[1..]
+            .iter()
+            .map(|f| {
+                (
+                    f.cluster == fixtures[0].cluster,
+                    cosine(&fixtures[0].embedding, &f.embedding),
+                )
+            })
+            .fold(
+                (Vec::new(), Vec::new()),
+                |(mut same, mut other), (is_same, sim)| {
+                    if is_same {
+                        same.push(sim)
+                    } else {
+                        other.push(sim)
+                    }
+                    (same, other)
+                },
+            );
+        let min_same = same.iter().copied().fold(f32::MAX, f32::min);
+        let max_other = other.iter().copied().fold(f32::MIN, f32::max);
+        assert!(min_same > max_other, "{min_same} <= {max_other}");
+
+        let first = &fixtures[0];
+        assert_eq!(first.embedding.len(), EMBEDDING_DIMS);
+        assert!((cosine(&first.embedding, &first.embedding) - 1.0).abs() < 1e-4);
+        assert!(first.content.ends_with('.'));
+        assert!(first.created_at.starts_with("2024-"));
+    }
+
+    #[test]
+    fn test_options_validation() {
+        for bad in [
+            FixtureOptions::default().with_count(0),
+            FixtureOptions::default().with_count(MAX_FIXTURE_COUNT + 1),
+            FixtureOptions::default().with_clusters(MAX_FIXTURE_COUNT + 1),
+            FixtureOptions::default().with_clusters(0),
+            FixtureOptions::default().with_spread(1.5),
+        ] {
+            assert!(
+                matches!(generate(&bad), Err(Error::Validation(_))),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_populate_writes_one_memory_per_fixture() {
+        let db = Database::open_in_memory().unwrap();
+        let options = FixtureOptions::default().with_count(30).with_clusters(3);
+        let ids = populate(&db, "bench", &options).unwrap();
+        assert_eq!(ids.len(), 30);
+        assert_eq!(db.count_project("bench").unwrap(), 30);
+
+        let fixtures = generate(&options).unwrap();
+        let memory = db.get(&ids[0]).unwrap().unwrap();
+        assert_eq!(memory.content, fixtures[0].content);
+        assert_eq!(memory.metadata, Some(fixtures[0].metadata()));
+        assert_eq!(memory.created_at, fixtures[0].created_at);
+        assert_eq!(memory.origin.to_string(), "import:fixtures");
+    }
+}
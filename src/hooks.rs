@@ -0,0 +1,249 @@
+//! Mutation hooks: user commands run after memories change.
+//!
+//! Configured in the `[hooks]` section (see [`HooksConfig`]). After an add,
+//! update or delete is stored, the matching command runs through the shell
+//! with a JSON payload on stdin:
+//!
+//! ```json
+//! {"event": "add", "memory": {"id": "...", "project_id": "owner/repo", "content": "...", ...}}
+//! ```
+//!
+//! The event, memory ID and project ID are also passed as the environment
+//! variables `VIPUNE_HOOK_EVENT`, `VIPUNE_MEMORY_ID` and `VIPUNE_PROJECT_ID`.
+//! The command's stdout is discarded (so it cannot corrupt `--json` output);
+//! stderr is passed through. A command still running after `timeout_ms` is
+//! killed. Failures are handled according to `on_failure`. Memories a hook
+//! command adds with `vipune` get the `hook` origin.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Environment variable holding the event of the hook being run.
+pub(crate) const HOOK_EVENT_VAR: &str = "VIPUNE_HOOK_EVENT";
+
+use serde::Serialize;
+
+use crate::config::{HookFailurePolicy, HooksConfig};
+use crate::errors::Error;
+use crate::sqlite::Memory;
+
+/// How often a running hook is checked for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Kind of mutation that triggered a hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookEvent {
+    /// A memory was added.
+    Add,
+    /// A memory's content or metadata was updated.
+    Update,
+    /// A memory was deleted.
+    Delete,
+}
+
+impl HookEvent {
+    /// Lowercase event name, as in the payload.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::Add => "add",
+            HookEvent::Update => "update",
+            HookEvent::Delete => "delete",
+        }
+    }
+
+    /// Command configured for this event, if any.
+    pub(crate) fn command(self, hooks: &HooksConfig) -> Option<&str> {
+        match self {
+            HookEvent::Add => hooks.on_add.as_deref(),
+            HookEvent::Update => hooks.on_update.as_deref(),
+            HookEvent::Delete => hooks.on_delete.as_deref(),
+        }
+    }
+}
+
+/// JSON written to the hook's stdin.
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    event: HookEvent,
+    memory: &'a Memory,
+}
+
+/// Run the hook configured for `event`, if any, on `memory`.
+///
+/// # Errors
+///
+/// Returns `Error::Hook` if the hook fails and `on_failure` is `fail`.
+pub(crate) fn run_hook(
+    hooks: &HooksConfig,
+    event: HookEvent,
+    memory: &Memory,
+) -> Result<(), Error> {
+    let Some(command) = event.command(hooks) else {
+        return Ok(());
+    };
+    let timeout = Duration::from_millis(hooks.timeout_ms);
+    let result = execute(command, event, memory, timeout);
+    match (result, hooks.on_failure) {
+        (Ok(()), _) => Ok(()),
+        (Err(e), HookFailurePolicy::Fail) => Err(Error::Hook(e)),
+        (Err(e), HookFailurePolicy::Warn) => {
+            log::warn!("{} hook failed: {}", event.as_str(), e);
+            Ok(())
+        }
+        (Err(e), HookFailurePolicy::Ignore) => {
+            log::debug!("{} hook failed: {}", event.as_str(), e);
+            Ok(())
+        }
+    }
+}
+
+/// Run `command` with the payload for `memory`, describing any failure.
+fn execute(
+    command: &str,
+    event: HookEvent,
+    memory: &Memory,
+    timeout: Duration,
+) -> Result<(), String> {
+    let payload = serde_json::to_vec(&HookPayload { event, memory })
+        .map_err(|e| format!("cannot serialize payload: {}", e))?;
+
+    let mut child = shell(command)
+        .env(HOOK_EVENT_VAR, event.as_str())
+        .env("VIPUNE_MEMORY_ID", &memory.id)
+        .env("VIPUNE_PROJECT_ID", &memory.project_id)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| format!("cannot run '{}': {}", command, e))?;
+
+    // Write from another thread so a hook that never reads stdin cannot
+    // block us past the timeout; a hook that exits early closes the pipe
+    if let Some(mut stdin) = child.stdin.take() {
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&payload);
+        });
+    }
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => return Err(format!("'{}' exited with {}", command, status)),
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "'{}' timed out after {} ms",
+                    command,
+                    timeout.as_millis()
+                ));
+            }
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(format!("cannot wait for '{}': {}", command, e)),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::sqlite::{MemoryKind, Origin};
+    use tempfile::TempDir;
+
+    fn memory() -> Memory {
+        Memory {
+            id: "mem-1".to_string(),
+            project_id: "proj".to_string(),
+            content: "Alice works at Microsoft".to_string(),
+            metadata: None,
+            kind: MemoryKind::Fact,
+            origin: Origin::default(),
+            title: None,
+            similarity: None,
+            snippet: None,
+            explanation: None,
+            created_at: "2026-01-05T09:00:00+00:00".to_string(),
+            updated_at: "2026-01-05T09:00:00+00:00".to_string(),
+        }
+    }
+
+    fn hooks(on_add: &str, on_failure: HookFailurePolicy) -> HooksConfig {
+        HooksConfig {
+            on_add: Some(on_add.to_string()),
+            timeout_ms: 2000,
+            on_failure,
+            ..HooksConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_hook_receives_payload_and_env() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("payload.json");
+        let command = format!(
+            "cat > '{}'; echo \"$VIPUNE_HOOK_EVENT $VIPUNE_MEMORY_ID $VIPUNE_PROJECT_ID\" >> '{}'",
+            out.display(),
+            out.display()
+        );
+        run_hook(
+            &hooks(&command, HookFailurePolicy::Fail),
+            HookEvent::Add,
+            &memory(),
+        )
+        .unwrap();
+
+        let written = std::fs::read_to_string(&out).unwrap();
+        let (json, env) = written.split_at(written.rfind('}').unwrap() + 1);
+        let payload: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(payload["event"], "add");
+        assert_eq!(payload["memory"]["id"], "mem-1");
+        assert_eq!(payload["memory"]["content"], "Alice works at Microsoft");
+        assert_eq!(env.trim(), "add mem-1 proj");
+    }
+
+    #[test]
+    fn test_unconfigured_event_does_nothing() {
+        let hooks = hooks("exit 1", HookFailurePolicy::Fail);
+        assert!(run_hook(&hooks, HookEvent::Delete, &memory()).is_ok());
+    }
+
+    #[test]
+    fn test_failure_policies() {
+        let failing = |policy| run_hook(&hooks("exit 3", policy), HookEvent::Add, &memory());
+        assert!(matches!(
+            failing(HookFailurePolicy::Fail),
+            Err(Error::Hook(ref e)) if e.contains("exit")
+        ));
+        assert!(failing(HookFailurePolicy::Warn).is_ok());
+        assert!(failing(HookFailurePolicy::Ignore).is_ok());
+    }
+
+    #[test]
+    fn test_hook_is_killed_after_timeout() {
+        let hooks = HooksConfig {
+            timeout_ms: 100,
+            ..hooks("sleep 10", HookFailurePolicy::Fail)
+        };
+        let started = Instant::now();
+        let result = run_hook(&hooks, HookEvent::Add, &memory());
+        assert!(matches!(result, Err(Error::Hook(ref e)) if e.contains("timed out")));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,290 @@
+//! Embedding prepared records and storing them under the duplicate policy.
+
+use chrono::Utc;
+
+use crate::errors::Error;
+use crate::hooks::HookEvent;
+use crate::import_options::{DuplicatePolicy, ImportOptions};
+use crate::memory::MemoryStore;
+use crate::memory::metadata::merge_patch;
+use crate::memory::quota::payload_bytes;
+use crate::project::ProjectId;
+use crate::sqlite::{Memory, MemoryKind, NewMemory, Origin, Visibility};
+
+use super::ImportStats;
+use super::records::{JsonMemory, metadata_to_string, resolve_timestamp};
+
+/// A record embedded and ready to store.
+pub(super) struct PreparedRecord {
+    project_id: String,
+    content: String,
+    metadata: Option<String>,
+    kind: MemoryKind,
+    created_at: String,
+    embedding: Vec<f32>,
+    chunks: Vec<(String, Vec<f32>)>,
+    /// Sampled token embeddings for late-interaction scoring (empty unless
+    /// `[late_interaction] enabled`).
+    tokens: Vec<Vec<f32>>,
+    reused_embedding: bool,
+}
+
+/// What to do with a record, decided before its batch transaction starts.
+pub(super) enum Prepared {
+    /// Already processed by an earlier, interrupted run.
+    Resumed,
+    /// Content empty or too long.
+    Invalid,
+    /// Similar to a memory stored before the batch started, and skipped.
+    Duplicate,
+    /// To be stored, or handled by the duplicate policy if a similar memory
+    /// exists by the time the batch transaction runs.
+    Ready(Box<PreparedRecord>),
+}
+
+/// Validate and embed one record, without holding the write lock.
+pub(super) fn prepare_record(
+    store: &mut MemoryStore,
+    mut record: JsonMemory,
+    index: usize,
+    default_project: &str,
+    options: &ImportOptions,
+) -> Result<Prepared, Error> {
+    let content = match store.prepare_content(&record.content) {
+        Ok(content) => content,
+        Err(e) => {
+            log::warn!("Skipping record {}: {}", index + 1, e);
+            return Ok(Prepared::Invalid);
+        }
+    };
+    let metadata = metadata_to_string(record.metadata.take())?;
+    if let Err(e) = store.config.metadata_schema.check(metadata.as_deref()) {
+        log::warn!("Skipping record {}: {}", index + 1, e);
+        return Ok(Prepared::Invalid);
+    }
+    // An exported embedding belongs to the unfiltered content
+    if content != record.content {
+        record.embedding = None;
+        record.content = content;
+    }
+
+    let source_project = record.source_project().unwrap_or(default_project);
+    let project_id = ProjectId::new(&options.resolve_project(source_project))?.into_string();
+
+    let reused = record.reusable_embedding(&store.model_id, index);
+    let reused_embedding = reused.is_some();
+    let embedding = match reused {
+        Some(embedding) => embedding,
+        None => store.embed_document_checked(&record.content)?,
+    };
+    // Skip chunk embedding for records that are already known to be skipped
+    let threshold = options.duplicate_threshold(store.config.similarity_threshold)?;
+    if options.on_duplicate == DuplicatePolicy::Skip
+        && !store
+            .db
+            .find_similar(&project_id, &embedding, threshold, &store.viewer_filter())?
+            .is_empty()
+    {
+        return Ok(Prepared::Duplicate);
+    }
+
+    let chunks = store.embed_chunks(&record.content)?;
+    let tokens = store.document_tokens(&record.content)?;
+    let kind = record.memory_kind();
+    let created_at = resolve_timestamp(record.created_at.as_deref(), index);
+    Ok(Prepared::Ready(Box::new(PreparedRecord {
+        project_id,
+        content: record.content,
+        metadata,
+        kind,
+        created_at,
+        embedding,
+        chunks,
+        tokens,
+        reused_embedding,
+    })))
+}
+
+/// Store a prepared batch and checkpoint it in one write transaction,
+/// updating `stats`, and return the hook event and ID of every added or
+/// updated memory.
+///
+/// Duplicates are checked under the write lock, against memories written by
+/// other processes since the batch was prepared and against earlier records
+/// of the same batch, and handled by `options.on_duplicate`.
+pub(super) fn store_batch(
+    store: &MemoryStore,
+    batch: Vec<(usize, String, Prepared)>,
+    source: &str,
+    options: &ImportOptions,
+    stats: &mut ImportStats,
+    on_progress: &mut dyn FnMut(usize, &ImportStats),
+) -> Result<Vec<(HookEvent, String)>, Error> {
+    let threshold = options.duplicate_threshold(store.config.similarity_threshold)?;
+    let origin = Origin::import(source);
+    let tx = store.db.write_transaction()?;
+    let mut keys = Vec::with_capacity(batch.len());
+    let mut changed = Vec::new();
+    for (index, key, prepared) in batch {
+        match prepared {
+            Prepared::Resumed => {
+                stats.skipped_resumed += 1;
+                on_progress(index + 1, stats);
+                continue;
+            }
+            Prepared::Invalid => stats.skipped_invalid += 1,
+            Prepared::Duplicate => stats.skipped_duplicates += 1,
+            Prepared::Ready(record) => {
+                let similar = store.db.find_similar(
+                    &record.project_id,
+                    &record.embedding,
+                    threshold,
+                    &store.viewer_filter(),
+                )?;
+                match (similar.first(), options.on_duplicate) {
+                    (None, _) | (Some(_), DuplicatePolicy::KeepBoth) => {
+                        changed.push((HookEvent::Add, store_record(store, &record, &origin)?));
+                        stats.imported += 1;
+                        if !similar.is_empty() {
+                            stats.kept_duplicates += 1;
+                        }
+                        if record.reused_embedding {
+                            stats.reused_embeddings += 1;
+                        }
+                    }
+                    (Some(_), DuplicatePolicy::Skip) => {
+                        log::debug!("Record {} was stored concurrently, skipping", index + 1);
+                        stats.skipped_duplicates += 1;
+                    }
+                    (Some(existing), DuplicatePolicy::Overwrite) => {
+                        overwrite_record(store, &existing.id, &record, &origin)?;
+                        changed.push((HookEvent::Update, existing.id.clone()));
+                        stats.overwritten += 1;
+                        if record.reused_embedding {
+                            stats.reused_embeddings += 1;
+                        }
+                    }
+                    (Some(existing), DuplicatePolicy::Merge) => {
+                        if merge_record(store, existing, &record)? {
+                            changed.push((HookEvent::Update, existing.id.clone()));
+                        }
+                        stats.merged += 1;
+                    }
+                }
+            }
+        }
+        keys.push(key);
+        on_progress(index + 1, stats);
+    }
+    store.db.checkpoint_import(source, &keys)?;
+    tx.commit()?;
+    Ok(changed)
+}
+
+/// Insert a prepared record and its chunks, returning the new memory's ID.
+fn store_record(
+    store: &MemoryStore,
+    record: &PreparedRecord,
+    origin: &Origin,
+) -> Result<String, Error> {
+    store.enforce_quota(
+        &record.project_id,
+        payload_bytes(
+            &record.content,
+            &record.embedding,
+            record.metadata.as_deref(),
+        ),
+    )?;
+    let id = store.db.insert_record(&NewMemory {
+        project_id: &record.project_id,
+        content: &record.content,
+        embedding: &record.embedding,
+        metadata: record.metadata.as_deref(),
+        kind: record.kind,
+        origin,
+        created_at: &record.created_at,
+        updated_at: &record.created_at,
+        owner: store.config.owner.as_deref(),
+        visibility: store.config.visibility,
+    })?;
+    store.store_language(&id, &record.content)?;
+    if !record.reused_embedding {
+        store.store_model_revision(&id)?;
+    }
+    if !record.chunks.is_empty() {
+        store.db.insert_chunks(&id, &record.chunks)?;
+    }
+    if !record.tokens.is_empty() {
+        store.db.set_token_embeddings(&id, Some(&record.tokens))?;
+    }
+    Ok(id)
+}
+
+/// Replace memory `id` with a prepared record, keeping its ID, project,
+/// origin and creation time.
+fn overwrite_record(
+    store: &MemoryStore,
+    id: &str,
+    record: &PreparedRecord,
+    origin: &Origin,
+) -> Result<(), Error> {
+    let now = Utc::now().to_rfc3339();
+    store.db.replace_record(
+        id,
+        &NewMemory {
+            project_id: &record.project_id,
+            content: &record.content,
+            embedding: &record.embedding,
+            metadata: record.metadata.as_deref(),
+            kind: record.kind,
+            origin,
+            created_at: &record.created_at,
+            updated_at: &now,
+            owner: None,
+            visibility: Visibility::Shared,
+        },
+    )?;
+    store.store_language(id, &record.content)?;
+    if record.reused_embedding {
+        // The exported embedding's model revision is unknown
+        store.db.set_model_revision(id, None)?;
+    } else {
+        store.store_model_revision(id)?;
+    }
+    if !record.chunks.is_empty() {
+        store.db.insert_chunks(id, &record.chunks)?;
+    }
+    let tokens = (!record.tokens.is_empty()).then_some(record.tokens.as_slice());
+    store.db.set_token_embeddings(id, tokens)?;
+    Ok(())
+}
+
+/// Merge a prepared record's metadata into `existing` as a JSON merge patch
+/// (RFC 7396), returning whether the metadata changed.
+///
+/// Both sides already fit the `metadata_schema` (the record was checked when
+/// prepared, the memory when stored), so the merge does too.
+fn merge_record(
+    store: &MemoryStore,
+    existing: &Memory,
+    record: &PreparedRecord,
+) -> Result<bool, Error> {
+    let Some(patch) = record.metadata.as_deref() else {
+        return Ok(false);
+    };
+    let patch: serde_json::Value = serde_json::from_str(patch)?;
+    let mut target = existing
+        .metadata
+        .as_deref()
+        .and_then(|metadata| serde_json::from_str(metadata).ok())
+        .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new()));
+    let before = target.clone();
+    merge_patch(&mut target, &patch);
+    if target == before {
+        return Ok(false);
+    }
+    store
+        .db
+        .update_metadata(&existing.id, Some(&target.to_string()))?;
+    Ok(true)
+}
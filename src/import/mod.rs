@@ -0,0 +1,326 @@
+//! Import memories from JSON exports.
+//!
+//! Accepts either a JSON array or JSON Lines, one memory object per entry:
+//!
+//! ```json
+//! {"content": "Alice works at Microsoft", "user_id": "alice", "kind": "fact", "metadata": {"topic": "people"}}
+//! ```
+//!
+//! The source project is read from `project_id`, falling back to `user_id`
+//! (stores that scope memories per user), then to the detected project.
+//! `ImportOptions` can rename or override it. Near-duplicates of existing
+//! memories (similarity >= `ImportOptions::threshold`, by default the
+//! store's `similarity_threshold`) are handled by
+//! `ImportOptions::on_duplicate`: skipped (the default), overwriting the
+//! most similar memory, stored next to it, or merged into its metadata.
+//!
+//! Records written by `vipune export` also carry the memory's embedding
+//! (`embedding`, base64 of little-endian `f32`s) and the `model_id` that
+//! produced it. The embedding is reused when it came from the store's model
+//! and has the expected dimensions; otherwise the content is re-embedded.
+//!
+//! Records are imported in batches of `BATCH_SIZE`. A batch is embedded
+//! first, then stored in one `BEGIN IMMEDIATE` transaction that re-checks
+//! every record for near-duplicates under the write lock, so memories another
+//! process (e.g. a running agent) wrote meanwhile are not duplicated. The
+//! same transaction checkpoints the batch to the `import_progress` staging
+//! table, keyed by the source record's `id` field or, without one, a hash of
+//! its project and content. With `ImportOptions::resume`, checkpointed
+//! records are skipped. With `ImportOptions::exclusive`, the whole import is
+//! a single transaction holding the write lock throughout. With
+//! `ImportOptions::bulk`, that transaction is a bulk session (see
+//! [`MemoryStore::bulk_session`]) that defers full-text indexing to the end.
+//! Add hooks run after the transaction that stored the memory commits.
+//!
+//! Notes directories are split into records by `crate::notes`, and ChatGPT
+//! and Claude conversation exports by `crate::conversations`; both go
+//! through the same pipeline.
+//!
+//! Library users import through [`import`] with an [`ImportSource`]:
+//!
+//! ```no_run
+//! use vipune::import::{ImportSource, import};
+//! use vipune::{Config, ImportOptions, MemoryStore};
+//!
+//! let config = Config::default();
+//! let mut store =
+//!     MemoryStore::new(&config.database_path, &config.embedding_model, config.clone())?;
+//! let source = ImportSource::Json("memories.jsonl".into());
+//! let options = ImportOptions::default().with_mapping("alice", "owner/repo");
+//! let stats = import(&mut store, &source, "owner/repo", &options, &mut |done, stats| {
+//!     eprintln!("{}/{}", done, stats.total);
+//! })?;
+//! println!("Imported {} of {}", stats.imported, stats.total);
+//! # Ok::<(), vipune::Error>(())
+//! ```
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::conversations::{ChatRole, ConversationFormat, import_from_conversations};
+use crate::errors::Error;
+use crate::hooks::HookEvent;
+use crate::import_options::ImportOptions;
+use crate::memory::MemoryStore;
+use crate::notes::{SplitMode, import_from_markdown};
+
+mod apply;
+pub(crate) mod records;
+#[cfg(test)]
+mod tests;
+
+use apply::{Prepared, prepare_record, store_batch};
+pub(crate) use records::JsonMemory;
+use records::parse_records;
+
+/// Records embedded, then stored and checkpointed in one transaction.
+const BATCH_SIZE: usize = 50;
+
+/// Counts reported after an import run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportStats {
+    /// Records found in the source.
+    pub total: usize,
+    /// Records stored as new memories.
+    pub imported: usize,
+    /// Records skipped because a similar memory already exists.
+    pub skipped_duplicates: usize,
+    /// Records that replaced a similar memory
+    /// ([`DuplicatePolicy::Overwrite`](crate::import_options::DuplicatePolicy::Overwrite)).
+    pub overwritten: usize,
+    /// Records whose metadata was merged into a similar memory
+    /// ([`DuplicatePolicy::Merge`](crate::import_options::DuplicatePolicy::Merge)).
+    pub merged: usize,
+    /// Records stored as new memories although a similar memory exists
+    /// ([`DuplicatePolicy::KeepBoth`](crate::import_options::DuplicatePolicy::KeepBoth)); also counted in `imported`.
+    pub kept_duplicates: usize,
+    /// Records skipped because their content was empty or too long.
+    pub skipped_invalid: usize,
+    /// Records skipped because an interrupted earlier run already processed them.
+    pub skipped_resumed: usize,
+    /// Imported or overwriting records stored with their exported embedding
+    /// instead of being re-embedded.
+    pub reused_embeddings: usize,
+}
+
+/// Where [`import`] reads memories from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+    /// JSON array or JSON Lines file of memory records, such as a
+    /// `vipune export`.
+    Json(PathBuf),
+    /// Directory (or single file) of markdown and plain-text notes, split
+    /// into one memory per section.
+    Markdown { path: PathBuf, split: SplitMode },
+    /// ChatGPT or Claude conversation export (`conversations.json` or the
+    /// unpacked export directory), one memory per message of `roles`.
+    Conversations {
+        path: PathBuf,
+        format: ConversationFormat,
+        roles: Vec<ChatRole>,
+    },
+}
+
+impl ImportSource {
+    /// File or directory the memories are read from.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn path(&self) -> &Path {
+        match self {
+            ImportSource::Json(path)
+            | ImportSource::Markdown { path, .. }
+            | ImportSource::Conversations { path, .. } => path,
+        }
+    }
+}
+
+/// Import every memory of `source` into `store`.
+///
+/// Records without a project of their own go to `default_project`;
+/// `options` renames or overrides source projects and controls duplicate
+/// handling, resuming and locking. `on_progress` is called after each record with the number of
+/// records processed so far and the running counts.
+///
+/// # Errors
+///
+/// Returns error if the source cannot be read or parsed, or if embedding or
+/// database operations fail. Batches stored before the failure are
+/// checkpointed, so the import can be resumed (an exclusive or bulk import is
+/// rolled back entirely instead).
+pub fn import(
+    store: &mut MemoryStore,
+    source: &ImportSource,
+    default_project: &str,
+    options: &ImportOptions,
+    on_progress: &mut dyn FnMut(usize, &ImportStats),
+) -> Result<ImportStats, Error> {
+    match source {
+        ImportSource::Json(path) => {
+            import_from_json(store, path, default_project, options, on_progress)
+        }
+        ImportSource::Markdown { path, split } => {
+            import_from_markdown(store, path, *split, default_project, options, on_progress)
+        }
+        ImportSource::Conversations {
+            path,
+            format,
+            roles,
+        } => import_from_conversations(
+            store,
+            path,
+            *format,
+            roles,
+            default_project,
+            options,
+            on_progress,
+        ),
+    }
+}
+
+/// Import memories from a JSON or JSON Lines file.
+///
+/// `on_progress` is called after each record with the number of records
+/// processed so far and the running counts.
+///
+/// # Errors
+///
+/// Returns error if the file cannot be read or parsed, or if embedding or
+/// database operations fail. Batches stored before the failure are
+/// checkpointed, so the import can be resumed (an exclusive or bulk import is
+/// rolled back entirely instead).
+pub fn import_from_json(
+    store: &mut MemoryStore,
+    path: &Path,
+    default_project: &str,
+    options: &ImportOptions,
+    on_progress: &mut dyn FnMut(usize, &ImportStats),
+) -> Result<ImportStats, Error> {
+    let raw = std::fs::read_to_string(path)?;
+    let records = parse_records(&raw)?;
+    import_records(store, records, path, default_project, options, on_progress)
+}
+
+/// Import parsed `records` read from `path`, checkpointing progress under
+/// the canonical form of `path`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`import_from_json`], apart from parsing.
+pub(crate) fn import_records(
+    store: &mut MemoryStore,
+    records: Vec<JsonMemory>,
+    path: &Path,
+    default_project: &str,
+    options: &ImportOptions,
+    on_progress: &mut dyn FnMut(usize, &ImportStats),
+) -> Result<ImportStats, Error> {
+    options.duplicate_threshold(store.config.similarity_threshold)?;
+    let source = std::fs::canonicalize(path)?.display().to_string();
+    let done = if options.resume {
+        let done = store.db.imported_keys(&source)?;
+        log::info!(
+            "Resuming import of {}: {} records already imported",
+            source,
+            done.len()
+        );
+        done
+    } else {
+        store.db.clear_import_progress(&source)?;
+        HashSet::new()
+    };
+    let mut stats = ImportStats {
+        total: records.len(),
+        ..Default::default()
+    };
+
+    let changed = if options.bulk {
+        // Dropping the session on error rolls the whole import back
+        let mut session = store.bulk_session()?;
+        let changed = import_batches(
+            &mut session,
+            records,
+            &source,
+            &done,
+            default_project,
+            options,
+            &mut stats,
+            on_progress,
+        )?;
+        session.commit()?;
+        changed
+    } else {
+        if options.exclusive {
+            store.db.begin_exclusive()?;
+        }
+        let result = import_batches(
+            store,
+            records,
+            &source,
+            &done,
+            default_project,
+            options,
+            &mut stats,
+            on_progress,
+        );
+        if options.exclusive {
+            // Keep the import's own error if it failed; the rollback is best effort
+            match result {
+                Ok(changed) => {
+                    store.db.end_exclusive(true)?;
+                    changed
+                }
+                Err(e) => {
+                    let _ = store.db.end_exclusive(false);
+                    return Err(e);
+                }
+            }
+        } else {
+            result?
+        }
+    };
+    for (event, id) in changed {
+        store.fire_hook(event, &id)?;
+    }
+
+    store.db.clear_import_progress(&source)?;
+    log::debug!("Import of {} finished: {:?}", source, stats);
+    Ok(stats)
+}
+
+/// Import `records` batch by batch, returning the added and updated memories
+/// whose hooks have not run yet (all of them for an exclusive or bulk import,
+/// whose transaction commits later).
+#[allow(clippy::too_many_arguments)]
+fn import_batches(
+    store: &mut MemoryStore,
+    records: Vec<JsonMemory>,
+    source: &str,
+    done: &HashSet<String>,
+    default_project: &str,
+    options: &ImportOptions,
+    stats: &mut ImportStats,
+    on_progress: &mut dyn FnMut(usize, &ImportStats),
+) -> Result<Vec<(HookEvent, String)>, Error> {
+    let mut pending_hooks = Vec::new();
+    let mut records = records.into_iter().enumerate().peekable();
+    while records.peek().is_some() {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        for (index, record) in records.by_ref().take(BATCH_SIZE) {
+            let key = record.resume_key();
+            let prepared = if done.contains(&key) {
+                Prepared::Resumed
+            } else {
+                prepare_record(store, record, index, default_project, options)?
+            };
+            batch.push((index, key, prepared));
+        }
+        let changed = store_batch(store, batch, source, options, stats, on_progress)?;
+        if options.exclusive || options.bulk {
+            pending_hooks.extend(changed);
+        } else {
+            for (event, id) in changed {
+                store.fire_hook(event, &id)?;
+            }
+        }
+    }
+    Ok(pending_hooks)
+}
@@ -0,0 +1,182 @@
+//! Parsing of JSON and JSON Lines import records.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::embedding::EMBEDDING_DIMS;
+use crate::errors::Error;
+use crate::sqlite::MemoryKind;
+use crate::sqlite::audit::payload_hash;
+
+/// A single memory record in a JSON import file.
+#[derive(Debug, Deserialize)]
+pub(crate) struct JsonMemory {
+    #[serde(default)]
+    pub(crate) id: Option<serde_json::Value>,
+    pub(crate) content: String,
+    #[serde(default)]
+    pub(crate) project_id: Option<String>,
+    #[serde(default)]
+    pub(crate) user_id: Option<String>,
+    #[serde(default)]
+    pub(crate) metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub(crate) created_at: Option<String>,
+    #[serde(default)]
+    pub(crate) kind: Option<String>,
+    #[serde(default)]
+    pub(crate) embedding: Option<String>,
+    #[serde(default)]
+    pub(crate) model_id: Option<String>,
+}
+
+impl JsonMemory {
+    /// Project identifier carried by the record itself, if any.
+    pub(crate) fn source_project(&self) -> Option<&str> {
+        self.project_id
+            .as_deref()
+            .or(self.user_id.as_deref())
+            .filter(|p| !p.trim().is_empty())
+    }
+
+    /// Memory kind named by the record; unknown names fall back to `other`
+    /// with a warning.
+    pub(crate) fn memory_kind(&self) -> MemoryKind {
+        match self.kind.as_deref().map(str::parse::<MemoryKind>) {
+            None => MemoryKind::Other,
+            Some(Ok(kind)) => kind,
+            Some(Err(e)) => {
+                log::warn!("{}, using 'other'", e);
+                MemoryKind::Other
+            }
+        }
+    }
+
+    /// The record's embedding, if it was produced by `model_id` and is valid.
+    ///
+    /// Records from another model, without a model, or with a malformed
+    /// embedding return `None` and are re-embedded.
+    pub(super) fn reusable_embedding(&self, model_id: &str, index: usize) -> Option<Vec<f32>> {
+        let encoded = self.embedding.as_deref()?;
+        match self.model_id.as_deref() {
+            Some(source_model) if source_model == model_id => {}
+            source_model => {
+                log::debug!(
+                    "Record {} was embedded with {}, re-embedding with {}",
+                    index + 1,
+                    source_model.unwrap_or("an unknown model"),
+                    model_id
+                );
+                return None;
+            }
+        }
+        match decode_embedding(encoded) {
+            Ok(embedding) => Some(embedding),
+            Err(e) => {
+                log::warn!("Record {} has {}, re-embedding", index + 1, e);
+                None
+            }
+        }
+    }
+
+    /// Stable key identifying this record across import runs.
+    pub(super) fn resume_key(&self) -> String {
+        match &self.id {
+            Some(serde_json::Value::String(id)) => format!("id:{}", id),
+            Some(serde_json::Value::Null) | None => {
+                format!(
+                    "sha256:{}",
+                    payload_hash(&self.content, self.source_project())
+                )
+            }
+            Some(id) => format!("id:{}", id),
+        }
+    }
+}
+
+/// Parse a JSON array or JSON Lines document into memory records.
+pub(super) fn parse_records(raw: &str) -> Result<Vec<JsonMemory>, Error> {
+    if raw.trim_start().starts_with('[') {
+        return Ok(serde_json::from_str(raw)?);
+    }
+
+    let mut records = Vec::new();
+    for (line_no, line) in raw.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(line).map_err(|e| {
+            Error::InvalidInput(format!("Invalid JSON on line {}: {}", line_no + 1, e))
+        })?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Encode an embedding as base64 of its little-endian `f32` bytes.
+#[allow(dead_code)] // Dead code justified: only the CLI export command uses this
+pub(crate) fn encode_embedding(embedding: &[f32]) -> String {
+    let bytes: Vec<u8> = embedding.iter().flat_map(|x| x.to_le_bytes()).collect();
+    BASE64.encode(bytes)
+}
+
+/// Decode an embedding written by [`encode_embedding`].
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if the text is not valid base64, does not
+/// hold exactly `EMBEDDING_DIMS` values, or holds a non-finite value.
+pub(crate) fn decode_embedding(encoded: &str) -> Result<Vec<f32>, Error> {
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| Error::InvalidInput(format!("invalid base64 embedding: {}", e)))?;
+    if bytes.len() != EMBEDDING_DIMS * 4 {
+        return Err(Error::InvalidInput(format!(
+            "embedding of {} bytes (expected {} dimensions)",
+            bytes.len(),
+            EMBEDDING_DIMS
+        )));
+    }
+    let embedding: Vec<f32> = bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    if !embedding.iter().all(|x| x.is_finite()) {
+        return Err(Error::InvalidInput(
+            "embedding with non-finite values".to_string(),
+        ));
+    }
+    Ok(embedding)
+}
+
+/// Store metadata as a JSON string, keeping string values verbatim.
+pub(crate) fn metadata_to_string(
+    metadata: Option<serde_json::Value>,
+) -> Result<Option<String>, Error> {
+    match metadata {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(s)) => Ok(Some(s)),
+        Some(value) => Ok(Some(serde_json::to_string(&value)?)),
+    }
+}
+
+/// Keep the source creation time when it is valid RFC3339, otherwise use now.
+pub(super) fn resolve_timestamp(created_at: Option<&str>, index: usize) -> String {
+    match created_at {
+        Some(ts) => match DateTime::parse_from_rfc3339(ts) {
+            Ok(parsed) => parsed.with_timezone(&Utc).to_rfc3339(),
+            Err(e) => {
+                log::warn!(
+                    "Record {} has invalid created_at '{}' ({}), using current time",
+                    index + 1,
+                    ts,
+                    e
+                );
+                Utc::now().to_rfc3339()
+            }
+        },
+        None => Utc::now().to_rfc3339(),
+    }
+}
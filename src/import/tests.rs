@@ -0,0 +1,404 @@
+//! Tests for the import pipeline.
+
+use chrono::DateTime;
+
+use super::records::{decode_embedding, encode_embedding, metadata_to_string, resolve_timestamp};
+use super::*;
+use crate::config::Config;
+use crate::embedding::EMBEDDING_DIMS;
+use crate::fixtures::axis;
+use crate::import_options::DuplicatePolicy;
+use crate::sqlite::{Database, MemoryKind, Origin};
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[test]
+fn test_parse_records_json_array() {
+    let raw = r#"[{"content": "a", "user_id": "u1"}, {"content": "b"}]"#;
+    let records = parse_records(raw).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].user_id.as_deref(), Some("u1"));
+    assert!(records[1].project_id.is_none());
+}
+
+#[test]
+fn test_parse_records_jsonl_skips_blank_lines() {
+    let raw = "{\"content\": \"a\"}\n\n{\"content\": \"b\", \"project_id\": \"p\"}\n";
+    let records = parse_records(raw).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].project_id.as_deref(), Some("p"));
+}
+
+#[test]
+fn test_parse_records_reports_line_number() {
+    let raw = "{\"content\": \"a\"}\nnot json\n";
+    let err = parse_records(raw).unwrap_err();
+    assert!(err.to_string().contains("line 2"));
+}
+
+#[test]
+fn test_resume_key_prefers_source_id() {
+    let records = parse_records(
+        r#"[{"id": "m-1", "content": "a"}, {"id": 7, "content": "a"}, {"content": "a"}]"#,
+    )
+    .unwrap();
+    assert_eq!(records[0].resume_key(), "id:m-1");
+    assert_eq!(records[1].resume_key(), "id:7");
+    assert!(records[2].resume_key().starts_with("sha256:"));
+}
+
+#[test]
+fn test_resume_key_without_id_depends_on_project() {
+    let raw = r#"[
+        {"content": "a", "user_id": "u1"},
+        {"content": "a", "user_id": "u2"},
+        {"content": "a", "user_id": "u1"}
+    ]"#;
+    let records = parse_records(raw).unwrap();
+    assert_ne!(records[0].resume_key(), records[1].resume_key());
+    assert_eq!(records[0].resume_key(), records[2].resume_key());
+}
+
+#[test]
+fn test_memory_kind_from_record() {
+    let records = parse_records(
+        r#"[{"content": "a", "kind": "Decision"}, {"content": "b"}, {"content": "c", "kind": "rumor"}]"#,
+    )
+    .unwrap();
+    assert_eq!(records[0].memory_kind(), MemoryKind::Decision);
+    assert_eq!(records[1].memory_kind(), MemoryKind::Other);
+    assert_eq!(records[2].memory_kind(), MemoryKind::Other);
+}
+
+#[test]
+fn test_embedding_roundtrip() {
+    let embedding: Vec<f32> = (0..EMBEDDING_DIMS).map(|i| i as f32 / 100.0).collect();
+    assert_eq!(
+        decode_embedding(&encode_embedding(&embedding)).unwrap(),
+        embedding
+    );
+}
+
+#[test]
+fn test_decode_embedding_rejects_malformed() {
+    assert!(decode_embedding("not base64!").is_err());
+    assert!(decode_embedding(&encode_embedding(&[0.5; 10])).is_err());
+    let mut embedding = vec![0.0; EMBEDDING_DIMS];
+    embedding[3] = f32::NAN;
+    assert!(decode_embedding(&encode_embedding(&embedding)).is_err());
+}
+
+#[test]
+fn test_reusable_embedding_requires_matching_model() {
+    let encoded = encode_embedding(&[0.25; EMBEDDING_DIMS]);
+    let raw = format!(
+        r#"[
+            {{"content": "a", "embedding": "{e}", "model_id": "model-a"}},
+            {{"content": "b", "embedding": "{e}", "model_id": "model-b"}},
+            {{"content": "c", "embedding": "{e}"}},
+            {{"content": "d", "model_id": "model-a"}},
+            {{"content": "e", "embedding": "AAAA", "model_id": "model-a"}}
+        ]"#,
+        e = encoded
+    );
+    let records = parse_records(&raw).unwrap();
+    assert_eq!(
+        records[0].reusable_embedding("model-a", 0),
+        Some(vec![0.25; EMBEDDING_DIMS])
+    );
+    for (index, record) in records.iter().enumerate().skip(1) {
+        assert_eq!(record.reusable_embedding("model-a", index), None);
+    }
+}
+
+#[test]
+fn test_metadata_to_string() {
+    assert_eq!(metadata_to_string(None).unwrap(), None);
+    assert_eq!(
+        metadata_to_string(Some(serde_json::json!("raw"))).unwrap(),
+        Some("raw".to_string())
+    );
+    assert_eq!(
+        metadata_to_string(Some(serde_json::json!({"k": 1}))).unwrap(),
+        Some(r#"{"k":1}"#.to_string())
+    );
+}
+
+#[test]
+fn test_resolve_timestamp_keeps_valid_rfc3339() {
+    let ts = resolve_timestamp(Some("2024-06-01T12:00:00Z"), 0);
+    assert!(ts.starts_with("2024-06-01T12:00:00"));
+}
+
+#[test]
+fn test_resolve_timestamp_invalid_falls_back_to_now() {
+    let ts = resolve_timestamp(Some("yesterday"), 0);
+    assert!(DateTime::parse_from_rfc3339(&ts).is_ok());
+}
+
+/// A record in project `proj` carrying an embedding the store can reuse.
+fn embedded_line(store: &MemoryStore, content: &str, embedding: &[f32]) -> String {
+    format!(
+        r#"{{"content": "{}", "project_id": "proj", "embedding": "{}", "model_id": "{}"}}"#,
+        content,
+        encode_embedding(embedding),
+        store.model_id
+    )
+}
+
+#[test]
+fn test_import_source_reports_progress() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("memories.jsonl");
+    let mut store =
+        MemoryStore::open_without_embedder(&dir.path().join("test.db"), Config::default()).unwrap();
+    let lines = [
+        embedded_line(&store, "first", &axis(0)),
+        embedded_line(&store, "second", &axis(1)),
+    ];
+    std::fs::write(&file, lines.join("\n")).unwrap();
+
+    let source = ImportSource::Json(file.clone());
+    assert_eq!(source.path(), file);
+    let mut calls = Vec::new();
+    let stats = import(
+        &mut store,
+        &source,
+        "default",
+        &ImportOptions::default(),
+        &mut |processed, stats| calls.push((processed, stats.imported)),
+    )
+    .unwrap();
+    assert_eq!(stats.imported, 2);
+    assert_eq!(stats.reused_embeddings, 2);
+    assert_eq!(calls, vec![(1, 1), (2, 2)]);
+    assert_eq!(store.db.count_project("proj").unwrap(), 2);
+}
+
+#[test]
+fn test_stable_ids_match_across_stores() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("memories.jsonl");
+    let config = Config {
+        stable_ids: true,
+        ..Config::default()
+    };
+    let mut ids = Vec::new();
+    for name in ["a.db", "b.db"] {
+        let mut store =
+            MemoryStore::open_without_embedder(&dir.path().join(name), config.clone()).unwrap();
+        let lines = [
+            embedded_line(&store, "first", &axis(0)),
+            embedded_line(&store, "second", &axis(1)),
+        ];
+        std::fs::write(&file, lines.join("\n")).unwrap();
+        let source = ImportSource::Json(file.clone());
+        import(
+            &mut store,
+            &source,
+            "default",
+            &ImportOptions::default(),
+            &mut |_, _| {},
+        )
+        .unwrap();
+        let mut stored: Vec<String> = store
+            .db
+            .list("proj", 10)
+            .unwrap()
+            .into_iter()
+            .map(|memory| memory.id)
+            .collect();
+        stored.sort();
+        ids.push(stored);
+    }
+    assert_eq!(ids[0].len(), 2);
+    assert_eq!(ids[0], ids[1]);
+}
+
+#[test]
+fn test_batch_rechecks_duplicates_written_concurrently() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let mut store = MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+    let lines = [
+        embedded_line(&store, "first", &axis(0)),
+        embedded_line(&store, "second", &axis(1)),
+    ];
+    let mut batch = Vec::new();
+    for (index, record) in parse_records(&lines.join("\n"))
+        .unwrap()
+        .into_iter()
+        .enumerate()
+    {
+        let key = record.resume_key();
+        let prepared = prepare_record(
+            &mut store,
+            record,
+            index,
+            "default",
+            &ImportOptions::default(),
+        )
+        .unwrap();
+        assert!(matches!(prepared, Prepared::Ready(_)));
+        batch.push((index, key, prepared));
+    }
+
+    // Another process stores a near-copy of the first record meanwhile
+    let agent = Database::open(&path).unwrap();
+    agent
+        .insert("proj", "first, stored by the agent", &axis(0), None)
+        .unwrap();
+
+    let mut stats = ImportStats::default();
+    let options = ImportOptions::default();
+    let added = store_batch(
+        &store,
+        batch,
+        "source",
+        &options,
+        &mut stats,
+        &mut |_, _| {},
+    )
+    .unwrap();
+    assert_eq!(added.len(), 1);
+    assert_eq!(stats.imported, 1);
+    assert_eq!(stats.skipped_duplicates, 1);
+    assert_eq!(store.db.count_project("proj").unwrap(), 2);
+    assert_eq!(store.db.imported_keys("source").unwrap().len(), 2);
+    let memory = store.db.get(&added[0].1).unwrap().unwrap();
+    assert_eq!(memory.origin, Origin::import("source"));
+}
+
+#[test]
+fn test_duplicate_policies() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("memories.jsonl");
+    let run = |name: &str, options: ImportOptions, embedding: Vec<f32>| {
+        let mut store =
+            MemoryStore::open_without_embedder(&dir.path().join(name), Config::default()).unwrap();
+        let id = store
+            .db
+            .insert("proj", "old text", &axis(0), Some(r#"{"a": 1, "b": 1}"#))
+            .unwrap();
+        let line = embedded_line(&store, "new text", &embedding);
+        let line = format!(
+            r#"{}, "metadata": {{"b": 2, "c": 3}}}}"#,
+            line.strip_suffix('}').unwrap()
+        );
+        std::fs::write(&file, line).unwrap();
+        let stats =
+            import_from_json(&mut store, &file, "default", &options, &mut |_, _| {}).unwrap();
+        let memory = store.db.get(&id).unwrap().unwrap();
+        let metadata: serde_json::Value =
+            serde_json::from_str(memory.metadata.as_deref().unwrap()).unwrap();
+        let count = store.db.count_project("proj").unwrap();
+        (stats, memory.content, metadata, count)
+    };
+
+    let (stats, content, _, count) = run("skip.db", ImportOptions::default(), axis(0));
+    assert_eq!((stats.skipped_duplicates, stats.imported, count), (1, 0, 1));
+    assert_eq!(content, "old text");
+
+    let options = ImportOptions::default().with_on_duplicate(DuplicatePolicy::Overwrite);
+    let (stats, content, metadata, count) = run("overwrite.db", options, axis(0));
+    assert_eq!((stats.overwritten, stats.imported, count), (1, 0, 1));
+    assert_eq!(content, "new text");
+    assert_eq!(metadata, serde_json::json!({"b": 2, "c": 3}));
+
+    let options = ImportOptions::default().with_on_duplicate(DuplicatePolicy::KeepBoth);
+    let (stats, content, _, count) = run("keep.db", options, axis(0));
+    assert_eq!((stats.kept_duplicates, stats.imported, count), (1, 1, 2));
+    assert_eq!(content, "old text");
+
+    let options = ImportOptions::default().with_on_duplicate(DuplicatePolicy::Merge);
+    let (stats, content, metadata, count) = run("merge.db", options, axis(0));
+    assert_eq!((stats.merged, stats.imported, count), (1, 0, 1));
+    assert_eq!(content, "old text");
+    assert_eq!(metadata, serde_json::json!({"a": 1, "b": 2, "c": 3}));
+
+    // The threshold decides what counts as a duplicate
+    let (stats, _, _, count) = run("distinct.db", ImportOptions::default(), axis(1));
+    assert_eq!((stats.imported, count), (1, 2));
+    let options = ImportOptions::default().with_threshold(0.0);
+    let (stats, _, _, count) = run("loose.db", options, axis(1));
+    assert_eq!((stats.skipped_duplicates, count), (1, 1));
+}
+
+#[test]
+fn test_exclusive_import_holds_lock_and_rolls_back_on_failure() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let file = dir.path().join("memories.jsonl");
+    let mut store = MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+    // One full batch that can be stored, then a record that needs the
+    // (unavailable) model
+    let mut lines: Vec<String> = (0..BATCH_SIZE)
+        .map(|i| embedded_line(&store, &format!("memory {i}"), &axis(i)))
+        .collect();
+    lines.push(r#"{"content": "needs embedding", "project_id": "proj"}"#.to_string());
+    std::fs::write(&file, lines.join("\n")).unwrap();
+
+    let agent = Database::open(&path).unwrap();
+    agent.set_busy_timeout(Duration::from_millis(10)).unwrap();
+    let mut blocked = 0;
+    let options = ImportOptions::default().with_exclusive(true);
+    let result = import_from_json(&mut store, &file, "default", &options, &mut |_, _| {
+        if agent
+            .insert("other", "agent write", &axis(0), None)
+            .is_err()
+        {
+            blocked += 1;
+        }
+    });
+    assert!(result.is_err());
+    assert_eq!(blocked, BATCH_SIZE);
+    assert_eq!(store.db.count_project("proj").unwrap(), 0);
+    agent
+        .insert("other", "agent write", &axis(0), None)
+        .unwrap();
+
+    // Without --exclusive the stored batch survives the failure
+    let result = import_from_json(
+        &mut store,
+        &file,
+        "default",
+        &ImportOptions::default(),
+        &mut |_, _| {},
+    );
+    assert!(result.is_err());
+    assert_eq!(store.db.count_project("proj").unwrap(), BATCH_SIZE);
+}
+
+#[test]
+fn test_bulk_import_indexes_on_commit_and_rolls_back_on_failure() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let file = dir.path().join("memories.jsonl");
+    let mut store = MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+    let options = ImportOptions::default().with_bulk(true);
+
+    let lines: Vec<String> = (0..BATCH_SIZE + 1)
+        .map(|i| embedded_line(&store, &format!("bulk memory {i}"), &axis(i)))
+        .collect();
+    std::fs::write(&file, lines.join("\n")).unwrap();
+    let stats = import_from_json(&mut store, &file, "default", &options, &mut |_, _| {}).unwrap();
+    assert_eq!(stats.imported, BATCH_SIZE + 1);
+    assert!(!store.db.in_bulk());
+    assert_eq!(
+        store.db.search_bm25("bulk", "proj", 100).unwrap().len(),
+        BATCH_SIZE + 1
+    );
+
+    // A record needing the (unavailable) model fails the import after a
+    // full batch was stored; the session rolls it back
+    let mut lines: Vec<String> = (0..BATCH_SIZE)
+        .map(|i| embedded_line(&store, &format!("other memory {i}"), &axis(i)))
+        .collect();
+    lines.push(r#"{"content": "needs embedding", "project_id": "other"}"#.to_string());
+    std::fs::write(&file, lines.join("\n").replace("\"proj\"", "\"other\"")).unwrap();
+    let result = import_from_json(&mut store, &file, "default", &options, &mut |_, _| {});
+    assert!(result.is_err());
+    assert!(!store.db.in_bulk());
+    assert_eq!(store.db.count_project("other").unwrap(), 0);
+    assert_eq!(store.db.count_project("proj").unwrap(), BATCH_SIZE + 1);
+}
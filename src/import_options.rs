@@ -0,0 +1,231 @@
+//! Options controlling how imported memories are assigned to projects,
+//! what happens to near-duplicates of existing memories, whether an
+//! interrupted import is resumed, whether it locks out other writers and
+//! whether it runs as a write-optimized bulk session.
+
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+
+use crate::errors::Error;
+
+/// What an import does with a record at least as similar as the duplicate
+/// threshold to a memory of its project.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DuplicatePolicy {
+    /// Leave the existing memory alone and drop the record.
+    #[default]
+    Skip,
+    /// Replace the content, metadata, kind and embedding of the most similar
+    /// memory with the record's, keeping its ID and creation time.
+    Overwrite,
+    /// Store the record as a new memory next to the similar ones.
+    KeepBoth,
+    /// Keep the most similar memory's content and merge the record's
+    /// metadata into its own (record keys win).
+    Merge,
+}
+
+/// Project assignment rules applied to every imported memory.
+///
+/// Source records carry their own project identifier (a `project_id`, or a
+/// `user_id` for stores that scope memories per user). These options let a
+/// migration rename those identifiers or funnel everything into one project.
+///
+/// Resolution priority: project override > explicit mapping > source project.
+///
+/// # Example
+///
+/// ```
+/// use vipune::ImportOptions;
+///
+/// let options = ImportOptions::default().with_mapping("alice", "owner/repo");
+/// assert_eq!(options.resolve_project("alice"), "owner/repo");
+/// assert_eq!(options.resolve_project("bob"), "bob");
+///
+/// let options = options.with_project("shared");
+/// assert_eq!(options.resolve_project("alice"), "shared");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Project that receives every imported memory, ignoring source projects.
+    pub project_override: Option<String>,
+    /// Renames applied to source project identifiers (old -> new).
+    pub project_map: HashMap<String, String>,
+    /// Skip records checkpointed by an earlier, interrupted import of the same file.
+    pub resume: bool,
+    /// Hold the database write lock for the whole import, so no other process
+    /// can write until it finishes (a failure then rolls back every record).
+    pub exclusive: bool,
+    /// Run the whole import in a bulk session (see
+    /// [`MemoryStore::bulk_session`](crate::MemoryStore::bulk_session)),
+    /// which implies `exclusive` and defers full-text indexing to the end.
+    pub bulk: bool,
+    /// What to do with records similar to existing memories.
+    pub on_duplicate: DuplicatePolicy,
+    /// Similarity at which a record counts as a duplicate (default: the
+    /// store's `similarity_threshold`).
+    pub threshold: Option<f64>,
+}
+
+impl ImportOptions {
+    /// Send every imported memory to `project`, regardless of its source project.
+    pub fn with_project(mut self, project: &str) -> Self {
+        self.project_override = Some(project.trim().to_string());
+        self
+    }
+
+    /// Rename source project `old` to `new` during import.
+    pub fn with_mapping(mut self, old: &str, new: &str) -> Self {
+        self.project_map
+            .insert(old.trim().to_string(), new.trim().to_string());
+        self
+    }
+
+    /// Continue an interrupted import instead of starting from the first record.
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Hold the write lock for the whole import instead of per batch.
+    pub fn with_exclusive(mut self, exclusive: bool) -> Self {
+        self.exclusive = exclusive;
+        self
+    }
+
+    /// Import in a write-optimized bulk session.
+    pub fn with_bulk(mut self, bulk: bool) -> Self {
+        self.bulk = bulk;
+        self
+    }
+
+    /// Handle records similar to existing memories with `policy`.
+    pub fn with_on_duplicate(mut self, policy: DuplicatePolicy) -> Self {
+        self.on_duplicate = policy;
+        self
+    }
+
+    /// Count records at least `threshold` similar to an existing memory as
+    /// duplicates, instead of using the store's `similarity_threshold`.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Duplicate threshold in effect, given the store's `similarity_threshold`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` if the threshold is not between 0.0 and 1.0.
+    pub fn duplicate_threshold(&self, default: f64) -> Result<f64, Error> {
+        let threshold = self.threshold.unwrap_or(default);
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(Error::Validation(format!(
+                "Invalid duplicate threshold: {} (must be between 0.0 and 1.0)",
+                threshold
+            )));
+        }
+        Ok(threshold)
+    }
+
+    /// Parse a mapping specification of the form `old=new`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the separator is missing or either side is empty.
+    pub fn parse_mapping(spec: &str) -> Result<(String, String), Error> {
+        let (old, new) = spec.split_once('=').ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Invalid project mapping '{}' (expected old=new)",
+                spec
+            ))
+        })?;
+        let (old, new) = (old.trim(), new.trim());
+        if old.is_empty() || new.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Invalid project mapping '{}' (both sides must be non-empty)",
+                spec
+            )));
+        }
+        Ok((old.to_string(), new.to_string()))
+    }
+
+    /// Resolve the vipune project for a memory imported from `source_project`.
+    pub fn resolve_project(&self, source_project: &str) -> String {
+        if let Some(project) = &self.project_override {
+            return project.clone();
+        }
+        let source_project = source_project.trim();
+        self.project_map
+            .get(source_project)
+            .cloned()
+            .unwrap_or_else(|| source_project.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_without_rules_keeps_source() {
+        let options = ImportOptions::default();
+        assert_eq!(options.resolve_project("user-1"), "user-1");
+    }
+
+    #[test]
+    fn test_resolve_with_mapping() {
+        let options = ImportOptions::default()
+            .with_mapping("user-1", "owner/repo")
+            .with_mapping("user-2", "other");
+        assert_eq!(options.resolve_project("user-1"), "owner/repo");
+        assert_eq!(options.resolve_project("user-2"), "other");
+        assert_eq!(options.resolve_project("user-3"), "user-3");
+    }
+
+    #[test]
+    fn test_project_override_wins_over_mapping() {
+        let options = ImportOptions::default()
+            .with_mapping("user-1", "owner/repo")
+            .with_project("shared");
+        assert_eq!(options.resolve_project("user-1"), "shared");
+        assert_eq!(options.resolve_project("anything"), "shared");
+    }
+
+    #[test]
+    fn test_duplicate_threshold_defaults_to_store_and_is_validated() {
+        let options = ImportOptions::default();
+        assert_eq!(options.on_duplicate, DuplicatePolicy::Skip);
+        assert_eq!(options.duplicate_threshold(0.85).unwrap(), 0.85);
+        let options = options.with_threshold(0.95);
+        assert_eq!(options.duplicate_threshold(0.85).unwrap(), 0.95);
+        assert!(matches!(
+            ImportOptions::default()
+                .with_threshold(1.5)
+                .duplicate_threshold(0.85),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_mapping_valid() {
+        let (old, new) = ImportOptions::parse_mapping(" user-1 = owner/repo ").unwrap();
+        assert_eq!(old, "user-1");
+        assert_eq!(new, "owner/repo");
+    }
+
+    #[test]
+    fn test_parse_mapping_missing_separator() {
+        assert!(matches!(
+            ImportOptions::parse_mapping("user-1"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_mapping_empty_side() {
+        assert!(ImportOptions::parse_mapping("=new").is_err());
+        assert!(ImportOptions::parse_mapping("old=").is_err());
+    }
+}
@@ -0,0 +1,39 @@
+//! Language detection for memory content.
+//!
+//! The detected language is stored with each memory (when
+//! `detect_language` is enabled) so users can see which languages a project
+//! holds and pick an FTS tokenizer that suits them.
+
+/// ISO 639-3 code of the language `text` is written in (e.g. `"eng"`,
+/// `"fin"`, `"jpn"`), or `None` if it cannot be told reliably, as is common
+/// for very short texts.
+pub fn detect(text: &str) -> Option<&'static str> {
+    whatlang::detect(text)
+        .filter(|info| info.is_reliable())
+        .map(|info| info.lang().code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_languages() {
+        assert_eq!(
+            detect("The deployment pipeline runs the integration tests before every release"),
+            Some("eng")
+        );
+        assert_eq!(
+            detect(
+                "Tietokannan varmuuskopiot otetaan joka yö ennen julkaisua ja säilytetään kuukauden ajan"
+            ),
+            Some("fin")
+        );
+        assert_eq!(
+            detect("本番環境へのデプロイは毎週金曜日の午後に行われます"),
+            Some("jpn")
+        );
+        assert_eq!(detect(""), None);
+        assert_eq!(detect("42"), None);
+    }
+}
@@ -1,12 +1,21 @@
 //! vipune - A minimal memory layer for AI agents.
 //!
 //! This crate provides a local, semantic memory store with conflict detection.
-//! All operations are synchronous (no async/await required).
+//! All operations are synchronous (no async/await required); with the
+//! `async` feature, `AsyncMemoryStore` runs them on tokio's blocking pool.
+//! The `rerank` feature adds cross-encoder reranking of search results.
+//! The `late-interaction` feature adds MaxSim rescoring of search results
+//! with stored token embeddings.
+//! The `client` feature adds `client::RemoteStore`, which sends memory
+//! operations to a running `vipune daemon` behind the same
+//! `client::MemoryApi` trait as `MemoryStore`.
+//! The `test-util` feature exposes `fixtures`, a generator of deterministic
+//! synthetic stores for tests and benchmarks.
 //!
 //! # Example
 //!
 //! ```no_run
-//! use vipune::{Config, MemoryStore, detect_project};
+//! use vipune::{Config, MemoryStore, SearchOptions, detect_project};
 //!
 //! // Initialize memory store
 //! let config = Config::default();
@@ -29,7 +38,11 @@
 //! }
 //!
 //! // Search memories
-//! let results = store.search(&project_id, "where does alice work", 10, 0.0);
+//! let results = store.query(
+//!     &project_id,
+//!     "where does alice work",
+//!     SearchOptions::default().with_limit(10),
+//! );
 //! for memory in results.unwrap() {
 //!     println!("{:.2}: {}", memory.similarity.unwrap_or(0.0), memory.content);
 //! }
@@ -40,22 +53,88 @@
 //! Methods that generate embeddings (`add`, `search`, `update`) require `&mut self`
 //! because the embedding engine internally mutates state for ONNX tensor allocations.
 
+pub mod cancel;
+mod chunking;
+#[cfg(all(unix, feature = "client"))]
+pub mod client;
 pub mod config;
+pub mod content_filter;
+mod conversations;
+pub mod daemon;
+pub mod diff;
 pub mod embedding;
 pub mod errors;
+#[cfg(any(test, feature = "test-util"))]
+pub mod fixtures;
+pub mod hooks;
+pub mod import;
+pub mod import_options;
+mod language;
+pub mod list_options;
 pub mod memory;
 pub mod memory_types;
+pub mod metrics;
+pub mod model_files;
+pub mod model_registry;
+mod normalize;
+mod notes;
 pub mod project;
+#[cfg(feature = "rerank")]
+pub mod rerank;
 mod rrf;
+pub mod search_options;
+mod secrets;
 mod sqlite;
+pub mod storage;
 mod temporal;
+pub mod timeparse;
+mod title;
 
 // Re-export public API
+pub use cancel::CancellationToken;
 pub use config::Config;
-pub use embedding::{EMBEDDING_DIMS, EmbeddingEngine};
+pub use content_filter::ContentFilter;
+pub use conversations::{ChatRole, ConversationFormat};
+pub use embedding::{EMBEDDING_DIMS, EmbeddingEngine, MAX_TOKENS, TokenCounter};
 pub use errors::Error;
+pub use import::{ImportSource, ImportStats};
+pub use import_options::{DuplicatePolicy, ImportOptions};
+pub use list_options::{Field, ListOptions, SelectedFields, select_fields};
 pub use memory::MemoryStore;
-pub use memory::store::{MAX_INPUT_LENGTH, MAX_SEARCH_LIMIT};
-pub use memory_types::{AddResult, ConflictMemory};
-pub use project::detect_project;
-pub use sqlite::Memory;
+#[cfg(feature = "async")]
+pub use memory::async_store::AsyncMemoryStore;
+pub use memory::bulk::BulkSession;
+pub use memory::shared::SharedMemoryStore;
+pub use memory::store::{
+    BATCH_QUERIES_CAP, DEFAULT_DRIFT_TOLERANCE, IN_MEMORY_DB_PATH, INPUT_LENGTH_CAP,
+    MAX_BATCH_QUERIES, MAX_INPUT_LENGTH, MAX_SEARCH_LIMIT, SEARCH_LIMIT_CAP,
+};
+pub use memory_types::{
+    AddPreview, AddResult, ClusterAssignment, Clustering, CompressionStats, ConflictMemory,
+    ContextEntry, ContextPack, DriftedEmbedding, EmbeddingInfo, EmbeddingScan, EmbeddingStats,
+    EmbeddingVerification, HealthReport, MemoryCluster, MetadataUpdate, ProjectHits, ProjectRename,
+    ProjectStats, PruneStats, PrunedProject, ReviewAction, ReviewCandidate, ReviewDecision,
+    ReviewStats, RevisionDeviation, SearchResults, TokenStats, UpdateResult,
+};
+pub use metrics::{Metrics, MetricsExporter};
+pub use model_files::ModelFiles;
+pub use model_registry::ModelPrefixes;
+pub use normalize::TextNormalization;
+pub use notes::SplitMode;
+pub use project::{MonorepoMode, ProjectId, detect_project, detect_project_with};
+#[cfg(feature = "rerank")]
+pub use rerank::CrossEncoder;
+pub use rrf::{FusionStrategy, RrfConfig};
+pub use search_options::{
+    FieldWeights, FilterOp, MetadataFilter, QuerySyntax, Rank, SearchOptions, SearchStrategy,
+};
+pub use sqlite::fts::INDEXED_METADATA_FIELDS;
+pub use sqlite::review::IdleMemory;
+pub use sqlite::sync::{SyncState, SyncStats};
+pub use sqlite::trash::TrashedMemory;
+pub use sqlite::verify::EmbeddingSample;
+pub use sqlite::{
+    AccessStats, AuditEntry, Database, EmbeddingFormat, FtsTokenizer, Memory, MemoryKind,
+    MemoryVersion, NewMemory, Origin, ScoreExplanation, StoreMeta, Visibility,
+};
+pub use storage::StorageBackend;
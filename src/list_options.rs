@@ -0,0 +1,336 @@
+//! Options for listing memories, and selection of the fields shown for each
+//! listed or found memory (`--fields`).
+
+use chrono::{DateTime, Utc};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+
+use crate::errors::Error;
+use crate::sqlite::{Memory, MemoryKind, Origin};
+
+/// Memories returned by [`MemoryStore::list_with`](crate::MemoryStore::list_with)
+/// unless [`ListOptions::with_limit`] says otherwise.
+pub const DEFAULT_LIST_LIMIT: usize = 10;
+
+/// A field of a listed or found memory that output can be limited to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    /// Memory ID.
+    Id,
+    /// Project the memory belongs to.
+    ProjectId,
+    /// Title given on add or derived from the content.
+    Title,
+    /// Memory content.
+    Content,
+    /// Search score (`similarity` in JSON; null when listing).
+    Score,
+    /// Memory kind.
+    Kind,
+    /// Entry path the memory was written through.
+    Origin,
+    /// User-provided metadata (JSON string).
+    Metadata,
+    /// Best-matching chunk of a chunked memory.
+    Snippet,
+    /// Score breakdown of a search with `explain`.
+    Explanation,
+    /// Model tokens in the content.
+    TokenCount,
+    /// Creation timestamp.
+    CreatedAt,
+    /// Last update timestamp.
+    UpdatedAt,
+}
+
+impl Field {
+    /// Every field, in the order they are documented.
+    pub const ALL: [Field; 13] = [
+        Field::Id,
+        Field::ProjectId,
+        Field::Title,
+        Field::Content,
+        Field::Score,
+        Field::Kind,
+        Field::Origin,
+        Field::Metadata,
+        Field::Snippet,
+        Field::Explanation,
+        Field::TokenCount,
+        Field::CreatedAt,
+        Field::UpdatedAt,
+    ];
+
+    /// Name accepted by `--fields`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Id => "id",
+            Field::ProjectId => "project_id",
+            Field::Title => "title",
+            Field::Content => "content",
+            Field::Score => "score",
+            Field::Kind => "kind",
+            Field::Origin => "origin",
+            Field::Metadata => "metadata",
+            Field::Snippet => "snippet",
+            Field::Explanation => "explanation",
+            Field::TokenCount => "token_count",
+            Field::CreatedAt => "created_at",
+            Field::UpdatedAt => "updated_at",
+        }
+    }
+
+    /// JSON key of the field, the same as in the default output.
+    pub fn key(self) -> &'static str {
+        match self {
+            Field::Score => "similarity",
+            field => field.name(),
+        }
+    }
+
+    /// Parse a comma-separated field list such as `id,content,score`.
+    ///
+    /// `similarity` is accepted for `score`; repeated fields are kept once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the list is empty or names an unknown field.
+    pub fn parse_list(spec: &str) -> Result<Vec<Field>, Error> {
+        let mut fields = Vec::new();
+        for name in spec.split(',').map(str::trim) {
+            let field = Field::ALL
+                .into_iter()
+                .find(|field| field.name() == name || field.key() == name)
+                .ok_or_else(|| {
+                    let known: Vec<_> = Field::ALL.iter().map(|field| field.name()).collect();
+                    Error::InvalidInput(format!(
+                        "Unknown field '{}' (expected a comma-separated list of {})",
+                        name,
+                        known.join(", ")
+                    ))
+                })?;
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+        Ok(fields)
+    }
+
+    /// Value of the field for `memory`, null if it has none. `token_count`
+    /// is the token count of the content, if known.
+    pub fn value(self, memory: &Memory, token_count: Option<usize>) -> Value {
+        match self {
+            Field::Id => memory.id.clone().into(),
+            Field::ProjectId => memory.project_id.clone().into(),
+            Field::Title => memory.title.clone().into(),
+            Field::Content => memory.content.clone().into(),
+            Field::Score => memory.similarity.into(),
+            Field::Kind => memory.kind.to_string().into(),
+            Field::Origin => memory.origin.to_string().into(),
+            Field::Metadata => memory.metadata.clone().into(),
+            Field::Snippet => memory.snippet.clone().into(),
+            Field::Explanation => memory
+                .explanation
+                .as_ref()
+                .and_then(|explanation| serde_json::to_value(explanation).ok())
+                .unwrap_or(Value::Null),
+            Field::TokenCount => token_count.into(),
+            Field::CreatedAt => memory.created_at.clone().into(),
+            Field::UpdatedAt => memory.updated_at.clone().into(),
+        }
+    }
+}
+
+/// The selected fields of one memory, serialized as a JSON object with the
+/// fields in the order they were selected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectedFields(Vec<(Field, Value)>);
+
+impl SelectedFields {
+    /// Fields and their values, in order.
+    pub fn entries(&self) -> &[(Field, Value)] {
+        &self.0
+    }
+}
+
+impl Serialize for SelectedFields {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (field, value) in &self.0 {
+            map.serialize_entry(field.key(), value)?;
+        }
+        map.end()
+    }
+}
+
+/// Only `fields` of `memory`, in the given order. `token_count` is the token
+/// count of the content, if known.
+pub fn select_fields(
+    memory: &Memory,
+    fields: &[Field],
+    token_count: Option<usize>,
+) -> SelectedFields {
+    SelectedFields(
+        fields
+            .iter()
+            .map(|&field| (field, field.value(memory, token_count)))
+            .collect(),
+    )
+}
+
+/// Which memories [`MemoryStore::list_with`](crate::MemoryStore::list_with)
+/// returns, and which of their fields are shown.
+///
+/// # Example
+///
+/// ```
+/// use vipune::{Field, ListOptions, MemoryKind};
+/// use vipune::timeparse;
+///
+/// let options = ListOptions::default()
+///     .with_limit(50)
+///     .with_kind(MemoryKind::Task)
+///     .with_since(timeparse::parse("2 weeks ago").unwrap())
+///     .with_fields(vec![Field::Id]);
+/// assert_eq!(options.fields.as_deref(), Some(&[Field::Id][..]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ListOptions {
+    /// Maximum number of memories, newest first.
+    pub limit: usize,
+    /// Only memories of this kind.
+    pub kind: Option<MemoryKind>,
+    /// Only memories of this origin (see [`Origin::matches`]).
+    pub origin: Option<Origin>,
+    /// Only memories last updated at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only memories last updated before this time.
+    pub before: Option<DateTime<Utc>>,
+    /// Fields shown for each memory (`None` for all of `vipune list`'s).
+    pub fields: Option<Vec<Field>>,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIST_LIMIT,
+            kind: None,
+            origin: None,
+            since: None,
+            before: None,
+            fields: None,
+        }
+    }
+}
+
+impl ListOptions {
+    /// Return at most `limit` memories.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Only return memories of `kind`.
+    pub fn with_kind(mut self, kind: MemoryKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Only return memories written through `origin`; an import origin
+    /// without a source matches every import.
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Only return memories last updated at or after `since`.
+    pub fn with_since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only return memories last updated before `before`.
+    pub fn with_before(mut self, before: DateTime<Utc>) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Whether `memory` was last updated within the `since`/`before` range.
+    pub(crate) fn in_range(&self, memory: &Memory) -> bool {
+        let updated = memory.updated_at.as_str();
+        self.since
+            .is_none_or(|since| updated >= since.to_rfc3339().as_str())
+            && self
+                .before
+                .is_none_or(|before| updated < before.to_rfc3339().as_str())
+    }
+
+    /// Check that `since` is not after `before`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidInput`] for an empty range.
+    pub fn validate(&self) -> Result<(), Error> {
+        if let (Some(since), Some(before)) = (self.since, self.before) {
+            if since >= before {
+                return Err(Error::InvalidInput(format!(
+                    "Invalid date range: since {} is not before {}",
+                    since.to_rfc3339(),
+                    before.to_rfc3339()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Show only `fields`, in this order.
+    pub fn with_fields(mut self, fields: Vec<Field>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory() -> Memory {
+        Memory {
+            id: "m1".to_string(),
+            project_id: "p".to_string(),
+            content: "hello".to_string(),
+            metadata: Some(r#"{"tag":"x"}"#.to_string()),
+            kind: MemoryKind::Fact,
+            origin: Origin::default(),
+            title: None,
+            similarity: Some(0.5),
+            snippet: None,
+            explanation: None,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-02-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            Field::parse_list("id, content,score,similarity").unwrap(),
+            [Field::Id, Field::Content, Field::Score]
+        );
+        assert!(matches!(
+            Field::parse_list("id,body"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(Field::parse_list("").is_err());
+    }
+
+    #[test]
+    fn test_select_fields_keeps_order() {
+        let fields = Field::parse_list("updated_at,score,id,metadata,snippet").unwrap();
+        let json = serde_json::to_string(&select_fields(&memory(), &fields, None)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"updated_at":"2024-02-01T00:00:00Z","similarity":0.5,"id":"m1","metadata":"{\"tag\":\"x\"}","snippet":null}"#
+        );
+    }
+}
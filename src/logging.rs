@@ -0,0 +1,72 @@
+//! Diagnostic logging for the CLI.
+//!
+//! The library logs through the `log` facade; the binary routes records to
+//! stderr with `env_logger`. Verbosity comes from repeated `-v` flags or, when
+//! none are given, the `VIPUNE_LOG` environment variable (env_logger filter
+//! syntax, e.g. `debug` or `vipune::import=trace`).
+
+/// Environment variable holding the log filter used when `-v` is not given.
+pub const LOG_ENV_VAR: &str = "VIPUNE_LOG";
+
+/// Filter used when neither `-v` nor `VIPUNE_LOG` is set.
+const DEFAULT_FILTER: &str = "warn";
+
+/// Log filter for `-v` repeated `verbosity` times.
+///
+/// `-v` and `-vv` raise vipune's own modules to info and debug while keeping
+/// dependencies at warn; `-vvv` enables trace output everywhere.
+pub fn verbosity_filter(verbosity: u8) -> &'static str {
+    match verbosity {
+        0 => DEFAULT_FILTER,
+        1 => "warn,vipune=info",
+        2 => "warn,vipune=debug",
+        _ => "trace",
+    }
+}
+
+/// Choose the filter from `-v` count, falling back to `env_filter` (`VIPUNE_LOG`).
+fn select_filter(verbosity: u8, env_filter: Option<String>) -> String {
+    match env_filter {
+        Some(filter) if verbosity == 0 && !filter.trim().is_empty() => filter,
+        _ => verbosity_filter(verbosity).to_string(),
+    }
+}
+
+/// Install the stderr logger.
+///
+/// Safe to call more than once; later calls are ignored.
+pub fn init(verbosity: u8) {
+    let filter = select_filter(verbosity, std::env::var(LOG_ENV_VAR).ok());
+    let _ = env_logger::Builder::new()
+        .parse_filters(&filter)
+        .format_timestamp_millis()
+        .target(env_logger::Target::Stderr)
+        .try_init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity_levels() {
+        assert_eq!(verbosity_filter(0), "warn");
+        assert_eq!(verbosity_filter(1), "warn,vipune=info");
+        assert_eq!(verbosity_filter(2), "warn,vipune=debug");
+        assert_eq!(verbosity_filter(5), "trace");
+    }
+
+    #[test]
+    fn test_flags_take_precedence_over_env() {
+        assert_eq!(select_filter(0, None), "warn");
+        assert_eq!(
+            select_filter(0, Some("vipune::import=trace".to_string())),
+            "vipune::import=trace"
+        );
+        assert_eq!(select_filter(0, Some(" ".to_string())), "warn");
+        assert_eq!(
+            select_filter(2, Some("error".to_string())),
+            "warn,vipune=debug"
+        );
+    }
+}
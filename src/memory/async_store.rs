@@ -0,0 +1,391 @@
+//! Async adapter for tokio hosts (behind the `async` feature).
+//!
+//! [`AsyncMemoryStore`] wraps a [`SharedMemoryStore`] and runs each
+//! operation on tokio's blocking thread pool with
+//! [`spawn_blocking`](tokio::task::spawn_blocking), so embedding and SQLite
+//! work never stall the async executor. The store itself stays synchronous.
+
+use std::io;
+use std::panic;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::cancel::CancellationToken;
+use crate::config::Config;
+use crate::errors::Error;
+use crate::memory_types::{AddResult, UpdateResult};
+use crate::search_options::SearchOptions;
+use crate::sqlite::{Memory, MemoryKind, Visibility};
+
+use super::shared::SharedMemoryStore;
+use super::store::MemoryStore;
+
+/// A [`SharedMemoryStore`] with `async` methods, for web servers, MCP hosts
+/// and other tokio applications.
+///
+/// Cloning is cheap and clones share the same pool. Methods must be called
+/// from within a tokio runtime. A panic inside an operation is resumed in
+/// the awaiting task; if the runtime shuts down before an operation runs,
+/// it fails with `Error::Io` of kind `Interrupted`.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example() -> Result<(), vipune::Error> {
+/// use vipune::{AsyncMemoryStore, Config, SearchOptions, SharedMemoryStore};
+///
+/// let store = AsyncMemoryStore::new(
+///     "memories.db",
+///     "BAAI/bge-small-en-v1.5",
+///     Config::default(),
+///     SharedMemoryStore::DEFAULT_POOL_SIZE,
+/// )
+/// .await?;
+/// store
+///     .add_with_conflict("owner/repo", "Alice works at Microsoft", None, false)
+///     .await?;
+/// let results = store
+///     .query("owner/repo", "where does alice work", SearchOptions::default())
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+pub struct AsyncMemoryStore {
+    shared: Arc<SharedMemoryStore>,
+}
+
+impl From<SharedMemoryStore> for AsyncMemoryStore {
+    fn from(shared: SharedMemoryStore) -> Self {
+        Self::from_shared(Arc::new(shared))
+    }
+}
+
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+impl AsyncMemoryStore {
+    /// Open a pool of `pool_size` connections to the database at `db_path`,
+    /// as [`SharedMemoryStore::new`] does, without blocking the executor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SharedMemoryStore::new`].
+    pub async fn new(
+        db_path: impl Into<PathBuf>,
+        model_id: &str,
+        config: Config,
+        pool_size: usize,
+    ) -> Result<Self, Error> {
+        let db_path = db_path.into();
+        let model_id = model_id.to_string();
+        let shared =
+            blocking(move || SharedMemoryStore::new(&db_path, &model_id, config, pool_size))
+                .await??;
+        Ok(shared.into())
+    }
+
+    /// Async store over a private in-memory database (a single connection).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database schema cannot be created.
+    pub async fn new_in_memory(model_id: &str, config: Config) -> Result<Self, Error> {
+        let model_id = model_id.to_string();
+        let shared =
+            blocking(move || SharedMemoryStore::new_in_memory(&model_id, config)).await??;
+        Ok(shared.into())
+    }
+
+    /// Wrap a shared store that may also be used synchronously elsewhere.
+    pub fn from_shared(shared: Arc<SharedMemoryStore>) -> Self {
+        Self { shared }
+    }
+
+    /// The underlying shared store, for synchronous use.
+    pub fn shared(&self) -> &Arc<SharedMemoryStore> {
+        &self.shared
+    }
+
+    /// Run `f` with exclusive use of one pooled store on the blocking pool.
+    ///
+    /// Gives access to every [`MemoryStore`] operation, including those
+    /// without an `AsyncMemoryStore` counterpart.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if the runtime shuts down before `f` runs.
+    pub async fn with_store<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut MemoryStore) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let shared = Arc::clone(&self.shared);
+        blocking(move || shared.with_store(f)).await
+    }
+
+    /// See [`SharedMemoryStore::set_owner`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `owner` is empty, or if `visibility`
+    /// is private without an owner.
+    pub fn set_owner(&self, owner: Option<String>, visibility: Visibility) -> Result<(), Error> {
+        self.shared.set_owner(owner, visibility)
+    }
+
+    /// Whether the shared embedding engine has been loaded.
+    pub fn is_warm(&self) -> bool {
+        self.shared.is_warm()
+    }
+
+    /// See [`SharedMemoryStore::warm_up`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model cannot be loaded.
+    pub async fn warm_up(&self) -> Result<(), Error> {
+        self.with_store(|store| store.warm_up()).await?
+    }
+
+    /// See [`MemoryStore::add_with_conflict`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::add_with_conflict`].
+    pub async fn add_with_conflict(
+        &self,
+        project_id: &str,
+        content: &str,
+        metadata: Option<&str>,
+        force: bool,
+    ) -> Result<AddResult, Error> {
+        self.add_with_kind(project_id, content, metadata, MemoryKind::Other, force)
+            .await
+    }
+
+    /// See [`MemoryStore::add_with_kind`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::add_with_conflict`].
+    pub async fn add_with_kind(
+        &self,
+        project_id: &str,
+        content: &str,
+        metadata: Option<&str>,
+        kind: MemoryKind,
+        force: bool,
+    ) -> Result<AddResult, Error> {
+        let (project_id, content) = (project_id.to_string(), content.to_string());
+        let metadata = metadata.map(str::to_string);
+        self.with_store(move |store| {
+            store.add_with_kind(&project_id, &content, metadata.as_deref(), kind, force)
+        })
+        .await?
+    }
+
+    /// See [`MemoryStore::get`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database query fails.
+    pub async fn get(&self, id: &str) -> Result<Option<Memory>, Error> {
+        let id = id.to_string();
+        self.with_store(move |store| store.get(&id)).await?
+    }
+
+    /// See [`MemoryStore::list_filtered`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::list`].
+    pub async fn list(
+        &self,
+        project_id: &str,
+        limit: usize,
+        kind: Option<MemoryKind>,
+    ) -> Result<Vec<Memory>, Error> {
+        let project_id = project_id.to_string();
+        self.with_store(move |store| store.list_filtered(&project_id, limit, kind))
+            .await?
+    }
+
+    /// See [`MemoryStore::query`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::query`].
+    pub async fn query(
+        &self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<Memory>, Error> {
+        let (project_id, query) = (project_id.to_string(), query.to_string());
+        self.with_store(move |store| store.query(&project_id, &query, options))
+            .await?
+    }
+
+    /// See [`MemoryStore::query_with_cancel`]. Dropping the future does not
+    /// stop the search on the blocking pool; cancelling `cancel` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::query_with_cancel`].
+    pub async fn query_with_cancel(
+        &self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<Memory>, Error> {
+        let (project_id, query, cancel) =
+            (project_id.to_string(), query.to_string(), cancel.clone());
+        self.with_store(move |store| store.query_with_cancel(&project_id, &query, options, &cancel))
+            .await?
+    }
+
+    /// See [`MemoryStore::update`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory doesn't exist.
+    pub async fn update(&self, id: &str, content: &str) -> Result<(), Error> {
+        let (id, content) = (id.to_string(), content.to_string());
+        self.with_store(move |store| store.update(&id, &content))
+            .await?
+    }
+
+    /// See [`MemoryStore::append`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::append`].
+    pub async fn append(&self, id: &str, text: &str) -> Result<String, Error> {
+        let (id, text) = (id.to_string(), text.to_string());
+        self.with_store(move |store| store.append(&id, &text))
+            .await?
+    }
+
+    /// See [`MemoryStore::update_with_conflict`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::update_with_conflict`].
+    pub async fn update_with_conflict(
+        &self,
+        id: &str,
+        content: &str,
+        force: bool,
+    ) -> Result<UpdateResult, Error> {
+        let (id, content) = (id.to_string(), content.to_string());
+        self.with_store(move |store| store.update_with_conflict(&id, &content, force))
+            .await?
+    }
+
+    /// See [`MemoryStore::update_metadata`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory doesn't exist or the database write fails.
+    pub async fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), Error> {
+        let id = id.to_string();
+        let metadata = metadata.map(str::to_string);
+        self.with_store(move |store| store.update_metadata(&id, metadata.as_deref()))
+            .await?
+    }
+
+    /// See [`MemoryStore::touch`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory doesn't exist or the database write fails.
+    pub async fn touch(&self, id: &str) -> Result<(), Error> {
+        let id = id.to_string();
+        self.with_store(move |store| store.touch(&id)).await?
+    }
+
+    /// See [`MemoryStore::delete`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database query fails.
+    pub async fn delete(&self, id: &str) -> Result<bool, Error> {
+        let id = id.to_string();
+        self.with_store(move |store| store.delete(&id)).await?
+    }
+}
+
+/// Run `f` on the blocking pool, resuming its panic in the caller.
+async fn blocking<T, F>(f: F) -> Result<T, Error>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(value) => Ok(value),
+        Err(e) if e.is_panic() => panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(Error::Io(io::Error::new(io::ErrorKind::Interrupted, e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_async_store_runs_operations_off_the_executor() {
+        runtime().block_on(async {
+            let store = AsyncMemoryStore::new_in_memory("model", Config::default())
+                .await
+                .unwrap();
+            assert!(!store.is_warm());
+            let id = store
+                .with_store(|store| {
+                    store
+                        .db
+                        .insert("p", "Alice works at Microsoft", &[0.1; 384], None)
+                })
+                .await
+                .unwrap()
+                .unwrap();
+
+            let memory = store.get(&id).await.unwrap().unwrap();
+            assert_eq!(memory.content, "Alice works at Microsoft");
+            assert_eq!(store.list("p", 10, None).await.unwrap().len(), 1);
+            store
+                .update_metadata(&id, Some(r#"{"topic":"people"}"#))
+                .await
+                .unwrap();
+            let clone = store.clone();
+            let metadata = tokio::spawn(async move { clone.get(&id).await })
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap()
+                .metadata;
+            assert_eq!(metadata.as_deref(), Some(r#"{"topic":"people"}"#));
+            assert!(store.delete(&memory.id).await.unwrap());
+            assert!(store.get(&memory.id).await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_async_store_resumes_panics() {
+        let result = panic::catch_unwind(|| {
+            runtime().block_on(async {
+                let store = AsyncMemoryStore::new_in_memory("model", Config::default())
+                    .await
+                    .unwrap();
+                store.with_store(|_| panic!("boom")).await
+            })
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Audit log and write sequence of SQLite-backed stores.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::Error;
+use crate::project::ProjectId;
+use crate::sqlite::AuditEntry;
+
+use super::store::MemoryStore;
+
+/// How often [`MemoryStore::wait_for_seq`] checks the write sequence.
+const SEQ_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+impl MemoryStore {
+    #[must_use = "handle the error or results may be lost"]
+    /// Read the audit log of mutations for a project, oldest first.
+    ///
+    /// Every add, update and delete (including quota evictions and retention
+    /// pruning) is recorded with a timestamp and a SHA-256 hash of the payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - Project identifier
+    /// * `since` - Only return entries at or after this time
+    /// * `limit` - Maximum number of entries to return
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the limit is out of range or the database query fails.
+    pub fn audit_log(
+        &self,
+        project_id: &str,
+        since: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>, Error> {
+        let project = ProjectId::new(project_id)?;
+        let project_id = project.as_str();
+        self.validate_limit(limit)?;
+        let since = since.map(|t| t.to_rfc3339());
+        Ok(self.db.audit_log(project_id, since.as_deref(), limit)?)
+    }
+
+    /// Sequence number of the latest write to the store (add, update or
+    /// delete, in any project; 0 for a store never written to).
+    ///
+    /// Sequence numbers only grow, so a process that saw a write report
+    /// sequence `n` can make sure another store handle includes it with
+    /// [`MemoryStore::wait_for_seq`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database query fails.
+    pub fn write_seq(&self) -> Result<i64, Error> {
+        Ok(self.db.write_seq()?)
+    }
+
+    /// Wait up to `timeout` for the store to reach write sequence `min_seq`,
+    /// returning the sequence it is at. A zero timeout checks once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::StaleStore` if the store is still older than `min_seq`
+    /// when the timeout runs out, or an error if the database query fails.
+    pub fn wait_for_seq(&self, min_seq: i64, timeout: Duration) -> Result<i64, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let seq = self.write_seq()?;
+            if seq >= min_seq {
+                return Ok(seq);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::StaleStore { seq, min_seq });
+            }
+            std::thread::sleep(SEQ_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+}
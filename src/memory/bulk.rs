@@ -0,0 +1,112 @@
+//! Write-optimized bulk ingestion through [`MemoryStore::bulk_session`].
+
+use std::ops::{Deref, DerefMut};
+use std::sync::PoisonError;
+
+use crate::errors::Error;
+use crate::hooks::run_hook;
+
+use super::store::MemoryStore;
+
+/// A store in a bulk session (see [`MemoryStore::bulk_session`]).
+///
+/// Dereferences to the store, so memories are added through the usual
+/// methods. Nothing is visible to other connections until
+/// [`BulkSession::commit`], and hooks run only after it; dropping the session
+/// without committing rolls back every write made through it and discards
+/// its hooks.
+pub struct BulkSession<'s> {
+    store: &'s mut MemoryStore,
+    open: bool,
+}
+
+impl BulkSession<'_> {
+    /// Rebuild the full-text index, commit the session's writes and run the
+    /// hooks queued during the session.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the rebuild or commit fails; the session's writes
+    /// are then rolled back and no hook runs. Returns `Error::Hook` if a hook
+    /// fails under the `fail` policy (the writes stay committed).
+    pub fn commit(mut self) -> Result<(), Error> {
+        self.open = false;
+        let hooks = self.store.take_deferred_hooks();
+        self.store.db.end_bulk(true)?;
+        for (event, memory) in hooks {
+            run_hook(&self.store.config.hooks, event, &memory)?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for BulkSession<'_> {
+    type Target = MemoryStore;
+
+    fn deref(&self) -> &MemoryStore {
+        self.store
+    }
+}
+
+impl DerefMut for BulkSession<'_> {
+    fn deref_mut(&mut self) -> &mut MemoryStore {
+        self.store
+    }
+}
+
+impl Drop for BulkSession<'_> {
+    fn drop(&mut self) {
+        if !self.open {
+            return;
+        }
+        self.store.take_deferred_hooks();
+        if let Err(e) = self.store.db.end_bulk(false) {
+            log::warn!("Failed to roll back bulk session: {}", e);
+        }
+    }
+}
+
+impl MemoryStore {
+    /// Start a write-optimized session for ingesting many memories.
+    ///
+    /// Until the session commits, all writes share one transaction holding
+    /// the write lock, the full-text index is not updated per row (keyword
+    /// search does not see new memories until the commit rebuilds it) and
+    /// hooks are queued, with the memory as it was when they fired, until
+    /// the commit succeeds. A crash rolls the whole session back.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write lock cannot be taken or a transaction is
+    /// already open.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vipune::{Config, MemoryStore};
+    ///
+    /// let config = Config::default();
+    /// let mut store = MemoryStore::new(
+    ///     config.database_path.as_path(),
+    ///     &config.embedding_model,
+    ///     config.clone(),
+    /// )?;
+    /// let mut session = store.bulk_session()?;
+    /// for line in ["Alice works at Microsoft", "Bob works at Google"] {
+    ///     session.add_with_conflict("owner/repo", line, None, true)?;
+    /// }
+    /// session.commit()?;
+    /// # Ok::<(), vipune::Error>(())
+    /// ```
+    pub fn bulk_session(&mut self) -> Result<BulkSession<'_>, Error> {
+        self.db.begin_bulk()?;
+        *self
+            .deferred_hooks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(Vec::new());
+        Ok(BulkSession {
+            store: self,
+            open: true,
+        })
+    }
+}
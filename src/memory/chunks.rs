@@ -0,0 +1,49 @@
+//! Chunking of long memories into separately embedded chunk rows.
+
+use crate::chunking::split_into_chunks;
+use crate::errors::Error;
+use crate::storage::StorageBackend;
+
+use super::store::MemoryStore;
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Split `content` into chunks and store them under the memory `parent_id`.
+    ///
+    /// Does nothing unless `chunking.enabled` is set and the content is longer
+    /// than one chunk. Returns the number of chunks stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if embedding generation or the database write fails.
+    pub(crate) fn store_chunks(&mut self, parent_id: &str, content: &str) -> Result<usize, Error> {
+        let chunks = self.embed_chunks(content)?;
+        if chunks.is_empty() {
+            return Ok(0);
+        }
+        self.db.insert_chunks(parent_id, &chunks)
+    }
+
+    /// Split `content` into chunks and embed each one, ready for
+    /// [`StorageBackend::insert_chunks`].
+    ///
+    /// Returns no chunks unless `chunking.enabled` is set and the content is
+    /// longer than one chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if embedding generation fails.
+    pub(crate) fn embed_chunks(&mut self, content: &str) -> Result<Vec<(String, Vec<f32>)>, Error> {
+        let chunking = &self.config.chunking;
+        if !chunking.enabled {
+            return Ok(Vec::new());
+        }
+        let chunks = split_into_chunks(content, chunking.chunk_size, chunking.overlap);
+
+        let mut embedded = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            let embedding = self.embedder()?.embed_document(&chunk)?;
+            embedded.push((chunk, embedding));
+        }
+        Ok(embedded)
+    }
+}
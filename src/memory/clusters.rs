@@ -0,0 +1,296 @@
+//! Semantic clustering of a project's memories (`vipune clusters`).
+//!
+//! Spherical k-means over the stored embeddings: memories are assigned to
+//! the centroid they are most cosine-similar to, and centroids are the
+//! normalized mean of their members. Initial centroids are picked by
+//! farthest-point traversal from the memory closest to the project's mean
+//! direction, so the same memories always give the same clusters.
+
+use std::collections::HashMap;
+
+use crate::errors::Error;
+use crate::memory_types::{ClusterAssignment, Clustering, MemoryCluster};
+use crate::project::ProjectId;
+
+use super::stats::{dot, normalize, normalize_f64};
+use super::store::MemoryStore;
+
+/// Iterations after which k-means stops even if assignments still change.
+const MAX_ITERATIONS: usize = 100;
+
+/// Terms reported per cluster.
+const LABEL_TERMS: usize = 5;
+
+/// Shortest word counted as a term, in characters.
+const MIN_TERM_CHARS: usize = 3;
+
+impl MemoryStore {
+    #[must_use = "handle the error or results may be lost"]
+    /// Group a project's memories into at most `k` clusters by embedding
+    /// similarity, labeling each with the terms that best distinguish it.
+    ///
+    /// Fewer clusters are returned when the project has fewer than `k`
+    /// distinct embeddings; memories with an all-zero embedding are left
+    /// out. Reads stored embeddings only; the model is never loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `k` is 0, or error if the project ID
+    /// is invalid or the query fails.
+    pub fn clusters(&self, project_id: &str, k: usize) -> Result<Clustering, Error> {
+        let project = ProjectId::new(project_id)?;
+        if k == 0 {
+            return Err(Error::InvalidInput(
+                "Number of clusters must be at least 1".to_string(),
+            ));
+        }
+        let (memories, vectors): (Vec<_>, Vec<_>) = self
+            .db
+            .export_project(project.as_str())?
+            .into_iter()
+            .filter_map(|(memory, embedding)| normalize(&embedding).map(|v| (memory, v)))
+            .unzip();
+
+        let (labels, centroids) = kmeans(&vectors, k);
+        let contents: Vec<&str> = memories.iter().map(|m| m.content.as_str()).collect();
+        let terms = cluster_terms(&contents, &labels, centroids.len());
+
+        // Number clusters by size, largest first
+        let mut sizes = vec![0usize; centroids.len()];
+        for &label in &labels {
+            sizes[label] += 1;
+        }
+        let mut order: Vec<usize> = (0..centroids.len()).filter(|&c| sizes[c] > 0).collect();
+        order.sort_by_key(|&c| std::cmp::Reverse(sizes[c]));
+        let mut number = vec![0; centroids.len()];
+        for (position, &c) in order.iter().enumerate() {
+            number[c] = position;
+        }
+
+        let assignments: Vec<ClusterAssignment> = memories
+            .iter()
+            .zip(&vectors)
+            .zip(&labels)
+            .map(|((memory, vector), &label)| ClusterAssignment {
+                id: memory.id.clone(),
+                content: memory.content.clone(),
+                cluster: number[label],
+                similarity: dot(vector, &centroids[label]),
+            })
+            .collect();
+        let clusters = order
+            .iter()
+            .map(|&c| {
+                let similarity_sum: f64 = assignments
+                    .iter()
+                    .filter(|a| a.cluster == number[c])
+                    .map(|a| a.similarity)
+                    .sum();
+                MemoryCluster {
+                    cluster: number[c],
+                    size: sizes[c],
+                    terms: terms[c].clone(),
+                    cohesion: similarity_sum / sizes[c] as f64,
+                }
+            })
+            .collect();
+
+        Ok(Clustering {
+            project_id: project.as_str().to_string(),
+            clusters,
+            assignments,
+        })
+    }
+}
+
+/// Spherical k-means over unit `vectors`: the cluster of each vector and the
+/// (unit) centroid of each cluster. Clusters may end up empty.
+fn kmeans(vectors: &[Vec<f64>], k: usize) -> (Vec<usize>, Vec<Vec<f64>>) {
+    let mut centroids = initial_centroids(vectors, k);
+    let dims = vectors.first().map_or(0, Vec::len);
+    let mut labels = vec![usize::MAX; vectors.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (label, vector) in labels.iter_mut().zip(vectors) {
+            let nearest = nearest(vector, &centroids);
+            if *label != nearest {
+                *label = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+        let mut sums = vec![vec![0.0; dims]; centroids.len()];
+        for (vector, &label) in vectors.iter().zip(&labels) {
+            for (total, value) in sums[label].iter_mut().zip(vector) {
+                *total += value;
+            }
+        }
+        // An emptied cluster keeps its previous centroid
+        for (centroid, sum) in centroids.iter_mut().zip(&sums) {
+            if let Some(mean) = normalize_f64(sum) {
+                *centroid = mean;
+            }
+        }
+    }
+    (labels, centroids)
+}
+
+/// Index of the centroid most similar to `vector` (the first on ties).
+fn nearest(vector: &[f64], centroids: &[Vec<f64>]) -> usize {
+    let mut best = (0, f64::NEG_INFINITY);
+    for (index, centroid) in centroids.iter().enumerate() {
+        let similarity = dot(vector, centroid);
+        if similarity > best.1 {
+            best = (index, similarity);
+        }
+    }
+    best.0
+}
+
+/// Up to `k` initial centroids by farthest-point traversal: start from the
+/// vector closest to the mean direction, then repeatedly add the vector
+/// least similar to every centroid so far. Stops early when only
+/// duplicates of chosen centroids are left.
+fn initial_centroids(vectors: &[Vec<f64>], k: usize) -> Vec<Vec<f64>> {
+    let Some(dims) = vectors.first().map(Vec::len) else {
+        return Vec::new();
+    };
+    let mut sum = vec![0.0; dims];
+    for vector in vectors {
+        for (total, value) in sum.iter_mut().zip(vector) {
+            *total += value;
+        }
+    }
+    let first = normalize_f64(&sum).map_or(0, |direction| {
+        argmax(vectors.iter().map(|v| dot(v, &direction)))
+    });
+
+    let mut centroids = vec![vectors[first].clone()];
+    let mut closest: Vec<f64> = vectors.iter().map(|v| dot(v, &centroids[0])).collect();
+    while centroids.len() < k {
+        let next = argmax(closest.iter().map(|similarity| -similarity));
+        if closest[next] >= 1.0 - 1e-9 {
+            break;
+        }
+        centroids.push(vectors[next].clone());
+        for (similarity, vector) in closest.iter_mut().zip(vectors) {
+            *similarity = similarity.max(dot(vector, &vectors[next]));
+        }
+    }
+    centroids
+}
+
+/// Position of the largest value (the first on ties).
+fn argmax(values: impl Iterator<Item = f64>) -> usize {
+    let mut best = (0, f64::NEG_INFINITY);
+    for (index, value) in values.enumerate() {
+        if value > best.1 {
+            best = (index, value);
+        }
+    }
+    best.0
+}
+
+/// Lowercased words of `content` long enough to be terms (numbers excluded).
+fn words(content: &str) -> impl Iterator<Item = String> + '_ {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| {
+            word.chars().count() >= MIN_TERM_CHARS && !word.chars().all(|c| c.is_ascii_digit())
+        })
+        .map(str::to_lowercase)
+}
+
+/// The [`LABEL_TERMS`] best terms of each of `clusters` clusters: frequency
+/// in the cluster times BM25 inverse document frequency over all
+/// `contents`, best first (alphabetical on ties).
+fn cluster_terms(contents: &[&str], labels: &[usize], clusters: usize) -> Vec<Vec<String>> {
+    let mut frequencies: Vec<HashMap<String, usize>> = vec![HashMap::new(); clusters];
+    let mut document_frequency: HashMap<String, usize> = HashMap::new();
+    for (content, &label) in contents.iter().zip(labels) {
+        let mut seen: Vec<String> = Vec::new();
+        for word in words(content) {
+            *frequencies[label].entry(word.clone()).or_default() += 1;
+            if !seen.contains(&word) {
+                seen.push(word);
+            }
+        }
+        for word in seen {
+            *document_frequency.entry(word).or_default() += 1;
+        }
+    }
+
+    let n = contents.len() as f64;
+    let idf = |term: &str| {
+        let df = document_frequency[term] as f64;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    };
+    frequencies
+        .into_iter()
+        .map(|frequency| {
+            let mut scored: Vec<(f64, String)> = frequency
+                .into_iter()
+                .map(|(term, count)| (count as f64 * idf(&term), term))
+                .collect();
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+            scored
+                .into_iter()
+                .take(LABEL_TERMS)
+                .map(|(_, term)| term)
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(v: [f64; 3]) -> Vec<f64> {
+        normalize_f64(&v).unwrap()
+    }
+
+    #[test]
+    fn test_kmeans_separates_directions() {
+        let vectors = vec![
+            unit([1.0, 0.1, 0.0]),
+            unit([0.0, 1.0, 0.1]),
+            unit([1.0, 0.0, 0.1]),
+            unit([0.1, 1.0, 0.0]),
+            unit([0.0, 0.1, 1.0]),
+        ];
+        let (labels, centroids) = kmeans(&vectors, 3);
+        assert_eq!(centroids.len(), 3);
+        assert_eq!(labels[0], labels[2]);
+        assert_eq!(labels[1], labels[3]);
+        assert_ne!(labels[0], labels[1]);
+        assert_ne!(labels[4], labels[0]);
+        assert_ne!(labels[4], labels[1]);
+    }
+
+    #[test]
+    fn test_kmeans_stops_at_distinct_vectors() {
+        let vectors = vec![unit([1.0, 0.0, 0.0]), unit([1.0, 0.0, 0.0])];
+        let (labels, centroids) = kmeans(&vectors, 5);
+        assert_eq!(centroids.len(), 1);
+        assert_eq!(labels, vec![0, 0]);
+        assert_eq!(kmeans(&[], 3), (Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn test_cluster_terms_prefer_distinctive_words() {
+        let contents = [
+            "The database migration failed on the index",
+            "Database index rebuild after the migration",
+            "The login token expired for the user",
+            "User login needs a fresh token",
+        ];
+        let terms = cluster_terms(&contents, &[0, 0, 1, 1], 2);
+        assert_eq!(terms[0][..3], ["database", "index", "migration"]);
+        assert_eq!(terms[1][..3], ["login", "token", "user"]);
+        assert!(terms.iter().all(|t| t.len() <= LABEL_TERMS));
+        assert!(!terms[0].contains(&"the".to_string()));
+    }
+}
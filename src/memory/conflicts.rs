@@ -0,0 +1,202 @@
+//! Conflict detection for adds and updates: similar memories scored by
+//! similarity and term overlap.
+
+use std::collections::HashSet;
+
+use crate::errors::Error;
+use crate::memory_types::ConflictMemory;
+use crate::normalize::TextNormalization;
+use crate::search_options::SearchFilter;
+use crate::storage::StorageBackend;
+
+use super::store::MemoryStore;
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Filter leaving out the private memories of other owners than this
+    /// store's, which must not conflict with what it adds.
+    pub(crate) fn viewer_filter(&self) -> SearchFilter {
+        SearchFilter {
+            viewer: self.config.owner.clone(),
+            ..SearchFilter::default()
+        }
+    }
+
+    /// Memories in `project_id` whose conflict confidence with `content`
+    /// (embedded as `embedding`) reaches `similarity_threshold`, most
+    /// confident first. `exclude` leaves out the memory being updated.
+    pub(super) fn conflicts_with(
+        &self,
+        project_id: &str,
+        content: &str,
+        embedding: &[f32],
+        exclude: Option<&str>,
+    ) -> Result<Vec<ConflictMemory>, Error> {
+        let threshold = self.config.similarity_threshold;
+        let floor = self.config.conflicts.candidate_floor(threshold);
+        let filter = self.viewer_filter();
+        let similar = self
+            .metrics
+            .time_db(|| self.db.find_similar(project_id, embedding, floor, &filter))?;
+
+        let mut conflicts: Vec<ConflictMemory> = similar
+            .into_iter()
+            .filter(|m| exclude != Some(m.id.as_str()))
+            .filter_map(|memory| {
+                let overlap = term_overlap(&memory.content, content, &self.config.normalization);
+                let confidence = self
+                    .config
+                    .conflicts
+                    .confidence(memory.similarity.unwrap_or(0.0), overlap);
+                (confidence >= threshold)
+                    .then(|| ConflictMemory::scored(memory, overlap, confidence))
+            })
+            .collect();
+        conflicts.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        Ok(conflicts)
+    }
+}
+
+/// Jaccard index of the words of `a` and `b` (0.0 when neither has any).
+///
+/// Words are split like the default full-text tokenizer does (runs of
+/// letters and digits, case-insensitive) after `normalization`, so the
+/// overlap sees the same terms keyword search does.
+pub(crate) fn term_overlap(a: &str, b: &str, normalization: &TextNormalization) -> f64 {
+    let a = terms(a, normalization);
+    let b = terms(b, normalization);
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+fn terms(text: &str, normalization: &TextNormalization) -> HashSet<String> {
+    normalization
+        .apply(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, ConflictsConfig};
+    use crate::sqlite::embedding::cosine_similarity;
+
+    #[derive(serde::Deserialize)]
+    struct LabeledPair {
+        stored: String,
+        proposed: String,
+        conflict: bool,
+    }
+
+    /// Pairs of memories labeled by whether adding `proposed` next to
+    /// `stored` should be reported as a conflict.
+    fn corpus() -> Vec<LabeledPair> {
+        include_str!("../../tests/fixtures/conflict_corpus.jsonl")
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    /// Best accuracy of `(score, conflict)` pairs over all thresholds, and
+    /// the threshold reaching it.
+    fn best_threshold(scores: &[(f64, bool)]) -> (f64, f64) {
+        let mut best = (0.0, 0.0);
+        for &(threshold, _) in scores {
+            let correct = scores
+                .iter()
+                .filter(|(score, conflict)| (*score >= threshold) == *conflict)
+                .count();
+            let accuracy = correct as f64 / scores.len() as f64;
+            if accuracy > best.0 {
+                best = (accuracy, threshold);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_term_overlap() {
+        let none = TextNormalization::default();
+        assert_eq!(
+            term_overlap("Deploy with make", "deploy WITH make", &none),
+            1.0
+        );
+        assert_eq!(term_overlap("a b", "c d", &none), 0.0);
+        // {alice, works, at, on, azure} shared of 7 words
+        let overlap = term_overlap(
+            "Alice works at Microsoft on Azure",
+            "Alice works at Google on Azure",
+            &none,
+        );
+        assert!((overlap - 5.0 / 7.0).abs() < 1e-9);
+        assert_eq!(term_overlap("", "", &none), 0.0);
+
+        let strip = TextNormalization {
+            strip_accents: true,
+            ..TextNormalization::default()
+        };
+        assert_eq!(term_overlap("café", "cafe", &none), 0.0);
+        assert_eq!(term_overlap("café", "cafe", &strip), 1.0);
+    }
+
+    #[test]
+    fn test_corpus_overlap_separates_labels() {
+        let corpus = corpus();
+        let none = TextNormalization::default();
+        let mean = |conflict: bool| {
+            let overlaps: Vec<f64> = corpus
+                .iter()
+                .filter(|pair| pair.conflict == conflict)
+                .map(|pair| term_overlap(&pair.stored, &pair.proposed, &none))
+                .collect();
+            assert!(!overlaps.is_empty());
+            overlaps.iter().sum::<f64>() / overlaps.len() as f64
+        };
+        assert!(mean(true) > 0.5);
+        assert!(mean(false) < 0.2);
+    }
+
+    #[ignore]
+    #[test]
+    fn test_integration_calibrate_conflict_weights() {
+        // Full integration test with real model
+        // Requires: cargo test -- --ignored
+        let mut store =
+            MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+        let none = TextNormalization::default();
+        let mut signals = Vec::new();
+        for pair in corpus() {
+            let stored = store.embed_document_checked(&pair.stored).unwrap();
+            let proposed = store.embed_document_checked(&pair.proposed).unwrap();
+            let similarity = cosine_similarity(&stored, &proposed).unwrap();
+            let overlap = term_overlap(&pair.stored, &pair.proposed, &none);
+            signals.push((similarity, overlap, pair.conflict));
+        }
+
+        // Overlap weights from 0 (similarity alone) to 1 (equal weights)
+        let mut results = Vec::new();
+        for step in 0..=10 {
+            let conflicts = ConflictsConfig {
+                overlap_weight: f64::from(step) / 10.0,
+                ..ConflictsConfig::default()
+            };
+            let scores: Vec<(f64, bool)> = signals
+                .iter()
+                .map(|&(s, o, c)| (conflicts.confidence(s, o), c))
+                .collect();
+            let (accuracy, threshold) = best_threshold(&scores);
+            results.push((conflicts.overlap_weight, accuracy, threshold));
+        }
+        // (overlap_weight, accuracy, similarity_threshold) in the message
+        // calibrate the defaults
+        assert!(
+            results.iter().all(|&(_, accuracy, _)| accuracy > 0.5),
+            "{results:?}"
+        );
+    }
+}
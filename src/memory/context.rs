@@ -0,0 +1,247 @@
+//! Context packs: the memories relevant to a task, fitted to a token budget.
+
+use std::collections::HashMap;
+
+use crate::errors::Error;
+use crate::memory_types::{ContextEntry, ContextPack};
+use crate::search_options::SearchOptions;
+use crate::sqlite::Memory;
+use crate::sqlite::embedding::cosine_similarity;
+use crate::storage::StorageBackend;
+
+use super::store::MemoryStore;
+
+/// Characters per token assumed when estimating prompt size.
+///
+/// Roughly right for English text with common LLM tokenizers; the embedding
+/// model's tokenizer is not used since it truncates at 512 tokens.
+const CHARS_PER_TOKEN: usize = 4;
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Assemble the memories most relevant to `task` into a prompt block of
+    /// at most `budget` (estimated) tokens.
+    ///
+    /// Candidates are retrieved with `options` (typically hybrid search with
+    /// recency weighting), so their order reflects relevance and recency.
+    /// Candidates at least `similarity_threshold` similar to a higher-ranked
+    /// one are dropped as near-duplicates. The rest are packed in order;
+    /// one that does not fit is skipped so smaller ones further down can
+    /// still use the remaining budget.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `budget` is 0, and otherwise the same
+    /// errors as [`MemoryStore::query`].
+    pub fn context(
+        &mut self,
+        project_id: &str,
+        task: &str,
+        budget: usize,
+        options: SearchOptions,
+    ) -> Result<ContextPack, Error> {
+        if budget == 0 {
+            return Err(Error::InvalidInput(
+                "Context budget must be greater than 0".to_string(),
+            ));
+        }
+        let candidates = self.query(project_id, task, options)?;
+        let ids: Vec<&str> = candidates.iter().map(|m| m.id.as_str()).collect();
+        let embeddings = self.db.embeddings_for(&ids)?;
+        let (candidates, duplicates) =
+            deduplicate(candidates, &embeddings, self.config.similarity_threshold)?;
+
+        let mut pack = pack(task.trim(), budget, candidates);
+        pack.duplicates = duplicates;
+        log::debug!(
+            "Packed {} memories into {}/{} tokens ({} duplicates, {} omitted)",
+            pack.memories.len(),
+            pack.tokens,
+            budget,
+            pack.duplicates,
+            pack.omitted
+        );
+        Ok(pack)
+    }
+}
+
+/// Drop memories at least `threshold` similar to an earlier kept one.
+///
+/// Returns the kept memories, in order, and the number dropped.
+fn deduplicate(
+    candidates: Vec<Memory>,
+    embeddings: &HashMap<String, Vec<f32>>,
+    threshold: f64,
+) -> Result<(Vec<Memory>, usize), Error> {
+    let mut kept: Vec<Memory> = Vec::with_capacity(candidates.len());
+    let mut kept_embeddings: Vec<&[f32]> = Vec::new();
+    let mut duplicates = 0;
+    for memory in candidates {
+        if let Some(embedding) = embeddings.get(&memory.id) {
+            let mut duplicate = false;
+            for other in &kept_embeddings {
+                if cosine_similarity(embedding, other)? >= threshold {
+                    duplicate = true;
+                    break;
+                }
+            }
+            if duplicate {
+                duplicates += 1;
+                continue;
+            }
+            kept_embeddings.push(embedding);
+        }
+        kept.push(memory);
+    }
+    Ok((kept, duplicates))
+}
+
+/// Render `candidates` into a prompt block of at most `budget` tokens.
+fn pack(task: &str, budget: usize, candidates: Vec<Memory>) -> ContextPack {
+    let mut text = format!(
+        "Relevant memories for: {}\nCite memories by their [id].\n",
+        task
+    );
+    let mut tokens = estimate_tokens(&text);
+    let mut memories = Vec::new();
+    let mut omitted = 0;
+
+    for memory in candidates {
+        let entry = render_entry(&memory);
+        let cost = estimate_tokens(&entry);
+        if tokens + cost > budget {
+            omitted += 1;
+            continue;
+        }
+        text.push_str(&entry);
+        tokens += cost;
+        memories.push(ContextEntry {
+            score: memory.similarity.unwrap_or(0.0),
+            id: memory.id,
+            content: memory.content,
+            kind: memory.kind,
+            updated_at: memory.updated_at,
+            tokens: cost,
+        });
+    }
+
+    ContextPack {
+        task: task.to_string(),
+        budget,
+        tokens,
+        text,
+        memories,
+        duplicates: 0,
+        omitted,
+    }
+}
+
+/// One memory as it appears in the prompt block:
+/// `[id] (kind, YYYY-MM-DD) content`, continuation lines indented.
+fn render_entry(memory: &Memory) -> String {
+    let date = memory.updated_at.get(..10).unwrap_or(&memory.updated_at);
+    let content = memory.content.trim().replace('\n', "\n  ");
+    format!(
+        "\n[{}] ({}, {}) {}\n",
+        memory.id, memory.kind, date, content
+    )
+}
+
+/// Estimated LLM tokens in `text`.
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::{MemoryKind, Origin};
+
+    fn memory(id: &str, content: &str, score: f64) -> Memory {
+        Memory {
+            id: id.to_string(),
+            project_id: "proj".to_string(),
+            content: content.to_string(),
+            metadata: None,
+            kind: MemoryKind::Fact,
+            origin: Origin::default(),
+            title: None,
+            similarity: Some(score),
+            snippet: None,
+            explanation: None,
+            created_at: "2026-01-05T09:00:00+00:00".to_string(),
+            updated_at: "2026-01-05T09:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_pack_renders_citations() {
+        let pack = pack(
+            "fix login",
+            1000,
+            vec![
+                memory("a", "Sessions expire after 30 minutes", 0.9),
+                memory("b", "Line one\nLine two", 0.5),
+            ],
+        );
+        assert_eq!(pack.memories.len(), 2);
+        assert_eq!(pack.omitted, 0);
+        assert!(pack.text.starts_with("Relevant memories for: fix login\n"));
+        assert!(
+            pack.text
+                .contains("[a] (fact, 2026-01-05) Sessions expire after 30 minutes\n")
+        );
+        assert!(
+            pack.text
+                .contains("[b] (fact, 2026-01-05) Line one\n  Line two\n")
+        );
+        let header = "Relevant memories for: fix login\nCite memories by their [id].\n";
+        assert_eq!(
+            pack.tokens,
+            estimate_tokens(header) + pack.memories.iter().map(|m| m.tokens).sum::<usize>()
+        );
+        assert_eq!(pack.memories[0].score, 0.9);
+    }
+
+    #[test]
+    fn test_pack_skips_entries_over_budget() {
+        let header_tokens = pack("task", 1000, Vec::new()).tokens;
+        let short = memory("s", "short", 0.5);
+        let budget = header_tokens + estimate_tokens(&render_entry(&short));
+
+        let pack = pack(
+            "task",
+            budget,
+            vec![memory("l", &"long ".repeat(100), 0.9), short],
+        );
+        let ids: Vec<&str> = pack.memories.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["s"]);
+        assert_eq!(pack.omitted, 1);
+        assert_eq!(pack.tokens, budget);
+    }
+
+    #[test]
+    fn test_deduplicate_drops_near_duplicates() {
+        let embeddings = HashMap::from([
+            ("a".to_string(), vec![1.0, 0.0]),
+            ("b".to_string(), vec![0.99, 0.01]),
+            ("c".to_string(), vec![0.0, 1.0]),
+        ]);
+        let candidates = vec![
+            memory("a", "first", 0.9),
+            memory("b", "near copy", 0.8),
+            memory("c", "different", 0.7),
+            memory("d", "no embedding", 0.6),
+        ];
+        let (kept, duplicates) = deduplicate(candidates, &embeddings, 0.85).unwrap();
+        let ids: Vec<&str> = kept.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "d"]);
+        assert_eq!(duplicates, 1);
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+}
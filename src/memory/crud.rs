@@ -1,18 +1,34 @@
 //! CRUD operations for the memory store.
 
+use chrono::{DateTime, Utc};
+
 use crate::errors::Error;
-use crate::memory_types::{AddResult, ConflictMemory};
-use crate::sqlite::Memory;
+use crate::hooks::HookEvent;
+use crate::list_options::ListOptions;
+use crate::memory_types::{AddPreview, AddResult, UpdateResult};
+use crate::project::ProjectId;
+use crate::sqlite::{Memory, MemoryKind, NewMemory};
+use crate::storage::StorageBackend;
 
+use super::quota::payload_bytes;
 use super::store::MemoryStore;
 
-impl MemoryStore {
+impl<B: StorageBackend> MemoryStore<B> {
     #[must_use = "handle the error or results may be lost"]
     /// Add a memory with conflict detection.
     ///
     /// Checks for similar existing memories before adding. If conflicts are found
     /// (similarity >= threshold), returns conflicts details without storing.
     ///
+    /// With `chunking.enabled`, content longer than one chunk is also stored as
+    /// linked chunks so that all of it is searchable.
+    ///
+    /// With `retention.prune_on_add` enabled, expired memories in the project are
+    /// pruned first. When a configured quota is reached, the oldest memories are evicted if
+    /// `evict_on_quota` is enabled; otherwise `Error::QuotaExceeded` is returned.
+    ///
+    /// The `hooks.on_add` command, if configured, runs after the memory is stored.
+    ///
     /// # Arguments
     ///
     /// * `project_id` - Project identifier (e.g., git repo URL or user-defined)
@@ -30,8 +46,13 @@ impl MemoryStore {
     /// Returns error if:
     /// - Input is empty
     /// - Input exceeds 100,000 characters
+    /// - `secret_scan` is enabled and the content looks like a secret
+    /// - The metadata does not fit the configured `metadata_schema`
+    /// - A quota is reached and eviction is disabled
     /// - Embedding generation fails
     /// - Database operations fail
+    /// - The `on_add` hook fails under the `fail` policy (the memory is stored regardless)
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
     pub fn add_with_conflict(
         &mut self,
         project_id: &str,
@@ -39,30 +60,57 @@ impl MemoryStore {
         metadata: Option<&str>,
         force: bool,
     ) -> Result<AddResult, Error> {
-        Self::validate_input_length(content)?;
+        self.add_with_kind(project_id, content, metadata, MemoryKind::Other, force)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Add a memory of the given kind with conflict detection.
+    ///
+    /// Behaves like [`MemoryStore::add_with_conflict`]; conflicts are detected
+    /// against memories of every kind.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::add_with_conflict`].
+    pub fn add_with_kind(
+        &mut self,
+        project_id: &str,
+        content: &str,
+        metadata: Option<&str>,
+        kind: MemoryKind,
+        force: bool,
+    ) -> Result<AddResult, Error> {
+        let project = ProjectId::new(project_id)?;
+        let project_id = project.as_str();
+        let content = &self.prepare_content(content)?;
+        self.config.metadata_schema.check(metadata)?;
+        if self.config.retention.prune_on_add {
+            self.prune_project(project_id)?;
+        }
         if force {
-            let embedding = self.embedder()?.embed(content)?;
-            let id = self.db.insert(project_id, content, &embedding, metadata)?;
+            let embedding = self.embed_document_checked(content)?;
+            self.enforce_quota(project_id, payload_bytes(content, &embedding, metadata))?;
+            let id = self.insert_now(project_id, content, &embedding, metadata, kind)?;
+            self.store_chunks(&id, content)?;
+            self.store_token_embeddings(&id, content)?;
+            self.metrics.record_add();
+            self.fire_hook(HookEvent::Add, &id)?;
             return Ok(AddResult::Added { id });
         }
 
-        let embedding = self.embedder()?.embed(content)?;
-        let similars =
-            self.db
-                .find_similar(project_id, &embedding, self.config.similarity_threshold)?;
-        let conflicts: Vec<ConflictMemory> = similars
-            .into_iter()
-            .map(|m| ConflictMemory {
-                id: m.id,
-                content: m.content,
-                similarity: m.similarity.unwrap_or(0.0),
-            })
-            .collect();
+        let embedding = self.embed_document_checked(content)?;
+        let conflicts = self.conflicts_with(project_id, content, &embedding, None)?;
 
         if conflicts.is_empty() {
-            let id = self.db.insert(project_id, content, &embedding, metadata)?;
+            self.enforce_quota(project_id, payload_bytes(content, &embedding, metadata))?;
+            let id = self.insert_now(project_id, content, &embedding, metadata, kind)?;
+            self.store_chunks(&id, content)?;
+            self.store_token_embeddings(&id, content)?;
+            self.metrics.record_add();
+            self.fire_hook(HookEvent::Add, &id)?;
             Ok(AddResult::Added { id })
         } else {
+            self.metrics.record_conflict();
             Ok(AddResult::Conflicts {
                 proposed: content.to_string(),
                 conflicts,
@@ -70,18 +118,84 @@ impl MemoryStore {
         }
     }
 
+    #[must_use = "handle the error or results may be lost"]
+    /// Report what [`MemoryStore::add_with_kind`] would do with `content`,
+    /// without writing anything.
+    ///
+    /// Runs the same validation, embedding and conflict detection as an add.
+    /// Quotas are not checked and no retention pruning or hook runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the content is empty, too long or looks like a secret
+    /// (with `secret_scan`), embedding generation fails or the database query
+    /// fails.
+    pub fn preview_add(&mut self, project_id: &str, content: &str) -> Result<AddPreview, Error> {
+        let project = ProjectId::new(project_id)?;
+        let project_id = project.as_str();
+        let content = &self.prepare_content(content)?;
+        let embedding = self.embed_document_checked(content)?;
+        let conflicts = self.conflicts_with(project_id, content, &embedding, None)?;
+        if conflicts.is_empty() {
+            Ok(AddPreview::WouldAdd)
+        } else {
+            Ok(AddPreview::Conflicts {
+                proposed: content.to_string(),
+                conflicts,
+            })
+        }
+    }
+
+    /// Insert a memory timestamped with the current time and labeled with
+    /// this store's owner and visibility, detecting its language if enabled
+    /// and recording the model revision.
+    pub(super) fn insert_now(
+        &self,
+        project_id: &str,
+        content: &str,
+        embedding: &[f32],
+        metadata: Option<&str>,
+        kind: MemoryKind,
+    ) -> Result<String, Error> {
+        let now = Utc::now().to_rfc3339();
+        let id = self.metrics.time_db(|| {
+            self.db.insert(&NewMemory {
+                project_id,
+                content,
+                embedding,
+                metadata,
+                kind,
+                origin: &self.origin,
+                created_at: &now,
+                updated_at: &now,
+                owner: self.config.owner.as_deref(),
+                visibility: self.config.visibility,
+            })
+        })?;
+        self.store_language(&id, content)?;
+        self.store_model_revision(&id)?;
+        Ok(id)
+    }
+
     #[must_use = "handle the error or results may be lost"]
     /// Get a specific memory by ID.
     ///
-    /// Returns `None` if the memory doesn't exist.
+    /// Returns `None` if the memory doesn't exist. A found memory has the
+    /// access recorded for frecency ranking.
     pub fn get(&self, id: &str) -> Result<Option<Memory>, Error> {
-        Ok(self.db.get(id)?)
+        let memory = self.db.get(id)?;
+        if memory.is_some() {
+            self.track_access(&[id]);
+        }
+        Ok(memory)
     }
 
     #[must_use = "handle the error or results may be lost"]
     /// List all memories for a project.
     ///
-    /// Returns memories ordered by creation time (newest first).
+    /// Returns memories ordered by creation time (newest first). With an
+    /// owner set, other owners' private memories are left out (see
+    /// [`MemoryStore::set_owner`]).
     ///
     /// # Arguments
     ///
@@ -92,17 +206,89 @@ impl MemoryStore {
     ///
     /// Returns error if:
     /// - Limit is 0
-    /// - Limit exceeds MAX_SEARCH_LIMIT
+    /// - Limit exceeds the configured `limits.max_search_limit`
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
     pub fn list(&self, project_id: &str, limit: usize) -> Result<Vec<Memory>, Error> {
-        use super::store::validate_limit;
-        validate_limit(limit)?;
-        Ok(self.db.list(project_id, limit)?)
+        self.list_filtered(project_id, limit, None)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// List memories for a project, optionally only those of one kind.
+    ///
+    /// Returns memories ordered by creation time (newest first).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::list`].
+    pub fn list_filtered(
+        &self,
+        project_id: &str,
+        limit: usize,
+        kind: Option<MemoryKind>,
+    ) -> Result<Vec<Memory>, Error> {
+        let project = ProjectId::new(project_id)?;
+        let project_id = project.as_str();
+        self.validate_limit(limit)?;
+        self.db
+            .list(project_id, limit, kind, None, self.config.owner.as_deref())
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// List memories for a project as `options` says, newest first.
+    ///
+    /// `options.fields` does not change what is loaded; pass it to
+    /// [`select_fields`](crate::select_fields) to show only those fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::list`], and
+    /// `Error::InvalidInput` if `options.since` is not before
+    /// `options.before`.
+    pub fn list_with(&self, project_id: &str, options: &ListOptions) -> Result<Vec<Memory>, Error> {
+        let project = ProjectId::new(project_id)?;
+        self.validate_limit(options.limit)?;
+        options.validate()?;
+        self.db
+            .list_with(project.as_str(), options, self.config.owner.as_deref())
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Memories in a project whose content is exactly `content`, or equal
+    /// up to case with `ignore_case`, newest first.
+    ///
+    /// Looked up by content hash, without embedding anything, so agents can
+    /// recover the ID of a memory they stored. `content` goes through the
+    /// content filters first, so the text given to an add finds the memory
+    /// it stored. Only memories the store's owner may see are returned (see
+    /// [`MemoryStore::set_owner`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the project ID is invalid, a content filter fails,
+    /// `content` is empty or exceeds the configured
+    /// `limits.max_input_length`, or the lookup fails.
+    pub fn find_exact(
+        &self,
+        project_id: &str,
+        content: &str,
+        ignore_case: bool,
+    ) -> Result<Vec<Memory>, Error> {
+        let project = ProjectId::new(project_id)?;
+        let content = self.filter_content(content)?;
+        self.validate_input_length(&content)?;
+        self.db.find_by_content(
+            project.as_str(),
+            &content,
+            ignore_case,
+            self.config.owner.as_deref(),
+        )
     }
 
     #[must_use = "handle the error or results may be lost"]
     /// Update a memory's content.
     ///
-    /// Generates a new embedding for the updated content and persists it.
+    /// Generates a new embedding for the updated content and persists it, and
+    /// re-chunks the content if chunking is enabled.
     /// The memory ID, project ID, and creation timestamp remain unchanged.
     ///
     /// # Arguments
@@ -112,21 +298,193 @@ impl MemoryStore {
     ///
     /// # Errors
     ///
-    /// Returns error if the memory doesn't exist.
+    /// Returns error if the memory doesn't exist, or `Error::PotentialSecret`
+    /// if `secret_scan` is enabled and the content looks like a secret.
     pub fn update(&mut self, id: &str, content: &str) -> Result<(), Error> {
-        Self::validate_input_length(content)?;
-        let embedding = self.embedder()?.embed(content)?;
-        Ok(self.db.update(id, content, &embedding)?)
+        let content = &self.prepare_content(content)?;
+        let embedding = self.embed_document_checked(content)?;
+        self.apply_update(id, content, &embedding)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Add detail to a memory instead of storing a near-duplicate of it.
+    ///
+    /// `text` goes through the content filters and checks of an update and
+    /// is appended to the content as a paragraph prefixed with the current
+    /// time (`[2026-01-15 10:30 UTC] ...`). The combined content is
+    /// re-embedded and re-chunked; there is no conflict check, since the
+    /// memory is expected to resemble what it is being added to. The old
+    /// content is kept as an earlier version ([`MemoryStore::history`]).
+    ///
+    /// Returns the new content.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if the memory doesn't exist, or the errors
+    /// of [`MemoryStore::update`] for the addition and the combined content.
+    pub fn append(&mut self, id: &str, text: &str) -> Result<String, Error> {
+        let addition = self.prepare_content(text)?;
+        let memory = self
+            .db
+            .get(id)?
+            .ok_or_else(|| Error::NotFound("memory not found".to_string()))?;
+        let content = appended(&memory.content, &addition, Utc::now());
+        self.validate_input_length(&content)?;
+        let embedding = self.embed_document_checked(&content)?;
+        self.apply_update(id, &content, &embedding)?;
+        Ok(content)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Update a memory's content with conflict detection.
+    ///
+    /// Like [`MemoryStore::update`], but first checks the new content against
+    /// the other memories of the same project. If any is at least
+    /// `similarity_threshold` similar, returns the conflicts without changing
+    /// anything. The memory being updated never conflicts with itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Memory ID to update
+    /// * `content` - New content for the memory
+    /// * `force` - If true, bypass conflict detection and update regardless
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory doesn't exist, the content is empty, too
+    /// long or looks like a secret (with `secret_scan`), embedding generation
+    /// fails or database operations fail.
+    pub fn update_with_conflict(
+        &mut self,
+        id: &str,
+        content: &str,
+        force: bool,
+    ) -> Result<UpdateResult, Error> {
+        let content = &self.prepare_content(content)?;
+        let memory = self
+            .db
+            .get(id)?
+            .ok_or_else(|| Error::NotFound("memory not found".to_string()))?;
+        let embedding = self.embed_document_checked(content)?;
+
+        if !force {
+            let conflicts =
+                self.conflicts_with(&memory.project_id, content, &embedding, Some(id))?;
+            if !conflicts.is_empty() {
+                return Ok(UpdateResult::Conflicts {
+                    proposed: content.to_string(),
+                    conflicts,
+                });
+            }
+        }
+
+        self.apply_update(id, content, &embedding)?;
+        Ok(UpdateResult::Updated { id: id.to_string() })
+    }
+
+    /// Persist new content and its embedding, then re-chunk it.
+    fn apply_update(&mut self, id: &str, content: &str, embedding: &[f32]) -> Result<(), Error> {
+        self.db.update(id, content, embedding)?;
+        self.store_language(id, content)?;
+        self.store_model_revision(id)?;
+        self.db.delete_chunks(id)?;
+        self.store_chunks(id, content)?;
+        self.store_token_embeddings(id, content)?;
+        self.fire_hook(HookEvent::Update, id)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Replace a memory's JSON metadata, or clear it with `None`.
+    ///
+    /// Content, embedding and chunks are left untouched, so this is much
+    /// cheaper than [`MemoryStore::update`] and doesn't shift the memory's
+    /// position in semantic search.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the metadata does not fit the configured
+    /// `metadata_schema`, the memory doesn't exist or the database write fails.
+    pub fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), Error> {
+        self.config.metadata_schema.check(metadata)?;
+        self.db.update_metadata(id, metadata)?;
+        self.fire_hook(HookEvent::Update, id)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Set a memory's title, which list output shows instead of the content
+    /// and keyword search ranks with the metadata fields. With `None` the
+    /// title is derived from the first sentence of the content again.
+    ///
+    /// A set title is kept when the content is updated; a derived one
+    /// follows the content.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the title is blank, or error if the
+    /// database write fails.
+    pub fn set_title(&self, id: &str, title: Option<&str>) -> Result<(), Error> {
+        let title = title.map(str::trim);
+        if title.is_some_and(str::is_empty) {
+            return Err(Error::InvalidInput("Title cannot be empty".to_string()));
+        }
+        self.db.set_title(id, title)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Mark a memory as current by bumping its `updated_at`, without
+    /// re-embedding it or changing its content or metadata.
+    ///
+    /// Restarts its retention TTL and review idle time. No hook runs, since
+    /// nothing a hook could act on changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if the memory doesn't exist, or error if
+    /// the database write fails.
+    pub fn touch(&self, id: &str) -> Result<(), Error> {
+        self.db.touch(id)
     }
 
     #[must_use = "handle the error or results may be lost"]
     /// Delete a memory.
     ///
+    /// With `trash_retention_days` set, the memory is moved to the trash
+    /// instead, from which [`MemoryStore::restore`] brings it back until
+    /// pruning removes it.
+    ///
     /// # Returns
     ///
     /// - `Ok(true)` if memory was deleted
     /// - `Ok(false)` if memory didn't exist
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database write fails, or `Error::Hook` if the
+    /// `on_delete` hook fails under the `fail` policy (the memory is deleted
+    /// regardless).
     pub fn delete(&self, id: &str) -> Result<bool, Error> {
-        Ok(self.db.delete(id)?)
+        // The hook needs the memory, so read it before it is gone
+        let memory = match HookEvent::Delete.command(&self.config.hooks) {
+            Some(_) => self.db.get(id)?,
+            None => None,
+        };
+        let deleted = match self.config.trash_retention_days {
+            Some(_) => self.db.trash(id)?,
+            None => self.db.delete(id)?,
+        };
+        if let Some(memory) = memory.filter(|_| deleted) {
+            self.dispatch_hook(HookEvent::Delete, memory)?;
+        }
+        Ok(deleted)
     }
 }
+
+/// `content` with `addition` appended as a paragraph stamped with `now`.
+fn appended(content: &str, addition: &str, now: DateTime<Utc>) -> String {
+    format!(
+        "{}\n\n[{}] {}",
+        content.trim_end(),
+        now.format("%Y-%m-%d %H:%M UTC"),
+        addition.trim()
+    )
+}
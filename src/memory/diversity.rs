@@ -0,0 +1,134 @@
+//! Maximal Marginal Relevance (MMR) re-ranking of search results.
+
+use std::collections::HashMap;
+
+use crate::sqlite::Memory;
+use crate::sqlite::embedding::cosine_similarity;
+
+/// Select up to `limit` candidates by Maximal Marginal Relevance.
+///
+/// Greedily picks the candidate maximizing
+/// `(1 - diversity) * relevance - diversity * max_similarity_to_selected`.
+/// Relevance is the candidate score min-max normalized over the pool, which
+/// puts RRF scores on the same scale as cosine similarity. Candidates without
+/// a stored embedding are never penalized.
+pub(super) fn mmr_rerank(
+    candidates: Vec<Memory>,
+    embeddings: &HashMap<String, Vec<f32>>,
+    limit: usize,
+    diversity: f64,
+) -> Vec<Memory> {
+    let scores: Vec<f64> = candidates
+        .iter()
+        .map(|m| m.similarity.unwrap_or(0.0))
+        .collect();
+    let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    let relevance: Vec<f64> = scores
+        .iter()
+        .map(|s| if range > 0.0 { (s - min) / range } else { 1.0 })
+        .collect();
+
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+    let mut selected = Vec::with_capacity(limit.min(candidates.len()));
+    // Highest similarity of each candidate to anything already selected
+    let mut redundancy = vec![0.0f64; candidates.len()];
+
+    while selected.len() < limit && !remaining.is_empty() {
+        let mut best_pos = 0;
+        let mut best_score = f64::NEG_INFINITY;
+        for (pos, &i) in remaining.iter().enumerate() {
+            let score = (1.0 - diversity) * relevance[i] - diversity * redundancy[i];
+            // Strict comparison keeps the original order on ties
+            if score > best_score {
+                best_pos = pos;
+                best_score = score;
+            }
+        }
+
+        let chosen = remaining.remove(best_pos);
+        selected.push(chosen);
+
+        if let Some(chosen_embedding) = embeddings.get(&candidates[chosen].id) {
+            for &i in &remaining {
+                if let Some(embedding) = embeddings.get(&candidates[i].id) {
+                    let similarity = cosine_similarity(chosen_embedding, embedding).unwrap_or(0.0);
+                    redundancy[i] = redundancy[i].max(similarity);
+                }
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<Memory>> = candidates.into_iter().map(Some).collect();
+    selected
+        .into_iter()
+        .filter_map(|i| slots[i].take())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::{MemoryKind, Origin};
+
+    fn memory(id: &str, similarity: f64) -> Memory {
+        Memory {
+            id: id.to_string(),
+            project_id: "proj".to_string(),
+            content: id.to_string(),
+            metadata: None,
+            kind: MemoryKind::Other,
+            origin: Origin::default(),
+            title: None,
+            similarity: Some(similarity),
+            snippet: None,
+            explanation: None,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    /// Two near-identical top results and one distinct, slightly weaker result.
+    fn near_duplicates() -> (Vec<Memory>, HashMap<String, Vec<f32>>) {
+        let candidates = vec![memory("a", 0.95), memory("a2", 0.94), memory("b", 0.80)];
+        let embeddings = HashMap::from([
+            ("a".to_string(), vec![1.0, 0.0, 0.0]),
+            ("a2".to_string(), vec![0.99, 0.05, 0.0]),
+            ("b".to_string(), vec![0.0, 1.0, 0.0]),
+        ]);
+        (candidates, embeddings)
+    }
+
+    fn ids(memories: &[Memory]) -> Vec<&str> {
+        memories.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_mmr_promotes_distinct_result_over_near_duplicate() {
+        let (candidates, embeddings) = near_duplicates();
+        let results = mmr_rerank(candidates, &embeddings, 2, 0.5);
+        assert_eq!(ids(&results), vec!["a", "b"]);
+        // Scores are preserved
+        assert_eq!(results[1].similarity, Some(0.80));
+    }
+
+    #[test]
+    fn test_mmr_zero_diversity_keeps_relevance_order() {
+        let (candidates, embeddings) = near_duplicates();
+        let results = mmr_rerank(candidates, &embeddings, 3, 0.0);
+        assert_eq!(ids(&results), vec!["a", "a2", "b"]);
+    }
+
+    #[test]
+    fn test_mmr_without_embeddings_keeps_order_and_limit() {
+        let (candidates, _) = near_duplicates();
+        let results = mmr_rerank(candidates, &HashMap::new(), 2, 0.7);
+        assert_eq!(ids(&results), vec!["a", "a2"]);
+    }
+
+    #[test]
+    fn test_mmr_empty_candidates() {
+        assert!(mmr_rerank(Vec::new(), &HashMap::new(), 5, 0.5).is_empty());
+    }
+}
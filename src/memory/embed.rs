@@ -0,0 +1,161 @@
+//! Loading the embedding model and embedding memory content.
+
+use std::sync::{Arc, PoisonError};
+use std::time::Instant;
+
+use crate::cancel::Interrupt;
+use crate::embedding::{EmbeddingEngine, MAX_TOKENS, TokenCounter};
+use crate::errors::Error;
+use crate::model_files;
+use crate::storage::StorageBackend;
+
+use super::store::{EmbedderGuard, MemoryStore};
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Lazily initialize the embedding engine and lock it for exclusive use.
+    ///
+    /// Downloads the model on first call (unless `config.offline` is set);
+    /// subsequent calls return the cached engine. Stores sharing the engine
+    /// (see [`SharedMemoryStore`](crate::SharedMemoryStore)) wait here while
+    /// another store is embedding. Model runs through the guard stop once
+    /// the running search is interrupted or, outside a search, after
+    /// `limits.timeout_ms`.
+    pub(crate) fn embedder(&mut self) -> Result<EmbedderGuard<'_>, Error> {
+        if !self.embedding_enabled {
+            return Err(Error::Config(
+                "Embedding model unavailable: store was opened without an embedder".to_string(),
+            ));
+        }
+        let mut embedder = self.embedder.lock().unwrap_or_else(PoisonError::into_inner);
+        if embedder.is_none() {
+            let started = Instant::now();
+            let engine = EmbeddingEngine::with_download(
+                &self.model_id,
+                self.config.offline,
+                &self.config.download,
+            )?;
+            log::info!(
+                "Loaded embedding model {} in {:.0?}",
+                self.model_id,
+                started.elapsed()
+            );
+            *embedder = Some(engine);
+        }
+        let mut guard = EmbedderGuard(embedder);
+        // Outside a search, each model run gets the whole timeout
+        guard.set_interrupt(if self.interrupt.is_set() {
+            self.interrupt.clone()
+        } else {
+            Interrupt::new(None, self.config.limits.timeout_ms)
+        });
+        Ok(guard)
+    }
+
+    /// Number of model tokens in `text`, including special tokens.
+    ///
+    /// Uses the embedding model's tokenizer if the model is loaded, and
+    /// otherwise loads just the tokenizer from the model cache (never from the
+    /// network). Counts above [`MAX_TOKENS`] mean the text is truncated before
+    /// embedding.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ModelNotCached` if the model is not loaded and its
+    /// files are not cached, or an error if tokenization fails.
+    pub fn count_tokens(&self, text: &str) -> Result<usize, Error> {
+        {
+            let embedder = self.embedder.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(engine) = embedder.as_ref() {
+                return engine.count_tokens(text);
+            }
+        }
+        let counter = match self.token_counter.get() {
+            Some(counter) => counter,
+            None => {
+                let files = model_files::cached_model_files(&self.model_id)?;
+                let counter = TokenCounter::from_file(&files.tokenizer)?;
+                self.token_counter.get_or_init(|| counter)
+            }
+        };
+        counter.count(text)
+    }
+
+    /// Embed `content` for storage, warning if it is too long to be embedded
+    /// in full.
+    ///
+    /// With chunking enabled the overflow is searchable through the chunks,
+    /// so it is only logged at debug level. An embedding holding NaN or
+    /// infinite values is rejected with `Error::InvalidEmbedding` before it
+    /// can reach the store.
+    pub(crate) fn embed_document_checked(&mut self, content: &str) -> Result<Vec<f32>, Error> {
+        let chunking = self.config.chunking.enabled;
+        let metrics = Arc::clone(&self.metrics);
+        let mut engine = self.embedder()?;
+        let tokens = engine.count_tokens(content)?;
+        if tokens > MAX_TOKENS {
+            if chunking {
+                log::debug!(
+                    "Content is {} tokens; the full memory embeds the first {}, chunks cover the rest",
+                    tokens,
+                    MAX_TOKENS
+                );
+            } else {
+                log::warn!(
+                    "Content is {} tokens but only the first {} are embedded; the rest is \
+                     only found by keyword search (enable [chunking] to embed it)",
+                    tokens,
+                    MAX_TOKENS
+                );
+            }
+        }
+        let embedding = metrics.time_embed(|| engine.embed_document(content))?;
+        if embedding.iter().any(|x| !x.is_finite()) {
+            return Err(Error::InvalidEmbedding(
+                "the model produced NaN or infinite values; its cache may be corrupted \
+                 (delete it and run `vipune model download`)"
+                    .to_string(),
+            ));
+        }
+        Ok(embedding)
+    }
+
+    /// Sampled token embeddings of `content` for late-interaction scoring,
+    /// or none unless `[late_interaction] enabled` is set (which needs the
+    /// `late-interaction` feature).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if embedding generation fails.
+    #[cfg(feature = "late-interaction")]
+    pub(crate) fn document_tokens(&mut self, content: &str) -> Result<Vec<Vec<f32>>, Error> {
+        if !self.config.late_interaction.enabled {
+            return Ok(Vec::new());
+        }
+        let metrics = Arc::clone(&self.metrics);
+        let tokens = metrics.time_embed(|| self.embedder()?.embed_document_tokens(content))?;
+        Ok(super::late_interaction::sample_tokens(
+            tokens,
+            self.config.late_interaction.tokens,
+        ))
+    }
+
+    #[cfg(not(feature = "late-interaction"))]
+    pub(crate) fn document_tokens(&mut self, _content: &str) -> Result<Vec<Vec<f32>>, Error> {
+        Ok(Vec::new())
+    }
+
+    /// Store the sampled token embeddings of memory `id`'s `content`, if
+    /// `[late_interaction] enabled` is set; otherwise remove any it has, so
+    /// tokens of older content are never scored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if embedding generation or the database write fails.
+    pub(crate) fn store_token_embeddings(&mut self, id: &str, content: &str) -> Result<(), Error> {
+        let tokens = self.document_tokens(content)?;
+        if tokens.is_empty() {
+            return self.db.set_token_embeddings(id, None);
+        }
+        self.db.set_token_embeddings(id, Some(&tokens))
+    }
+}
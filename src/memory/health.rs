@@ -0,0 +1,80 @@
+//! Readiness self-test for the memory store.
+
+use std::time::Instant;
+
+use crate::embedding::EMBEDDING_DIMS;
+use crate::errors::Error;
+use crate::memory_types::HealthReport;
+use crate::sqlite::SCHEMA_VERSION;
+
+use super::store::MemoryStore;
+
+/// Text embedded by the health check.
+const PROBE_TEXT: &str = "vipune health check";
+
+impl MemoryStore {
+    /// Run a cheap self-test of the embedding engine and database.
+    ///
+    /// Loads the model if needed, embeds a short probe text and checks its
+    /// dimensions and values, then compares the schema version and FTS5 row counts
+    /// (unless full-text search is disabled).
+    /// Model failures are recorded in `HealthReport::error` instead of being
+    /// returned, so the report can serve as a readiness probe.
+    ///
+    /// # Errors
+    ///
+    /// Returns error only if the database cannot be queried.
+    pub fn health_check(&mut self) -> Result<HealthReport, Error> {
+        let schema_version = self.db.schema_version()?;
+        let fts_enabled = self.db.fts_enabled();
+        let (memory_rows, fts_rows) = if fts_enabled {
+            self.db.fts_row_counts()?
+        } else {
+            (self.db.memory_rows()?, 0)
+        };
+        let mut report = HealthReport {
+            healthy: false,
+            model_id: self.model_id.clone(),
+            dims: 0,
+            tokenizer_loaded: false,
+            embed_ms: None,
+            schema_version,
+            memory_rows,
+            fts_enabled,
+            fts_rows,
+            fts_in_sync: !fts_enabled || memory_rows == fts_rows,
+            error: None,
+        };
+
+        if let Err(e) = self.probe_embedding(&mut report) {
+            report.error = Some(e.to_string());
+        }
+        report.healthy =
+            report.error.is_none() && report.fts_in_sync && schema_version == SCHEMA_VERSION;
+        Ok(report)
+    }
+
+    /// Tokenize and embed the probe text, filling in the embedding fields.
+    fn probe_embedding(&mut self, report: &mut HealthReport) -> Result<(), Error> {
+        let mut engine = self.embedder()?;
+        report.tokenizer_loaded = engine.count_tokens(PROBE_TEXT)? > 0;
+
+        let started = Instant::now();
+        let embedding = engine.embed(PROBE_TEXT)?;
+        report.embed_ms = Some(started.elapsed().as_secs_f64() * 1000.0);
+        report.dims = embedding.len();
+
+        if report.dims != EMBEDDING_DIMS {
+            return Err(Error::Inference(format!(
+                "Probe embedding has {} dimensions, expected {}",
+                report.dims, EMBEDDING_DIMS
+            )));
+        }
+        if embedding.iter().any(|x| !x.is_finite()) {
+            return Err(Error::InvalidEmbedding(
+                "Probe embedding contains NaN or infinite values".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+//! Earlier versions of memories in SQLite-backed stores.
+
+use crate::errors::Error;
+use crate::sqlite::MemoryVersion;
+
+use super::store::MemoryStore;
+
+impl MemoryStore {
+    #[must_use = "handle the error or results may be lost"]
+    /// Earlier versions of memory `id`, oldest first.
+    ///
+    /// Every content update (`update`, `append`, `edit`, a stable-ID re-add
+    /// or a sync) keeps the content and metadata it replaces. Empty if the
+    /// content never changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if the memory doesn't exist, or an error if
+    /// the database query fails.
+    pub fn history(&self, id: &str) -> Result<Vec<MemoryVersion>, Error> {
+        if self.db.get(id)?.is_none() {
+            return Err(Error::NotFound("memory not found".to_string()));
+        }
+        Ok(self.db.history(id)?)
+    }
+}
@@ -0,0 +1,127 @@
+//! Inspection of stored embeddings for debugging retrieval (`vipune embedding`).
+
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::memory_types::EmbeddingInfo;
+use crate::sqlite::embedding::cosine_similarity;
+
+use super::store::MemoryStore;
+
+impl MemoryStore {
+    #[must_use = "handle the error or results may be lost"]
+    /// Dimensions, norm and the first `values` values of a memory's stored
+    /// embedding, with the model it comes from.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if the memory does not exist, or error if
+    /// the query fails or the stored embedding is malformed.
+    pub fn embedding_info(&self, id: &str, values: usize) -> Result<EmbeddingInfo, Error> {
+        let embedding = self.stored_embedding(id)?;
+        let norm = embedding
+            .iter()
+            .map(|&v| f64::from(v) * f64::from(v))
+            .sum::<f64>()
+            .sqrt();
+        let model_id = self
+            .db
+            .store_meta()?
+            .embedding_model
+            .unwrap_or_else(|| self.model_id.clone());
+        Ok(EmbeddingInfo {
+            id: id.to_string(),
+            model_id,
+            dims: embedding.len(),
+            norm,
+            values: embedding.into_iter().take(values).collect(),
+        })
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Cosine similarity between the stored embeddings of two memories.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if either memory does not exist, or error if
+    /// the embeddings are malformed or have different dimensions.
+    pub fn embedding_similarity(&self, a: &str, b: &str) -> Result<f64, Error> {
+        let a = self.stored_embedding(a)?;
+        let b = self.stored_embedding(b)?;
+        Ok(cosine_similarity(&a, &b)?)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Cosine similarity between `query`, embedded as a search query, and a
+    /// memory's stored embedding: the score a plain semantic search gives
+    /// the memory (before recency, fusion or reranking).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::EmptyInput` or `Error::InputTooLong` for an invalid
+    /// query, `Error::NotFound` if the memory does not exist, or error if the
+    /// model cannot be loaded or embedding fails.
+    pub fn query_similarity(&mut self, query: &str, id: &str) -> Result<f64, Error> {
+        self.validate_input_length(query)?;
+        let stored = self.stored_embedding(id)?;
+        let metrics = Arc::clone(&self.metrics);
+        let embedding = metrics.time_embed(|| self.embedder()?.embed_query(query.trim()))?;
+        Ok(cosine_similarity(&embedding, &stored)?)
+    }
+
+    fn stored_embedding(&self, id: &str) -> Result<Vec<f32>, Error> {
+        self.db
+            .get_embedding(id)?
+            .ok_or_else(|| Error::NotFound("memory not found".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::memory::store::IN_MEMORY_DB_PATH;
+    use std::path::Path;
+
+    #[test]
+    fn test_embedding_info_and_similarity() {
+        let store =
+            MemoryStore::open_without_embedder(Path::new(IN_MEMORY_DB_PATH), Config::default())
+                .unwrap();
+        let mut a = vec![0.0f32; 384];
+        a[0] = 0.6;
+        a[1] = 0.8;
+        let mut b = vec![0.0f32; 384];
+        b[0] = 1.0;
+        let id_a = store.db.insert("proj", "first", &a, None).unwrap();
+        let id_b = store.db.insert("proj", "second", &b, None).unwrap();
+
+        let info = store.embedding_info(&id_a, 3).unwrap();
+        assert_eq!(info.dims, 384);
+        assert!((info.norm - 1.0).abs() < 1e-6);
+        assert_eq!(info.values, vec![0.6, 0.8, 0.0]);
+        assert_eq!(info.model_id, store.model_id);
+
+        let similarity = store.embedding_similarity(&id_a, &id_b).unwrap();
+        assert!((similarity - 0.6).abs() < 1e-6);
+        assert!(matches!(
+            store.embedding_similarity(&id_a, "missing"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_query_similarity_validates_before_embedding() {
+        let mut store =
+            MemoryStore::open_without_embedder(Path::new(IN_MEMORY_DB_PATH), Config::default())
+                .unwrap();
+        assert!(matches!(
+            store.query_similarity("  ", "id"),
+            Err(Error::EmptyInput)
+        ));
+        assert!(matches!(
+            store.query_similarity("query", "missing"),
+            Err(Error::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+//! Late-interaction (MaxSim) step of searches (`late-interaction` feature).
+//!
+//! The first retrieval stage compares one pooled embedding per memory with
+//! the query's, which blurs short, specific queries into the average of
+//! their words. Late interaction keeps a sample of each memory's token
+//! embeddings and scores a candidate by matching every query token with
+//! its most similar stored token, so a query word that appears in the
+//! memory counts in full however long the memory is.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::sqlite::{Memory, ScoreExplanation};
+use crate::storage::StorageBackend;
+
+use super::ranking::sort_by_score;
+use super::store::MemoryStore;
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Rescore the top `[late_interaction] candidates` of `results` (at
+    /// least `limit`) by MaxSim against the query's token embeddings, drop
+    /// the others and sort by the new scores.
+    ///
+    /// Candidates without stored tokens (added before `[late_interaction]
+    /// enabled`, or restored from the trash) are scored by the cosine
+    /// similarity of their sentence embedding to the query's `embedding`.
+    pub(super) fn late_interaction(
+        &mut self,
+        query: &str,
+        embedding: &[f32],
+        results: &mut Vec<Memory>,
+        limit: usize,
+        explanations: &mut HashMap<String, ScoreExplanation>,
+    ) -> Result<(), Error> {
+        results.truncate(self.config.late_interaction.candidates.max(limit));
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        let metrics = Arc::clone(&self.metrics);
+        let query_tokens = metrics.time_embed(|| self.embedder()?.embed_query_tokens(query))?;
+        let ids: Vec<&str> = results.iter().map(|m| m.id.as_str()).collect();
+        let tokens = self.db.token_embeddings(&ids)?;
+        let missing: Vec<&str> = ids
+            .into_iter()
+            .filter(|id| !tokens.contains_key(*id))
+            .collect();
+        let without_tokens = missing.len();
+        let sentences = if missing.is_empty() {
+            HashMap::new()
+        } else {
+            self.db.embeddings_for(&missing)?
+        };
+
+        for memory in results.iter_mut() {
+            let score = match tokens.get(&memory.id) {
+                Some(stored) => max_sim(&query_tokens, stored),
+                None => sentences
+                    .get(&memory.id)
+                    .map_or(0.0, |stored| dot(embedding, stored)),
+            };
+            memory.similarity = Some(score);
+            if let Some(explanation) = explanations.get_mut(&memory.id) {
+                explanation.late_interaction = Some(score);
+            }
+        }
+        sort_by_score(results);
+        log::debug!(
+            "Rescored {} candidates by late interaction ({} without stored tokens)",
+            results.len(),
+            without_tokens
+        );
+        Ok(())
+    }
+}
+
+/// Keep at most `count` of `tokens`, evenly spaced from the first one.
+pub(crate) fn sample_tokens(tokens: Vec<Vec<f32>>, count: usize) -> Vec<Vec<f32>> {
+    let total = tokens.len();
+    if total <= count {
+        return tokens;
+    }
+    let mut tokens: Vec<Option<Vec<f32>>> = tokens.into_iter().map(Some).collect();
+    (0..count)
+        .filter_map(|slot| tokens[slot * total / count].take())
+        .collect()
+}
+
+/// MaxSim score: the mean over query tokens of their highest similarity to
+/// any document token (all L2-normalized). 0.0 if either side is empty.
+pub(crate) fn max_sim(query: &[Vec<f32>], document: &[Vec<f32>]) -> f64 {
+    if query.is_empty() || document.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = query
+        .iter()
+        .map(|q| {
+            document
+                .iter()
+                .map(|d| dot(q, d))
+                .fold(f64::NEG_INFINITY, f64::max)
+        })
+        .sum();
+    total / query.len() as f64
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| f64::from(*x) * f64::from(*y))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(index: usize) -> Vec<f32> {
+        let mut token = vec![0.0; 4];
+        token[index] = 1.0;
+        token
+    }
+
+    #[test]
+    fn test_sample_tokens_spreads_evenly() {
+        let tokens: Vec<Vec<f32>> = (0..10).map(|i| vec![i as f32]).collect();
+        let sample = sample_tokens(tokens.clone(), 4);
+        assert_eq!(sample.len(), 4);
+        assert_eq!(sample[0], vec![0.0]);
+        assert!(sample.windows(2).all(|w| w[1][0] - w[0][0] >= 2.0));
+        assert_eq!(sample_tokens(tokens.clone(), 10), tokens);
+        assert_eq!(sample_tokens(tokens, 1), vec![vec![0.0]]);
+    }
+
+    #[test]
+    fn test_max_sim_matches_each_query_token() {
+        let document = vec![unit(0), unit(1), unit(2)];
+        assert_eq!(max_sim(&[unit(0), unit(2)], &document), 1.0);
+        assert_eq!(max_sim(&[unit(0), unit(3)], &document), 0.5);
+        assert_eq!(max_sim(&[], &document), 0.0);
+        assert_eq!(max_sim(&[unit(0)], &[]), 0.0);
+    }
+}
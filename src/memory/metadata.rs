@@ -0,0 +1,135 @@
+//! Bulk metadata updates with JSON merge patches.
+
+use serde_json::{Map, Value};
+
+use crate::errors::Error;
+use crate::hooks::HookEvent;
+use crate::memory_types::MetadataUpdate;
+use crate::project::ProjectId;
+use crate::search_options::SearchFilter;
+
+use super::store::MemoryStore;
+
+impl MemoryStore {
+    /// Apply the JSON merge patch `patch` (RFC 7396) to the metadata of every
+    /// memory in a project that passes `filter`, in one transaction.
+    ///
+    /// Keys in `patch` are set, nested objects are merged and `null` values
+    /// remove keys. Memories without metadata start from an empty object;
+    /// memories the patch doesn't change are not written. Like
+    /// [`MemoryStore::update_metadata`] this leaves content and embeddings
+    /// alone. Only memories the store's owner may see are touched (see
+    /// [`MemoryStore::set_owner`]). With `dry_run`, only reports what would
+    /// be updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `patch` is not a JSON object, a
+    /// matching memory's metadata is not a JSON object, or a patched
+    /// metadata does not fit the configured `metadata_schema` (nothing is
+    /// updated then), or error if the project ID is invalid or the database
+    /// write fails.
+    pub fn update_metadata_where(
+        &self,
+        project_id: &str,
+        filter: &SearchFilter,
+        patch: &Value,
+        dry_run: bool,
+    ) -> Result<MetadataUpdate, Error> {
+        let project = ProjectId::new(project_id)?;
+        if !patch.is_object() {
+            return Err(Error::InvalidInput(
+                "Metadata patch must be a JSON object".to_string(),
+            ));
+        }
+        self.config
+            .metadata_schema
+            .check_filters(&filter.metadata)?;
+        let filter = SearchFilter {
+            viewer: self.config.owner.clone(),
+            ..filter.clone()
+        };
+
+        let (matched, updated) =
+            self.db
+                .update_metadata_where(project.as_str(), &filter, dry_run, |id, metadata| {
+                    let mut target = match metadata {
+                        None => Value::Object(Map::new()),
+                        Some(metadata) => match serde_json::from_str(metadata) {
+                            Ok(value @ Value::Object(_)) => value,
+                            _ => {
+                                return Err(Error::InvalidInput(format!(
+                                    "Metadata of memory {} is not a JSON object",
+                                    id
+                                )));
+                            }
+                        },
+                    };
+                    let before = target.clone();
+                    merge_patch(&mut target, patch);
+                    if target == before {
+                        return Ok(None);
+                    }
+                    let target = target.to_string();
+                    self.config.metadata_schema.check(Some(&target))?;
+                    Ok(Some(target))
+                })?;
+
+        if !dry_run {
+            log::info!(
+                "Updated metadata of {} of {} matching memories in '{}'",
+                updated.len(),
+                matched,
+                project
+            );
+            for id in &updated {
+                self.fire_hook(HookEvent::Update, id)?;
+            }
+        }
+        Ok(MetadataUpdate { matched, updated })
+    }
+}
+
+/// Apply the JSON merge patch `patch` to `target` (RFC 7396).
+pub(crate) fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        unreachable!("target was made an object above");
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_patch_follows_rfc_7396() {
+        let mut target = json!({"a": "b", "c": {"d": "e", "f": "g"}, "tags": ["old"]});
+        merge_patch(
+            &mut target,
+            &json!({"a": "z", "c": {"f": null}, "tags": ["new"], "n": 1}),
+        );
+        assert_eq!(
+            target,
+            json!({"a": "z", "c": {"d": "e"}, "tags": ["new"], "n": 1})
+        );
+
+        let mut target = json!({"a": "b"});
+        merge_patch(&mut target, &json!({"a": {"b": null}}));
+        assert_eq!(target, json!({"a": {}}));
+    }
+}
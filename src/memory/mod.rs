@@ -3,10 +3,42 @@
 //! Provides a high-level API for storing, searching, and retrieving memories
 //! with automatic embedding generation via the ONNX model.
 
+mod audit;
+mod chunks;
+mod clusters;
+mod conflicts;
+mod context;
 mod crud;
+mod diversity;
+mod embed;
+mod health;
+mod history;
+mod inspect;
+#[cfg(feature = "late-interaction")]
+pub(crate) mod late_interaction;
+pub(crate) mod metadata;
+mod open;
+mod projects;
+pub(crate) mod quota;
+mod ranking;
+mod reembed;
+#[cfg(feature = "rerank")]
+mod rerank;
+mod retention;
+mod review;
 mod search;
+mod stats;
+mod sync;
+mod synonyms;
+mod trash;
+mod validation;
+mod verify;
 
 // pub(crate): module internals hidden; public items re-exported explicitly via lib.rs
+#[cfg(feature = "async")]
+pub(crate) mod async_store;
+pub(crate) mod bulk;
+pub(crate) mod shared;
 pub(crate) mod store;
 
 pub use store::MemoryStore;
@@ -0,0 +1,135 @@
+//! Opening the SQLite database a store is built on.
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::errors::Error;
+use crate::sqlite::{Database, FtsCheck};
+
+use super::store::IN_MEMORY_DB_PATH;
+
+/// How long a file database connection waits for another connection's
+/// write lock before failing with `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Index a database that has no memories yet with the configured
+/// `normalization`, or warn if an existing index uses another one.
+pub(super) fn apply_normalization(db: &Database, config: &Config) -> Result<(), Error> {
+    let Some(tokenizer) = db.fts_tokenizer()? else {
+        return Ok(());
+    };
+    let current = db.fts_normalization()?;
+    if current == config.normalization {
+        return Ok(());
+    }
+    if db.memory_rows()? == 0 {
+        db.reindex_fts(tokenizer, config.normalization)?;
+    } else {
+        log::warn!(
+            "Full-text index uses {} normalization but normalization is {}; \
+             run `vipune reindex` to switch",
+            current,
+            config.normalization
+        );
+    }
+    Ok(())
+}
+
+/// Validate the database path and open the database.
+///
+/// New databases index content with `fts_tokenizer` and `normalization` (no
+/// full-text index without `enable_fts`), embeddings are written in
+/// `embedding_format` and long content is compressed as `compression` says.
+/// File databases get the FTS5 consistency check configured by
+/// `fts_auto_repair` and `fts_integrity_check`, and a warning if their index
+/// uses another tokenizer or normalization.
+pub(super) fn open_database(db_path: &Path, config: &Config) -> Result<Database, Error> {
+    if db_path == Path::new(IN_MEMORY_DB_PATH) {
+        let mut db = if config.enable_fts {
+            Database::open_in_memory_with(config.fts_tokenizer)?
+        } else {
+            Database::open_in_memory_without_fts()?
+        };
+        db.set_embedding_format(config.embedding_format);
+        db.set_compression(config.compression.threshold())?;
+        db.set_stable_ids(config.stable_ids);
+        apply_normalization(&db, config)?;
+        return Ok(db);
+    }
+
+    use std::path::Component;
+
+    // Path traversal guard: reject parent directory components (works on all platforms)
+    for component in db_path.components() {
+        if matches!(component, Component::ParentDir) {
+            return Err(Error::Config(
+                "Invalid database path: contains '..' which may escape the intended directory"
+                    .to_string(),
+            ));
+        }
+    }
+
+    // Canonicalize the full db_path to resolve any symlinks and get the real path
+    // Use canonical parent + filename approach to handle non-existent paths
+    let db_real_path = if db_path.exists() {
+        std::fs::canonicalize(db_path).map_err(|e| {
+            Error::Config(format!(
+                "Invalid database path: cannot canonicalize existing path: {}",
+                e
+            ))
+        })?
+    } else {
+        // For non-existent paths, canonicalize parent and reconstruct
+        let parent = db_path.parent().ok_or_else(|| {
+            Error::Config("Invalid database path: no parent directory".to_string())
+        })?;
+        let canonical_parent = std::fs::canonicalize(parent).map_err(|e| {
+            Error::Config(format!(
+                "Invalid database path: parent directory not accessible: {}",
+                e
+            ))
+        })?;
+        // Join canonical parent with just the filename (safe: no .. in filename extraction)
+        let filename = db_path
+            .file_name()
+            .ok_or_else(|| Error::Config("Invalid database path: no filename".to_string()))?;
+        canonical_parent.join(filename)
+    };
+
+    let check = FtsCheck {
+        integrity_check: config.fts_integrity_check,
+        auto_repair: config.fts_auto_repair,
+    };
+    let mut db = if config.enable_fts {
+        Database::open_with(&db_real_path, check, config.fts_tokenizer)?
+    } else {
+        Database::open_without_fts(&db_real_path)?
+    };
+    db.set_embedding_format(config.embedding_format);
+    db.set_compression(config.compression.threshold())?;
+    db.set_stable_ids(config.stable_ids);
+    if let Some(tokenizer) = db.fts_tokenizer()? {
+        if tokenizer != config.fts_tokenizer {
+            log::warn!(
+                "Full-text index uses the {} tokenizer but fts_tokenizer is {}; \
+                 run `vipune reindex` to switch",
+                tokenizer,
+                config.fts_tokenizer
+            );
+        }
+    }
+    apply_normalization(&db, config)?;
+    // Other processes (an agent, an import) may be writing to the same file
+    db.set_busy_timeout(BUSY_TIMEOUT)?;
+    if let Some(path) = &config.sqlite_vec_path {
+        if let Err(e) = db.enable_vector_index(path) {
+            log::warn!(
+                "Cannot use sqlite-vec from {}: {}; searching without the vector index",
+                path.display(),
+                e
+            );
+        }
+    }
+    Ok(db)
+}
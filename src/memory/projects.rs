@@ -0,0 +1,125 @@
+//! Migration of stored project IDs to their normalized form, and removal of
+//! abandoned projects.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::errors::Error;
+use crate::memory_types::{ProjectRename, PrunedProject};
+use crate::project::ProjectId;
+
+use super::store::MemoryStore;
+
+impl MemoryStore {
+    /// Rename every stored project ID that is not in [`ProjectId`] normal
+    /// form (e.g. `Owner/Repo.git` to `owner/repo`), merging projects whose
+    /// IDs normalize to the same one.
+    ///
+    /// With `dry_run`, only reports what would be renamed. Stored IDs that
+    /// cannot be normalized (e.g. blank ones) are left alone with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operations fail.
+    pub fn normalize_projects(&self, dry_run: bool) -> Result<Vec<ProjectRename>, Error> {
+        let counts = self.db.project_memory_counts()?;
+        let mut targets: HashSet<String> = counts
+            .iter()
+            .filter(|(id, _)| ProjectId::normalize(id) == *id)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let mut renames = Vec::new();
+        for (from, count) in counts {
+            let to = match ProjectId::new(&from) {
+                Ok(id) if id == from.as_str() => continue,
+                Ok(id) => id.into_string(),
+                Err(e) => {
+                    log::warn!("Leaving project '{}' as is: {}", from, e);
+                    continue;
+                }
+            };
+            let merged = !targets.insert(to.clone());
+            let memories = if dry_run {
+                count
+            } else {
+                let moved = self.db.rename_project(&from, &to)?;
+                log::info!(
+                    "Renamed project '{}' to '{}' ({} memories)",
+                    from,
+                    to,
+                    moved
+                );
+                moved
+            };
+            renames.push(ProjectRename {
+                from,
+                to,
+                memories,
+                merged,
+            });
+        }
+        Ok(renames)
+    }
+
+    /// Permanently delete projects that are empty (no live memories, only
+    /// archived or trashed ones) or, with `inactive_before`, whose live
+    /// memories were last updated or accessed before that time.
+    ///
+    /// A project with a memory moved to the trash within
+    /// `trash_retention_days` is kept, so the delete can still be undone.
+    /// Deletion removes a project's memories, archive, trash and synonyms
+    /// without going through the trash. With `dry_run`, only reports what
+    /// would be deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database operations fail.
+    pub fn prune_projects(
+        &self,
+        inactive_before: Option<DateTime<Utc>>,
+        dry_run: bool,
+    ) -> Result<Vec<PrunedProject>, Error> {
+        let before = |time: &str, cutoff: DateTime<Utc>| {
+            DateTime::parse_from_rfc3339(time).is_ok_and(|time| time.with_timezone(&Utc) < cutoff)
+        };
+        let trash_cutoff = self
+            .config
+            .trash_retention_days
+            .map(|days| Utc::now() - Duration::days(i64::from(days)));
+        let mut pruned = Vec::new();
+        for project in self.db.project_activity()? {
+            let stale = match (&project.last_activity, inactive_before) {
+                (None, _) => true,
+                (Some(last), Some(cutoff)) => before(last, cutoff),
+                (Some(_), None) => false,
+            };
+            let restorable = match (&project.last_trashed, trash_cutoff) {
+                (Some(trashed), Some(cutoff)) => !before(trashed, cutoff),
+                _ => false,
+            };
+            if !stale || restorable {
+                continue;
+            }
+            if !dry_run {
+                self.db.delete_project(&project.project_id)?;
+                log::info!(
+                    "Deleted project '{}' ({} memories, {} archived, {} in trash)",
+                    project.project_id,
+                    project.memories,
+                    project.archived,
+                    project.trashed
+                );
+            }
+            pruned.push(PrunedProject {
+                project_id: project.project_id,
+                memories: project.memories,
+                archived: project.archived,
+                trashed: project.trashed,
+                last_activity: project.last_activity,
+            });
+        }
+        Ok(pruned)
+    }
+}
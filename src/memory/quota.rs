@@ -0,0 +1,98 @@
+//! Quota enforcement for the memory store.
+
+use crate::errors::Error;
+use crate::storage::StorageBackend;
+
+use super::store::MemoryStore;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Most memories one add may evict to stay under `max_total_db_size_mb`.
+pub(crate) const MAX_EVICTIONS_PER_ADD: usize = 100;
+
+/// Bytes a memory's payload takes: its content, embedding and metadata.
+pub(crate) fn payload_bytes(content: &str, embedding: &[f32], metadata: Option<&str>) -> u64 {
+    (content.len() + std::mem::size_of_val(embedding) + metadata.map_or(0, str::len)) as u64
+}
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Make room for one more memory of `payload` bytes (see [`payload_bytes`])
+    /// in `project_id`.
+    ///
+    /// Checks `max_memories_per_project` and `max_total_db_size_mb`. When a
+    /// limit is reached and `evict_on_quota` is enabled, the oldest memories
+    /// of `project_id` are deleted: enough of them to fit the new one, but no
+    /// more than [`MAX_EVICTIONS_PER_ADD`] for the size limit. Other projects
+    /// are never evicted. Otherwise the add is rejected.
+    ///
+    /// Evicted memories are deleted outright, without going to the trash or
+    /// firing `on_delete` hooks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotaExceeded` if a limit is reached and eviction is
+    /// disabled or cannot free enough space.
+    pub(crate) fn enforce_quota(&self, project_id: &str, payload: u64) -> Result<(), Error> {
+        if let Some(max) = self.config.max_memories_per_project {
+            let count = self.db.count_project(project_id)?;
+            if count >= max {
+                if !self.config.evict_on_quota {
+                    return Err(Error::QuotaExceeded(format!(
+                        "project has {} memories (max_memories_per_project: {})",
+                        count, max
+                    )));
+                }
+                let needed = count + 1 - max;
+                let evicted = self.db.evict_oldest(Some(project_id), needed)?;
+                if evicted < needed {
+                    return Err(Error::QuotaExceeded(format!(
+                        "project has {} memories and only {} could be evicted \
+                         (max_memories_per_project: {})",
+                        count, evicted, max
+                    )));
+                }
+                log::info!(
+                    "Evicted {} oldest memories from {} (max_memories_per_project: {})",
+                    evicted,
+                    project_id,
+                    max
+                );
+            }
+        }
+
+        if let Some(max_mb) = self.config.max_total_db_size_mb {
+            let max_bytes = max_mb.saturating_mul(BYTES_PER_MB);
+            // Backends that cannot report their size are not size-limited
+            if let Some(used) = self.db.used_size_bytes()? {
+                // The database must stay below the limit with the new memory in it
+                let needed = (used + payload + 1).saturating_sub(max_bytes);
+                if needed > 0 {
+                    if !self.config.evict_on_quota {
+                        return Err(Error::QuotaExceeded(format!(
+                            "database uses {} bytes (max_total_db_size_mb: {})",
+                            used, max_mb
+                        )));
+                    }
+                    let evicted = self
+                        .db
+                        .evict_bytes(project_id, needed, MAX_EVICTIONS_PER_ADD)?;
+                    if evicted == 0 {
+                        return Err(Error::QuotaExceeded(format!(
+                            "database uses {} bytes and the {} oldest memories of {} \
+                             hold less than the {} bytes to free (max_total_db_size_mb: {})",
+                            used, MAX_EVICTIONS_PER_ADD, project_id, needed, max_mb
+                        )));
+                    }
+                    log::info!(
+                        "Evicted {} oldest memories from {} (max_total_db_size_mb: {})",
+                        evicted,
+                        project_id,
+                        max_mb
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
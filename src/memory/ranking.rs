@@ -0,0 +1,378 @@
+//! The ranking pipeline behind [`MemoryStore::query`]: candidate retrieval,
+//! fusion and the score adjustments applied before results are selected.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::Error;
+use crate::rrf;
+use crate::search_options::{Rank, SearchFilter, SearchOptions, SearchStrategy};
+use crate::sqlite::embedding::cosine_similarity;
+use crate::sqlite::{Memory, ScoreExplanation};
+use crate::storage::StorageBackend;
+use crate::temporal::{DecayConfig, apply_recency_weight, frecency};
+
+use super::diversity::mmr_rerank;
+use super::store::MemoryStore;
+
+/// Largest fraction by which frecency ranking raises a score (for a memory
+/// with frecency close to 1.0).
+const FRECENCY_BOOST: f64 = 0.5;
+
+/// Maximum allowed candidate pool size for hybrid search to prevent DoS.
+const MAX_CANDIDATE_POOL: usize = 10_000;
+
+/// Candidate pool size for re-ranking stages (limit × 10, min 50, max MAX_CANDIDATE_POOL).
+fn candidate_pool(limit: usize) -> usize {
+    limit.saturating_mul(10).clamp(50, MAX_CANDIDATE_POOL)
+}
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Keyword candidates for hybrid search, or `None` if the backend has no
+    /// full-text index.
+    fn keyword_candidates(
+        &self,
+        query: &str,
+        project_id: &str,
+        limit: usize,
+        filter: &SearchFilter,
+        options: &SearchOptions,
+    ) -> Result<Option<Vec<Memory>>, Error> {
+        let keyword = self.metrics.time_db(|| {
+            self.db.search_keyword(
+                query,
+                options.query_syntax,
+                project_id,
+                limit,
+                filter,
+                &options.exclude,
+                options.field_weights,
+            )
+        });
+        match keyword {
+            Ok(memories) => Ok(Some(memories)),
+            Err(Error::FtsDisabled) => {
+                log::debug!("Full-text search is disabled; ranking by similarity only");
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Steps 1-8 of [`MemoryStore::query`] for a validated query and its
+    /// embedding.
+    pub(super) fn query_embedded(
+        &mut self,
+        project_id: &str,
+        query: &str,
+        embedding: &[f32],
+        options: &SearchOptions,
+    ) -> Result<Vec<Memory>, Error> {
+        let mut filter = options.search_filter();
+        filter.viewer = self.config.owner.clone();
+        filter.active_only = self.config.inactive_after_days.is_some() && !options.include_inactive;
+
+        // MMR selects `limit` results out of a larger pool, and exclusions and
+        // frecency move some candidates past others that would otherwise be cut
+        let mut pool = if options.diversity > 0.0
+            || !options.exclude.is_empty()
+            || options.rank == Rank::Frecency
+        {
+            candidate_pool(options.limit).min(self.config.limits.max_search_limit)
+        } else {
+            options.limit
+        };
+        if options.rerank {
+            pool = pool.max(
+                self.config
+                    .rerank
+                    .candidates
+                    .min(self.config.limits.max_search_limit),
+            );
+        }
+        if options.late_interaction {
+            pool = pool.max(
+                self.config
+                    .late_interaction
+                    .candidates
+                    .min(self.config.limits.max_search_limit),
+            );
+        }
+
+        let mut explanations: HashMap<String, ScoreExplanation> = HashMap::new();
+        // Hybrid search ranks by similarity only when there is no full-text index
+        let hybrid = options.strategy == SearchStrategy::Hybrid;
+        let candidates = if hybrid { candidate_pool(pool) } else { pool };
+        let mut semantic = self
+            .metrics
+            .time_db(|| self.db.search(project_id, embedding, candidates, &filter))?;
+        self.interrupt.check()?;
+        let keyword = if hybrid {
+            self.keyword_candidates(query, project_id, candidates, &filter, options)?
+        } else {
+            None
+        };
+        self.interrupt.check()?;
+        let mut results = match keyword {
+            None => {
+                if let Some(min_similarity) = options.min_similarity {
+                    semantic.retain(|m| m.similarity.unwrap_or(0.0) >= min_similarity);
+                }
+                if options.explain {
+                    for memory in &semantic {
+                        explanations.entry(memory.id.clone()).or_default().semantic =
+                            memory.similarity;
+                    }
+                }
+                semantic
+            }
+            Some(mut bm25) => {
+                if let Some(min_similarity) = options.min_similarity {
+                    // BM25 scores say nothing about semantic relevance, so score
+                    // lexical hits by their embedding too
+                    let similarities = self.query_similarities(embedding, &semantic, &bm25)?;
+                    let passes = |m: &Memory| {
+                        similarities.get(&m.id).copied().unwrap_or(0.0) >= min_similarity
+                    };
+                    semantic.retain(passes);
+                    bm25.retain(passes);
+                    if options.explain {
+                        for (id, similarity) in similarities {
+                            explanations.entry(id).or_default().semantic = Some(similarity);
+                        }
+                    }
+                }
+                if options.explain {
+                    for memory in &semantic {
+                        explanations.entry(memory.id.clone()).or_default().semantic =
+                            memory.similarity;
+                    }
+                    for (rank, memory) in bm25.iter().enumerate() {
+                        explanations.entry(memory.id.clone()).or_default().bm25_rank =
+                            Some(rank + 1);
+                    }
+                }
+                let fused = rrf::fuse(semantic, bm25, options.fusion)?;
+                for memory in &fused {
+                    if let Some(explanation) = explanations.get_mut(&memory.id) {
+                        explanation.fused = memory.similarity;
+                    }
+                }
+                fused
+            }
+        };
+
+        // An as-of search measures recency from its cutoff
+        let now = options.as_of.unwrap_or_else(Utc::now);
+        apply_recency(
+            &mut results,
+            options.recency_weight,
+            &now,
+            &mut explanations,
+        )?;
+        self.apply_exclusions(&mut results, &options.exclude, &mut explanations)?;
+        if options.rank == Rank::Frecency {
+            self.apply_frecency(&mut results, &now, &mut explanations)?;
+        }
+        results.truncate(pool);
+        self.interrupt.check()?;
+        #[cfg(feature = "late-interaction")]
+        if options.late_interaction {
+            self.late_interaction(
+                query,
+                embedding,
+                &mut results,
+                options.limit,
+                &mut explanations,
+            )?;
+        }
+        #[cfg(feature = "rerank")]
+        if options.rerank {
+            self.interrupt.check()?;
+            self.rerank(query, &mut results, options.limit, &mut explanations)?;
+        }
+
+        if options.diversity > 0.0 {
+            let ids: Vec<&str> = results.iter().map(|m| m.id.as_str()).collect();
+            let embeddings = self.db.embeddings_for(&ids)?;
+            results = mmr_rerank(results, &embeddings, options.limit, options.diversity);
+        } else {
+            results.truncate(options.limit);
+        }
+
+        if options.explain {
+            let scanned = self.db.semantic_scan_size(project_id, &filter)?;
+            for memory in results.iter_mut() {
+                let mut explanation = explanations.remove(&memory.id).unwrap_or_default();
+                explanation.score = memory.similarity.unwrap_or(0.0);
+                explanation.scanned = scanned;
+                memory.explanation = Some(explanation);
+            }
+        }
+
+        let ids: Vec<&str> = results.iter().map(|m| m.id.as_str()).collect();
+        self.track_access(&ids);
+
+        log::debug!(
+            "{:?} search in {} returned {} results",
+            options.strategy,
+            project_id,
+            results.len()
+        );
+        Ok(results)
+    }
+
+    /// Lower each score by the fraction given by the memory's highest cosine
+    /// similarity to an `exclude` term, then re-sort (no-op without terms).
+    ///
+    /// A positive score `s` becomes `s × (1 − similarity)`; negative scores are
+    /// pushed further down by the same fraction. Records the similarity in
+    /// `explanations` for memories that have an entry.
+    fn apply_exclusions(
+        &mut self,
+        memories: &mut [Memory],
+        exclude: &[String],
+        explanations: &mut HashMap<String, ScoreExplanation>,
+    ) -> Result<(), Error> {
+        if exclude.is_empty() || memories.is_empty() {
+            return Ok(());
+        }
+
+        let excluded = {
+            let mut embedder = self.embedder()?;
+            exclude
+                .iter()
+                .map(|term| embedder.embed_query(term.trim()))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let ids: Vec<&str> = memories.iter().map(|m| m.id.as_str()).collect();
+        let embeddings = self.db.embeddings_for(&ids)?;
+
+        for memory in memories.iter_mut() {
+            let Some(embedding) = embeddings.get(&memory.id) else {
+                continue;
+            };
+            let mut closest = 0.0f64;
+            for term in &excluded {
+                closest = closest.max(cosine_similarity(term, embedding)?);
+            }
+            let closest = closest.min(1.0);
+            let score = memory.similarity.unwrap_or(0.0);
+            memory.similarity = Some(score - score.abs() * closest);
+            if let Some(explanation) = explanations.get_mut(&memory.id) {
+                explanation.exclusion = Some(closest);
+            }
+        }
+
+        sort_by_score(memories);
+        Ok(())
+    }
+
+    /// Raise each score by up to [`FRECENCY_BOOST`] of its magnitude in
+    /// proportion to the memory's frecency at `now`, then re-sort.
+    ///
+    /// Records the frecency in `explanations` for memories that have an entry.
+    pub(super) fn apply_frecency(
+        &self,
+        memories: &mut [Memory],
+        now: &DateTime<Utc>,
+        explanations: &mut HashMap<String, ScoreExplanation>,
+    ) -> Result<(), Error> {
+        if memories.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<&str> = memories.iter().map(|m| m.id.as_str()).collect();
+        let stats = self.db.access_stats(&ids)?;
+
+        for memory in memories.iter_mut() {
+            let value = match stats.get(&memory.id) {
+                Some(stats) => frecency(
+                    stats.access_count,
+                    stats.last_accessed_at_utc()?.as_ref(),
+                    now,
+                ),
+                None => 0.0,
+            };
+            let score = memory.similarity.unwrap_or(0.0);
+            memory.similarity = Some(score + score.abs() * FRECENCY_BOOST * value);
+            if let Some(explanation) = explanations.get_mut(&memory.id) {
+                explanation.frecency = Some(value);
+            }
+        }
+
+        sort_by_score(memories);
+        Ok(())
+    }
+
+    /// Cosine similarity to the query for every semantic and BM25 candidate.
+    ///
+    /// Semantic candidates keep their search score (best chunk for chunked
+    /// memories); BM25-only hits are scored against their stored embedding.
+    pub(super) fn query_similarities(
+        &self,
+        query_embedding: &[f32],
+        semantic: &[Memory],
+        bm25: &[Memory],
+    ) -> Result<HashMap<String, f64>, Error> {
+        let mut similarities: HashMap<String, f64> = semantic
+            .iter()
+            .map(|m| (m.id.clone(), m.similarity.unwrap_or(0.0)))
+            .collect();
+        let missing: Vec<&str> = bm25
+            .iter()
+            .map(|m| m.id.as_str())
+            .filter(|id| !similarities.contains_key(*id))
+            .collect();
+        for (id, embedding) in self.db.embeddings_for(&missing)? {
+            let similarity = cosine_similarity(query_embedding, &embedding)?;
+            similarities.insert(id, similarity);
+        }
+        Ok(similarities)
+    }
+}
+
+/// Blend temporal decay, measured at `now`, into each score and re-sort
+/// (no-op for weight 0).
+///
+/// Records the decay factor in `explanations` for memories that have an entry.
+fn apply_recency(
+    memories: &mut [Memory],
+    recency_weight: f64,
+    now: &DateTime<Utc>,
+    explanations: &mut HashMap<String, ScoreExplanation>,
+) -> Result<(), Error> {
+    if recency_weight <= 0.0 {
+        return Ok(());
+    }
+
+    let decay_config = DecayConfig::new()?;
+    for memory in memories.iter_mut() {
+        let created_at = memory.created_at_utc()?;
+        let similarity = memory.similarity.unwrap_or(0.0);
+        memory.similarity = Some(apply_recency_weight(
+            similarity,
+            &created_at,
+            now,
+            recency_weight,
+            &decay_config,
+        ));
+        if let Some(explanation) = explanations.get_mut(&memory.id) {
+            explanation.recency_decay = Some(decay_config.calculate_decay_at(&created_at, now));
+        }
+    }
+
+    // Re-sort by recency-adjusted scores
+    sort_by_score(memories);
+    Ok(())
+}
+
+/// Sort memories by score, highest first.
+pub(super) fn sort_by_score(memories: &mut [Memory]) {
+    memories.sort_by(|a, b| {
+        b.similarity
+            .unwrap_or(0.0)
+            .partial_cmp(&a.similarity.unwrap_or(0.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
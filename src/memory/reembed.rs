@@ -0,0 +1,88 @@
+//! Re-embedding stored memories with the current model.
+
+use crate::errors::Error;
+use crate::memory_types::EmbeddingScan;
+
+use super::store::MemoryStore;
+
+/// Log progress after every this many re-embedded rows.
+const PROGRESS_INTERVAL: usize = 100;
+
+impl MemoryStore {
+    /// Embed stored memories and chunks of every project again with the
+    /// current model: with `mismatched_only`, only those whose stored
+    /// embedding has the wrong dimensions (and is skipped by searches).
+    /// Returns the number of rows re-embedded.
+    ///
+    /// Each row is written, with the model revision, as soon as it is embedded, so an interrupted run
+    /// keeps its progress and `mismatched_only` picks up where it stopped.
+    /// The model is not loaded when there is nothing to do. A full run then
+    /// stores token embeddings of every memory if `[late_interaction]
+    /// enabled` is set, and records the current model as the store's
+    /// embedding model, so a store opened with
+    /// [`MemoryStore::open_for_reembed`] switches models.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if embedding generation or a database write fails.
+    pub fn reembed(&mut self, mismatched_only: bool) -> Result<usize, Error> {
+        let candidates = self.db.reembed_candidates(mismatched_only)?;
+        let total = candidates.len();
+        for (done, (id, content)) in candidates.into_iter().enumerate() {
+            let embedding = self.embed_document_checked(&content)?;
+            self.metrics
+                .time_db(|| self.db.set_embedding(&id, &embedding))?;
+            self.store_model_revision(&id)?;
+            if (done + 1) % PROGRESS_INTERVAL == 0 {
+                log::info!("Re-embedded {}/{} rows", done + 1, total);
+            }
+        }
+        log::debug!("Re-embedded {} rows with {}", total, self.model_id);
+        if !mismatched_only {
+            if self.config.late_interaction.enabled {
+                for (id, content) in self.db.token_embedding_candidates()? {
+                    self.store_token_embeddings(&id, &content)?;
+                }
+            }
+            self.db
+                .set_embedding_model(&self.model_id, crate::embedding::EMBEDDING_DIMS)?;
+        }
+        Ok(total)
+    }
+
+    /// Find stored embeddings (memories and chunks, all projects) holding
+    /// NaN or infinite values and, with `repair`, embed them again with the
+    /// current model.
+    ///
+    /// Such rows predate embedding validation; a model that still produces
+    /// them fails the repair with `Error::InvalidEmbedding`. The model is not
+    /// loaded when nothing is contaminated.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the scan, embedding generation or a database write
+    /// fails.
+    pub fn scan_embeddings(&mut self, repair: bool) -> Result<EmbeddingScan, Error> {
+        let rows = self.db.non_finite_embeddings()?;
+        let mut reembedded = 0;
+        if repair {
+            for (id, content) in &rows {
+                let embedding = self.embed_document_checked(content)?;
+                self.metrics
+                    .time_db(|| self.db.set_embedding(id, &embedding))?;
+                self.store_model_revision(id)?;
+                reembedded += 1;
+            }
+        }
+        if !rows.is_empty() {
+            log::warn!(
+                "Found {} embeddings with NaN or infinite values",
+                rows.len()
+            );
+        }
+        Ok(EmbeddingScan {
+            contaminated: rows.into_iter().map(|(id, _)| id).collect(),
+            reembedded,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+//! Cross-encoder reranking step of searches (`rerank` feature).
+
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError};
+use std::time::Instant;
+
+use crate::errors::Error;
+use crate::rerank::CrossEncoder;
+use crate::sqlite::{Memory, ScoreExplanation};
+use crate::storage::StorageBackend;
+
+use super::ranking::sort_by_score;
+use super::store::MemoryStore;
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Score the top `[rerank] candidates` of `results` (at least `limit`)
+    /// against `query` with the cross-encoder, loading it on first use,
+    /// drop the others and sort by the new scores.
+    pub(super) fn rerank(
+        &mut self,
+        query: &str,
+        results: &mut Vec<Memory>,
+        limit: usize,
+        explanations: &mut HashMap<String, ScoreExplanation>,
+    ) -> Result<(), Error> {
+        results.truncate(self.config.rerank.candidates.max(limit));
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        let mut reranker = self.reranker.lock().unwrap_or_else(PoisonError::into_inner);
+        if reranker.is_none() {
+            let started = Instant::now();
+            let model = &self.config.rerank.model;
+            let engine =
+                CrossEncoder::with_download(model, self.config.offline, &self.config.download)?;
+            log::info!(
+                "Loaded reranking model {} in {:.0?}",
+                model,
+                started.elapsed()
+            );
+            *reranker = Some(engine);
+        }
+        let engine = reranker.as_mut().expect("reranker was loaded above");
+        let documents: Vec<&str> = results.iter().map(|m| m.content.as_str()).collect();
+        let metrics = Arc::clone(&self.metrics);
+        let scores = metrics.time_embed(|| engine.score(query, &documents))?;
+
+        for (memory, score) in results.iter_mut().zip(scores) {
+            memory.similarity = Some(score);
+            if let Some(explanation) = explanations.get_mut(&memory.id) {
+                explanation.rerank = Some(score);
+            }
+        }
+        sort_by_score(results);
+        log::debug!("Reranked {} candidates", results.len());
+        Ok(())
+    }
+}
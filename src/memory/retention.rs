@@ -0,0 +1,92 @@
+//! Retention policy enforcement for the memory store.
+
+use chrono::{Duration, Utc};
+
+use crate::errors::Error;
+use crate::memory_types::PruneStats;
+use crate::storage::StorageBackend;
+
+use super::store::MemoryStore;
+
+impl<B: StorageBackend> MemoryStore<B> {
+    #[must_use = "handle the error or results may be lost"]
+    /// Apply the configured retention rules to every project.
+    ///
+    /// Memories whose last update is older than the project's TTL are deleted,
+    /// or moved to the archive table when `archive_instead_of_delete` is set.
+    /// With `trash_retention_days` set, memories deleted longer ago than
+    /// that are also removed from the trash for good, and with
+    /// `inactive_after_days` set, memories idle for longer are marked
+    /// inactive.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a TTL is invalid or database operations fail.
+    pub fn prune(&self) -> Result<PruneStats, Error> {
+        let mut stats = PruneStats {
+            archived: self.config.retention.archive_instead_of_delete,
+            trash_purged: self.purge_trash()?,
+            deactivated: self.deactivate_idle()?,
+            ..Default::default()
+        };
+        if !self.config.retention.is_enabled() {
+            return Ok(stats);
+        }
+
+        for project_id in self.db.project_ids()? {
+            let removed = self.prune_project(&project_id)?;
+            if removed > 0 {
+                stats.projects += 1;
+                stats.removed += removed;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Apply the retention rule for a single project, returning memories removed.
+    pub(crate) fn prune_project(&self, project_id: &str) -> Result<usize, Error> {
+        let Some(ttl) = self.config.retention.ttl_for(project_id)? else {
+            return Ok(0);
+        };
+        let cutoff = (Utc::now() - ttl).to_rfc3339();
+        let archive = self.config.retention.archive_instead_of_delete;
+        let removed = self.db.prune_project(project_id, &cutoff, archive)?;
+        if removed > 0 {
+            log::info!(
+                "Pruned {} memories from {} older than {}",
+                removed,
+                project_id,
+                cutoff
+            );
+        }
+        Ok(removed)
+    }
+
+    /// Permanently remove trash older than `trash_retention_days`, returning
+    /// memories removed.
+    fn purge_trash(&self) -> Result<usize, Error> {
+        let Some(days) = self.config.trash_retention_days else {
+            return Ok(0);
+        };
+        let cutoff = (Utc::now() - Duration::days(i64::from(days))).to_rfc3339();
+        let purged = self.db.purge_trash(&cutoff)?;
+        if purged > 0 {
+            log::info!("Purged {} memories trashed before {}", purged, cutoff);
+        }
+        Ok(purged)
+    }
+
+    /// Mark memories idle for `inactive_after_days` inactive, returning
+    /// memories marked.
+    fn deactivate_idle(&self) -> Result<usize, Error> {
+        let Some(days) = self.config.inactive_after_days else {
+            return Ok(0);
+        };
+        let cutoff = (Utc::now() - Duration::days(i64::from(days))).to_rfc3339();
+        let marked = self.db.deactivate_idle(&cutoff)?;
+        if marked > 0 {
+            log::info!("Marked {} memories idle since {} inactive", marked, cutoff);
+        }
+        Ok(marked)
+    }
+}
@@ -0,0 +1,99 @@
+//! Review queue: idle, unimportant memories put up for a keep, archive or
+//! delete decision.
+
+use chrono::Utc;
+
+use crate::config::ReviewConfig;
+use crate::errors::Error;
+use crate::memory_types::{ReviewAction, ReviewCandidate, ReviewDecision, ReviewStats};
+use crate::project::ProjectId;
+
+use super::store::MemoryStore;
+
+impl MemoryStore {
+    #[must_use = "handle the error or results may be lost"]
+    /// Up to `limit` memories of a project due for review under `review`:
+    /// idle for at least `stale_days` and with a decayed importance of at
+    /// most `max_importance`. Lowest decayed importance first, then longest
+    /// idle.
+    ///
+    /// Listing the queue does not count as an access.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the project ID or limit is invalid or the query fails.
+    pub fn review_queue(
+        &self,
+        project_id: &str,
+        review: &ReviewConfig,
+        limit: usize,
+    ) -> Result<Vec<ReviewCandidate>, Error> {
+        let project = ProjectId::new(project_id)?;
+        self.validate_limit(limit)?;
+        let now = Utc::now().to_rfc3339();
+        let idle = self
+            .db
+            .idle_memories(project.as_str(), &now, f64::from(review.stale_days))?;
+
+        let mut candidates: Vec<ReviewCandidate> = idle
+            .into_iter()
+            .map(|idle| ReviewCandidate {
+                decayed_importance: review
+                    .decayed_importance(idle.importance.unwrap_or(0.0), idle.idle_days),
+                id: idle.memory.id,
+                content: idle.memory.content,
+                kind: idle.memory.kind,
+                importance: idle.importance,
+                idle_days: idle.idle_days,
+                updated_at: idle.memory.updated_at,
+                last_accessed_at: idle.last_accessed_at,
+                reviewed_at: idle.reviewed_at,
+            })
+            .filter(|candidate| candidate.decayed_importance <= review.max_importance)
+            .collect();
+        // Stable, so equally important memories stay longest idle first
+        candidates.sort_by(|a, b| a.decayed_importance.total_cmp(&b.decayed_importance));
+        candidates.truncate(limit);
+        Ok(candidates)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Apply review decisions in order.
+    ///
+    /// Kept memories are marked reviewed now, which takes them out of the
+    /// queue for another `stale_days`. Archived memories are moved to the
+    /// archive table like pruned ones; deleted memories are deleted as by
+    /// [`MemoryStore::delete`] (running the delete hook).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a database write fails; decisions applied before it
+    /// stay applied.
+    pub fn apply_review(&self, decisions: &[ReviewDecision]) -> Result<ReviewStats, Error> {
+        let now = Utc::now().to_rfc3339();
+        let mut stats = ReviewStats::default();
+        for decision in decisions {
+            let id = decision.id.as_str();
+            let (applied, count) = match decision.action {
+                ReviewAction::Keep => (self.db.mark_reviewed(&[id], &now)? > 0, &mut stats.kept),
+                ReviewAction::Archive => {
+                    (self.db.archive_memories(&[id])? > 0, &mut stats.archived)
+                }
+                ReviewAction::Delete => (self.delete(id)?, &mut stats.deleted),
+            };
+            if applied {
+                *count += 1;
+            } else {
+                stats.not_found.push(decision.id.clone());
+            }
+        }
+        if stats.archived + stats.deleted > 0 {
+            log::info!(
+                "Review archived {} and deleted {} memories",
+                stats.archived,
+                stats.deleted
+            );
+        }
+        Ok(stats)
+    }
+}
@@ -1,22 +1,307 @@
 //! Search operations for the memory store (semantic and hybrid search).
 
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::cancel::CancellationToken;
 use crate::errors::Error;
-use crate::rrf;
+use crate::memory_types::SearchResults;
+use crate::project::ProjectId;
+use crate::search_options::{QuerySyntax, SearchOptions, SearchStrategy};
 use crate::sqlite::Memory;
-use crate::temporal::{DecayConfig, apply_recency_weight, validate_recency_weight};
+use crate::sqlite::fts::keyword_terms;
+use crate::storage::StorageBackend;
+
+use super::ranking::sort_by_score;
+use super::store::MemoryStore;
+
+/// Text to embed for `query`: the query itself, or its words without the
+/// keyword syntax of `options.query_syntax`.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if `query` is not valid in that syntax.
+fn semantic_text<'q>(query: &'q str, options: &SearchOptions) -> Result<Cow<'q, str>, Error> {
+    if options.query_syntax == QuerySyntax::Plain {
+        return Ok(Cow::Borrowed(query));
+    }
+    let terms = keyword_terms(query, options.query_syntax)
+        .map_err(|e| Error::InvalidInput(format!("Invalid keyword query: {}", e)))?;
+    let words: Vec<&str> = terms.iter().map(|term| term.text).collect();
+    Ok(Cow::Owned(words.join(" ")))
+}
+
+impl<B: StorageBackend> MemoryStore<B> {
+    #[must_use = "handle the error or results may be lost"]
+    /// Search memories with the given options.
+    ///
+    /// Pipeline:
+    /// 1. Retrieve candidates by `options.strategy` (semantic, or semantic and
+    ///    BM25 combined by `options.fusion`, RRF by default; BM25 also matches
+    ///    stored synonyms of the query words, and with `synonym_embeddings`
+    ///    so does the query embedding), keeping only
+    ///    memories that match the kind, origin, metadata filters, namespace,
+    ///    tags and `since`/`as_of` range and that the store's owner may see
+    ///    (applied by the backend before scoring, see
+    ///    [`SearchOptions::search_filter`] and [`MemoryStore::set_owner`]) and whose cosine
+    ///    similarity to the query is at least `min_similarity`
+    /// 2. Blend in recency when `recency_weight > 0` (measured from `as_of`, if
+    ///    set) and re-sort
+    /// 3. Penalize memories similar to an `exclude` term (whose keyword matches
+    ///    were already dropped in step 1) and re-sort
+    /// 4. With `rank = Frecency`, raise scores of memories that are returned
+    ///    often and were returned recently, and re-sort
+    /// 5. With `late_interaction` (and the `late-interaction` feature),
+    ///    rescore the top `[late_interaction] candidates` (at least `limit`)
+    ///    by MaxSim between the query's and their stored token embeddings,
+    ///    drop the rest and re-sort
+    /// 6. With `rerank` (and the `rerank` feature), score the top
+    ///    `[rerank] candidates` (at least `limit`) with the cross-encoder,
+    ///    drop the rest and re-sort by its relevance
+    /// 7. Re-rank a larger candidate pool with Maximal Marginal Relevance when
+    ///    `diversity > 0`, so near-duplicates do not crowd out other results
+    /// 8. Return the top `limit`, with `Memory::explanation` set if `explain` is on
+    ///
+    /// Returned memories have the access recorded (see
+    /// [`StorageBackend::record_access`]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vipune::{Config, MemoryStore, SearchOptions, SearchStrategy};
+    ///
+    /// let mut store = MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default())?;
+    /// let results = store.query(
+    ///     "owner/repo",
+    ///     "where does alice work",
+    ///     SearchOptions::default()
+    ///         .with_limit(10)
+    ///         .with_strategy(SearchStrategy::Hybrid),
+    /// )?;
+    /// # Ok::<(), vipune::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - Limit is 0 or exceeds the configured `limits.max_search_limit`
+    /// - Query is empty or exceeds the configured `limits.max_input_length`
+    /// - Recency weight, diversity or `min_similarity` is outside [0.0, 1.0]
+    /// - An exclusion term is empty
+    /// - `rerank` is set without the `rerank` feature, or the cross-encoder
+    ///   fails to load or run
+    /// - `late_interaction` is set without the `late-interaction` feature
+    /// - A metadata filter does not fit the configured `metadata_schema`
+    /// - Embedding generation fails
+    /// - Database operations fail
+    /// - The search takes longer than the configured `limits.timeout_ms`
+    ///   (`Error::Timeout`)
+    pub fn query(
+        &mut self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<Memory>, Error> {
+        self.interruptible(None, |store| store.query_now(project_id, query, options))
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Search like [`MemoryStore::query`], stopping once `cancel` is
+    /// cancelled from another thread.
+    ///
+    /// The token is checked between the steps of the search, and a model
+    /// run in progress is terminated, so hosts can abort a slow search
+    /// cleanly; the store stays usable afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vipune::{CancellationToken, Config, MemoryStore, SearchOptions};
+    ///
+    /// let mut store = MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default())?;
+    /// let cancel = CancellationToken::new();
+    /// let handle = cancel.clone();
+    /// // e.g. when the user presses Ctrl-C
+    /// std::thread::spawn(move || handle.cancel());
+    /// match store.query_with_cancel("owner/repo", "deploys", SearchOptions::default(), &cancel) {
+    ///     Err(vipune::Error::Cancelled) => println!("search cancelled"),
+    ///     result => println!("{} results", result?.len()),
+    /// }
+    /// # Ok::<(), vipune::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Cancelled` once `cancel` is cancelled, and otherwise
+    /// the same errors as [`MemoryStore::query`].
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn query_with_cancel(
+        &mut self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<Memory>, Error> {
+        self.interruptible(Some(cancel.clone()), |store| {
+            store.query_now(project_id, query, options)
+        })
+    }
+
+    /// [`MemoryStore::query`] under the interrupt already set.
+    fn query_now(
+        &mut self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<Memory>, Error> {
+        let project = ProjectId::new(project_id)?;
+        let project_id = project.as_str();
+        self.validate_limit(options.limit)?;
+        let query = query.trim();
+        self.validate_input_length(query)?;
+        self.validate_options(&options)?;
+        let text = semantic_text(query, &options)?;
+        self.interrupt.check()?;
+
+        self.metrics.record_searches(1);
+        let embedding = if options.synonym_embeddings {
+            self.embed_with_synonyms(project_id, &[&text])?.remove(0)
+        } else {
+            let metrics = Arc::clone(&self.metrics);
+            metrics.time_embed(|| self.embedder()?.embed_query(&text))?
+        };
+        self.query_embedded(project_id, query, &embedding, &options)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Run several searches with the same options, embedding all queries in
+    /// a single model run.
+    ///
+    /// Returns one result list per query, in order, each as
+    /// [`MemoryStore::query`] would return it. Cheaper than separate calls
+    /// when an agent expands a question into several sub-queries.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `queries` is empty or has more than
+    /// the configured `limits.max_batch_queries` entries, and otherwise the same errors as
+    /// [`MemoryStore::query`] (checked for every query before any search runs).
+    pub fn search_batch(
+        &mut self,
+        project_id: &str,
+        queries: &[&str],
+        options: SearchOptions,
+    ) -> Result<Vec<Vec<Memory>>, Error> {
+        let project = ProjectId::new(project_id)?;
+        let project_id = project.as_str();
+        let max_queries = self.config.limits.max_batch_queries;
+        if queries.is_empty() || queries.len() > max_queries {
+            return Err(Error::InvalidInput(format!(
+                "Batch must have between 1 and {} queries (got {}; set by limits.max_batch_queries)",
+                max_queries,
+                queries.len()
+            )));
+        }
+        self.validate_limit(options.limit)?;
+        let queries: Vec<&str> = queries.iter().map(|q| q.trim()).collect();
+        for query in &queries {
+            self.validate_input_length(query)?;
+        }
+        self.validate_options(&options)?;
+        let texts = queries
+            .iter()
+            .map(|query| semantic_text(query, &options))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let texts: Vec<&str> = texts.iter().map(|text| text.as_ref()).collect();
+
+        self.metrics.record_searches(queries.len());
+        let embeddings = if options.synonym_embeddings {
+            self.embed_with_synonyms(project_id, &texts)?
+        } else {
+            let metrics = Arc::clone(&self.metrics);
+            metrics.time_embed(|| self.embedder()?.embed_queries(&texts))?
+        };
+        queries
+            .iter()
+            .zip(&embeddings)
+            .map(|(query, embedding)| self.query_embedded(project_id, query, embedding, &options))
+            .collect()
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Search every project in the store.
+    ///
+    /// Runs the [`MemoryStore::query`] pipeline in each project on its own,
+    /// with the query embedded once (once per project with
+    /// `synonym_embeddings`, as synonyms are per project), and merges the hits
+    /// by score. Each project contributes up to `options.limit` hits; see
+    /// [`SearchResults::top`] and [`SearchResults::grouped`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::query`].
+    pub fn query_all_projects(
+        &mut self,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<SearchResults, Error> {
+        self.validate_limit(options.limit)?;
+        let query = query.trim();
+        self.validate_input_length(query)?;
+        self.validate_options(&options)?;
+        let text = semantic_text(query, &options)?;
+
+        self.metrics.record_searches(1);
+        let projects = self.db.project_ids()?;
+        let shared = if options.synonym_embeddings || projects.is_empty() {
+            None
+        } else {
+            let metrics = Arc::clone(&self.metrics);
+            Some(metrics.time_embed(|| self.embedder()?.embed_query(&text))?)
+        };
 
-use super::store::{MemoryStore, validate_limit};
+        let mut memories = Vec::new();
+        for project_id in &projects {
+            let embedding = match &shared {
+                Some(embedding) => embedding.clone(),
+                None => self.embed_with_synonyms(project_id, &[&text])?.remove(0),
+            };
+            memories.extend(self.query_embedded(project_id, query, &embedding, &options)?);
+        }
+        sort_by_score(&mut memories);
+        Ok(SearchResults { memories })
+    }
 
-/// Maximum allowed candidate pool size for hybrid search to prevent DoS.
-const MAX_CANDIDATE_POOL: usize = 10_000;
+    /// Validate `options`, and their metadata filters against the configured
+    /// `metadata_schema`.
+    fn validate_options(&self, options: &SearchOptions) -> Result<(), Error> {
+        options.validate()?;
+        if options.rerank && !cfg!(feature = "rerank") {
+            return Err(Error::Config(
+                "Reranking needs vipune built with the `rerank` feature".to_string(),
+            ));
+        }
+        if options.late_interaction && !cfg!(feature = "late-interaction") {
+            return Err(Error::Config(
+                "Late interaction needs vipune built with the `late-interaction` feature"
+                    .to_string(),
+            ));
+        }
+        self.config
+            .metadata_schema
+            .check_filters(&options.search_filter().metadata)
+    }
 
-impl MemoryStore {
     #[must_use = "handle the error or results may be lost"]
+    #[deprecated(
+        since = "0.1.9",
+        note = "use `MemoryStore::query` with `SearchOptions`"
+    )]
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
     /// Search memories by semantic similarity.
     ///
-    /// Generates an embedding for the query and finds memories with highest
-    /// cosine similarity scores. Optionally applies recency weighting to
-    /// boost recent memories.
+    /// Equivalent to [`MemoryStore::query`] with `limit` and `recency_weight`
+    /// set and all other options at their defaults.
     ///
     /// # Arguments
     ///
@@ -32,12 +317,7 @@ impl MemoryStore {
     ///
     /// # Errors
     ///
-    /// Returns error if:
-    /// - Query is empty
-    /// - Query exceeds 100,000 characters
-    /// - Recency weight is invalid
-    /// - Embedding generation fails
-    /// - Database operations fail
+    /// Returns the same errors as [`MemoryStore::query`].
     pub fn search(
         &mut self,
         project_id: &str,
@@ -45,52 +325,25 @@ impl MemoryStore {
         limit: usize,
         recency_weight: f64,
     ) -> Result<Vec<Memory>, Error> {
-        // Validate limit to prevent resource exhaustion
-        validate_limit(limit)?;
-
-        // Validate query before processing
-        let query = query.trim();
-        Self::validate_input_length(query)?;
-
-        validate_recency_weight(recency_weight).map_err(Error::Validation)?;
-        let embedding = self.embedder()?.embed(query)?;
-        let mut memories = self.db.search(project_id, &embedding, limit)?;
-
-        if recency_weight > 0.0 {
-            let decay_config = DecayConfig::new()?;
-            for memory in memories.iter_mut() {
-                let created_at = memory
-                    .created_at
-                    .parse::<chrono::DateTime<chrono::Utc>>()
-                    .map_err(|e| Error::InvalidTimestamp {
-                        timestamp: memory.created_at.clone(),
-                        error: e.to_string(),
-                    })?;
-                let similarity = memory.similarity.unwrap_or(0.0);
-                memory.similarity = Some(apply_recency_weight(
-                    similarity,
-                    &created_at,
-                    recency_weight,
-                    &decay_config,
-                ));
-            }
-            // Re-sort by recency-adjusted scores
-            memories.sort_by(|a, b| {
-                b.similarity
-                    .unwrap_or(0.0)
-                    .partial_cmp(&a.similarity.unwrap_or(0.0))
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-        }
-
-        Ok(memories)
+        self.query(
+            project_id,
+            query,
+            SearchOptions::default()
+                .with_limit(limit)
+                .with_recency_weight(recency_weight),
+        )
     }
 
     #[must_use = "handle the error or results may be lost"]
+    #[deprecated(
+        since = "0.1.9",
+        note = "use `MemoryStore::query` with `SearchOptions`"
+    )]
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
     /// Search memories using hybrid search (semantic + BM25 fused with RRF).
     ///
-    /// Combines semantic embedding search and BM25 full-text search using
-    /// Reciprocal Rank Fusion (RRF), then optionally applies recency weighting.
+    /// Equivalent to [`MemoryStore::query`] with `SearchStrategy::Hybrid`,
+    /// `limit` and `recency_weight` set and all other options at their defaults.
     ///
     /// # Arguments
     ///
@@ -106,12 +359,7 @@ impl MemoryStore {
     ///
     /// # Errors
     ///
-    /// Returns error if:
-    /// - Query is empty
-    /// - Query exceeds 100,000 characters
-    /// - Recency weight is invalid
-    /// - Embedding generation fails
-    /// - Database operations fail
+    /// Returns the same errors as [`MemoryStore::query`].
     pub fn search_hybrid(
         &mut self,
         project_id: &str,
@@ -119,65 +367,13 @@ impl MemoryStore {
         limit: usize,
         recency_weight: f64,
     ) -> Result<Vec<Memory>, Error> {
-        // Validate query before processing
-        let query = query.trim();
-        Self::validate_input_length(query)?;
-
-        validate_recency_weight(recency_weight).map_err(Error::Validation)?;
-
-        // Validate limit before proceeding
-        validate_limit(limit)?;
-
-        // 1. Encode query for semantic search
-        let embedding = self.embedder()?.embed(query)?;
-
-        // 2. Calculate candidate pool (limit × 10, min 50, max MAX_CANDIDATE_POOL)
-        let candidate_pool = limit.saturating_mul(10).clamp(50, MAX_CANDIDATE_POOL);
-
-        // 3. Run semantic search
-        let semantic_results = self.db.search(project_id, &embedding, candidate_pool)?;
-
-        // 4. Run BM25 search
-        let bm25_results = self.db.search_bm25(query, project_id, candidate_pool)?;
-
-        // 5. Fuse with RRF (use default config)
-        let fused = rrf::rrf_fusion(vec![semantic_results, bm25_results], None)?;
-
-        // 6. Apply temporal decay if weight > 0
-        let mut final_results = if recency_weight > 0.0 {
-            let decay_config = DecayConfig::new()?;
-            let mut results = fused;
-            for memory in results.iter_mut() {
-                let timestamp = memory.created_at.clone();
-                let created_at =
-                    timestamp
-                        .parse::<chrono::DateTime<chrono::Utc>>()
-                        .map_err(|e| Error::InvalidTimestamp {
-                            timestamp,
-                            error: e.to_string(),
-                        })?;
-                let similarity = memory.similarity.unwrap_or(0.0);
-                memory.similarity = Some(apply_recency_weight(
-                    similarity,
-                    &created_at,
-                    recency_weight,
-                    &decay_config,
-                ));
-            }
-            // Re-sort by recency-adjusted scores
-            results.sort_by(|a, b| {
-                b.similarity
-                    .unwrap_or(0.0)
-                    .partial_cmp(&a.similarity.unwrap_or(0.0))
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-            results
-        } else {
-            fused
-        };
-
-        // 7. Return top 'limit' results
-        final_results.truncate(limit);
-        Ok(final_results)
+        self.query(
+            project_id,
+            query,
+            SearchOptions::default()
+                .with_limit(limit)
+                .with_recency_weight(recency_weight)
+                .with_strategy(SearchStrategy::Hybrid),
+        )
     }
 }
@@ -0,0 +1,347 @@
+//! Thread-safe memory store for multi-threaded hosts.
+//!
+//! [`SharedMemoryStore`] keeps a small pool of SQLite-backed [`MemoryStore`]s,
+//! one connection each, that share a single lazily loaded embedding engine.
+//! Every method takes `&self`, so the store can live in an `Arc` and be used
+//! from any thread (e.g. web request handlers).
+
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+
+use crate::config::Config;
+use crate::errors::Error;
+use crate::memory_types::{AddResult, UpdateResult};
+#[cfg(feature = "rerank")]
+use crate::rerank::SharedReranker;
+use crate::search_options::SearchOptions;
+use crate::sqlite::{Memory, MemoryKind, Visibility};
+
+use super::open::open_database;
+use super::store::{IN_MEMORY_DB_PATH, MemoryStore, SharedEmbedder, check_owner};
+
+/// A [`MemoryStore`] that can be shared between threads.
+///
+/// Each call checks out one pooled store (waiting if all are busy), runs the
+/// operation on it and returns it to the pool. Database work runs in parallel
+/// up to the pool size; embedding is serialized on the shared engine. The
+/// model is loaded on first use by whichever call needs it first.
+///
+/// Operations are not atomic with respect to each other: two concurrent adds of
+/// near-identical content can both pass conflict detection.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use vipune::{Config, SearchOptions, SharedMemoryStore};
+///
+/// let store = Arc::new(SharedMemoryStore::new(
+///     "memories.db".as_ref(),
+///     "BAAI/bge-small-en-v1.5",
+///     Config::default(),
+///     SharedMemoryStore::DEFAULT_POOL_SIZE,
+/// )?);
+/// let handle = {
+///     let store = Arc::clone(&store);
+///     std::thread::spawn(move || store.query("owner/repo", "alice", SearchOptions::default()))
+/// };
+/// store.add_with_conflict("owner/repo", "Alice works at Microsoft", None, false)?;
+/// handle.join().unwrap()?;
+/// # Ok::<(), vipune::Error>(())
+/// ```
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+pub struct SharedMemoryStore {
+    idle: Mutex<Vec<MemoryStore>>,
+    returned: Condvar,
+    embedder: SharedEmbedder,
+    /// Owner and visibility given to every store as it is checked out.
+    owner: Mutex<(Option<String>, Visibility)>,
+}
+
+/// A store checked out of the pool; returned to it when dropped.
+struct Checkout<'a> {
+    pool: &'a SharedMemoryStore,
+    store: Option<MemoryStore>,
+}
+
+impl Drop for Checkout<'_> {
+    fn drop(&mut self) {
+        if let Some(store) = self.store.take() {
+            self.pool
+                .idle
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(store);
+            self.pool.returned.notify_one();
+        }
+    }
+}
+
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+impl SharedMemoryStore {
+    /// Pool size suitable for most servers.
+    pub const DEFAULT_POOL_SIZE: usize = 4;
+
+    /// Open `pool_size` connections to the database at `db_path`.
+    ///
+    /// Arguments are as for [`MemoryStore::new`]. Connections wait up to five
+    /// seconds for each other's write locks. An in-memory database
+    /// (`":memory:"`) always gets a single connection, since every in-memory
+    /// connection is a separate database.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `pool_size` is 0, and otherwise the
+    /// same errors as [`MemoryStore::new`].
+    pub fn new(
+        db_path: &Path,
+        model_id: &str,
+        config: Config,
+        pool_size: usize,
+    ) -> Result<Self, Error> {
+        if pool_size == 0 {
+            return Err(Error::InvalidInput(
+                "Pool size must be greater than 0".to_string(),
+            ));
+        }
+        let pool_size = if db_path == Path::new(IN_MEMORY_DB_PATH) {
+            1
+        } else {
+            pool_size
+        };
+
+        let embedder = SharedEmbedder::default();
+        #[cfg(feature = "rerank")]
+        let reranker = SharedReranker::default();
+        let metrics = Arc::default();
+        let mut idle = Vec::with_capacity(pool_size);
+        for _ in 0..pool_size {
+            let db = open_database(db_path, &config)?;
+            let mut store = MemoryStore::assemble(
+                db,
+                model_id,
+                config.clone(),
+                SharedEmbedder::clone(&embedder),
+            );
+            #[cfg(feature = "rerank")]
+            {
+                store.reranker = SharedReranker::clone(&reranker);
+            }
+            store.metrics = Arc::clone(&metrics);
+            idle.push(store);
+        }
+        idle[0].check_embedding_model()?;
+        Ok(Self {
+            idle: Mutex::new(idle),
+            returned: Condvar::new(),
+            embedder,
+            owner: Mutex::new((config.owner.clone(), config.visibility)),
+        })
+    }
+
+    /// Shared store over a private in-memory database (a single connection).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database schema cannot be created.
+    pub fn new_in_memory(model_id: &str, config: Config) -> Result<Self, Error> {
+        Self::new(Path::new(IN_MEMORY_DB_PATH), model_id, config, 1)
+    }
+
+    /// Run `f` with exclusive use of one pooled store.
+    ///
+    /// Blocks until a store is free. Gives access to every [`MemoryStore`]
+    /// operation, including those without a `SharedMemoryStore` counterpart.
+    /// The store starts out with the pool's owner and visibility; changing
+    /// them with [`MemoryStore::set_owner`] lasts only for this call (use
+    /// [`SharedMemoryStore::set_owner`] for the whole pool).
+    pub fn with_store<T>(&self, f: impl FnOnce(&mut MemoryStore) -> T) -> T {
+        let mut idle = self.idle.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut store = loop {
+            match idle.pop() {
+                Some(store) => break store,
+                None => {
+                    idle = self
+                        .returned
+                        .wait(idle)
+                        .unwrap_or_else(PoisonError::into_inner)
+                }
+            }
+        };
+        drop(idle);
+
+        let (owner, visibility) = self
+            .owner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        store.config.owner = owner;
+        store.config.visibility = visibility;
+        let mut checkout = Checkout {
+            pool: self,
+            store: Some(store),
+        };
+        f(checkout
+            .store
+            .as_mut()
+            .expect("store is only taken when the checkout is dropped"))
+    }
+
+    /// Add memories as `owner` with `visibility` from every pooled store, and
+    /// search and list as `owner`, as [`MemoryStore::set_owner`] does.
+    /// Operations already running keep the previous owner.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `owner` is empty, or if `visibility`
+    /// is private without an owner.
+    pub fn set_owner(&self, owner: Option<String>, visibility: Visibility) -> Result<(), Error> {
+        check_owner(owner.as_deref(), visibility)?;
+        *self.owner.lock().unwrap_or_else(PoisonError::into_inner) = (owner, visibility);
+        Ok(())
+    }
+
+    /// Whether the shared embedding engine has been loaded.
+    pub fn is_warm(&self) -> bool {
+        self.embedder
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+
+    /// Load the embedding model now instead of on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model cannot be loaded.
+    pub fn warm_up(&self) -> Result<(), Error> {
+        self.with_store(|store| store.warm_up())
+    }
+
+    /// See [`MemoryStore::add_with_conflict`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::add_with_conflict`].
+    pub fn add_with_conflict(
+        &self,
+        project_id: &str,
+        content: &str,
+        metadata: Option<&str>,
+        force: bool,
+    ) -> Result<AddResult, Error> {
+        self.with_store(|store| store.add_with_conflict(project_id, content, metadata, force))
+    }
+
+    /// See [`MemoryStore::add_with_kind`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::add_with_conflict`].
+    pub fn add_with_kind(
+        &self,
+        project_id: &str,
+        content: &str,
+        metadata: Option<&str>,
+        kind: MemoryKind,
+        force: bool,
+    ) -> Result<AddResult, Error> {
+        self.with_store(|store| store.add_with_kind(project_id, content, metadata, kind, force))
+    }
+
+    /// See [`MemoryStore::get`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database query fails.
+    pub fn get(&self, id: &str) -> Result<Option<Memory>, Error> {
+        self.with_store(|store| store.get(id))
+    }
+
+    /// See [`MemoryStore::list_filtered`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::list`].
+    pub fn list(
+        &self,
+        project_id: &str,
+        limit: usize,
+        kind: Option<MemoryKind>,
+    ) -> Result<Vec<Memory>, Error> {
+        self.with_store(|store| store.list_filtered(project_id, limit, kind))
+    }
+
+    /// See [`MemoryStore::query`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::query`].
+    pub fn query(
+        &self,
+        project_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<Memory>, Error> {
+        self.with_store(|store| store.query(project_id, query, options))
+    }
+
+    /// See [`MemoryStore::update`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory doesn't exist.
+    pub fn update(&self, id: &str, content: &str) -> Result<(), Error> {
+        self.with_store(|store| store.update(id, content))
+    }
+
+    /// See [`MemoryStore::append`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::append`].
+    pub fn append(&self, id: &str, text: &str) -> Result<String, Error> {
+        self.with_store(|store| store.append(id, text))
+    }
+
+    /// See [`MemoryStore::update_with_conflict`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::update_with_conflict`].
+    pub fn update_with_conflict(
+        &self,
+        id: &str,
+        content: &str,
+        force: bool,
+    ) -> Result<UpdateResult, Error> {
+        self.with_store(|store| store.update_with_conflict(id, content, force))
+    }
+
+    /// See [`MemoryStore::update_metadata`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory doesn't exist or the database write fails.
+    pub fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), Error> {
+        self.with_store(|store| store.update_metadata(id, metadata))
+    }
+
+    /// See [`MemoryStore::touch`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory doesn't exist or the database write fails.
+    pub fn touch(&self, id: &str) -> Result<(), Error> {
+        self.with_store(|store| store.touch(id))
+    }
+
+    /// See [`MemoryStore::delete`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database query fails.
+    pub fn delete(&self, id: &str) -> Result<bool, Error> {
+        self.with_store(|store| store.delete(id))
+    }
+}
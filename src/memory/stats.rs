@@ -0,0 +1,339 @@
+//! Per-project statistics and embedding drift report.
+
+use std::collections::BTreeMap;
+
+use crate::embedding::MAX_TOKENS;
+use crate::errors::Error;
+use crate::memory_types::{
+    CompressionStats, EmbeddingStats, HistogramBucket, KindCount, LanguageCount, Outlier,
+    OutlierCluster, ProjectStats, TokenStats,
+};
+use crate::project::ProjectId;
+use crate::sqlite::Memory;
+
+use super::store::MemoryStore;
+
+/// Number of 0.1-wide histogram buckets covering similarities 0.0 to 1.0.
+const HISTOGRAM_BUCKETS: usize = 10;
+
+/// Memories more than this many standard deviations below the mean
+/// similarity to the centroid are outliers.
+const OUTLIER_STDDEVS: f64 = 2.0;
+
+/// Minimum distance of the outlier threshold below the mean, so a tight
+/// project doesn't get memories flagged over tiny differences.
+const MIN_OUTLIER_MARGIN: f64 = 0.05;
+
+/// Outliers at least this similar to each other land in the same cluster.
+const CLUSTER_SIMILARITY: f64 = 0.8;
+
+impl MemoryStore {
+    /// Summarize a project: memory counts per kind, time range and, with
+    /// `embeddings`, how coherent its memory embeddings are.
+    ///
+    /// Reads stored embeddings only; the model is never loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a database query fails.
+    pub fn project_stats(&self, project_id: &str, embeddings: bool) -> Result<ProjectStats, Error> {
+        let project = ProjectId::new(project_id)?;
+        let project_id = project.as_str();
+        let kinds = self
+            .db
+            .kind_counts(project_id)?
+            .into_iter()
+            .map(|(kind, count)| KindCount { kind, count })
+            .collect();
+        let languages = self
+            .db
+            .language_counts(project_id)?
+            .into_iter()
+            .map(|(language, count)| LanguageCount { language, count })
+            .collect();
+        let (oldest, newest) = self.db.time_range(project_id)?.unzip();
+        let memories = self.db.count_project(project_id)?;
+        let tokens = match self.token_stats(project_id) {
+            Ok(tokens) => Some(tokens),
+            Err(Error::ModelNotCached(model)) => {
+                log::debug!("Skipping token counts: {} is not cached", model);
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        let (compressed, stored_bytes, original_bytes) = self.db.compression_totals(project_id)?;
+        let compression = (compressed > 0).then(|| CompressionStats {
+            compressed,
+            stored_bytes,
+            original_bytes,
+            saved_bytes: original_bytes.saturating_sub(stored_bytes),
+        });
+        let embeddings = if embeddings {
+            Some(embedding_stats(self.db.export_project(project_id)?))
+        } else {
+            None
+        };
+
+        Ok(ProjectStats {
+            project_id: project_id.to_string(),
+            memories,
+            kinds,
+            languages,
+            oldest,
+            newest,
+            tokens,
+            compression,
+            embeddings,
+            store: self.db.store_meta()?,
+        })
+    }
+}
+
+impl MemoryStore {
+    /// Token counts of a project's memories.
+    fn token_stats(&self, project_id: &str) -> Result<TokenStats, Error> {
+        let mut stats = TokenStats {
+            total: 0,
+            max: 0,
+            limit: MAX_TOKENS,
+            over_limit: 0,
+        };
+        for content in self.db.contents(project_id)? {
+            let tokens = self.count_tokens(&content)?;
+            stats.total += tokens;
+            stats.max = stats.max.max(tokens);
+            if tokens > MAX_TOKENS {
+                stats.over_limit += 1;
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// Analyze how tightly `memories`' embeddings cluster around their centroid.
+fn embedding_stats(memories: Vec<(Memory, Vec<f32>)>) -> EmbeddingStats {
+    let (memories, vectors): (Vec<Memory>, Vec<Vec<f64>>) = memories
+        .into_iter()
+        .filter_map(|(memory, embedding)| normalize(&embedding).map(|v| (memory, v)))
+        .unzip();
+    let n = vectors.len();
+    let dims = vectors.first().map_or(0, Vec::len);
+
+    let mut sum = vec![0.0; dims];
+    for vector in &vectors {
+        for (total, value) in sum.iter_mut().zip(vector) {
+            *total += value;
+        }
+    }
+    let sum_norm_sq = dot(&sum, &sum);
+    let centroid_norm = if n == 0 {
+        0.0
+    } else {
+        sum_norm_sq.sqrt() / n as f64
+    };
+    // For unit vectors, |sum|² = n + 2·Σ(pairwise dot products)
+    let mean_pairwise_similarity =
+        (n >= 2).then(|| (sum_norm_sq - n as f64) / (n * (n - 1)) as f64);
+
+    let direction = normalize_f64(&sum);
+    let similarities: Vec<f64> = vectors
+        .iter()
+        .map(|v| direction.as_deref().map_or(0.0, |d| dot(v, d)))
+        .collect();
+    let (mean, stddev) = mean_and_stddev(&similarities);
+    let outlier_threshold = mean - (OUTLIER_STDDEVS * stddev).max(MIN_OUTLIER_MARGIN);
+
+    let outliers: Vec<usize> = (0..n)
+        .filter(|&i| similarities[i] < outlier_threshold)
+        .collect();
+    let outlier_clusters = cluster(&outliers, &vectors)
+        .into_iter()
+        .map(|mut group| {
+            group.sort_by(|&a, &b| similarities[a].total_cmp(&similarities[b]));
+            OutlierCluster {
+                members: group
+                    .into_iter()
+                    .map(|i| Outlier {
+                        id: memories[i].id.clone(),
+                        content: memories[i].content.clone(),
+                        centroid_similarity: similarities[i],
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    EmbeddingStats {
+        count: n,
+        centroid_norm,
+        mean_pairwise_similarity,
+        mean_centroid_similarity: mean,
+        stddev_centroid_similarity: stddev,
+        histogram: histogram(&similarities),
+        outlier_threshold,
+        outlier_clusters,
+    }
+}
+
+/// Group `members` (indices into `vectors`) by single-linkage at
+/// [`CLUSTER_SIMILARITY`], largest group first.
+fn cluster(members: &[usize], vectors: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    // Union-find over positions in `members`
+    let mut parent: Vec<usize> = (0..members.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for a in 0..members.len() {
+        for b in a + 1..members.len() {
+            if dot(&vectors[members[a]], &vectors[members[b]]) >= CLUSTER_SIMILARITY {
+                let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                parent[ra.max(rb)] = ra.min(rb);
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (position, &member) in members.iter().enumerate() {
+        let r = root(&mut parent, position);
+        groups.entry(r).or_default().push(member);
+    }
+    let mut groups: Vec<Vec<usize>> = groups.into_values().collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.len()));
+    groups
+}
+
+/// Count `similarities` into [`HISTOGRAM_BUCKETS`] buckets over 0.0..=1.0.
+fn histogram(similarities: &[f64]) -> Vec<HistogramBucket> {
+    let width = 1.0 / HISTOGRAM_BUCKETS as f64;
+    let mut buckets: Vec<HistogramBucket> = (0..HISTOGRAM_BUCKETS)
+        .map(|i| HistogramBucket {
+            min: i as f64 * width,
+            max: (i + 1) as f64 * width,
+            count: 0,
+        })
+        .collect();
+    for &similarity in similarities {
+        let index = ((similarity / width).floor().max(0.0) as usize).min(HISTOGRAM_BUCKETS - 1);
+        buckets[index].count += 1;
+    }
+    buckets
+}
+
+/// Population mean and standard deviation (both 0.0 for no values).
+fn mean_and_stddev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+pub(super) fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Unit-length `f64` copy of `embedding`, or `None` for a zero vector.
+pub(super) fn normalize(embedding: &[f32]) -> Option<Vec<f64>> {
+    normalize_f64(&embedding.iter().map(|&v| f64::from(v)).collect::<Vec<_>>())
+}
+
+pub(super) fn normalize_f64(vector: &[f64]) -> Option<Vec<f64>> {
+    let norm = dot(vector, vector).sqrt();
+    (norm > 0.0 && norm.is_finite()).then(|| vector.iter().map(|v| v / norm).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::{MemoryKind, Origin};
+
+    fn memory(id: &str) -> Memory {
+        Memory {
+            id: id.to_string(),
+            project_id: "proj".to_string(),
+            content: format!("content {id}"),
+            metadata: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            kind: MemoryKind::Fact,
+            origin: Origin::default(),
+            title: None,
+            similarity: None,
+            snippet: None,
+            explanation: None,
+        }
+    }
+
+    fn entries(vectors: &[(&str, [f32; 3])]) -> Vec<(Memory, Vec<f32>)> {
+        vectors
+            .iter()
+            .map(|(id, v)| (memory(id), v.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_embeddings_are_fully_coherent() {
+        let stats = embedding_stats(entries(&[
+            ("a", [1.0, 0.0, 0.0]),
+            ("b", [2.0, 0.0, 0.0]),
+            ("c", [0.5, 0.0, 0.0]),
+        ]));
+        assert_eq!(stats.count, 3);
+        assert!((stats.centroid_norm - 1.0).abs() < 1e-9);
+        assert!((stats.mean_pairwise_similarity.unwrap() - 1.0).abs() < 1e-9);
+        assert!((stats.mean_centroid_similarity - 1.0).abs() < 1e-9);
+        assert_eq!(stats.histogram.len(), HISTOGRAM_BUCKETS);
+        assert_eq!(stats.histogram[HISTOGRAM_BUCKETS - 1].count, 3);
+        assert!(stats.outlier_clusters.is_empty());
+    }
+
+    #[test]
+    fn test_orthogonal_pair_has_zero_pairwise_similarity() {
+        let stats = embedding_stats(entries(&[("a", [1.0, 0.0, 0.0]), ("b", [0.0, 1.0, 0.0])]));
+        assert!(stats.mean_pairwise_similarity.unwrap().abs() < 1e-9);
+        assert!((stats.centroid_norm - 0.5f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_outliers_are_clustered() {
+        let mut vectors: Vec<(String, [f32; 3])> = (0..20)
+            .map(|i| (format!("main{i}"), [1.0, 0.01 * i as f32, 0.0]))
+            .collect();
+        vectors.push(("drift1".to_string(), [0.0, 0.0, 1.0]));
+        vectors.push(("drift2".to_string(), [0.05, 0.0, 1.0]));
+        let vectors: Vec<(&str, [f32; 3])> =
+            vectors.iter().map(|(id, v)| (id.as_str(), *v)).collect();
+
+        let stats = embedding_stats(entries(&vectors));
+        assert_eq!(stats.count, 22);
+        assert_eq!(stats.outlier_clusters.len(), 1);
+        let ids: Vec<&str> = stats.outlier_clusters[0]
+            .members
+            .iter()
+            .map(|o| o.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["drift1", "drift2"]);
+        assert!(
+            stats.outlier_clusters[0]
+                .members
+                .iter()
+                .all(|o| o.centroid_similarity < stats.outlier_threshold)
+        );
+    }
+
+    #[test]
+    fn test_empty_and_zero_embeddings() {
+        let stats = embedding_stats(entries(&[("zero", [0.0, 0.0, 0.0])]));
+        assert_eq!(stats.count, 0);
+        assert_eq!(stats.centroid_norm, 0.0);
+        assert_eq!(stats.mean_pairwise_similarity, None);
+        assert!(stats.histogram.iter().all(|b| b.count == 0));
+        assert!(stats.outlier_clusters.is_empty());
+    }
+}
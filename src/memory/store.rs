@@ -1,33 +1,70 @@
 //! Core memory store struct combining embedding generation and persistence.
 
+use std::ops::{Deref, DerefMut};
 use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
 
+use crate::cancel::{CancellationToken, Interrupt};
 use crate::config::Config;
-use crate::embedding::EmbeddingEngine;
+use crate::content_filter::{self, ContentFilter};
+use crate::embedding::{EMBEDDING_DIMS, EmbeddingEngine, TokenCounter};
 use crate::errors::Error;
-use crate::sqlite::Database;
+use crate::hooks::{HookEvent, run_hook};
+use crate::language;
+use crate::metrics::Metrics;
+#[cfg(feature = "rerank")]
+use crate::rerank::SharedReranker;
+use crate::sqlite::{Database, Memory, Origin, Visibility};
+use crate::storage::StorageBackend;
 
-/// Maximum allowed input length (100,000 characters).
+use super::open::{apply_normalization, open_database};
+
+/// Database path that selects an in-memory database instead of a file.
+pub const IN_MEMORY_DB_PATH: &str = ":memory:";
+
+/// Default maximum input length (100,000 bytes); see `LimitsConfig::max_input_length`.
 pub const MAX_INPUT_LENGTH: usize = 100_000;
-/// Maximum allowed limit for search operations.
+/// Default maximum limit for search and list operations; see
+/// `LimitsConfig::max_search_limit`.
 pub const MAX_SEARCH_LIMIT: usize = 10_000;
+/// Default maximum number of queries in one [`MemoryStore::search_batch`]
+/// call; see `LimitsConfig::max_batch_queries`.
+pub const MAX_BATCH_QUERIES: usize = 64;
 
-/// Validate that a limit parameter is within acceptable bounds.
-///
-/// Returns error if limit is 0 or exceeds MAX_SEARCH_LIMIT.
-pub(crate) fn validate_limit(limit: usize) -> Result<(), Error> {
-    if limit == 0 {
-        return Err(Error::InvalidInput(
-            "Limit must be greater than 0".to_string(),
-        ));
+/// Default cosine deviation above which [`MemoryStore::verify_embeddings`]
+/// counts a stored embedding as drifted: well above what int8 storage
+/// loses, well below what a different model produces.
+pub const DEFAULT_DRIFT_TOLERANCE: f64 = 0.001;
+
+/// Highest `limits.max_input_length` a config may set (10 MB).
+pub const INPUT_LENGTH_CAP: usize = 10_000_000;
+/// Highest `limits.max_search_limit` a config may set.
+pub const SEARCH_LIMIT_CAP: usize = 1_000_000;
+/// Highest `limits.max_batch_queries` a config may set.
+pub const BATCH_QUERIES_CAP: usize = 1024;
+
+/// Lazily loaded embedding engine, shareable between stores.
+pub(crate) type SharedEmbedder = Arc<Mutex<Option<EmbeddingEngine>>>;
+
+/// Exclusive access to a loaded embedding engine.
+pub(crate) struct EmbedderGuard<'a>(pub(super) MutexGuard<'a, Option<EmbeddingEngine>>);
+
+impl Deref for EmbedderGuard<'_> {
+    type Target = EmbeddingEngine;
+
+    fn deref(&self) -> &EmbeddingEngine {
+        self.0
+            .as_ref()
+            .expect("guard is only created once the engine is loaded")
     }
-    if limit > MAX_SEARCH_LIMIT {
-        return Err(Error::InvalidInput(format!(
-            "Limit {} exceeds maximum allowed ({})",
-            limit, MAX_SEARCH_LIMIT
-        )));
+}
+
+impl DerefMut for EmbedderGuard<'_> {
+    fn deref_mut(&mut self) -> &mut EmbeddingEngine {
+        self.0
+            .as_mut()
+            .expect("guard is only created once the engine is loaded")
     }
-    Ok(())
 }
 
 /// Core memory store combining embedding generation and persistence.
@@ -40,11 +77,43 @@ pub(crate) fn validate_limit(limit: usize) -> Result<(), Error> {
 /// Methods that generate embeddings (`add`, `search`, `update`) require
 /// `&mut self` because `EmbeddingEngine::embed` internally mutates state
 /// for ONNX tensor allocations.
-pub struct MemoryStore {
-    pub(crate) db: Database,
-    pub(crate) embedder: Option<EmbeddingEngine>,
+///
+/// # Lazy Model Loading
+///
+/// The ONNX model is loaded on the first operation that needs an embedding,
+/// so metadata-only operations (`get`, `list`, `delete`) never pay the model
+/// startup cost. Call [`MemoryStore::warm_up`] to load it eagerly, or open the
+/// store with [`MemoryStore::open_without_embedder`] to forbid loading it.
+///
+/// # Storage Backends
+///
+/// Persistence goes through the [`StorageBackend`] trait. The constructors
+/// below use the SQLite [`Database`]; [`MemoryStore::with_backend`] accepts
+/// any other implementation. Audit log, health check and import checkpoints
+/// are only available on SQLite-backed stores.
+pub struct MemoryStore<B = Database> {
+    pub(crate) db: B,
+    pub(crate) embedder: SharedEmbedder,
     pub(crate) model_id: String,
     pub(crate) config: Config,
+    pub(crate) embedding_enabled: bool,
+    /// Tokenizer loaded on its own when tokens are counted without the model.
+    pub(crate) token_counter: OnceLock<TokenCounter>,
+    /// Operation counters and latencies, shared across a store pool.
+    pub(crate) metrics: Arc<Metrics>,
+    /// Preprocessing applied to content on add and update, in order.
+    pub(crate) content_filters: Vec<Arc<dyn ContentFilter>>,
+    /// Origin recorded on the memories this store adds.
+    pub(crate) origin: Origin,
+    /// Cross-encoder for reranked searches, loaded on first use.
+    #[cfg(feature = "rerank")]
+    pub(crate) reranker: SharedReranker,
+    /// When the running search must stop (see
+    /// [`MemoryStore::query_with_cancel`]); unset between operations.
+    pub(crate) interrupt: Interrupt,
+    /// Hooks held back until the open bulk session commits (see
+    /// [`MemoryStore::bulk_session`]); `None` outside a session.
+    pub(crate) deferred_hooks: Mutex<Option<Vec<(HookEvent, Memory)>>>,
 }
 
 impl MemoryStore {
@@ -52,7 +121,8 @@ impl MemoryStore {
     ///
     /// # Arguments
     ///
-    /// * `db_path` - Path to the SQLite database file (created if it doesn't exist)
+    /// * `db_path` - Path to the SQLite database file (created if it doesn't exist),
+    ///   or `":memory:"` for an in-memory database
     /// * `model_id` - HuggingFace model ID (e.g., "BAAI/bge-small-en-v1.5")
     /// * `config` - Configuration including similarity threshold for conflict detection
     ///
@@ -62,76 +132,312 @@ impl MemoryStore {
     /// - Database path contains path traversal sequences (e.g., "../")
     /// - Parent directory cannot be canonicalized
     /// - Database cannot be opened
+    /// - The database's embeddings come from another model than `model_id`
+    ///   (`Error::Config`; see [`MemoryStore::open_for_reembed`])
     pub fn new(db_path: &Path, model_id: &str, config: Config) -> Result<Self, Error> {
-        use std::path::Component;
-
-        // Path traversal guard: reject parent directory components (works on all platforms)
-        for component in db_path.components() {
-            if matches!(component, Component::ParentDir) {
-                return Err(Error::Config(
-                    "Invalid database path: contains '..' which may escape the intended directory"
-                        .to_string(),
-                ));
-            }
-        }
+        let store = Self::open_for_reembed(db_path, model_id, config)?;
+        store.check_embedding_model()?;
+        Ok(store)
+    }
+
+    /// Open a memory store without checking that the database's embeddings
+    /// come from `model_id`, to switch it to that model with
+    /// [`MemoryStore::reembed`].
+    ///
+    /// Searches mix incompatible vectors until the full re-embed finishes and
+    /// records `model_id` as the store's model.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::new`], except for the model
+    /// check.
+    pub fn open_for_reembed(db_path: &Path, model_id: &str, config: Config) -> Result<Self, Error> {
+        let db = open_database(db_path, &config)?;
+        Ok(Self::assemble(
+            db,
+            model_id,
+            config,
+            SharedEmbedder::default(),
+        ))
+    }
 
-        // Canonicalize the full db_path to resolve any symlinks and get the real path
-        // Use canonical parent + filename approach to handle non-existent paths
-        let db_real_path = if db_path.exists() {
-            std::fs::canonicalize(db_path).map_err(|e| {
-                Error::Config(format!(
-                    "Invalid database path: cannot canonicalize existing path: {}",
-                    e
-                ))
-            })?
+    /// Initialize a memory store backed by a private in-memory SQLite database.
+    ///
+    /// Nothing touches the filesystem (apart from the model cache) and all
+    /// memories are discarded when the store is dropped. Intended for tests
+    /// and short-lived agents. Passing `":memory:"` as `db_path` to
+    /// [`MemoryStore::new`] is equivalent.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database schema cannot be created.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn new_in_memory(model_id: &str, config: Config) -> Result<Self, Error> {
+        let mut db = if config.enable_fts {
+            Database::open_in_memory_with(config.fts_tokenizer)?
         } else {
-            // For non-existent paths, canonicalize parent and reconstruct
-            let parent = db_path.parent().ok_or_else(|| {
-                Error::Config("Invalid database path: no parent directory".to_string())
-            })?;
-            let canonical_parent = std::fs::canonicalize(parent).map_err(|e| {
-                Error::Config(format!(
-                    "Invalid database path: parent directory not accessible: {}",
-                    e
-                ))
-            })?;
-            // Join canonical parent with just the filename (safe: no .. in filename extraction)
-            let filename = db_path
-                .file_name()
-                .ok_or_else(|| Error::Config("Invalid database path: no filename".to_string()))?;
-            canonical_parent.join(filename)
+            Database::open_in_memory_without_fts()?
         };
+        db.set_embedding_format(config.embedding_format);
+        db.set_compression(config.compression.threshold())?;
+        db.set_stable_ids(config.stable_ids);
+        apply_normalization(&db, &config)?;
+        let store = Self::assemble(db, model_id, config, SharedEmbedder::default());
+        store.check_embedding_model()?;
+        Ok(store)
+    }
+
+    /// Open a memory store for metadata-only workflows.
+    ///
+    /// The embedding model is never loaded: `get`, `list` and `delete` work as
+    /// usual, while operations that need an embedding (`add_with_conflict`,
+    /// `search`, `search_hybrid`, `update`) return `Error::Config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`MemoryStore::new`].
+    pub fn open_without_embedder(db_path: &Path, config: Config) -> Result<Self, Error> {
+        let db = open_database(db_path, &config)?;
+        let model_id = config.embedding_model.clone();
+        let mut store = Self::assemble(db, &model_id, config, SharedEmbedder::default());
+        store.embedding_enabled = false;
+        Ok(store)
+    }
 
-        let db = Database::open(&db_real_path)?;
-        Ok(MemoryStore {
+    /// Refuse a database whose embeddings come from another model or have
+    /// other dimensions, and record the store's model in one that has none
+    /// recorded yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` on a mismatch, or error if `store_meta` cannot
+    /// be read or written.
+    pub(crate) fn check_embedding_model(&self) -> Result<(), Error> {
+        let meta = self.db.store_meta()?;
+        let Some(stored) = meta.embedding_model else {
+            self.db
+                .set_embedding_model(&self.model_id, EMBEDDING_DIMS)?;
+            return Ok(());
+        };
+        let dims = meta.embedding_dims.unwrap_or(EMBEDDING_DIMS);
+        if stored != self.model_id || dims != EMBEDDING_DIMS {
+            return Err(Error::Config(format!(
+                "Database embeddings come from {} ({} dims), not the configured model {} ({} dims); \
+                 set embedding_model back to {} or switch with `vipune reembed`",
+                stored, dims, self.model_id, EMBEDDING_DIMS, stored
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Initialize a memory store on top of a custom storage backend.
+    ///
+    /// The embedding model is loaded lazily, as with [`MemoryStore::new`].
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn with_backend(backend: B, model_id: &str, config: Config) -> Self {
+        Self::assemble(backend, model_id, config, SharedEmbedder::default())
+    }
+
+    /// A store over `db` using `embedder`, with the defaults every
+    /// constructor starts from: embedding enabled, origin `api`, no
+    /// reranker loaded and its own metrics.
+    pub(crate) fn assemble(
+        db: B,
+        model_id: &str,
+        config: Config,
+        embedder: SharedEmbedder,
+    ) -> Self {
+        MemoryStore {
             db,
-            embedder: None,
+            embedder,
             model_id: model_id.to_string(),
+            content_filters: content_filter::from_config(&config.filters),
             config,
-        })
+            embedding_enabled: true,
+            token_counter: OnceLock::new(),
+            #[cfg(feature = "rerank")]
+            reranker: SharedReranker::default(),
+            metrics: Arc::default(),
+            origin: Origin::Api,
+            interrupt: Interrupt::default(),
+            deferred_hooks: Mutex::new(None),
+        }
+    }
+
+    /// Load the embedding model now instead of on first use.
+    ///
+    /// Useful for long-running processes that want predictable latency on the
+    /// first add or search.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model cannot be loaded, or if the store was opened
+    /// without an embedder.
+    pub fn warm_up(&mut self) -> Result<(), Error> {
+        self.embedder().map(|_| ())
     }
 
-    /// Lazily initialize and return a mutable reference to the embedding engine.
+    /// Run `operation` with an interrupt on `token` and the configured
+    /// `limits.timeout_ms`, checked by the operation's steps and model runs.
+    pub(crate) fn interruptible<T>(
+        &mut self,
+        token: Option<CancellationToken>,
+        operation: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        self.interrupt = Interrupt::new(token, self.config.limits.timeout_ms);
+        let result = operation(self);
+        self.interrupt = Interrupt::default();
+        result
+    }
+
+    /// Run the hook configured for `event` on the stored memory `id`.
+    ///
+    /// Skips the lookup when no hook is configured for `event`.
     ///
-    /// Downloads the model on first call; subsequent calls return the cached engine.
-    pub(crate) fn embedder(&mut self) -> Result<&mut EmbeddingEngine, Error> {
-        if self.embedder.is_none() {
-            self.embedder = Some(EmbeddingEngine::new(&self.model_id)?);
+    /// # Errors
+    ///
+    /// Returns `Error::Hook` if the hook fails under the `fail` policy.
+    pub(crate) fn fire_hook(&self, event: HookEvent, id: &str) -> Result<(), Error> {
+        if event.command(&self.config.hooks).is_none() {
+            return Ok(());
+        }
+        match self.db.get(id)? {
+            Some(memory) => self.dispatch_hook(event, memory),
+            None => Ok(()),
         }
-        Ok(self.embedder.as_mut().unwrap())
     }
 
-    /// Validate input length (rejects empty and whitespace-only inputs).
-    pub(crate) fn validate_input_length(text: &str) -> Result<(), Error> {
-        if text.trim().is_empty() {
-            return Err(Error::EmptyInput);
+    /// Run the hook for `event` on `memory`, or queue it while a bulk
+    /// session is open.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Hook` if the hook fails under the `fail` policy.
+    pub(crate) fn dispatch_hook(&self, event: HookEvent, memory: Memory) -> Result<(), Error> {
+        let mut deferred = self
+            .deferred_hooks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        match deferred.as_mut() {
+            Some(queue) => {
+                queue.push((event, memory));
+                Ok(())
+            }
+            None => run_hook(&self.config.hooks, event, &memory),
         }
-        if text.len() > MAX_INPUT_LENGTH {
-            return Err(Error::InputTooLong {
-                max_length: MAX_INPUT_LENGTH,
-                actual_length: text.len(),
-            });
+    }
+
+    /// End hook deferral, returning the hooks queued since
+    /// [`MemoryStore::bulk_session`].
+    pub(crate) fn take_deferred_hooks(&self) -> Vec<(HookEvent, Memory)> {
+        self.deferred_hooks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .unwrap_or_default()
+    }
+
+    /// Record that the memories in `ids` were returned to the caller.
+    ///
+    /// Access tracking only feeds frecency ranking, so a failure (e.g. a
+    /// read-only database) is logged rather than failing the read.
+    pub(crate) fn track_access(&self, ids: &[&str]) {
+        if let Err(e) = self.db.record_access(ids) {
+            log::warn!("Cannot record memory access: {}", e);
         }
+    }
+
+    /// Counters and latency histograms of this store's operations.
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Origin recorded on the memories this store adds (`api` unless set).
+    pub fn origin(&self) -> &Origin {
+        &self.origin
+    }
+
+    /// Record `origin` on the memories this store adds from now on, e.g.
+    /// [`Origin::Mcp`] in an MCP server. Imports record their source instead.
+    pub fn set_origin(&mut self, origin: Origin) {
+        self.origin = origin;
+    }
+
+    /// Owner recorded on added memories and whose view searches and lists
+    /// return (the configured `owner` unless set).
+    pub fn owner(&self) -> Option<&str> {
+        self.config.owner.as_deref()
+    }
+
+    /// Add memories as `owner` with `visibility` from now on, and search and
+    /// list as `owner`: their own memories and shared ones. Without an owner
+    /// nothing is left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if `owner` is empty, or if `visibility`
+    /// is private without an owner.
+    pub fn set_owner(
+        &mut self,
+        owner: Option<String>,
+        visibility: Visibility,
+    ) -> Result<(), Error> {
+        check_owner(owner.as_deref(), visibility)?;
+        self.config.owner = owner;
+        self.config.visibility = visibility;
         Ok(())
     }
+
+    /// Visibility of the memories this store adds.
+    pub fn visibility(&self) -> Visibility {
+        self.config.visibility
+    }
+
+    /// Detect and store the language of memory `id`'s `content`, if
+    /// `detect_language` is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database write fails.
+    pub(crate) fn store_language(&self, id: &str, content: &str) -> Result<(), Error> {
+        if !self.config.detect_language {
+            return Ok(());
+        }
+        self.db.set_language(id, language::detect(content))
+    }
+
+    /// Hub commit hash of the loaded embedding model, if it is loaded and
+    /// came from the Hub cache.
+    pub(crate) fn model_revision(&self) -> Option<String> {
+        let embedder = self.embedder.lock().unwrap_or_else(PoisonError::into_inner);
+        embedder
+            .as_ref()
+            .and_then(|engine| engine.revision().map(str::to_string))
+    }
+
+    /// Record the loaded model's revision as the one that embedded memory
+    /// (or chunk row) `id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database write fails.
+    pub(crate) fn store_model_revision(&self, id: &str) -> Result<(), Error> {
+        self.db
+            .set_model_revision(id, self.model_revision().as_deref())
+    }
+}
+
+/// Reject an empty owner, and private visibility without an owner.
+pub(super) fn check_owner(owner: Option<&str>, visibility: Visibility) -> Result<(), Error> {
+    if owner.is_some_and(|owner| owner.trim().is_empty()) {
+        return Err(Error::InvalidInput("Owner must not be empty".to_string()));
+    }
+    if visibility == Visibility::Private && owner.is_none() {
+        return Err(Error::InvalidInput(
+            "Private memories need an owner".to_string(),
+        ));
+    }
+    Ok(())
 }
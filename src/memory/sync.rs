@@ -0,0 +1,106 @@
+//! Syncing memories with another store (`vipune sync`).
+
+use std::path::Path;
+
+use crate::embedding::EMBEDDING_DIMS;
+use crate::errors::Error;
+use crate::sqlite::Database;
+use crate::sqlite::sync::SyncStats;
+
+use super::open::open_database;
+use super::store::MemoryStore;
+
+impl MemoryStore {
+    #[must_use = "handle the error or results may be lost"]
+    /// Send the memories added, updated or deleted since the last push to
+    /// `peer` to the database at `peer`, which is created if it does not
+    /// exist.
+    ///
+    /// Conflicts are resolved as `crate::sqlite::sync` describes: the copy
+    /// updated later wins, and a memory is matched to a similar one (at
+    /// `similarity_threshold`) when the other store has no memory with its
+    /// ID. Add hooks do not run for synced memories.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if the stores' embeddings come from different
+    /// models, or error if either database cannot be read or written.
+    pub fn sync_push(&self, peer: &Path) -> Result<SyncStats, Error> {
+        let (remote, key) = self.open_peer(peer, true)?;
+        let state = self.db.sync_state(&key)?;
+        // A store restored from an older archive starts over
+        let since = if self.db.write_seq()? < state.pushed_seq {
+            0
+        } else {
+            state.pushed_seq
+        };
+        let (changes, reached) = self.db.changes_since(since)?;
+        let stats = remote.apply_changes(&changes, self.config.similarity_threshold)?;
+        self.db.set_sync_state(&key, reached, state.pulled_seq)?;
+        log::info!("Pushed {} changes to {}: {:?}", changes.len(), key, stats);
+        Ok(stats)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Apply the memories added, updated or deleted in the database at
+    /// `peer` since the last pull from it, resolving conflicts as
+    /// [`MemoryStore::sync_push`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if there is no database at `peer`,
+    /// `Error::Config` if the stores' embeddings come from different models,
+    /// or error if either database cannot be read or written.
+    pub fn sync_pull(&self, peer: &Path) -> Result<SyncStats, Error> {
+        let (remote, key) = self.open_peer(peer, false)?;
+        let state = self.db.sync_state(&key)?;
+        let since = if remote.write_seq()? < state.pulled_seq {
+            0
+        } else {
+            state.pulled_seq
+        };
+        let (changes, reached) = remote.changes_since(since)?;
+        // Record the position in the same transaction as the changes
+        let tx = self.db.write_transaction()?;
+        let stats = self
+            .db
+            .apply_changes(&changes, self.config.similarity_threshold)?;
+        self.db.set_sync_state(&key, state.pushed_seq, reached)?;
+        tx.commit()?;
+        log::info!("Pulled {} changes from {}: {:?}", changes.len(), key, stats);
+        Ok(stats)
+    }
+
+    /// Open the database at `peer` (creating it only if `create` is set),
+    /// check that its embeddings come from this store's model, and return it
+    /// with the key its sync positions are recorded under.
+    fn open_peer(&self, peer: &Path, create: bool) -> Result<(Database, String), Error> {
+        if !create && !peer.exists() {
+            return Err(Error::InvalidInput(format!(
+                "No database at {}",
+                peer.display()
+            )));
+        }
+        let remote = open_database(peer, &self.config)?;
+        let key = std::fs::canonicalize(peer)?.display().to_string();
+
+        let ours = self.db.store_meta()?;
+        let theirs = remote.store_meta()?;
+        match (&ours.embedding_model, &theirs.embedding_model) {
+            (Some(model), Some(other))
+                if model != other || ours.embedding_dims != theirs.embedding_dims =>
+            {
+                return Err(Error::Config(format!(
+                    "Cannot sync with {}: its embeddings come from {}, this store's from {}",
+                    key, other, model
+                )));
+            }
+            (Some(model), None) => {
+                let dims = ours.embedding_dims.unwrap_or(EMBEDDING_DIMS);
+                remote.set_embedding_model(model, dims)?;
+            }
+            _ => {}
+        }
+        Ok((remote, key))
+    }
+}
@@ -0,0 +1,232 @@
+//! Per-project synonyms and synonym-expanded query embeddings.
+
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::project::ProjectId;
+use crate::sqlite::synonyms::lookup_key;
+use crate::storage::StorageBackend;
+
+use super::store::MemoryStore;
+
+/// Most rewritten queries embedded per query, so a query full of words with
+/// many synonyms doesn't run the model dozens of times.
+const MAX_SYNONYM_VARIANTS: usize = 8;
+
+impl MemoryStore {
+    /// Store `synonyms` of `term` for a project, returning how many were new.
+    ///
+    /// Keyword search then matches any of them wherever a query has `term`
+    /// (and `term` wherever a query has one of them). The term must be a
+    /// single word; a synonym may be a phrase. Both are stored lowercased.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the term is not a single word, a
+    /// synonym is empty or equal to the term, or none is given; otherwise
+    /// returns error if the database write fails.
+    pub fn add_synonyms(
+        &self,
+        project_id: &str,
+        term: &str,
+        synonyms: &[&str],
+    ) -> Result<usize, Error> {
+        let project = ProjectId::new(project_id)?;
+        let term = normalize_term(term)?;
+        if synonyms.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "No synonyms given for '{}'",
+                term
+            )));
+        }
+        let mut normalized = Vec::with_capacity(synonyms.len());
+        for synonym in synonyms {
+            let synonym = synonym
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase();
+            if synonym.is_empty() || synonym == term {
+                return Err(Error::InvalidInput(format!(
+                    "Invalid synonym '{}' for '{}'",
+                    synonym, term
+                )));
+            }
+            self.validate_input_length(&synonym)?;
+            normalized.push(synonym);
+        }
+        let normalized: Vec<&str> = normalized.iter().map(String::as_str).collect();
+        Ok(self.db.add_synonyms(project.as_str(), &term, &normalized)?)
+    }
+
+    /// Remove `synonym` of `term` (all synonyms of `term` with `None`),
+    /// returning how many pairs were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the term is not a single word, or
+    /// error if the database write fails.
+    pub fn remove_synonyms(
+        &self,
+        project_id: &str,
+        term: &str,
+        synonym: Option<&str>,
+    ) -> Result<usize, Error> {
+        let project = ProjectId::new(project_id)?;
+        let term = normalize_term(term)?;
+        let synonym = synonym.map(|s| {
+            s.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        });
+        Ok(self
+            .db
+            .remove_synonyms(project.as_str(), &term, synonym.as_deref())?)
+    }
+
+    /// A project's synonym pairs as `(term, synonym)`, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database query fails.
+    pub fn synonyms(&self, project_id: &str) -> Result<Vec<(String, String)>, Error> {
+        let project = ProjectId::new(project_id)?;
+        Ok(self.db.list_synonyms(project.as_str())?)
+    }
+}
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Query embeddings of `queries`, each averaged with the embeddings of
+    /// the query rewritten with the stored synonyms of its words.
+    ///
+    /// All rewrites are embedded in one model run.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the synonym lookup or embedding generation fails.
+    pub(crate) fn embed_with_synonyms(
+        &mut self,
+        project_id: &str,
+        queries: &[&str],
+    ) -> Result<Vec<Vec<f32>>, Error> {
+        let mut texts: Vec<String> = Vec::new();
+        let mut groups = Vec::with_capacity(queries.len());
+        for query in queries {
+            let words: Vec<&str> = query.split_whitespace().collect();
+            let synonyms = self.db.synonyms_for(project_id, &words)?;
+            let variants = synonym_variants(&words, |word| synonyms.get(&lookup_key(word)));
+            groups.push(1 + variants.len());
+            texts.push(query.to_string());
+            texts.extend(variants);
+        }
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let metrics = Arc::clone(&self.metrics);
+        let embeddings = metrics.time_embed(|| self.embedder()?.embed_queries(&texts))?;
+
+        let mut embeddings = embeddings.into_iter();
+        Ok(groups
+            .into_iter()
+            .map(|size| average(embeddings.by_ref().take(size).collect()))
+            .collect())
+    }
+}
+
+/// Validate and lowercase a synonym term.
+fn normalize_term(term: &str) -> Result<String, Error> {
+    let term = term.trim();
+    if term.is_empty() || term.contains(char::is_whitespace) || lookup_key(term).is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "Invalid synonym term '{}' (expected a single word)",
+            term
+        )));
+    }
+    Ok(term.to_lowercase())
+}
+
+/// `words` rewritten with one word replaced by one of its synonyms, for
+/// every word and synonym, up to [`MAX_SYNONYM_VARIANTS`].
+fn synonym_variants<'a>(
+    words: &[&str],
+    synonyms_of: impl Fn(&str) -> Option<&'a Vec<String>>,
+) -> Vec<String> {
+    let mut variants = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        for synonym in synonyms_of(word).into_iter().flatten() {
+            let mut rewritten: Vec<&str> = words.to_vec();
+            rewritten[index] = synonym;
+            variants.push(rewritten.join(" "));
+            if variants.len() == MAX_SYNONYM_VARIANTS {
+                return variants;
+            }
+        }
+    }
+    variants
+}
+
+/// Unit-length mean of `embeddings` (the single embedding if there is one).
+fn average(mut embeddings: Vec<Vec<f32>>) -> Vec<f32> {
+    if embeddings.len() == 1 {
+        return embeddings.remove(0);
+    }
+    let mut sum = vec![0.0f32; embeddings.first().map_or(0, Vec::len)];
+    for embedding in &embeddings {
+        for (total, value) in sum.iter_mut().zip(embedding) {
+            *total += value;
+        }
+    }
+    let norm = sum.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in &mut sum {
+            *value /= norm;
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_synonym_variants_replace_one_word_at_a_time() {
+        let synonyms = HashMap::from([
+            ("k8s".to_string(), vec!["kubernetes".to_string()]),
+            (
+                "db".to_string(),
+                vec!["database".to_string(), "sql store".to_string()],
+            ),
+        ]);
+        let lookup = |word: &str| synonyms.get(&lookup_key(word));
+        assert_eq!(
+            synonym_variants(&["K8s", "db", "setup"], lookup),
+            vec![
+                "kubernetes db setup",
+                "K8s database setup",
+                "K8s sql store setup"
+            ]
+        );
+        assert!(synonym_variants(&["plain", "query"], lookup).is_empty());
+
+        let many: Vec<String> = (0..20).map(|i| format!("alt{i}")).collect();
+        let capped = synonym_variants(&["x"], |_| Some(&many));
+        assert_eq!(capped.len(), MAX_SYNONYM_VARIANTS);
+    }
+
+    #[test]
+    fn test_average_is_unit_length() {
+        assert_eq!(average(vec![vec![0.6, 0.8]]), vec![0.6, 0.8]);
+        let mean = average(vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert!((mean[0] - 0.5f32.sqrt()).abs() < 1e-6);
+        assert!((mean[1] - 0.5f32.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_normalize_term() {
+        assert_eq!(normalize_term(" K8s ").unwrap(), "k8s");
+        assert!(normalize_term("two words").is_err());
+        assert!(normalize_term("  ").is_err());
+        assert!(normalize_term("??").is_err());
+    }
+}
@@ -0,0 +1,367 @@
+//! Custom storage backends and the shared store.
+
+use super::*;
+
+/// Minimal in-process backend implementing only the required methods.
+#[derive(Default)]
+struct VecBackend {
+    rows: std::cell::RefCell<Vec<(Memory, Vec<f32>)>>,
+}
+
+impl StorageBackend for VecBackend {
+    fn insert(&self, record: &NewMemory<'_>) -> Result<String, Error> {
+        let id = format!("mem-{}", self.rows.borrow().len());
+        let memory = Memory {
+            id: id.clone(),
+            project_id: record.project_id.to_string(),
+            content: record.content.to_string(),
+            metadata: record.metadata.map(str::to_string),
+            kind: record.kind,
+            origin: record.origin.clone(),
+            title: None,
+            similarity: None,
+            snippet: None,
+            explanation: None,
+            created_at: record.created_at.to_string(),
+            updated_at: record.updated_at.to_string(),
+        };
+        self.rows
+            .borrow_mut()
+            .push((memory, record.embedding.to_vec()));
+        Ok(id)
+    }
+
+    fn get(&self, id: &str) -> Result<Option<Memory>, Error> {
+        Ok(self
+            .rows
+            .borrow()
+            .iter()
+            .find(|(m, _)| m.id == id)
+            .map(|(m, _)| m.clone()))
+    }
+
+    fn list(
+        &self,
+        project_id: &str,
+        limit: usize,
+        kind: Option<MemoryKind>,
+        origin: Option<&Origin>,
+        _viewer: Option<&str>,
+    ) -> Result<Vec<Memory>, Error> {
+        Ok(self
+            .rows
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|(m, _)| {
+                m.project_id == project_id
+                    && kind.is_none_or(|k| m.kind == k)
+                    && origin.is_none_or(|o| m.origin.matches(o))
+            })
+            .take(limit)
+            .map(|(m, _)| m.clone())
+            .collect())
+    }
+
+    fn update(&self, id: &str, content: &str, embedding: &[f32]) -> Result<(), Error> {
+        let mut rows = self.rows.borrow_mut();
+        let (memory, stored) = rows
+            .iter_mut()
+            .find(|(m, _)| m.id == id)
+            .ok_or_else(|| Error::NotFound(id.to_string()))?;
+        memory.content = content.to_string();
+        *stored = embedding.to_vec();
+        Ok(())
+    }
+
+    fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), Error> {
+        let mut rows = self.rows.borrow_mut();
+        let (memory, _) = rows
+            .iter_mut()
+            .find(|(m, _)| m.id == id)
+            .ok_or_else(|| Error::NotFound(id.to_string()))?;
+        memory.metadata = metadata.map(str::to_string);
+        Ok(())
+    }
+
+    fn delete(&self, id: &str) -> Result<bool, Error> {
+        let mut rows = self.rows.borrow_mut();
+        let before = rows.len();
+        rows.retain(|(m, _)| m.id != id);
+        Ok(rows.len() < before)
+    }
+
+    fn search(
+        &self,
+        project_id: &str,
+        query_embedding: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Memory>, Error> {
+        let mut results = Vec::new();
+        for (memory, embedding) in self.rows.borrow().iter() {
+            if memory.project_id == project_id && filter.matches(memory) {
+                let mut memory = memory.clone();
+                memory.similarity = Some(crate::sqlite::embedding::cosine_similarity(
+                    query_embedding,
+                    embedding,
+                )?);
+                results.push(memory);
+            }
+        }
+        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap());
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    fn embeddings_for(&self, ids: &[&str]) -> Result<HashMap<String, Vec<f32>>, Error> {
+        Ok(self
+            .rows
+            .borrow()
+            .iter()
+            .filter(|(m, _)| ids.contains(&m.id.as_str()))
+            .map(|(m, e)| (m.id.clone(), e.clone()))
+            .collect())
+    }
+
+    fn count_project(&self, project_id: &str) -> Result<usize, Error> {
+        Ok(self
+            .rows
+            .borrow()
+            .iter()
+            .filter(|(m, _)| m.project_id == project_id)
+            .count())
+    }
+}
+
+#[test]
+fn test_custom_backend_crud_through_store() {
+    let store = MemoryStore::with_backend(
+        VecBackend::default(),
+        "BAAI/bge-small-en-v1.5",
+        Config::default(),
+    );
+    let now = chrono::Utc::now().to_rfc3339();
+    let record = NewMemory {
+        project_id: "p",
+        content: "use postgres",
+        embedding: &axis(0),
+        metadata: None,
+        kind: MemoryKind::Decision,
+        origin: &Origin::Mcp,
+        created_at: &now,
+        updated_at: &now,
+        owner: None,
+        visibility: Visibility::Shared,
+    };
+    let id = store.db.insert(&record).unwrap();
+    store
+        .db
+        .insert(&NewMemory {
+            content: "other project",
+            project_id: "q",
+            ..record
+        })
+        .unwrap();
+
+    assert_eq!(store.get(&id).unwrap().unwrap().content, "use postgres");
+    assert_eq!(store.list("p", 10).unwrap().len(), 1);
+    assert_eq!(
+        store
+            .list_filtered("p", 10, Some(MemoryKind::Task))
+            .unwrap()
+            .len(),
+        0
+    );
+    let by_origin = |origin: Origin| {
+        store
+            .list_with("p", &ListOptions::default().with_origin(origin))
+            .unwrap()
+            .len()
+    };
+    assert_eq!(by_origin(Origin::Mcp), 1);
+    assert_eq!(by_origin(Origin::Cli), 0);
+
+    // Default capabilities: conflict search via `search`, no keyword hits
+    let similar = store
+        .db
+        .find_similar("p", &axis(0), 0.9, &SearchFilter::default())
+        .unwrap();
+    assert_eq!(similar.len(), 1);
+    assert!(
+        store
+            .db
+            .search_keyword(
+                "postgres",
+                QuerySyntax::Plain,
+                "p",
+                10,
+                &SearchFilter::default(),
+                &[],
+                FieldWeights::default()
+            )
+            .unwrap()
+            .is_empty()
+    );
+
+    store
+        .update_metadata(&id, Some(r#"{"topic": "db"}"#))
+        .unwrap();
+    let memory = store.get(&id).unwrap().unwrap();
+    assert_eq!(memory.metadata.as_deref(), Some(r#"{"topic": "db"}"#));
+    assert_eq!(memory.content, "use postgres");
+
+    assert!(store.delete(&id).unwrap());
+    assert!(store.get(&id).unwrap().is_none());
+}
+
+#[test]
+fn test_custom_backend_without_eviction_rejects_over_quota() {
+    let store = MemoryStore::with_backend(
+        VecBackend::default(),
+        "BAAI/bge-small-en-v1.5",
+        Config {
+            max_memories_per_project: Some(1),
+            max_total_db_size_mb: Some(1),
+            evict_on_quota: true,
+            ..Config::default()
+        },
+    );
+    let now = chrono::Utc::now().to_rfc3339();
+    store
+        .db
+        .insert(&NewMemory {
+            project_id: "p",
+            content: "first",
+            embedding: &axis(0),
+            metadata: None,
+            kind: MemoryKind::Other,
+            origin: &Origin::Api,
+            created_at: &now,
+            updated_at: &now,
+            owner: None,
+            visibility: Visibility::Shared,
+        })
+        .unwrap();
+
+    assert!(store.enforce_quota("q", 0).is_ok());
+    assert!(matches!(
+        store.enforce_quota("p", 0),
+        Err(Error::QuotaExceeded(_))
+    ));
+    assert_eq!(store.prune().unwrap().removed, 0);
+}
+
+#[test]
+fn test_shared_store_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<shared::SharedMemoryStore>();
+}
+
+#[test]
+fn test_shared_store_serves_concurrent_threads() {
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    let dir = TempDir::new().unwrap();
+    let store = Arc::new(
+        shared::SharedMemoryStore::new(
+            &dir.path().join("shared.db"),
+            "BAAI/bge-small-en-v1.5",
+            Config::default(),
+            3,
+        )
+        .unwrap(),
+    );
+
+    let handles: Vec<_> = (0..8)
+        .map(|thread| {
+            let store = Arc::clone(&store);
+            std::thread::spawn(move || {
+                for i in 0..10 {
+                    let content = format!("thread {} memory {}", thread, i);
+                    let id = store
+                        .with_store(|s| s.db.insert("p", &content, &vec![0.1; 384], None))
+                        .unwrap();
+                    store
+                        .update_metadata(&id, Some(r#"{"seen": true}"#))
+                        .unwrap();
+                    assert_eq!(store.get(&id).unwrap().unwrap().content, content);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(store.list("p", 100, None).unwrap().len(), 80);
+    assert!(!store.is_warm());
+}
+
+#[test]
+fn test_shared_store_pool_size() {
+    assert!(matches!(
+        shared::SharedMemoryStore::new(
+            std::path::Path::new(store::IN_MEMORY_DB_PATH),
+            "BAAI/bge-small-en-v1.5",
+            Config::default(),
+            0,
+        ),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // Every in-memory connection is its own database, so the pool keeps one
+    let store = shared::SharedMemoryStore::new(
+        std::path::Path::new(store::IN_MEMORY_DB_PATH),
+        "BAAI/bge-small-en-v1.5",
+        Config::default(),
+        4,
+    )
+    .unwrap();
+    let id = store
+        .with_store(|s| s.db.insert("p", "kept", &vec![0.1; 384], None))
+        .unwrap();
+    assert!(store.delete(&id).unwrap());
+    assert!(store.get(&id).unwrap().is_none());
+}
+
+#[test]
+fn test_shared_store_owner_applies_to_every_pooled_store() {
+    use tempfile::TempDir;
+
+    let dir = TempDir::new().unwrap();
+    let store = shared::SharedMemoryStore::new(
+        &dir.path().join("shared.db"),
+        "BAAI/bge-small-en-v1.5",
+        Config::default(),
+        3,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        store.set_owner(None, Visibility::Private),
+        Err(Error::InvalidInput(_))
+    ));
+    store
+        .set_owner(Some("alice".to_string()), Visibility::Private)
+        .unwrap();
+
+    // Check out every pooled store at once so each one is seen
+    let barrier = std::sync::Barrier::new(3);
+    std::thread::scope(|scope| {
+        for _ in 0..3 {
+            scope.spawn(|| {
+                store.with_store(|s| {
+                    assert_eq!(s.owner(), Some("alice"));
+                    assert_eq!(s.visibility(), Visibility::Private);
+                    barrier.wait();
+                })
+            });
+        }
+    });
+
+    // Changing the owner of a checked-out store lasts only for that call
+    store.with_store(|s| s.set_owner(None, Visibility::Shared).unwrap());
+    store.with_store(|s| assert_eq!(s.owner(), Some("alice")));
+}
@@ -0,0 +1,485 @@
+//! Adding, reading, updating and deleting memories.
+
+use super::*;
+
+#[test]
+fn test_memory_store_new() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let _path = dir.path().join("test.db");
+
+    // Note: This test is ignored because it requires downloading the model
+    // Use `cargo test -- --ignored` to run it
+}
+
+#[test]
+fn test_add_and_get() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let embedding = vec![0.5f32; 384];
+    let id = db
+        .insert("test-project", "test content", &embedding, Some("metadata"))
+        .unwrap();
+
+    let memory = db.get(&id).unwrap().unwrap();
+    assert_eq!(memory.id, id);
+    assert_eq!(memory.content, "test content");
+    assert_eq!(memory.project_id, "test-project");
+    assert_eq!(memory.metadata, Some("metadata".to_string()));
+}
+
+#[test]
+fn test_search_basic() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let embedding_match = vec![1.0f32; 384];
+    let embedding_other = vec![0.0f32; 384];
+
+    let id_match = db
+        .insert("test-project", "matching content", &embedding_match, None)
+        .unwrap();
+    db.insert("test-project", "other content", &embedding_other, None)
+        .unwrap();
+
+    let results = db.search("test-project", &embedding_match, 5).unwrap();
+    assert!(!results.is_empty());
+    assert_eq!(results[0].id, id_match);
+    assert!(results[0].similarity.unwrap() > 0.9);
+}
+
+#[test]
+fn test_list_by_project() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let embedding = vec![0.5f32; 384];
+
+    db.insert("project-a", "content a", &embedding, None)
+        .unwrap();
+    db.insert("project-b", "content b", &embedding, None)
+        .unwrap();
+    db.insert("project-a", "content a2", &embedding, None)
+        .unwrap();
+
+    let memories_a = db.list("project-a", 10).unwrap();
+    assert_eq!(memories_a.len(), 2);
+
+    let memories_b = db.list("project-b", 10).unwrap();
+    assert_eq!(memories_b.len(), 1);
+}
+
+#[test]
+fn test_update_memory() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let embedding_old = vec![0.3f32; 384];
+    let embedding_new = vec![0.8f32; 384];
+
+    let id = db
+        .insert("test-project", "original", &embedding_old, None)
+        .unwrap();
+
+    db.update(&id, "updated", &embedding_new).unwrap();
+
+    let memory = db.get(&id).unwrap().unwrap();
+    assert_eq!(memory.content, "updated");
+    assert_ne!(memory.created_at, memory.updated_at);
+}
+
+#[test]
+fn test_update_nonexistent() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let embedding = vec![0.5f32; 384];
+
+    let result = db.update("does-not-exist", "content", &embedding);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_delete_memory() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let embedding = vec![0.5f32; 384];
+
+    let id = db
+        .insert("test-project", "to delete", &embedding, None)
+        .unwrap();
+    assert!(db.delete(&id).unwrap());
+
+    let memory = db.get(&id).unwrap();
+    assert!(memory.is_none());
+}
+
+#[test]
+fn test_delete_nonexistent() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let deleted = db.delete("does-not-exist").unwrap();
+    assert!(!deleted);
+}
+
+#[test]
+fn test_get_nonexistent() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let memory = db.get("does-not-exist").unwrap();
+    assert!(memory.is_none());
+}
+
+#[test]
+fn test_search_sorting() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let embedding_high = vec![1.0f32; 384];
+    let embedding_medium = vec![0.5f32; 384];
+    let embedding_low = vec![0.0f32; 384];
+
+    db.insert("test-project", "low", &embedding_low, None)
+        .unwrap();
+    db.insert("test-project", "high", &embedding_high, None)
+        .unwrap();
+    db.insert("test-project", "medium", &embedding_medium, None)
+        .unwrap();
+
+    let results = db.search("test-project", &embedding_high, 10).unwrap();
+    assert!(results[0].similarity.unwrap() >= results[1].similarity.unwrap());
+    assert!(results[1].similarity.unwrap() >= results[2].similarity.unwrap());
+}
+
+#[test]
+fn test_negative_similarity() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    let embedding_pos = vec![1.0f32; 384];
+    let embedding_neg = vec![-1.0f32; 384];
+
+    db.insert("test-project", "positive", &embedding_pos, None)
+        .unwrap();
+
+    let results = db.search("test-project", &embedding_neg, 10).unwrap();
+    assert!(!results.is_empty());
+    assert!(results[0].similarity.unwrap() < 0.0);
+}
+
+#[ignore]
+#[test]
+fn test_integration_preview_add_writes_nothing() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    use crate::memory_types::AddPreview;
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    assert!(matches!(
+        store.preview_add("p", "Alice works at Microsoft").unwrap(),
+        AddPreview::WouldAdd
+    ));
+    assert_eq!(store.db.count_project("p").unwrap(), 0);
+
+    store
+        .add_with_conflict("p", "Alice works at Microsoft", None, false)
+        .unwrap();
+    match store.preview_add("p", "Alice works at Microsoft").unwrap() {
+        AddPreview::Conflicts { conflicts, .. } => {
+            assert_eq!(conflicts.len(), 1);
+            assert!(conflicts[0].similarity > 0.99);
+        }
+        AddPreview::WouldAdd => panic!("Expected AddPreview::Conflicts"),
+    }
+    assert_eq!(store.db.count_project("p").unwrap(), 1);
+    assert!(matches!(
+        store.preview_add("p", "  "),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[ignore]
+#[test]
+fn test_integration_update_changes_embedding() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let config = Config::default();
+
+    let mut store = MemoryStore::new(&path, "BAAI/bge-small-en-v1.5", config).unwrap();
+
+    let id = match store
+        .add_with_conflict("test-project", "original content", None, false)
+        .unwrap()
+    {
+        crate::memory_types::AddResult::Added { id } => id,
+        _ => panic!("Expected AddResult::Added"),
+    };
+
+    store.update(&id, "completely different content").unwrap();
+
+    let memory = store.get(&id).unwrap().unwrap();
+    assert_eq!(memory.content, "completely different content");
+}
+
+#[ignore]
+#[test]
+fn test_integration_append_extends_content() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    let id = match store
+        .add_with_conflict("test-project", "Alice works at Microsoft", None, false)
+        .unwrap()
+    {
+        crate::memory_types::AddResult::Added { id } => id,
+        _ => panic!("Expected AddResult::Added"),
+    };
+
+    let content = store.append(&id, "  She leads the Azure team. ").unwrap();
+    assert!(content.starts_with("Alice works at Microsoft\n\n["));
+    assert!(content.ends_with(" UTC] She leads the Azure team."));
+    assert_eq!(store.get(&id).unwrap().unwrap().content, content);
+
+    // The content before the append is kept as an earlier version
+    let versions = store.history(&id).unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].content, "Alice works at Microsoft");
+}
+
+#[test]
+fn test_append_checks_text_and_memory() {
+    let mut store = MemoryStore::open_without_embedder(
+        std::path::Path::new(store::IN_MEMORY_DB_PATH),
+        Config::default(),
+    )
+    .unwrap();
+    let id = store
+        .db
+        .insert("p", "original", &vec![0.1; 384], None)
+        .unwrap();
+    assert!(matches!(store.append(&id, "  "), Err(Error::EmptyInput)));
+    assert!(matches!(
+        store.append("missing", "more"),
+        Err(Error::NotFound(_))
+    ));
+    assert_eq!(store.get(&id).unwrap().unwrap().content, "original");
+}
+
+#[test]
+fn test_history_keeps_replaced_content() {
+    let store = MemoryStore::open_without_embedder(
+        std::path::Path::new(store::IN_MEMORY_DB_PATH),
+        Config::default(),
+    )
+    .unwrap();
+    let id = store
+        .db
+        .insert("p", "original", &vec![0.1; 384], None)
+        .unwrap();
+    assert!(store.history(&id).unwrap().is_empty());
+
+    // What `append` persists: the old content followed by the addition
+    store
+        .db
+        .update(
+            &id,
+            "original\n\n[2026-01-15 10:30 UTC] more",
+            &vec![0.1; 384],
+        )
+        .unwrap();
+    let versions = store.history(&id).unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].content, "original");
+    assert!(matches!(store.history("missing"), Err(Error::NotFound(_))));
+}
+
+#[ignore]
+#[test]
+fn test_integration_update_with_conflict_ignores_itself() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    use crate::memory_types::UpdateResult;
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+
+    let add = |store: &mut MemoryStore, content: &str| match store
+        .add_with_conflict("test-project", content, None, true)
+        .unwrap()
+    {
+        crate::memory_types::AddResult::Added { id } => id,
+        _ => panic!("Expected AddResult::Added"),
+    };
+    let id = add(&mut store, "Alice works at Microsoft");
+    let other = add(&mut store, "The build uses cargo nextest");
+
+    // Rewording a memory does not conflict with its own old content
+    let result = store
+        .update_with_conflict(&id, "Alice works at Microsoft Research", false)
+        .unwrap();
+    assert!(matches!(result, UpdateResult::Updated { .. }));
+
+    // Duplicating another memory does
+    let result = store
+        .update_with_conflict(&id, "The build uses cargo nextest", false)
+        .unwrap();
+    match result {
+        UpdateResult::Conflicts { conflicts, .. } => {
+            let stored = store.get(&other).unwrap().unwrap();
+            assert_eq!(conflicts[0].id, other);
+            assert_eq!(conflicts[0].created_at, stored.created_at);
+            assert_eq!(conflicts[0].updated_at, stored.updated_at);
+            assert_eq!(conflicts[0].metadata, None);
+        }
+        _ => panic!("Expected UpdateResult::Conflicts"),
+    }
+    assert_eq!(
+        store.get(&id).unwrap().unwrap().content,
+        "Alice works at Microsoft Research"
+    );
+}
+
+#[test]
+fn test_new_in_memory_does_not_share_state() {
+    let store = MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    let id = store
+        .db
+        .insert("p", "ephemeral", &[0.5f32; 384], None)
+        .unwrap();
+    assert!(store.get(&id).unwrap().is_some());
+    assert_eq!(store.db.search_bm25("ephemeral", "p", 5).unwrap().len(), 1);
+
+    let other = MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    assert!(other.get(&id).unwrap().is_none());
+}
+
+#[test]
+fn test_memory_db_path_opens_in_memory() {
+    let path = std::path::Path::new(store::IN_MEMORY_DB_PATH);
+    let store = MemoryStore::open_without_embedder(path, Config::default()).unwrap();
+    store.db.insert("p", "x", &[0.5f32; 384], None).unwrap();
+    assert_eq!(store.list("p", 10).unwrap().len(), 1);
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_set_title_and_list_titles() {
+    let store = MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+    let embedding = vec![0.5f32; 384];
+    let id = store
+        .db
+        .insert(
+            "p",
+            "Prefer rebase over merge. Keeps history linear.",
+            &embedding,
+            None,
+        )
+        .unwrap();
+    let listed = store.list("p", 10).unwrap();
+    assert_eq!(listed[0].title.as_deref(), Some("Prefer rebase over merge"));
+
+    store.set_title(&id, Some("  Git workflow ")).unwrap();
+    assert_eq!(
+        store.get(&id).unwrap().unwrap().title.as_deref(),
+        Some("Git workflow")
+    );
+    assert!(matches!(
+        store.set_title(&id, Some(" ")),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_list_with_time_range() {
+    let store = MemoryStore::open_without_embedder(
+        std::path::Path::new(store::IN_MEMORY_DB_PATH),
+        Config::default(),
+    )
+    .unwrap();
+    let old = store.db.insert("p", "old", &[0.5f32; 384], None).unwrap();
+    let recent = store
+        .db
+        .insert("p", "recent", &[0.5f32; 384], None)
+        .unwrap();
+    store
+        .db
+        .conn()
+        .execute(
+            "UPDATE memories SET updated_at = '2024-01-01T00:00:00+00:00' WHERE id = ?1",
+            [&old],
+        )
+        .unwrap();
+
+    let ids = |options: ListOptions| -> Vec<String> {
+        store
+            .list_with("p", &options)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect()
+    };
+    let yesterday = crate::timeparse::parse("yesterday").unwrap();
+    let new_year = crate::timeparse::parse("2024-01-01").unwrap();
+    assert_eq!(
+        ids(ListOptions::default().with_since(yesterday)),
+        vec![recent]
+    );
+    assert_eq!(
+        ids(ListOptions::default().with_before(yesterday)),
+        vec![old.clone()]
+    );
+    // `since` is inclusive, `before` exclusive
+    assert_eq!(
+        ids(ListOptions::default()
+            .with_since(new_year)
+            .with_before(yesterday)),
+        vec![old]
+    );
+    assert!(ids(ListOptions::default().with_before(new_year)).is_empty());
+    assert!(matches!(
+        store.list_with(
+            "p",
+            &ListOptions::default()
+                .with_since(yesterday)
+                .with_before(new_year)
+        ),
+        Err(Error::InvalidInput(_))
+    ));
+}
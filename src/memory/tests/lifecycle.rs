@@ -0,0 +1,451 @@
+//! Quotas, pruning, trash, health checks and the review queue.
+
+use super::*;
+
+fn quota_store(config: Config) -> MemoryStore {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+    MemoryStore::new(&path, "BAAI/bge-small-en-v1.5", config).unwrap()
+}
+
+#[test]
+fn test_enforce_quota_rejects_when_project_full() {
+    let config = Config {
+        max_memories_per_project: Some(2),
+        ..Config::default()
+    };
+    let store = quota_store(config);
+    let embedding = vec![0.5f32; 384];
+    store.db.insert("p", "one", &embedding, None).unwrap();
+    assert!(store.enforce_quota("p", 0).is_ok());
+    store.db.insert("p", "two", &embedding, None).unwrap();
+
+    assert!(matches!(
+        store.enforce_quota("p", 0),
+        Err(crate::errors::Error::QuotaExceeded(_))
+    ));
+    // Other projects are unaffected
+    assert!(store.enforce_quota("other", 0).is_ok());
+}
+
+#[test]
+fn test_enforce_quota_evicts_oldest_in_project() {
+    let config = Config {
+        max_memories_per_project: Some(2),
+        evict_on_quota: true,
+        ..Config::default()
+    };
+    let store = quota_store(config);
+    let embedding = vec![0.5f32; 384];
+    let ts = |day: u32| format!("2024-01-{:02}T00:00:00Z", day);
+    let oldest = store
+        .db
+        .insert_with_time("p", "one", &embedding, None, &ts(1), &ts(1))
+        .unwrap();
+    let newer = store
+        .db
+        .insert_with_time("p", "two", &embedding, None, &ts(2), &ts(2))
+        .unwrap();
+
+    store.enforce_quota("p", 0).unwrap();
+
+    assert!(store.get(&oldest).unwrap().is_none());
+    assert!(store.get(&newer).unwrap().is_some());
+}
+
+#[test]
+fn test_enforce_quota_db_size() {
+    let config = Config {
+        max_total_db_size_mb: Some(1),
+        ..Config::default()
+    };
+    let store = quota_store(config);
+    let embedding = vec![0.5f32; 384];
+    assert!(store.enforce_quota("p", 0).is_ok());
+
+    let content = "x".repeat(10_000);
+    for _ in 0..150 {
+        store.db.insert("p", &content, &embedding, None).unwrap();
+    }
+    assert!(matches!(
+        store.enforce_quota("p", 0),
+        Err(crate::errors::Error::QuotaExceeded(_))
+    ));
+
+    let config = Config {
+        max_total_db_size_mb: Some(1),
+        evict_on_quota: true,
+        ..store.config.clone()
+    };
+    let store = MemoryStore { config, ..store };
+    let used = store.db.used_size_bytes().unwrap();
+    store.enforce_quota("p", 11_536).unwrap();
+
+    // Only as many memories as the excess payload needs are evicted
+    let excess = used + 11_536 - 1024 * 1024;
+    let left = store.db.count_project("p").unwrap();
+    assert!(left > 0);
+    assert!(left >= 150 - crate::memory::quota::MAX_EVICTIONS_PER_ADD);
+    assert!((150 - left) as u64 * 11_536 < excess + 11_536);
+}
+
+#[test]
+fn test_enforce_quota_db_size_only_evicts_the_project() {
+    let config = Config {
+        max_total_db_size_mb: Some(1),
+        evict_on_quota: true,
+        ..Config::default()
+    };
+    let store = quota_store(config);
+    let embedding = vec![0.5f32; 384];
+    let other = "y".repeat(10_000);
+    for _ in 0..150 {
+        store.db.insert("other", &other, &embedding, None).unwrap();
+    }
+    store.db.insert("p", "small", &embedding, None).unwrap();
+
+    // Evicting all of "p" cannot make room, so nothing is evicted
+    assert!(matches!(
+        store.enforce_quota("p", 11_536),
+        Err(crate::errors::Error::QuotaExceeded(_))
+    ));
+    assert_eq!(store.db.count_project("p").unwrap(), 1);
+    assert_eq!(store.db.count_project("other").unwrap(), 150);
+}
+
+#[test]
+fn test_open_without_embedder_rejects_embedding_operations() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+
+    let mut store = MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+    let id = store
+        .db
+        .insert("p", "stored content", &[0.5f32; 384], None)
+        .unwrap();
+
+    // Metadata-only operations work without the model
+    assert!(store.get(&id).unwrap().is_some());
+    assert_eq!(store.list("p", 10).unwrap().len(), 1);
+
+    assert!(matches!(
+        store.add_with_conflict("p", "new content", None, false),
+        Err(crate::errors::Error::Config(_))
+    ));
+    assert!(matches!(
+        store.query("p", "query", SearchOptions::default()),
+        Err(crate::errors::Error::Config(_))
+    ));
+    assert!(matches!(
+        store.warm_up(),
+        Err(crate::errors::Error::Config(_))
+    ));
+    // Nothing has mismatched dimensions, so the model isn't needed
+    assert_eq!(store.reembed(true).unwrap(), 0);
+    assert!(matches!(
+        store.reembed(false),
+        Err(crate::errors::Error::Config(_))
+    ));
+    assert!(store.embedder.lock().unwrap().is_none());
+
+    assert!(store.delete(&id).unwrap());
+}
+
+#[test]
+fn test_scan_embeddings_finds_contaminated_rows() {
+    use tempfile::TempDir;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    // Without FTS triggers, the embedding can be overwritten by a plain connection
+    let config = Config {
+        enable_fts: false,
+        ..Config::default()
+    };
+    let mut store = MemoryStore::open_without_embedder(&path, config).unwrap();
+    let id = store
+        .db
+        .insert("p", "stored content", &[0.5f32; 384], None)
+        .unwrap();
+    let mut nan = vec![0.5f32; 384];
+    nan[0] = f32::NAN;
+    let rejected: crate::errors::Error =
+        store.db.insert("p", "bad", &nan, None).unwrap_err().into();
+    assert!(matches!(
+        rejected,
+        crate::errors::Error::InvalidEmbedding(_)
+    ));
+    assert_eq!(store.scan_embeddings(true).unwrap().contaminated.len(), 0);
+
+    let blob: Vec<u8> = nan.iter().flat_map(|x| x.to_le_bytes()).collect();
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute(
+            "UPDATE memories SET embedding = ?2 WHERE id = ?1",
+            rusqlite::params![id, blob],
+        )
+        .unwrap();
+    let scan = store.scan_embeddings(false).unwrap();
+    assert_eq!(scan.contaminated, vec![id]);
+    assert_eq!(scan.reembedded, 0);
+    // Repairing needs the model
+    assert!(matches!(
+        store.scan_embeddings(true),
+        Err(crate::errors::Error::Config(_))
+    ));
+}
+
+#[test]
+fn test_health_check_reports_missing_embedder() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+
+    let mut store = MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+    store
+        .db
+        .insert("p", "stored content", &[0.5f32; 384], None)
+        .unwrap();
+
+    let report = store.health_check().unwrap();
+    assert!(!report.healthy);
+    assert!(!report.tokenizer_loaded);
+    assert_eq!(report.dims, 0);
+    assert!(report.embed_ms.is_none());
+    assert!(report.error.is_some());
+    assert_eq!(report.schema_version, crate::sqlite::SCHEMA_VERSION);
+    assert_eq!((report.memory_rows, report.fts_rows), (1, 1));
+    assert!(report.fts_in_sync);
+}
+
+#[ignore]
+#[test]
+fn test_integration_health_check_passes_with_model() {
+    // Requires: cargo test -- --ignored
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    let report = store.health_check().unwrap();
+    assert!(report.healthy, "{:?}", report.error);
+    assert!(report.tokenizer_loaded);
+    assert_eq!(report.dims, crate::embedding::EMBEDDING_DIMS);
+    assert!(report.embed_ms.is_some());
+}
+
+#[test]
+fn test_prune_applies_ttl_per_project() {
+    use crate::config::RetentionConfig;
+    use crate::config::retention::ProjectRetention;
+
+    let mut retention = RetentionConfig {
+        default_ttl: Some("30d".to_string()),
+        ..Default::default()
+    };
+    retention.projects.insert(
+        "keep".to_string(),
+        ProjectRetention {
+            ttl: Some("never".to_string()),
+        },
+    );
+    let store = quota_store(Config {
+        retention,
+        ..Config::default()
+    });
+
+    let embedding = vec![0.5f32; 384];
+    let old = "2020-01-01T00:00:00Z";
+    let expired = store
+        .db
+        .insert_with_time("p", "old", &embedding, None, old, old)
+        .unwrap();
+    let kept = store
+        .db
+        .insert_with_time("keep", "old", &embedding, None, old, old)
+        .unwrap();
+    let fresh = store.db.insert("p", "new", &embedding, None).unwrap();
+
+    let stats = store.prune().unwrap();
+    assert_eq!(stats.removed, 1);
+    assert_eq!(stats.projects, 1);
+    assert!(!stats.archived);
+    assert!(store.get(&expired).unwrap().is_none());
+    assert!(store.get(&kept).unwrap().is_some());
+    assert!(store.get(&fresh).unwrap().is_some());
+}
+
+#[test]
+fn test_prune_without_rules_is_noop() {
+    let store = quota_store(Config::default());
+    let old = "2020-01-01T00:00:00Z";
+    store
+        .db
+        .insert_with_time("p", "old", &[0.5f32; 384], None, old, old)
+        .unwrap();
+    assert_eq!(store.prune().unwrap().removed, 0);
+    assert_eq!(store.db.count_project("p").unwrap(), 1);
+}
+
+#[test]
+fn test_delete_moves_to_trash_when_retained() {
+    let store = quota_store(Config {
+        trash_retention_days: Some(30),
+        ..Config::default()
+    });
+    let id = store.db.insert("p", "kept", &[0.5f32; 384], None).unwrap();
+
+    assert!(store.delete(&id).unwrap());
+    assert!(store.get(&id).unwrap().is_none());
+    let trashed = store.trashed("p").unwrap();
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].memory.id, id);
+
+    // Trashed memories are only purged once the retention window has passed
+    assert_eq!(store.prune().unwrap().trash_purged, 0);
+    assert!(store.restore(&id).unwrap());
+    assert_eq!(store.get(&id).unwrap().unwrap().content, "kept");
+
+    assert!(store.delete(&id).unwrap());
+    assert_eq!(store.empty_trash("p").unwrap(), 1);
+    assert!(!store.restore(&id).unwrap());
+}
+
+#[test]
+fn test_delete_is_permanent_without_trash() {
+    let store = quota_store(Config::default());
+    let id = store.db.insert("p", "gone", &[0.5f32; 384], None).unwrap();
+    assert!(store.delete(&id).unwrap());
+    assert!(store.trashed("p").unwrap().is_empty());
+    assert!(!store.restore(&id).unwrap());
+}
+
+#[test]
+fn test_clusters_group_memories_by_embedding() {
+    let store = quota_store(Config::default());
+    let mut first = vec![0.0f32; 384];
+    first[0] = 1.0;
+    let mut second = vec![0.0f32; 384];
+    second[1] = 1.0;
+    for (content, embedding) in [
+        ("rust borrow checker", &first),
+        ("rust lifetimes", &first),
+        ("coffee brewing", &second),
+    ] {
+        store.db.insert("p", content, embedding, None).unwrap();
+    }
+
+    let clustering = store.clusters("p", 5).unwrap();
+    assert_eq!(clustering.clusters.len(), 2);
+    assert_eq!(clustering.clusters[0].size, 2);
+    assert!(clustering.clusters[0].terms.contains(&"rust".to_string()));
+    assert_eq!(clustering.assignments.len(), 3);
+    assert!(
+        clustering
+            .assignments
+            .iter()
+            .all(|a| (a.similarity - 1.0).abs() < 1e-6)
+    );
+    assert!(matches!(
+        store.clusters("p", 0),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(store.clusters("empty", 3).unwrap().clusters.is_empty());
+}
+
+#[test]
+fn test_prune_marks_idle_memories_inactive_for_search() {
+    let mut store = MemoryStore::open_without_embedder(
+        std::path::Path::new(store::IN_MEMORY_DB_PATH),
+        Config {
+            inactive_after_days: Some(30),
+            ..Config::default()
+        },
+    )
+    .unwrap();
+    let idle = store.db.insert("p", "idle", &[0.5f32; 384], None).unwrap();
+    store
+        .db
+        .insert("p", "recent", &[0.5f32; 384], None)
+        .unwrap();
+    store
+        .db
+        .conn()
+        .execute(
+            "UPDATE memories SET updated_at = '2024-01-01T00:00:00+00:00' WHERE id = ?1",
+            [&idle],
+        )
+        .unwrap();
+
+    assert_eq!(store.prune().unwrap().deactivated, 1);
+    let found = |store: &mut MemoryStore, options: SearchOptions| {
+        store
+            .query_embedded("p", "query", &[0.5f32; 384], &options)
+            .unwrap()
+            .len()
+    };
+    assert_eq!(found(&mut store, SearchOptions::default()), 1);
+    assert_eq!(
+        found(
+            &mut store,
+            SearchOptions::default().with_include_inactive(true)
+        ),
+        2
+    );
+    // Reading it makes it active again
+    store.get(&idle).unwrap();
+    assert_eq!(found(&mut store, SearchOptions::default()), 2);
+}
+
+#[test]
+fn test_review_queue_and_decisions() {
+    use crate::config::ReviewConfig;
+    use crate::memory_types::{ReviewAction, ReviewDecision};
+
+    let store = MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+    let old = "2020-01-01T00:00:00Z";
+    let insert = |metadata: Option<&str>, ts: &str| {
+        store
+            .db
+            .insert_with_time("p", "content", &[0.1; 384], metadata, ts, ts)
+            .unwrap()
+    };
+    // Importance 9 has decayed far below 3 after years of idleness
+    let important = insert(Some(r#"{"importance":9}"#), old);
+    let unrated = insert(None, old);
+    let now = chrono::Utc::now().to_rfc3339();
+    insert(Some(r#"{"importance":1}"#), &now);
+    insert(Some(r#"{"pinned":true}"#), old);
+
+    let review = ReviewConfig::default();
+    let queue = store.review_queue("p", &review, 10).unwrap();
+    let ids: Vec<_> = queue.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, [unrated.as_str(), important.as_str()]);
+    assert_eq!(queue[1].importance, Some(9.0));
+    assert!(queue[1].decayed_importance < 0.1);
+
+    // A threshold the decayed importance is above keeps it out
+    let strict = ReviewConfig {
+        half_life_days: 100_000,
+        ..review
+    };
+    assert_eq!(store.review_queue("p", &strict, 10).unwrap().len(), 1);
+
+    let decide = |id: &str, action| ReviewDecision {
+        id: id.to_string(),
+        action,
+    };
+    let stats = store
+        .apply_review(&[
+            decide(&unrated, ReviewAction::Keep),
+            decide(&important, ReviewAction::Archive),
+            decide("missing", ReviewAction::Delete),
+        ])
+        .unwrap();
+    assert_eq!((stats.kept, stats.archived, stats.deleted), (1, 1, 0));
+    assert_eq!(stats.not_found, ["missing"]);
+    assert!(store.db.get(&important).unwrap().is_none());
+    assert_eq!(store.db.count_archived("p").unwrap(), 1);
+    // Kept memories leave the queue until they are idle again
+    assert!(store.review_queue("p", &review, 10).unwrap().is_empty());
+}
@@ -0,0 +1,232 @@
+//! Metadata updates, the metadata schema and write hooks.
+
+use super::*;
+
+#[cfg(unix)]
+#[test]
+fn test_delete_and_metadata_update_run_hooks() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let log = dir.path().join("hooks.log");
+    let command = format!(
+        "echo \"$VIPUNE_HOOK_EVENT $VIPUNE_MEMORY_ID\" >> '{}'",
+        log.display()
+    );
+    let mut config = Config::default();
+    config.hooks.on_update = Some(command.clone());
+    config.hooks.on_delete = Some(command);
+    let store = MemoryStore::open_without_embedder(":memory:".as_ref(), config).unwrap();
+
+    let id = store
+        .db
+        .insert_with_kind("p", "hooked", &[0.1; 384], None, MemoryKind::Fact)
+        .unwrap();
+    store.update_metadata(&id, Some(r#"{"k":1}"#)).unwrap();
+    assert!(store.delete(&id).unwrap());
+    // Deleting a missing memory runs no hook
+    assert!(!store.delete(&id).unwrap());
+
+    let logged = std::fs::read_to_string(&log).unwrap();
+    assert_eq!(logged, format!("update {id}\ndelete {id}\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bulk_session_runs_hooks_after_commit() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let log = dir.path().join("hooks.log");
+    let mut config = Config::default();
+    config.hooks.on_delete = Some(format!(
+        "echo \"$VIPUNE_HOOK_EVENT $VIPUNE_MEMORY_ID\" >> '{}'",
+        log.display()
+    ));
+    let mut store = MemoryStore::open_without_embedder(":memory:".as_ref(), config).unwrap();
+    let first = store.db.insert("p", "first", &[0.1; 384], None).unwrap();
+    let second = store.db.insert("p", "second", &[0.1; 384], None).unwrap();
+
+    // A session dropped without committing rolls back and runs no hook
+    {
+        let session = store.bulk_session().unwrap();
+        assert!(session.delete(&first).unwrap());
+    }
+    assert!(store.get(&first).unwrap().is_some());
+    assert!(!log.exists());
+
+    let session = store.bulk_session().unwrap();
+    assert!(session.delete(&second).unwrap());
+    assert!(!log.exists());
+    session.commit().unwrap();
+    let logged = std::fs::read_to_string(&log).unwrap();
+    assert_eq!(logged, format!("delete {second}\n"));
+}
+
+#[test]
+fn test_metadata_update_and_touch_keep_content_and_embedding() {
+    let store = MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+    let id = store
+        .db
+        .insert_with_time(
+            "p",
+            "kept",
+            &[0.25; 384],
+            None,
+            "2020-01-01T00:00:00Z",
+            "2020-01-01T00:00:00Z",
+        )
+        .unwrap();
+    let embedding = |store: &MemoryStore| store.db.embeddings_for(&[&id]).unwrap();
+    let before = embedding(&store);
+
+    store
+        .update_metadata(&id, Some(r#"{"topic":"db"}"#))
+        .unwrap();
+    store.touch(&id).unwrap();
+    let memory = store.get(&id).unwrap().unwrap();
+    assert_eq!(memory.content, "kept");
+    assert_eq!(memory.metadata.as_deref(), Some(r#"{"topic":"db"}"#));
+    assert!(memory.updated_at.as_str() > "2020-01-01T00:00:00Z");
+    assert_eq!(embedding(&store), before);
+
+    assert!(matches!(store.touch("missing"), Err(Error::NotFound(_))));
+}
+
+#[test]
+fn test_update_metadata_where_patches_matching_memories() {
+    use serde_json::json;
+
+    let config = Config {
+        metadata_schema: toml::from_str("[fields]\nstatus = [\"open\", \"done\"]").unwrap(),
+        ..Config::default()
+    };
+    let store = MemoryStore::open_without_embedder(":memory:".as_ref(), config).unwrap();
+    let embedding = [0.25; 384];
+    let tagged = store
+        .db
+        .insert("p", "a", &embedding, Some(r#"{"tags":["old"],"n":1}"#))
+        .unwrap();
+    let done = store
+        .db
+        .insert(
+            "p",
+            "b",
+            &embedding,
+            Some(r#"{"tags":["old"],"status":"done"}"#),
+        )
+        .unwrap();
+    let untagged = store.db.insert("p", "c", &embedding, None).unwrap();
+    let filter = SearchOptions::default().with_tag("old").search_filter();
+    let metadata = |id: &str| -> serde_json::Value {
+        serde_json::from_str(&store.get(id).unwrap().unwrap().metadata.unwrap()).unwrap()
+    };
+
+    let patch = json!({"status": "done", "n": null});
+    let dry = store
+        .update_metadata_where("p", &filter, &patch, true)
+        .unwrap();
+    assert_eq!(dry.matched, 2);
+    assert_eq!(dry.updated, std::slice::from_ref(&tagged));
+    assert_eq!(metadata(&tagged), json!({"tags": ["old"], "n": 1}));
+
+    let update = store
+        .update_metadata_where("p", &filter, &patch, false)
+        .unwrap();
+    assert_eq!(update, dry);
+    assert_eq!(
+        metadata(&tagged),
+        json!({"tags": ["old"], "status": "done"})
+    );
+    assert_eq!(metadata(&done), json!({"tags": ["old"], "status": "done"}));
+    assert!(store.get(&untagged).unwrap().unwrap().metadata.is_none());
+
+    // A patch breaking the schema updates nothing
+    let all = SearchOptions::default().search_filter();
+    assert!(matches!(
+        store.update_metadata_where("p", &all, &json!({"status": "lost"}), false),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(store.get(&untagged).unwrap().unwrap().metadata.is_none());
+    assert!(matches!(
+        store.update_metadata_where("p", &all, &json!(["status"]), false),
+        Err(Error::InvalidInput(_))
+    ));
+
+    let update = store
+        .update_metadata_where("p", &all, &json!({"status": "open"}), false)
+        .unwrap();
+    assert_eq!(update.updated.len(), 3);
+    assert_eq!(metadata(&untagged), json!({"status": "open"}));
+}
+
+#[test]
+fn test_metadata_schema_checks_writes_and_filters() {
+    let config = Config {
+        metadata_schema: toml::from_str(
+            r#"
+            strict = true
+            [fields]
+            importance = "number"
+            status = ["open", "done"]
+            "#,
+        )
+        .unwrap(),
+        ..Config::default()
+    };
+    let mut store = MemoryStore::open_without_embedder(":memory:".as_ref(), config).unwrap();
+    let id = store.db.insert("p", "kept", &[0.25; 384], None).unwrap();
+
+    store
+        .update_metadata(&id, Some(r#"{"importance": 2, "status": "open"}"#))
+        .unwrap();
+    for bad in [r#"{"importance": "high"}"#, r#"{"owner": "alice"}"#] {
+        assert!(matches!(
+            store.update_metadata(&id, Some(bad)),
+            Err(Error::InvalidInput(_))
+        ));
+        // Rejected before the content is embedded
+        assert!(matches!(
+            store.add_with_kind("p", "new", Some(bad), MemoryKind::Other, true),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+    assert_eq!(
+        store.get(&id).unwrap().unwrap().metadata.as_deref(),
+        Some(r#"{"importance": 2, "status": "open"}"#)
+    );
+
+    for spec in ["status=closed", "status>1", "owner=alice"] {
+        let options = SearchOptions::default()
+            .with_metadata_filter(crate::search_options::MetadataFilter::parse(spec).unwrap());
+        assert!(
+            matches!(
+                store.query("p", "anything", options),
+                Err(Error::InvalidInput(_))
+            ),
+            "{spec}"
+        );
+    }
+}
+
+#[test]
+fn test_wait_for_seq_sees_writes_from_other_handles() {
+    use std::time::Duration;
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+
+    let writer = MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+    let reader = MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+    assert_eq!(reader.write_seq().unwrap(), 0);
+
+    let id = writer
+        .db
+        .insert("proj", "written elsewhere", &[0.5f32; 384], None)
+        .unwrap();
+    let seq = writer.write_seq().unwrap();
+    assert_eq!(reader.wait_for_seq(seq, Duration::ZERO).unwrap(), seq);
+
+    assert!(matches!(
+        reader.wait_for_seq(seq + 1, Duration::from_millis(30)),
+        Err(Error::StaleStore { min_seq, .. }) if min_seq == seq + 1
+    ));
+    writer.delete(&id).unwrap();
+    assert!(reader.wait_for_seq(seq + 1, Duration::ZERO).is_ok());
+}
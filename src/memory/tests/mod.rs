@@ -0,0 +1,23 @@
+//! Tests for the memory store.
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::config::Config;
+use crate::errors::Error;
+use crate::fixtures::axis;
+use crate::list_options::ListOptions;
+use crate::search_options::{
+    FieldWeights, QuerySyntax, SearchFilter, SearchOptions, SearchStrategy,
+};
+use crate::sqlite::{Database, Memory, MemoryKind, NewMemory, Origin, Visibility};
+use crate::storage::StorageBackend;
+
+mod backend;
+mod crud;
+mod lifecycle;
+mod metadata;
+mod policy;
+mod ranking;
+mod search;
+mod stores;
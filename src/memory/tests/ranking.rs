@@ -0,0 +1,209 @@
+//! Scoring and ordering of search results.
+
+use super::*;
+
+#[test]
+fn test_query_similarities_scores_bm25_only_hits() {
+    let path = std::path::Path::new(store::IN_MEMORY_DB_PATH);
+    let store = MemoryStore::open_without_embedder(path, Config::default()).unwrap();
+    let mut query = vec![0.0f32; 384];
+    query[0] = 1.0;
+    let mut orthogonal = vec![0.0f32; 384];
+    orthogonal[1] = 1.0;
+    let close = store.db.insert("p", "close", &query, None).unwrap();
+    let far = store.db.insert("p", "far", &orthogonal, None).unwrap();
+
+    let semantic = store.db.search("p", &query, 1).unwrap();
+    let bm25 = store.db.search_bm25("far", "p", 5).unwrap();
+    let similarities = store.query_similarities(&query, &semantic, &bm25).unwrap();
+
+    assert!(similarities[&close] > 0.99);
+    assert!(similarities[&far].abs() < 1e-6);
+}
+
+#[test]
+fn test_hybrid_search_without_fts_ranks_by_similarity() {
+    let path = std::path::Path::new(store::IN_MEMORY_DB_PATH);
+    let config = Config {
+        enable_fts: false,
+        ..Config::default()
+    };
+    let mut store = MemoryStore::open_without_embedder(path, config).unwrap();
+    let mut query = vec![0.0f32; 384];
+    query[0] = 1.0;
+    let mut orthogonal = vec![0.0f32; 384];
+    orthogonal[1] = 1.0;
+    let close = store.db.insert("p", "close", &query, None).unwrap();
+    let far = store.db.insert("p", "far", &orthogonal, None).unwrap();
+
+    assert!(matches!(
+        store.db.search_bm25("far", "p", 5),
+        Err(crate::sqlite::Error::FtsDisabled)
+    ));
+    let options = SearchOptions::default().with_strategy(SearchStrategy::Hybrid);
+    let results = store.query_embedded("p", "far", &query, &options).unwrap();
+    let ids: Vec<&str> = results.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec![close.as_str(), far.as_str()]);
+    // Similarities are kept rather than replaced by fused rank scores
+    assert!(results[0].similarity.unwrap() > 0.99);
+
+    let report = store.db.fts_row_counts();
+    assert!(matches!(report, Err(crate::sqlite::Error::FtsDisabled)));
+}
+
+#[test]
+fn test_explain_reports_scan_shrunk_by_filters() {
+    let path = std::path::Path::new(store::IN_MEMORY_DB_PATH);
+    let mut store = MemoryStore::open_without_embedder(path, Config::default()).unwrap();
+    let embedding = vec![0.1f32; 384];
+    for i in 0..10 {
+        store
+            .db
+            .insert("p", &format!("untagged {}", i), &embedding, None)
+            .unwrap();
+    }
+    let tagged = store
+        .db
+        .insert("p", "tagged", &embedding, Some(r#"{"tags": ["rust"]}"#))
+        .unwrap();
+
+    let scanned = |store: &mut MemoryStore, options: SearchOptions| {
+        let results = store
+            .query_embedded("p", "q", &embedding, &options.with_explain(true))
+            .unwrap();
+        let explanation = results[0].explanation.clone().unwrap();
+        (results.len(), explanation.scanned)
+    };
+    assert_eq!(scanned(&mut store, SearchOptions::default()), (5, Some(11)));
+    let since = chrono::Utc::now() - chrono::Duration::days(1);
+    let options = SearchOptions::default().with_tag("rust").with_since(since);
+    assert_eq!(scanned(&mut store, options.clone()), (1, Some(1)));
+    assert_eq!(
+        store
+            .query_embedded("p", "q", &embedding, &options)
+            .unwrap()[0]
+            .id,
+        tagged
+    );
+    // Nothing updated in the future
+    let options =
+        SearchOptions::default().with_since(chrono::Utc::now() + chrono::Duration::days(1));
+    assert!(
+        store
+            .query_embedded("p", "q", &embedding, &options)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_token_embeddings_cleared_without_late_interaction() {
+    let mut store =
+        MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+    let id = store.db.insert("p", "old", &[0.5f32; 384], None).unwrap();
+    let mut token = vec![0.0f32; 384];
+    token[0] = 1.0;
+    store.db.set_token_embeddings(&id, Some(&[token])).unwrap();
+
+    store.store_token_embeddings(&id, "new").unwrap();
+    assert!(store.db.token_embeddings(&[&id]).unwrap().is_empty());
+}
+
+#[test]
+fn test_get_records_access_and_frecency_reorders() {
+    let store = MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+    let used = store.db.insert("p", "used", &[0.5f32; 384], None).unwrap();
+    let unused = store
+        .db
+        .insert("p", "unused", &[0.5f32; 384], None)
+        .unwrap();
+    for _ in 0..5 {
+        assert!(store.get(&used).unwrap().is_some());
+    }
+    assert!(store.get("missing").unwrap().is_none());
+    let stats = store.db.access_stats(&[&used, &unused]).unwrap();
+    assert_eq!(stats[&used].access_count, 5);
+    assert_eq!(stats[&unused].access_count, 0);
+
+    let mut results = vec![
+        store.db.get(&unused).unwrap().unwrap(),
+        store.db.get(&used).unwrap().unwrap(),
+    ];
+    results[0].similarity = Some(0.8);
+    results[1].similarity = Some(0.7);
+    let mut explanations = HashMap::from([(used.clone(), Default::default())]);
+    store
+        .apply_frecency(&mut results, &chrono::Utc::now(), &mut explanations)
+        .unwrap();
+
+    assert_eq!(results[0].id, used);
+    assert!(results[0].similarity.unwrap() > 0.8);
+    assert_eq!(results[1].similarity, Some(0.8));
+    let frecency = explanations[&used].frecency.unwrap();
+    // 5 accesses just now: 5 / (5 + 5)
+    assert!((frecency - 0.5).abs() < 1e-3);
+}
+
+#[ignore]
+#[test]
+fn test_integration_synonym_embeddings_find_other_names() {
+    // Requires: cargo test -- --ignored
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    store
+        .add_with_conflict("p", "Upgraded the kubernetes cluster to 1.30", None, true)
+        .unwrap();
+    store.add_synonyms("p", "k8s", &["kubernetes"]).unwrap();
+
+    let options = SearchOptions::default().with_synonym_embeddings(true);
+    let expanded = store.query("p", "k8s upgrade", options).unwrap();
+    let plain = store
+        .query("p", "k8s upgrade", SearchOptions::default())
+        .unwrap();
+    assert!(expanded[0].similarity > plain[0].similarity);
+}
+
+#[test]
+fn test_search_results_grouped_per_project() {
+    use crate::memory_types::SearchResults;
+
+    let hit = |id: &str, project_id: &str, score: f64| Memory {
+        id: id.to_string(),
+        project_id: project_id.to_string(),
+        content: format!("memory {id}"),
+        metadata: None,
+        kind: MemoryKind::Other,
+        origin: Origin::default(),
+        title: None,
+        similarity: Some(score),
+        snippet: None,
+        explanation: None,
+        created_at: "2026-01-05T09:00:00+00:00".to_string(),
+        updated_at: "2026-01-05T09:00:00+00:00".to_string(),
+    };
+    let results = SearchResults {
+        memories: vec![
+            hit("a1", "alpha", 0.9),
+            hit("b1", "beta", 0.8),
+            hit("a2", "alpha", 0.7),
+            hit("a3", "alpha", 0.6),
+            hit("b2", "beta", 0.5),
+        ],
+    };
+
+    let ids = |memories: &[Memory]| memories.iter().map(|m| m.id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&results.top(3)), ["a1", "b1", "a2"]);
+
+    let groups = results.grouped(2);
+    let summary: Vec<_> = groups
+        .iter()
+        .map(|g| (g.project_id.as_str(), g.count, ids(&g.memories)))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("alpha", 3, vec!["a1".to_string(), "a2".to_string()]),
+            ("beta", 2, vec!["b1".to_string(), "b2".to_string()]),
+        ]
+    );
+}
@@ -0,0 +1,369 @@
+//! Semantic, batch and hybrid search.
+
+use super::*;
+
+#[ignore]
+#[test]
+fn test_integration_add_search_roundtrip() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let config = Config::default();
+
+    let mut store = MemoryStore::new(&path, "BAAI/bge-small-en-v1.5", config).unwrap();
+
+    let id = match store
+        .add_with_conflict("test-project", "semantic search is useful", None, false)
+        .unwrap()
+    {
+        crate::memory_types::AddResult::Added { id } => id,
+        _ => panic!("Expected AddResult::Added"),
+    };
+
+    let results = store
+        .query(
+            "test-project",
+            "finding information",
+            SearchOptions::default(),
+        )
+        .unwrap();
+    assert!(!results.is_empty());
+
+    let memory = store.get(&id).unwrap().unwrap();
+    assert_eq!(memory.content, "semantic search is useful");
+}
+
+#[test]
+fn test_search_batch_validates_before_embedding() {
+    let mut store = MemoryStore::open_without_embedder(
+        std::path::Path::new(super::store::IN_MEMORY_DB_PATH),
+        Config::default(),
+    )
+    .unwrap();
+    assert!(matches!(
+        store.search_batch("p", &[], SearchOptions::default()),
+        Err(Error::InvalidInput(_))
+    ));
+    let too_many = vec!["q"; super::store::MAX_BATCH_QUERIES + 1];
+    assert!(matches!(
+        store.search_batch("p", &too_many, SearchOptions::default()),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        store.search_batch("p", &["ok", "  "], SearchOptions::default()),
+        Err(Error::EmptyInput)
+    ));
+}
+
+#[ignore]
+#[test]
+fn test_integration_search_batch_matches_query() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    for content in [
+        "Sessions expire after 30 minutes",
+        "The database is PostgreSQL 16",
+        "Deploys run every Friday afternoon",
+    ] {
+        store.add_with_conflict("p", content, None, true).unwrap();
+    }
+
+    let queries = ["session timeout", "which database do we use"];
+    let options = SearchOptions::default()
+        .with_limit(2)
+        .with_strategy(SearchStrategy::Hybrid);
+    let batch = store.search_batch("p", &queries, options.clone()).unwrap();
+    assert_eq!(batch.len(), 2);
+    for (query, results) in queries.iter().zip(&batch) {
+        let single = store.query("p", query, options.clone()).unwrap();
+        let ids = |m: &[Memory]| m.iter().map(|m| m.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(results), ids(&single));
+    }
+    assert!(batch[1][0].content.contains("PostgreSQL"));
+}
+
+#[ignore]
+#[test]
+fn test_integration_query_filters_and_explains() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    store
+        .add_with_conflict(
+            "p",
+            "Sessions expire after 30 minutes",
+            Some(r#"{"namespace": "auth"}"#),
+            true,
+        )
+        .unwrap();
+    store
+        .add_with_conflict("p", "Sessions are stored in Redis", None, true)
+        .unwrap();
+
+    let options = SearchOptions::default()
+        .with_strategy(SearchStrategy::Hybrid)
+        .with_namespace("auth")
+        .with_explain(true);
+    let results = store.query("p", "session expiry", options).unwrap();
+    assert_eq!(results.len(), 1);
+    let explanation = results[0].explanation.as_ref().unwrap();
+    assert!(explanation.semantic.is_some());
+    assert!(explanation.fused.is_some());
+    assert_eq!(Some(explanation.score), results[0].similarity);
+}
+
+#[ignore]
+#[test]
+fn test_integration_query_exclusions_demote_concept() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    for content in [
+        "Database tuning: raised postgres shared_buffers to 4GB",
+        "Database tuning: increased the MySQL InnoDB buffer pool",
+    ] {
+        store.add_with_conflict("p", content, None, true).unwrap();
+    }
+
+    for strategy in [SearchStrategy::Semantic, SearchStrategy::Hybrid] {
+        let options = SearchOptions::default()
+            .with_strategy(strategy)
+            .with_exclude("postgres")
+            .with_explain(true);
+        let results = store.query("p", "database tuning", options).unwrap();
+        assert!(results[0].content.contains("MySQL"), "{:?}", strategy);
+        let explanation = results[0].explanation.as_ref().unwrap();
+        assert!(explanation.exclusion.is_some());
+    }
+}
+
+#[test]
+fn test_hybrid_search_basic() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    db.initialize_fts().unwrap();
+
+    // Add memories with different embeddings for semantic search
+    let embedding_rust: Vec<f32> = vec![1.0, 0.0, 0.0f32]
+        .into_iter()
+        .cycle()
+        .take(384)
+        .collect();
+    let embedding_python: Vec<f32> = vec![0.0, 1.0, 0.0f32]
+        .into_iter()
+        .cycle()
+        .take(384)
+        .collect();
+    let embedding_general: Vec<f32> = vec![0.5, 0.5, 0.5f32]
+        .into_iter()
+        .cycle()
+        .take(384)
+        .collect();
+
+    let id_rust = db
+        .insert(
+            "test-project",
+            "rust programming language",
+            &embedding_rust,
+            None,
+        )
+        .unwrap();
+    let _id_python = db
+        .insert(
+            "test-project",
+            "python code examples",
+            &embedding_python,
+            None,
+        )
+        .unwrap();
+    let _id_general = db
+        .insert(
+            "test-project",
+            "general software development",
+            &embedding_general,
+            None,
+        )
+        .unwrap();
+
+    // Test hybrid search combines semantic and BM25
+    let semantic_results = db.search("test-project", &embedding_rust, 50).unwrap();
+    let bm25_results = db.search_bm25("rust", "test-project", 50).unwrap();
+
+    // Verify semantic search finds rust-related content
+    assert!(!semantic_results.is_empty());
+    assert!(semantic_results.iter().any(|m| m.id == id_rust));
+    assert!(semantic_results.iter().any(|m| m.content.contains("rust")));
+
+    // BM25 should find matches for "rust" query
+    assert!(!bm25_results.is_empty());
+
+    // Rust memory should appear in both semantic and BM25 results
+    assert!(semantic_results.iter().any(|m| m.id == id_rust));
+    assert!(
+        bm25_results
+            .iter()
+            .any(|m| m.content.to_lowercase().contains("rust"))
+    );
+}
+
+#[test]
+fn test_hybrid_search_empty_results() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    db.initialize_fts().unwrap();
+
+    // Add one memory
+    let embedding = vec![0.5f32; 384];
+    db.insert("test-project", "some content", &embedding, None)
+        .unwrap();
+
+    // Query with non-matching text
+    let semantic_results = db.search("test-project", &vec![0.1f32; 384], 50).unwrap();
+    let bm25_results = db
+        .search_bm25("nonexistent term xyz", "test-project", 50)
+        .unwrap();
+
+    // One may be empty, but fusion should handle it
+    assert!(!semantic_results.is_empty() || !bm25_results.is_empty());
+}
+
+#[test]
+fn test_hybrid_search_with_recency() {
+    use chrono::{Duration, Utc};
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    let db = Database::open(&path).unwrap();
+    db.initialize_fts().unwrap();
+
+    // Add memories with known embedding
+    let embedding_good = vec![1.0f32; 384];
+    let embedding_bad = vec![0.0f32; 384];
+
+    let now = Utc::now();
+    let old_time = (now - Duration::days(100)).to_rfc3339();
+    let new_time = now.to_rfc3339();
+
+    // Old high-similarity memory
+    let id_old = db
+        .insert_with_time(
+            "test-project",
+            "old but relevant",
+            &embedding_good,
+            None,
+            &old_time,
+            &old_time,
+        )
+        .unwrap();
+
+    // New low-similarity memory (same content for BM25 relevance)
+    let _id_new = db
+        .insert_with_time(
+            "test-project",
+            "new but less relevant",
+            &embedding_bad,
+            None,
+            &new_time,
+            &new_time,
+        )
+        .unwrap();
+
+    // Search without recency weighting - should find high-similarity first
+    let semantic_results = db.search("test-project", &embedding_good, 10).unwrap();
+    if !semantic_results.is_empty() {
+        let top_id = &semantic_results[0].id;
+        // With zero recency weight, similarity dominates
+        // Either the high-similarity old memory is first, or we have a single result
+        assert!(top_id == &id_old || semantic_results.len() == 1);
+    }
+}
+
+#[ignore]
+#[test]
+fn test_integration_context_pack() {
+    // Full integration test with real model
+    // Requires: cargo test -- --ignored
+    let mut store =
+        MemoryStore::new_in_memory("BAAI/bge-small-en-v1.5", Config::default()).unwrap();
+    for content in [
+        "Sessions expire after 30 minutes",
+        "Sessions expire after thirty minutes",
+        "Login uses OAuth with GitHub",
+        "The office plant needs water on Fridays",
+    ] {
+        store.add_with_conflict("p", content, None, true).unwrap();
+    }
+
+    let options = SearchOptions::default()
+        .with_limit(10)
+        .with_strategy(SearchStrategy::Hybrid);
+    let pack = store
+        .context("p", "why do users get logged out", 2000, options.clone())
+        .unwrap();
+    assert!(pack.duplicates >= 1);
+    assert!(pack.tokens <= 2000);
+    for entry in &pack.memories {
+        assert!(pack.text.contains(&format!("[{}]", entry.id)));
+    }
+
+    let tiny = store.context("p", "login", 20, options).unwrap();
+    assert!(tiny.memories.len() < pack.memories.len());
+    assert!(tiny.tokens <= 20);
+}
+
+#[cfg(not(feature = "rerank"))]
+#[test]
+fn test_rerank_needs_feature() {
+    let mut store =
+        MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+    let result = store.query("p", "query", SearchOptions::default().with_rerank(true));
+    assert!(matches!(result, Err(Error::Config(msg)) if msg.contains("`rerank` feature")));
+}
+
+#[cfg(not(feature = "late-interaction"))]
+#[test]
+fn test_late_interaction_needs_feature() {
+    let mut store =
+        MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+    let options = SearchOptions::default().with_late_interaction(true);
+    let result = store.query("p", "query", options);
+    assert!(
+        matches!(result, Err(Error::Config(msg)) if msg.contains("`late-interaction` feature"))
+    );
+}
+
+#[test]
+fn test_cancelled_query_leaves_the_store_usable() {
+    let mut store = MemoryStore::open_without_embedder(
+        std::path::Path::new(store::IN_MEMORY_DB_PATH),
+        Config::default(),
+    )
+    .unwrap();
+    let cancel = crate::cancel::CancellationToken::new();
+    cancel.cancel();
+    assert!(matches!(
+        store.query_with_cancel("p", "deploys", SearchOptions::default(), &cancel),
+        Err(Error::Cancelled)
+    ));
+    // The next search runs without the token (and fails for want of a model)
+    assert!(matches!(
+        store.query("p", "deploys", SearchOptions::default()),
+        Err(Error::Config(_))
+    ));
+}
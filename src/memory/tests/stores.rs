@@ -0,0 +1,306 @@
+//! Projects, owners, embedding models and sync between stores.
+
+use super::*;
+
+#[test]
+fn test_normalize_projects_merges_spellings() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+
+    let store = MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+    for project in ["owner/repo", "Owner/Repo", "owner/repo.git", "notes"] {
+        store
+            .db
+            .insert(project, "stored content", &[0.5f32; 384], None)
+            .unwrap();
+    }
+    // Lookups normalize, but rows stored before normalization are not found
+    assert_eq!(store.list("Owner/Repo.git", 10).unwrap().len(), 1);
+
+    let planned = store.normalize_projects(true).unwrap();
+    let summary: Vec<(&str, &str, usize, bool)> = planned
+        .iter()
+        .map(|r| (r.from.as_str(), r.to.as_str(), r.memories, r.merged))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Owner/Repo", "owner/repo", 1, true),
+            ("owner/repo.git", "owner/repo", 1, true),
+        ]
+    );
+    assert_eq!(store.db.count_project("Owner/Repo").unwrap(), 1);
+
+    assert_eq!(store.normalize_projects(false).unwrap(), planned);
+    assert_eq!(store.list("owner/repo", 10).unwrap().len(), 3);
+    assert_eq!(store.list("notes", 10).unwrap().len(), 1);
+    assert!(store.normalize_projects(true).unwrap().is_empty());
+}
+
+#[test]
+fn test_prune_projects_removes_empty_and_stale_projects() {
+    let store = MemoryStore::open_without_embedder(":memory:".as_ref(), Config::default()).unwrap();
+    store
+        .db
+        .insert("active", "recent", &[0.5f32; 384], None)
+        .unwrap();
+    store
+        .db
+        .insert("old", "stale", &[0.5f32; 384], None)
+        .unwrap();
+    let trashed = store
+        .db
+        .insert("empty", "deleted", &[0.5f32; 384], None)
+        .unwrap();
+    store.db.trash(&trashed).unwrap();
+    store
+        .db
+        .conn()
+        .execute(
+            "UPDATE memories SET updated_at = '2024-01-01T00:00:00+00:00' WHERE project_id = 'old'",
+            [],
+        )
+        .unwrap();
+
+    let projects = |pruned: Vec<crate::memory_types::PrunedProject>| -> Vec<String> {
+        pruned.into_iter().map(|p| p.project_id).collect()
+    };
+    // Without a cutoff only the project with no live memories goes
+    assert_eq!(
+        projects(store.prune_projects(None, true).unwrap()),
+        vec!["empty"]
+    );
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(180);
+    let planned = store.prune_projects(Some(cutoff), true).unwrap();
+    assert_eq!(projects(planned.clone()), vec!["empty", "old"]);
+    assert_eq!(planned[0].trashed, 1);
+    assert_eq!(store.db.count_project("old").unwrap(), 1);
+
+    assert_eq!(store.prune_projects(Some(cutoff), false).unwrap(), planned);
+    assert_eq!(store.db.count_project("old").unwrap(), 0);
+    assert_eq!(store.list("active", 10).unwrap().len(), 1);
+    assert!(store.prune_projects(Some(cutoff), true).unwrap().is_empty());
+
+    // Memories trashed within the retention window keep their project
+    let trashed = store
+        .db
+        .insert("recent", "deleted", &[0.5f32; 384], None)
+        .unwrap();
+    store.db.trash(&trashed).unwrap();
+    let config = Config {
+        trash_retention_days: Some(30),
+        ..store.config.clone()
+    };
+    let store = MemoryStore { config, ..store };
+    assert!(store.prune_projects(None, true).unwrap().is_empty());
+}
+
+#[test]
+fn test_find_exact_applies_filters_and_owner() {
+    use crate::config::{FilterKind, FiltersConfig};
+
+    let config = Config {
+        filters: FiltersConfig {
+            pipeline: vec![FilterKind::NormalizeWhitespace],
+            ..Default::default()
+        },
+        owner: Some("alice".to_string()),
+        ..Config::default()
+    };
+    let mut store = MemoryStore::open_without_embedder(":memory:".as_ref(), config).unwrap();
+    let embedding = vec![0.1; 384];
+    let shared = store
+        .db
+        .insert("p", "Deploy with make", &embedding, None)
+        .unwrap();
+    let private = store
+        .db
+        .insert("p", "deploy with make", &embedding, None)
+        .unwrap();
+    store
+        .db
+        .set_access(&private, Some("bob"), Visibility::Private)
+        .unwrap();
+
+    let ids = |store: &MemoryStore, text: &str, ignore_case: bool| -> Vec<String> {
+        store
+            .find_exact("p", text, ignore_case)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect()
+    };
+    // Found by the text given to add, before whitespace normalization
+    assert_eq!(
+        ids(&store, "Deploy  with\tmake ", false),
+        std::slice::from_ref(&shared)
+    );
+    assert_eq!(ids(&store, "DEPLOY WITH MAKE", true), [shared]);
+    store.set_owner(None, Visibility::Shared).unwrap();
+    assert_eq!(ids(&store, "DEPLOY WITH MAKE", true).len(), 2);
+    assert!(ids(&store, "Deploy with", true).is_empty());
+    assert!(matches!(
+        store.find_exact("p", " ", false),
+        Err(Error::EmptyInput)
+    ));
+}
+
+#[test]
+fn test_conflicts_leave_out_private_memories_of_other_owners() {
+    let config = Config {
+        owner: Some("alice".to_string()),
+        ..Config::default()
+    };
+    let mut store = MemoryStore::open_without_embedder(":memory:".as_ref(), config).unwrap();
+    let private = store
+        .db
+        .insert("p", "use postgres", &axis(0), None)
+        .unwrap();
+    store
+        .db
+        .set_access(&private, Some("bob"), Visibility::Private)
+        .unwrap();
+
+    let conflicts = |store: &MemoryStore| {
+        store
+            .conflicts_with("p", "use postgres", &axis(0), None)
+            .unwrap()
+            .len()
+    };
+    assert_eq!(conflicts(&store), 0);
+    store
+        .set_owner(Some("bob".to_string()), Visibility::Shared)
+        .unwrap();
+    assert_eq!(conflicts(&store), 1);
+}
+
+#[test]
+fn test_owner_labels_added_memories_and_scopes_lists() {
+    let config = Config {
+        owner: Some("alice".to_string()),
+        visibility: Visibility::Private,
+        ..Config::default()
+    };
+    let mut store =
+        MemoryStore::open_without_embedder(std::path::Path::new(store::IN_MEMORY_DB_PATH), config)
+            .unwrap();
+    let embedding = vec![0.1; 384];
+    let private = store
+        .insert_now("p", "alice's note", &embedding, None, MemoryKind::Other)
+        .unwrap();
+    assert_eq!(
+        store.db.access(&private).unwrap(),
+        Some((Some("alice".to_string()), Visibility::Private))
+    );
+    store
+        .set_owner(Some("bob".to_string()), Visibility::Shared)
+        .unwrap();
+    let shared = store
+        .insert_now("p", "bob's note", &embedding, None, MemoryKind::Other)
+        .unwrap();
+
+    let ids = |store: &MemoryStore| -> Vec<String> {
+        store
+            .list_with("p", &ListOptions::default())
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect()
+    };
+    assert_eq!(ids(&store), vec![shared.clone()]);
+    store
+        .set_owner(Some("alice".to_string()), Visibility::Shared)
+        .unwrap();
+    assert_eq!(ids(&store).len(), 2);
+    store.set_owner(None, Visibility::Shared).unwrap();
+    assert_eq!(ids(&store).len(), 2);
+
+    assert!(store.set_owner(None, Visibility::Private).is_err());
+    assert!(
+        store
+            .set_owner(Some(" ".to_string()), Visibility::Shared)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_store_refuses_other_embedding_model_until_reembedded() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+
+    let store = MemoryStore::new(&path, "model-a", Config::default()).unwrap();
+    let stats = store.project_stats("p", false).unwrap();
+    assert_eq!(stats.store.embedding_model.as_deref(), Some("model-a"));
+    assert_eq!(
+        stats.store.embedding_dims,
+        Some(crate::embedding::EMBEDDING_DIMS)
+    );
+    drop(store);
+
+    let result = MemoryStore::new(&path, "model-b", Config::default());
+    assert!(matches!(result, Err(Error::Config(ref msg)) if msg.contains("model-a")));
+    // Metadata-only access doesn't care about the model
+    MemoryStore::open_without_embedder(&path, Config::default()).unwrap();
+
+    // A full re-embed (nothing to embed here) switches the store's model
+    let mut store = MemoryStore::open_for_reembed(&path, "model-b", Config::default()).unwrap();
+    store.reembed(false).unwrap();
+    drop(store);
+    MemoryStore::new(&path, "model-b", Config::default()).unwrap();
+    assert!(MemoryStore::new(&path, "model-a", Config::default()).is_err());
+}
+
+#[test]
+fn test_sync_push_and_pull_between_stores() {
+    use tempfile::TempDir;
+    let dir = TempDir::new().unwrap();
+    let laptop_path = dir.path().join("laptop.db");
+    let desktop_path = dir.path().join("desktop.db");
+    let laptop = MemoryStore::open_without_embedder(&laptop_path, Config::default()).unwrap();
+    let desktop = MemoryStore::open_without_embedder(&desktop_path, Config::default()).unwrap();
+    let mut axis = vec![0.0f32; 384];
+    axis[0] = 1.0;
+
+    assert!(matches!(
+        laptop.sync_pull(&dir.path().join("missing.db")),
+        Err(Error::InvalidInput(_))
+    ));
+
+    let id = laptop
+        .db
+        .insert("proj", "from the laptop", &axis, None)
+        .unwrap();
+    let stats = laptop.sync_push(&desktop_path).unwrap();
+    assert_eq!(stats.added, 1);
+    assert!(desktop.db.get(&id).unwrap().is_some());
+    // Already pushed
+    assert_eq!(laptop.sync_push(&desktop_path).unwrap().changes, 0);
+
+    axis[0] = 0.0;
+    axis[1] = 1.0;
+    let other = desktop
+        .db
+        .insert("proj", "from the desktop", &axis, None)
+        .unwrap();
+    let stats = laptop.sync_pull(&desktop_path).unwrap();
+    assert_eq!((stats.changes, stats.added, stats.skipped), (2, 1, 1));
+    assert!(laptop.db.get(&other).unwrap().is_some());
+    assert_eq!(laptop.sync_pull(&desktop_path).unwrap().changes, 0);
+
+    let key = std::fs::canonicalize(&desktop_path)
+        .unwrap()
+        .display()
+        .to_string();
+    let state = laptop.db.sync_state(&key).unwrap();
+    assert_eq!(state.pulled_seq, desktop.db.write_seq().unwrap());
+
+    // Stores of different models are not mixed
+    laptop.db.set_embedding_model("model-a", 384).unwrap();
+    desktop.db.set_embedding_model("model-b", 384).unwrap();
+    assert!(matches!(
+        laptop.sync_push(&desktop_path),
+        Err(Error::Config(_))
+    ));
+}
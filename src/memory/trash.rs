@@ -0,0 +1,50 @@
+//! Trash of deleted memories (`trash_retention_days`).
+
+use crate::errors::Error;
+use crate::project::ProjectId;
+use crate::sqlite::trash::TrashedMemory;
+
+use super::store::MemoryStore;
+
+impl MemoryStore {
+    #[must_use = "handle the error or results may be lost"]
+    /// Memories of a project in the trash, most recently deleted first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the project ID is invalid or the query fails.
+    pub fn trashed(&self, project_id: &str) -> Result<Vec<TrashedMemory>, Error> {
+        let project = ProjectId::new(project_id)?;
+        Ok(self.db.trashed(project.as_str())?)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Move a memory deleted into the trash back into the store, with its
+    /// embedding, chunks and timestamps as they were.
+    ///
+    /// Returns false if the memory is not in the trash (never deleted,
+    /// already restored, or removed for good).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database write fails.
+    pub fn restore(&self, id: &str) -> Result<bool, Error> {
+        Ok(self.db.restore(id)?)
+    }
+
+    #[must_use = "handle the error or results may be lost"]
+    /// Permanently remove every memory of a project from the trash,
+    /// returning how many were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the project ID is invalid or the delete fails.
+    pub fn empty_trash(&self, project_id: &str) -> Result<usize, Error> {
+        let project = ProjectId::new(project_id)?;
+        let removed = self.db.empty_trash(project.as_str())?;
+        if removed > 0 {
+            log::info!("Emptied {} memories from the trash of {}", removed, project);
+        }
+        Ok(removed)
+    }
+}
@@ -0,0 +1,100 @@
+//! Content filters and validation of inputs and limits.
+
+use std::sync::Arc;
+
+use crate::config::LimitsConfig;
+use crate::content_filter::ContentFilter;
+use crate::errors::Error;
+use crate::secrets;
+use crate::storage::StorageBackend;
+
+use super::store::MemoryStore;
+
+impl<B: StorageBackend> MemoryStore<B> {
+    /// Append `filter` to the content filters run on add and update, after
+    /// those configured in the `[filters]` section.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn add_content_filter(&mut self, filter: Arc<dyn ContentFilter>) {
+        self.content_filters.push(filter);
+    }
+
+    /// Run `content` through the content filters, then validate its length
+    /// and scan it for secrets, returning the content to store.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a filter fails, or the filtered content is empty, too
+    /// long or looks like a secret (with `secret_scan`).
+    pub(crate) fn prepare_content(&self, content: &str) -> Result<String, Error> {
+        let content = self.filter_content(content)?;
+        self.validate_input_length(&content)?;
+        self.check_secrets(&content)?;
+        Ok(content)
+    }
+
+    /// `content` as the content filters would store it.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a filter fails.
+    pub(crate) fn filter_content(&self, content: &str) -> Result<String, Error> {
+        let mut content = content.to_string();
+        for filter in &self.content_filters {
+            content = filter.apply(&content)?;
+            log::trace!("Applied content filter {}", filter.name());
+        }
+        Ok(content)
+    }
+
+    /// Input validation limits in effect (the `[limits]` config section).
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn limits(&self) -> LimitsConfig {
+        self.config.limits
+    }
+
+    /// Validate that a limit parameter is within acceptable bounds.
+    ///
+    /// Returns error if limit is 0 or exceeds the configured `limits.max_search_limit`.
+    pub(crate) fn validate_limit(&self, limit: usize) -> Result<(), Error> {
+        if limit == 0 {
+            return Err(Error::InvalidInput(
+                "Limit must be greater than 0".to_string(),
+            ));
+        }
+        let max = self.config.limits.max_search_limit;
+        if limit > max {
+            return Err(Error::InvalidInput(format!(
+                "Limit {} exceeds maximum allowed ({}, set by limits.max_search_limit)",
+                limit, max
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validate input length against the configured `limits.max_input_length`
+    /// (rejects empty and whitespace-only inputs).
+    pub(crate) fn validate_input_length(&self, text: &str) -> Result<(), Error> {
+        if text.trim().is_empty() {
+            return Err(Error::EmptyInput);
+        }
+        if text.len() > self.config.limits.max_input_length {
+            return Err(Error::InputTooLong {
+                max_length: self.config.limits.max_input_length,
+                actual_length: text.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Reject `text` with `Error::PotentialSecret` if `secret_scan` is enabled
+    /// and it matches a secret detector.
+    pub(crate) fn check_secrets(&self, text: &str) -> Result<(), Error> {
+        if !self.config.secret_scan {
+            return Ok(());
+        }
+        match secrets::scan(text) {
+            Some(rule) => Err(Error::PotentialSecret(rule.to_string())),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Reproducibility check of stored embeddings against the current model.
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::errors::Error;
+use crate::memory_types::{DriftedEmbedding, EmbeddingVerification, RevisionDeviation};
+use crate::sqlite::embedding::cosine_similarity;
+
+use super::store::MemoryStore;
+
+impl MemoryStore {
+    #[must_use = "handle the error or results may be lost"]
+    /// Embed up to `sample` memories of every project again and compare the
+    /// results with their stored embeddings.
+    ///
+    /// The memories are picked pseudo-randomly by `seed` (a fresh one when
+    /// `None`, reported back so the run can be repeated on the same sample).
+    /// Deviations above `tolerance` are listed as drifted. Nothing is
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `sample` is invalid, the model cannot be loaded, or
+    /// embedding or a database query fails.
+    pub fn verify_embeddings(
+        &mut self,
+        sample: usize,
+        seed: Option<u64>,
+        tolerance: f64,
+    ) -> Result<EmbeddingVerification, Error> {
+        self.validate_limit(sample)?;
+        if !(tolerance.is_finite() && tolerance >= 0.0) {
+            return Err(Error::InvalidInput(format!(
+                "Invalid tolerance {}: must be a non-negative number",
+                tolerance
+            )));
+        }
+        let seed = seed.unwrap_or_else(fresh_seed);
+        let rows = self.db.embedding_sample(sample, seed)?;
+
+        let mut mismatched = 0;
+        let mut deviations = Vec::with_capacity(rows.len());
+        for row in rows {
+            let Some(stored) = row.embedding else {
+                mismatched += 1;
+                continue;
+            };
+            let fresh = self.embed_document_checked(&row.content)?;
+            let deviation = 1.0 - cosine_similarity(&stored, &fresh)?;
+            deviations.push(DriftedEmbedding {
+                id: row.id,
+                model_revision: row.model_revision,
+                deviation,
+            });
+        }
+
+        let mut report = verification_report(deviations, tolerance);
+        report.model_id = self.model_id.clone();
+        report.model_revision = self.model_revision();
+        report.seed = seed;
+        report.mismatched = mismatched;
+        log::debug!(
+            "Verified {} embeddings: max deviation {:.6}",
+            report.sampled,
+            report.max_deviation
+        );
+        Ok(report)
+    }
+}
+
+/// Seed for a sample nobody asked to reproduce.
+fn fresh_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// Aggregate per-memory deviations into a report (model and sample fields
+/// left for the caller).
+fn verification_report(
+    mut deviations: Vec<DriftedEmbedding>,
+    tolerance: f64,
+) -> EmbeddingVerification {
+    deviations.sort_by(|a, b| b.deviation.total_cmp(&a.deviation));
+    let sampled = deviations.len();
+    let max_deviation = deviations.first().map_or(0.0, |d| d.deviation);
+    let mean_deviation = if sampled == 0 {
+        0.0
+    } else {
+        deviations.iter().map(|d| d.deviation).sum::<f64>() / sampled as f64
+    };
+
+    // Sorted by deviation, so the first memory of a revision has its maximum
+    let mut revisions: Vec<RevisionDeviation> = Vec::new();
+    let mut index: HashMap<Option<&str>, usize> = HashMap::new();
+    for d in &deviations {
+        let i = *index.entry(d.model_revision.as_deref()).or_insert_with(|| {
+            revisions.push(RevisionDeviation {
+                revision: d.model_revision.clone(),
+                sampled: 0,
+                max_deviation: d.deviation,
+            });
+            revisions.len() - 1
+        });
+        revisions[i].sampled += 1;
+    }
+
+    let drifted = deviations
+        .into_iter()
+        .take_while(|d| d.deviation > tolerance)
+        .collect();
+    EmbeddingVerification {
+        model_id: String::new(),
+        model_revision: None,
+        seed: 0,
+        sampled,
+        mismatched: 0,
+        max_deviation,
+        mean_deviation,
+        tolerance,
+        revisions,
+        drifted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::store::DEFAULT_DRIFT_TOLERANCE;
+
+    fn deviation(id: &str, revision: Option<&str>, deviation: f64) -> DriftedEmbedding {
+        DriftedEmbedding {
+            id: id.to_string(),
+            model_revision: revision.map(str::to_string),
+            deviation,
+        }
+    }
+
+    #[test]
+    fn test_report_attributes_drift_to_revisions() {
+        let report = verification_report(
+            vec![
+                deviation("a", Some("new"), 0.0),
+                deviation("b", Some("old"), 0.2),
+                deviation("c", None, 0.0001),
+                deviation("d", Some("old"), 0.1),
+            ],
+            DEFAULT_DRIFT_TOLERANCE,
+        );
+        assert_eq!(report.sampled, 4);
+        assert_eq!(report.max_deviation, 0.2);
+        assert!((report.mean_deviation - 0.075025).abs() < 1e-12);
+
+        let ids: Vec<&str> = report.drifted.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "d"]);
+        let revisions: Vec<(Option<&str>, usize, f64)> = report
+            .revisions
+            .iter()
+            .map(|r| (r.revision.as_deref(), r.sampled, r.max_deviation))
+            .collect();
+        assert_eq!(
+            revisions,
+            vec![
+                (Some("old"), 2, 0.2),
+                (None, 1, 0.0001),
+                (Some("new"), 1, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_of_empty_sample() {
+        let report = verification_report(Vec::new(), DEFAULT_DRIFT_TOLERANCE);
+        assert_eq!(report.sampled, 0);
+        assert_eq!(report.max_deviation, 0.0);
+        assert_eq!(report.mean_deviation, 0.0);
+        assert!(report.revisions.is_empty() && report.drifted.is_empty());
+    }
+}
@@ -0,0 +1,354 @@
+//! Memory store data types.
+
+use serde::{Deserialize, Serialize};
+
+use crate::sqlite::{Memory, MemoryKind};
+
+mod stats;
+
+pub use stats::{
+    ClusterAssignment, Clustering, CompressionStats, DriftedEmbedding, EmbeddingInfo,
+    EmbeddingScan, EmbeddingStats, EmbeddingVerification, HistogramBucket, KindCount,
+    LanguageCount, MemoryCluster, Outlier, OutlierCluster, ProjectStats, RevisionDeviation,
+    TokenStats,
+};
+
+/// Result type for conflict-aware add operations.
+///
+/// Returned by `MemoryStore::add_with_conflict()` to indicate whether
+/// a memory was successfully added or conflicts were detected.
+#[derive(Debug, Serialize)]
+pub enum AddResult {
+    /// Memory was successfully added.
+    Added { id: String },
+    /// Memory conflicts with existing similar memories.
+    Conflicts {
+        proposed: String,
+        conflicts: Vec<ConflictMemory>,
+    },
+}
+
+/// What a conflict-aware add would do, without doing it.
+///
+/// Returned by `MemoryStore::preview_add()`.
+#[derive(Debug, Serialize)]
+pub enum AddPreview {
+    /// The memory would be stored.
+    WouldAdd,
+    /// The memory would be rejected for conflicting with existing similar
+    /// memories.
+    Conflicts {
+        proposed: String,
+        conflicts: Vec<ConflictMemory>,
+    },
+}
+
+/// Details about a conflicting memory.
+///
+/// Provides information about memories that are similar to a proposed addition,
+/// including their IDs, content, and the signals conflict detection combined,
+/// plus the metadata and timestamps needed to tell which memory is newer or
+/// where it came from.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConflictMemory {
+    /// Unique identifier of the conflicting memory.
+    pub id: String,
+    /// Memory content that conflicts with the proposed addition.
+    pub content: String,
+    /// Cosine similarity of the embeddings (0.0 to 1.0).
+    pub similarity: f64,
+    /// Term overlap of the two texts: shared words over all words (0.0 to
+    /// 1.0).
+    pub overlap: f64,
+    /// Conflict confidence combining similarity and overlap with the
+    /// `[conflicts]` weights (0.0 to 1.0); compared to
+    /// `similarity_threshold`.
+    pub confidence: f64,
+    /// Metadata of the conflicting memory (JSON string), if any.
+    pub metadata: Option<String>,
+    /// Creation timestamp in RFC3339 format.
+    pub created_at: String,
+    /// Last update timestamp in RFC3339 format.
+    pub updated_at: String,
+}
+
+impl ConflictMemory {
+    /// Conflict details from a similarity search result and its scores.
+    pub(crate) fn scored(memory: Memory, overlap: f64, confidence: f64) -> Self {
+        Self {
+            id: memory.id,
+            content: memory.content,
+            similarity: memory.similarity.unwrap_or(0.0),
+            overlap,
+            confidence,
+            metadata: memory.metadata,
+            created_at: memory.created_at,
+            updated_at: memory.updated_at,
+        }
+    }
+}
+
+/// Result type for conflict-aware update operations.
+///
+/// Returned by `MemoryStore::update_with_conflict()` to indicate whether
+/// the memory was updated or its new content conflicts with other memories.
+#[derive(Debug, Serialize)]
+pub enum UpdateResult {
+    /// Memory was successfully updated.
+    Updated { id: String },
+    /// New content conflicts with other similar memories; nothing was changed.
+    Conflicts {
+        proposed: String,
+        conflicts: Vec<ConflictMemory>,
+    },
+}
+
+/// Summary of a retention run.
+///
+/// Returned by `MemoryStore::prune()`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PruneStats {
+    /// Memories removed because they outlived their TTL.
+    pub removed: usize,
+    /// Projects that had at least one memory removed.
+    pub projects: usize,
+    /// Whether removed memories were archived rather than deleted.
+    pub archived: bool,
+    /// Memories permanently removed from the trash after
+    /// `trash_retention_days`.
+    pub trash_purged: usize,
+    /// Memories marked inactive after `inactive_after_days` without an
+    /// update or access.
+    pub deactivated: usize,
+}
+
+/// A memory up for review: idle for `review.stale_days` and of low
+/// importance once decayed.
+///
+/// Returned by `MemoryStore::review_queue()`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewCandidate {
+    /// Memory ID.
+    pub id: String,
+    /// Memory content.
+    pub content: String,
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// Numeric `importance` metadata, if any.
+    pub importance: Option<f64>,
+    /// Importance after decaying over the idle time (0 without one).
+    pub decayed_importance: f64,
+    /// Days since the latest update, access or review.
+    pub idle_days: f64,
+    /// Last update timestamp in RFC3339 format.
+    pub updated_at: String,
+    /// Last access timestamp in RFC3339 format, if ever accessed.
+    pub last_accessed_at: Option<String>,
+    /// Last review timestamp in RFC3339 format, if ever kept in a review.
+    pub reviewed_at: Option<String>,
+}
+
+/// What to do with a reviewed memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewAction {
+    /// Keep it and restart its idle time.
+    Keep,
+    /// Move it to the archive table.
+    Archive,
+    /// Delete it.
+    Delete,
+}
+
+/// A review decision on one memory, as given to `MemoryStore::apply_review()`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewDecision {
+    /// Memory ID.
+    pub id: String,
+    /// What to do with the memory.
+    pub action: ReviewAction,
+}
+
+/// Outcome of applying review decisions.
+///
+/// Returned by `MemoryStore::apply_review()`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewStats {
+    /// Memories kept (their review time is now).
+    pub kept: usize,
+    /// Memories moved to the archive table.
+    pub archived: usize,
+    /// Memories deleted.
+    pub deleted: usize,
+    /// Decisions on IDs that matched no memory.
+    pub not_found: Vec<String>,
+}
+
+/// A stored project ID that is not in normalized form, and the ID it
+/// normalizes to.
+///
+/// Returned by `MemoryStore::normalize_projects()`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectRename {
+    /// Project ID as stored.
+    pub from: String,
+    /// Normalized project ID.
+    pub to: String,
+    /// Live memories moved (or, in a dry run, that would be moved).
+    pub memories: usize,
+    /// Whether `to` already had memories, so the two projects merge.
+    pub merged: bool,
+}
+
+/// A project deleted (or, in a dry run, that would be deleted) by
+/// `MemoryStore::prune_projects()`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrunedProject {
+    /// Project ID as stored.
+    pub project_id: String,
+    /// Live memories deleted with it.
+    pub memories: usize,
+    /// Archived memories deleted with it.
+    pub archived: usize,
+    /// Trashed memories deleted with it.
+    pub trashed: usize,
+    /// Latest update or access of a live memory (RFC3339), or `None` for an
+    /// empty project.
+    pub last_activity: Option<String>,
+}
+
+/// Outcome of `MemoryStore::update_metadata_where()`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MetadataUpdate {
+    /// Memories that passed the filter.
+    pub matched: usize,
+    /// IDs of the memories whose metadata changed (or, in a dry run, would
+    /// change); the others already had the patched values.
+    pub updated: Vec<String>,
+}
+
+/// Readiness report for the embedding engine and database.
+///
+/// Returned by `MemoryStore::health_check()`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    /// Whether every check passed.
+    pub healthy: bool,
+    /// Configured embedding model ID.
+    pub model_id: String,
+    /// Dimensions of the probe embedding (0 if it could not be generated).
+    pub dims: usize,
+    /// Whether the tokenizer loaded and encoded the probe text.
+    pub tokenizer_loaded: bool,
+    /// Wall-clock time to embed the probe text, in milliseconds.
+    pub embed_ms: Option<f64>,
+    /// Schema version recorded in the database.
+    pub schema_version: u32,
+    /// Rows in the memories table, including chunk rows.
+    pub memory_rows: usize,
+    /// Whether the database keeps an FTS5 index (`enable_fts`).
+    pub fts_enabled: bool,
+    /// Documents in the FTS5 index (0 without one).
+    pub fts_rows: usize,
+    /// Whether the FTS5 index covers exactly the rows in the memories table
+    /// (always true without an index).
+    pub fts_in_sync: bool,
+    /// Why the embedding checks failed, if they did.
+    pub error: Option<String>,
+}
+
+/// Memories packed into a token budget for an LLM prompt.
+///
+/// Returned by `MemoryStore::context()`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContextPack {
+    /// Task description the memories were retrieved for.
+    pub task: String,
+    /// Token budget requested.
+    pub budget: usize,
+    /// Estimated tokens used by `text`.
+    pub tokens: usize,
+    /// Prompt block citing each memory by ID, ready to paste.
+    pub text: String,
+    /// Packed memories, most relevant first.
+    pub memories: Vec<ContextEntry>,
+    /// Candidates dropped as near-duplicates of a packed memory.
+    pub duplicates: usize,
+    /// Candidates left out because they did not fit the budget.
+    pub omitted: usize,
+}
+
+/// A memory included in a [`ContextPack`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContextEntry {
+    /// Memory ID, used as the citation.
+    pub id: String,
+    /// Memory content.
+    pub content: String,
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// Last update time (RFC3339).
+    pub updated_at: String,
+    /// Search score (relevance blended with recency).
+    pub score: f64,
+    /// Estimated tokens of the memory's entry in `text`.
+    pub tokens: usize,
+}
+
+/// Search hits from every project, best first.
+///
+/// Returned by `MemoryStore::query_all_projects()`. Each project is searched
+/// on its own, so this holds up to the search `limit` hits of every project;
+/// [`SearchResults::top`] keeps the best overall, [`SearchResults::grouped`]
+/// buckets them per project.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SearchResults {
+    /// Hits of all projects, highest score first.
+    pub memories: Vec<Memory>,
+}
+
+impl SearchResults {
+    /// The `limit` best hits across all projects.
+    pub fn top(&self, limit: usize) -> Vec<Memory> {
+        self.memories.iter().take(limit).cloned().collect()
+    }
+
+    /// Hits bucketed per project, each with its `per_project` best hits and
+    /// the number found in it; projects with better top hits come first.
+    pub fn grouped(&self, per_project: usize) -> Vec<ProjectHits> {
+        let mut groups: Vec<ProjectHits> = Vec::new();
+        for memory in &self.memories {
+            let index = match groups
+                .iter()
+                .position(|g| g.project_id == memory.project_id)
+            {
+                Some(index) => index,
+                None => {
+                    groups.push(ProjectHits {
+                        project_id: memory.project_id.clone(),
+                        count: 0,
+                        memories: Vec::new(),
+                    });
+                    groups.len() - 1
+                }
+            };
+            let group = &mut groups[index];
+            group.count += 1;
+            if group.memories.len() < per_project {
+                group.memories.push(memory.clone());
+            }
+        }
+        groups
+    }
+}
+
+/// The hits of one project in [`SearchResults::grouped`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectHits {
+    /// Project identifier.
+    pub project_id: String,
+    /// Hits found in the project (at most the search `limit`).
+    pub count: usize,
+    /// The project's best hits, highest score first.
+    pub memories: Vec<Memory>,
+}
@@ -0,0 +1,270 @@
+//! Statistics, clustering and embedding inspection results.
+
+use serde::Serialize;
+
+use crate::sqlite::{MemoryKind, StoreMeta};
+
+/// Summary of one project's memories.
+///
+/// Returned by `MemoryStore::project_stats()`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectStats {
+    /// Project identifier.
+    pub project_id: String,
+    /// Memories stored (chunk rows excluded).
+    pub memories: usize,
+    /// Memory count per kind, most common first.
+    pub kinds: Vec<KindCount>,
+    /// Memory count per detected language, most common first (empty unless
+    /// `detect_language` was enabled when memories were stored).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<LanguageCount>,
+    /// Earliest creation time (RFC3339), if the project has memories.
+    pub oldest: Option<String>,
+    /// Latest update time (RFC3339), if the project has memories.
+    pub newest: Option<String>,
+    /// Token counts, if the model's tokenizer is available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<TokenStats>,
+    /// Storage saved by content compression, if any content is compressed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<CompressionStats>,
+    /// Embedding coherence report, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<EmbeddingStats>,
+    /// Store-level facts (embedding model, versions, creation time), which
+    /// apply to every project in the database.
+    pub store: StoreMeta,
+}
+
+/// Model token counts of a project's memories.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenStats {
+    /// Tokens across all memories.
+    pub total: usize,
+    /// Tokens in the longest memory.
+    pub max: usize,
+    /// The model's token limit.
+    pub limit: usize,
+    /// Memories longer than `limit`, which are truncated before embedding.
+    pub over_limit: usize,
+}
+
+/// Storage of a project's compressed content.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompressionStats {
+    /// Rows stored compressed (chunk rows included).
+    pub compressed: usize,
+    /// Bytes the compressed content takes.
+    pub stored_bytes: u64,
+    /// Bytes the content would take uncompressed.
+    pub original_bytes: u64,
+    /// `original_bytes - stored_bytes`.
+    pub saved_bytes: u64,
+}
+
+/// Number of memories of one kind.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KindCount {
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// Memories of this kind.
+    pub count: usize,
+}
+
+/// Number of memories in one detected language.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageCount {
+    /// ISO 639-3 language code (e.g. `"fin"`).
+    pub language: String,
+    /// Memories in this language.
+    pub count: usize,
+}
+
+/// How tightly a project's memory embeddings cluster.
+///
+/// A coherent project has a long centroid and high similarities; a noisy one
+/// has a short centroid, low pairwise similarity and a wide histogram.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmbeddingStats {
+    /// Embeddings analyzed (all-zero embeddings are skipped).
+    pub count: usize,
+    /// Length of the mean of the normalized embeddings: 1.0 when all point
+    /// the same way, near 0.0 when they point in unrelated directions.
+    pub centroid_norm: f64,
+    /// Mean cosine similarity over all pairs of memories (`None` for fewer
+    /// than two).
+    pub mean_pairwise_similarity: Option<f64>,
+    /// Mean cosine similarity of each memory to the centroid.
+    pub mean_centroid_similarity: f64,
+    /// Standard deviation of the similarities to the centroid.
+    pub stddev_centroid_similarity: f64,
+    /// Similarities to the centroid in 0.1-wide buckets from 0.0 to 1.0
+    /// (negative similarities count towards the first bucket).
+    pub histogram: Vec<HistogramBucket>,
+    /// Memories less similar to the centroid than this are outliers.
+    pub outlier_threshold: f64,
+    /// Outliers grouped with outliers similar to each other, largest group
+    /// first.
+    pub outlier_clusters: Vec<OutlierCluster>,
+}
+
+/// One histogram bucket of similarities to the centroid.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistogramBucket {
+    /// Inclusive lower bound.
+    pub min: f64,
+    /// Exclusive upper bound (inclusive for the last bucket).
+    pub max: f64,
+    /// Memories in the bucket.
+    pub count: usize,
+}
+
+/// Outlier memories that resemble each other.
+///
+/// A group of several outliers often marks a topic that drifted into the
+/// project; a single outlier is usually a stray memory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutlierCluster {
+    /// Outliers in the group, least similar to the centroid first.
+    pub members: Vec<Outlier>,
+}
+
+/// A memory far from its project's centroid.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Outlier {
+    /// Memory ID.
+    pub id: String,
+    /// Memory content.
+    pub content: String,
+    /// Cosine similarity to the project centroid.
+    pub centroid_similarity: f64,
+}
+
+/// A project's memories grouped by embedding similarity.
+///
+/// Returned by `MemoryStore::clusters()`, for plotting the memory space in
+/// external tools or spotting topics that dominate a project.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Clustering {
+    /// Project identifier.
+    pub project_id: String,
+    /// Clusters, largest first; cluster numbers follow this order.
+    pub clusters: Vec<MemoryCluster>,
+    /// The cluster of each memory, oldest memory first.
+    pub assignments: Vec<ClusterAssignment>,
+}
+
+/// One cluster of a [`Clustering`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemoryCluster {
+    /// Cluster number (0 is the largest cluster).
+    pub cluster: usize,
+    /// Memories in the cluster.
+    pub size: usize,
+    /// Terms that best distinguish the cluster's content (term frequency
+    /// in the cluster weighted by BM25 inverse document frequency in the
+    /// project), best first.
+    pub terms: Vec<String>,
+    /// Mean cosine similarity of the members to the cluster centroid.
+    pub cohesion: f64,
+}
+
+/// The cluster of one memory in a [`Clustering`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClusterAssignment {
+    /// Memory ID.
+    pub id: String,
+    /// Memory content.
+    pub content: String,
+    /// Cluster number.
+    pub cluster: usize,
+    /// Cosine similarity to the cluster centroid.
+    pub similarity: f64,
+}
+
+/// A memory's stored embedding, summarized by
+/// [`MemoryStore::embedding_info`](crate::MemoryStore::embedding_info).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmbeddingInfo {
+    /// Memory ID.
+    pub id: String,
+    /// Model the store's embeddings come from (the configured model when the
+    /// store has none recorded).
+    pub model_id: String,
+    /// Number of dimensions.
+    pub dims: usize,
+    /// Euclidean length; 1.0 for an unquantized embedding, since embeddings
+    /// are L2-normalized.
+    pub norm: f64,
+    /// The first values, in order.
+    pub values: Vec<f32>,
+}
+
+/// How far stored embeddings are from embedding the same content again.
+///
+/// Returned by `MemoryStore::verify_embeddings()`. The current model should
+/// reproduce its own embeddings almost exactly (int8 storage loses a little
+/// precision), so larger deviations point at a changed model or a corrupted
+/// model cache; `revisions` tells which stored model revisions are affected.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmbeddingVerification {
+    /// Configured embedding model ID.
+    pub model_id: String,
+    /// Hub commit hash of the loaded model files, if known.
+    pub model_revision: Option<String>,
+    /// Seed that picked the sample; pass it again to verify the same memories.
+    pub seed: u64,
+    /// Memories re-embedded and compared.
+    pub sampled: usize,
+    /// Sampled memories skipped because their stored embedding has the wrong
+    /// dimensions (see `vipune reembed --mismatched-only`).
+    pub mismatched: usize,
+    /// Largest cosine deviation (1 - cosine similarity) from a stored
+    /// embedding (0.0 for an empty sample).
+    pub max_deviation: f64,
+    /// Mean cosine deviation over the sample.
+    pub mean_deviation: f64,
+    /// Deviations above this count as drifted.
+    pub tolerance: f64,
+    /// Deviation by the model revision recorded for the memories, largest
+    /// first.
+    pub revisions: Vec<RevisionDeviation>,
+    /// Memories deviating by more than `tolerance`, largest first.
+    pub drifted: Vec<DriftedEmbedding>,
+}
+
+/// Deviation of the sampled memories embedded by one model revision.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RevisionDeviation {
+    /// Hub commit hash, or `None` for memories embedded before revisions
+    /// were recorded.
+    pub revision: Option<String>,
+    /// Sampled memories with this revision.
+    pub sampled: usize,
+    /// Largest cosine deviation among them.
+    pub max_deviation: f64,
+}
+
+/// A memory whose stored embedding no longer matches its content.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DriftedEmbedding {
+    /// Memory ID.
+    pub id: String,
+    /// Model revision recorded for the memory, if any.
+    pub model_revision: Option<String>,
+    /// Cosine deviation (1 - cosine similarity) from a fresh embedding.
+    pub deviation: f64,
+}
+
+/// Stored embeddings holding NaN or infinite values, which make searches
+/// fail.
+///
+/// Returned by `MemoryStore::scan_embeddings()`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmbeddingScan {
+    /// IDs of the contaminated rows (memories and chunks, all projects).
+    pub contaminated: Vec<String>,
+    /// Rows embedded again (0 unless repairing).
+    pub reembedded: usize,
+}
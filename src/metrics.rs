@@ -0,0 +1,230 @@
+//! Operation counters and latency histograms of a memory store.
+//!
+//! Every [`MemoryStore`](crate::MemoryStore) counts adds, add conflicts and
+//! searches and times embedding runs and database queries. [`Metrics::render`]
+//! writes them in the Prometheus text exposition format, which
+//! [`MetricsExporter`] publishes for the node_exporter textfile collector
+//! (`vipune watch --metrics-file`, `vipune daemon --metrics-file`) and
+//! library hosts can serve from their own `/metrics` endpoint.
+//!
+//! Updates are lock-free atomics, so the stores of a
+//! [`SharedMemoryStore`](crate::SharedMemoryStore) pool share one set.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Upper bounds (seconds) of the latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Counters and histograms of one store (or store pool).
+#[derive(Debug, Default)]
+pub struct Metrics {
+    adds: AtomicU64,
+    conflicts: AtomicU64,
+    searches: AtomicU64,
+    embed: Histogram,
+    db_query: Histogram,
+}
+
+impl Metrics {
+    /// Memories stored by adds (imports are not counted).
+    pub fn adds(&self) -> u64 {
+        self.adds.load(Ordering::Relaxed)
+    }
+
+    /// Adds rejected because similar memories exist.
+    pub fn conflicts(&self) -> u64 {
+        self.conflicts.load(Ordering::Relaxed)
+    }
+
+    /// Search queries run (each query of a batch counts once).
+    pub fn searches(&self) -> u64 {
+        self.searches.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_add(&self) {
+        self.adds.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_conflict(&self) {
+        self.conflicts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_searches(&self, count: usize) {
+        self.searches.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Run `f`, recording its duration as an embedding run.
+    pub(crate) fn time_embed<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.embed.time(f)
+    }
+
+    /// Run `f`, recording its duration as a database query.
+    pub(crate) fn time_db<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.db_query.time(f)
+    }
+
+    /// All metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        counter(
+            &mut out,
+            "vipune_memories_added_total",
+            "Memories stored by adds.",
+            self.adds(),
+        );
+        counter(
+            &mut out,
+            "vipune_add_conflicts_total",
+            "Adds rejected because similar memories exist.",
+            self.conflicts(),
+        );
+        counter(
+            &mut out,
+            "vipune_searches_total",
+            "Search queries run.",
+            self.searches(),
+        );
+        self.embed.render(
+            &mut out,
+            "vipune_embed_duration_seconds",
+            "Time spent running the embedding model.",
+        );
+        self.db_query.render(
+            &mut out,
+            "vipune_db_query_duration_seconds",
+            "Time spent in database searches and writes.",
+        );
+        out
+    }
+}
+
+/// Publishes [`Metrics`] to a file read by the node_exporter textfile
+/// collector.
+#[derive(Debug, Clone)]
+pub struct MetricsExporter {
+    path: PathBuf,
+}
+
+impl MetricsExporter {
+    /// Exporter rewriting `path` (e.g. `vipune.prom`).
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// File the metrics are written to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Replace the file with `metrics` rendered, through a `.tmp` file so a
+    /// scrape never reads a half-written one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be written or renamed.
+    pub fn export(&self, metrics: &Metrics) -> std::io::Result<()> {
+        let mut partial = self.path.as_os_str().to_owned();
+        partial.push(".tmp");
+        std::fs::write(&partial, metrics.render())?;
+        std::fs::rename(&partial, &self.path)
+    }
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// Cumulative latency histogram over [`LATENCY_BUCKETS`].
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations per bucket (not cumulative; summed when rendered).
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    fn time<T>(&self, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.observe(started.elapsed());
+        result
+    }
+
+    fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(index) = LATENCY_BUCKETS.iter().position(|&bound| seconds <= bound) {
+            self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        let mut cumulative = 0;
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6;
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+        let _ = writeln!(out, "{}_sum {}", name, sum);
+        let _ = writeln!(out, "{}_count {}", name, count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_cumulative_buckets() {
+        let metrics = Metrics::default();
+        metrics.record_add();
+        metrics.record_add();
+        metrics.record_conflict();
+        metrics.record_searches(3);
+        metrics.db_query.observe(Duration::from_millis(3));
+        metrics.db_query.observe(Duration::from_millis(30));
+        metrics.db_query.observe(Duration::from_secs(60));
+        assert_eq!(metrics.time_embed(|| 7), 7);
+
+        let text = metrics.render();
+        assert!(text.contains(
+            "# TYPE vipune_memories_added_total counter\nvipune_memories_added_total 2\n"
+        ));
+        assert!(text.contains("vipune_add_conflicts_total 1\n"));
+        assert!(text.contains("vipune_searches_total 3\n"));
+        assert!(text.contains("vipune_db_query_duration_seconds_bucket{le=\"0.001\"} 0\n"));
+        assert!(text.contains("vipune_db_query_duration_seconds_bucket{le=\"0.005\"} 1\n"));
+        assert!(text.contains("vipune_db_query_duration_seconds_bucket{le=\"0.05\"} 2\n"));
+        assert!(text.contains("vipune_db_query_duration_seconds_bucket{le=\"10\"} 2\n"));
+        assert!(text.contains("vipune_db_query_duration_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("vipune_db_query_duration_seconds_sum 60.033\n"));
+        assert!(text.contains("vipune_embed_duration_seconds_count 1\n"));
+    }
+
+    #[test]
+    fn test_exporter_replaces_the_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let exporter = MetricsExporter::new(dir.path().join("vipune.prom"));
+        let metrics = Metrics::default();
+        exporter.export(&metrics).unwrap();
+        metrics.record_add();
+        exporter.export(&metrics).unwrap();
+        let text = std::fs::read_to_string(exporter.path()).unwrap();
+        assert!(text.contains("vipune_memories_added_total 1\n"));
+        assert!(!dir.path().join("vipune.prom.tmp").exists());
+    }
+}
@@ -0,0 +1,350 @@
+//! Locating and pre-fetching embedding model files.
+//!
+//! Model files live in the HuggingFace Hub cache (`$HF_HOME/hub`, default
+//! `~/.cache/huggingface/hub`). Online mode downloads missing files on first
+//! use, retrying transient network errors (see `[download]` in the config);
+//! offline mode (config `offline = true` or `HF_HUB_OFFLINE=1`) only reads
+//! the cache and never touches the network.
+
+use std::path::{Path, PathBuf};
+
+use hf_hub::Cache;
+use hf_hub::api::sync::{ApiBuilder, ApiError};
+use ureq::ErrorKind;
+
+use crate::config::DownloadConfig;
+use crate::errors::Error;
+
+/// ONNX model locations tried in order (repos differ in layout).
+const MODEL_FILES: [&str; 2] = ["onnx/model.onnx", "model.onnx"];
+const TOKENIZER_FILE: &str = "tokenizer.json";
+
+/// Local paths to the files needed by `EmbeddingEngine`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelFiles {
+    /// ONNX model file.
+    pub model: PathBuf,
+    /// HuggingFace tokenizer definition.
+    pub tokenizer: PathBuf,
+}
+
+impl ModelFiles {
+    /// Commit hash of the Hub snapshot the model file belongs to, or `None`
+    /// if the file is not laid out like the Hub cache
+    /// (`snapshots/<commit>/...`).
+    pub fn revision(&self) -> Option<String> {
+        self.model
+            .ancestors()
+            .find(|dir| {
+                dir.parent()
+                    .and_then(Path::file_name)
+                    .is_some_and(|name| name == "snapshots")
+            })
+            .and_then(Path::file_name)
+            .map(|commit| commit.to_string_lossy().into_owned())
+    }
+}
+
+/// Download the model and tokenizer into the cache (no-op for cached files).
+///
+/// Always uses the network for files that are not cached yet, regardless of
+/// offline settings; this is the explicit pre-fetch path. Transient network
+/// errors are retried as configured by the default [`DownloadConfig`].
+///
+/// # Errors
+///
+/// Returns `Error::ModelDownload` if the files cannot be downloaded.
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+pub fn download_model(model_id: &str) -> Result<ModelFiles, Error> {
+    download_model_with(model_id, &DownloadConfig::default())
+}
+
+/// Like [`download_model`], retrying transient network errors as `retry`
+/// specifies.
+///
+/// # Errors
+///
+/// Returns `Error::ModelDownload` with the status and URL of the last failed
+/// request if a file cannot be downloaded, or `Error::HfHub` if the Hub
+/// client cannot be set up.
+pub fn download_model_with(model_id: &str, retry: &DownloadConfig) -> Result<ModelFiles, Error> {
+    log::debug!("Resolving model files for {} (network allowed)", model_id);
+    let api = ApiBuilder::from_env().build()?;
+    let repo = api.model(model_id.to_string());
+    let fetch = |file: &str| with_retries(retry, model_id, file, || repo.get(file));
+
+    let model = match fetch(MODEL_FILES[0]) {
+        Err(Error::ModelDownload {
+            transient: false, ..
+        }) => fetch(MODEL_FILES[1]),
+        result => result,
+    }?;
+    let tokenizer = fetch(TOKENIZER_FILE)?;
+    Ok(ModelFiles { model, tokenizer })
+}
+
+/// Run `get` until it succeeds, fails permanently or `retry.retries` retries
+/// are used up, sleeping with exponential backoff in between.
+fn with_retries<T>(
+    retry: &DownloadConfig,
+    model_id: &str,
+    file: &str,
+    mut get: impl FnMut() -> Result<T, ApiError>,
+) -> Result<T, Error> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let failure = match get() {
+            Ok(value) => return Ok(value),
+            Err(e) => Failure::from(e),
+        };
+        if !failure.transient || attempts > retry.retries {
+            return Err(Error::ModelDownload {
+                model: model_id.to_string(),
+                file: file.to_string(),
+                attempts,
+                status: failure.status,
+                url: failure.url,
+                transient: failure.transient,
+                reason: failure.reason,
+            });
+        }
+        let delay = retry.backoff(attempts - 1);
+        log::warn!(
+            "Downloading {} of {} failed ({}); retrying in {:.1?}",
+            file,
+            model_id,
+            failure.reason,
+            delay
+        );
+        std::thread::sleep(delay);
+    }
+}
+
+/// A failed Hub request, classified for retrying.
+struct Failure {
+    status: Option<u16>,
+    url: Option<String>,
+    /// Network flake or server overload that may go away on retry.
+    transient: bool,
+    reason: String,
+}
+
+impl From<ApiError> for Failure {
+    fn from(error: ApiError) -> Self {
+        match error {
+            ApiError::RequestError(request) => match *request {
+                ureq::Error::Status(code, response) => Failure {
+                    status: Some(code),
+                    url: Some(response.get_url().to_string()),
+                    transient: matches!(code, 408 | 429 | 500..=599),
+                    reason: format!("HTTP {code} from {}", response.get_url()),
+                },
+                ureq::Error::Transport(transport) => {
+                    let url = transport.url().map(ToString::to_string);
+                    let transient = matches!(
+                        transport.kind(),
+                        ErrorKind::Dns
+                            | ErrorKind::ConnectionFailed
+                            | ErrorKind::Io
+                            | ErrorKind::ProxyConnect
+                    );
+                    let reason = match &url {
+                        Some(url) => format!("{transport} ({url})"),
+                        None => transport.to_string(),
+                    };
+                    Failure {
+                        status: None,
+                        url,
+                        transient,
+                        reason: if transient {
+                            format!("network error: {reason}")
+                        } else {
+                            reason
+                        },
+                    }
+                }
+            },
+            ApiError::TooManyRetries(inner) => Failure {
+                transient: true,
+                ..Failure::from(*inner)
+            },
+            other => Failure {
+                status: None,
+                url: None,
+                transient: false,
+                reason: other.to_string(),
+            },
+        }
+    }
+}
+
+/// Resolve model files from the local cache without network access.
+///
+/// # Errors
+///
+/// Returns `Error::ModelNotCached` if any required file is missing.
+pub fn cached_model_files(model_id: &str) -> Result<ModelFiles, Error> {
+    cached_model_files_in(&Cache::from_env(), model_id)
+}
+
+fn cached_model_files_in(cache: &Cache, model_id: &str) -> Result<ModelFiles, Error> {
+    log::debug!("Resolving model files for {} from cache only", model_id);
+    let repo = cache.model(model_id.to_string());
+    let model = MODEL_FILES.iter().find_map(|file| repo.get(file));
+    let tokenizer = repo.get(TOKENIZER_FILE);
+    match (model, tokenizer) {
+        (Some(model), Some(tokenizer)) => Ok(ModelFiles { model, tokenizer }),
+        _ => Err(Error::ModelNotCached(model_id.to_string())),
+    }
+}
+
+/// Whether `HF_HUB_OFFLINE` requests offline mode.
+pub fn offline_from_env() -> bool {
+    std::env::var("HF_HUB_OFFLINE")
+        .map(|v| is_truthy(&v))
+        .unwrap_or(false)
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const MODEL_ID: &str = "BAAI/bge-small-en-v1.5";
+
+    /// Lay out files the way hf_hub stores them: refs/main -> snapshots/<commit>/...
+    fn populate_cache(root: &std::path::Path, files: &[&str]) {
+        let repo_dir = root.join("models--BAAI--bge-small-en-v1.5");
+        std::fs::create_dir_all(repo_dir.join("refs")).unwrap();
+        std::fs::write(repo_dir.join("refs/main"), "abc123").unwrap();
+        for file in files {
+            let path = repo_dir.join("snapshots/abc123").join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"stub").unwrap();
+        }
+    }
+
+    fn status_error(code: u16) -> ApiError {
+        let response = ureq::Response::new(code, "status", "").unwrap();
+        ApiError::RequestError(Box::new(ureq::Error::Status(code, response)))
+    }
+
+    const NO_BACKOFF: DownloadConfig = DownloadConfig {
+        retries: 2,
+        initial_backoff_ms: 0,
+        max_backoff_ms: 0,
+    };
+
+    #[test]
+    fn test_with_retries_recovers_from_transient_errors() {
+        let mut calls = 0;
+        let result = with_retries(&NO_BACKOFF, MODEL_ID, TOKENIZER_FILE, || {
+            calls += 1;
+            if calls < 3 {
+                Err(status_error(503))
+            } else {
+                Ok("done")
+            }
+        });
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_with_retries_gives_up_with_status() {
+        let mut calls = 0;
+        let err = with_retries(&NO_BACKOFF, MODEL_ID, TOKENIZER_FILE, || {
+            calls += 1;
+            Err::<(), _>(status_error(429))
+        })
+        .unwrap_err();
+        assert_eq!(calls, 3);
+        assert!(matches!(
+            err,
+            Error::ModelDownload {
+                attempts: 3,
+                status: Some(429),
+                transient: true,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("HTTP 429"), "{err}");
+    }
+
+    #[test]
+    fn test_with_retries_does_not_retry_permanent_errors() {
+        let mut calls = 0;
+        let err = with_retries(&NO_BACKOFF, MODEL_ID, TOKENIZER_FILE, || {
+            calls += 1;
+            Err::<(), _>(status_error(404))
+        })
+        .unwrap_err();
+        assert_eq!(calls, 1);
+        assert!(matches!(
+            err,
+            Error::ModelDownload {
+                status: Some(404),
+                transient: false,
+                ..
+            }
+        ));
+
+        let failure = Failure::from(ApiError::TooManyRetries(Box::new(status_error(404))));
+        assert!(failure.transient);
+    }
+
+    #[test]
+    fn test_cached_model_files_missing() {
+        let dir = TempDir::new().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        let err = cached_model_files_in(&cache, MODEL_ID).unwrap_err();
+        assert!(matches!(err, Error::ModelNotCached(ref id) if id == MODEL_ID));
+        assert!(err.to_string().contains("vipune model download"));
+    }
+
+    #[test]
+    fn test_cached_model_files_requires_tokenizer() {
+        let dir = TempDir::new().unwrap();
+        populate_cache(dir.path(), &["onnx/model.onnx"]);
+        let cache = Cache::new(dir.path().to_path_buf());
+        assert!(cached_model_files_in(&cache, MODEL_ID).is_err());
+    }
+
+    #[test]
+    fn test_cached_model_files_found() {
+        let dir = TempDir::new().unwrap();
+        populate_cache(dir.path(), &["model.onnx", "tokenizer.json"]);
+        let cache = Cache::new(dir.path().to_path_buf());
+        let files = cached_model_files_in(&cache, MODEL_ID).unwrap();
+        assert!(files.model.ends_with("model.onnx"));
+        assert!(files.tokenizer.ends_with("tokenizer.json"));
+        assert_eq!(files.revision().as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_revision_outside_hub_cache() {
+        let files = ModelFiles {
+            model: PathBuf::from("/models/bge/model.onnx"),
+            tokenizer: PathBuf::from("/models/bge/tokenizer.json"),
+        };
+        assert_eq!(files.revision(), None);
+    }
+
+    #[test]
+    fn test_is_truthy() {
+        for value in ["1", "true", "TRUE", " yes ", "on"] {
+            assert!(is_truthy(value), "{value}");
+        }
+        for value in ["", "0", "false", "off", "no"] {
+            assert!(!is_truthy(value), "{value}");
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Instruction prefixes for asymmetric retrieval models.
+//!
+//! Models such as BGE and E5 are trained to embed search queries and the
+//! passages they should find differently: the input is prefixed with an
+//! instruction (e.g. BGE's "Represent this sentence for searching relevant
+//! passages: ") that tells the model which side it is embedding. Leaving the
+//! prefixes out still works but ranks relevant passages lower.
+//!
+//! Models are matched by name, ignoring the owner (so ONNX mirrors such as
+//! `Xenova/bge-small-en-v1.5` are recognized) and case. Unknown models get no
+//! prefixes.
+
+/// Prefixes a model expects in front of queries and documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModelPrefixes {
+    /// Prepended to search queries.
+    pub query: &'static str,
+    /// Prepended to stored memories (documents).
+    pub document: &'static str,
+}
+
+impl ModelPrefixes {
+    /// No prefixes: queries and documents are embedded as-is.
+    pub const NONE: Self = Self {
+        query: "",
+        document: "",
+    };
+}
+
+/// English BGE models: instruction on queries only.
+const BGE_EN: ModelPrefixes = ModelPrefixes {
+    query: "Represent this sentence for searching relevant passages: ",
+    document: "",
+};
+
+/// E5 models: a role marker on both sides.
+const E5: ModelPrefixes = ModelPrefixes {
+    query: "query: ",
+    document: "passage: ",
+};
+
+/// Nomic embed models: task prefixes on both sides.
+const NOMIC: ModelPrefixes = ModelPrefixes {
+    query: "search_query: ",
+    document: "search_document: ",
+};
+
+/// Known model families by lowercase model name prefix; the first match wins.
+const REGISTRY: &[(&str, ModelPrefixes)] = &[
+    ("bge-small-en", BGE_EN),
+    ("bge-base-en", BGE_EN),
+    ("bge-large-en", BGE_EN),
+    // Instruct variants take a task description instead of a role marker
+    ("multilingual-e5-large-instruct", ModelPrefixes::NONE),
+    ("e5-", E5),
+    ("multilingual-e5-", E5),
+    ("nomic-embed-text", NOMIC),
+];
+
+/// Prefixes for `model_id` (e.g. `"BAAI/bge-small-en-v1.5"`).
+pub fn prefixes_for(model_id: &str) -> ModelPrefixes {
+    let name = model_id
+        .rsplit('/')
+        .next()
+        .unwrap_or(model_id)
+        .to_ascii_lowercase();
+    REGISTRY
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map_or(ModelPrefixes::NONE, |(_, prefixes)| *prefixes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bge_prefixes_queries_only() {
+        let prefixes = prefixes_for("BAAI/bge-small-en-v1.5");
+        assert!(prefixes.query.starts_with("Represent this sentence"));
+        assert_eq!(prefixes.document, "");
+        assert_eq!(prefixes_for("Xenova/bge-small-en-v1.5"), prefixes);
+        assert_eq!(prefixes_for("baai/BGE-BASE-EN-v1.5"), prefixes);
+    }
+
+    #[test]
+    fn test_e5_and_nomic_prefix_both_sides() {
+        assert_eq!(prefixes_for("intfloat/e5-small-v2"), E5);
+        assert_eq!(prefixes_for("intfloat/multilingual-e5-small"), E5);
+        assert_eq!(prefixes_for("nomic-ai/nomic-embed-text-v1.5"), NOMIC);
+        assert_eq!(
+            prefixes_for("intfloat/multilingual-e5-large-instruct"),
+            ModelPrefixes::NONE
+        );
+    }
+
+    #[test]
+    fn test_unknown_models_get_no_prefixes() {
+        assert_eq!(
+            prefixes_for("sentence-transformers/all-MiniLM-L6-v2"),
+            ModelPrefixes::NONE
+        );
+        assert_eq!(prefixes_for("BAAI/bge-m3"), ModelPrefixes::NONE);
+        assert_eq!(prefixes_for(""), ModelPrefixes::NONE);
+    }
+}
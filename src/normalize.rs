@@ -0,0 +1,141 @@
+//! Unicode normalization of text for keyword matching (`[normalization]`).
+//!
+//! The same text can be written in several ways: "ｃａｆé" (full-width),
+//! "Café", "café" and "cafe" look alike to a reader and to the embedding
+//! model, but FTS5 tokenizers only fold some of them. When enabled, the
+//! full-text index and keyword queries both see the normalized form, so
+//! BM25 search matches what semantic search already treats as the same.
+//! Stored content is never changed.
+
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// Normalization steps applied to indexed text and keyword queries.
+///
+/// All steps are off by default. The steps an FTS5 index was built with are
+/// recorded in its schema; changing them takes effect for an existing
+/// database after `vipune reindex`.
+///
+/// ```toml
+/// [normalization]
+/// nfkc = true
+/// case_fold = true
+/// strip_accents = true
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TextNormalization {
+    /// Unicode compatibility normalization (NFKC): full-width and other
+    /// compatibility forms become their plain equivalents ("ｆｕｌｌ" →
+    /// "full", "ﬁ" → "fi"), and composed and decomposed accents compare
+    /// equal.
+    pub nfkc: bool,
+    /// Lowercase the text, so "Café" matches "café".
+    pub case_fold: bool,
+    /// Remove accents and other combining marks, so "café" matches "cafe".
+    pub strip_accents: bool,
+}
+
+impl TextNormalization {
+    /// Whether no step is enabled.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `text` with the enabled steps applied.
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = if self.nfkc {
+            text.nfkc().collect()
+        } else {
+            text.to_string()
+        };
+        if self.strip_accents {
+            text = text
+                .nfd()
+                .filter(|c| !is_combining_mark(*c))
+                .nfc()
+                .collect();
+        }
+        if self.case_fold {
+            text = text.to_lowercase();
+        }
+        text
+    }
+
+    /// Comma-separated names of the enabled steps, as recorded in the FTS5
+    /// schema (empty when none is).
+    pub(crate) fn spec(&self) -> String {
+        let steps = [
+            (self.nfkc, "nfkc"),
+            (self.case_fold, "case_fold"),
+            (self.strip_accents, "strip_accents"),
+        ];
+        steps
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Parse [`Self::spec`] output, ignoring unknown step names.
+    pub(crate) fn from_spec(spec: &str) -> Self {
+        let mut normalization = Self::default();
+        for step in spec.split(',') {
+            match step.trim() {
+                "nfkc" => normalization.nfkc = true,
+                "case_fold" => normalization.case_fold = true,
+                "strip_accents" => normalization.strip_accents = true,
+                _ => {}
+            }
+        }
+        normalization
+    }
+}
+
+impl std::fmt::Display for TextNormalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_identity() {
+            f.write_str("none")
+        } else {
+            f.write_str(&self.spec().replace(',', ", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: TextNormalization = TextNormalization {
+        nfkc: true,
+        case_fold: true,
+        strip_accents: true,
+    };
+
+    #[test]
+    fn test_apply() {
+        let nfkc = TextNormalization {
+            nfkc: true,
+            ..TextNormalization::default()
+        };
+        assert_eq!(nfkc.apply("ＣＡＦＥ ﬁle"), "CAFE file");
+        // Decomposed "é" composes
+        assert_eq!(nfkc.apply("Cafe\u{301}"), "Café");
+
+        assert_eq!(ALL.apply("Ｃａｆé Crème"), "cafe creme");
+        assert_eq!(ALL.apply("Cafe\u{301}"), "cafe");
+        assert_eq!(TextNormalization::default().apply("Café"), "Café");
+    }
+
+    #[test]
+    fn test_spec_round_trip() {
+        assert_eq!(ALL.spec(), "nfkc,case_fold,strip_accents");
+        assert_eq!(TextNormalization::from_spec(&ALL.spec()), ALL);
+        assert!(TextNormalization::default().spec().is_empty());
+        assert!(TextNormalization::from_spec("").is_identity());
+        assert_eq!(ALL.to_string(), "nfkc, case_fold, strip_accents");
+        assert_eq!(TextNormalization::default().to_string(), "none");
+    }
+}
@@ -0,0 +1,344 @@
+//! Import a directory of markdown or plain-text notes.
+//!
+//! `.md`, `.markdown` and `.txt` files under the directory (hidden files and
+//! directories such as `.git` or `.obsidian` excluded) are split into
+//! sections, each stored as one memory with metadata recording the file path
+//! relative to the directory and the heading the section falls under:
+//!
+//! ```json
+//! {"path": "projects/vipune.md", "heading": "Design"}
+//! ```
+//!
+//! Sections go through the same pipeline as JSON imports: conflict detection
+//! skips near-duplicates, and progress is checkpointed so `--resume` works.
+//! YAML front matter at the top of a file is ignored.
+
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+use crate::errors::Error;
+use crate::import::{ImportStats, JsonMemory, import_records};
+use crate::import_options::ImportOptions;
+use crate::memory::MemoryStore;
+
+/// How note files are split into memories.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SplitMode {
+    /// One memory per heading section (text before the first heading is its
+    /// own section).
+    #[default]
+    Heading,
+    /// One memory per blank-line separated paragraph.
+    Paragraph,
+}
+
+/// A section of a note file, ready to be stored as a memory.
+#[derive(Debug, PartialEq)]
+pub struct NoteSection {
+    /// File path relative to the imported directory, `/`-separated.
+    pub path: String,
+    /// Nearest heading above the section, if any.
+    pub heading: Option<String>,
+    /// Section text.
+    pub content: String,
+}
+
+/// Import every note under `path` (a directory or a single note file).
+///
+/// `on_progress` is called after each section as for
+/// [`crate::import::import_from_json`].
+///
+/// # Errors
+///
+/// Returns error if a note cannot be read, or if embedding or database
+/// operations fail.
+pub fn import_from_markdown(
+    store: &mut MemoryStore,
+    path: &Path,
+    split: SplitMode,
+    default_project: &str,
+    options: &ImportOptions,
+    on_progress: &mut dyn FnMut(usize, &ImportStats),
+) -> Result<ImportStats, Error> {
+    let records = read_notes(path, split)?
+        .into_iter()
+        .map(|section| {
+            let mut metadata = serde_json::json!({ "path": section.path });
+            if let Some(heading) = section.heading {
+                metadata["heading"] = serde_json::Value::String(heading);
+            }
+            JsonMemory {
+                id: None,
+                content: section.content,
+                project_id: None,
+                user_id: None,
+                metadata: Some(metadata),
+                created_at: None,
+                kind: None,
+                embedding: None,
+                model_id: None,
+            }
+        })
+        .collect();
+    import_records(store, records, path, default_project, options, on_progress)
+}
+
+/// Read and split every note under `path`, in path order.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if `path` does not exist, and an I/O error
+/// if a file cannot be read.
+pub fn read_notes(path: &Path, split: SplitMode) -> Result<Vec<NoteSection>, Error> {
+    if !path.exists() {
+        return Err(Error::InvalidInput(format!(
+            "Notes path does not exist: {}",
+            path.display()
+        )));
+    }
+    let files = if path.is_dir() {
+        let mut files = Vec::new();
+        collect_note_files(path, &mut files)?;
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let root = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    let mut sections = Vec::new();
+    for file in files {
+        let relative = file
+            .strip_prefix(root)
+            .unwrap_or(&file)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let text = std::fs::read_to_string(&file)?;
+        for (heading, content) in split_note(&text, split) {
+            sections.push(NoteSection {
+                path: relative.clone(),
+                heading,
+                content,
+            });
+        }
+    }
+    log::debug!(
+        "Read {} sections from notes in {}",
+        sections.len(),
+        path.display()
+    );
+    Ok(sections)
+}
+
+/// Recursively collect note files under `dir`, skipping hidden entries.
+fn collect_note_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_note_files(&path, files)?;
+        } else if is_note(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_note(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("md") | Some("markdown") | Some("txt")
+    )
+}
+
+/// Split note `text` into `(heading, content)` sections.
+///
+/// Lines inside fenced code blocks are never treated as headings or
+/// paragraph breaks. Sections with no text besides their heading are dropped.
+fn split_note(text: &str, split: SplitMode) -> Vec<(Option<String>, String)> {
+    let mut sections = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    let flush = |sections: &mut Vec<(Option<String>, String)>,
+                 heading: &Option<String>,
+                 current: &mut Vec<&str>| {
+        let has_body = current.iter().any(|line| {
+            let line = line.trim();
+            !line.is_empty() && parse_heading(line).is_none()
+        });
+        if has_body {
+            sections.push((heading.clone(), current.join("\n").trim().to_string()));
+        }
+        current.clear();
+    };
+
+    for line in strip_front_matter(text).lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            current.push(line);
+            continue;
+        }
+        if in_fence {
+            current.push(line);
+            continue;
+        }
+
+        if let Some(title) = parse_heading(trimmed) {
+            flush(&mut sections, &heading, &mut current);
+            heading = Some(title.to_string());
+            if split == SplitMode::Heading {
+                current.push(line);
+            }
+        } else if trimmed.is_empty() && split == SplitMode::Paragraph {
+            flush(&mut sections, &heading, &mut current);
+        } else {
+            current.push(line);
+        }
+    }
+    flush(&mut sections, &heading, &mut current);
+    sections
+}
+
+/// Title of an ATX heading line (`# Title`, up to six `#`), if `line` is one.
+fn parse_heading(line: &str) -> Option<&str> {
+    let level = line.bytes().take_while(|&b| b == b'#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim_end();
+    (!title.is_empty()).then_some(title)
+}
+
+/// `text` without a leading `---` delimited YAML front matter block.
+fn strip_front_matter(text: &str) -> &str {
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return text;
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_end() == "---" {
+            return &rest[offset..];
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const NOTE: &str = "Intro line\n\n# Design\n\nFirst paragraph.\nStill first.\n\nSecond paragraph.\n\n## Empty\n\n## Code\n\n```sh\n# not a heading\n\nstill code\n```\n";
+
+    #[test]
+    fn test_split_by_heading() {
+        let sections = split_note(NOTE, SplitMode::Heading);
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0], (None, "Intro line".to_string()));
+        assert_eq!(sections[1].0.as_deref(), Some("Design"));
+        assert_eq!(
+            sections[1].1,
+            "# Design\n\nFirst paragraph.\nStill first.\n\nSecond paragraph."
+        );
+        assert_eq!(sections[2].0.as_deref(), Some("Code"));
+        assert!(sections[2].1.contains("# not a heading\n\nstill code"));
+    }
+
+    #[test]
+    fn test_split_by_paragraph() {
+        let sections = split_note(NOTE, SplitMode::Paragraph);
+        let contents: Vec<(Option<&str>, &str)> = sections
+            .iter()
+            .map(|(h, c)| (h.as_deref(), c.as_str()))
+            .collect();
+        assert_eq!(
+            contents,
+            vec![
+                (None, "Intro line"),
+                (Some("Design"), "First paragraph.\nStill first."),
+                (Some("Design"), "Second paragraph."),
+                (Some("Code"), "```sh\n# not a heading\n\nstill code\n```"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_heading() {
+        assert_eq!(parse_heading("# Title"), Some("Title"));
+        assert_eq!(parse_heading("### Closed ###"), Some("Closed"));
+        assert_eq!(parse_heading("#hashtag"), None);
+        assert_eq!(parse_heading("####### Too deep"), None);
+        assert_eq!(parse_heading("#"), None);
+    }
+
+    #[test]
+    fn test_front_matter_is_skipped() {
+        let text = "---\ntitle: Note\ntags: [a]\n---\nBody text\n";
+        assert_eq!(strip_front_matter(text), "Body text\n");
+        // An unterminated block is kept as text
+        assert_eq!(strip_front_matter("---\nno end\n"), "---\nno end\n");
+    }
+
+    #[test]
+    fn test_read_notes_walks_directory() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("projects")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".obsidian")).unwrap();
+        std::fs::write(dir.path().join("a.md"), "# A\nAlpha").unwrap();
+        std::fs::write(dir.path().join("projects/b.txt"), "Beta").unwrap();
+        std::fs::write(dir.path().join(".obsidian/c.md"), "Hidden").unwrap();
+        std::fs::write(dir.path().join("image.png"), "binary").unwrap();
+
+        let sections = read_notes(dir.path(), SplitMode::Heading).unwrap();
+        assert_eq!(
+            sections,
+            vec![
+                NoteSection {
+                    path: "a.md".to_string(),
+                    heading: Some("A".to_string()),
+                    content: "# A\nAlpha".to_string(),
+                },
+                NoteSection {
+                    path: "projects/b.txt".to_string(),
+                    heading: None,
+                    content: "Beta".to_string(),
+                },
+            ]
+        );
+
+        let single = read_notes(&dir.path().join("projects/b.txt"), SplitMode::Heading).unwrap();
+        assert_eq!(single[0].path, "b.txt");
+    }
+
+    #[test]
+    fn test_read_notes_rejects_missing_path() {
+        assert!(matches!(
+            read_notes(Path::new("/nonexistent/notes"), SplitMode::Heading),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
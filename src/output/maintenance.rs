@@ -0,0 +1,347 @@
+//! Responses of the embedding, sync, archive and maintenance commands.
+
+use serde::Serialize;
+
+use crate::memory_types::{EmbeddingScan, HealthReport, ProjectRename, PrunedProject};
+use crate::sqlite::MemoryKind;
+
+/// Response for `vipune embedding show`.
+#[derive(Serialize)]
+pub struct EmbeddingShowResponse {
+    /// Memory ID.
+    pub id: String,
+    /// Model the store's embeddings come from.
+    pub model_id: String,
+    /// Number of dimensions.
+    pub dims: usize,
+    /// Euclidean length of the embedding.
+    pub norm: f64,
+    /// The first values of the embedding.
+    pub values: Vec<f32>,
+}
+
+/// Response for `vipune embedding compare`.
+#[derive(Serialize)]
+pub struct EmbeddingCompareResponse {
+    /// First memory ID.
+    pub id_a: String,
+    /// Second memory ID.
+    pub id_b: String,
+    /// Cosine similarity between their embeddings.
+    pub similarity: f64,
+}
+
+/// Response for `vipune embedding query`.
+#[derive(Serialize)]
+pub struct EmbeddingQueryResponse {
+    /// The search query.
+    pub query: String,
+    /// Memory ID.
+    pub id: String,
+    /// Cosine similarity between the query and the memory.
+    pub similarity: f64,
+}
+
+/// Response for `vipune sync push` and `vipune sync pull`.
+#[derive(Serialize)]
+pub struct SyncResponse {
+    /// Operation status ("pushed" or "pulled").
+    pub status: String,
+    /// The other database.
+    pub peer: String,
+    /// Changed memories sent.
+    pub changes: usize,
+    /// Memories added to the receiving store.
+    pub added: usize,
+    /// Memories overwritten by a copy updated later.
+    pub updated: usize,
+    /// Similar memories overwritten by a copy updated later.
+    pub merged: usize,
+    /// Memories moved to the receiving store's trash.
+    pub deleted: usize,
+    /// Changes the receiving store already had (or had newer).
+    pub skipped: usize,
+}
+
+/// Response for writing or restoring a whole-store archive.
+#[derive(Serialize)]
+pub struct ArchiveResponse {
+    /// Operation status ("exported" or "imported").
+    pub status: String,
+    /// Archive file.
+    pub path: String,
+    /// Memories in the archive.
+    pub memories: usize,
+    /// Schema version of the archived database.
+    pub schema_version: u32,
+    /// Embedding model of the archived memories.
+    pub model_id: String,
+    /// When the archive was written (RFC3339).
+    pub created_at: String,
+}
+
+/// Response for `vipune doctor`: the health report, plus the embedding scan
+/// with `--scan-embeddings`.
+#[derive(Serialize)]
+pub struct DoctorResponse {
+    #[serde(flatten)]
+    pub health: HealthReport,
+    /// Stored embeddings holding NaN or infinite values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<EmbeddingScan>,
+}
+
+/// Response for embedding stored memories again.
+#[derive(Serialize)]
+pub struct ReembedResponse {
+    /// Operation status ("reembedded").
+    pub status: String,
+    /// Whether only rows with mismatched dimensions were re-embedded.
+    pub mismatched_only: bool,
+    /// Rows (memories and chunks) re-embedded.
+    pub reembedded: usize,
+}
+
+/// Response for converting stored embeddings to another format.
+#[derive(Serialize)]
+pub struct QuantizeResponse {
+    /// Operation status ("converted").
+    pub status: String,
+    /// Format the embeddings are now stored in ("f32" or "int8").
+    pub format: String,
+    /// Rows (memories and chunks) rewritten.
+    pub converted: usize,
+}
+
+/// Response for rebuilding the full-text index.
+#[derive(Serialize)]
+pub struct ReindexResponse {
+    /// Operation status ("reindexed").
+    pub status: String,
+    /// Tokenizer the index now uses.
+    pub tokenizer: String,
+    /// Tokenizer the index used before, if known.
+    pub previous: Option<String>,
+    /// Normalization steps the index now applies ("none" or a
+    /// comma-separated list).
+    pub normalization: String,
+    /// Rows (memories and chunks) indexed.
+    pub indexed: usize,
+}
+
+/// Response for a retention prune run.
+#[derive(Serialize)]
+pub struct PruneResponse {
+    /// Operation status ("pruned").
+    pub status: String,
+    /// Memories removed because they outlived their TTL.
+    pub removed: usize,
+    /// Projects that had at least one memory removed.
+    pub projects: usize,
+    /// Whether removed memories were archived rather than deleted.
+    pub archived: bool,
+    /// Memories permanently removed from the trash.
+    pub trash_purged: usize,
+    /// Memories marked inactive for being idle.
+    pub deactivated: usize,
+}
+
+/// Response for `vipune review` without decisions.
+#[derive(Serialize)]
+pub struct ReviewQueueResponse {
+    /// Project the memories belong to.
+    pub project_id: String,
+    /// Idle days that put a memory up for review.
+    pub stale_days: u32,
+    /// Highest decayed importance in the queue.
+    pub max_importance: f64,
+    /// Memories due for review, lowest decayed importance first.
+    pub candidates: Vec<crate::memory_types::ReviewCandidate>,
+}
+
+/// Response for applying review decisions.
+#[derive(Serialize)]
+pub struct ReviewResponse {
+    /// Operation status ("reviewed").
+    pub status: String,
+    /// Memories kept (their review time is now).
+    pub kept: usize,
+    /// Memories moved to the archive table.
+    pub archived: usize,
+    /// Memories deleted.
+    pub deleted: usize,
+    /// Decisions on IDs that matched no memory.
+    pub not_found: Vec<String>,
+}
+
+/// Response for audit log queries.
+#[derive(Serialize)]
+pub struct AuditResponse {
+    /// Recorded mutations, oldest first.
+    pub entries: Vec<crate::sqlite::AuditEntry>,
+}
+
+/// Response for a completed model download.
+#[derive(Serialize)]
+pub struct ModelDownloadResponse {
+    /// Operation status ("downloaded").
+    pub status: String,
+    /// HuggingFace model ID.
+    pub model: String,
+    /// Local path of the ONNX model file.
+    pub model_path: String,
+    /// Local path of the tokenizer file.
+    pub tokenizer_path: String,
+}
+
+/// Response for `vipune project normalize`.
+#[derive(Serialize)]
+pub struct ProjectNormalizeResponse {
+    /// Operation status ("normalized", or "dry_run" when nothing was changed).
+    pub status: String,
+    /// Project IDs renamed (or that would be renamed).
+    pub renames: Vec<ProjectRename>,
+}
+
+/// Response for `vipune config profiles`.
+#[derive(Serialize)]
+pub struct ProfilesResponse {
+    /// Profile in use (`--profile` or `VIPUNE_PROFILE`), if any.
+    pub active: Option<String>,
+    /// Profiles defined in the config file, sorted by name.
+    pub profiles: Vec<ProfileResponse>,
+}
+
+/// A configuration profile in a [`ProfilesResponse`].
+#[derive(Serialize)]
+pub struct ProfileResponse {
+    /// Profile name (`[profiles.<name>]`).
+    pub name: String,
+    /// Whether this is the profile in use.
+    pub active: bool,
+    /// Database the profile uses.
+    pub database_path: String,
+    /// Embedding model the profile uses.
+    pub embedding_model: String,
+}
+
+/// Response for `vipune project prune`.
+#[derive(Serialize)]
+pub struct ProjectPruneResponse {
+    /// Operation status ("pruned", or "dry_run" when nothing was changed).
+    pub status: String,
+    /// Projects deleted (or that would be deleted).
+    pub projects: Vec<PrunedProject>,
+}
+
+/// Response for `vipune fixture generate`.
+#[derive(Serialize)]
+pub struct FixtureResponse {
+    /// Operation status ("generated").
+    pub status: String,
+    /// Project the memories were added to.
+    pub project_id: String,
+    /// Memories generated.
+    pub count: usize,
+    /// Seed the memories were generated from.
+    pub seed: u64,
+}
+
+/// Response for `vipune meta set`.
+#[derive(Serialize)]
+pub struct MetaSetResponse {
+    /// Operation status ("updated", or "dry_run" when nothing was changed).
+    pub status: String,
+    /// Memories that passed the filters.
+    pub matched: usize,
+    /// IDs of the memories updated (or that would be updated).
+    pub updated: Vec<String>,
+}
+
+/// Response for `vipune synonym add` and `vipune synonym remove`.
+#[derive(Serialize)]
+pub struct SynonymChangeResponse {
+    /// Operation status ("added" or "removed").
+    pub status: String,
+    /// Term whose synonyms changed.
+    pub term: String,
+    /// Synonym pairs added or removed.
+    pub changed: usize,
+}
+
+/// One term and its synonyms in a [`SynonymListResponse`].
+#[derive(Serialize)]
+pub struct SynonymEntry {
+    /// Term as stored (lowercase, a single word).
+    pub term: String,
+    /// Synonyms of the term, sorted.
+    pub synonyms: Vec<String>,
+}
+
+/// Response for `vipune synonym list`.
+#[derive(Serialize)]
+pub struct SynonymListResponse {
+    /// Project the synonyms belong to.
+    pub project_id: String,
+    /// Terms with their synonyms, sorted by term.
+    pub synonyms: Vec<SynonymEntry>,
+}
+
+/// A memory in a [`TrashListResponse`].
+#[derive(Serialize)]
+pub struct TrashItem {
+    /// Unique identifier of the memory.
+    pub id: String,
+    /// Memory content.
+    pub content: String,
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// Creation timestamp in RFC3339 format.
+    pub created_at: String,
+    /// Deletion timestamp in RFC3339 format.
+    pub deleted_at: String,
+}
+
+/// Response for `vipune trash list`.
+#[derive(Serialize)]
+pub struct TrashListResponse {
+    /// Trashed memories, most recently deleted first.
+    pub memories: Vec<TrashItem>,
+}
+
+/// Response for `vipune trash restore`.
+#[derive(Serialize)]
+pub struct RestoreResponse {
+    /// Operation status ("restored").
+    pub status: String,
+    /// Unique identifier of the restored memory.
+    pub id: String,
+    /// Write sequence of the store after the restore.
+    pub seq: i64,
+}
+
+/// Response for `vipune trash empty`.
+#[derive(Serialize)]
+pub struct TrashEmptyResponse {
+    /// Operation status ("emptied").
+    pub status: String,
+    /// Memories permanently removed.
+    pub removed: usize,
+}
+
+/// Progress report emitted by watch mode after each poll that found entries.
+#[derive(Serialize)]
+pub struct WatchProgressResponse {
+    /// Entries stored during this poll.
+    pub ingested: usize,
+    /// Modified files whose memories were updated during this poll.
+    pub updated: usize,
+    /// Entries skipped (duplicates or invalid) during this poll.
+    pub skipped: usize,
+    /// Entries stored since the watcher started.
+    pub total_ingested: usize,
+    /// Memories updated since the watcher started.
+    pub total_updated: usize,
+    /// Entries skipped since the watcher started.
+    pub total_skipped: usize,
+}
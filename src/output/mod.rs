@@ -0,0 +1,350 @@
+//! JSON response types and formatting for CLI output.
+//!
+//! [`Printer`] decides how results reach the terminal: JSON with `--json`,
+//! only memory IDs with `--quiet`, otherwise human-readable text that is
+//! colored when stdout is a terminal.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::list_options::SelectedFields;
+use crate::sqlite::{MemoryKind, Origin, ScoreExplanation};
+
+mod maintenance;
+mod printer;
+#[cfg(test)]
+mod tests;
+
+pub use maintenance::{
+    ArchiveResponse, AuditResponse, DoctorResponse, EmbeddingCompareResponse,
+    EmbeddingQueryResponse, EmbeddingShowResponse, FixtureResponse, MetaSetResponse,
+    ModelDownloadResponse, ProfileResponse, ProfilesResponse, ProjectNormalizeResponse,
+    ProjectPruneResponse, PruneResponse, QuantizeResponse, ReembedResponse, ReindexResponse,
+    RestoreResponse, ReviewQueueResponse, ReviewResponse, SyncResponse, SynonymChangeResponse,
+    SynonymEntry, SynonymListResponse, TrashEmptyResponse, TrashItem, TrashListResponse,
+    WatchProgressResponse,
+};
+pub use printer::Printer;
+
+/// Response for successful memory addition.
+#[derive(Serialize, JsonSchema)]
+pub struct AddResponse {
+    /// Operation status ("added").
+    pub status: String,
+    /// Unique identifier of the added memory.
+    pub id: String,
+    /// Write sequence of the store after the add (see `search --min-seq`).
+    pub seq: i64,
+}
+
+/// Response for `vipune add --dry-run` when the memory would be added.
+#[derive(Serialize)]
+pub struct AddPreviewResponse {
+    /// Operation status ("would_add").
+    pub status: String,
+}
+
+/// A listed or found memory with its usual fields, or only those chosen
+/// with `--fields`.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Selectable<T> {
+    /// Every field of the response item.
+    All(T),
+    /// The fields chosen with `--fields`, in that order.
+    Selected(SelectedFields),
+}
+
+/// Response for search results.
+#[derive(Serialize, JsonSchema)]
+pub struct SearchResponse<T = SearchResultItem> {
+    /// List of search results ranked by relevance.
+    pub results: Vec<T>,
+}
+
+/// Response for `vipune search --all-projects --group-by project`.
+#[derive(Serialize)]
+pub struct GroupedSearchResponse<T = SearchResultItem> {
+    /// Results per project, projects with the best hits first.
+    pub groups: Vec<SearchGroup<T>>,
+}
+
+/// Results of one project in a grouped search.
+#[derive(Serialize)]
+pub struct SearchGroup<T = SearchResultItem> {
+    /// Project identifier.
+    pub project_id: String,
+    /// Hits found in the project (at most `--limit`).
+    pub count: usize,
+    /// The project's best hits (at most `--per-project`).
+    pub results: Vec<T>,
+}
+
+/// Response for `vipune search --queries-file`.
+#[derive(Serialize)]
+pub struct BatchSearchResponse<T = SearchResultItem> {
+    /// Results per query, in file order.
+    pub queries: Vec<BatchSearchItem<T>>,
+}
+
+/// Results for one query of a batch search.
+#[derive(Serialize)]
+pub struct BatchSearchItem<T = SearchResultItem> {
+    /// Query text.
+    pub query: String,
+    /// Search results ranked by relevance.
+    pub results: Vec<T>,
+}
+
+/// Individual search result item.
+#[derive(Serialize, JsonSchema)]
+pub struct SearchResultItem {
+    /// Unique identifier of the memory.
+    pub id: String,
+    /// Project identifier for this memory.
+    pub project_id: String,
+    /// Memory content.
+    pub content: String,
+    /// Relevance score (0.0 to 1.0, higher is better).
+    pub similarity: f64,
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// User-provided metadata (JSON string), if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+    /// Values of the `tags` metadata, if any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Best-matching chunk, for memories stored in chunks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    /// Score breakdown, when requested with `--explain`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
+    /// Model tokens in the content, if the tokenizer is available (the model
+    /// embeds at most 512).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_count: Option<usize>,
+    /// Creation timestamp in RFC3339 format.
+    pub created_at: String,
+    /// Last update timestamp in RFC3339 format.
+    pub updated_at: String,
+}
+
+/// Response for retrieving a specific memory.
+#[derive(Serialize, JsonSchema)]
+pub struct GetResponse {
+    /// Unique identifier of the memory.
+    pub id: String,
+    /// Memory content.
+    pub content: String,
+    /// Title: the one given on add, or the first sentence of the content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Project identifier for this memory.
+    pub project_id: String,
+    /// Optional user-provided metadata (JSON string).
+    pub metadata: Option<String>,
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// Entry path the memory was written through.
+    pub origin: Origin,
+    /// Model tokens in the content, if the tokenizer is available (the model
+    /// embeds at most 512).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_count: Option<usize>,
+    /// Creation timestamp in RFC3339 format.
+    pub created_at: String,
+    /// Last update timestamp in RFC3339 format.
+    pub updated_at: String,
+}
+
+/// Response for listing memories.
+#[derive(Serialize, JsonSchema)]
+pub struct ListResponse<T = ListItem> {
+    /// List of memories ordered by creation time (newest first).
+    pub memories: Vec<T>,
+}
+
+/// Individual list item.
+#[derive(Serialize, JsonSchema)]
+pub struct ListItem {
+    /// Unique identifier of the memory.
+    pub id: String,
+    /// Title: the one given on add, or the first sentence of the content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Memory content.
+    pub content: String,
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// Model tokens in the content, if the tokenizer is available (the model
+    /// embeds at most 512).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_count: Option<usize>,
+    /// Creation timestamp in RFC3339 format.
+    pub created_at: String,
+}
+
+/// Response for successful memory deletion.
+#[derive(Serialize)]
+pub struct DeleteResponse {
+    /// Operation status ("deleted", or "trashed" with `trash_retention_days`).
+    pub status: String,
+    /// Unique identifier of the deleted memory.
+    pub id: String,
+    /// Write sequence of the store after the delete.
+    pub seq: i64,
+}
+
+/// Response for successful memory update.
+#[derive(Serialize)]
+pub struct UpdateResponse {
+    /// Operation status ("updated", "touched" or "appended").
+    pub status: String,
+    /// Unique identifier of the updated memory.
+    pub id: String,
+    /// Write sequence of the store after the update.
+    pub seq: i64,
+}
+
+/// Response for `vipune history`.
+#[derive(Serialize)]
+pub struct HistoryResponse {
+    /// Unique identifier of the memory.
+    pub id: String,
+    /// Earlier versions, oldest first, followed by the current content.
+    pub versions: Vec<HistoryVersion>,
+}
+
+/// One version in a [`HistoryResponse`].
+#[derive(Serialize)]
+pub struct HistoryVersion {
+    /// Content of the version.
+    pub content: String,
+    /// Metadata of the version (JSON string), if any.
+    pub metadata: Option<String>,
+    /// When the version was written (RFC3339).
+    pub updated_at: String,
+    /// When an update replaced it (RFC3339); `None` for the current content.
+    pub replaced_at: Option<String>,
+    /// Word diff from the version before it, with `--diff`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+/// Response for error cases.
+#[derive(Serialize, JsonSchema)]
+pub struct ErrorResponse {
+    /// Error message describing what went wrong.
+    pub error: String,
+}
+
+/// Response for conflict detection.
+#[derive(Serialize, JsonSchema)]
+pub struct ConflictsResponse {
+    /// Operation status ("conflicts").
+    pub status: String,
+    /// The proposed memory content.
+    pub proposed: String,
+    /// List of conflicting memories.
+    pub conflicts: Vec<ConflictMemoryResponse>,
+}
+
+/// Individual conflicting memory in response.
+#[derive(Serialize, JsonSchema)]
+pub struct ConflictMemoryResponse {
+    /// Unique identifier of the conflicting memory.
+    pub id: String,
+    /// Memory content.
+    pub content: String,
+    /// Cosine similarity of the embeddings (0.0 to 1.0).
+    pub similarity: f64,
+    /// Term overlap of the two texts (0.0 to 1.0).
+    pub overlap: f64,
+    /// Conflict confidence combining similarity and overlap (0.0 to 1.0).
+    pub confidence: f64,
+    /// Optional user-provided metadata (JSON string).
+    pub metadata: Option<String>,
+    /// Creation timestamp in RFC3339 format.
+    pub created_at: String,
+    /// Last update timestamp in RFC3339 format.
+    pub updated_at: String,
+}
+
+/// Response for a completed import.
+#[derive(Serialize, JsonSchema)]
+pub struct ImportResponse {
+    /// Operation status ("imported").
+    pub status: String,
+    /// Records found in the source.
+    pub total: usize,
+    /// Records stored as new memories.
+    pub imported: usize,
+    /// Records skipped because a similar memory already exists.
+    pub skipped_duplicates: usize,
+    /// Records that replaced a similar memory (`--on-duplicate overwrite`).
+    pub overwritten: usize,
+    /// Records merged into a similar memory's metadata (`--on-duplicate merge`).
+    pub merged: usize,
+    /// Records stored next to a similar memory (`--on-duplicate keep-both`).
+    pub kept_duplicates: usize,
+    /// Records skipped because their content was invalid.
+    pub skipped_invalid: usize,
+    /// Records skipped because an interrupted earlier run already imported them.
+    pub skipped_resumed: usize,
+    /// Imported records stored with their exported embedding.
+    pub reused_embeddings: usize,
+}
+
+/// Response for an export written to a file.
+#[derive(Serialize)]
+pub struct ExportResponse {
+    /// Operation status ("exported").
+    pub status: String,
+    /// Memories written.
+    pub exported: usize,
+    /// Projects exported.
+    pub projects: usize,
+    /// File the memories were written to.
+    pub path: String,
+}
+
+/// Response for writing pinned memories into a context file.
+#[derive(Serialize)]
+pub struct ExportContextResponse {
+    /// Operation status ("exported", or "unchanged" if the file already
+    /// held the same section).
+    pub status: String,
+    /// Memories in the section.
+    pub memories: usize,
+    /// File the section was written to.
+    pub path: String,
+}
+
+/// Serialize a value as formatted JSON and print to stdout.
+///
+/// Exits with status 1 if serialization fails.
+pub fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("Failed to serialize JSON: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Serialize a value as single-line JSON and print to stdout.
+///
+/// Used for streaming output (one JSON document per line).
+/// Exits with status 1 if serialization fails.
+pub fn print_json_line<T: Serialize>(value: &T) {
+    match serde_json::to_string(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("Failed to serialize JSON: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Human, quiet and colored output of CLI results.
+
+use std::fmt::Display;
+use std::io::IsTerminal;
+
+use chrono::Utc;
+
+use super::{ErrorResponse, print_json};
+use crate::diff::{self, Change, ChangeKind};
+use crate::errors::Error;
+use crate::timeparse;
+
+/// ANSI styles used in human output.
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// Scores at or above this are shown in green, as likely matches.
+const STRONG_SCORE: f64 = 0.75;
+
+/// Scores at or above this (and below [`STRONG_SCORE`]) are shown in yellow.
+const FAIR_SCORE: f64 = 0.5;
+
+/// Output mode of a CLI run, from the global `--json`, `--quiet`,
+/// `--no-color` and `--relative-times` flags.
+///
+/// - JSON: responses are printed with [`print_json`]; `--quiet` and colors
+///   do not apply
+/// - Quiet: only the memory IDs a command produces (and data such as `get`
+///   content) are printed; status lines are suppressed and exit codes
+///   carry the outcome
+/// - Human: full text, with scores, conflicts and errors colored when the
+///   stream is a terminal and neither `--no-color` nor `NO_COLOR` is set,
+///   and timestamps shown as relative times ("3 days ago") with
+///   `--relative-times`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Printer {
+    json: bool,
+    quiet: bool,
+    color: bool,
+    color_stderr: bool,
+    relative_times: bool,
+}
+
+impl Printer {
+    /// Printer for the given flags, detecting whether stdout and stderr are
+    /// terminals.
+    pub fn new(json: bool, quiet: bool, no_color: bool) -> Self {
+        let no_color = no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self {
+            json,
+            quiet: quiet && !json,
+            color: !no_color && !json && std::io::stdout().is_terminal(),
+            color_stderr: !no_color && std::io::stderr().is_terminal(),
+            relative_times: false,
+        }
+    }
+
+    /// Show timestamps in human output relative to now.
+    pub fn with_relative_times(mut self, relative_times: bool) -> Self {
+        self.relative_times = relative_times && !self.json;
+        self
+    }
+
+    /// Whether responses are printed as JSON.
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Whether human output is limited to IDs and requested data.
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Print a status line, unless quiet.
+    pub fn status(&self, text: impl Display) {
+        if !self.quiet {
+            println!("{}", text);
+        }
+    }
+
+    /// Print a line about one memory, or only `id` when quiet.
+    pub fn result(&self, id: &str, text: impl Display) {
+        if self.quiet {
+            println!("{}", id);
+        } else {
+            println!("{}", text);
+        }
+    }
+
+    /// `score` with `precision` decimals, colored by strength.
+    pub fn score(&self, score: f64, precision: usize) -> String {
+        let style = if score >= STRONG_SCORE {
+            GREEN
+        } else if score >= FAIR_SCORE {
+            YELLOW
+        } else {
+            RED
+        };
+        self.paint(self.color, style, &format!("{:.*}", precision, score))
+    }
+
+    /// RFC3339 `timestamp` for human output: as is, or relative to now
+    /// ("3 days ago") with `--relative-times`.
+    pub fn time(&self, timestamp: &str) -> String {
+        if self.relative_times {
+            timeparse::humanize_rfc3339(timestamp, Utc::now())
+        } else {
+            timestamp.to_string()
+        }
+    }
+
+    /// `text` highlighted as a warning (e.g. a conflict).
+    pub fn warning(&self, text: &str) -> String {
+        self.paint(self.color, YELLOW, text)
+    }
+
+    /// Word diff in `[-deleted-]` / `{+inserted+}` form (see
+    /// [`crate::diff::render_words`]), deletions red and insertions green.
+    pub fn word_diff(&self, changes: &[Change]) -> String {
+        if !self.color {
+            return diff::render_words(changes);
+        }
+        changes
+            .iter()
+            .map(|change| match change.kind {
+                ChangeKind::Equal => change.text.clone(),
+                ChangeKind::Delete => self.paint(self.color, RED, &format!("[-{}-]", change.text)),
+                ChangeKind::Insert => {
+                    self.paint(self.color, GREEN, &format!("{{+{}+}}", change.text))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Print `error` to stderr, or as a JSON [`ErrorResponse`] on stdout.
+    pub fn error(&self, error: &Error) {
+        if self.json {
+            print_json(&ErrorResponse {
+                error: error.to_string(),
+            });
+        } else {
+            eprintln!(
+                "{} {}",
+                self.paint(self.color_stderr, BOLD_RED, "Error:"),
+                error
+            );
+        }
+    }
+
+    fn paint(&self, enabled: bool, style: &str, text: &str) -> String {
+        if enabled {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_printer_modes() {
+        let plain = Printer::new(false, false, true);
+        assert!(!plain.json() && !plain.quiet());
+        assert_eq!(plain.score(0.876, 2), "0.88");
+        assert_eq!(plain.warning("Conflicts"), "Conflicts");
+
+        // --json wins over --quiet
+        assert!(!Printer::new(true, true, false).quiet());
+        assert!(Printer::new(false, true, false).quiet());
+
+        let colored = Printer {
+            color: true,
+            ..Printer::default()
+        };
+        assert_eq!(colored.score(0.9, 1), "\x1b[32m0.9\x1b[0m");
+        assert_eq!(colored.score(0.6, 1), "\x1b[33m0.6\x1b[0m");
+        assert_eq!(colored.score(0.1, 1), "\x1b[31m0.1\x1b[0m");
+
+        let timestamp = "2000-01-01T00:00:00+00:00";
+        assert_eq!(plain.time(timestamp), timestamp);
+        assert!(
+            plain
+                .with_relative_times(true)
+                .time(timestamp)
+                .ends_with("years ago")
+        );
+        // JSON keeps timestamps as they are
+        let json = Printer::new(true, false, true).with_relative_times(true);
+        assert_eq!(json.time(timestamp), timestamp);
+    }
+}
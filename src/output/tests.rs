@@ -0,0 +1,85 @@
+//! Tests for the JSON responses.
+
+use super::*;
+
+#[test]
+fn test_serialize_add_response() {
+    let response = AddResponse {
+        status: "added".to_string(),
+        id: "test-id".to_string(),
+        seq: 42,
+    };
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(json.contains("\"status\":\"added\""));
+    assert!(json.contains("\"id\":\"test-id\""));
+    assert!(json.contains("\"seq\":42"));
+}
+
+#[test]
+fn test_serialize_search_response() {
+    let response = SearchResponse {
+        results: vec![SearchResultItem {
+            id: "test-id".to_string(),
+            project_id: "proj".to_string(),
+            content: "test content".to_string(),
+            similarity: 0.95,
+            kind: MemoryKind::Other,
+            metadata: Some(r#"{"tags":["db"]}"#.to_string()),
+            tags: vec!["db".to_string()],
+            snippet: None,
+            explanation: None,
+            token_count: Some(7),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-02T00:00:00Z".to_string(),
+        }],
+    };
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(json.contains("\"results\""));
+    assert!(json.contains("\"similarity\":0.95"));
+    assert!(json.contains("\"token_count\":7"));
+    assert!(json.contains("\"project_id\":\"proj\""));
+    assert!(json.contains("\"tags\":[\"db\"]"));
+    assert!(json.contains("\"updated_at\":\"2024-01-02T00:00:00Z\""));
+    assert!(!json.contains("snippet"));
+}
+
+#[test]
+fn test_serialize_conflicts_response() {
+    let response = ConflictsResponse {
+        status: "conflicts".to_string(),
+        proposed: "new".to_string(),
+        conflicts: vec![ConflictMemoryResponse {
+            id: "test-id".to_string(),
+            content: "old".to_string(),
+            similarity: 0.9,
+            overlap: 0.5,
+            confidence: 0.9,
+            metadata: Some(r#"{"source":"notes"}"#.to_string()),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-02-01T00:00:00Z".to_string(),
+        }],
+    };
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(json.contains("\"updated_at\":\"2024-02-01T00:00:00Z\""));
+    assert!(json.contains("\"created_at\":\"2024-01-01T00:00:00Z\""));
+    assert!(json.contains("\"metadata\":"));
+}
+
+#[test]
+fn test_serialize_import_response() {
+    let response = ImportResponse {
+        status: "imported".to_string(),
+        total: 3,
+        imported: 2,
+        skipped_duplicates: 1,
+        overwritten: 0,
+        merged: 0,
+        kept_duplicates: 0,
+        skipped_invalid: 0,
+        skipped_resumed: 0,
+        reused_embeddings: 0,
+    };
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(json.contains("\"imported\":2"));
+    assert!(json.contains("\"skipped_duplicates\":1"));
+}
@@ -0,0 +1,349 @@
+//! Project identifiers and their auto-detection from the git repository.
+
+use std::env;
+use std::fmt;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Package manifests that mark a package directory inside a monorepo.
+const PACKAGE_MANIFESTS: [&str; 2] = ["Cargo.toml", "package.json"];
+
+/// A normalized project identifier.
+///
+/// Project identifiers scope every memory, so two spellings of the same
+/// project would silently split its memories. Normalization:
+/// - trims surrounding whitespace,
+/// - reduces git remote URLs (`git@host:owner/repo`, `https://host/owner/repo`)
+///   to `owner/repo`,
+/// - strips trailing `/` and `.git`,
+/// - lowercases the identifier (git hosts treat owner and repository names
+///   case-insensitively, so `Owner/Repo` and `owner/repo` are one project).
+///
+/// # Example
+///
+/// ```
+/// use vipune::ProjectId;
+///
+/// let id = ProjectId::new(" git@github.com:Owner/Repo.git ")?;
+/// assert_eq!(id, "owner/repo");
+/// assert_eq!(ProjectId::new("owner/Repo/")?, id);
+/// assert!(ProjectId::new("   ").is_err());
+/// # Ok::<(), vipune::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProjectId(String);
+
+impl ProjectId {
+    /// Maximum length of a normalized identifier, in characters.
+    pub const MAX_LEN: usize = 256;
+
+    /// Normalize and validate `raw`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the normalized identifier is empty,
+    /// longer than [`ProjectId::MAX_LEN`] or contains control characters.
+    pub fn new(raw: &str) -> Result<Self, Error> {
+        let normalized = Self::normalize(raw);
+        if normalized.is_empty() {
+            return Err(Error::InvalidInput(
+                "Project ID cannot be empty".to_string(),
+            ));
+        }
+        if normalized.chars().count() > Self::MAX_LEN {
+            return Err(Error::InvalidInput(format!(
+                "Project ID too long (max {} characters)",
+                Self::MAX_LEN
+            )));
+        }
+        if normalized.chars().any(char::is_control) {
+            return Err(Error::InvalidInput(
+                "Project ID cannot contain control characters".to_string(),
+            ));
+        }
+        Ok(Self(normalized))
+    }
+
+    /// Apply the normalization rules without validating the result.
+    pub fn normalize(raw: &str) -> String {
+        let trimmed = raw.trim().trim_end_matches('/');
+        let reduced = if trimmed.starts_with("git@") || trimmed.contains("://") {
+            parse_git_remote(trimmed)
+        } else {
+            trimmed.to_string()
+        };
+        let mut id = reduced.as_str();
+        loop {
+            let stripped = id.trim_end_matches('/').trim_end_matches(".git");
+            if stripped == id {
+                break;
+            }
+            id = stripped;
+        }
+        id.trim().to_lowercase()
+    }
+
+    /// The identifier as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The identifier as an owned string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for ProjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Deref for ProjectId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ProjectId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for ProjectId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl PartialEq<str> for ProjectId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for ProjectId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+/// How a project is identified inside a repository holding several packages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MonorepoMode {
+    /// The whole repository is one project.
+    #[default]
+    Root,
+    /// Each package is its own project: the path of the nearest directory
+    /// with a `Cargo.toml` or `package.json`, relative to the repository
+    /// root, is appended to the repository's ID (`owner/repo/crates/core`).
+    Subdir,
+}
+
+/// Detect project identifier from environment.
+///
+/// Detection priority (checked in order):
+/// 1. Explicit override parameter (if provided and non-whitespace)
+/// 2. `VIPUNE_PROJECT` environment variable (if set and non-whitespace)
+/// 3. Git remote origin URL (parsed to owner/repo format)
+/// 4. Git repository root directory name
+/// 5. Current working directory name
+///
+/// Every candidate is normalized as a [`ProjectId`]; candidates that are not
+/// valid identifiers are skipped. Falls back to "unknown" if all detection
+/// methods fail.
+///
+/// # Arguments
+/// * `explicit` - Optional explicit project identifier that overrides all other detection methods.
+///   If provided but empty/whitespace, falls back to automatic detection.
+///
+/// # Returns
+/// A normalized project identifier
+///
+/// # Example
+/// ```no_run
+/// use vipune::project::detect_project;
+///
+/// // Use explicit override
+/// let project = detect_project(Some("my-project"));
+/// assert_eq!(project, "my-project");
+///
+/// // Auto-detect from git
+/// let project = detect_project(None);
+/// println!("Detected project: {}", project);
+/// ```
+#[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+pub fn detect_project(explicit: Option<&str>) -> ProjectId {
+    detect_project_with(explicit, MonorepoMode::Root)
+}
+
+/// Detect project identifier from environment, scoping it to the current
+/// package under [`MonorepoMode::Subdir`].
+///
+/// Follows the same priority as [`detect_project`]. An explicit or
+/// `VIPUNE_PROJECT` identifier is used as is; in `Subdir` mode an identifier
+/// detected from git gets the package path appended when the current
+/// directory is inside a package below the repository root.
+pub fn detect_project_with(explicit: Option<&str>, mode: MonorepoMode) -> ProjectId {
+    // 1. Explicit override takes priority (must be non-empty)
+    if let Some(project) = explicit.and_then(valid_candidate) {
+        return project;
+    }
+
+    // 2. Check environment variable
+    if let Some(project) = env::var("VIPUNE_PROJECT")
+        .ok()
+        .as_deref()
+        .and_then(valid_candidate)
+    {
+        return project;
+    }
+
+    let root = find_git_root();
+    let subdir = match (mode, &root) {
+        (MonorepoMode::Subdir, Some(root)) => env::current_dir()
+            .ok()
+            .and_then(|cwd| package_subdir(&cwd, root)),
+        _ => None,
+    };
+    let scoped = |base: String| match &subdir {
+        Some(subdir) => format!("{base}/{subdir}"),
+        None => base,
+    };
+
+    // 3. Try git remote origin
+    if let Some(remote) = get_git_remote_origin() {
+        if let Some(project) = valid_candidate(&scoped(parse_git_remote(&remote))) {
+            return project;
+        }
+    }
+
+    // 4. Try git root directory name
+    if let Some(root) = &root {
+        if let Some(project) = root
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| valid_candidate(&scoped(name.to_string())))
+        {
+            return project;
+        }
+    }
+
+    // 5. Fallback to current directory name
+    env::current_dir()
+        .ok()
+        .and_then(|p| {
+            p.file_name()
+                .and_then(|n| valid_candidate(&n.to_string_lossy()))
+        })
+        .unwrap_or_else(|| ProjectId("unknown".to_string()))
+}
+
+/// `candidate` as a project ID, or `None` (with a warning unless it is
+/// blank) if it is not a valid one.
+fn valid_candidate(candidate: &str) -> Option<ProjectId> {
+    if candidate.trim().is_empty() {
+        return None;
+    }
+    match ProjectId::new(candidate) {
+        Ok(project) => Some(project),
+        Err(e) => {
+            log::warn!("Ignoring project '{}': {}", candidate, e);
+            None
+        }
+    }
+}
+
+/// Path, relative to `root`, of the nearest directory at or above `start`
+/// holding a package manifest.
+///
+/// Returns `None` if that directory is `root` itself, if no directory below
+/// `root` has a manifest, or if `start` is not inside `root`.
+fn package_subdir(start: &Path, root: &Path) -> Option<String> {
+    let start = start.canonicalize().ok()?;
+    let root = root.canonicalize().ok()?;
+    let package = start
+        .ancestors()
+        .take_while(|dir| dir.starts_with(&root) && *dir != root)
+        .find(|dir| PACKAGE_MANIFESTS.iter().any(|m| dir.join(m).is_file()))?;
+    let relative = package.strip_prefix(&root).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    Some(parts.join("/"))
+}
+
+/// Get git remote origin URL
+fn get_git_remote_origin() -> Option<String> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !url.is_empty() {
+            return Some(url);
+        }
+    }
+    None
+}
+
+/// Find git repository root
+pub(crate) fn find_git_root() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        let path_str = String::from_utf8_lossy(&output.stdout);
+        let path = path_str.trim();
+        if !path.is_empty() {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Parse git remote URL to owner/repo format
+fn parse_git_remote(url: &str) -> String {
+    let url = url.trim().trim_end_matches(".git");
+
+    // SSH format: git@github.com:owner/repo
+    if let Some(rest) = url.strip_prefix("git@") {
+        if let Some(colon_pos) = rest.find(':') {
+            return rest[colon_pos + 1..].to_string();
+        }
+    }
+
+    // HTTPS format: https://github.com/owner/repo
+    if let Some(rest) = url.split("://").nth(1) {
+        let parts: Vec<&str> = rest.split('/').collect();
+        if parts.len() >= 3 {
+            return format!("{}/{}", parts[parts.len() - 2], parts[parts.len() - 1]);
+        }
+    }
+
+    // Fallback: return original URL
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests;
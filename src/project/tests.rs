@@ -0,0 +1,168 @@
+//! Tests for project ID detection and normalization.
+
+use super::*;
+
+#[test]
+fn test_parse_ssh_remote() {
+    assert_eq!(
+        parse_git_remote("git@github.com:owner/repo.git"),
+        "owner/repo"
+    );
+    assert_eq!(parse_git_remote("git@github.com:owner/repo"), "owner/repo");
+}
+
+#[test]
+fn test_parse_https_remote() {
+    assert_eq!(
+        parse_git_remote("https://github.com/owner/repo.git"),
+        "owner/repo"
+    );
+    assert_eq!(
+        parse_git_remote("https://github.com/owner/repo"),
+        "owner/repo"
+    );
+}
+
+#[test]
+fn test_parse_ssh_url_with_protocol() {
+    assert_eq!(
+        parse_git_remote("ssh://git@github.com/owner/repo.git"),
+        "owner/repo"
+    );
+}
+
+#[test]
+fn test_git_suffix_stripping() {
+    assert_eq!(parse_git_remote("owner/repo.git"), "owner/repo");
+}
+
+#[test]
+fn test_fallback_when_no_domain() {
+    assert_eq!(parse_git_remote("just-name"), "just-name");
+}
+
+#[test]
+fn test_package_subdir_in_nested_workspaces() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let root = dir.path();
+    let touch = |path: &str| {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    };
+    touch("Cargo.toml");
+    touch("crates/core/Cargo.toml");
+    touch("crates/core/src/lib.rs");
+    touch("web/package.json");
+    touch("web/packages/ui/package.json");
+    touch("web/packages/ui/src/index.ts");
+    touch("docs/guide.md");
+
+    assert_eq!(
+        package_subdir(&root.join("crates/core/src"), root).as_deref(),
+        Some("crates/core")
+    );
+    assert_eq!(
+        package_subdir(&root.join("web/packages/ui/src"), root).as_deref(),
+        Some("web/packages/ui")
+    );
+    assert_eq!(
+        package_subdir(&root.join("web"), root).as_deref(),
+        Some("web")
+    );
+    // Outside any package the workspace root's manifest applies
+    assert_eq!(package_subdir(&root.join("docs"), root), None);
+    assert_eq!(package_subdir(root, root), None);
+    // Not inside the repository at all
+    assert_eq!(
+        package_subdir(&std::env::temp_dir(), &root.join("web")),
+        None
+    );
+}
+
+#[test]
+fn test_monorepo_mode_parsing() {
+    #[derive(Deserialize)]
+    struct Wrapper {
+        mode: MonorepoMode,
+    }
+    let parsed: Wrapper = toml::from_str(r#"mode = "subdir""#).unwrap();
+    assert_eq!(parsed.mode, MonorepoMode::Subdir);
+    assert_eq!(MonorepoMode::default(), MonorepoMode::Root);
+}
+
+#[test]
+fn test_explicit_override_ignores_monorepo_mode() {
+    assert_eq!(
+        detect_project_with(Some("my-project"), MonorepoMode::Subdir),
+        "my-project"
+    );
+}
+
+#[test]
+fn test_explicit_override() {
+    assert_eq!(detect_project(Some("my-project")), "my-project");
+    assert_eq!(detect_project(Some(" Owner/Repo.git ")), "owner/repo");
+}
+
+#[test]
+fn test_project_id_normalization() {
+    for raw in [
+        "owner/repo",
+        "Owner/Repo",
+        "  owner/repo\n",
+        "owner/repo.git",
+        "owner/repo/",
+        "owner/repo.git/",
+        "git@github.com:Owner/repo.git",
+        "https://github.com/owner/Repo",
+        "ssh://git@GitHub.com/owner/repo.git",
+    ] {
+        assert_eq!(ProjectId::new(raw).unwrap(), "owner/repo", "{raw:?}");
+    }
+    assert_eq!(ProjectId::new("My Notes").unwrap().as_str(), "my notes");
+}
+
+#[test]
+fn test_project_id_validation() {
+    assert!(matches!(ProjectId::new(""), Err(Error::InvalidInput(_))));
+    assert!(ProjectId::new(" / ").is_err());
+    assert!(ProjectId::new(".git").is_err());
+    assert!(ProjectId::new("a\u{0}b").is_err());
+    assert!(ProjectId::new(&"x".repeat(ProjectId::MAX_LEN + 1)).is_err());
+    assert!(ProjectId::new(&"x".repeat(ProjectId::MAX_LEN)).is_ok());
+    assert_eq!("A/B".parse::<ProjectId>().unwrap().to_string(), "a/b");
+}
+
+#[test]
+fn test_explicit_override_empty() {
+    // Empty explicit string should fallback to other detection methods
+    let project = detect_project(Some(""));
+    assert!(!project.is_empty());
+}
+
+#[test]
+fn test_explicit_override_whitespace() {
+    // Whitespace-only explicit string should fallback to other detection methods
+    let project = detect_project(Some("   \t  "));
+    assert!(!project.is_empty());
+}
+
+#[test]
+fn test_detect_fallback_to_current_dir() {
+    let project = detect_project(None);
+    assert!(!project.is_empty());
+}
+
+#[test]
+fn test_env_var_whitespace() {
+    // This test runs in isolation, safe to set env var
+    unsafe {
+        std::env::set_var("VIPUNE_PROJECT", "   ");
+    }
+    let project = detect_project(None);
+    assert!(!project.is_empty()); // Should ignore whitespace and use fallback
+    unsafe {
+        std::env::remove_var("VIPUNE_PROJECT");
+    }
+}
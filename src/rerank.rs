@@ -0,0 +1,214 @@
+//! Cross-encoder reranking of search candidates (`rerank` feature).
+//!
+//! A cross-encoder reads the query and a candidate together and scores
+//! their relevance, which orders ambiguous queries much better than the
+//! cosine similarities and rank fusion of the first retrieval stage, at the
+//! cost of one model run per candidate. Searches with
+//! [`SearchOptions::rerank`](crate::SearchOptions::rerank) rerank their top
+//! candidates with the model configured in `[rerank]`.
+
+use std::sync::{Arc, Mutex};
+
+use ort::inputs;
+use ort::session::Session;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::value::Tensor;
+use tokenizers::{Tokenizer, TruncationParams};
+
+use crate::config::DownloadConfig;
+use crate::embedding::MAX_TOKENS;
+use crate::errors::Error;
+use crate::model_files::{self, ModelFiles};
+
+/// Query-document pairs scored in one model run.
+const BATCH_SIZE: usize = 16;
+
+/// Lazily loaded cross-encoder, shareable between stores.
+pub(crate) type SharedReranker = Arc<Mutex<Option<CrossEncoder>>>;
+
+/// ONNX cross-encoder scoring how relevant documents are to a query.
+///
+/// Expects a sequence-classification model (e.g. `BAAI/bge-reranker-base`)
+/// with one output logit per pair. Like [`EmbeddingEngine`](crate::EmbeddingEngine),
+/// scoring takes `&mut self` and is synchronous.
+pub struct CrossEncoder {
+    session: Session,
+    tokenizer: Tokenizer,
+    requires_token_type_ids: bool,
+    pad_id: i64,
+}
+
+impl CrossEncoder {
+    /// Load the model from the cache, downloading it first unless `offline`
+    /// (or `HF_HUB_OFFLINE`) is set; transient download errors are retried
+    /// as `retry` specifies.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ModelNotCached` in offline mode if the files have not
+    /// been downloaded, or `Error::ModelDownload` if a download failed.
+    pub fn with_download(
+        model_id: &str,
+        offline: bool,
+        retry: &DownloadConfig,
+    ) -> Result<Self, Error> {
+        let files = if offline || model_files::offline_from_env() {
+            model_files::cached_model_files(model_id)?
+        } else {
+            model_files::download_model_with(model_id, retry)?
+        };
+        Self::from_files(&files)
+    }
+
+    /// Load the model from already resolved local files.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tokenization` or `Error::Onnx` if the files cannot be
+    /// loaded.
+    pub fn from_files(files: &ModelFiles) -> Result<Self, Error> {
+        let mut tokenizer = Tokenizer::from_file(&files.tokenizer)?;
+        tokenizer
+            .with_padding(None)
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_TOKENS,
+                ..Default::default()
+            }))?;
+        let pad_id = ["<pad>", "[PAD]"]
+            .iter()
+            .find_map(|token| tokenizer.token_to_id(token))
+            .map_or(0, i64::from);
+
+        let session = Session::builder()?
+            .with_optimization_level(GraphOptimizationLevel::Level1)?
+            .commit_from_file(&files.model)?;
+        let requires_token_type_ids = session
+            .inputs()
+            .iter()
+            .any(|input| input.name() == "token_type_ids");
+
+        Ok(Self {
+            session,
+            tokenizer,
+            requires_token_type_ids,
+            pad_id,
+        })
+    }
+
+    /// Relevance of each of `documents` to `query`, from 0.0 to 1.0 (the
+    /// sigmoid of the model's logit), in the order of `documents`.
+    ///
+    /// Pairs longer than [`MAX_TOKENS`] are truncated, the longer of the two
+    /// texts first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tokenization` if encoding fails, or
+    /// `Error::Inference` if the model output is not one logit per pair.
+    pub fn score(&mut self, query: &str, documents: &[&str]) -> Result<Vec<f64>, Error> {
+        let mut scores = Vec::with_capacity(documents.len());
+        for batch in documents.chunks(BATCH_SIZE) {
+            scores.extend(self.score_batch(query, batch)?);
+        }
+        Ok(scores)
+    }
+
+    fn score_batch(&mut self, query: &str, documents: &[&str]) -> Result<Vec<f64>, Error> {
+        let encodings = documents
+            .iter()
+            .map(|document| self.tokenizer.encode((query, *document), true))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let batch_size = encodings.len();
+        let seq_len = encodings.iter().map(|e| e.len()).max().unwrap_or(0);
+        let mut input_ids: Vec<i64> = Vec::with_capacity(batch_size * seq_len);
+        let mut attention_mask: Vec<i64> = Vec::with_capacity(batch_size * seq_len);
+        let mut token_type_ids: Vec<i64> = Vec::with_capacity(batch_size * seq_len);
+        for encoding in &encodings {
+            let padding = seq_len - encoding.len();
+            input_ids.extend(encoding.get_ids().iter().map(|&id| i64::from(id)));
+            input_ids.extend(std::iter::repeat_n(self.pad_id, padding));
+            attention_mask.extend(encoding.get_attention_mask().iter().map(|&m| i64::from(m)));
+            attention_mask.extend(std::iter::repeat_n(0, padding));
+            token_type_ids.extend(encoding.get_type_ids().iter().map(|&t| i64::from(t)));
+            token_type_ids.extend(std::iter::repeat_n(0, padding));
+        }
+
+        let input_ids = Tensor::from_array(([batch_size, seq_len], input_ids))?;
+        let attention_mask = Tensor::from_array(([batch_size, seq_len], attention_mask))?;
+        let outputs = if self.requires_token_type_ids {
+            let token_type_ids = Tensor::from_array(([batch_size, seq_len], token_type_ids))?;
+            self.session.run(inputs![
+                "input_ids" => input_ids,
+                "attention_mask" => attention_mask,
+                "token_type_ids" => token_type_ids
+            ])?
+        } else {
+            self.session.run(inputs![
+                "input_ids" => input_ids,
+                "attention_mask" => attention_mask
+            ])?
+        };
+
+        let (_, logits) = outputs
+            .get("logits")
+            .ok_or_else(|| Error::Inference("Output tensor 'logits' not found".to_string()))?
+            .try_extract_tensor::<f32>()?;
+        relevance(logits, batch_size)
+    }
+}
+
+/// Sigmoid of the single logit of each of `batch_size` pairs.
+fn relevance(logits: &[f32], batch_size: usize) -> Result<Vec<f64>, Error> {
+    if logits.len() != batch_size {
+        return Err(Error::Inference(format!(
+            "Expected one logit per pair ({}), got {} (is the model a single-label cross-encoder?)",
+            batch_size,
+            logits.len()
+        )));
+    }
+    Ok(logits
+        .iter()
+        .map(|&logit| 1.0 / (1.0 + (-f64::from(logit)).exp()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relevance_is_sigmoid_of_logits() {
+        let scores = relevance(&[0.0, 10.0, -10.0], 3).unwrap();
+        assert_eq!(scores[0], 0.5);
+        assert!(scores[1] > 0.999);
+        assert!(scores[2] < 0.001);
+
+        // Multi-label heads are rejected
+        assert!(matches!(
+            relevance(&[0.1, 0.9], 1),
+            Err(Error::Inference(_))
+        ));
+    }
+
+    #[ignore]
+    #[test]
+    fn test_integration_cross_encoder_orders_by_relevance() {
+        // Full integration test with real model
+        // Requires: cargo test --features rerank -- --ignored
+        let config = crate::config::RerankConfig::default();
+        let mut encoder =
+            CrossEncoder::with_download(&config.model, false, &DownloadConfig::default()).unwrap();
+        let scores = encoder
+            .score(
+                "where does alice work",
+                &[
+                    "Alice likes hiking on weekends",
+                    "Alice works at Microsoft on Azure",
+                ],
+            )
+            .unwrap();
+        assert_eq!(scores.len(), 2);
+        assert!(scores[1] > scores[0]);
+    }
+}
@@ -0,0 +1,313 @@
+//! Reciprocal Rank Fusion (RRF) and score-based fusion for hybrid search
+//!
+//! RRF merges multiple ranked result lists without score normalization.
+//! Formula: score = Σ 1 / (k + rank) for each ranking list
+//!
+//! Documents appearing in both BM25 and semantic rankings get boosted scores.
+//! RRF scores only reflect ranks and are small (~0.02–0.08); the normalized
+//! [`FusionStrategy`] variants keep score magnitudes and map them onto a
+//! comparable scale instead.
+
+use crate::errors::Error;
+use crate::sqlite::Memory;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// RRF fusion configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RrfConfig {
+    /// The k parameter for RRF formula (default: 25.0)
+    /// Prevents division by very small numbers and gives diminishing returns for top ranks
+    pub k: f64,
+}
+
+impl Default for RrfConfig {
+    fn default() -> Self {
+        Self { k: 25.0 }
+    }
+}
+
+/// How hybrid search combines its semantic and BM25 result lists.
+///
+/// Score-based strategies read each result's `similarity` as its score in that
+/// list (higher is better). A result missing from a list scores 0.0 there under
+/// min-max normalization, and one standard deviation below the list's lowest
+/// result under z-score normalization.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FusionStrategy {
+    /// Reciprocal Rank Fusion: ranks only, scores are discarded.
+    Rrf(RrfConfig),
+    /// Mean of per-list min-max normalized scores, in [0.0, 1.0].
+    MinMax,
+    /// Mean of per-list z-scores (standard deviations from the list mean).
+    ZScore,
+    /// `semantic_weight * semantic + (1 - semantic_weight) * bm25` over
+    /// min-max normalized scores, in [0.0, 1.0].
+    Weighted {
+        /// Weight of the semantic score (0.0 to 1.0).
+        semantic_weight: f64,
+    },
+}
+
+impl Default for FusionStrategy {
+    fn default() -> Self {
+        Self::Rrf(RrfConfig::default())
+    }
+}
+
+impl FusionStrategy {
+    /// Check that the semantic weight is within [0.0, 1.0].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` if a `Weighted` weight is out of range.
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Self::Weighted { semantic_weight } = self {
+            if !(0.0..=1.0).contains(semantic_weight) {
+                return Err(Error::Validation(format!(
+                    "Invalid semantic weight: {} (must be between 0.0 and 1.0)",
+                    semantic_weight
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FusionStrategy {
+    type Err = Error;
+
+    /// Parse `rrf`, `minmax`, `zscore`, `weighted` (weight 0.5) or `weighted:<weight>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        let (name, weight) = match s.split_once(':') {
+            Some((name, weight)) => (name, Some(weight)),
+            None => (s.as_str(), None),
+        };
+        let strategy = match (name, weight) {
+            ("rrf", None) => Self::default(),
+            ("minmax" | "min-max", None) => Self::MinMax,
+            ("zscore" | "z-score", None) => Self::ZScore,
+            ("weighted", None) => Self::Weighted {
+                semantic_weight: 0.5,
+            },
+            ("weighted", Some(weight)) => Self::Weighted {
+                semantic_weight: weight.trim().parse().map_err(|_| {
+                    Error::InvalidInput(format!("Invalid semantic weight '{}'", weight))
+                })?,
+            },
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "Unknown fusion strategy '{}' (expected rrf, minmax, zscore or weighted[:W])",
+                    s
+                )));
+            }
+        };
+        strategy.validate()?;
+        Ok(strategy)
+    }
+}
+
+/// Fuses a semantic and a BM25 result list (in that order) with `strategy`.
+///
+/// The `similarity` field of each returned Memory holds the fused score.
+///
+/// # Errors
+///
+/// Returns `Error::Validation` if the strategy's parameters are out of range.
+pub fn fuse(
+    semantic: Vec<Memory>,
+    bm25: Vec<Memory>,
+    strategy: FusionStrategy,
+) -> Result<Vec<Memory>, Error> {
+    strategy.validate()?;
+    match strategy {
+        FusionStrategy::Rrf(config) => rrf_fusion(vec![semantic, bm25], Some(config)),
+        FusionStrategy::MinMax => Ok(score_fusion(
+            vec![semantic, bm25],
+            min_max_normalize,
+            &[0.5, 0.5],
+        )),
+        FusionStrategy::ZScore => Ok(score_fusion(
+            vec![semantic, bm25],
+            z_score_normalize,
+            &[0.5, 0.5],
+        )),
+        FusionStrategy::Weighted { semantic_weight } => Ok(score_fusion(
+            vec![semantic, bm25],
+            min_max_normalize,
+            &[semantic_weight, 1.0 - semantic_weight],
+        )),
+    }
+}
+
+/// Scale scores to [0.0, 1.0] (all-equal scores become 1.0); absent results score 0.0.
+fn min_max_normalize(scores: &[f64]) -> (Vec<f64>, f64) {
+    let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    let values = scores
+        .iter()
+        .map(|s| if range > 0.0 { (s - min) / range } else { 1.0 })
+        .collect();
+    (values, 0.0)
+}
+
+/// Standardize scores to zero mean and unit variance (all-equal scores become
+/// 0.0); absent results score one standard deviation below the lowest result.
+fn z_score_normalize(scores: &[f64]) -> (Vec<f64>, f64) {
+    if scores.is_empty() {
+        return (Vec::new(), 0.0);
+    }
+    let n = scores.len() as f64;
+    let mean = scores.iter().sum::<f64>() / n;
+    let std_dev = (scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+    let values: Vec<f64> = scores
+        .iter()
+        .map(|s| {
+            if std_dev > 0.0 {
+                (s - mean) / std_dev
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let absent = values.iter().copied().fold(f64::INFINITY, f64::min) - 1.0;
+    (values, absent)
+}
+
+/// Weighted sum of per-list normalized scores, sorted descending.
+///
+/// `normalize` returns the normalized scores of a list and the score given to
+/// results absent from it.
+fn score_fusion(
+    result_lists: Vec<Vec<Memory>>,
+    normalize: fn(&[f64]) -> (Vec<f64>, f64),
+    weights: &[f64],
+) -> Vec<Memory> {
+    let mut memories: HashMap<String, Memory> = HashMap::new();
+    let mut normalized: Vec<(HashMap<String, f64>, f64)> = Vec::new();
+
+    for list in result_lists {
+        let scores: Vec<f64> = list.iter().map(|m| m.similarity.unwrap_or(0.0)).collect();
+        let (values, absent) = normalize(&scores);
+        let mut by_id = HashMap::new();
+        for (memory, value) in list.into_iter().zip(values) {
+            by_id.insert(memory.id.clone(), value);
+            memories.entry(memory.id.clone()).or_insert(memory);
+        }
+        normalized.push((by_id, absent));
+    }
+
+    let mut fused: Vec<Memory> = memories
+        .into_values()
+        .map(|mut memory| {
+            let score = normalized
+                .iter()
+                .zip(weights)
+                .map(|((by_id, absent), weight)| {
+                    weight * by_id.get(&memory.id).copied().unwrap_or(*absent)
+                })
+                .sum();
+            memory.similarity = Some(score);
+            memory
+        })
+        .collect();
+    fused.sort_by(|a, b| {
+        b.similarity
+            .partial_cmp(&a.similarity)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    fused
+}
+
+/// Fuses multiple search result lists using Reciprocal Rank Fusion (RRF)
+///
+/// RRF combines rankings from different retrieval methods without requiring score normalization.
+/// Formula: score = Σ (1 / (k + rank)) for each result across all result sets
+///
+/// # Arguments
+///
+/// * `result_lists` - Vector of search result lists from different retrieval methods.
+///   Each list should be pre-sorted by relevance (best results first).
+/// * `config` - Optional RRF configuration. Uses default (k=25.0) if None.
+///
+/// # Returns
+///
+/// Fused and ranked list of unique search results, sorted by accumulated RRF score descending.
+/// The `similarity` field in each Memory contains the fused RRF score.
+///
+/// # Example
+///
+/// ```ignore
+/// // Semantic search results (sorted by cosine similarity)
+/// let semantic_results = vec![memory_a, memory_b, memory_c];
+///
+/// // BM25 search results (sorted by BM25 score)
+/// let bm25_results = vec![memory_b, memory_d, memory_e];
+///
+/// // Fuse with RRF
+/// let fused = rrf_fusion(vec![semantic_results, bm25_results], None)?;
+///
+/// // memory_b appears in both lists → gets highest RRF score
+/// assert_eq!(fused[0].id, memory_b.id);
+/// ```
+pub fn rrf_fusion(
+    result_lists: Vec<Vec<Memory>>,
+    config: Option<RrfConfig>,
+) -> Result<Vec<Memory>, Error> {
+    let config = config.unwrap_or_default();
+
+    if result_lists.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Map memory ID to accumulated Memory and RRF score
+    let mut fused_results: HashMap<String, (Memory, f64)> = HashMap::new();
+
+    // Process each result list
+    for result_list in result_lists {
+        for (rank, mut result) in result_list.into_iter().enumerate() {
+            let rank = rank + 1; // 1-based ranking for RRF formula
+            let rrf_score = 1.0f64 / (config.k + rank as f64);
+
+            // Additive scoring for duplicate documents across different retrieval methods
+            let id = result.id.clone();
+            match fused_results.get_mut(&id) {
+                Some((_, accumulated_score)) => {
+                    *accumulated_score += rrf_score;
+                }
+                None => {
+                    // Store RRF score in similarity field temporarily
+                    result.similarity = Some(rrf_score);
+                    fused_results.insert(id, (result, rrf_score));
+                }
+            }
+        }
+    }
+
+    // Convert to vector and sort by accumulated RRF score (higher is better)
+    let mut fused_vec: Vec<(Memory, f64)> = fused_results.into_values().collect();
+    fused_vec.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.id.cmp(&b.0.id))
+    });
+
+    // Extract Memory objects with final RRF scores
+    let final_results = fused_vec
+        .into_iter()
+        .map(|(mut result, score)| {
+            result.similarity = Some(score);
+            result
+        })
+        .collect();
+
+    Ok(final_results)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,369 @@
+//! Tests for rank and score fusion.
+
+use super::*;
+use crate::sqlite::{MemoryKind, Origin};
+
+fn create_test_memory(
+    id: &str,
+    content: &str,
+    project_id: &str,
+    similarity: Option<f64>,
+) -> Memory {
+    Memory {
+        id: id.to_string(),
+        project_id: project_id.to_string(),
+        content: content.to_string(),
+        metadata: None,
+        kind: MemoryKind::Other,
+        origin: Origin::default(),
+        title: None,
+        similarity,
+        snippet: None,
+        explanation: None,
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        updated_at: "2024-01-01T00:00:00Z".to_string(),
+    }
+}
+
+#[test]
+fn test_rrf_fusion_basic() {
+    // Simulate semantic search results
+    let semantic_results = vec![
+        create_test_memory("mem-1", "rust programming", "proj-a", Some(0.9)),
+        create_test_memory("mem-2", "python code", "proj-a", Some(0.7)),
+    ];
+
+    // Simulate BM25 results (mem-2 appears in both lists)
+    let bm25_results = vec![
+        create_test_memory("mem-2", "python code", "proj-a", Some(0.5)),
+        create_test_memory("mem-3", "database query", "proj-a", Some(0.3)),
+    ];
+
+    let fused = rrf_fusion(vec![semantic_results, bm25_results], None).unwrap();
+
+    assert_eq!(fused.len(), 3);
+
+    // mem-2 appears in both lists, should have highest RRF score
+    assert_eq!(fused[0].id, "mem-2");
+    assert!(fused[0].similarity.unwrap() > fused[1].similarity.unwrap());
+}
+
+#[test]
+fn test_rrf_fusion_empty_lists() {
+    let fused = rrf_fusion(vec![], None).unwrap();
+    assert!(fused.is_empty());
+}
+
+#[test]
+fn test_rrf_fusion_single_list() {
+    let results = vec![
+        create_test_memory("mem-1", "content 1", "proj-a", Some(0.9)),
+        create_test_memory("mem-2", "content 2", "proj-a", Some(0.8)),
+    ];
+
+    let fused = rrf_fusion(vec![results], None).unwrap();
+
+    assert_eq!(fused.len(), 2);
+    // With single list, RRF preserves order
+    assert_eq!(fused[0].id, "mem-1");
+}
+
+#[test]
+fn test_rrf_fusion_duplicate_documents() {
+    // Same memory appears in all 3 result lists at rank 1
+    let memory = create_test_memory("mem-1", "shared content", "proj-a", Some(0.8));
+
+    let list1 = vec![memory.clone()];
+    let list2 = vec![memory.clone()];
+    let list3 = vec![memory];
+
+    let default_config = RrfConfig::default();
+    let fused = rrf_fusion(vec![list1, list2, list3], None).unwrap();
+
+    assert_eq!(fused.len(), 1);
+
+    // Should have accumulated RRF score from all 3 lists at rank=1
+    // score = 3 * (1 / (k + 1))
+    let expected_score = 3.0 * (1.0 / (default_config.k + 1.0));
+    assert!((fused[0].similarity.unwrap() - expected_score).abs() < 0.001);
+}
+
+#[test]
+fn test_rrf_fusion_different_k_values() {
+    let configs = [RrfConfig { k: 10.0 }, RrfConfig { k: 100.0 }];
+
+    let semantic_results = vec![create_test_memory("mem-1", "doc1", "proj-a", Some(0.9))];
+    let bm25_results = vec![create_test_memory("mem-1", "doc1", "proj-a", Some(0.9))];
+
+    for &config in &[configs[0], configs[1]] {
+        let fused = rrf_fusion(
+            vec![semantic_results.clone(), bm25_results.clone()],
+            Some(config),
+        )
+        .unwrap();
+
+        // Memory appears at rank 1 in both lists
+        // score = 2 * (1 / (k + 1))
+        let expected_score = 2.0 * (1.0 / (config.k + 1.0));
+        assert!((fused[0].similarity.unwrap() - expected_score).abs() < 0.001);
+    }
+}
+
+#[test]
+fn test_rrf_fusion_ranking_priority() {
+    // RRF scoring verification:
+    // With k=25, rank 1&3 gives higher score than rank 2&2
+    // mem-1: 1/(25+1) + 1/(25+3) = 0.03846 + 0.03571 = 0.07417
+    // mem-2: 1/(25+1) + 1/(25+3) = 0.03846 + 0.03571 = 0.07417 (same ranks)
+    // mem-3: 1/(25+2) + 1/(25+2) = 0.03704 + 0.03704 = 0.07408 (slightly lower)
+
+    let semantic_results = vec![
+        create_test_memory("mem-1", "doc_a", "proj-a", Some(0.9)), // rank 1
+        create_test_memory("mem-3", "doc_c", "proj-a", Some(0.8)), // rank 2
+        create_test_memory("mem-2", "doc_b", "proj-a", Some(0.7)), // rank 3
+    ];
+
+    let bm25_results = vec![
+        create_test_memory("mem-2", "doc_b", "proj-a", Some(0.9)), // rank 1
+        create_test_memory("mem-3", "doc_c", "proj-a", Some(0.8)), // rank 2
+        create_test_memory("mem-1", "doc_a", "proj-a", Some(0.7)), // rank 3
+    ];
+
+    let fused = rrf_fusion(vec![semantic_results, bm25_results], None).unwrap();
+
+    assert_eq!(fused.len(), 3);
+
+    // mem-1 and mem-2 (rank 1&3, 3&1) should tie for highest RRF score
+    // Both beat mem-3 (rank 2&2)
+    assert_eq!(fused[0].similarity.unwrap(), fused[1].similarity.unwrap());
+    assert!(fused[2].similarity.unwrap() < fused[0].similarity.unwrap());
+}
+
+#[test]
+fn test_rrf_fusion_preserves_metadata() {
+    let memory = Memory {
+        id: "mem-1".to_string(),
+        project_id: "proj-a".to_string(),
+        content: "test content".to_string(),
+        metadata: Some("metadata".to_string()),
+        kind: MemoryKind::Decision,
+        origin: Origin::default(),
+        title: None,
+        similarity: Some(0.9),
+        snippet: None,
+        explanation: None,
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        updated_at: "2024-01-01T00:00:00Z".to_string(),
+    };
+
+    let fused = rrf_fusion(vec![vec![memory]], None).unwrap();
+
+    assert_eq!(fused.len(), 1);
+    assert_eq!(fused[0].metadata, Some("metadata".to_string()));
+    assert_eq!(fused[0].kind, MemoryKind::Decision);
+    assert_eq!(fused[0].project_id, "proj-a");
+}
+
+#[test]
+fn test_rrf_fusion_empty_lists_in_vector() {
+    let results = vec![create_test_memory("mem-1", "content", "proj-a", Some(0.9))];
+
+    // Empty list should not affect fusion
+    let fused = rrf_fusion(vec![vec![], results.clone()], None).unwrap();
+
+    assert_eq!(fused.len(), 1);
+    assert_eq!(fused[0].id, "mem-1");
+}
+
+#[test]
+fn test_rrf_fusion_many_results() {
+    let list1: Vec<Memory> = (1..=10)
+        .map(|i| {
+            create_test_memory(
+                &format!("mem-{}", i),
+                &format!("content {}", i),
+                "proj-a",
+                None,
+            )
+        })
+        .collect();
+
+    let list2: Vec<Memory> = (5..=15)
+        .map(|i| {
+            create_test_memory(
+                &format!("mem-{}", i),
+                &format!("content {}", i),
+                "proj-a",
+                None,
+            )
+        })
+        .collect();
+
+    let fused = rrf_fusion(vec![list1, list2], None).unwrap();
+
+    // mem-5 through mem-10 appear in both lists → should have highest scores
+    assert_eq!(fused.len(), 15);
+    verify_top_results_are_overlap(&fused, 5, 10);
+}
+
+fn verify_top_results_are_overlap(results: &[Memory], start: u32, end: u32) {
+    let mut overlap_count = 0;
+    for result in results.iter().take((end - start + 1) as usize) {
+        let id_num = result
+            .id
+            .strip_prefix("mem-")
+            .unwrap()
+            .parse::<u32>()
+            .unwrap();
+        if id_num >= start && id_num <= end {
+            overlap_count += 1;
+        }
+    }
+    assert!(
+        overlap_count >= 3,
+        "Expected at least 3 overlapping documents in top results, got {}",
+        overlap_count
+    );
+}
+
+/// Semantic and BM25 lists where RRF and score-based fusion disagree:
+/// mem-c tops BM25 but is third semantically, while mem-b is a close
+/// second in both lists.
+fn disagreeing_lists() -> (Vec<Memory>, Vec<Memory>) {
+    let semantic = vec![
+        create_test_memory("mem-a", "a", "proj-a", Some(0.95)),
+        create_test_memory("mem-b", "b", "proj-a", Some(0.60)),
+        create_test_memory("mem-c", "c", "proj-a", Some(0.35)),
+        create_test_memory("mem-d", "d", "proj-a", Some(0.30)),
+    ];
+    let bm25 = vec![
+        create_test_memory("mem-c", "c", "proj-a", Some(9.0)),
+        create_test_memory("mem-b", "b", "proj-a", Some(8.0)),
+        create_test_memory("mem-d", "d", "proj-a", Some(2.0)),
+        create_test_memory("mem-a", "a", "proj-a", Some(1.0)),
+    ];
+    (semantic, bm25)
+}
+
+fn ids(results: &[Memory]) -> Vec<&str> {
+    results.iter().map(|m| m.id.as_str()).collect()
+}
+
+#[test]
+fn test_fuse_orderings_by_strategy() {
+    let (semantic, bm25) = disagreeing_lists();
+
+    // RRF only sees ranks: mem-c (3 + 1) edges out mem-b (2 + 2)
+    let rrf = fuse(semantic.clone(), bm25.clone(), FusionStrategy::default()).unwrap();
+    assert_eq!(ids(&rrf), vec!["mem-c", "mem-b", "mem-a", "mem-d"]);
+    assert!(rrf[0].similarity.unwrap() < 0.1);
+
+    // Score-based fusion sees that mem-b is close to the top of both lists
+    let min_max = fuse(semantic.clone(), bm25.clone(), FusionStrategy::MinMax).unwrap();
+    assert_eq!(ids(&min_max), vec!["mem-b", "mem-c", "mem-a", "mem-d"]);
+    for memory in &min_max {
+        assert!((0.0..=1.0).contains(&memory.similarity.unwrap()));
+    }
+
+    let z_score = fuse(semantic.clone(), bm25.clone(), FusionStrategy::ZScore).unwrap();
+    assert_eq!(ids(&z_score), vec!["mem-b", "mem-a", "mem-c", "mem-d"]);
+
+    // A heavy semantic weight follows the semantic order
+    let weighted = fuse(
+        semantic,
+        bm25,
+        FusionStrategy::Weighted {
+            semantic_weight: 0.9,
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&weighted), vec!["mem-a", "mem-b", "mem-c", "mem-d"]);
+    assert!((weighted[0].similarity.unwrap() - 0.9).abs() < 1e-9);
+}
+
+#[test]
+fn test_fuse_handles_sparse_lists() {
+    let semantic = vec![
+        create_test_memory("mem-a", "a", "proj-a", Some(0.9)),
+        create_test_memory("mem-b", "b", "proj-a", Some(0.8)),
+        create_test_memory("mem-c", "c", "proj-a", Some(0.5)),
+    ];
+    let bm25 = vec![create_test_memory("mem-b", "b", "proj-a", Some(3.0))];
+
+    // A single BM25 hit counts in full; absent results get nothing
+    let fused = fuse(semantic.clone(), bm25.clone(), FusionStrategy::MinMax).unwrap();
+    assert_eq!(ids(&fused), vec!["mem-b", "mem-a", "mem-c"]);
+    assert!((fused[0].similarity.unwrap() - 0.875).abs() < 1e-9);
+
+    let fused = fuse(semantic.clone(), bm25, FusionStrategy::ZScore).unwrap();
+    assert_eq!(ids(&fused), vec!["mem-b", "mem-a", "mem-c"]);
+    assert!(fused.iter().all(|m| m.similarity.unwrap().is_finite()));
+
+    // An empty BM25 list leaves the semantic order intact
+    let fused = fuse(semantic, vec![], FusionStrategy::ZScore).unwrap();
+    assert_eq!(ids(&fused), vec!["mem-a", "mem-b", "mem-c"]);
+}
+
+#[test]
+fn test_fuse_rejects_invalid_weight() {
+    let strategy = FusionStrategy::Weighted {
+        semantic_weight: -0.5,
+    };
+    assert!(matches!(
+        fuse(vec![], vec![], strategy),
+        Err(Error::Validation(_))
+    ));
+}
+
+#[test]
+fn test_fusion_strategy_parse() {
+    assert_eq!(
+        "rrf".parse::<FusionStrategy>().unwrap(),
+        FusionStrategy::default()
+    );
+    assert_eq!(
+        "Min-Max".parse::<FusionStrategy>().unwrap(),
+        FusionStrategy::MinMax
+    );
+    assert_eq!(
+        "zscore".parse::<FusionStrategy>().unwrap(),
+        FusionStrategy::ZScore
+    );
+    assert_eq!(
+        "weighted".parse::<FusionStrategy>().unwrap(),
+        FusionStrategy::Weighted {
+            semantic_weight: 0.5
+        }
+    );
+    assert_eq!(
+        "weighted:0.25".parse::<FusionStrategy>().unwrap(),
+        FusionStrategy::Weighted {
+            semantic_weight: 0.25
+        }
+    );
+    assert!("weighted:2".parse::<FusionStrategy>().is_err());
+    assert!("weighted:x".parse::<FusionStrategy>().is_err());
+    assert!("rrf:3".parse::<FusionStrategy>().is_err());
+    assert!("mean".parse::<FusionStrategy>().is_err());
+}
+
+#[test]
+fn test_rrf_fusion_order_consistency() {
+    // Same input should produce same output order
+    let list1 = vec![
+        create_test_memory("mem-1", "a", "proj-a", None),
+        create_test_memory("mem-2", "b", "proj-a", None),
+    ];
+    let list2 = vec![
+        create_test_memory("mem-2", "b", "proj-a", None),
+        create_test_memory("mem-1", "a", "proj-a", None),
+    ];
+
+    let fused1 = rrf_fusion(vec![list1.clone(), list2.clone()], None).unwrap();
+    let fused2 = rrf_fusion(vec![list1, list2], None).unwrap();
+
+    assert_eq!(fused1.len(), fused2.len());
+    for (r1, r2) in fused1.iter().zip(fused2.iter()) {
+        assert_eq!(r1.id, r2.id);
+    }
+}
@@ -0,0 +1,207 @@
+//! Metadata filters and the combined structured filter of a search.
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::sqlite::{Memory, MemoryKind, Origin};
+
+/// How a [`MetadataFilter`] compares a metadata value with its own.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterOp {
+    /// `key=value`
+    #[default]
+    Eq,
+    /// `key<value`
+    Lt,
+    /// `key<=value`
+    Le,
+    /// `key>value`
+    Gt,
+    /// `key>=value`
+    Ge,
+}
+
+impl FilterOp {
+    /// Operator as written in a filter specification.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FilterOp::Eq => "=",
+            FilterOp::Lt => "<",
+            FilterOp::Le => "<=",
+            FilterOp::Gt => ">",
+            FilterOp::Ge => ">=",
+        }
+    }
+}
+
+/// Filter on a top-level metadata key.
+///
+/// With [`FilterOp::Eq`], string values match JSON strings exactly; numbers
+/// match by value (`"3"` matches `3` and `3.0`) and other JSON values
+/// (booleans, null) match the value parsed as JSON, e.g. `"true"`. The
+/// range operators only match numbers. An array matches if any of its
+/// elements does, so `tags=rust` matches `{"tags": ["rust", "db"]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataFilter {
+    /// Top-level metadata key.
+    pub key: String,
+    /// How the metadata value is compared with `value`.
+    #[serde(default)]
+    pub op: FilterOp,
+    /// Expected value, or the bound of a range comparison.
+    pub value: String,
+}
+
+impl MetadataFilter {
+    /// Parse a filter specification of the form `key=value`, or
+    /// `key<value`, `key<=value`, `key>value`, `key>=value` for a number.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` if the operator is missing, the key is
+    /// empty, or a range comparison has no numeric bound.
+    pub fn parse(spec: &str) -> Result<Self, Error> {
+        let at = spec.find(['=', '<', '>']).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Invalid filter '{}' (expected key=value, or key<value etc. for a number)",
+                spec
+            ))
+        })?;
+        let (key, rest) = spec.split_at(at);
+        let (op, value) = [
+            (FilterOp::Le, "<="),
+            (FilterOp::Ge, ">="),
+            (FilterOp::Lt, "<"),
+            (FilterOp::Gt, ">"),
+            (FilterOp::Eq, "="),
+        ]
+        .into_iter()
+        .find_map(|(op, token)| rest.strip_prefix(token).map(|value| (op, value)))
+        .expect("spec has an operator at the split point");
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Invalid filter '{}' (key must be non-empty)",
+                spec
+            )));
+        }
+        let value = value.trim();
+        if op != FilterOp::Eq && parse_number(value).is_none() {
+            return Err(Error::InvalidInput(format!(
+                "Invalid filter '{}' ({} needs a number)",
+                spec,
+                op.as_str()
+            )));
+        }
+        Ok(Self {
+            key: key.to_string(),
+            op,
+            value: value.to_string(),
+        })
+    }
+
+    fn matches(&self, metadata: &serde_json::Value) -> bool {
+        match metadata.get(&self.key) {
+            Some(serde_json::Value::Array(values)) => {
+                values.iter().any(|value| self.matches_value(value))
+            }
+            Some(value) => self.matches_value(value),
+            None => false,
+        }
+    }
+
+    fn matches_value(&self, value: &serde_json::Value) -> bool {
+        if let (serde_json::Value::Number(number), Some(bound)) = (value, parse_number(&self.value))
+        {
+            let number = number.as_f64().unwrap_or(f64::NAN);
+            return match self.op {
+                FilterOp::Eq => number == bound,
+                FilterOp::Lt => number < bound,
+                FilterOp::Le => number <= bound,
+                FilterOp::Gt => number > bound,
+                FilterOp::Ge => number >= bound,
+            };
+        }
+        if self.op != FilterOp::Eq {
+            return false;
+        }
+        match value {
+            serde_json::Value::String(s) => *s == self.value,
+            other => serde_json::from_str::<serde_json::Value>(&self.value)
+                .is_ok_and(|value| value == *other),
+        }
+    }
+}
+
+/// `value` as a finite number, if it is one.
+pub(crate) fn parse_number(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Whether a memory's metadata (JSON string) satisfies every filter.
+///
+/// Memories without metadata, or with invalid JSON, only match an empty filter list.
+pub(crate) fn metadata_matches(filters: &[MetadataFilter], metadata: Option<&str>) -> bool {
+    if filters.is_empty() {
+        return true;
+    }
+    match metadata.and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok()) {
+        Some(value) => filters.iter().all(|f| f.matches(&value)),
+        None => false,
+    }
+}
+
+/// The structured (non-semantic) restrictions of a search, combined.
+///
+/// Built by [`SearchOptions::search_filter`](super::SearchOptions::search_filter) and handed to the storage
+/// backend, which applies them before scoring: the SQLite backend turns
+/// them into SQL conditions, so a semantic search only decodes and scores
+/// the embeddings of rows that pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchFilter {
+    /// Metadata filters (including namespace and tags); all must match.
+    pub metadata: Vec<MetadataFilter>,
+    /// Only memories of this kind.
+    pub kind: Option<MemoryKind>,
+    /// Only memories of this origin (see [`Origin::matches`]).
+    pub origin: Option<Origin>,
+    /// Only memories last updated at or after this time (RFC3339).
+    pub since: Option<String>,
+    /// Only memories last updated at or before this time (RFC3339).
+    pub as_of: Option<String>,
+    /// Only memories this owner may see: their own and shared ones. Set by
+    /// the store from its configured `owner`.
+    pub viewer: Option<String>,
+    /// Skip memories marked inactive. Set by the store when
+    /// `inactive_after_days` is configured, unless the search includes them.
+    pub active_only: bool,
+}
+
+impl SearchFilter {
+    /// Whether the filter lets every memory through.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether `memory` passes the filter, for backends that filter in Rust.
+    ///
+    /// `viewer` and `active_only` are not checked: [`Memory`] doesn't carry
+    /// owner labels or the active flag.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn matches(&self, memory: &Memory) -> bool {
+        metadata_matches(&self.metadata, memory.metadata.as_deref())
+            && self.kind.is_none_or(|kind| memory.kind == kind)
+            && self
+                .origin
+                .as_ref()
+                .is_none_or(|origin| memory.origin.matches(origin))
+            && self
+                .since
+                .as_deref()
+                .is_none_or(|since| memory.updated_at.as_str() >= since)
+            && self
+                .as_of
+                .as_deref()
+                .is_none_or(|as_of| memory.updated_at.as_str() <= as_of)
+    }
+}
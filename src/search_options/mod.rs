@@ -0,0 +1,485 @@
+//! Options controlling how search results are scored, filtered and selected.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::rrf::FusionStrategy;
+use crate::sqlite::{MemoryKind, Origin};
+
+mod filter;
+
+pub use filter::{FilterOp, MetadataFilter, SearchFilter};
+pub(crate) use filter::{metadata_matches, parse_number};
+
+/// Default number of results returned by a search.
+pub const DEFAULT_SEARCH_LIMIT: usize = 5;
+
+/// Metadata key matched by [`SearchOptions::with_namespace`].
+pub const NAMESPACE_KEY: &str = "namespace";
+
+/// Metadata key matched by [`SearchOptions::with_tag`].
+pub const TAGS_KEY: &str = "tags";
+
+/// How candidates are retrieved and scored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchStrategy {
+    /// Cosine similarity between query and memory embeddings.
+    #[default]
+    Semantic,
+    /// Semantic and BM25 rankings fused with Reciprocal Rank Fusion.
+    Hybrid,
+}
+
+/// How keyword (BM25) search reads the words of a query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuerySyntax {
+    /// Every word matches literally; `*`, `:` and quotes are plain text.
+    #[default]
+    Plain,
+    /// Every word also matches the words it begins, so "auth" matches
+    /// "authentication".
+    Prefix,
+    /// A trailing `*` makes a word match by prefix (`auth*`), and a
+    /// `content:` or `metadata:` prefix limits it to the content or the
+    /// indexed metadata fields (`metadata:deploy`). Any other `*` or column
+    /// name is an error rather than passed on to FTS5.
+    Fts,
+}
+
+/// Final ordering of search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rank {
+    /// Order by relevance score (after recency weighting and exclusions).
+    #[default]
+    Score,
+    /// Boost memories that are returned often and were returned recently,
+    /// so what an agent actually uses surfaces first.
+    Frecency,
+}
+
+impl FromStr for Rank {
+    type Err = Error;
+
+    /// Parse `score` or `frecency`, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "score" => Ok(Self::Score),
+            "frecency" => Ok(Self::Frecency),
+            _ => Err(Error::InvalidInput(format!(
+                "Invalid rank '{}' (expected one of: score, frecency)",
+                s
+            ))),
+        }
+    }
+}
+
+/// BM25 weights of the two columns keyword search matches: the memory
+/// content and its indexed metadata fields
+/// ([`INDEXED_METADATA_FIELDS`](crate::INDEXED_METADATA_FIELDS)).
+///
+/// A word found in a short title or tag list says more about a memory than
+/// the same word somewhere in a long body, so metadata matches weigh double
+/// by default.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FieldWeights {
+    /// Weight of matches in the content.
+    pub content: f64,
+    /// Weight of matches in the indexed metadata fields.
+    pub metadata: f64,
+}
+
+impl Default for FieldWeights {
+    fn default() -> Self {
+        Self {
+            content: 1.0,
+            metadata: 2.0,
+        }
+    }
+}
+
+impl FieldWeights {
+    /// Check that both weights are finite and non-negative, and not both zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` describing the invalid weights.
+    pub fn validate(&self) -> Result<(), Error> {
+        for (name, weight) in [("content", self.content), ("metadata", self.metadata)] {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(Error::Validation(format!(
+                    "Invalid {} field weight: {} (must be a non-negative number)",
+                    name, weight
+                )));
+            }
+        }
+        if self.content == 0.0 && self.metadata == 0.0 {
+            return Err(Error::Validation(
+                "Field weights cannot both be 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Search parameters for [`MemoryStore::query`](crate::MemoryStore::query).
+///
+/// Built with chained `with_*` calls on top of the defaults (5 results,
+/// semantic strategy, score ranking, no recency weighting, no similarity
+/// cutoff, no filters).
+///
+/// # Example
+///
+/// ```
+/// use vipune::{SearchOptions, SearchStrategy};
+///
+/// let options = SearchOptions::default()
+///     .with_limit(10)
+///     .with_strategy(SearchStrategy::Hybrid)
+///     .with_filter("topic", "auth")
+///     .with_diversity(0.3)
+///     .with_explain(true);
+/// assert_eq!(options.limit, 10);
+/// assert!(options.validate().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    /// Maximum number of results to return.
+    pub limit: usize,
+    /// Weight for temporal decay (0.0 = pure relevance, 1.0 = max recency).
+    pub recency_weight: f64,
+    /// Drop candidates whose cosine similarity to the query is below this value.
+    ///
+    /// Applied to the raw semantic similarity before fusion and recency
+    /// weighting, so it means the same thing for semantic and hybrid search
+    /// (RRF scores are rank-based and not comparable to a similarity cutoff).
+    /// Hybrid BM25 hits are kept only if their embedding also passes.
+    pub min_similarity: Option<f64>,
+    /// Retrieval and scoring strategy.
+    pub strategy: SearchStrategy,
+    /// How hybrid search combines semantic and BM25 results (ignored for
+    /// semantic search).
+    pub fusion: FusionStrategy,
+    /// Metadata equality filters; all must match.
+    pub filters: Vec<MetadataFilter>,
+    /// Only return memories whose `namespace` metadata equals this value.
+    pub namespace: Option<String>,
+    /// Only return memories whose `tags` metadata holds every one of these.
+    pub tags: Vec<String>,
+    /// Only return memories of this kind.
+    pub kind: Option<MemoryKind>,
+    /// Only return memories of this origin (see [`Origin::matches`]).
+    pub origin: Option<Origin>,
+    /// Terms or concepts to steer away from.
+    ///
+    /// Keyword (BM25) matches containing every word of a term are dropped,
+    /// and each result's score is lowered by the fraction given by its
+    /// highest cosine similarity to a term, so memories about an excluded
+    /// concept sink.
+    pub exclude: Vec<String>,
+    /// Only consider memories last updated at or before this time.
+    ///
+    /// Replays what was known at that point: memories added later are
+    /// ignored, and so are memories edited later, since their earlier
+    /// versions keep no embedding to search.
+    pub as_of: Option<DateTime<Utc>>,
+    /// Only consider memories last updated at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Maximal Marginal Relevance trade-off (0.0 = relevance only, 1.0 = max diversity).
+    ///
+    /// Results too similar to already-selected ones are pushed down, so the
+    /// top-k is not filled with near-duplicates.
+    pub diversity: f64,
+    /// Attach a per-result score breakdown (`Memory::explanation`).
+    pub explain: bool,
+    /// Final ordering of results.
+    pub rank: Rank,
+    /// Average the query embedding with embeddings of the query rewritten
+    /// with each stored synonym of its words, so semantic search also finds
+    /// memories using the other names. Keyword search always matches
+    /// synonyms.
+    pub synonym_embeddings: bool,
+    /// BM25 weights of content and metadata matches in keyword (hybrid)
+    /// search.
+    pub field_weights: FieldWeights,
+    /// How keyword (hybrid) search reads the query; the query embedding is
+    /// computed from its words without the syntax.
+    pub query_syntax: QuerySyntax,
+    /// Also search memories marked inactive for being idle longer than
+    /// `inactive_after_days` (they are skipped by default).
+    pub include_inactive: bool,
+    /// Rerank the top candidates with the configured cross-encoder
+    /// (`[rerank]`), whose relevance replaces their scores. Needs the
+    /// `rerank` feature.
+    pub rerank: bool,
+    /// Rescore the top candidates by late interaction (MaxSim) with their
+    /// stored token embeddings (`[late_interaction]`), which replaces their
+    /// scores. Needs the `late-interaction` feature.
+    pub late_interaction: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_SEARCH_LIMIT,
+            recency_weight: 0.0,
+            min_similarity: None,
+            strategy: SearchStrategy::Semantic,
+            fusion: FusionStrategy::default(),
+            filters: Vec::new(),
+            namespace: None,
+            tags: Vec::new(),
+            kind: None,
+            origin: None,
+            exclude: Vec::new(),
+            as_of: None,
+            since: None,
+            diversity: 0.0,
+            explain: false,
+            rank: Rank::Score,
+            synonym_embeddings: false,
+            field_weights: FieldWeights::default(),
+            query_syntax: QuerySyntax::Plain,
+            include_inactive: false,
+            rerank: false,
+            late_interaction: false,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Return at most `limit` results.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Blend recency into the score with the given weight.
+    pub fn with_recency_weight(mut self, recency_weight: f64) -> Self {
+        self.recency_weight = recency_weight;
+        self
+    }
+
+    /// Only return memories with cosine similarity of at least `min_similarity`.
+    pub fn with_min_similarity(mut self, min_similarity: f64) -> Self {
+        self.min_similarity = Some(min_similarity);
+        self
+    }
+
+    /// Use the given retrieval strategy.
+    pub fn with_strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Combine hybrid results with the given fusion strategy.
+    pub fn with_fusion(mut self, fusion: FusionStrategy) -> Self {
+        self.fusion = fusion;
+        self
+    }
+
+    /// Require metadata `key` to equal `value` (repeatable; all must match).
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn with_filter(mut self, key: &str, value: &str) -> Self {
+        self.filters.push(MetadataFilter {
+            key: key.trim().to_string(),
+            op: FilterOp::Eq,
+            value: value.trim().to_string(),
+        });
+        self
+    }
+
+    /// Require metadata to pass `filter`, e.g. a range comparison parsed
+    /// with [`MetadataFilter::parse`] (repeatable; all must match).
+    pub fn with_metadata_filter(mut self, filter: MetadataFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Restrict results to memories whose `namespace` metadata equals `namespace`.
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.trim().to_string());
+        self
+    }
+
+    /// Restrict results to memories whose `tags` metadata array holds `tag`
+    /// (repeatable; every tag must be present).
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.trim().to_string());
+        self
+    }
+
+    /// Restrict results to memories of the given kind.
+    pub fn with_kind(mut self, kind: MemoryKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Restrict results to memories written through `origin`; an import
+    /// origin without a source matches every import.
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Exclude memories about `term` (repeatable).
+    pub fn with_exclude(mut self, term: &str) -> Self {
+        self.exclude.push(term.trim().to_string());
+        self
+    }
+
+    /// Search as of `as_of`, ignoring memories added or updated after it.
+    pub fn with_as_of(mut self, as_of: DateTime<Utc>) -> Self {
+        self.as_of = Some(as_of);
+        self
+    }
+
+    /// Ignore memories last updated before `since`.
+    pub fn with_since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Re-rank results with MMR using the given diversity.
+    pub fn with_diversity(mut self, diversity: f64) -> Self {
+        self.diversity = diversity;
+        self
+    }
+
+    /// Attach a score breakdown to each result.
+    pub fn with_explain(mut self, explain: bool) -> Self {
+        self.explain = explain;
+        self
+    }
+
+    /// Order results by the given ranking.
+    pub fn with_rank(mut self, rank: Rank) -> Self {
+        self.rank = rank;
+        self
+    }
+
+    /// Blend stored synonyms into the query embedding.
+    pub fn with_synonym_embeddings(mut self, synonym_embeddings: bool) -> Self {
+        self.synonym_embeddings = synonym_embeddings;
+        self
+    }
+
+    /// Also search memories marked inactive.
+    pub fn with_include_inactive(mut self, include_inactive: bool) -> Self {
+        self.include_inactive = include_inactive;
+        self
+    }
+
+    /// Rerank the top candidates with the configured cross-encoder.
+    pub fn with_rerank(mut self, rerank: bool) -> Self {
+        self.rerank = rerank;
+        self
+    }
+
+    /// Rescore the top candidates by late interaction with their token
+    /// embeddings.
+    pub fn with_late_interaction(mut self, late_interaction: bool) -> Self {
+        self.late_interaction = late_interaction;
+        self
+    }
+
+    /// Weigh keyword matches in the content and in indexed metadata fields
+    /// with the given BM25 weights.
+    pub fn with_field_weights(mut self, content: f64, metadata: f64) -> Self {
+        self.field_weights = FieldWeights { content, metadata };
+        self
+    }
+
+    /// Read the query of keyword (hybrid) search with `query_syntax`.
+    pub fn with_query_syntax(mut self, query_syntax: QuerySyntax) -> Self {
+        self.query_syntax = query_syntax;
+        self
+    }
+
+    /// The metadata (including namespace and tags), kind, origin and date
+    /// restrictions, combined for the storage backend.
+    pub fn search_filter(&self) -> SearchFilter {
+        let mut metadata = self.filters.clone();
+        if let Some(namespace) = &self.namespace {
+            metadata.push(MetadataFilter {
+                key: NAMESPACE_KEY.to_string(),
+                op: FilterOp::Eq,
+                value: namespace.clone(),
+            });
+        }
+        metadata.extend(self.tags.iter().map(|tag| MetadataFilter {
+            key: TAGS_KEY.to_string(),
+            op: FilterOp::Eq,
+            value: tag.clone(),
+        }));
+        SearchFilter {
+            metadata,
+            kind: self.kind,
+            origin: self.origin.clone(),
+            since: self.since.map(|t| t.to_rfc3339()),
+            as_of: self.as_of.map(|t| t.to_rfc3339()),
+            viewer: None,
+            active_only: false,
+        }
+    }
+
+    /// Check that weights and `min_similarity` are within [0.0, 1.0].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` if `recency_weight`, `diversity`,
+    /// `min_similarity`, the fusion weight or a field weight is out of range,
+    /// an exclusion term or tag is empty, `since` is after `as_of`, or a
+    /// query syntax is set for a search without keyword matching.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.query_syntax != QuerySyntax::Plain && self.strategy != SearchStrategy::Hybrid {
+            return Err(Error::Validation(
+                "Query syntax applies to keyword matching; use the hybrid strategy".to_string(),
+            ));
+        }
+        crate::temporal::validate_recency_weight(self.recency_weight).map_err(Error::Validation)?;
+        self.fusion.validate()?;
+        self.field_weights.validate()?;
+        if !(0.0..=1.0).contains(&self.diversity) {
+            return Err(Error::Validation(format!(
+                "Invalid diversity: {} (must be between 0.0 and 1.0)",
+                self.diversity
+            )));
+        }
+        if self.exclude.iter().any(|term| term.trim().is_empty()) {
+            return Err(Error::Validation(
+                "Exclusion terms must be non-empty".to_string(),
+            ));
+        }
+        if self.tags.iter().any(|tag| tag.is_empty()) {
+            return Err(Error::Validation("Tags must be non-empty".to_string()));
+        }
+        if let (Some(since), Some(as_of)) = (self.since, self.as_of) {
+            if since > as_of {
+                return Err(Error::Validation(format!(
+                    "Invalid date range: since {} is after as_of {}",
+                    since.to_rfc3339(),
+                    as_of.to_rfc3339()
+                )));
+            }
+        }
+        if let Some(min_similarity) = self.min_similarity {
+            if !(0.0..=1.0).contains(&min_similarity) {
+                return Err(Error::Validation(format!(
+                    "Invalid min_similarity: {} (must be between 0.0 and 1.0)",
+                    min_similarity
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
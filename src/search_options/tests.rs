@@ -0,0 +1,195 @@
+//! Tests for search option validation and filters.
+
+use super::*;
+
+#[test]
+fn test_defaults_disable_reranking() {
+    let options = SearchOptions::default();
+    assert_eq!(options.limit, DEFAULT_SEARCH_LIMIT);
+    assert_eq!(options.diversity, 0.0);
+    assert_eq!(options.strategy, SearchStrategy::Semantic);
+    assert_eq!(options.rank, Rank::Score);
+    assert!(options.search_filter().is_empty());
+    assert!(options.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_out_of_range_diversity() {
+    for diversity in [-0.1, 1.1, f64::NAN] {
+        let options = SearchOptions::default().with_diversity(diversity);
+        assert!(matches!(options.validate(), Err(Error::Validation(_))));
+    }
+}
+
+#[test]
+fn test_query_syntax_needs_hybrid_search() {
+    let options = SearchOptions::default().with_query_syntax(QuerySyntax::Prefix);
+    assert!(matches!(options.validate(), Err(Error::Validation(_))));
+    let options = options.with_strategy(SearchStrategy::Hybrid);
+    assert!(options.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_out_of_range_recency() {
+    let options = SearchOptions::default().with_recency_weight(2.0);
+    assert!(options.validate().is_err());
+}
+
+#[test]
+fn test_validate_min_similarity_range() {
+    for min_similarity in [-0.1, 1.5, f64::NAN] {
+        let options = SearchOptions::default().with_min_similarity(min_similarity);
+        assert!(matches!(options.validate(), Err(Error::Validation(_))));
+    }
+    for min_similarity in [0.0, 0.5, 1.0] {
+        let options = SearchOptions::default().with_min_similarity(min_similarity);
+        assert!(options.validate().is_ok());
+    }
+}
+
+#[test]
+fn test_validate_rejects_out_of_range_fusion_weight() {
+    let options = SearchOptions::default().with_fusion(FusionStrategy::Weighted {
+        semantic_weight: 1.5,
+    });
+    assert!(matches!(options.validate(), Err(Error::Validation(_))));
+}
+
+#[test]
+fn test_validate_rejects_empty_exclusion() {
+    let options = SearchOptions::default().with_exclude("postgres");
+    assert_eq!(options.exclude, vec!["postgres"]);
+    assert!(options.validate().is_ok());
+    let options = options.with_exclude("   ");
+    assert!(matches!(options.validate(), Err(Error::Validation(_))));
+}
+
+#[test]
+fn test_validate_field_weights() {
+    let options = SearchOptions::default().with_field_weights(1.0, 3.5);
+    assert_eq!(options.field_weights.metadata, 3.5);
+    assert!(options.validate().is_ok());
+    assert!(
+        SearchOptions::default()
+            .with_field_weights(0.0, 1.0)
+            .validate()
+            .is_ok()
+    );
+    for (content, metadata) in [(-1.0, 1.0), (1.0, f64::NAN), (0.0, 0.0)] {
+        let options = SearchOptions::default().with_field_weights(content, metadata);
+        assert!(matches!(options.validate(), Err(Error::Validation(_))));
+    }
+}
+
+#[test]
+fn test_parse_rank() {
+    assert_eq!("score".parse::<Rank>().unwrap(), Rank::Score);
+    assert_eq!(" Frecency ".parse::<Rank>().unwrap(), Rank::Frecency);
+    assert!(matches!(
+        "popular".parse::<Rank>(),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_namespace_and_tags_become_filters() {
+    let since = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let options = SearchOptions::default()
+        .with_filter("topic", "auth")
+        .with_namespace("docs")
+        .with_tag(" rust ")
+        .with_kind(MemoryKind::Decision)
+        .with_since(since);
+    let filter = options.search_filter();
+    let filters = &filter.metadata;
+    assert_eq!(filters.len(), 3);
+    assert_eq!(filters[1].key, NAMESPACE_KEY);
+    assert_eq!(filters[1].value, "docs");
+    assert_eq!(filters[2].key, TAGS_KEY);
+    assert_eq!(filters[2].value, "rust");
+    assert_eq!(filter.kind, Some(MemoryKind::Decision));
+    assert_eq!(filter.since.as_deref(), Some("2024-06-01T00:00:00+00:00"));
+    assert!(filter.as_of.is_none());
+    assert!(!filter.is_empty());
+    assert!(SearchOptions::default().search_filter().is_empty());
+
+    assert!(options.with_as_of(since).validate().is_ok());
+    let earlier = since - chrono::Duration::days(1);
+    let inverted = SearchOptions::default()
+        .with_since(since)
+        .with_as_of(earlier);
+    assert!(matches!(inverted.validate(), Err(Error::Validation(_))));
+    assert!(SearchOptions::default().with_tag(" ").validate().is_err());
+}
+
+#[test]
+fn test_metadata_matches() {
+    let filters = vec![
+        MetadataFilter::parse("topic=auth").unwrap(),
+        MetadataFilter::parse("priority=2").unwrap(),
+    ];
+    assert!(metadata_matches(
+        &filters,
+        Some(r#"{"topic": "auth", "priority": 2}"#)
+    ));
+    assert!(!metadata_matches(
+        &filters,
+        Some(r#"{"topic": "auth", "priority": 3}"#)
+    ));
+    assert!(!metadata_matches(&filters, Some(r#"{"topic": "auth"}"#)));
+    // Arrays match if any element does
+    assert!(metadata_matches(
+        &filters,
+        Some(r#"{"topic": ["db", "auth"], "priority": [1, 2]}"#)
+    ));
+    assert!(!metadata_matches(&filters, None));
+    assert!(!metadata_matches(&filters, Some("not json")));
+    assert!(metadata_matches(&[], None));
+}
+
+#[test]
+fn test_range_filters_compare_numbers() {
+    let at_least_two = [MetadataFilter::parse("importance>=2").unwrap()];
+    assert_eq!(at_least_two[0].op, FilterOp::Ge);
+    assert!(metadata_matches(
+        &at_least_two,
+        Some(r#"{"importance": 2}"#)
+    ));
+    assert!(metadata_matches(
+        &at_least_two,
+        Some(r#"{"importance": 4.5}"#)
+    ));
+    assert!(!metadata_matches(
+        &at_least_two,
+        Some(r#"{"importance": 1.5}"#)
+    ));
+    assert!(!metadata_matches(
+        &at_least_two,
+        Some(r#"{"importance": "3"}"#)
+    ));
+    let below_two = [MetadataFilter::parse("importance<2").unwrap()];
+    assert!(metadata_matches(
+        &below_two,
+        Some(r#"{"importance": [5, 1]}"#)
+    ));
+    // Equality on numbers compares values
+    let three = [MetadataFilter::parse("importance=3").unwrap()];
+    assert!(metadata_matches(&three, Some(r#"{"importance": 3.0}"#)));
+    assert!(!metadata_matches(&three, Some(r#"{"importance": true}"#)));
+
+    assert!(MetadataFilter::parse("importance>high").is_err());
+    let url = MetadataFilter::parse("url=a?b=c").unwrap();
+    assert_eq!((url.op, url.value.as_str()), (FilterOp::Eq, "a?b=c"));
+    assert_eq!(MetadataFilter::parse("n <= 1").unwrap().op, FilterOp::Le);
+}
+
+#[test]
+fn test_filter_parse_errors() {
+    assert!(MetadataFilter::parse("novalue").is_err());
+    assert!(MetadataFilter::parse("=value").is_err());
+    let filter = MetadataFilter::parse(" flag = true ").unwrap();
+    assert_eq!(filter.key, "flag");
+    assert_eq!(filter.value, "true");
+}
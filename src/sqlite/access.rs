@@ -0,0 +1,94 @@
+//! Access tracking: when and how often memories are returned to callers.
+//!
+//! Accesses are bookkeeping, not edits: they leave `updated_at` alone and
+//! are not written to the audit log.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use rusqlite::OptionalExtension;
+
+use super::{AccessStats, Database, Result};
+
+impl Database {
+    /// Record an access of each memory in `ids`: bump its `access_count` and
+    /// set `last_accessed_at` to now. Returns the number of memories updated
+    /// (unknown IDs are skipped).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the update fails.
+    pub fn record_access(&self, ids: &[&str]) -> Result<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let now = Utc::now().to_rfc3339();
+        let tx = self.write_transaction()?;
+        let mut updated = 0;
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE memories SET access_count = access_count + 1, last_accessed_at = ?2 WHERE id = ?1",
+            )?;
+            for id in ids {
+                updated += stmt.execute([id, &now.as_str()])?;
+            }
+        }
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// Access counts and last access times for the given IDs; unknown IDs are
+    /// absent from the map.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn access_stats(&self, ids: &[&str]) -> Result<HashMap<String, AccessStats>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT access_count, last_accessed_at FROM memories WHERE id = ?1")?;
+        let mut stats = HashMap::with_capacity(ids.len());
+        for id in ids {
+            let row = stmt
+                .query_row([id], |row| {
+                    Ok(AccessStats {
+                        access_count: row.get::<_, i64>(0)?.max(0) as u64,
+                        last_accessed_at: row.get(1)?,
+                    })
+                })
+                .optional()?;
+            if let Some(row) = row {
+                stats.insert(id.to_string(), row);
+            }
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_access_counts_without_editing() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+        let id = db.insert("proj", "alpha beta", &embedding, None).unwrap();
+        let before = db.get(&id).unwrap().unwrap();
+        assert_eq!(
+            db.access_stats(&[&id]).unwrap()[&id],
+            AccessStats::default()
+        );
+
+        assert_eq!(db.record_access(&[&id, "missing"]).unwrap(), 1);
+        assert_eq!(db.record_access(&[&id]).unwrap(), 1);
+        let stats = db.access_stats(&[&id, "missing"]).unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[&id].access_count, 2);
+        assert!(stats[&id].last_accessed_at_utc().unwrap().is_some());
+
+        assert_eq!(db.get(&id).unwrap().unwrap().updated_at, before.updated_at);
+        assert_eq!(db.audit_log("proj", None, 10).unwrap().len(), 1);
+        assert_eq!(db.search_bm25("alpha", "proj", 10).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,115 @@
+//! Active and inactive memories, so searches of large stores can skip ones
+//! nobody has touched for a long time.
+//!
+//! `vipune prune` marks memories idle since `inactive_after_days` inactive
+//! (`active = 0`), and searches filtered with
+//! [`SearchFilter::active_only`](crate::search_options::SearchFilter) skip
+//! them through the `(project_id, active)` index without reading their
+//! embeddings. Any update or access makes a memory active again.
+
+use super::{Database, Result};
+
+/// Reactivates a memory (and its chunks) when it is updated or accessed.
+pub(super) const ACTIVITY_SCHEMA: &str = r#"
+    CREATE INDEX IF NOT EXISTS idx_memories_active ON memories(project_id, active);
+
+    CREATE TRIGGER IF NOT EXISTS memories_reactivate
+    AFTER UPDATE OF updated_at, last_accessed_at ON memories
+    WHEN NEW.active = 0
+    BEGIN
+        UPDATE memories SET active = 1 WHERE id = NEW.id OR parent_id = NEW.id;
+    END;
+"#;
+
+impl Database {
+    /// Mark memories last updated or accessed before `cutoff` (RFC3339)
+    /// inactive, with their chunks. Pinned memories stay active. Returns
+    /// the number of memories (not chunks) marked.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the update fails.
+    pub fn deactivate_idle(&self, cutoff: &str) -> Result<usize> {
+        let tx = self.write_transaction()?;
+        let marked = tx.execute(
+            r#"
+            UPDATE memories SET active = 0
+            WHERE active = 1 AND parent_id IS NULL
+              AND MAX(julianday(updated_at), COALESCE(julianday(last_accessed_at), 0))
+                  < julianday(?1)
+              AND NOT (json_valid(metadata) AND json_extract(metadata, '$.pinned') IS 1)
+            "#,
+            [cutoff],
+        )?;
+        tx.execute(
+            r#"
+            UPDATE memories SET active = 0
+            WHERE active = 1 AND parent_id IN
+                (SELECT id FROM memories WHERE parent_id IS NULL AND active = 0)
+            "#,
+            [],
+        )?;
+        tx.commit()?;
+        Ok(marked)
+    }
+
+    /// Number of inactive memories (not chunks) in a project.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn count_inactive(&self, project_id: &str) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM memories
+             WHERE project_id = ?1 AND parent_id IS NULL AND active = 0",
+            [project_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_options::SearchFilter;
+
+    #[test]
+    fn test_idle_memories_are_skipped_until_touched() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+        let idle = db.insert("p", "idle", &embedding, None).unwrap();
+        let pinned = db
+            .insert("p", "pinned", &embedding, Some(r#"{"pinned": true}"#))
+            .unwrap();
+        db.insert("p", "recent", &embedding, None).unwrap();
+        db.conn
+            .execute(
+                "UPDATE memories SET updated_at = '2024-01-01T00:00:00+00:00'
+                 WHERE id IN (?1, ?2)",
+                [&idle, &pinned],
+            )
+            .unwrap();
+
+        assert_eq!(db.deactivate_idle("2025-01-01T00:00:00+00:00").unwrap(), 1);
+        assert_eq!(db.count_inactive("p").unwrap(), 1);
+        let active_only = SearchFilter {
+            active_only: true,
+            ..SearchFilter::default()
+        };
+        let found = |filter: &SearchFilter| {
+            db.search_filtered("p", &embedding, 10, filter)
+                .unwrap()
+                .len()
+        };
+        assert_eq!(found(&active_only), 2);
+        assert_eq!(found(&SearchFilter::default()), 3);
+
+        // An access brings it back
+        db.record_access(&[&idle]).unwrap();
+        assert_eq!(db.count_inactive("p").unwrap(), 0);
+        assert_eq!(found(&active_only), 3);
+        assert_eq!(db.deactivate_idle("2025-01-01T00:00:00+00:00").unwrap(), 0);
+    }
+}
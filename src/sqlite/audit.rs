@@ -0,0 +1,262 @@
+//! Write-ahead audit log of memory mutations.
+//!
+//! Every insert, update and delete on `memories` made through `Database`
+//! writes an `audit_log` row in the same transaction as the change itself,
+//! including bulk operations (quota eviction, retention pruning). The payload
+//! hash is the SHA-256 of the memory content and metadata, computed by the
+//! `vipune_sha256` SQL function registered on vipune's connection. No triggers
+//! are used, so external SQLite tools can still modify the database.
+
+use hmac_sha256::Hash;
+use rusqlite::Connection;
+use rusqlite::functions::FunctionFlags;
+use serde::Serialize;
+
+use super::{Database, Result};
+
+/// A single recorded mutation.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AuditEntry {
+    /// Monotonic sequence number.
+    pub seq: i64,
+    /// Time of the mutation in RFC3339 format (UTC).
+    pub timestamp: String,
+    /// Operation name: `add`, `update` or `delete`.
+    pub operation: String,
+    /// Project that owns the memory.
+    pub project_id: String,
+    /// Identifier of the mutated memory.
+    pub memory_id: String,
+    /// Hex SHA-256 of the memory content and metadata after the operation
+    /// (before it, for deletes).
+    pub payload_hash: String,
+}
+
+/// Schema for the audit table.
+pub(super) const AUDIT_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS audit_log (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp TEXT NOT NULL,
+        operation TEXT NOT NULL,
+        project_id TEXT NOT NULL,
+        memory_id TEXT NOT NULL,
+        payload_hash TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_audit_log_project ON audit_log(project_id, timestamp);
+"#;
+
+/// Audit operation recorded for inserts.
+pub(super) const OP_ADD: &str = "add";
+/// Audit operation recorded for content updates.
+pub(super) const OP_UPDATE: &str = "update";
+/// Audit operation recorded for deletes (including eviction and pruning).
+pub(super) const OP_DELETE: &str = "delete";
+
+/// Record `operation` for every memory matching `condition`.
+///
+/// Must run inside the mutation's transaction: after the write for adds and
+/// updates, before it for deletes.
+pub(super) fn record_where<P: rusqlite::Params>(
+    conn: &Connection,
+    operation: &'static str,
+    condition: &str,
+    params: P,
+) -> Result<usize> {
+    let sql = format!(
+        r#"
+        INSERT INTO audit_log (timestamp, operation, project_id, memory_id, payload_hash)
+        SELECT strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), '{operation}', project_id, id,
+               vipune_sha256(vipune_content(content, content_zstd), metadata)
+        FROM memories
+        WHERE {condition}
+        "#
+    );
+    Ok(conn.execute(&sql, params)?)
+}
+
+/// Register the `vipune_sha256(content, metadata)` SQL function, which
+/// [`record_where`] uses to hash the payload of each audited row.
+pub(super) fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "vipune_sha256",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let content: String = ctx.get(0)?;
+            let metadata: Option<String> = ctx.get(1)?;
+            Ok(payload_hash(&content, metadata.as_deref()))
+        },
+    )?;
+    Ok(())
+}
+
+/// Hex SHA-256 over content and metadata, separated by a NUL byte.
+pub fn payload_hash(content: &str, metadata: Option<&str>) -> String {
+    let mut hasher = Hash::new();
+    hasher.update(content.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(metadata.unwrap_or("").as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+impl Database {
+    /// Read audit entries for a project, oldest first.
+    ///
+    /// `since` is an RFC3339 timestamp; only entries at or after it are returned.
+    pub fn audit_log(
+        &self,
+        project_id: &str,
+        since: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>> {
+        super::search::validate_limit(limit)?;
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT seq, timestamp, operation, project_id, memory_id, payload_hash
+            FROM audit_log
+            WHERE project_id = ?1
+              AND (?2 IS NULL OR julianday(timestamp) >= julianday(?2))
+            ORDER BY seq ASC
+            LIMIT ?3
+            "#,
+        )?;
+        let entries = stmt
+            .query_map(rusqlite::params![project_id, since, limit as i64], |row| {
+                Ok(AuditEntry {
+                    seq: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    operation: row.get(2)?,
+                    project_id: row.get(3)?,
+                    memory_id: row.get(4)?,
+                    payload_hash: row.get(5)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Sequence number of the latest recorded mutation in any project (0 if
+    /// nothing was ever written).
+    ///
+    /// Read from the `AUTOINCREMENT` counter of `audit_log`, so it only grows,
+    /// across processes and connections, even once entries are removed. A
+    /// store whose sequence is at least the one returned after a write
+    /// includes that write.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn write_seq(&self) -> Result<i64> {
+        let seq = self.conn.query_row(
+            "SELECT coalesce((SELECT seq FROM sqlite_sequence WHERE name = 'audit_log'), 0)",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_db() -> Database {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let db = Database::open(&path).unwrap();
+        std::mem::forget(dir);
+        db
+    }
+
+    #[test]
+    fn test_payload_hash_is_stable_hex() {
+        let hash = payload_hash("content", None);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, payload_hash("content", Some("")));
+        assert_ne!(hash, payload_hash("content", Some("{}")));
+        assert_eq!(
+            payload_hash("", None),
+            // SHA-256 of a single NUL byte
+            "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d"
+        );
+    }
+
+    #[test]
+    fn test_mutations_are_recorded() {
+        let db = create_test_db();
+        let id = db
+            .insert("p", "first", &[0.1; 384], Some(r#"{"k":1}"#))
+            .unwrap();
+        db.update(&id, "second", &[0.2; 384]).unwrap();
+        db.delete(&id).unwrap();
+
+        let entries = db.audit_log("p", None, 10).unwrap();
+        let ops: Vec<&str> = entries.iter().map(|e| e.operation.as_str()).collect();
+        assert_eq!(ops, vec!["add", "update", "delete"]);
+        assert!(entries.iter().all(|e| e.memory_id == id));
+        assert_eq!(
+            entries[0].payload_hash,
+            payload_hash("first", Some(r#"{"k":1}"#))
+        );
+        assert_eq!(
+            entries[1].payload_hash,
+            payload_hash("second", Some(r#"{"k":1}"#))
+        );
+        assert!(chrono::DateTime::parse_from_rfc3339(&entries[0].timestamp).is_ok());
+    }
+
+    #[test]
+    fn test_write_seq_grows_with_mutations() {
+        let db = create_test_db();
+        assert_eq!(db.write_seq().unwrap(), 0);
+        let id = db.insert("p", "first", &[0.1; 384], None).unwrap();
+        let added = db.write_seq().unwrap();
+        assert!(added > 0);
+        db.update_metadata(&id, Some(r#"{"k":1}"#)).unwrap();
+        let updated = db.write_seq().unwrap();
+        assert!(updated > added);
+        db.delete(&id).unwrap();
+        assert!(db.write_seq().unwrap() > updated);
+    }
+
+    #[test]
+    fn test_bulk_deletes_are_recorded() {
+        let db = create_test_db();
+        db.insert("p", "a", &[0.1; 384], None).unwrap();
+        db.insert("p", "b", &[0.1; 384], None).unwrap();
+        db.evict_oldest(Some("p"), 2).unwrap();
+
+        let deletes = db
+            .audit_log("p", None, 10)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.operation == "delete")
+            .count();
+        assert_eq!(deletes, 2);
+    }
+
+    #[test]
+    fn test_audit_log_filters_by_project_and_since() {
+        let db = create_test_db();
+        db.insert("p", "a", &[0.1; 384], None).unwrap();
+        db.insert("other", "b", &[0.1; 384], None).unwrap();
+
+        assert_eq!(db.audit_log("p", None, 10).unwrap().len(), 1);
+        assert_eq!(
+            db.audit_log("p", Some("2000-01-01T00:00:00Z"), 10)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            db.audit_log("p", Some("2999-01-01T00:00:00Z"), 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! [`StorageBackend`] implementation for the SQLite database.
+
+use std::collections::HashMap;
+
+use super::{AccessStats, Database, Memory, MemoryKind, NewMemory, Origin};
+use crate::errors::Error;
+use crate::list_options::ListOptions;
+use crate::search_options::{FieldWeights, QuerySyntax, SearchFilter};
+use crate::storage::StorageBackend;
+
+impl StorageBackend for Database {
+    fn insert(&self, record: &NewMemory<'_>) -> Result<String, Error> {
+        Ok(self.insert_record(record)?)
+    }
+
+    fn get(&self, id: &str) -> Result<Option<Memory>, Error> {
+        Ok(Database::get(self, id)?)
+    }
+
+    fn list(
+        &self,
+        project_id: &str,
+        limit: usize,
+        kind: Option<MemoryKind>,
+        origin: Option<&Origin>,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>, Error> {
+        Ok(self.list_filtered(project_id, limit, kind, origin, viewer)?)
+    }
+
+    fn list_with(
+        &self,
+        project_id: &str,
+        options: &ListOptions,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>, Error> {
+        Ok(Database::list_with(self, project_id, options, viewer)?)
+    }
+
+    fn find_by_content(
+        &self,
+        project_id: &str,
+        content: &str,
+        ignore_case: bool,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>, Error> {
+        Ok(Database::find_by_content(
+            self,
+            project_id,
+            content,
+            ignore_case,
+            viewer,
+        )?)
+    }
+
+    fn update(&self, id: &str, content: &str, embedding: &[f32]) -> Result<(), Error> {
+        Ok(Database::update(self, id, content, embedding)?)
+    }
+
+    fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), Error> {
+        Ok(Database::update_metadata(self, id, metadata)?)
+    }
+
+    fn touch(&self, id: &str) -> Result<(), Error> {
+        Ok(Database::touch(self, id)?)
+    }
+
+    fn delete(&self, id: &str) -> Result<bool, Error> {
+        Ok(Database::delete(self, id)?)
+    }
+
+    fn search(
+        &self,
+        project_id: &str,
+        query_embedding: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Memory>, Error> {
+        Ok(self.search_filtered(project_id, query_embedding, limit, filter)?)
+    }
+
+    fn semantic_scan_size(
+        &self,
+        project_id: &str,
+        filter: &SearchFilter,
+    ) -> Result<Option<usize>, Error> {
+        Ok(Some(Database::semantic_scan_size(
+            self, project_id, filter,
+        )?))
+    }
+
+    fn embeddings_for(&self, ids: &[&str]) -> Result<HashMap<String, Vec<f32>>, Error> {
+        Ok(Database::embeddings_for(self, ids)?)
+    }
+
+    fn count_project(&self, project_id: &str) -> Result<usize, Error> {
+        Ok(Database::count_project(self, project_id)?)
+    }
+
+    fn search_keyword(
+        &self,
+        query: &str,
+        syntax: QuerySyntax,
+        project_id: &str,
+        limit: usize,
+        filter: &SearchFilter,
+        exclude: &[String],
+        field_weights: FieldWeights,
+    ) -> Result<Vec<Memory>, Error> {
+        let mut memories = self.search_bm25_with_syntax(
+            query,
+            syntax,
+            project_id,
+            limit,
+            filter,
+            exclude,
+            field_weights,
+        )?;
+        // FTS5 bm25() is lower-is-better; the trait reports higher-is-better
+        for memory in &mut memories {
+            memory.similarity = memory.similarity.map(|score| -score);
+        }
+        Ok(memories)
+    }
+
+    fn find_similar(
+        &self,
+        project_id: &str,
+        embedding: &[f32],
+        threshold: f64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Memory>, Error> {
+        Ok(Database::find_similar(
+            self, project_id, embedding, threshold, filter,
+        )?)
+    }
+
+    fn insert_chunks(
+        &self,
+        parent_id: &str,
+        chunks: &[(String, Vec<f32>)],
+    ) -> Result<usize, Error> {
+        Ok(Database::insert_chunks(self, parent_id, chunks)?)
+    }
+
+    fn delete_chunks(&self, parent_id: &str) -> Result<usize, Error> {
+        Ok(Database::delete_chunks(self, parent_id)?)
+    }
+
+    fn used_size_bytes(&self) -> Result<Option<u64>, Error> {
+        Ok(Some(Database::used_size_bytes(self)?))
+    }
+
+    fn evict_oldest(&self, project_id: Option<&str>, count: usize) -> Result<usize, Error> {
+        Ok(Database::evict_oldest(self, project_id, count)?)
+    }
+
+    fn evict_bytes(&self, project_id: &str, bytes: u64, limit: usize) -> Result<usize, Error> {
+        Ok(Database::evict_bytes(self, project_id, bytes, limit)?)
+    }
+
+    fn project_ids(&self) -> Result<Vec<String>, Error> {
+        Ok(Database::project_ids(self)?)
+    }
+
+    fn prune_project(&self, project_id: &str, cutoff: &str, archive: bool) -> Result<usize, Error> {
+        Ok(Database::prune_project(self, project_id, cutoff, archive)?)
+    }
+
+    fn trash(&self, id: &str) -> Result<bool, Error> {
+        Ok(Database::trash(self, id)?)
+    }
+
+    fn purge_trash(&self, cutoff: &str) -> Result<usize, Error> {
+        Ok(Database::purge_trash(self, cutoff)?)
+    }
+
+    fn deactivate_idle(&self, cutoff: &str) -> Result<usize, Error> {
+        Ok(Database::deactivate_idle(self, cutoff)?)
+    }
+
+    fn record_access(&self, ids: &[&str]) -> Result<(), Error> {
+        Database::record_access(self, ids)?;
+        Ok(())
+    }
+
+    fn access_stats(&self, ids: &[&str]) -> Result<HashMap<String, AccessStats>, Error> {
+        Ok(Database::access_stats(self, ids)?)
+    }
+
+    fn set_language(&self, id: &str, language: Option<&str>) -> Result<(), Error> {
+        Database::set_language(self, id, language)?;
+        Ok(())
+    }
+
+    fn set_title(&self, id: &str, title: Option<&str>) -> Result<(), Error> {
+        Database::set_title(self, id, title)?;
+        Ok(())
+    }
+
+    fn set_model_revision(&self, id: &str, revision: Option<&str>) -> Result<(), Error> {
+        Database::set_model_revision(self, id, revision)?;
+        Ok(())
+    }
+
+    fn set_token_embeddings(&self, id: &str, tokens: Option<&[Vec<f32>]>) -> Result<(), Error> {
+        Database::set_token_embeddings(self, id, tokens)?;
+        Ok(())
+    }
+
+    fn token_embeddings(&self, ids: &[&str]) -> Result<HashMap<String, Vec<Vec<f32>>>, Error> {
+        Ok(Database::token_embeddings(self, ids)?)
+    }
+
+    fn synonyms_for(
+        &self,
+        project_id: &str,
+        words: &[&str],
+    ) -> Result<HashMap<String, Vec<String>>, Error> {
+        Ok(Database::synonyms_for(self, project_id, words)?)
+    }
+}
@@ -0,0 +1,157 @@
+//! Write-optimized bulk sessions.
+//!
+//! [`Database::begin_bulk`] opens one `BEGIN IMMEDIATE` transaction that
+//! every following write joins and drops the FTS5 sync triggers. Inserts
+//! then skip the per-row full-text update and share a single fsync at the
+//! commit. [`Database::end_bulk`] restores the triggers, rebuilds the FTS5
+//! index in one pass and commits, or rolls everything back (trigger changes
+//! included, as DDL is transactional).
+//!
+//! `PRAGMA synchronous` is left as configured, so the session is as durable
+//! as any other transaction: if the process or the OS dies before the
+//! commit, the next open rolls the database back to where the session
+//! began.
+
+use super::{Database, Error, Result, fts};
+
+impl Database {
+    /// Start a bulk session: take the write lock and stop maintaining the
+    /// FTS5 index per row until [`Database::end_bulk`].
+    ///
+    /// Keyword search does not see rows written during the session until it
+    /// ends. B-tree indexes stay in place, as duplicate checks need them.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the lock cannot be taken or a transaction (or
+    /// another bulk session) is already open.
+    pub fn begin_bulk(&mut self) -> Result<()> {
+        if !self.conn.is_autocommit() {
+            return Err(Error::Sqlite(
+                "Cannot start a bulk session inside a transaction".to_string(),
+            ));
+        }
+        let started = self.conn.execute_batch("BEGIN IMMEDIATE").and_then(|()| {
+            if self.fts {
+                fts::suspend_sync(&self.conn)
+            } else {
+                Ok(())
+            }
+        });
+        if let Err(e) = started {
+            if !self.conn.is_autocommit() {
+                let _ = self.conn.execute_batch("ROLLBACK");
+            }
+            return Err(e.into());
+        }
+        self.bulk = true;
+        Ok(())
+    }
+
+    /// End the session started by [`Database::begin_bulk`]: with `commit`,
+    /// restore the FTS5 triggers, rebuild the index and commit; otherwise
+    /// roll back every write of the session.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no session is open, or if the rebuild or commit
+    /// fails (the session is then rolled back).
+    pub fn end_bulk(&mut self, commit: bool) -> Result<()> {
+        if !std::mem::take(&mut self.bulk) {
+            return Err(Error::Sqlite("No bulk session is open".to_string()));
+        }
+        if commit {
+            let committed = if self.fts {
+                fts::resume_sync(&self.conn)
+            } else {
+                Ok(())
+            }
+            .and_then(|()| self.conn.execute_batch("COMMIT"));
+            if committed.is_err() && !self.conn.is_autocommit() {
+                let _ = self.conn.execute_batch("ROLLBACK");
+            }
+            Ok(committed?)
+        } else {
+            Ok(self.conn.execute_batch("ROLLBACK")?)
+        }
+    }
+
+    /// Whether a bulk session is open.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn in_bulk(&self) -> bool {
+        self.bulk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synchronous(db: &Database) -> i64 {
+        db.conn
+            .query_row("PRAGMA synchronous", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn trigger_count(db: &Database) -> i64 {
+        db.conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='trigger' AND name LIKE 'memories_fts_%'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bulk_session_rebuilds_fts_on_commit() {
+        let mut db = Database::open_in_memory().unwrap();
+        let before = synchronous(&db);
+        let embedding = vec![0.1f32; 384];
+
+        db.begin_bulk().unwrap();
+        assert!(db.in_bulk());
+        assert_eq!(synchronous(&db), before);
+        assert_eq!(trigger_count(&db), 0);
+        db.insert("proj", "Alice works at Microsoft", &embedding, None)
+            .unwrap();
+        db.insert("proj", "Bob works at Google", &embedding, None)
+            .unwrap();
+        assert!(db.search_bm25("microsoft", "proj", 10).unwrap().is_empty());
+        db.end_bulk(true).unwrap();
+
+        assert!(!db.in_bulk());
+        assert_eq!(synchronous(&db), before);
+        assert_eq!(trigger_count(&db), 3);
+        assert_eq!(db.search_bm25("microsoft", "proj", 10).unwrap().len(), 1);
+        assert!(db.fts_integrity_ok().unwrap());
+
+        // Triggers maintain the index again after the session
+        db.insert("proj", "Carol works at Microsoft", &embedding, None)
+            .unwrap();
+        assert_eq!(db.search_bm25("microsoft", "proj", 10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_bulk_session_rollback_discards_writes() {
+        let mut db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1f32; 384];
+        db.begin_bulk().unwrap();
+        db.insert("proj", "Alice works at Microsoft", &embedding, None)
+            .unwrap();
+        db.end_bulk(false).unwrap();
+
+        assert!(db.list("proj", 10).unwrap().is_empty());
+        assert_eq!(trigger_count(&db), 3);
+        assert!(db.end_bulk(true).is_err());
+    }
+
+    #[test]
+    fn test_bulk_session_cannot_nest() {
+        let mut db = Database::open_in_memory().unwrap();
+        db.begin_bulk().unwrap();
+        assert!(db.begin_bulk().is_err());
+        assert!(db.in_bulk());
+        db.end_bulk(true).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+//! Chunk rows for long memories.
+//!
+//! A chunked memory is stored as a parent row holding the full content plus
+//! one row per chunk with `parent_id` pointing at the parent. Chunk rows share
+//! the parent's project, kind, origin and timestamps, are excluded from listing, BM25 search
+//! and quota counts, and are removed together with their parent.
+
+use uuid::Uuid;
+
+use super::{Database, Result};
+
+impl Database {
+    /// Store chunks of the memory `parent_id`, each with its own embedding.
+    ///
+    /// Chunks inherit project, kind, origin and timestamps from the parent. Returns the
+    /// number of chunks stored (0 if the parent does not exist).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if an embedding has invalid dimensions or the write fails.
+    pub fn insert_chunks(&self, parent_id: &str, chunks: &[(String, Vec<f32>)]) -> Result<usize> {
+        let tx = self.write_transaction()?;
+        let mut inserted = 0;
+        for (content, embedding) in chunks {
+            let blob = self.encode_embedding(embedding)?;
+            let (content, zstd) = self.encode_content(content)?;
+            inserted += tx.execute(
+                r#"
+                INSERT INTO memories
+                    (id, project_id, content, compressed, embedding, metadata, kind, origin,
+                     created_at, updated_at, parent_id, owner, visibility, content_zstd)
+                SELECT ?1, project_id, ?2, ?3, ?4, NULL, kind, origin, created_at, updated_at,
+                       id, owner, visibility, ?6
+                FROM memories
+                WHERE id = ?5
+                "#,
+                rusqlite::params![
+                    Uuid::new_v4().to_string(),
+                    content,
+                    zstd.is_some(),
+                    &blob,
+                    parent_id,
+                    zstd
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Delete all chunks of the memory `parent_id`.
+    ///
+    /// Returns the number of chunks deleted.
+    pub fn delete_chunks(&self, parent_id: &str) -> Result<usize> {
+        Ok(self
+            .conn
+            .execute("DELETE FROM memories WHERE parent_id = ?1", [parent_id])?)
+    }
+
+    /// Count the chunks stored for the memory `parent_id`.
+    #[allow(dead_code)] // Used in tests
+    pub(crate) fn count_chunks(&self, parent_id: &str) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM memories WHERE parent_id = ?1",
+            [parent_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::axis;
+
+    fn chunk(content: &str, value: f32) -> (String, Vec<f32>) {
+        (content.to_string(), vec![value; 384])
+    }
+
+    #[test]
+    fn test_chunks_are_hidden_from_list_and_counts() {
+        let db = Database::open_in_memory().unwrap();
+        let parent = db
+            .insert("proj", "long content", &vec![0.1; 384], None)
+            .unwrap();
+        let stored = db
+            .insert_chunks(&parent, &[chunk("long", 0.2), chunk("content", 0.3)])
+            .unwrap();
+
+        assert_eq!(stored, 2);
+        assert_eq!(db.count_chunks(&parent).unwrap(), 2);
+        assert_eq!(db.list("proj", 10).unwrap().len(), 1);
+        assert_eq!(db.count_project("proj").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_insert_chunks_for_missing_parent_is_noop() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.insert_chunks("missing", &[chunk("x", 0.1)]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_delete_removes_chunks() {
+        let db = Database::open_in_memory().unwrap();
+        let parent = db.insert("proj", "content", &vec![0.1; 384], None).unwrap();
+        db.insert_chunks(&parent, &[chunk("a", 0.2), chunk("b", 0.3)])
+            .unwrap();
+
+        assert!(db.delete(&parent).unwrap());
+        assert_eq!(db.count_chunks(&parent).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_search_returns_parent_with_best_chunk_snippet() {
+        let db = Database::open_in_memory().unwrap();
+        let parent = db
+            .insert("proj", "intro ... the tail", &axis(0), None)
+            .unwrap();
+        db.insert_chunks(
+            &parent,
+            &[
+                ("intro".to_string(), axis(0)),
+                ("the tail".to_string(), axis(1)),
+            ],
+        )
+        .unwrap();
+        let other = db.insert("proj", "unrelated", &axis(2), None).unwrap();
+
+        let results = db.search("proj", &axis(1), 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, parent);
+        assert_eq!(results[0].content, "intro ... the tail");
+        assert_eq!(results[0].snippet.as_deref(), Some("the tail"));
+        assert!(results[0].similarity.unwrap() > 0.99);
+        assert_eq!(results[1].id, other);
+        assert_eq!(results[1].snippet, None);
+    }
+
+    #[test]
+    fn test_search_keeps_parent_score_above_its_chunks() {
+        let db = Database::open_in_memory().unwrap();
+        let parent = db.insert("proj", "whole", &axis(0), None).unwrap();
+        db.insert_chunks(&parent, &[("part".to_string(), axis(1))])
+            .unwrap();
+
+        let results = db.search("proj", &axis(0), 10).unwrap();
+        assert!(results[0].similarity.unwrap() > 0.99);
+        assert_eq!(results[0].snippet, None);
+    }
+
+    #[test]
+    fn test_bm25_skips_chunk_rows() {
+        let db = Database::open_in_memory().unwrap();
+        let parent = db
+            .insert("proj", "alpha beta gamma", &vec![0.1; 384], None)
+            .unwrap();
+        db.insert_chunks(&parent, &[chunk("alpha beta", 0.2)])
+            .unwrap();
+
+        let results = db.search_bm25("alpha", "proj", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, parent);
+    }
+
+    #[test]
+    fn test_open_adds_parent_id_to_existing_database() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("old.db");
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE memories (
+                    id TEXT PRIMARY KEY,
+                    project_id TEXT NOT NULL,
+                    content TEXT NOT NULL,
+                    embedding BLOB NOT NULL,
+                    metadata TEXT,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );",
+            )
+            .unwrap();
+
+        let db = Database::open(&path).unwrap();
+        let parent = db.insert("proj", "content", &vec![0.1; 384], None).unwrap();
+        db.insert_chunks(&parent, &[chunk("content", 0.2)]).unwrap();
+        assert_eq!(db.list("proj", 10).unwrap().len(), 1);
+
+        // Reopening an already migrated database is a no-op
+        drop(db);
+        assert!(Database::open(&path).is_ok());
+    }
+}
@@ -0,0 +1,263 @@
+//! Transparent zstd compression of long memory content.
+//!
+//! Content of at least the configured size is stored zstd-compressed in the
+//! `content_zstd` BLOB column, with `content` left empty and the row's
+//! `compressed` flag set. Queries read content through the
+//! `vipune_content(content, content_zstd)` SQL function registered on
+//! vipune's connection, which decompresses the payload when there is one.
+//!
+//! The FTS5 source view and sync triggers only call the function once a
+//! database holds compressed rows (see [`super::fts::set_decompression`]),
+//! so a database that never enabled compression stays writable from SQLite
+//! tools without vipune's functions.
+
+use rusqlite::Connection;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::{Value, ValueRef};
+
+use super::{Database, Error, Result, fts};
+
+/// zstd level used for content (the library default).
+const LEVEL: i32 = 0;
+
+/// Tables holding memory rows, whose content may be compressed.
+const TABLES: [&str; 3] = ["memories", "memories_archive", "memories_trash"];
+
+/// Register the `vipune_content(content, content_zstd)` SQL function.
+pub(super) fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "vipune_content",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| match ctx.get_raw(1) {
+            ValueRef::Blob(compressed) => decompress(compressed)
+                .map(Value::Text)
+                .map_err(|e| rusqlite::Error::UserFunctionError(e.to_string().into())),
+            _ => ctx.get::<Value>(0),
+        },
+    )?;
+    Ok(())
+}
+
+/// Values to store for `content`: the text for the `content` column, and
+/// the compressed payload for `content_zstd` (`None` when uncompressed).
+///
+/// Content is compressed when `threshold` is set, it is at least that many
+/// bytes and compressing makes it smaller.
+pub(super) fn encode(content: &str, threshold: Option<usize>) -> Result<(String, Option<Vec<u8>>)> {
+    if threshold.is_some_and(|min| content.len() >= min) {
+        let compressed = zstd::encode_all(content.as_bytes(), LEVEL)
+            .map_err(|e| Error::Sqlite(format!("Failed to compress content: {}", e)))?;
+        if compressed.len() < content.len() {
+            return Ok((String::new(), Some(compressed)));
+        }
+    }
+    Ok((content.to_string(), None))
+}
+
+/// Whether any memory row, archived and trashed ones included, is
+/// compressed (tables created before compression are skipped).
+pub(super) fn has_compressed_rows(conn: &Connection) -> rusqlite::Result<bool> {
+    for table in TABLES {
+        let has_flag = conn
+            .prepare("SELECT 1 FROM pragma_table_info(?1) WHERE name = 'compressed'")?
+            .exists([table])?;
+        if !has_flag {
+            continue;
+        }
+        let sql = format!("SELECT 1 FROM {} WHERE compressed = 1 LIMIT 1", table);
+        if conn.prepare(&sql)?.exists([])? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Decompress stored content.
+fn decompress(compressed: &[u8]) -> Result<String> {
+    let bytes = zstd::decode_all(compressed)
+        .map_err(|e| Error::Sqlite(format!("Failed to decompress content: {}", e)))?;
+    String::from_utf8(bytes)
+        .map_err(|e| Error::Sqlite(format!("Decompressed content is not UTF-8: {}", e)))
+}
+
+impl Database {
+    /// Compress content written from now on when it is at least `threshold`
+    /// bytes (`None` stores all content as text).
+    ///
+    /// Enabling compression makes the FTS5 sync triggers read content
+    /// through `vipune_content`; they go back to plain `content` once
+    /// compression is disabled and no compressed rows are left.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the FTS5 source view and triggers cannot be
+    /// recreated.
+    pub fn set_compression(&mut self, threshold: Option<usize>) -> Result<()> {
+        self.compression = threshold;
+        if self.fts {
+            let decompress = threshold.is_some() || has_compressed_rows(&self.conn)?;
+            fts::set_decompression(&self.conn, decompress)?;
+        }
+        Ok(())
+    }
+
+    /// Encode content for storage with the configured threshold.
+    pub(super) fn encode_content(&self, content: &str) -> Result<(String, Option<Vec<u8>>)> {
+        encode(content, self.compression)
+    }
+
+    /// Compressed rows of a project (chunk rows included) with their stored
+    /// and uncompressed sizes in bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails or stored content cannot be
+    /// decompressed.
+    pub fn compression_totals(&self, project_id: &str) -> Result<(usize, u64, u64)> {
+        let totals = self.conn.query_row(
+            r#"
+            SELECT COUNT(*),
+                   COALESCE(SUM(length(content_zstd)), 0),
+                   COALESCE(SUM(length(CAST(vipune_content(content, content_zstd) AS BLOB))), 0)
+            FROM memories
+            WHERE project_id = ?1 AND compressed = 1
+            "#,
+            [project_id],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, i64>(1)? as u64,
+                    row.get::<_, i64>(2)? as u64,
+                ))
+            },
+        )?;
+        Ok(totals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::SCHEMA_VERSION;
+    use crate::sqlite::fts::schema::decompression_of;
+    use tempfile::TempDir;
+
+    fn long_content() -> String {
+        "the quick brown fox jumps over the lazy dog\n".repeat(200)
+    }
+
+    #[test]
+    fn test_encode_compresses_long_content_only() {
+        let (text, zstd) = encode("short", Some(100)).unwrap();
+        assert_eq!((text.as_str(), zstd), ("short", None));
+
+        let content = long_content();
+        let (text, zstd) = encode(&content, None).unwrap();
+        assert!(text == content && zstd.is_none());
+
+        let (text, zstd) = encode(&content, Some(100)).unwrap();
+        assert!(text.is_empty());
+        let blob = zstd.expect("compressed payload");
+        assert!(blob.len() < content.len());
+        assert_eq!(decompress(&blob).unwrap(), content);
+    }
+
+    #[test]
+    fn test_compressed_rows_read_and_index_as_text() {
+        let mut db = Database::open_in_memory().unwrap();
+        db.set_compression(Some(1024)).unwrap();
+        let embedding = vec![0.1; 384];
+        let content = format!("{}needle at the end", long_content());
+        let long = db.insert("proj", &content, &embedding, None).unwrap();
+        let short = db.insert("proj", "short needle", &embedding, None).unwrap();
+
+        let flags: Vec<(String, bool, String, String)> = db
+            .conn
+            .prepare(
+                "SELECT id, compressed, typeof(content), typeof(content_zstd) FROM memories
+                 ORDER BY rowid",
+            )
+            .unwrap()
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            flags,
+            vec![
+                (long.clone(), true, "text".to_string(), "blob".to_string()),
+                (short.clone(), false, "text".to_string(), "null".to_string())
+            ]
+        );
+        assert_eq!(db.get(&long).unwrap().unwrap().content, content);
+
+        let hits = db.search_bm25("needle", "proj", 10).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().any(|m| m.id == long && m.content == content));
+
+        let (rows, stored, original) = db.compression_totals("proj").unwrap();
+        assert_eq!(rows, 1);
+        assert_eq!(original, content.len() as u64);
+        assert!(stored < original);
+
+        // Updating to short content stores it as text and keeps the index in sync
+        db.update(&long, "rewritten", &embedding).unwrap();
+        assert_eq!(db.compression_totals("proj").unwrap(), (0, 0, 0));
+        assert_eq!(db.search_bm25("needle", "proj", 10).unwrap().len(), 1);
+        assert_eq!(db.search_bm25("rewritten", "proj", 10).unwrap().len(), 1);
+        db.delete(&short).unwrap();
+        assert!(db.search_bm25("needle", "proj", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_triggers_need_no_function_without_compression() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let embedding = vec![0.1; 384];
+        {
+            let mut db = Database::open(&path).unwrap();
+            db.set_compression(None).unwrap();
+            db.insert("proj", "written by vipune", &embedding, None)
+                .unwrap();
+        }
+
+        // A connection without vipune's functions, like the sqlite3 shell
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "UPDATE memories SET content = 'edited outside';
+             INSERT INTO memories (id, project_id, content, embedding, created_at, updated_at)
+             VALUES ('x', 'proj', 'inserted outside', x'00', 'now', 'now');
+             DELETE FROM memories WHERE id = 'x';",
+        )
+        .unwrap();
+        drop(conn);
+
+        let mut db = Database::open(&path).unwrap();
+        assert_eq!(db.search_bm25("outside", "proj", 10).unwrap().len(), 1);
+        // Compression makes the triggers decompress, until no rows are left
+        db.set_compression(Some(1024)).unwrap();
+        assert!(decompression_of(&db.conn).unwrap());
+        db.set_compression(None).unwrap();
+        assert!(!decompression_of(&db.conn).unwrap());
+        db.set_compression(Some(1024)).unwrap();
+        db.insert("proj", &long_content(), &embedding, None)
+            .unwrap();
+        db.set_compression(None).unwrap();
+        assert!(decompression_of(&db.conn).unwrap());
+    }
+
+    #[test]
+    fn test_open_refuses_newer_schema() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        Connection::open(&path)
+            .unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        let err = Database::open(&path).err().expect("newer schema refused");
+        assert!(err.to_string().contains("newer than this vipune supports"));
+    }
+}
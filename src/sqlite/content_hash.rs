@@ -0,0 +1,158 @@
+//! Finding memories by their exact text.
+//!
+//! The `content_hash` column holds the SHA-256 of a memory's lowercased
+//! content (chunk rows have none), indexed per project, so a lookup by text
+//! reads only the rows whose content is equal up to case and compares those.
+//! Case-sensitive and case-insensitive lookups share the index. The
+//! `vipune_content_hash(content)` SQL function computes the hash, so existing
+//! rows are hashed in place when the column is added.
+
+use hmac_sha256::Hash;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{Connection, params};
+
+use super::{Database, Memory, Result, memory_from_row};
+
+/// Index of content hashes, created with the other migrated indexes.
+pub(super) const CONTENT_HASH_SCHEMA: &str = r#"
+    CREATE INDEX IF NOT EXISTS idx_memories_content_hash ON memories(project_id, content_hash);
+"#;
+
+/// Register the `vipune_content_hash(content)` SQL function.
+pub(super) fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "vipune_content_hash",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| Ok(ctx.get::<Option<String>>(0)?.map(|c| content_hash(&c))),
+    )?;
+    Ok(())
+}
+
+/// Hex SHA-256 of `content` lowercased.
+pub(crate) fn content_hash(content: &str) -> String {
+    Hash::hash(content.to_lowercase().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Hash the content of every memory (chunk rows excluded); used when the
+/// `content_hash` column is added to an existing database.
+pub(super) fn hash_contents(conn: &Connection) -> Result<()> {
+    log::info!("Migrating schema: hashing memory contents");
+    conn.execute(
+        "UPDATE memories SET content_hash = vipune_content_hash(vipune_content(content, content_zstd))
+         WHERE parent_id IS NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+impl Database {
+    /// Memories in a project whose content equals `content`, ignoring case
+    /// if `ignore_case` is set, and that `viewer` may see (their own and
+    /// shared ones), newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn find_by_content(
+        &self,
+        project_id: &str,
+        content: &str,
+        ignore_case: bool,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, project_id, vipune_content(content, content_zstd), metadata, created_at, updated_at,
+                   kind, origin, title
+            FROM memories
+            WHERE project_id = ?1 AND content_hash = ?2 AND parent_id IS NULL
+                  AND (?3 IS NULL OR visibility = 'shared' OR owner = ?3)
+            ORDER BY created_at DESC
+            "#,
+        )?;
+        let candidates = stmt
+            .query_map(
+                params![project_id, content_hash(content), viewer],
+                memory_from_row,
+            )?
+            .collect::<rusqlite::Result<Vec<Memory>>>()?;
+        Ok(candidates
+            .into_iter()
+            .filter(|memory| {
+                memory.content == content
+                    || (ignore_case && memory.content.to_lowercase() == content.to_lowercase())
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_by_content_exact_and_ignoring_case() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+        let exact = db
+            .insert("p", "Alice works at Microsoft", &embedding, None)
+            .unwrap();
+        let lower = db
+            .insert("p", "alice works at microsoft", &embedding, None)
+            .unwrap();
+        db.insert("q", "Alice works at Microsoft", &embedding, None)
+            .unwrap();
+
+        let ids = |text: &str, ignore_case: bool| -> Vec<String> {
+            db.find_by_content("p", text, ignore_case, None)
+                .unwrap()
+                .into_iter()
+                .map(|m| m.id)
+                .collect()
+        };
+        assert_eq!(
+            ids("Alice works at Microsoft", false),
+            std::slice::from_ref(&exact)
+        );
+        let mut found = ids("ALICE works at microsoft", true);
+        found.sort();
+        let mut expected = vec![exact.clone(), lower];
+        expected.sort();
+        assert_eq!(found, expected);
+        assert!(ids("ALICE works at microsoft", false).is_empty());
+        assert!(ids("Alice works at", true).is_empty());
+
+        // Updates rehash
+        db.update(&exact, "Alice works at Google", &embedding)
+            .unwrap();
+        assert_eq!(ids("Alice works at Google", false), [exact]);
+    }
+
+    #[test]
+    fn test_hash_contents_backfills_existing_rows() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+        let id = db
+            .insert("p", "Deploy with make", &embedding, None)
+            .unwrap();
+        db.conn
+            .execute("UPDATE memories SET content_hash = NULL", [])
+            .unwrap();
+        assert!(
+            db.find_by_content("p", "Deploy with make", false, None)
+                .unwrap()
+                .is_empty()
+        );
+
+        hash_contents(&db.conn).unwrap();
+        let found = db
+            .find_by_content("p", "deploy WITH make", true, None)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, id);
+    }
+}
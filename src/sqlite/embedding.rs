@@ -1,17 +1,150 @@
 //! Embedding BLOB conversion and cosine similarity computation.
+//!
+//! Embeddings are stored either as little-endian f32 values or, with
+//! [`EmbeddingFormat::Int8`], as a little-endian f32 scale followed by one
+//! signed byte per value (about a quarter of the size). The two layouts have
+//! different lengths, so readers tell them apart without a format column.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
 
 use super::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 const EMBEDDING_DIMS: usize = 384;
-const EMBEDDING_BLOB_SIZE: usize = EMBEDDING_DIMS * 4; // 384 f32 values × 4 bytes each
+pub(crate) const EMBEDDING_BLOB_SIZE: usize = EMBEDDING_DIMS * 4; // 384 f32 values × 4 bytes each
+pub(crate) const INT8_BLOB_SIZE: usize = EMBEDDING_DIMS + 4; // f32 scale + 384 i8 values
+
+/// How new and rewritten embeddings are stored.
+///
+/// Set by `embedding_format` in the config; [`Database::convert_embeddings`]
+/// (`vipune quantize`) rewrites existing rows. Both formats are read
+/// transparently.
+///
+/// [`Database::convert_embeddings`]: super::Database::convert_embeddings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingFormat {
+    /// Full-precision f32 values (1,536 bytes per embedding).
+    #[default]
+    F32,
+    /// Values quantized to 8-bit integers with a per-vector scale (388 bytes
+    /// per embedding). Cosine similarities shift by less than 0.01.
+    Int8,
+}
+
+impl EmbeddingFormat {
+    /// Lowercase name, as in the config file.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EmbeddingFormat::F32 => "f32",
+            EmbeddingFormat::Int8 => "int8",
+        }
+    }
+
+    /// Size in bytes of a stored embedding with `dims` values.
+    pub(crate) fn blob_size(self, dims: usize) -> usize {
+        match self {
+            EmbeddingFormat::F32 => dims * 4,
+            EmbeddingFormat::Int8 => dims + 4,
+        }
+    }
+}
+
+impl fmt::Display for EmbeddingFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EmbeddingFormat {
+    type Err = crate::errors::Error;
+
+    /// Parse `f32` or `int8`, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "f32" => Ok(Self::F32),
+            "int8" => Ok(Self::Int8),
+            _ => Err(crate::errors::Error::InvalidInput(format!(
+                "Invalid embedding format '{}' (expected one of: f32, int8)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Convert an embedding to a BLOB in `format`.
+///
+/// # Errors
+///
+/// Returns `Error::MismatchedDimensions` if the vector length is not exactly
+/// 384, or `Error::InvalidEmbedding` if it holds a NaN or infinite value.
+pub fn encode_blob(vec: &[f32], format: EmbeddingFormat) -> Result<Vec<u8>> {
+    match format {
+        EmbeddingFormat::F32 => vec_to_blob(vec),
+        EmbeddingFormat::Int8 => vec_to_int8_blob(vec),
+    }
+}
+
+/// Convert a vector of f32 embedding values to an int8 BLOB: the scale
+/// (largest absolute value / 127) as little-endian f32, then each value
+/// divided by the scale and rounded.
+///
+/// # Errors
+///
+/// Returns `Error::MismatchedDimensions` if the vector length is not exactly
+/// 384, or `Error::InvalidEmbedding` if a value is NaN or infinite.
+pub fn vec_to_int8_blob(vec: &[f32]) -> Result<Vec<u8>> {
+    if vec.len() != EMBEDDING_DIMS {
+        return Err(Error::MismatchedDimensions {
+            expected: EMBEDDING_DIMS,
+            actual: vec.len(),
+        });
+    }
+    check_finite(vec)?;
+    let max_abs = vec.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+    let scale = max_abs / 127.0;
+    let mut blob = Vec::with_capacity(INT8_BLOB_SIZE);
+    blob.extend_from_slice(&scale.to_le_bytes());
+    for &x in vec {
+        let q = if scale > 0.0 {
+            (x / scale).round().clamp(-127.0, 127.0) as i8
+        } else {
+            0
+        };
+        blob.push(q as u8);
+    }
+    Ok(blob)
+}
+
+/// Decode a stored embedding of either format holding `dims` values.
+///
+/// Returns `None` if the BLOB has neither format's size for `dims` (e.g. it
+/// was written by a model with other dimensions).
+pub fn decode_blob(blob: &[u8], dims: usize) -> Option<Vec<f32>> {
+    if blob.len() == EmbeddingFormat::F32.blob_size(dims) {
+        Some(
+            blob.chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+        )
+    } else if blob.len() == EmbeddingFormat::Int8.blob_size(dims) {
+        let scale = f32::from_le_bytes([blob[0], blob[1], blob[2], blob[3]]);
+        Some(blob[4..].iter().map(|&q| q as i8 as f32 * scale).collect())
+    } else {
+        None
+    }
+}
 
 /// Convert a vector of f32 embedding values to a BLOB (little-endian bytes).
 ///
 /// # Errors
 ///
-/// Returns `Error::MismatchedDimensions` if the vector length is not exactly 384.
+/// Returns `Error::MismatchedDimensions` if the vector length is not exactly
+/// 384, or `Error::InvalidEmbedding` if a value is NaN or infinite.
 pub fn vec_to_blob(vec: &[f32]) -> Result<Vec<u8>> {
     if vec.len() != EMBEDDING_DIMS {
         return Err(Error::MismatchedDimensions {
@@ -19,27 +152,33 @@ pub fn vec_to_blob(vec: &[f32]) -> Result<Vec<u8>> {
             actual: vec.len(),
         });
     }
+    check_finite(vec)?;
     Ok(vec.iter().flat_map(|&x| x.to_le_bytes()).collect())
 }
 
-/// Convert a BLOB (little-endian bytes) to a vector of f32 embedding values.
+/// Reject vectors holding NaN or infinite values, which would make every
+/// later similarity computation against them fail.
+fn check_finite(vec: &[f32]) -> Result<()> {
+    if vec.iter().any(|x| !x.is_finite()) {
+        return Err(Error::InvalidEmbedding(
+            "Vector contains NaN or infinite values".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Convert a stored BLOB of either [`EmbeddingFormat`] to a vector of f32
+/// embedding values.
 ///
 /// # Errors
 ///
-/// Returns `Error::InvalidBlobSize` if the blob length is not exactly 1,536 bytes.
+/// Returns `Error::InvalidBlobSize` if the blob length is neither 1,536 bytes
+/// (f32) nor 388 bytes (int8).
 pub fn blob_to_vec(blob: &[u8]) -> Result<Vec<f32>> {
-    if blob.len() != EMBEDDING_BLOB_SIZE {
-        return Err(Error::InvalidBlobSize {
-            expected: EMBEDDING_BLOB_SIZE,
-            actual: blob.len(),
-        });
-    }
-    let mut vec = Vec::with_capacity(EMBEDDING_DIMS);
-    for chunk in blob.chunks_exact(4) {
-        let val = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-        vec.push(val);
-    }
-    Ok(vec)
+    decode_blob(blob, EMBEDDING_DIMS).ok_or(Error::InvalidBlobSize {
+        expected: EMBEDDING_BLOB_SIZE,
+        actual: blob.len(),
+    })
 }
 
 /// Compute cosine similarity between two embedding vectors.
@@ -69,13 +208,13 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Result<f64> {
         ));
     }
 
-    let dot: f64 = a
-        .iter()
-        .zip(b.iter())
-        .map(|(x, y)| (*x as f64) * (*y as f64))
-        .sum();
-    let norm_a: f64 = a.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
-    let norm_b: f64 = b.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    #[cfg(feature = "simd")]
+    let (dot, norm_a_sq, norm_b_sq) = super::simd::dot_and_norms(a, b);
+    #[cfg(not(feature = "simd"))]
+    let (dot, norm_a_sq, norm_b_sq) = dot_and_norms_scalar(a, b);
+
+    let norm_a = norm_a_sq.sqrt();
+    let norm_b = norm_b_sq.sqrt();
 
     if norm_a == 0.0 || norm_b == 0.0 {
         return Ok(0.0);
@@ -84,6 +223,20 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Result<f64> {
     Ok(dot / (norm_a * norm_b))
 }
 
+/// Compute the dot product and squared norms of two equal-length vectors in f64.
+///
+/// Reference implementation; the `simd` feature swaps in a vectorized equivalent.
+pub fn dot_and_norms_scalar(a: &[f32], b: &[f32]) -> (f64, f64, f64) {
+    let dot: f64 = a
+        .iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x as f64) * (*y as f64))
+        .sum();
+    let norm_a: f64 = a.iter().map(|x| (*x as f64).powi(2)).sum();
+    let norm_b: f64 = b.iter().map(|x| (*x as f64).powi(2)).sum();
+    (dot, norm_a, norm_b)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,6 +257,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_vec_to_blob_rejects_non_finite_values() {
+        for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let mut vec = vec![0.1f32; EMBEDDING_DIMS];
+            vec[7] = bad;
+            assert!(matches!(
+                encode_blob(&vec, EmbeddingFormat::F32),
+                Err(Error::InvalidEmbedding(_))
+            ));
+        }
+    }
+
     #[test]
     fn test_blob_to_vec_correct_size() {
         let vec = vec![0.1f32; 384];
@@ -124,6 +289,76 @@ mod tests {
         ));
     }
 
+    /// Deterministic pseudo-random vector with values in [-1, 1).
+    fn sample_vector(seed: u64) -> Vec<f32> {
+        let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (0..EMBEDDING_DIMS)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) as f32 / (1u64 << 31) as f32) * 2.0 - 1.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_int8_blob_roundtrip() {
+        let vec = sample_vector(1);
+        let blob = encode_blob(&vec, EmbeddingFormat::Int8).unwrap();
+        assert_eq!(blob.len(), INT8_BLOB_SIZE);
+        let recovered = blob_to_vec(&blob).unwrap();
+        let max_abs = vec.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+        for (a, b) in vec.iter().zip(recovered.iter()) {
+            assert!((a - b).abs() <= max_abs / 254.0 + 1e-6);
+        }
+
+        let zero = blob_to_vec(&vec_to_int8_blob(&[0.0; EMBEDDING_DIMS]).unwrap()).unwrap();
+        assert!(zero.iter().all(|&x| x == 0.0));
+        let mut nan = vec![0.1f32; EMBEDDING_DIMS];
+        nan[3] = f32::NAN;
+        assert!(matches!(
+            vec_to_int8_blob(&nan),
+            Err(Error::InvalidEmbedding(_))
+        ));
+    }
+
+    #[test]
+    fn test_int8_similarity_within_tolerance() {
+        let query = sample_vector(0);
+        for seed in 1..50 {
+            let vec = sample_vector(seed);
+            let exact = cosine_similarity(&query, &vec).unwrap();
+            let quantized = blob_to_vec(&vec_to_int8_blob(&vec).unwrap()).unwrap();
+            let approx = cosine_similarity(&query, &quantized).unwrap();
+            assert!((exact - approx).abs() < 0.01, "{} vs {}", exact, approx);
+        }
+    }
+
+    #[test]
+    fn test_decode_blob_checks_dimensions() {
+        let vec = sample_vector(2);
+        for format in [EmbeddingFormat::F32, EmbeddingFormat::Int8] {
+            let blob = encode_blob(&vec, format).unwrap();
+            assert_eq!(blob.len(), format.blob_size(EMBEDDING_DIMS));
+            assert_eq!(decode_blob(&blob, EMBEDDING_DIMS).unwrap().len(), 384);
+            assert!(decode_blob(&blob, 256).is_none());
+        }
+    }
+
+    #[test]
+    fn test_parse_embedding_format() {
+        assert_eq!(
+            " INT8 ".parse::<EmbeddingFormat>().unwrap(),
+            EmbeddingFormat::Int8
+        );
+        assert_eq!(
+            "f32".parse::<EmbeddingFormat>().unwrap(),
+            EmbeddingFormat::F32
+        );
+        assert!("f16".parse::<EmbeddingFormat>().is_err());
+    }
+
     #[test]
     fn test_cosine_similarity_identical_vectors() {
         let vec = vec![1.0f32; 384];
@@ -0,0 +1,65 @@
+//! Full project dumps, with stored embeddings, for `vipune export`.
+
+use super::embedding::blob_to_vec;
+use super::{Database, Memory, Result, memory_from_row};
+
+impl Database {
+    /// Every memory of a project (chunk rows excluded) with its stored
+    /// embedding, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails or a stored embedding is malformed.
+    pub fn export_project(&self, project_id: &str) -> Result<Vec<(Memory, Vec<f32>)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, project_id, vipune_content(content, content_zstd), metadata, created_at, updated_at, kind, origin, title,
+                   embedding
+            FROM memories
+            WHERE project_id = ?1 AND parent_id IS NULL
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )?;
+        let rows = stmt.query_map([project_id], |row| {
+            Ok((memory_from_row(row)?, row.get::<_, Vec<u8>>(9)?))
+        })?;
+
+        let mut memories = Vec::new();
+        for row in rows {
+            let (memory, blob) = row?;
+            memories.push((memory, blob_to_vec(&blob)?));
+        }
+        Ok(memories)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedding(value: f32) -> Vec<f32> {
+        vec![value; 384]
+    }
+
+    #[test]
+    fn test_export_project_includes_embeddings_but_not_chunks() {
+        let db = Database::open_in_memory().unwrap();
+        let first = db
+            .insert("proj", "first", &embedding(0.1), Some(r#"{"k":1}"#))
+            .unwrap();
+        let second = db.insert("proj", "second", &embedding(0.2), None).unwrap();
+        db.insert("other", "elsewhere", &embedding(0.3), None)
+            .unwrap();
+        db.insert_chunks(&first, &[("chunk".to_string(), embedding(0.4))])
+            .unwrap();
+
+        let exported = db.export_project("proj").unwrap();
+        assert_eq!(exported.len(), 2);
+        let (memory, stored) = exported.iter().find(|(m, _)| m.id == first).unwrap();
+        assert_eq!(memory.metadata.as_deref(), Some(r#"{"k":1}"#));
+        assert_eq!(*stored, embedding(0.1));
+        let (_, stored) = exported.iter().find(|(m, _)| m.id == second).unwrap();
+        assert_eq!(*stored, embedding(0.2));
+        assert!(db.export_project("missing").unwrap().is_empty());
+    }
+}
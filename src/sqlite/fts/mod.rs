@@ -0,0 +1,477 @@
+//! FTS5 full-text search and BM25 ranking (Issue #40).
+//!
+//! The index schema lives in `schema`, tokenizers and normalization in
+//! `tokenizer` and match expression building in `query`.
+
+mod query;
+pub(super) mod schema;
+#[cfg(test)]
+mod tests;
+mod tokenizer;
+
+use super::{Database, Error, Memory};
+use crate::normalize::TextNormalization;
+use crate::search_options::{FieldWeights, QuerySyntax, SearchFilter};
+use rusqlite::params_from_iter;
+use rusqlite::types::Value;
+
+pub(crate) use query::keyword_terms;
+#[allow(unused_imports)] // Re-export justified: library API, unused by the CLI
+pub use schema::INDEXED_METADATA_FIELDS;
+use schema::recreate_fts;
+pub(crate) use schema::{
+    add_metadata_fields, create_fts, drop_fts, index_titles, resume_sync, set_decompression,
+    suspend_sync,
+};
+pub use tokenizer::FtsTokenizer;
+pub(crate) use tokenizer::register_functions;
+use tokenizer::{normalization_of, tokenizer_of};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// How [`Database::open_with`] verifies the FTS5 index.
+///
+/// External-content FTS5 tables drift when the database was modified by an
+/// older binary or a crash interrupted the sync triggers; BM25 search then
+/// silently misses rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FtsCheck {
+    /// Also run FTS5 `integrity-check` against the content table when the row
+    /// counts match (reads every row, so slower on large databases).
+    pub integrity_check: bool,
+    /// Rebuild an inconsistent index instead of only warning.
+    pub auto_repair: bool,
+}
+
+impl Default for FtsCheck {
+    fn default() -> Self {
+        Self {
+            integrity_check: false,
+            auto_repair: true,
+        }
+    }
+}
+
+impl Database {
+    /// Initialize FTS5 table if needed and validate/migrate schema.
+    ///
+    /// This method:
+    /// 1. Checks if memories_fts table exists with correct schema
+    /// 2. If schema is outdated, performs drop-and-recreate migration
+    /// 3. Validates consistency by comparing row counts
+    ///
+    /// # Errors
+    ///
+    /// Returns error if migration fails or consistency check detects data loss.
+    pub fn initialize_fts(&self) -> Result<()> {
+        if !self.fts {
+            return Err(Error::FtsDisabled);
+        }
+        // Check if FTS5 table exists with correct schema
+        let fts_exists: bool = self
+            .conn
+            .query_row(
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='memories_fts'",
+                [],
+                |_row| Ok(true),
+            )
+            .unwrap_or(false);
+
+        if fts_exists {
+            // Check if project_id column exists using PRAGMA table_info
+            // This is locale-independent and more reliable than error message parsing
+            let has_project_id: bool = self.conn.query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('memories_fts') WHERE name = 'project_id'",
+                [],
+                |row| row.get::<_, i64>(0).map(|count| count > 0),
+            )?;
+
+            if !has_project_id {
+                log::info!("Migrating FTS5 index to add project_id");
+                // FTS5 schema migration: drop-and-recreate strategy
+                let tx = self.write_transaction()?;
+
+                // Validate external content table exists and has expected structure
+                let memories_exists: bool = tx.query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='memories'",
+                    [],
+                    |row| row.get::<_, i64>(0).map(|count| count > 0),
+                )?;
+
+                if !memories_exists {
+                    return Err(Error::Sqlite(
+                        "External content table 'memories' does not exist".to_string(),
+                    ));
+                }
+
+                // Get memory count before migration for validation
+                // Note: This count check assumes single-threaded operation. If threading is added,
+                // consider using transaction isolation levels to prevent race conditions.
+                let memory_count: i64 =
+                    tx.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+
+                // Tables this old predate configurable tokenizers
+                recreate_fts(&tx, FtsTokenizer::Porter, TextNormalization::default())
+                    .map_err(|e| Error::Sqlite(format!("FTS5 schema migration failed: {}", e)))?;
+
+                // Validate migration: verify row count matches
+                let fts_count: i64 =
+                    tx.query_row("SELECT COUNT(*) FROM memories_fts", [], |row| row.get(0))?;
+
+                if fts_count != memory_count {
+                    tx.rollback()?;
+                    return Err(Error::Sqlite(format!(
+                        "FTS5 migration incomplete: expected {} rows, got {} rows",
+                        memory_count, fts_count
+                    )));
+                }
+
+                tx.commit()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Search memories using FTS5 BM25 ranking.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FtsDisabled` without a full-text index, or error if the
+    /// FTS5 search fails.
+    #[allow(dead_code)] // Used in tests
+    pub fn search_bm25(&self, query: &str, project_id: &str, limit: usize) -> Result<Vec<Memory>> {
+        self.search_bm25_filtered(
+            query,
+            project_id,
+            limit,
+            &SearchFilter::default(),
+            &[],
+            FieldWeights::default(),
+        )
+    }
+
+    /// Search memories using FTS5 BM25 ranking, keeping only memories that
+    /// pass `filter` (applied in SQL, see [`Database::search_filtered`]).
+    ///
+    /// Query words match literally; see [`Database::search_bm25_with_syntax`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FtsDisabled` without a full-text index, or error if the
+    /// FTS5 search fails.
+    pub fn search_bm25_filtered(
+        &self,
+        query: &str,
+        project_id: &str,
+        limit: usize,
+        filter: &SearchFilter,
+        exclude: &[String],
+        field_weights: FieldWeights,
+    ) -> Result<Vec<Memory>> {
+        self.search_bm25_with_syntax(
+            query,
+            QuerySyntax::Plain,
+            project_id,
+            limit,
+            filter,
+            exclude,
+            field_weights,
+        )
+    }
+
+    /// [`Database::search_bm25_filtered`] with query words read as `syntax`
+    /// says (see [`keyword_terms`]).
+    ///
+    /// Words match the content and the indexed metadata fields
+    /// ([`INDEXED_METADATA_FIELDS`]), ranked with `field_weights` as the BM25
+    /// column weights. Each query word also matches its stored synonyms (see
+    /// [`Database::synonyms_for`]). Memories containing every word of any
+    /// `exclude` term are left out (FTS5 `NOT`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FtsDisabled` without a full-text index, error if
+    /// `query` is not valid in `syntax`, or error if the FTS5 search fails.
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_bm25_with_syntax(
+        &self,
+        query: &str,
+        syntax: QuerySyntax,
+        project_id: &str,
+        limit: usize,
+        filter: &SearchFilter,
+        exclude: &[String],
+        field_weights: FieldWeights,
+    ) -> Result<Vec<Memory>> {
+        if !self.fts {
+            return Err(Error::FtsDisabled);
+        }
+        super::search::validate_limit(limit)?;
+
+        // Auto-initialize FTS5 if not available
+        if !self.is_fts_initialized()? {
+            self.initialize_fts()?;
+        }
+
+        // Queries are normalized like the indexed text
+        let normalization = normalization_of(&self.conn)?;
+        let query = normalization.apply(query);
+        let terms = keyword_terms(&query, syntax)
+            .map_err(|e| Error::Sqlite(format!("Invalid keyword query: {}", e)))?;
+        let words: Vec<&str> = terms.iter().map(|term| term.text).collect();
+        let mut synonyms = self.synonyms_for(project_id, &words)?;
+        if !normalization.is_identity() {
+            for synonym in synonyms.values_mut().flatten() {
+                *synonym = normalization.apply(synonym);
+            }
+        }
+        let escaped_query = Self::expand_fts_query(&terms, &synonyms);
+
+        // Empty query returns no results (avoid FTS5 syntax error)
+        if escaped_query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let exclude: Vec<String> = exclude
+            .iter()
+            .map(|term| normalization.apply(term))
+            .collect();
+        let match_expr = Self::with_exclusions(escaped_query, &exclude);
+
+        let mut sql = String::from(
+            "SELECT m.id, m.project_id, vipune_content(m.content, m.content_zstd), m.metadata, m.created_at,
+                    m.updated_at, bm25(memories_fts, ?4, 0.0, ?5) as bm25_score, m.kind, m.origin,
+                    m.title
+             FROM memories_fts
+             JOIN memories m ON m.rowid = memories_fts.rowid
+             WHERE memories_fts MATCH ?1 AND m.project_id = ?2 AND m.parent_id IS NULL",
+        );
+        let mut params = vec![
+            Value::Text(match_expr),
+            Value::Text(project_id.to_string()),
+            Value::Integer(limit as i64),
+            Value::Real(field_weights.content),
+            Value::Real(field_weights.metadata),
+        ];
+        super::search::push_filter_conditions(&mut sql, filter, &mut params);
+        sql.push_str(" ORDER BY bm25_score LIMIT ?3");
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let memories: rusqlite::Result<Vec<Memory>> = stmt
+            .query_map(params_from_iter(&params), |row| {
+                Ok(Memory {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    content: row.get(2)?,
+                    metadata: row.get(3)?,
+                    kind: row.get(7)?,
+                    origin: row.get(8)?,
+                    title: row.get(9)?,
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                    similarity: Some(row.get::<_, f64>(6)?),
+                    snippet: None,
+                    explanation: None,
+                })
+            })?
+            .collect();
+        Ok(memories?)
+    }
+
+    /// Row counts of the memories table and of the documents indexed in FTS5.
+    ///
+    /// Returns `(memories, indexed)`. The triggers keep both equal; a mismatch
+    /// means the index has drifted and BM25 search may miss or misreport rows.
+    /// Counted from the FTS5 `docsize` shadow table, since counting
+    /// `memories_fts` itself reads the external content table.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FtsDisabled` without a full-text index, or error if
+    /// either count fails.
+    pub fn fts_row_counts(&self) -> Result<(usize, usize)> {
+        if !self.fts {
+            return Err(Error::FtsDisabled);
+        }
+        let memories = self.memory_rows()?;
+        let indexed: i64 =
+            self.conn
+                .query_row("SELECT COUNT(*) FROM memories_fts_docsize", [], |row| {
+                    row.get(0)
+                })?;
+        Ok((memories, indexed as usize))
+    }
+
+    /// Rows in the memories table, including chunk rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn memory_rows(&self) -> Result<usize> {
+        let memories: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+        Ok(memories as usize)
+    }
+
+    /// Check the FTS5 index against the memories table, rebuilding it if
+    /// `check.auto_repair` is set.
+    ///
+    /// Returns `true` if the index was inconsistent, which is logged as a
+    /// warning either way.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a check or the rebuild fails.
+    pub fn verify_fts(&self, check: FtsCheck) -> Result<bool> {
+        if !self.fts {
+            return Ok(false);
+        }
+        let (memories, indexed) = self.fts_row_counts()?;
+        let consistent = if memories != indexed {
+            false
+        } else if check.integrity_check {
+            self.fts_integrity_ok()?
+        } else {
+            true
+        };
+        if consistent {
+            return Ok(false);
+        }
+
+        if check.auto_repair {
+            self.rebuild_fts()?;
+            log::warn!(
+                "FTS index was out of sync ({} of {} rows indexed); rebuilt",
+                indexed,
+                memories
+            );
+        } else {
+            log::warn!(
+                "FTS index is out of sync ({} of {} rows indexed); hybrid search may \
+                 miss results (set fts_auto_repair = true to rebuild it)",
+                indexed,
+                memories
+            );
+        }
+        Ok(true)
+    }
+
+    /// Run the FTS5 `integrity-check` command, comparing the index with the
+    /// content table.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the check cannot run; an inconsistent index yields `Ok(false)`.
+    pub fn fts_integrity_ok(&self) -> Result<bool> {
+        if !self.fts {
+            return Err(Error::FtsDisabled);
+        }
+        match self.conn.execute(
+            "INSERT INTO memories_fts(memories_fts, rank) VALUES('integrity-check', 1)",
+            [],
+        ) {
+            Ok(_) => Ok(true),
+            Err(rusqlite::Error::SqliteFailure(e, _))
+                if e.code == rusqlite::ErrorCode::DatabaseCorrupt =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Rebuild the FTS5 index from the memories table.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the rebuild fails.
+    pub fn rebuild_fts(&self) -> Result<()> {
+        if !self.fts {
+            return Err(Error::FtsDisabled);
+        }
+        self.conn.execute(
+            "INSERT INTO memories_fts(memories_fts) VALUES('rebuild')",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Whether the database has a full-text index (see
+    /// [`Database::open_without_fts`]).
+    pub fn fts_enabled(&self) -> bool {
+        self.fts
+    }
+
+    /// Tokenizer the FTS5 index was created with (`None` if there is no
+    /// index or it uses a tokenizer vipune does not know).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the schema cannot be read.
+    pub fn fts_tokenizer(&self) -> Result<Option<FtsTokenizer>> {
+        Ok(tokenizer_of(&self.conn)?)
+    }
+
+    /// Normalization the FTS5 index was created with (none without an
+    /// index).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the schema cannot be read.
+    pub fn fts_normalization(&self) -> Result<TextNormalization> {
+        Ok(normalization_of(&self.conn)?)
+    }
+
+    /// Recreate the FTS5 index with `tokenizer` and `normalization` and
+    /// index every memory again, e.g. to switch a database of Finnish
+    /// memories away from English stemming. Returns the number of rows
+    /// indexed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the index cannot be recreated; it is then left as it was.
+    pub fn reindex_fts(
+        &self,
+        tokenizer: FtsTokenizer,
+        normalization: TextNormalization,
+    ) -> Result<usize> {
+        if !self.fts {
+            return Err(Error::FtsDisabled);
+        }
+        let tx = self.write_transaction()?;
+        recreate_fts(&tx, tokenizer, normalization)
+            .map_err(|e| Error::Sqlite(format!("FTS5 reindex failed: {}", e)))?;
+        let indexed: i64 =
+            tx.query_row("SELECT COUNT(*) FROM memories_fts_docsize", [], |row| {
+                row.get(0)
+            })?;
+        tx.commit()?;
+        log::debug!(
+            "Reindexed {} rows with the {} tokenizer",
+            indexed,
+            tokenizer
+        );
+        Ok(indexed as usize)
+    }
+
+    /// Check if FTS5 is ready for hybrid search.
+    fn is_fts_initialized(&self) -> Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='memories_fts'",
+            [],
+            |row| row.get(0),
+        )?;
+
+        if count == 0 {
+            return Ok(false);
+        }
+
+        // Check if FTS5 index has data
+        let fts_count: i64 =
+            self.conn
+                .query_row("SELECT COUNT(*) FROM memories_fts", [], |row| row.get(0))?;
+
+        Ok(fts_count > 0)
+    }
+}
@@ -0,0 +1,173 @@
+//! Building FTS5 match expressions from keyword queries.
+
+use std::collections::HashMap;
+
+use super::super::{Database, synonyms};
+use crate::search_options::QuerySyntax;
+
+impl Database {
+    /// Append an FTS5 `NOT` clause for each non-empty exclusion term to an
+    /// escaped query.
+    pub(super) fn with_exclusions(escaped_query: String, exclude: &[String]) -> String {
+        let excluded: Vec<String> = exclude
+            .iter()
+            .map(|term| Self::escape_fts_query(term))
+            .filter(|term| !term.is_empty())
+            .collect();
+        if excluded.is_empty() {
+            return escaped_query;
+        }
+        let mut expr = format!("({})", escaped_query);
+        for term in excluded {
+            expr.push_str(&format!(" NOT ({})", term));
+        }
+        expr
+    }
+
+    /// Escape and normalize FTS5 query string.
+    pub(super) fn escape_fts_query(query: &str) -> String {
+        let terms: Vec<KeywordTerm> = query.split_whitespace().map(KeywordTerm::literal).collect();
+        Self::expand_fts_query(&terms, &HashMap::new())
+    }
+
+    /// Render query `terms` as an FTS5 expression, letting each word match
+    /// any of its `synonyms` (keyed by [`synonyms::lookup_key`]) too.
+    pub(super) fn expand_fts_query(
+        terms: &[KeywordTerm],
+        synonyms: &HashMap<String, Vec<String>>,
+    ) -> String {
+        let mut grouped = false;
+        let terms: Vec<String> = terms
+            .iter()
+            .map(
+                |term| match synonyms.get(&synonyms::lookup_key(term.text)) {
+                    Some(alternatives) if !alternatives.is_empty() => {
+                        grouped = true;
+                        let mut terms = vec![term.render(term.text)];
+                        terms.extend(alternatives.iter().map(|s| term.render(s)));
+                        format!("({})", terms.join(" OR "))
+                    }
+                    _ => {
+                        grouped |= term.column.is_some();
+                        term.render(term.text)
+                    }
+                },
+            )
+            .collect();
+        // FTS5 only joins plain strings implicitly; groups and column filters
+        // need an explicit AND
+        terms.join(if grouped { " AND " } else { " " })
+    }
+}
+
+/// Names a [`QuerySyntax::Fts`] column filter may use, with the FTS5 column
+/// each one limits a word to.
+const QUERY_COLUMNS: [(&str, &str); 2] = [("content", "content"), ("metadata", "metadata_fields")];
+
+/// One word of a keyword query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeywordTerm<'q> {
+    /// FTS5 column the word must match in, or `None` for any.
+    pub(crate) column: Option<&'static str>,
+    /// The word without its syntax.
+    pub(crate) text: &'q str,
+    /// Whether the word also matches the words it begins.
+    pub(crate) prefix: bool,
+}
+
+impl<'q> KeywordTerm<'q> {
+    /// `text` matched literally in any column.
+    fn literal(text: &'q str) -> Self {
+        Self {
+            column: None,
+            text,
+            prefix: false,
+        }
+    }
+
+    /// FTS5 expression matching `text` (the word or one of its synonyms)
+    /// the way this word is matched.
+    fn render(&self, text: &str) -> String {
+        let mut expr = String::new();
+        if let Some(column) = self.column {
+            expr.push_str(column);
+            expr.push(':');
+        }
+        expr.push_str(&quote_fts_term(text));
+        if self.prefix {
+            expr.push('*');
+        }
+        expr
+    }
+}
+
+/// Split `query` into its words, read as `syntax` says.
+///
+/// Every word is quoted when rendered, so no input reaches FTS5 as syntax
+/// except the prefix `*` and column filters validated here.
+///
+/// # Errors
+///
+/// Returns a message naming the offending word for a [`QuerySyntax::Fts`]
+/// word that is only syntax, has a `*` anywhere but at its end, or names a
+/// column other than `content:` or `metadata:`.
+pub(crate) fn keyword_terms(
+    query: &str,
+    syntax: QuerySyntax,
+) -> std::result::Result<Vec<KeywordTerm<'_>>, String> {
+    query
+        .split_whitespace()
+        .map(|word| match syntax {
+            QuerySyntax::Plain => Ok(KeywordTerm::literal(word)),
+            QuerySyntax::Prefix => Ok(KeywordTerm {
+                prefix: true,
+                ..KeywordTerm::literal(word)
+            }),
+            QuerySyntax::Fts => fts_term(word),
+        })
+        .collect()
+}
+
+/// Parse a [`QuerySyntax::Fts`] word such as `auth*` or `metadata:deploy`.
+fn fts_term(word: &str) -> std::result::Result<KeywordTerm<'_>, String> {
+    let (column, rest) = match word.split_once(':') {
+        Some((name, rest)) if !name.is_empty() => {
+            let column = QUERY_COLUMNS
+                .iter()
+                .find(|(query_name, _)| query_name.eq_ignore_ascii_case(name))
+                .map(|(_, column)| *column)
+                .ok_or_else(|| {
+                    format!(
+                        "unknown column '{}' in '{}' (expected content: or metadata:)",
+                        name, word
+                    )
+                })?;
+            (Some(column), rest)
+        }
+        _ => (None, word),
+    };
+    let (text, prefix) = match rest.strip_suffix('*') {
+        Some(stem) => (stem, true),
+        None => (rest, false),
+    };
+    if text.is_empty() {
+        return Err(format!("'{}' has no word to match", word));
+    }
+    if text.contains('*') {
+        return Err(format!(
+            "'*' can only end a word, as in 'auth*' (got '{}')",
+            word
+        ));
+    }
+    Ok(KeywordTerm {
+        column,
+        text,
+        prefix,
+    })
+}
+
+/// Quote `text` as an FTS5 string (a phrase if it has several words).
+fn quote_fts_term(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\"\"");
+    format!("\"{}\"", escaped)
+}
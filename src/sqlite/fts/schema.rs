@@ -0,0 +1,268 @@
+//! Schema of the FTS5 index: the table, its source view and sync triggers.
+
+use rusqlite::{Connection, OptionalExtension};
+
+use super::tokenizer::{FtsTokenizer, normalization_of, normalized_expr, tokenizer_of};
+use super::{Error, Result};
+use crate::normalize::TextNormalization;
+
+/// Top-level metadata keys whose values are indexed in the `metadata_fields`
+/// column of the FTS5 index, so keyword search matches them and
+/// [`FieldWeights`](crate::search_options::FieldWeights) can rank them apart from the content.
+///
+/// Array values (e.g. `"tags": ["auth", "security"]`) are indexed as their
+/// JSON text, which the tokenizer splits into the individual words.
+pub const INDEXED_METADATA_FIELDS: [&str; 2] = ["title", "tags"];
+
+/// SQL expression for the `metadata_fields` text of a row, whose columns
+/// are referred to as `{row}title` and `{row}metadata`: the title followed by
+/// the [`INDEXED_METADATA_FIELDS`] (none for missing or malformed metadata).
+fn metadata_fields_expr(row: &str) -> String {
+    let metadata = format!("{}metadata", row);
+    let values: Vec<String> = INDEXED_METADATA_FIELDS
+        .iter()
+        .map(|key| format!("coalesce(json_extract({}, '$.{}'), '')", metadata, key))
+        .collect();
+    format!(
+        "trim(coalesce({}title, '') || ' ' || CASE WHEN json_valid({}) THEN trim({}) ELSE '' END)",
+        row,
+        metadata,
+        values.join(" || ' ' || ")
+    )
+}
+
+/// SQL creating the FTS5 index with `tokenizer`, the view it reads indexed
+/// values from and the triggers that keep it in sync with the memories
+/// table, unless they already exist.
+///
+/// With `decompress` set, content is read through `vipune_content` so
+/// compressed rows are indexed uncompressed (see [`super::compression`]);
+/// otherwise the plain `content` column is indexed, and the triggers need no
+/// vipune function unless `normalization` does something. Content and
+/// metadata fields are indexed after `normalization`, which the schema
+/// records (see [`normalization_of`]).
+pub(crate) fn fts_schema(
+    tokenizer: FtsTokenizer,
+    normalization: TextNormalization,
+    decompress: bool,
+) -> String {
+    let content = |row: &str| {
+        let column = if decompress {
+            format!("vipune_content({row}content, {row}content_zstd)")
+        } else {
+            format!("{row}content")
+        };
+        normalized_expr(column, normalization)
+    };
+    let fields = |row: &str| normalized_expr(metadata_fields_expr(row), normalization);
+    format!(
+        r#"
+        CREATE VIEW IF NOT EXISTS memories_fts_source AS
+            SELECT rowid AS rowid, {source_content} AS content, project_id, {source_fields} AS metadata_fields
+            FROM memories;
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS memories_fts USING fts5(
+            content,
+            project_id UNINDEXED,
+            metadata_fields,
+            tokenize='{tokenize}',
+            content_rowid='rowid',
+            content='memories_fts_source'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS memories_fts_insert AFTER INSERT ON memories BEGIN
+            INSERT INTO memories_fts(rowid, content, project_id, metadata_fields)
+            VALUES (new.rowid, {new_content}, new.project_id, {new_fields});
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS memories_fts_delete AFTER DELETE ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, content, project_id, metadata_fields)
+            VALUES('delete', old.rowid, {old_content}, old.project_id, {old_fields});
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS memories_fts_update AFTER UPDATE ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, content, project_id, metadata_fields)
+            VALUES('delete', old.rowid, {old_content}, old.project_id, {old_fields});
+            INSERT INTO memories_fts(rowid, content, project_id, metadata_fields)
+            VALUES (new.rowid, {new_content}, new.project_id, {new_fields});
+        END;
+        "#,
+        source_content = content(""),
+        source_fields = fields(""),
+        tokenize = tokenizer.tokenize_arg(),
+        new_content = content("new."),
+        new_fields = fields("new."),
+        old_content = content("old."),
+        old_fields = fields("old."),
+    )
+}
+
+/// SQL dropping the FTS5 index, its source view and its triggers.
+///
+/// Note: Triggers must be dropped BEFORE the FTS5 table to avoid cascade errors.
+pub(super) const DROP_FTS: &str = "
+    DROP TRIGGER IF EXISTS memories_fts_insert;
+    DROP TRIGGER IF EXISTS memories_fts_delete;
+    DROP TRIGGER IF EXISTS memories_fts_update;
+    DROP TABLE IF EXISTS memories_fts;
+    DROP VIEW IF EXISTS memories_fts_source;
+";
+
+/// Drop the FTS5 index, its source view and its triggers, then create them
+/// again with `tokenizer` and `normalization` and index every memory.
+///
+/// FTS5 virtual tables do not support ALTER TABLE, so full recreation is required.
+pub(super) fn recreate_fts(
+    conn: &Connection,
+    tokenizer: FtsTokenizer,
+    normalization: TextNormalization,
+) -> rusqlite::Result<()> {
+    let decompress = decompression_of(conn)?;
+    conn.execute_batch(&format!(
+        "{}
+         {}
+         INSERT INTO memories_fts(memories_fts) VALUES('rebuild');",
+        DROP_FTS,
+        fts_schema(tokenizer, normalization, decompress)
+    ))
+}
+
+/// Recreate the source view and sync triggers of the FTS5 index, keeping
+/// its tokenizer and normalization, with content read as `decompress` says
+/// (see [`fts_schema`]). Does nothing without an index.
+///
+/// The index holds uncompressed text either way, so it is not rebuilt.
+fn write_view(conn: &Connection, decompress: bool) -> rusqlite::Result<()> {
+    if !fts_exists(conn)? {
+        return Ok(());
+    }
+    let tokenizer = tokenizer_of(conn)?.unwrap_or_default();
+    let normalization = normalization_of(conn)?;
+    conn.execute_batch(&format!(
+        "DROP TRIGGER IF EXISTS memories_fts_insert;
+         DROP TRIGGER IF EXISTS memories_fts_delete;
+         DROP TRIGGER IF EXISTS memories_fts_update;
+         DROP VIEW IF EXISTS memories_fts_source;
+         {}",
+        fts_schema(tokenizer, normalization, decompress)
+    ))
+}
+
+/// Switch the FTS5 source view and sync triggers to read content as
+/// `decompress` says, unless they already do.
+pub(crate) fn set_decompression(conn: &Connection, decompress: bool) -> Result<()> {
+    if !fts_exists(conn)? || decompression_of(conn)? == decompress {
+        return Ok(());
+    }
+    log::debug!("FTS5 triggers decompress content: {}", decompress);
+    let tx = conn.unchecked_transaction()?;
+    write_view(&tx, decompress)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Drop the triggers that keep the FTS5 index in sync, so writes no longer
+/// update it (see [`super::bulk`]).
+pub(crate) fn suspend_sync(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "DROP TRIGGER IF EXISTS memories_fts_insert;
+         DROP TRIGGER IF EXISTS memories_fts_delete;
+         DROP TRIGGER IF EXISTS memories_fts_update;",
+    )
+}
+
+/// Recreate the triggers dropped by [`suspend_sync`] and rebuild the index
+/// from the memories table, catching up on the writes made meanwhile.
+pub(crate) fn resume_sync(conn: &Connection) -> rusqlite::Result<()> {
+    let tokenizer = tokenizer_of(conn)?.unwrap_or_default();
+    let normalization = normalization_of(conn)?;
+    let decompress = decompression_of(conn)?;
+    conn.execute_batch(&format!(
+        "{}
+         INSERT INTO memories_fts(memories_fts) VALUES('rebuild');",
+        fts_schema(tokenizer, normalization, decompress)
+    ))
+}
+
+/// Whether the database has an FTS5 index.
+pub(super) fn fts_exists(conn: &Connection) -> rusqlite::Result<bool> {
+    conn.prepare("SELECT 1 FROM sqlite_master WHERE type='table' AND name='memories_fts'")?
+        .exists([])
+}
+
+/// Create the FTS5 index with `tokenizer` unless it exists, indexing the
+/// memories of a database last opened without one.
+pub(crate) fn create_fts(conn: &Connection, tokenizer: FtsTokenizer) -> Result<()> {
+    let existed = fts_exists(conn)?;
+    let normalization = normalization_of(conn)?;
+    let decompress = decompression_of(conn)?;
+    conn.execute_batch(&fts_schema(tokenizer, normalization, decompress))?;
+    if !existed {
+        conn.execute(
+            "INSERT INTO memories_fts(memories_fts) VALUES('rebuild')",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// Drop the FTS5 index and its triggers, so writes no longer maintain it.
+pub(crate) fn drop_fts(conn: &Connection) -> Result<()> {
+    if fts_exists(conn)? {
+        log::info!("Dropping the FTS5 index (full-text search is disabled)");
+        conn.execute_batch(DROP_FTS)?;
+    }
+    Ok(())
+}
+
+/// Recreate an FTS5 index created before metadata fields were indexed,
+/// keeping its tokenizer.
+pub(crate) fn add_metadata_fields(conn: &Connection) -> Result<()> {
+    let has_fields = conn
+        .prepare("SELECT 1 FROM pragma_table_info('memories_fts') WHERE name = 'metadata_fields'")?
+        .exists([])?;
+    if has_fields {
+        return Ok(());
+    }
+    log::info!("Migrating FTS5 index to add metadata fields");
+    let tokenizer = tokenizer_of(conn)?.unwrap_or_default();
+    let tx = conn.unchecked_transaction()?;
+    recreate_fts(&tx, tokenizer, TextNormalization::default())
+        .map_err(|e| Error::Sqlite(format!("FTS5 schema migration failed: {}", e)))?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Recreate an FTS5 index created before memories had titles, so titles
+/// are indexed with the metadata fields, keeping its tokenizer.
+pub(crate) fn index_titles(conn: &Connection) -> Result<()> {
+    if view_sql(conn)?.is_none_or(|sql| sql.contains("coalesce(title")) {
+        return Ok(());
+    }
+    log::info!("Migrating FTS5 index to index titles");
+    let tokenizer = tokenizer_of(conn)?.unwrap_or_default();
+    let tx = conn.unchecked_transaction()?;
+    recreate_fts(&tx, tokenizer, TextNormalization::default())
+        .map_err(|e| Error::Sqlite(format!("FTS5 schema migration failed: {}", e)))?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Whether the FTS5 source view reads content through `vipune_content`
+/// (see [`fts_schema`]); without a view, whether any row is compressed.
+pub(crate) fn decompression_of(conn: &Connection) -> rusqlite::Result<bool> {
+    match view_sql(conn)? {
+        Some(sql) => Ok(sql.contains("vipune_content(")),
+        None => super::super::compression::has_compressed_rows(conn),
+    }
+}
+
+/// SQL of the FTS5 source view (`None` if there is no index).
+pub(super) fn view_sql(conn: &Connection) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type='view' AND name='memories_fts_source'",
+        [],
+        |row| row.get(0),
+    )
+    .optional()
+}
@@ -0,0 +1,302 @@
+//! Creating, migrating and repairing the FTS5 index.
+
+use super::*;
+
+#[test]
+fn test_initialize_fts_migration() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    {
+        let db = Database::open(&path).unwrap();
+        db.insert("proj1", "before migration", &vec![0.1f32; 384], None)
+            .unwrap();
+    }
+
+    {
+        let db = Database::open(&path).unwrap();
+        db.initialize_fts().unwrap();
+        assert_eq!(db.search_bm25("before", "proj1", 10).unwrap().len(), 1);
+    }
+}
+
+#[test]
+fn test_open_without_fts_drops_and_rebuilds_index() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let embedding = vec![0.1f32; 384];
+    Database::open(&path)
+        .unwrap()
+        .insert("proj1", "indexed before", &embedding, None)
+        .unwrap();
+
+    {
+        let db = Database::open_without_fts(&path).unwrap();
+        assert!(!db.fts_enabled());
+        assert!(!fts_exists(db.conn()).unwrap());
+        db.insert("proj1", "written without index", &embedding, None)
+            .unwrap();
+        assert!(matches!(
+            db.search_bm25("written", "proj1", 10),
+            Err(Error::FtsDisabled)
+        ));
+        assert!(matches!(db.rebuild_fts(), Err(Error::FtsDisabled)));
+    }
+
+    let db = Database::open(&path).unwrap();
+    assert_eq!(db.fts_row_counts().unwrap(), (2, 2));
+    assert_eq!(db.search_bm25("written", "proj1", 10).unwrap().len(), 1);
+    assert_eq!(db.search_bm25("before", "proj1", 10).unwrap().len(), 1);
+}
+
+#[test]
+fn test_initialize_fts_consistency_handling() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    std::mem::forget(dir);
+
+    // Initial data with 3 rows
+    {
+        let db = Database::open(&path).unwrap();
+        db.insert("proj1", "first", &vec![0.1f32; 384], None)
+            .unwrap();
+        db.insert("proj1", "second", &vec![0.1f32; 384], None)
+            .unwrap();
+        db.insert("proj1", "third", &vec![0.1f32; 384], None)
+            .unwrap();
+    }
+
+    // FTS migration
+    {
+        let db = Database::open(&path).unwrap();
+        db.initialize_fts().unwrap();
+
+        let fts_count: i64 = db
+            .conn()
+            .query_row("SELECT COUNT(*) FROM memories_fts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(fts_count, 3);
+    }
+
+    // Call initialize_fts again - should handle consistent state gracefully
+    {
+        let db = Database::open(&path).unwrap();
+        db.initialize_fts().unwrap();
+
+        assert_eq!(db.search_bm25("first", "proj1", 10).unwrap().len(), 1);
+        assert_eq!(db.search_bm25("second", "proj1", 10).unwrap().len(), 1);
+        assert_eq!(db.search_bm25("third", "proj1", 10).unwrap().len(), 1);
+    }
+}
+
+#[test]
+fn test_fts_row_counts_detect_drift() {
+    let db = create_test_db();
+    db.insert("proj1", "first", &vec![0.1f32; 384], None)
+        .unwrap();
+    db.insert("proj1", "second", &vec![0.1f32; 384], None)
+        .unwrap();
+    assert_eq!(db.fts_row_counts().unwrap(), (2, 2));
+
+    db.conn()
+        .execute_batch("DROP TRIGGER memories_fts_insert;")
+        .unwrap();
+    db.insert("proj1", "third", &vec![0.1f32; 384], None)
+        .unwrap();
+    assert_eq!(db.fts_row_counts().unwrap(), (3, 2));
+}
+
+fn drop_sync_triggers(db: &Database) {
+    db.conn()
+        .execute_batch(
+            "DROP TRIGGER memories_fts_insert;
+             DROP TRIGGER memories_fts_update;",
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_open_rebuilds_drifted_index() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    {
+        let db = Database::open(&path).unwrap();
+        db.insert("proj1", "indexed", &vec![0.1f32; 384], None)
+            .unwrap();
+        drop_sync_triggers(&db);
+        db.insert("proj1", "unindexed", &vec![0.1f32; 384], None)
+            .unwrap();
+        assert_eq!(db.fts_row_counts().unwrap(), (2, 1));
+    }
+
+    let db = Database::open(&path).unwrap();
+    assert_eq!(db.fts_row_counts().unwrap(), (2, 2));
+    assert_eq!(db.search_bm25("unindexed", "proj1", 10).unwrap().len(), 1);
+    assert!(!db.verify_fts(FtsCheck::default()).unwrap());
+}
+
+#[test]
+fn test_open_without_auto_repair_leaves_index() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    {
+        let db = Database::open(&path).unwrap();
+        drop_sync_triggers(&db);
+        db.insert("proj1", "unindexed", &vec![0.1f32; 384], None)
+            .unwrap();
+    }
+
+    let check = FtsCheck {
+        integrity_check: false,
+        auto_repair: false,
+    };
+    let db = Database::open_with(&path, check, FtsTokenizer::default()).unwrap();
+    assert_eq!(db.fts_row_counts().unwrap(), (1, 0));
+}
+
+#[test]
+fn test_integrity_check_detects_stale_content() {
+    let db = create_test_db();
+    let id = db
+        .insert("proj1", "original words", &vec![0.1f32; 384], None)
+        .unwrap();
+    drop_sync_triggers(&db);
+    db.conn()
+        .execute(
+            "UPDATE memories SET content = 'replacement text' WHERE id = ?1",
+            [&id],
+        )
+        .unwrap();
+
+    // Counts still match, so only the integrity check notices
+    let counts_only = FtsCheck {
+        integrity_check: false,
+        auto_repair: true,
+    };
+    assert!(!db.verify_fts(counts_only).unwrap());
+    assert!(!db.fts_integrity_ok().unwrap());
+
+    let deep = FtsCheck {
+        integrity_check: true,
+        auto_repair: true,
+    };
+    assert!(db.verify_fts(deep).unwrap());
+    assert!(db.fts_integrity_ok().unwrap());
+    assert_eq!(db.search_bm25("replacement", "proj1", 10).unwrap().len(), 1);
+}
+
+#[test]
+fn test_reindex_switches_tokenizer() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "running the nightly jobs", &embedding, None)
+        .unwrap();
+    db.insert("proj1", "本番環境へのデプロイ", &embedding, None)
+        .unwrap();
+    assert_eq!(db.fts_tokenizer().unwrap(), Some(FtsTokenizer::Porter));
+    let count = |query: &str| db.search_bm25(query, "proj1", 10).unwrap().len();
+    assert_eq!(count("run"), 1);
+    assert_eq!(count("本番環境"), 0);
+
+    assert_eq!(
+        db.reindex_fts(FtsTokenizer::Unicode61, TextNormalization::default())
+            .unwrap(),
+        2
+    );
+    assert_eq!(db.fts_tokenizer().unwrap(), Some(FtsTokenizer::Unicode61));
+    assert_eq!(count("run"), 0);
+    assert_eq!(count("running"), 1);
+
+    assert_eq!(
+        db.reindex_fts(FtsTokenizer::Trigram, TextNormalization::default())
+            .unwrap(),
+        2
+    );
+    assert_eq!(db.fts_tokenizer().unwrap(), Some(FtsTokenizer::Trigram));
+    assert_eq!(count("本番環境"), 1);
+    assert_eq!(count("nightly"), 1);
+
+    // The recreated triggers keep the new index in sync
+    db.insert("proj1", "本番環境の設定", &embedding, None)
+        .unwrap();
+    assert_eq!(count("本番環境"), 2);
+    assert_eq!(db.fts_row_counts().unwrap(), (3, 3));
+}
+
+#[test]
+fn test_normalized_index() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let normalization = TextNormalization {
+        nfkc: true,
+        case_fold: true,
+        strip_accents: true,
+    };
+    let embedding = vec![0.1f32; 384];
+    {
+        let db = Database::open(&path).unwrap();
+        db.insert("proj1", "Ｃａｆé Crème", &embedding, None)
+            .unwrap();
+        // The default unicode61 tokenizer folds case and accents, not width
+        assert!(db.search_bm25("cafe", "proj1", 10).unwrap().is_empty());
+
+        assert_eq!(
+            db.reindex_fts(FtsTokenizer::Trigram, normalization)
+                .unwrap(),
+            1
+        );
+        assert_eq!(db.fts_normalization().unwrap(), normalization);
+    }
+
+    // The recorded normalization survives reopening
+    let db = Database::open_with(&path, FtsCheck::default(), FtsTokenizer::Trigram).unwrap();
+    assert_eq!(db.fts_normalization().unwrap(), normalization);
+    let id = db
+        .insert("proj1", "CAFÉ au lait", &embedding, None)
+        .unwrap();
+    let count = |query: &str| db.search_bm25(query, "proj1", 10).unwrap().len();
+    assert_eq!(count("cafe"), 2);
+    assert_eq!(count("Ｃａｆé"), 2);
+    assert_eq!(count("creme"), 1);
+    // Results show the stored content, not the normalized form
+    assert_eq!(
+        db.search_bm25("lait", "proj1", 10).unwrap()[0].content,
+        "CAFÉ au lait"
+    );
+
+    // Deletes remove the normalized entries
+    db.delete(&id).unwrap();
+    assert_eq!(count("lait"), 0);
+    assert!(db.fts_integrity_ok().unwrap());
+}
+
+#[test]
+fn test_new_database_uses_configured_tokenizer() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let db = Database::open_with(&path, FtsCheck::default(), FtsTokenizer::Unicode61).unwrap();
+    assert_eq!(db.fts_tokenizer().unwrap(), Some(FtsTokenizer::Unicode61));
+    drop(db);
+
+    // An existing index keeps its tokenizer until reindexed
+    let db = Database::open(&path).unwrap();
+    assert_eq!(db.fts_tokenizer().unwrap(), Some(FtsTokenizer::Unicode61));
+    assert_eq!(
+        Database::open_in_memory_with(FtsTokenizer::Trigram)
+            .unwrap()
+            .fts_tokenizer()
+            .unwrap(),
+        Some(FtsTokenizer::Trigram)
+    );
+}
+
+#[test]
+fn test_tokenizer_from_str() {
+    assert_eq!(
+        " Trigram ".parse::<FtsTokenizer>().unwrap(),
+        FtsTokenizer::Trigram
+    );
+    assert_eq!(FtsTokenizer::Unicode61.to_string(), "unicode61");
+    assert!("snowball".parse::<FtsTokenizer>().is_err());
+}
@@ -0,0 +1,18 @@
+//! Tests for FTS5 search and index maintenance.
+
+mod index;
+mod search;
+
+use std::collections::HashMap;
+
+use super::schema::fts_exists;
+use super::*;
+use tempfile::TempDir;
+
+fn create_test_db() -> Database {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let db = Database::open(&path).unwrap();
+    std::mem::forget(dir);
+    db
+}
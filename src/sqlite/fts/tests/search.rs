@@ -0,0 +1,292 @@
+//! Keyword search over the FTS5 index.
+
+use super::*;
+
+#[test]
+fn test_fts5_search() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "rust programming", &embedding, None)
+        .unwrap();
+    db.insert("proj1", "python data", &embedding, None).unwrap();
+
+    let results = db.search_bm25("rust", "proj1", 10).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].content.contains("rust"));
+}
+
+#[test]
+fn test_fts5_triggers() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let id = db
+        .insert("proj1", "original text", &embedding, None)
+        .unwrap();
+
+    assert_eq!(db.search_bm25("original", "proj1", 10).unwrap().len(), 1);
+
+    db.update(&id, "updated text", &embedding).unwrap();
+    assert_eq!(db.search_bm25("updated", "proj1", 10).unwrap().len(), 1);
+
+    db.delete(&id).unwrap();
+    assert_eq!(db.search_bm25("updated", "proj1", 10).unwrap().len(), 0);
+}
+
+#[test]
+fn test_fts5_limit_validation() {
+    let db = create_test_db();
+    assert!(db.search_bm25("test", "proj1", 0).is_err());
+    assert!(
+        db.search_bm25("test", "proj1", crate::memory::store::SEARCH_LIMIT_CAP + 1)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_fts5_special_characters() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "test with \"quotes\"", &embedding, None)
+        .unwrap();
+    db.insert("proj1", "test with 'apos'", &embedding, None)
+        .unwrap();
+    db.insert("proj1", "test with\\slash", &embedding, None)
+        .unwrap();
+
+    assert_eq!(
+        db.search_bm25("test with \"quotes\"", "proj1", 10)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Test that backslash in query is properly escaped
+    assert_eq!(
+        db.search_bm25("test with\\slash", "proj1", 10)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_fts5_empty_query() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "test content", &embedding, None)
+        .unwrap();
+
+    // Empty query returns no results
+    let results = db.search_bm25("", "proj1", 10).unwrap();
+    assert_eq!(results.len(), 0);
+}
+
+#[test]
+fn test_fts5_expands_synonyms() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "kubernetes cluster upgrade", &embedding, None)
+        .unwrap();
+    db.insert("proj1", "machine learning pipeline", &embedding, None)
+        .unwrap();
+    db.insert("proj2", "kubernetes elsewhere", &embedding, None)
+        .unwrap();
+    assert!(db.search_bm25("k8s", "proj1", 10).unwrap().is_empty());
+
+    db.add_synonyms("proj1", "k8s", &["kubernetes"]).unwrap();
+    db.add_synonyms("proj1", "ml", &["machine learning"])
+        .unwrap();
+    let results = db.search_bm25("K8s upgrade", "proj1", 10).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].content.contains("kubernetes"));
+    assert_eq!(db.search_bm25("ml", "proj1", 10).unwrap().len(), 1);
+    // Synonyms of one project don't expand another's queries
+    assert!(db.search_bm25("k8s", "proj2", 10).unwrap().is_empty());
+
+    assert_eq!(
+        Database::expand_fts_query(
+            &keyword_terms("k8s \"x", QuerySyntax::Plain).unwrap(),
+            &HashMap::from([("k8s".to_string(), vec!["kube ctl".to_string()])])
+        ),
+        r#"("k8s" OR "kube ctl") AND """x""#
+    );
+}
+
+#[test]
+fn test_fts5_exclusions() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let mysql = db
+        .insert("proj1", "database tuning for mysql", &embedding, None)
+        .unwrap();
+    db.insert("proj1", "database tuning for postgres", &embedding, None)
+        .unwrap();
+    db.insert(
+        "proj1",
+        "database tuning with connection pool",
+        &embedding,
+        None,
+    )
+    .unwrap();
+
+    let search = |exclude: &[&str]| {
+        let exclude: Vec<String> = exclude.iter().map(|t| t.to_string()).collect();
+        db.search_bm25_filtered(
+            "database tuning",
+            "proj1",
+            10,
+            &SearchFilter::default(),
+            &exclude,
+            FieldWeights::default(),
+        )
+        .unwrap()
+    };
+    assert_eq!(search(&[]).len(), 3);
+    assert_eq!(search(&["postgres"]).len(), 2);
+    // Every word of a term must be present for it to exclude a memory
+    assert_eq!(search(&["connection pool"]).len(), 2);
+    assert_eq!(search(&["connection postgres"]).len(), 3);
+    // Terms are escaped like queries, and blank terms are ignored
+    assert_eq!(search(&["\"postgres\""]).len(), 2);
+    let results = search(&["postgres", "connection", "  "]);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, mysql);
+}
+
+#[test]
+fn test_fts5_prefix_and_column_syntax() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "authentication flow", &embedding, None)
+        .unwrap();
+    db.insert(
+        "proj1",
+        "release notes",
+        &embedding,
+        Some(r#"{"tags": ["authorization"]}"#),
+    )
+    .unwrap();
+    db.add_synonyms("proj1", "login", &["authentication"])
+        .unwrap();
+
+    let search = |query: &str, syntax: QuerySyntax| {
+        db.search_bm25_with_syntax(
+            query,
+            syntax,
+            "proj1",
+            10,
+            &SearchFilter::default(),
+            &[],
+            FieldWeights::default(),
+        )
+    };
+    let count = |query: &str, syntax: QuerySyntax| search(query, syntax).unwrap().len();
+    // Plain queries match whole words, with `*` and `:` as text
+    assert_eq!(count("auth", QuerySyntax::Plain), 0);
+    assert_eq!(count("auth*", QuerySyntax::Plain), 0);
+    assert_eq!(count("auth", QuerySyntax::Prefix), 2);
+    assert_eq!(count("auth flo", QuerySyntax::Prefix), 1);
+    assert_eq!(count("auth*", QuerySyntax::Fts), 2);
+    assert_eq!(count("content:auth*", QuerySyntax::Fts), 1);
+    assert_eq!(count("METADATA:autho*", QuerySyntax::Fts), 1);
+    assert_eq!(count("content:autho*", QuerySyntax::Fts), 0);
+    // Synonyms keep the word's prefix and column
+    assert_eq!(count("content:login", QuerySyntax::Fts), 1);
+    assert_eq!(count("metadata:login", QuerySyntax::Fts), 1);
+    assert_eq!(count("content:login notes", QuerySyntax::Fts), 0);
+
+    for query in ["*", "auth**", "a*th", "title:auth", "content:", "content:*"] {
+        let err = search(query, QuerySyntax::Fts).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid keyword query"),
+            "{query:?}: {err}"
+        );
+    }
+}
+
+#[test]
+fn test_keyword_terms_render_quoted() {
+    let render = |query: &str, syntax: QuerySyntax| {
+        Database::expand_fts_query(&keyword_terms(query, syntax).unwrap(), &HashMap::new())
+    };
+    assert_eq!(render("a* b", QuerySyntax::Plain), r#""a*" "b""#);
+    assert_eq!(render("a\"b c", QuerySyntax::Prefix), r#""a""b"* "c"*"#);
+    assert_eq!(
+        render("metadata:x* content:\"y\" :z", QuerySyntax::Fts),
+        r#"metadata_fields:"x"* AND content:"""y""" AND ":z""#
+    );
+}
+
+#[test]
+fn test_fts5_weights_metadata_fields() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let body = db
+        .insert(
+            "proj1",
+            "notes on deploys, where the cache is mentioned once among many other words",
+            &embedding,
+            None,
+        )
+        .unwrap();
+    let titled = db
+        .insert(
+            "proj1",
+            "short deploy notes",
+            &embedding,
+            Some(r#"{"title": "cache invalidation", "tags": ["redis"]}"#),
+        )
+        .unwrap();
+
+    let search = |query: &str, content: f64, metadata: f64| {
+        db.search_bm25_filtered(
+            query,
+            "proj1",
+            10,
+            &SearchFilter::default(),
+            &[],
+            FieldWeights { content, metadata },
+        )
+        .unwrap()
+    };
+    // Tags are indexed too, and a title match outranks a body match
+    assert_eq!(search("redis", 1.0, 2.0)[0].id, titled);
+    let results = search("cache", 1.0, 2.0);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].id, titled);
+    // Without metadata weight only the body matches score
+    assert_eq!(search("cache", 1.0, 0.0)[0].id, body);
+
+    // Updating metadata reindexes it
+    db.update_metadata(&titled, Some(r#"{"title": "other"}"#))
+        .unwrap();
+    assert!(search("redis", 1.0, 2.0).is_empty());
+}
+
+#[test]
+fn test_fts5_phrase_search() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "rust programming", &embedding, None)
+        .unwrap();
+    db.insert("proj1", "rust error handling", &embedding, None)
+        .unwrap();
+
+    // Multi-word phrase should find matching content
+    let results = db.search_bm25("rust programming", "proj1", 10).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].content.contains("programming"));
+}
+
+#[test]
+fn test_fts5_unicode_text() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "café résumé 日本語", &embedding, None)
+        .unwrap();
+
+    // Test basic Unicode matching
+    let results = db.search_bm25("café", "proj1", 10).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].content.contains("café"));
+}
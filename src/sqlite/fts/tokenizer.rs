@@ -0,0 +1,138 @@
+//! FTS5 tokenizers and the text normalization applied before indexing.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::Result;
+use super::schema::view_sql;
+use crate::normalize::TextNormalization;
+
+/// Tokenizer of the FTS5 index that keyword (BM25) search runs on.
+///
+/// Chosen when a database is created; [`Database::reindex_fts`](crate::sqlite::Database::reindex_fts) switches an
+/// existing one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FtsTokenizer {
+    /// Unicode word splitting with English Porter stemming, so "running"
+    /// matches "run". Stemming mangles words of other languages.
+    #[default]
+    Porter,
+    /// Unicode word splitting without stemming, for non-English text such
+    /// as Finnish.
+    Unicode61,
+    /// Overlapping three-character sequences, for text without spaces
+    /// between words such as Chinese or Japanese. Query words shorter than
+    /// three characters match nothing.
+    Trigram,
+}
+
+impl FtsTokenizer {
+    /// Lowercase name, as in the config file.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FtsTokenizer::Porter => "porter",
+            FtsTokenizer::Unicode61 => "unicode61",
+            FtsTokenizer::Trigram => "trigram",
+        }
+    }
+
+    /// Value of the FTS5 `tokenize` option.
+    pub(super) fn tokenize_arg(self) -> &'static str {
+        match self {
+            FtsTokenizer::Porter => "porter unicode61",
+            FtsTokenizer::Unicode61 => "unicode61",
+            FtsTokenizer::Trigram => "trigram",
+        }
+    }
+}
+
+impl fmt::Display for FtsTokenizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FtsTokenizer {
+    type Err = crate::errors::Error;
+
+    /// Parse `porter`, `unicode61` or `trigram`, ignoring case and
+    /// surrounding whitespace.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "porter" => Ok(Self::Porter),
+            "unicode61" => Ok(Self::Unicode61),
+            "trigram" => Ok(Self::Trigram),
+            _ => Err(crate::errors::Error::InvalidInput(format!(
+                "Invalid FTS tokenizer '{}' (expected one of: porter, unicode61, trigram)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Register the `vipune_normalize(text, steps)` SQL function, which applies
+/// the [`TextNormalization`] steps named in `steps` (see
+/// [`TextNormalization::spec`]) and passes NULL through.
+pub(crate) fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "vipune_normalize",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let text: Option<String> = ctx.get(0)?;
+            let steps: String = ctx.get(1)?;
+            Ok(text.map(|text| TextNormalization::from_spec(&steps).apply(&text)))
+        },
+    )?;
+    Ok(())
+}
+
+/// `expr` wrapped in `vipune_normalize` unless `normalization` does nothing.
+pub(super) fn normalized_expr(expr: String, normalization: TextNormalization) -> String {
+    if normalization.is_identity() {
+        expr
+    } else {
+        format!("vipune_normalize({}, '{}')", expr, normalization.spec())
+    }
+}
+
+/// Tokenizer the FTS5 index was created with (`None` if there is no index
+/// or it uses a tokenizer vipune does not know).
+pub(super) fn tokenizer_of(conn: &Connection) -> rusqlite::Result<Option<FtsTokenizer>> {
+    let sql: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE type='table' AND name='memories_fts'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(sql.and_then(|sql| {
+        let (_, rest) = sql.split_once("tokenize='")?;
+        let (arg, _) = rest.split_once('\'')?;
+        [
+            FtsTokenizer::Porter,
+            FtsTokenizer::Unicode61,
+            FtsTokenizer::Trigram,
+        ]
+        .into_iter()
+        .find(|t| t.tokenize_arg() == arg)
+    }))
+}
+
+/// Normalization the FTS5 index was created with, read from its source
+/// view (none if there is no index or it predates normalization).
+pub(super) fn normalization_of(conn: &Connection) -> rusqlite::Result<TextNormalization> {
+    Ok(view_sql(conn)?
+        .and_then(|sql| {
+            let (_, rest) = sql.split_once("vipune_normalize(")?;
+            let (_, rest) = rest.split_once(", '")?;
+            let (spec, _) = rest.split_once('\'')?;
+            Some(TextNormalization::from_spec(spec))
+        })
+        .unwrap_or_default())
+}
@@ -0,0 +1,146 @@
+//! Earlier versions of memory content, kept when an update replaces it.
+//!
+//! [`Database::update`] and [`Database::replace_record`] (and with them
+//! `append`, `edit`, stable-ID re-adds and sync) copy the content and
+//! metadata being replaced to `memory_history` in the same transaction, so
+//! `vipune history` can show how a memory changed. Versions go with their
+//! memory: triggers drop them once the memory is gone from the live table,
+//! the trash and the archive alike.
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use super::{Database, Result};
+
+/// A replaced version of a memory.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MemoryVersion {
+    /// Monotonic sequence number.
+    pub seq: i64,
+    /// Content of the version.
+    pub content: String,
+    /// Metadata of the version (JSON string), if any.
+    pub metadata: Option<String>,
+    /// When the version was written (RFC3339).
+    pub updated_at: String,
+    /// When an update replaced it (RFC3339).
+    pub replaced_at: String,
+}
+
+/// Schema for the version table and the triggers that forget the versions
+/// of deleted memories.
+pub(super) const HISTORY_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS memory_history (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        memory_id TEXT NOT NULL,
+        content TEXT NOT NULL,
+        content_zstd BLOB,
+        metadata TEXT,
+        updated_at TEXT NOT NULL,
+        replaced_at TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_memory_history_memory ON memory_history(memory_id, seq);
+
+    CREATE TRIGGER IF NOT EXISTS memory_history_forget
+    AFTER DELETE ON memories
+    WHEN OLD.parent_id IS NULL
+    BEGIN
+        DELETE FROM memory_history WHERE memory_id = OLD.id
+            AND NOT EXISTS (SELECT 1 FROM memories_trash WHERE id = OLD.id)
+            AND NOT EXISTS (SELECT 1 FROM memories_archive WHERE id = OLD.id);
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS memory_history_forget_trashed
+    AFTER DELETE ON memories_trash
+    WHEN OLD.parent_id IS NULL
+    BEGIN
+        DELETE FROM memory_history WHERE memory_id = OLD.id
+            AND NOT EXISTS (SELECT 1 FROM memories WHERE id = OLD.id);
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS memory_history_forget_archived
+    AFTER DELETE ON memories_archive
+    BEGIN
+        DELETE FROM memory_history WHERE memory_id = OLD.id
+            AND NOT EXISTS (SELECT 1 FROM memories WHERE id = OLD.id);
+    END;
+"#;
+
+/// Keep the current content and metadata of memory `id` as a version
+/// replaced at `replaced_at`, unless its content equals `content`.
+///
+/// Must run inside the update's transaction, before the write.
+pub(super) fn record(conn: &Connection, id: &str, content: &str, replaced_at: &str) -> Result<()> {
+    conn.execute(
+        r#"
+        INSERT INTO memory_history (memory_id, content, content_zstd, metadata, updated_at,
+                                    replaced_at)
+        SELECT id, content, content_zstd, metadata, updated_at, ?3
+        FROM memories
+        WHERE id = ?1 AND parent_id IS NULL
+          AND vipune_content(content, content_zstd) IS NOT ?2
+        "#,
+        rusqlite::params![id, content, replaced_at],
+    )?;
+    Ok(())
+}
+
+impl Database {
+    /// Earlier versions of memory `id`, oldest first (empty if its content
+    /// was never updated or it doesn't exist).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn history(&self, id: &str) -> Result<Vec<MemoryVersion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT seq, vipune_content(content, content_zstd), metadata, updated_at, replaced_at
+             FROM memory_history WHERE memory_id = ?1 ORDER BY seq",
+        )?;
+        let versions = stmt
+            .query_map([id], |row| {
+                Ok(MemoryVersion {
+                    seq: row.get(0)?,
+                    content: row.get(1)?,
+                    metadata: row.get(2)?,
+                    updated_at: row.get(3)?,
+                    replaced_at: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::axis;
+
+    #[test]
+    fn test_updates_keep_earlier_versions() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db
+            .insert("proj", "use postgres", &axis(0), Some(r#"{"a": 1}"#))
+            .unwrap();
+        assert!(db.history(&id).unwrap().is_empty());
+
+        db.update(&id, "use postgres 16", &axis(0)).unwrap();
+        // Writing the same content again is not a new version
+        db.update(&id, "use postgres 16", &axis(0)).unwrap();
+        db.update(&id, "use sqlite", &axis(1)).unwrap();
+        let versions = db.history(&id).unwrap();
+        let contents: Vec<&str> = versions.iter().map(|v| v.content.as_str()).collect();
+        assert_eq!(contents, ["use postgres", "use postgres 16"]);
+        assert_eq!(versions[0].metadata.as_deref(), Some(r#"{"a": 1}"#));
+
+        // Trashed memories keep their versions until purged
+        db.trash(&id).unwrap();
+        assert_eq!(db.history(&id).unwrap().len(), 2);
+        db.restore(&id).unwrap();
+        assert_eq!(db.history(&id).unwrap().len(), 2);
+        db.delete(&id).unwrap();
+        assert!(db.history(&id).unwrap().is_empty());
+    }
+}
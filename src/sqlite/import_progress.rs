@@ -0,0 +1,130 @@
+//! Staging table tracking records already handled by an in-progress import.
+//!
+//! `vipune import` checkpoints the keys of processed source records here, per
+//! source file, so an interrupted import can be resumed with `--resume`.
+//! Rows for a source are removed once its import completes. An exclusive
+//! import holds the write lock for its whole run.
+
+use std::collections::HashSet;
+
+use super::{Database, Result};
+
+/// Schema for the import staging table.
+pub(super) const IMPORT_PROGRESS_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS import_progress (
+        source TEXT NOT NULL,
+        record_key TEXT NOT NULL,
+        PRIMARY KEY (source, record_key)
+    );
+"#;
+
+impl Database {
+    /// Keys of records from `source` processed by an earlier, unfinished import.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn imported_keys(&self, source: &str) -> Result<HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT record_key FROM import_progress WHERE source = ?1")?;
+        let keys = stmt
+            .query_map([source], |row| row.get(0))?
+            .collect::<std::result::Result<HashSet<String>, _>>()?;
+        Ok(keys)
+    }
+
+    /// Record `keys` from `source` as processed, in a single transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write fails.
+    pub fn checkpoint_import(&self, source: &str, keys: &[String]) -> Result<()> {
+        let tx = self.write_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO import_progress (source, record_key) VALUES (?1, ?2)",
+            )?;
+            for key in keys {
+                stmt.execute([source, key])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Take the write lock and start a transaction that every following
+    /// write joins, until [`Database::end_exclusive`].
+    ///
+    /// Other connections cannot write meanwhile; they wait for their busy
+    /// timeout and then fail with `SQLITE_BUSY`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the lock cannot be taken or a transaction is
+    /// already open.
+    pub fn begin_exclusive(&self) -> Result<()> {
+        Ok(self.conn.execute_batch("BEGIN IMMEDIATE")?)
+    }
+
+    /// Commit (or, with `commit` false, roll back) the transaction started by
+    /// [`Database::begin_exclusive`] and release the write lock.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if no transaction is open or the commit fails.
+    pub fn end_exclusive(&self, commit: bool) -> Result<()> {
+        let statement = if commit { "COMMIT" } else { "ROLLBACK" };
+        Ok(self.conn.execute_batch(statement)?)
+    }
+
+    /// Forget the import progress recorded for `source`.
+    ///
+    /// Returns the number of keys removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the delete fails.
+    pub fn clear_import_progress(&self, source: &str) -> Result<usize> {
+        Ok(self
+            .conn
+            .execute("DELETE FROM import_progress WHERE source = ?1", [source])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_checkpoint_and_read_back_per_source() {
+        let db = Database::open_in_memory().unwrap();
+        db.checkpoint_import("a.jsonl", &keys(&["k1", "k2"]))
+            .unwrap();
+        db.checkpoint_import("a.jsonl", &keys(&["k2", "k3"]))
+            .unwrap();
+        db.checkpoint_import("b.jsonl", &keys(&["k1"])).unwrap();
+
+        let done = db.imported_keys("a.jsonl").unwrap();
+        assert_eq!(done.len(), 3);
+        assert!(done.contains("k3"));
+        assert_eq!(db.imported_keys("b.jsonl").unwrap().len(), 1);
+        assert!(db.imported_keys("c.jsonl").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clear_only_affects_one_source() {
+        let db = Database::open_in_memory().unwrap();
+        db.checkpoint_import("a.jsonl", &keys(&["k1", "k2"]))
+            .unwrap();
+        db.checkpoint_import("b.jsonl", &keys(&["k1"])).unwrap();
+
+        assert_eq!(db.clear_import_progress("a.jsonl").unwrap(), 2);
+        assert!(db.imported_keys("a.jsonl").unwrap().is_empty());
+        assert_eq!(db.imported_keys("b.jsonl").unwrap().len(), 1);
+    }
+}
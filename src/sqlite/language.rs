@@ -0,0 +1,52 @@
+//! Detected language of each memory's content.
+//!
+//! Like access tracking, this is bookkeeping derived from the content: it
+//! leaves `updated_at` alone and is not written to the audit log.
+
+use rusqlite::params;
+
+use super::{Database, Result};
+
+impl Database {
+    /// Set the detected language (ISO 639-3 code) of a memory, or clear it
+    /// with `None`. Returns `false` if the memory doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the update fails.
+    pub fn set_language(&self, id: &str, language: Option<&str>) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE memories SET language = ?2 WHERE id = ?1",
+            params![id, language],
+        )?;
+        Ok(updated > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_language_without_editing() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+        let id = db
+            .insert("proj", "hyvää huomenta", &embedding, None)
+            .unwrap();
+        let before = db.get(&id).unwrap().unwrap();
+
+        assert!(db.set_language(&id, Some("fin")).unwrap());
+        assert!(!db.set_language("missing", Some("fin")).unwrap());
+        assert_eq!(
+            db.language_counts("proj").unwrap(),
+            vec![("fin".to_string(), 1)]
+        );
+
+        assert_eq!(db.get(&id).unwrap().unwrap().updated_at, before.updated_at);
+        assert_eq!(db.audit_log("proj", None, 10).unwrap().len(), 1);
+
+        assert!(db.set_language(&id, None).unwrap());
+        assert!(db.language_counts("proj").unwrap().is_empty());
+    }
+}
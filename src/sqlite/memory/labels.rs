@@ -0,0 +1,311 @@
+//! Kind, origin and visibility labels stored with each memory.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+
+/// Category of a memory, stored in the indexed `kind` column.
+///
+/// Serialized and parsed as lowercase names (`"decision"`).
+///
+/// # Example
+///
+/// ```
+/// use vipune::MemoryKind;
+///
+/// let kind: MemoryKind = "Decision".parse().unwrap();
+/// assert_eq!(kind, MemoryKind::Decision);
+/// assert_eq!(kind.to_string(), "decision");
+/// assert!("opinion".parse::<MemoryKind>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[schemars(description = "Category of a memory.")]
+pub enum MemoryKind {
+    /// Something that is true about the world or the project.
+    Fact,
+    /// How a user or team likes things done.
+    Preference,
+    /// A choice that was made, usually with its rationale.
+    Decision,
+    /// Work to be done.
+    Task,
+    /// Anything else (the default).
+    #[default]
+    Other,
+}
+
+impl MemoryKind {
+    /// All kinds, in declaration order.
+    pub const ALL: [MemoryKind; 5] = [
+        MemoryKind::Fact,
+        MemoryKind::Preference,
+        MemoryKind::Decision,
+        MemoryKind::Task,
+        MemoryKind::Other,
+    ];
+
+    /// Lowercase name used in the database, CLI and JSON.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MemoryKind::Fact => "fact",
+            MemoryKind::Preference => "preference",
+            MemoryKind::Decision => "decision",
+            MemoryKind::Task => "task",
+            MemoryKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for MemoryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MemoryKind {
+    type Err = Error;
+
+    /// Parse a kind name, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == name)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "Invalid kind '{}' (expected one of: fact, preference, decision, task, other)",
+                    s
+                ))
+            })
+    }
+}
+
+impl ToSql for MemoryKind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for MemoryKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: Error| FromSqlError::Other(e.to_string().into()))
+    }
+}
+
+/// Entry path a memory was written through, stored in the `origin` column.
+///
+/// Set automatically: the CLI writes `cli` (`hook` inside a hook command),
+/// imports write `import:<source>`, and the library writes `api` unless
+/// told otherwise with [`MemoryStore::set_origin`](crate::MemoryStore::set_origin).
+/// Serialized and parsed as lowercase names; an import carries its source
+/// after a colon (`"import:notes.jsonl"`).
+///
+/// # Example
+///
+/// ```
+/// use vipune::Origin;
+///
+/// let origin: Origin = "import:notes.jsonl".parse().unwrap();
+/// assert_eq!(origin, Origin::Import { source: "notes.jsonl".to_string() });
+/// assert_eq!(origin.to_string(), "import:notes.jsonl");
+/// assert_eq!("MCP".parse::<Origin>().unwrap(), Origin::Mcp);
+/// assert!("email".parse::<Origin>().is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+#[schemars(
+    description = "Entry path a memory was written through: `cli`, `hook`, `mcp`, `api` or `import:<source>`."
+)]
+pub enum Origin {
+    /// Written with the `vipune` command line.
+    Cli,
+    /// Written by an MCP server on behalf of an agent.
+    Mcp,
+    /// Imported from a file or other source.
+    Import {
+        /// Where the memory was imported from (empty if unknown).
+        source: String,
+    },
+    /// Written through the library API (the default).
+    #[default]
+    Api,
+    /// Written by a command run from a `[hooks]` entry.
+    Hook,
+}
+
+impl Origin {
+    /// Lowercase name of the entry path, without an import's source.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Origin::Cli => "cli",
+            Origin::Mcp => "mcp",
+            Origin::Import { .. } => "import",
+            Origin::Api => "api",
+            Origin::Hook => "hook",
+        }
+    }
+
+    /// Import origin for `source`.
+    pub fn import(source: impl Into<String>) -> Self {
+        Origin::Import {
+            source: source.into(),
+        }
+    }
+
+    /// Whether a memory of this origin passes an origin filter of `filter`.
+    ///
+    /// An import filter without a source matches every import; anything else
+    /// must be equal.
+    pub fn matches(&self, filter: &Origin) -> bool {
+        match (self, filter) {
+            (Origin::Import { .. }, Origin::Import { source }) if source.is_empty() => true,
+            _ => self == filter,
+        }
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Import { source } if !source.is_empty() => write!(f, "import:{}", source),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+impl FromStr for Origin {
+    type Err = Error;
+
+    /// Parse an origin name, ignoring case and surrounding whitespace; the
+    /// source of `import:<source>` is kept as written.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, source) = match s.trim().split_once(':') {
+            Some((name, source)) => (name, Some(source)),
+            None => (s.trim(), None),
+        };
+        match (name.to_ascii_lowercase().as_str(), source) {
+            ("cli", None) => Ok(Origin::Cli),
+            ("mcp", None) => Ok(Origin::Mcp),
+            ("api", None) => Ok(Origin::Api),
+            ("hook", None) => Ok(Origin::Hook),
+            ("import", source) => Ok(Origin::import(source.unwrap_or_default())),
+            _ => Err(Error::InvalidInput(format!(
+                "Invalid origin '{}' (expected one of: cli, mcp, import[:source], api, hook)",
+                s
+            ))),
+        }
+    }
+}
+
+impl From<Origin> for String {
+    fn from(origin: Origin) -> Self {
+        origin.to_string()
+    }
+}
+
+impl TryFrom<String> for Origin {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl ToSql for Origin {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for Origin {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: Error| FromSqlError::Other(e.to_string().into()))
+    }
+}
+
+/// Who besides its owner may see a memory, stored in the `visibility`
+/// column next to the optional `owner`.
+///
+/// Searches and lists by a store with an `owner` configured return that
+/// owner's memories and shared ones; private memories of other owners are
+/// left out. Serialized and parsed as lowercase names.
+///
+/// # Example
+///
+/// ```
+/// use vipune::Visibility;
+///
+/// let visibility: Visibility = "Private".parse().unwrap();
+/// assert_eq!(visibility, Visibility::Private);
+/// assert_eq!(Visibility::default().to_string(), "shared");
+/// assert!("public".parse::<Visibility>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[schemars(description = "Who besides its owner may see a memory.")]
+pub enum Visibility {
+    /// Only the owner.
+    Private,
+    /// Everyone using the database (the default).
+    #[default]
+    Shared,
+}
+
+impl Visibility {
+    /// Lowercase name used in the database, CLI and configuration.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Visibility::Private => "private",
+            Visibility::Shared => "shared",
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = Error;
+
+    /// Parse a visibility name, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "private" => Ok(Visibility::Private),
+            "shared" => Ok(Visibility::Shared),
+            _ => Err(Error::InvalidInput(format!(
+                "Invalid visibility '{}' (expected private or shared)",
+                s
+            ))),
+        }
+    }
+}
+
+impl ToSql for Visibility {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for Visibility {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: Error| FromSqlError::Other(e.to_string().into()))
+    }
+}
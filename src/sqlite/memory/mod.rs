@@ -0,0 +1,231 @@
+//! Memory record type returned by database queries.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+
+mod labels;
+
+pub use labels::{MemoryKind, Origin, Visibility};
+
+/// A single memory record with metadata and optional similarity score.
+///
+/// Contains the stored memory content, metadata, and timestamps. The similarity
+/// field is populated only during search operations.
+///
+/// Timestamps are stored as RFC3339 strings (the database representation);
+/// use [`Memory::created_at_utc`] and [`Memory::updated_at_utc`] for typed access.
+///
+/// # Example
+///
+/// ```
+/// use vipune::Memory;
+///
+/// let json = r#"{
+///     "id": "1",
+///     "project_id": "owner/repo",
+///     "content": "Alice works at Microsoft",
+///     "metadata": "{\"topic\": \"people\"}",
+///     "created_at": "2024-06-01T12:00:00+00:00",
+///     "updated_at": "2024-06-01T12:00:00+00:00"
+/// }"#;
+/// let memory: Memory = serde_json::from_str(json).unwrap();
+/// assert_eq!(memory.created_at_utc().unwrap().to_rfc3339(), memory.created_at);
+/// assert_eq!(memory.metadata_json().unwrap().unwrap()["topic"], "people");
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Memory {
+    /// Unique identifier for this memory.
+    pub id: String,
+    /// Project identifier that owns this memory.
+    pub project_id: String,
+    /// The memory content (text to be embedded and searched).
+    pub content: String,
+    /// Optional user-provided metadata (JSON string).
+    #[serde(default)]
+    pub metadata: Option<String>,
+    /// Category of the memory (`other` when not specified).
+    #[serde(default)]
+    pub kind: MemoryKind,
+    /// Entry path the memory was written through (`api` when not known).
+    #[serde(default)]
+    pub origin: Origin,
+    /// Short title: the one given on add, or the first sentence of the
+    /// content (`None` for memories of backends that don't store titles).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Similarity score (search-dependent):
+    /// - Semantic search: Cosine similarity (0.0-1.0, higher = better match)
+    /// - FTS5 search: BM25 score (lower = better match, typically negative to positive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+    /// Best-matching chunk of a chunked memory (populated by semantic search only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    /// Score breakdown (populated when searching with `SearchOptions::explain`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
+    /// Creation timestamp in RFC3339 format.
+    pub created_at: String,
+    /// Last update timestamp in RFC3339 format.
+    pub updated_at: String,
+}
+
+/// Column values for a memory row about to be inserted.
+#[derive(Debug, Clone, Copy)]
+pub struct NewMemory<'a> {
+    /// Project the memory belongs to.
+    pub project_id: &'a str,
+    /// Text content.
+    pub content: &'a str,
+    /// Embedding of `content`.
+    pub embedding: &'a [f32],
+    /// Optional JSON metadata string.
+    pub metadata: Option<&'a str>,
+    /// Memory kind.
+    pub kind: MemoryKind,
+    /// Entry path the memory is written through.
+    pub origin: &'a Origin,
+    /// Creation timestamp (RFC3339).
+    pub created_at: &'a str,
+    /// Last update timestamp (RFC3339).
+    pub updated_at: &'a str,
+    /// Owner recorded for the memory and its chunks (`None` if unlabeled).
+    pub owner: Option<&'a str>,
+    /// Who besides the owner can see the memory.
+    pub visibility: Visibility,
+}
+
+/// How a search result's score was computed.
+///
+/// Components that did not apply to the search are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScoreExplanation {
+    /// Cosine similarity to the query (best chunk for chunked memories).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semantic: Option<f64>,
+    /// 1-based rank in the BM25 results (hybrid search only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bm25_rank: Option<usize>,
+    /// Reciprocal Rank Fusion score (hybrid search only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fused: Option<f64>,
+    /// Temporal decay factor blended in by the recency weight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recency_decay: Option<f64>,
+    /// Highest cosine similarity to an excluded term; the score was lowered
+    /// by this fraction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclusion: Option<f64>,
+    /// Frecency of the memory; the score was raised in proportion to it
+    /// (frecency ranking only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frecency: Option<f64>,
+    /// Cross-encoder relevance to the query, which replaced the score
+    /// (reranked searches only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<f64>,
+    /// MaxSim score against the query's token embeddings, which replaced
+    /// the score (late-interaction searches only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub late_interaction: Option<f64>,
+    /// Final score used for ranking (equals `Memory::similarity`).
+    pub score: f64,
+    /// Rows (memories and chunks) the semantic search scored after its
+    /// structured filters; the same for every result of a search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scanned: Option<usize>,
+}
+
+/// How often and how recently a memory was returned by a get or search.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessStats {
+    /// Number of times the memory was returned.
+    pub access_count: u64,
+    /// When the memory was last returned (RFC3339), if ever.
+    pub last_accessed_at: Option<String>,
+}
+
+impl AccessStats {
+    /// Last access timestamp parsed as UTC (`None` if never accessed).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTimestamp` if the stored value is not valid RFC3339.
+    pub fn last_accessed_at_utc(&self) -> Result<Option<DateTime<Utc>>, Error> {
+        self.last_accessed_at
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()
+    }
+}
+
+impl Memory {
+    /// Creation timestamp parsed as UTC.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTimestamp` if the stored value is not valid RFC3339.
+    pub fn created_at_utc(&self) -> Result<DateTime<Utc>, Error> {
+        parse_timestamp(&self.created_at)
+    }
+
+    /// Last update timestamp parsed as UTC.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidTimestamp` if the stored value is not valid RFC3339.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn updated_at_utc(&self) -> Result<DateTime<Utc>, Error> {
+        parse_timestamp(&self.updated_at)
+    }
+
+    /// Metadata parsed as a JSON value (`None` when no metadata is stored).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Json` if the stored metadata is not valid JSON.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn metadata_json(&self) -> Result<Option<serde_json::Value>, Error> {
+        self.metadata
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(Error::from)
+    }
+
+    /// Values of the `tags` metadata, an array of strings or a single
+    /// string; empty without tags or with malformed metadata.
+    pub fn tags(&self) -> Vec<String> {
+        let tags = match self.metadata_json() {
+            Ok(Some(serde_json::Value::Object(mut metadata))) => metadata.remove("tags"),
+            _ => None,
+        };
+        match tags {
+            Some(serde_json::Value::String(tag)) => vec![tag],
+            Some(serde_json::Value::Array(tags)) => tags
+                .into_iter()
+                .filter_map(|tag| match tag {
+                    serde_json::Value::String(tag) => Some(tag),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>, Error> {
+    timestamp
+        .parse::<DateTime<Utc>>()
+        .map_err(|e| Error::InvalidTimestamp {
+            timestamp: timestamp.to_string(),
+            error: e.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests;
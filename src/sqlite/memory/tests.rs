@@ -0,0 +1,155 @@
+//! Tests for memory kinds, origins and timestamp accessors.
+
+use super::*;
+
+fn sample() -> Memory {
+    Memory {
+        id: "id-1".to_string(),
+        project_id: "proj".to_string(),
+        content: "content".to_string(),
+        metadata: Some(r#"{"k": 1}"#.to_string()),
+        kind: MemoryKind::Decision,
+        origin: Origin::default(),
+        title: None,
+        similarity: None,
+        snippet: None,
+        explanation: None,
+        created_at: "2024-06-01T12:00:00+00:00".to_string(),
+        updated_at: "2024-06-02T08:30:00Z".to_string(),
+    }
+}
+
+#[test]
+fn test_typed_timestamps() {
+    let memory = sample();
+    assert_eq!(
+        memory.created_at_utc().unwrap(),
+        "2024-06-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    );
+    assert_eq!(
+        memory.updated_at_utc().unwrap().to_rfc3339(),
+        "2024-06-02T08:30:00+00:00"
+    );
+}
+
+#[test]
+fn test_invalid_timestamp_returns_typed_error() {
+    let memory = Memory {
+        created_at: "yesterday".to_string(),
+        ..sample()
+    };
+    assert!(matches!(
+        memory.created_at_utc(),
+        Err(Error::InvalidTimestamp { .. })
+    ));
+}
+
+#[test]
+fn test_tags() {
+    let with = |metadata: Option<&str>| {
+        Memory {
+            metadata: metadata.map(str::to_string),
+            ..sample()
+        }
+        .tags()
+    };
+    assert_eq!(with(Some(r#"{"tags": ["a", 1, "b"]}"#)), ["a", "b"]);
+    assert_eq!(with(Some(r#"{"tags": "a"}"#)), ["a"]);
+    assert!(with(Some(r#"{"k": 1}"#)).is_empty());
+    assert!(with(Some("not json")).is_empty());
+    assert!(with(None).is_empty());
+}
+
+#[test]
+fn test_metadata_json() {
+    assert_eq!(
+        sample().metadata_json().unwrap(),
+        Some(serde_json::json!({"k": 1}))
+    );
+
+    let no_metadata = Memory {
+        metadata: None,
+        ..sample()
+    };
+    assert_eq!(no_metadata.metadata_json().unwrap(), None);
+
+    let invalid = Memory {
+        metadata: Some("not json".to_string()),
+        ..sample()
+    };
+    assert!(matches!(invalid.metadata_json(), Err(Error::Json(_))));
+}
+
+#[test]
+fn test_serde_roundtrip_omits_missing_similarity() {
+    let memory = sample();
+    let json = serde_json::to_string(&memory).unwrap();
+    assert!(!json.contains("similarity"));
+
+    let parsed: Memory = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, memory);
+
+    let scored = Memory {
+        similarity: Some(0.5),
+        snippet: None,
+        explanation: None,
+        ..sample()
+    };
+    let json = serde_json::to_value(&scored).unwrap();
+    assert_eq!(json["similarity"], 0.5);
+}
+
+#[test]
+fn test_kind_serializes_lowercase_and_defaults_to_other() {
+    let json = serde_json::to_value(sample()).unwrap();
+    assert_eq!(json["kind"], "decision");
+
+    let legacy = r#"{"id": "1", "project_id": "p", "content": "c",
+        "created_at": "2024-06-01T12:00:00Z", "updated_at": "2024-06-01T12:00:00Z"}"#;
+    let parsed: Memory = serde_json::from_str(legacy).unwrap();
+    assert_eq!(parsed.kind, MemoryKind::Other);
+}
+
+#[test]
+fn test_origin_round_trips_and_matches_filters() {
+    for origin in [
+        Origin::Cli,
+        Origin::Mcp,
+        Origin::import("a:b.jsonl"),
+        Origin::import(""),
+        Origin::Api,
+        Origin::Hook,
+    ] {
+        assert_eq!(origin.to_string().parse::<Origin>().unwrap(), origin);
+        let json = serde_json::to_value(&origin).unwrap();
+        assert_eq!(json, origin.to_string());
+        assert_eq!(serde_json::from_value::<Origin>(json).unwrap(), origin);
+    }
+    assert!(matches!(
+        "cli:x".parse::<Origin>(),
+        Err(Error::InvalidInput(_))
+    ));
+
+    let any_import = Origin::import("");
+    assert!(Origin::import("notes.md").matches(&any_import));
+    assert!(!Origin::import("notes.md").matches(&Origin::import("other.md")));
+    assert!(!Origin::Cli.matches(&any_import));
+    assert!(Origin::Hook.matches(&Origin::Hook));
+
+    let legacy = r#"{"id": "1", "project_id": "p", "content": "c",
+        "created_at": "2024-06-01T12:00:00Z", "updated_at": "2024-06-01T12:00:00Z"}"#;
+    let parsed: Memory = serde_json::from_str(legacy).unwrap();
+    assert_eq!(parsed.origin, Origin::Api);
+}
+
+#[test]
+fn test_kind_parse_round_trips_every_variant() {
+    for kind in MemoryKind::ALL {
+        assert_eq!(kind.as_str().parse::<MemoryKind>().unwrap(), kind);
+    }
+    assert_eq!(" TASK ".parse::<MemoryKind>().unwrap(), MemoryKind::Task);
+    assert!(matches!(
+        "todo".parse::<MemoryKind>(),
+        Err(Error::InvalidInput(_))
+    ));
+}
@@ -0,0 +1,129 @@
+//! Bulk metadata updates, for `vipune meta set`.
+
+use chrono::Utc;
+use rusqlite::params;
+use rusqlite::types::Value;
+
+use super::{Database, audit, search};
+use crate::errors::Error;
+use crate::search_options::SearchFilter;
+
+impl Database {
+    /// Rewrite the metadata of a project's memories (chunk rows excluded)
+    /// that pass `filter`, in one transaction, bumping their `updated_at`.
+    ///
+    /// `update` gets each memory's ID and metadata and returns its new
+    /// metadata, or `None` to leave it alone; an error rolls every change
+    /// back. With `dry_run` nothing is written. Returns how many memories
+    /// passed the filter and the IDs of those updated (or that would be).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `update` fails or the query fails.
+    pub fn update_metadata_where<F>(
+        &self,
+        project_id: &str,
+        filter: &SearchFilter,
+        dry_run: bool,
+        mut update: F,
+    ) -> Result<(usize, Vec<String>), Error>
+    where
+        F: FnMut(&str, Option<&str>) -> Result<Option<String>, Error>,
+    {
+        let now = Utc::now().to_rfc3339();
+        let mut sql = "SELECT m.id, m.metadata FROM memories m
+                       WHERE m.project_id = ?1 AND m.parent_id IS NULL"
+            .to_string();
+        let mut values = vec![Value::Text(project_id.to_string())];
+        search::push_filter_conditions(&mut sql, filter, &mut values);
+        sql.push_str(" ORDER BY m.created_at, m.id");
+
+        let tx = self.write_transaction()?;
+        let matched = tx
+            .prepare(&sql)?
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut updated = Vec::new();
+        for (id, metadata) in &matched {
+            let Some(new) = update(id, metadata.as_deref())? else {
+                continue;
+            };
+            if !dry_run {
+                tx.execute(
+                    "UPDATE memories SET metadata = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![new, &now, id],
+                )?;
+                audit::record_where(&tx, audit::OP_UPDATE, "id = ?1", [id])?;
+            }
+            updated.push(id.clone());
+        }
+        if !dry_run {
+            tx.commit()?;
+        }
+        Ok((matched.len(), updated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_options::{FilterOp, MetadataFilter};
+
+    #[test]
+    fn test_update_metadata_where_filters_and_rolls_back() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+        let old = db
+            .insert("p", "Tagged old", &embedding, Some(r#"{"tags":["old"]}"#))
+            .unwrap();
+        let new = db
+            .insert("p", "Tagged new", &embedding, Some(r#"{"tags":["new"]}"#))
+            .unwrap();
+        db.insert(
+            "q",
+            "Other project",
+            &embedding,
+            Some(r#"{"tags":["old"]}"#),
+        )
+        .unwrap();
+        let filter = SearchFilter {
+            metadata: vec![MetadataFilter {
+                key: "tags".to_string(),
+                op: FilterOp::Eq,
+                value: "old".to_string(),
+            }],
+            ..SearchFilter::default()
+        };
+        let metadata = |id: &str| db.get(id).unwrap().unwrap().metadata;
+
+        let set_done = |_: &str, _: Option<&str>| Ok(Some(r#"{"done":true}"#.to_string()));
+        let (matched, updated) = db
+            .update_metadata_where("p", &filter, true, set_done)
+            .unwrap();
+        assert_eq!((matched, updated), (1, vec![old.clone()]));
+        assert_eq!(metadata(&old).as_deref(), Some(r#"{"tags":["old"]}"#));
+
+        let (_, updated) = db
+            .update_metadata_where("p", &filter, false, set_done)
+            .unwrap();
+        assert_eq!(updated, std::slice::from_ref(&old));
+        assert_eq!(metadata(&old).as_deref(), Some(r#"{"done":true}"#));
+        assert_eq!(metadata(&new).as_deref(), Some(r#"{"tags":["new"]}"#));
+
+        // A failing update leaves every memory as it was
+        let mut seen = 0;
+        let result = db.update_metadata_where("p", &SearchFilter::default(), false, |_, _| {
+            seen += 1;
+            if seen == 2 {
+                return Err(Error::InvalidInput("bad".to_string()));
+            }
+            Ok(Some("{}".to_string()))
+        });
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+        assert_eq!(metadata(&old).as_deref(), Some(r#"{"done":true}"#));
+        assert_eq!(metadata(&new).as_deref(), Some(r#"{"tags":["new"]}"#));
+    }
+}
@@ -2,46 +2,94 @@
 //!
 //! This module provides:
 //! - `Database`: Core SQLite connection and schema management
-//! - `Memory`: Data structure for stored memories
+//! - `schema`: Table creation and migrations of older databases
+//! - `records`: Inserting, reading, updating and deleting memory rows
+//! - `memory`: `Memory` record type with typed timestamp accessors
+//! - `access`: Access tracking (last access time and count) for frecency ranking
+//! - `activity`: Inactive memories skipped by default searches
+//! - `audit`: Write-ahead audit log of mutations
+//! - `backend`: `StorageBackend` implementation for `Database`
+//! - `bulk`: Write-optimized bulk sessions with deferred FTS5 indexing
+//! - `chunks`: Chunk rows linked to a parent memory via `parent_id`
+//! - `compression`: Transparent zstd compression of long content
 //! - `embedding`: BLOB conversion and cosine similarity
+//! - `export`: Project dumps with stored embeddings
 //! - `search`: Semantic search operations
-//! - `fts`: FTS5 full-text search (Issue #40)
-
+//! - `fts`: FTS5 full-text search and index consistency checks (Issue #40)
+//! - `history`: Earlier versions of memory content, kept on update
+//! - `import_progress`: Resume checkpoints for interrupted imports
+//! - `language`: Detected language of each memory
+//! - `pinned`: Pinned and importance-rated memories for context files
+//! - `projects`: Project ID listing and renaming
+//! - `quota`: Storage accounting and eviction for quota enforcement
+//! - `reembed`: Embedding rewrites, e.g. of rows with mismatched dimensions
+//! - `retention`: TTL-based pruning and archiving
+//! - `review`: Idle memories for the review queue, and keep and archive decisions
+//! - `snapshot`: Whole-database snapshots and restores for archives
+//! - `stable_id`: IDs derived from project and content, for idempotent inserts
+//! - `stats`: Per-project aggregates (kind and language counts, time range)
+//! - `synonyms`: Per-project synonyms expanding search queries
+//! - `sync`: Changes since a sync, applied to another store with conflict resolution
+//! - `title`: Titles given or derived from the content
+//! - `verify`: Model revisions of stored embeddings, and samples to verify them
+//! - `vector_index`: Optional sqlite-vec KNN index (behind the `sqlite-vec` feature)
+//! - `simd`: SIMD-accelerated vector math (behind the `simd` feature)
+
+pub mod access;
+pub mod activity;
+pub mod audit;
+pub mod backend;
+pub mod bulk;
+pub mod chunks;
+pub mod compression;
+pub mod content_hash;
 pub mod embedding;
+pub mod export;
 pub mod fts;
+pub mod history;
+pub mod import_progress;
+pub mod language;
+pub mod memory;
+pub mod metadata;
+pub mod pinned;
+pub mod projects;
+pub mod quota;
+mod records;
+pub mod reembed;
+pub mod retention;
+pub mod review;
+mod schema;
 pub mod search;
-
-use chrono::Utc;
-use rusqlite::{Connection, OptionalExtension, Result as SqliteResult, params};
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod snapshot;
+pub mod stable_id;
+pub mod stats;
+pub mod store_meta;
+pub mod sync;
+pub mod synonyms;
+pub mod title;
+pub mod token_embeddings;
+pub mod trash;
+pub mod vector_index;
+pub mod verify;
+pub mod visibility;
+
+use rusqlite::{Connection, Result as SqliteResult, Transaction, TransactionBehavior};
 use std::path::Path;
-use uuid::Uuid;
+use std::time::Duration;
 
-pub use self::embedding::vec_to_blob;
+pub use self::audit::AuditEntry;
+pub use self::embedding::EmbeddingFormat;
+pub use self::fts::{FtsCheck, FtsTokenizer};
+pub use self::history::MemoryVersion;
+pub use self::memory::{
+    AccessStats, Memory, MemoryKind, NewMemory, Origin, ScoreExplanation, Visibility,
+};
+pub use self::store_meta::StoreMeta;
 
-/// A single memory record with metadata and optional similarity score.
-///
-/// Contains the stored memory content, metadata, and timestamps. The similarity
-/// field is populated only during search operations.
-#[derive(Clone, Debug)]
-pub struct Memory {
-    /// Unique identifier for this memory.
-    pub id: String,
-    /// Project identifier that owns this memory.
-    pub project_id: String,
-    /// The memory content (text to be embedded and searched).
-    pub content: String,
-    /// Optional user-provided metadata (JSON string).
-    pub metadata: Option<String>,
-
-    /// Similarity score (search-dependent):
-    /// - Semantic search: Cosine similarity (0.0-1.0, higher = better match)
-    /// - FTS5 search: BM25 score (lower = better match, typically negative to positive)
-    pub similarity: Option<f64>,
-    /// Creation timestamp in RFC3339 format.
-    pub created_at: String,
-    /// Last update timestamp in RFC3339 format.
-    pub updated_at: String,
-}
+use self::records::memory_from_row;
+use self::schema::create_schema;
 
 /// Error types for SQLite operations.
 #[derive(Debug)]
@@ -58,6 +106,8 @@ pub enum Error {
     InvalidEmbedding(String),
     /// Invalid search limit value.
     InvalidLimit(String),
+    /// The database was opened without a full-text index.
+    FtsDisabled,
 }
 
 impl std::fmt::Display for Error {
@@ -81,6 +131,7 @@ impl std::fmt::Display for Error {
             Error::EmptyVector => write!(f, "Cannot compute similarity with empty vector"),
             Error::InvalidEmbedding(msg) => write!(f, "Invalid embedding: {}", msg),
             Error::InvalidLimit(msg) => write!(f, "Invalid limit: {}", msg),
+            Error::FtsDisabled => write!(f, "Full-text search is disabled"),
         }
     }
 }
@@ -95,401 +146,232 @@ impl From<rusqlite::Error> for Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Schema version stored in `PRAGMA user_version` once migrations have run.
+///
+/// Bump whenever `migrate_schema` gains a step.
+pub const SCHEMA_VERSION: u32 = 19;
+
 /// SQLite database backend for vipune.
 pub struct Database {
     /// Active SQLite connection to the database.
     conn: Connection,
+    /// Format of embeddings written from now on.
+    embedding_format: EmbeddingFormat,
+    /// Whether the sqlite-vec index answers semantic searches.
+    vector_index: bool,
+    /// Size from which content written from now on is compressed.
+    compression: Option<usize>,
+    /// Whether the FTS5 index exists and is kept in sync.
+    fts: bool,
+    /// Whether new memories get stable IDs (see [`stable_id`]).
+    stable_ids: bool,
+    /// Whether a bulk session is open (see [`bulk`]).
+    bulk: bool,
 }
 
-/// Initialize database schema and create necessary tables and triggers.
-fn create_schema(conn: &mut Connection) -> Result<()> {
-    conn.execute_batch(
-        r#"
-        CREATE TABLE IF NOT EXISTS memories (
-            id TEXT PRIMARY KEY,
-            project_id TEXT NOT NULL,
-            content TEXT NOT NULL,
-            embedding BLOB NOT NULL,
-            metadata TEXT,
-            created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_memories_project ON memories(project_id);
+/// A write transaction from [`Database::write_transaction`], or a scope
+/// joined to the enclosing one.
+///
+/// Dropping an owned transaction without committing rolls it back.
+pub(crate) struct WriteTransaction<'conn> {
+    conn: &'conn Connection,
+    tx: Option<Transaction<'conn>>,
+}
 
-        CREATE VIRTUAL TABLE IF NOT EXISTS memories_fts USING fts5(
-            content,
-            project_id UNINDEXED,
-            tokenize='porter unicode61',
-            content_rowid='rowid',
-            content='memories'
-        );
+impl WriteTransaction<'_> {
+    /// Commit the transaction (a no-op when joined to an enclosing one).
+    pub(crate) fn commit(self) -> SqliteResult<()> {
+        match self.tx {
+            Some(tx) => tx.commit(),
+            None => Ok(()),
+        }
+    }
 
-        CREATE TRIGGER IF NOT EXISTS memories_fts_insert AFTER INSERT ON memories BEGIN
-            INSERT INTO memories_fts(rowid, content, project_id)
-            VALUES (new.rowid, new.content, new.project_id);
-        END;
+    /// Roll back the transaction. A joined scope cannot be rolled back on
+    /// its own; the caller's error makes the enclosing transaction roll back.
+    pub(crate) fn rollback(self) -> SqliteResult<()> {
+        match self.tx {
+            Some(tx) => tx.rollback(),
+            None => Ok(()),
+        }
+    }
+}
 
-CREATE TRIGGER IF NOT EXISTS memories_fts_delete AFTER DELETE ON memories BEGIN
-            INSERT INTO memories_fts(memories_fts, rowid, content, project_id)
-            VALUES('delete', old.rowid, old.content, old.project_id);
-        END;
+impl std::ops::Deref for WriteTransaction<'_> {
+    type Target = Connection;
 
-        CREATE TRIGGER IF NOT EXISTS memories_fts_update AFTER UPDATE ON memories BEGIN
-            INSERT INTO memories_fts(memories_fts, rowid, content, project_id)
-            VALUES('delete', old.rowid, old.content, old.project_id);
-            INSERT INTO memories_fts(rowid, content, project_id)
-            VALUES (new.rowid, new.content, new.project_id);
-        END;
-        "#,
-    )?;
-    Ok(())
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
 }
 
 impl Database {
     /// Open or create a SQLite database at the given path.
     ///
-    /// Initializes the schema if the database is new.
+    /// Initializes the schema if the database is new (with the default
+    /// [`FtsTokenizer`]) and verifies the FTS5 index with the default
+    /// [`FtsCheck`] (row counts, rebuild on drift).
     ///
     /// # Errors
     ///
     /// Returns error if the database cannot be opened or schema initialization fails.
+    #[allow(dead_code)] // Used in tests
     pub fn open(path: &Path) -> Result<Self> {
-        let mut conn = Connection::open(path)?;
-        create_schema(&mut conn)?;
-        Ok(Self { conn })
+        Self::open_with(path, FtsCheck::default(), FtsTokenizer::default())
     }
 
-    /// Insert a new memory with embedding.
+    /// Open or create a SQLite database, verifying the FTS5 index as `check`
+    /// says. A new database indexes content with `tokenizer`.
     ///
     /// # Errors
     ///
-    /// Returns error if the embedding has invalid dimensions or database write fails.
-    pub fn insert(
-        &self,
-        project_id: &str,
-        content: &str,
-        embedding: &[f32],
-        metadata: Option<&str>,
-    ) -> Result<String> {
-        let id = Uuid::new_v4().to_string();
-        let now = Utc::now().to_rfc3339();
-        let blob = vec_to_blob(embedding)?;
-
-        self.conn.execute(
-            r#"
-            INSERT INTO memories (id, project_id, content, embedding, metadata, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-            "#,
-            params![&id, project_id, content, &blob, metadata, &now, &now],
-        )?;
-
-        Ok(id)
-    }
-
-    /// Insert a memory with explicit timestamps (for testing).
-    ///
-    /// This is used in tests to control the created_at and updated_at timestamps.
-    #[cfg(test)]
-    #[allow(dead_code)]
-    pub(crate) fn insert_with_time(
-        &self,
-        project_id: &str,
-        content: &str,
-        embedding: &[f32],
-        metadata: Option<&str>,
-        created_at: &str,
-        updated_at: &str,
-    ) -> Result<String> {
-        let id = Uuid::new_v4().to_string();
-        let blob = vec_to_blob(embedding)?;
-
-        self.conn.execute(
-            r#"
-            INSERT INTO memories (id, project_id, content, embedding, metadata, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-            "#,
-            params![&id, project_id, content, &blob, metadata, created_at, updated_at],
-        )?;
-
-        Ok(id)
+    /// Returns error if the database cannot be opened, schema initialization
+    /// fails or the FTS5 check or rebuild fails.
+    pub fn open_with(path: &Path, check: FtsCheck, tokenizer: FtsTokenizer) -> Result<Self> {
+        log::debug!("Opening database {}", path.display());
+        let db = Self::init(Connection::open(path)?, Some(tokenizer))?;
+        db.verify_fts(check)?;
+        Ok(db)
     }
 
-    /// Retrieve a single memory by ID.
+    /// Open or create a SQLite database without a full-text index.
     ///
-    /// Returns None if the memory does not exist.
+    /// An existing FTS5 index and its sync triggers are dropped, so writes
+    /// no longer maintain it; BM25 search returns [`Error::FtsDisabled`].
+    /// Opening the database with [`Database::open_with`] again rebuilds the
+    /// index.
     ///
     /// # Errors
     ///
-    /// Returns error if the database query fails.
-    pub fn get(&self, id: &str) -> Result<Option<Memory>> {
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, project_id, content, metadata, created_at, updated_at
-            FROM memories
-            WHERE id = ?1
-            "#,
-        )?;
-
-        let result = stmt
-            .query_row([id], |row| {
-                Ok(Memory {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    content: row.get(2)?,
-                    metadata: row.get(3)?,
-                    similarity: None,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                })
-            })
-            .optional()?;
-
-        Ok(result)
+    /// Returns error if the database cannot be opened or schema
+    /// initialization fails.
+    pub fn open_without_fts(path: &Path) -> Result<Self> {
+        log::debug!("Opening database {} without FTS", path.display());
+        Self::init(Connection::open(path)?, None)
     }
 
-    /// List memories for a project, ordered by creation time (newest first).
+    /// Open a private in-memory database (contents are lost when dropped).
     ///
     /// # Errors
     ///
-    /// Returns error if the limit is invalid or the query fails.
-    pub fn list(&self, project_id: &str, limit: usize) -> Result<Vec<Memory>> {
-        search::validate_limit(limit)?;
-
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, project_id, content, metadata, created_at, updated_at
-            FROM memories
-            WHERE project_id = ?1
-            ORDER BY created_at DESC
-            LIMIT ?2
-            "#,
-        )?;
-
-        let memories: SqliteResult<Vec<Memory>> = stmt
-            .query_map(params![project_id, limit as i64], |row| {
-                Ok(Memory {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    content: row.get(2)?,
-                    metadata: row.get(3)?,
-                    similarity: None,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                })
-            })?
-            .collect();
-
-        Ok(memories?)
+    /// Returns error if schema initialization fails.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn open_in_memory() -> Result<Self> {
+        Self::open_in_memory_with(FtsTokenizer::default())
     }
 
-    /// Update a memory's content and embedding.
-    ///
-    /// Returns an error if the memory does not exist.
+    /// Open a private in-memory database indexing content with `tokenizer`.
     ///
     /// # Errors
     ///
-    /// Returns error if the embedding has invalid dimensions, memory not found, or query fails.
-    pub fn update(&self, id: &str, content: &str, embedding: &[f32]) -> Result<()> {
-        let now = Utc::now().to_rfc3339();
-        let blob = vec_to_blob(embedding)?;
-
-        let rows = self.conn.execute(
-            r#"
-            UPDATE memories
-            SET content = ?1, embedding = ?2, updated_at = ?3
-            WHERE id = ?4
-            "#,
-            params![content, &blob, &now, id],
-        )?;
-
-        if rows == 0 {
-            return Err(Error::Sqlite("No memory found".to_string()));
-        }
-
-        Ok(())
+    /// Returns error if schema initialization fails.
+    pub fn open_in_memory_with(tokenizer: FtsTokenizer) -> Result<Self> {
+        Self::init(Connection::open_in_memory()?, Some(tokenizer))
     }
 
-    /// Delete a memory by ID.
-    ///
-    /// Returns true if a memory was deleted, false if it didn't exist.
+    /// Open a private in-memory database without a full-text index.
     ///
     /// # Errors
     ///
-    /// Returns error if the database query fails.
-    pub fn delete(&self, id: &str) -> Result<bool> {
-        let rows = self
-            .conn
-            .execute("DELETE FROM memories WHERE id = ?1", [id])?;
-        Ok(rows > 0)
+    /// Returns error if schema initialization fails.
+    pub fn open_in_memory_without_fts() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?, None)
     }
 
-    /// Get internal connection (for internal use, e.g., tests).
-    #[allow(dead_code)] // Used in fts.rs tests
-    pub(crate) fn conn(&self) -> &Connection {
-        &self.conn
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
-
-    fn create_test_db() -> Database {
-        let dir = TempDir::new().unwrap();
-        let path = dir.path().join("test.db");
-        let db = Database::open(&path).unwrap();
-        std::mem::forget(dir);
-        db
-    }
-
-    #[test]
-    fn test_insert_and_get() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 384];
-        let id = db
-            .insert("proj1", "test content", &embedding, None)
-            .unwrap();
-
-        let memory = db.get(&id).unwrap();
-        assert!(memory.is_some());
-        let m = memory.unwrap();
-        assert_eq!(m.content, "test content");
-        assert_eq!(m.project_id, "proj1");
-    }
-
-    #[test]
-    fn test_insert_with_metadata() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 384];
-        let id = db
-            .insert(
-                "proj1",
-                "test content",
-                &embedding,
-                Some(r#"{"key": "value"}"#),
-            )
-            .unwrap();
-
-        let m = db.get(&id).unwrap().unwrap();
-        assert_eq!(m.metadata, Some(r#"{"key": "value"}"#.to_string()));
-    }
-
-    #[test]
-    fn test_insert_invalid_embedding() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 256];
-        let result = db.insert("proj1", "test", &embedding, None);
-        assert!(result.is_err());
-    }
-
-    #[test]
-    fn test_get_nonexistent() {
-        let db = create_test_db();
-        let memory = db.get("nonexistent").unwrap();
-        assert!(memory.is_none());
+    fn init(mut conn: Connection, tokenizer: Option<FtsTokenizer>) -> Result<Self> {
+        let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(Error::Sqlite(format!(
+                "Database schema version {} is newer than this vipune supports ({}); upgrade vipune",
+                version, SCHEMA_VERSION
+            )));
+        }
+        audit::register_functions(&conn)?;
+        compression::register_functions(&conn)?;
+        title::register_functions(&conn)?;
+        content_hash::register_functions(&conn)?;
+        search::register_functions(&conn)?;
+        fts::register_functions(&conn)?;
+        create_schema(&mut conn, tokenizer)?;
+        Ok(Self {
+            conn,
+            embedding_format: EmbeddingFormat::default(),
+            vector_index: false,
+            compression: None,
+            fts: tokenizer.is_some(),
+            stable_ids: false,
+            bulk: false,
+        })
     }
 
-    #[test]
-    fn test_list_ordering() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 384];
-        let id1 = db
-            .insert_with_time(
-                "proj1",
-                "first",
-                &embedding,
-                None,
-                "2024-01-01T00:00:00Z",
-                "2024-01-01T00:00:00Z",
-            )
-            .unwrap();
-        let id2 = db
-            .insert_with_time(
-                "proj1",
-                "second",
-                &embedding,
-                None,
-                "2024-01-02T00:00:00Z",
-                "2024-01-02T00:00:00Z",
-            )
-            .unwrap();
-
-        let memories = db.list("proj1", 10).unwrap();
-        assert_eq!(memories.len(), 2);
-        assert_eq!(memories[0].id, id2); // Newest first
-        assert_eq!(memories[1].id, id1);
+    /// Store embeddings written from now on in `format` (existing rows keep
+    /// theirs until [`Database::convert_embeddings`] rewrites them).
+    pub fn set_embedding_format(&mut self, format: EmbeddingFormat) {
+        self.embedding_format = format;
     }
 
-    #[test]
-    fn test_list_limit() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 384];
-        for i in 0..5 {
-            db.insert("proj1", &format!("content {}", i), &embedding, None)
-                .unwrap();
-        }
-
-        let memories = db.list("proj1", 2).unwrap();
-        assert_eq!(memories.len(), 2);
+    /// Format new embeddings are stored in.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn embedding_format(&self) -> EmbeddingFormat {
+        self.embedding_format
     }
 
-    #[test]
-    fn test_update() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 384];
-        let id = db.insert("proj1", "original", &embedding, None).unwrap();
-
-        db.update(&id, "updated", &embedding).unwrap();
-
-        let m = db.get(&id).unwrap().unwrap();
-        assert_eq!(m.content, "updated");
+    /// Encode an embedding in the configured [`EmbeddingFormat`].
+    fn encode_embedding(&self, embedding: &[f32]) -> Result<Vec<u8>> {
+        embedding::encode_blob(embedding, self.embedding_format)
     }
 
-    #[test]
-    fn test_update_nonexistent() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 384];
-        let result = db.update("nonexistent", "content", &embedding);
-        assert!(result.is_err());
+    /// Schema version recorded in the database file (`PRAGMA user_version`).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the pragma cannot be read.
+    pub fn schema_version(&self) -> Result<u32> {
+        Ok(self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?)
     }
 
-    #[test]
-    fn test_delete() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 384];
-        let id = db.insert("proj1", "content", &embedding, None).unwrap();
-
-        let deleted = db.delete(&id).unwrap();
-        assert!(deleted);
-
-        let memory = db.get(&id).unwrap();
-        assert!(memory.is_none());
+    /// Begin a transaction that takes the write lock immediately.
+    ///
+    /// A deferred transaction that reads before writing cannot wait for
+    /// another connection's write lock (SQLite reports `SQLITE_BUSY` at once
+    /// to avoid a deadlock), so writes always start with `BEGIN IMMEDIATE`.
+    ///
+    /// Inside an outer write transaction (e.g. an import batch) this joins
+    /// it instead: `commit` is a no-op and the outer transaction commits or
+    /// rolls back every write made in it as a whole.
+    pub(crate) fn write_transaction(&self) -> SqliteResult<WriteTransaction<'_>> {
+        let tx = if self.conn.is_autocommit() {
+            Some(Transaction::new_unchecked(
+                &self.conn,
+                TransactionBehavior::Immediate,
+            )?)
+        } else {
+            None
+        };
+        Ok(WriteTransaction {
+            conn: &self.conn,
+            tx,
+        })
     }
 
-    #[test]
-    fn test_delete_nonexistent() {
-        let db = create_test_db();
-        let deleted = db.delete("nonexistent").unwrap();
-        assert!(!deleted);
+    /// Wait up to `timeout` for locks held by other connections instead of
+    /// failing immediately with `SQLITE_BUSY`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the timeout cannot be set.
+    pub fn set_busy_timeout(&self, timeout: Duration) -> Result<()> {
+        Ok(self.conn.busy_timeout(timeout)?)
     }
 
-    #[test]
-    fn test_project_isolation() {
-        let db = create_test_db();
-        let embedding = vec![0.1f32; 384];
-        db.insert("proj1", "proj1 content", &embedding, None)
-            .unwrap();
-        db.insert("proj2", "proj2 content", &embedding, None)
-            .unwrap();
-
-        let list1 = db.list("proj1", 10).unwrap();
-        let list2 = db.list("proj2", 10).unwrap();
-
-        assert_eq!(list1.len(), 1);
-        assert_eq!(list2.len(), 1);
-        assert_eq!(list1[0].project_id, "proj1");
-        assert_eq!(list2[0].project_id, "proj2");
+    /// Get internal connection (for internal use, e.g., tests).
+    #[allow(dead_code)] // Used in fts.rs tests
+    pub(crate) fn conn(&self) -> &Connection {
+        &self.conn
     }
 }
+
+#[cfg(test)]
+mod tests;
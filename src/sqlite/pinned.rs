@@ -0,0 +1,85 @@
+//! Pinned and high-importance memories, for `vipune export-context`.
+//!
+//! A memory is pinned when its JSON metadata has `"pinned": true`, and has an
+//! importance when its metadata has a numeric `"importance"`.
+
+use rusqlite::{Result as SqliteResult, params};
+
+use super::{Database, Memory, Result, memory_from_row, search};
+
+impl Database {
+    /// Up to `limit` pinned or importance-rated memories of a project (chunk
+    /// rows excluded): pinned ones first, then by importance (highest first),
+    /// then oldest first.
+    ///
+    /// The order only depends on the memories themselves, so the same
+    /// memories always come back in the same order.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the limit is invalid or the query fails.
+    pub fn pinned_memories(&self, project_id: &str, limit: usize) -> Result<Vec<Memory>> {
+        search::validate_limit(limit)?;
+
+        // json_extract fails on malformed metadata, so only look inside valid JSON
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, project_id, vipune_content(content, content_zstd), metadata, created_at, updated_at, kind, origin, title
+            FROM (
+                SELECT *,
+                    CASE WHEN json_valid(metadata)
+                        THEN json_extract(metadata, '$.pinned') IS 1 ELSE 0 END AS pinned,
+                    CASE WHEN json_valid(metadata)
+                        AND json_type(metadata, '$.importance') IN ('integer', 'real')
+                        THEN json_extract(metadata, '$.importance') END AS importance
+                FROM memories
+                WHERE project_id = ?1 AND parent_id IS NULL
+            )
+            WHERE pinned OR importance IS NOT NULL
+            ORDER BY pinned DESC, importance DESC, created_at ASC, id ASC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let memories: SqliteResult<Vec<Memory>> = stmt
+            .query_map(params![project_id, limit as i64], memory_from_row)?
+            .collect();
+
+        Ok(memories?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_memories_order() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+        let insert = |content: &str, metadata: Option<&str>| {
+            db.insert("proj", content, &embedding, metadata).unwrap()
+        };
+        insert("plain", None);
+        insert("not json", Some("pinned"));
+        insert("unpinned", Some(r#"{"pinned":false}"#));
+        insert("text importance", Some(r#"{"importance":"high"}"#));
+        insert("low", Some(r#"{"importance":1}"#));
+        insert("pinned", Some(r#"{"pinned":true}"#));
+        insert("high", Some(r#"{"importance":7.5}"#));
+        insert("pinned rated", Some(r#"{"pinned":true,"importance":0}"#));
+        db.insert("other", "elsewhere", &embedding, Some(r#"{"pinned":true}"#))
+            .unwrap();
+
+        let contents = |limit| -> Vec<String> {
+            db.pinned_memories("proj", limit)
+                .unwrap()
+                .into_iter()
+                .map(|m| m.content)
+                .collect()
+        };
+        assert_eq!(contents(10), vec!["pinned rated", "pinned", "high", "low"]);
+        assert_eq!(contents(2), vec!["pinned rated", "pinned"]);
+        assert!(db.pinned_memories("proj", 0).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Project-wide maintenance: listing, renaming and deleting project IDs.
+
+use super::{Database, Result, audit};
+
+/// Memory counts and last activity of a project, as seen by
+/// [`Database::project_activity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectActivity {
+    /// Project ID as stored.
+    pub project_id: String,
+    /// Live memories (chunk rows excluded).
+    pub memories: usize,
+    /// Archived memories.
+    pub archived: usize,
+    /// Memories in the trash.
+    pub trashed: usize,
+    /// Latest update or access of a live memory (RFC3339), or `None`
+    /// without live memories.
+    pub last_activity: Option<String>,
+    /// Latest move of a memory to the trash (RFC3339), or `None` with an
+    /// empty trash.
+    pub last_trashed: Option<String>,
+}
+
+impl Database {
+    /// Every project ID in use, with its number of live memories (chunk
+    /// rows excluded), sorted by ID.
+    ///
+    /// Projects that only have archived memories are listed with a count of 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn project_memory_counts(&self) -> Result<Vec<(String, usize)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT project_id, SUM(live)
+            FROM (
+                SELECT project_id, parent_id IS NULL AS live FROM memories
+                UNION ALL
+                SELECT project_id, 0 FROM memories_archive
+            )
+            GROUP BY project_id
+            ORDER BY project_id
+            "#,
+        )?;
+        let counts = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+
+    /// Every project ID in use (by live, archived or trashed memories), with
+    /// its memory counts and last activity, sorted by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn project_activity(&self) -> Result<Vec<ProjectActivity>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT project_id, SUM(live), SUM(archived), SUM(trashed),
+                   strftime('%Y-%m-%dT%H:%M:%SZ', MAX(activity)),
+                   strftime('%Y-%m-%dT%H:%M:%SZ', MAX(trashed_at))
+            FROM (
+                SELECT project_id, parent_id IS NULL AS live, 0 AS archived, 0 AS trashed,
+                       CASE WHEN parent_id IS NULL
+                            THEN MAX(julianday(updated_at),
+                                     julianday(COALESCE(last_accessed_at, updated_at)))
+                       END AS activity,
+                       NULL AS trashed_at
+                FROM memories
+                UNION ALL
+                SELECT project_id, 0, 1, 0, NULL, NULL FROM memories_archive
+                UNION ALL
+                SELECT project_id, 0, 0, parent_id IS NULL, NULL, julianday(deleted_at)
+                FROM memories_trash
+            )
+            GROUP BY project_id
+            ORDER BY project_id
+            "#,
+        )?;
+        let projects = stmt
+            .query_map([], |row| {
+                Ok(ProjectActivity {
+                    project_id: row.get(0)?,
+                    memories: row.get::<_, i64>(1)? as usize,
+                    archived: row.get::<_, i64>(2)? as usize,
+                    trashed: row.get::<_, i64>(3)? as usize,
+                    last_activity: row.get(4)?,
+                    last_trashed: row.get(5)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(projects)
+    }
+
+    /// Permanently delete project `project_id`: its memories and chunks,
+    /// archived and trashed memories and synonyms, in a single transaction.
+    /// Each live memory gets a `delete` audit entry. Returns the number of
+    /// live memories deleted, not counting chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a delete fails.
+    pub fn delete_project(&self, project_id: &str) -> Result<usize> {
+        let tx = self.write_transaction()?;
+        let deleted = audit::record_where(
+            &tx,
+            audit::OP_DELETE,
+            "project_id = ?1 AND parent_id IS NULL",
+            [project_id],
+        )?;
+        tx.execute("DELETE FROM memories WHERE project_id = ?1", [project_id])?;
+        tx.execute(
+            "DELETE FROM memories_archive WHERE project_id = ?1",
+            [project_id],
+        )?;
+        tx.execute(
+            "DELETE FROM memories_trash WHERE project_id = ?1",
+            [project_id],
+        )?;
+        tx.execute("DELETE FROM synonyms WHERE project_id = ?1", [project_id])?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// Move every memory of project `from` (chunks and archived memories
+    /// included) to project `to`, in a single transaction.
+    ///
+    /// Memories already in `to` are left alone, so renaming onto an existing
+    /// project merges the two; synonyms are merged the same way. Each moved memory gets an `update` audit entry
+    /// under its new project. Returns the number of live memories moved, not
+    /// counting chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the update fails.
+    pub fn rename_project(&self, from: &str, to: &str) -> Result<usize> {
+        if from == to {
+            return Ok(0);
+        }
+        let tx = self.write_transaction()?;
+        let ids = {
+            let mut stmt =
+                tx.prepare("SELECT id FROM memories WHERE project_id = ?1 AND parent_id IS NULL")?;
+            stmt.query_map([from], |row| row.get::<_, String>(0))?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+        tx.execute(
+            "UPDATE memories SET project_id = ?2 WHERE project_id = ?1",
+            [from, to],
+        )?;
+        tx.execute(
+            "UPDATE memories_archive SET project_id = ?2 WHERE project_id = ?1",
+            [from, to],
+        )?;
+        // Pairs the target project already has stay behind and are dropped
+        tx.execute(
+            "UPDATE OR IGNORE synonyms SET project_id = ?2 WHERE project_id = ?1",
+            [from, to],
+        )?;
+        tx.execute("DELETE FROM synonyms WHERE project_id = ?1", [from])?;
+        for id in &ids {
+            audit::record_where(&tx, audit::OP_UPDATE, "id = ?1", [id])?;
+        }
+        tx.commit()?;
+        Ok(ids.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::MemoryKind;
+    use tempfile::TempDir;
+
+    fn embedding() -> Vec<f32> {
+        vec![0.1; crate::embedding::EMBEDDING_DIMS]
+    }
+
+    #[test]
+    fn test_rename_project_moves_memories_and_audits() {
+        let dir = TempDir::new().unwrap();
+        let db = Database::open(&dir.path().join("test.db")).unwrap();
+        let a = db
+            .insert("Owner/Repo", "first", &embedding(), None)
+            .unwrap();
+        db.insert("Owner/Repo", "second", &embedding(), None)
+            .unwrap();
+        db.insert_with_kind("owner/repo", "third", &embedding(), None, MemoryKind::Fact)
+            .unwrap();
+        db.add_synonyms("Owner/Repo", "k8s", &["kubernetes", "kube"])
+            .unwrap();
+        db.add_synonyms("owner/repo", "k8s", &["kube"]).unwrap();
+
+        assert_eq!(
+            db.project_memory_counts().unwrap(),
+            vec![("Owner/Repo".to_string(), 2), ("owner/repo".to_string(), 1)]
+        );
+
+        assert_eq!(db.rename_project("Owner/Repo", "owner/repo").unwrap(), 2);
+        assert_eq!(
+            db.project_memory_counts().unwrap(),
+            vec![("owner/repo".to_string(), 3)]
+        );
+        assert_eq!(db.get(&a).unwrap().unwrap().project_id, "owner/repo");
+        assert_eq!(db.search_bm25("first", "owner/repo", 10).unwrap().len(), 1);
+        assert_eq!(db.list_synonyms("owner/repo").unwrap().len(), 2);
+        assert!(db.list_synonyms("Owner/Repo").unwrap().is_empty());
+
+        let log = db.audit_log("owner/repo", None, 100).unwrap();
+        assert_eq!(log.iter().filter(|e| e.operation == "update").count(), 2);
+        assert_eq!(db.rename_project("missing", "owner/repo").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_project_activity_and_delete_project() {
+        let db = Database::open_in_memory().unwrap();
+        let kept = db.insert("kept", "memory", &embedding(), None).unwrap();
+        db.insert("gone", "first", &embedding(), None).unwrap();
+        db.insert("gone", "second", &embedding(), None).unwrap();
+        db.add_synonyms("gone", "k8s", &["kubernetes"]).unwrap();
+        db.conn
+            .execute(
+                "UPDATE memories SET updated_at = '2024-01-01T00:00:00+00:00'
+                 WHERE project_id = 'gone'",
+                [],
+            )
+            .unwrap();
+        db.conn
+            .execute(
+                "UPDATE memories SET last_accessed_at = '2024-02-01T00:00:00+00:00'
+                 WHERE content = 'second'",
+                [],
+            )
+            .unwrap();
+
+        let activity = db.project_activity().unwrap();
+        assert_eq!(activity.len(), 2);
+        assert_eq!(
+            activity[0],
+            ProjectActivity {
+                project_id: "gone".to_string(),
+                memories: 2,
+                archived: 0,
+                trashed: 0,
+                last_activity: Some("2024-02-01T00:00:00Z".to_string()),
+                last_trashed: None,
+            }
+        );
+        assert_eq!(activity[1].project_id, "kept");
+
+        assert_eq!(db.delete_project("gone").unwrap(), 2);
+        assert_eq!(db.project_activity().unwrap().len(), 1);
+        assert!(db.list_synonyms("gone").unwrap().is_empty());
+        assert_eq!(db.search_bm25("first", "gone", 10).unwrap().len(), 0);
+        let log = db.audit_log("gone", None, 10).unwrap();
+        assert_eq!(log.iter().filter(|e| e.operation == "delete").count(), 2);
+        assert!(db.get(&kept).unwrap().is_some());
+    }
+}
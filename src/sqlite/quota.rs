@@ -0,0 +1,219 @@
+//! Storage accounting and eviction for quota enforcement.
+
+use super::{Database, Result, audit};
+
+impl Database {
+    /// Count memories stored for a project (chunks are not counted separately).
+    pub fn count_project(&self, project_id: &str) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM memories WHERE project_id = ?1 AND parent_id IS NULL",
+            [project_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Bytes occupied by live database pages.
+    ///
+    /// Pages on the freelist (left behind by deletes) are excluded, so the value
+    /// drops as soon as memories are removed, without requiring a VACUUM.
+    pub fn used_size_bytes(&self) -> Result<u64> {
+        let page_count: i64 = self
+            .conn
+            .query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let freelist_count: i64 = self
+            .conn
+            .query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+        let page_size: i64 = self
+            .conn
+            .query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok((page_count - freelist_count).max(0) as u64 * page_size as u64)
+    }
+
+    /// Delete the `count` oldest memories (with their chunks), optionally restricted
+    /// to one project.
+    ///
+    /// Evicted memories are deleted outright: they do not go to the trash and
+    /// no `on_delete` hook fires, since a trashed copy would keep the space.
+    /// Each one is still recorded in the audit log.
+    ///
+    /// Returns the number of memories deleted.
+    pub fn evict_oldest(&self, project_id: Option<&str>, count: usize) -> Result<usize> {
+        if count == 0 {
+            return Ok(0);
+        }
+        let tx = self.write_transaction()?;
+        let ids: Vec<String> = {
+            let mut stmt = tx.prepare(
+                "SELECT id FROM memories
+                 WHERE (?1 IS NULL OR project_id = ?1) AND parent_id IS NULL
+                 ORDER BY created_at ASC LIMIT ?2",
+            )?;
+            stmt.query_map(rusqlite::params![project_id, count as i64], |row| {
+                row.get(0)
+            })?
+            .collect::<std::result::Result<_, _>>()?
+        };
+
+        let deleted = delete_evicted(&tx, &ids)?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// Delete the oldest memories of `project_id` whose payloads add up to at
+    /// least `bytes`, evicting at most `limit` of them.
+    ///
+    /// A memory's payload is its content (compressed or not), embedding and
+    /// metadata, plus those of its chunks. If the `limit` oldest memories do
+    /// not add up to `bytes`, nothing is deleted. Evictions bypass the trash
+    /// and hooks like [`Database::evict_oldest`].
+    ///
+    /// Returns the number of memories deleted.
+    pub fn evict_bytes(&self, project_id: &str, bytes: u64, limit: usize) -> Result<usize> {
+        if bytes == 0 || limit == 0 {
+            return Ok(0);
+        }
+        let tx = self.write_transaction()?;
+        let candidates: Vec<(String, i64)> = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT p.id, {} + COALESCE(
+                     (SELECT SUM({}) FROM memories c WHERE c.parent_id = p.id), 0)
+                 FROM memories p
+                 WHERE p.project_id = ?1 AND p.parent_id IS NULL
+                 ORDER BY p.created_at ASC LIMIT ?2",
+                payload_bytes("p"),
+                payload_bytes("c")
+            ))?;
+            stmt.query_map(rusqlite::params![project_id, limit as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<std::result::Result<_, _>>()?
+        };
+
+        let mut freed = 0u64;
+        let mut ids = Vec::new();
+        for (id, size) in candidates {
+            if freed >= bytes {
+                break;
+            }
+            freed += size.max(0) as u64;
+            ids.push(id);
+        }
+        if freed < bytes {
+            return Ok(0);
+        }
+        let deleted = delete_evicted(&tx, &ids)?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+}
+
+/// SQL expression for the payload size of the row aliased `table`.
+fn payload_bytes(table: &str) -> String {
+    format!(
+        "COALESCE(length(CAST({t}.content AS BLOB)), 0) + COALESCE(length({t}.content_zstd), 0) \
+         + COALESCE(length({t}.embedding), 0) + COALESCE(length({t}.metadata), 0)",
+        t = table
+    )
+}
+
+/// Delete the memories `ids` with their chunks, recording each in the audit log.
+fn delete_evicted(tx: &rusqlite::Connection, ids: &[String]) -> Result<usize> {
+    let mut deleted = 0;
+    for id in ids {
+        audit::record_where(tx, audit::OP_DELETE, "id = ?1", [id])?;
+        tx.execute("DELETE FROM memories WHERE parent_id = ?1", [id])?;
+        deleted += tx.execute("DELETE FROM memories WHERE id = ?1", [id])?;
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_db() -> Database {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let db = Database::open(&path).unwrap();
+        std::mem::forget(dir);
+        db
+    }
+
+    fn insert_at(db: &Database, project: &str, content: &str, ts: &str) -> String {
+        db.insert_with_time(project, content, &vec![0.1; 384], None, ts, ts)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_count_project() {
+        let db = create_test_db();
+        assert_eq!(db.count_project("a").unwrap(), 0);
+        insert_at(&db, "a", "one", "2024-01-01T00:00:00Z");
+        insert_at(&db, "a", "two", "2024-01-02T00:00:00Z");
+        insert_at(&db, "b", "three", "2024-01-03T00:00:00Z");
+        assert_eq!(db.count_project("a").unwrap(), 2);
+        assert_eq!(db.count_project("b").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_evict_oldest_in_project() {
+        let db = create_test_db();
+        let oldest = insert_at(&db, "a", "one", "2024-01-01T00:00:00Z");
+        let newest = insert_at(&db, "a", "two", "2024-01-02T00:00:00Z");
+        let other = insert_at(&db, "b", "old other", "2023-01-01T00:00:00Z");
+
+        assert_eq!(db.evict_oldest(Some("a"), 1).unwrap(), 1);
+        assert!(db.get(&oldest).unwrap().is_none());
+        assert!(db.get(&newest).unwrap().is_some());
+        assert!(db.get(&other).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_evict_oldest_globally() {
+        let db = create_test_db();
+        let oldest = insert_at(&db, "b", "old", "2023-01-01T00:00:00Z");
+        let newer = insert_at(&db, "a", "new", "2024-01-01T00:00:00Z");
+
+        assert_eq!(db.evict_oldest(None, 1).unwrap(), 1);
+        assert!(db.get(&oldest).unwrap().is_none());
+        assert!(db.get(&newer).unwrap().is_some());
+        assert_eq!(db.evict_oldest(None, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_evict_bytes_stops_once_enough_is_freed() {
+        let db = create_test_db();
+        let oldest = insert_at(&db, "a", &"x".repeat(1000), "2024-01-01T00:00:00Z");
+        let middle = insert_at(&db, "a", &"y".repeat(1000), "2024-01-02T00:00:00Z");
+        let newest = insert_at(&db, "a", &"z".repeat(1000), "2024-01-03T00:00:00Z");
+        let other = insert_at(&db, "b", "old other", "2023-01-01T00:00:00Z");
+
+        // One memory carries 1000 bytes of content plus a 1536-byte embedding
+        assert_eq!(db.evict_bytes("a", 3000, 10).unwrap(), 2);
+        assert!(db.get(&oldest).unwrap().is_none());
+        assert!(db.get(&middle).unwrap().is_none());
+        assert!(db.get(&newest).unwrap().is_some());
+        assert!(db.get(&other).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_evict_bytes_deletes_nothing_past_the_limit() {
+        let db = create_test_db();
+        insert_at(&db, "a", "one", "2024-01-01T00:00:00Z");
+        insert_at(&db, "a", "two", "2024-01-02T00:00:00Z");
+        insert_at(&db, "b", &"x".repeat(100_000), "2023-01-01T00:00:00Z");
+
+        assert_eq!(db.evict_bytes("a", 50_000, 10).unwrap(), 0);
+        assert_eq!(db.evict_bytes("a", 2000, 1).unwrap(), 0);
+        assert_eq!(db.count_project("a").unwrap(), 2);
+        assert_eq!(db.count_project("b").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_used_size_bytes_is_positive() {
+        let db = create_test_db();
+        insert_at(&db, "a", "one", "2024-01-01T00:00:00Z");
+        assert!(db.used_size_bytes().unwrap() > 0);
+    }
+}
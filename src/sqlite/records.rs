@@ -0,0 +1,435 @@
+//! Inserting, reading, updating and deleting memory rows.
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult, params};
+
+use super::{
+    Database, Error, Memory, MemoryKind, NewMemory, Origin, Result, Visibility, audit,
+    content_hash, embedding, history, search,
+};
+use crate::list_options::ListOptions;
+
+/// Build a `Memory` from a row selecting
+/// `id, project_id, content, metadata, created_at, updated_at, kind, origin, title`.
+pub(super) fn memory_from_row(row: &rusqlite::Row<'_>) -> SqliteResult<Memory> {
+    Ok(Memory {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        content: row.get(2)?,
+        metadata: row.get(3)?,
+        kind: row.get(6)?,
+        origin: row.get(7)?,
+        title: row.get(8)?,
+        similarity: None,
+        snippet: None,
+        explanation: None,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+impl Database {
+    /// Insert a new memory with embedding.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the embedding has invalid dimensions or database write fails.
+    #[allow(dead_code)] // Used in tests
+    pub fn insert(
+        &self,
+        project_id: &str,
+        content: &str,
+        embedding: &[f32],
+        metadata: Option<&str>,
+    ) -> Result<String> {
+        self.insert_with_kind(project_id, content, embedding, metadata, MemoryKind::Other)
+    }
+
+    /// Insert a new memory of the given kind.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the embedding has invalid dimensions or database write fails.
+    #[allow(dead_code)] // Used in tests
+    pub fn insert_with_kind(
+        &self,
+        project_id: &str,
+        content: &str,
+        embedding: &[f32],
+        metadata: Option<&str>,
+        kind: MemoryKind,
+    ) -> Result<String> {
+        let now = Utc::now().to_rfc3339();
+        self.insert_record(&NewMemory {
+            project_id,
+            content,
+            embedding,
+            metadata,
+            kind,
+            origin: &Origin::Api,
+            created_at: &now,
+            updated_at: &now,
+            owner: None,
+            visibility: Visibility::Shared,
+        })
+    }
+
+    /// Insert a memory with explicit timestamps.
+    ///
+    /// Used in tests to control the created_at and updated_at timestamps.
+    #[allow(dead_code)] // Used in tests
+    pub(crate) fn insert_with_time(
+        &self,
+        project_id: &str,
+        content: &str,
+        embedding: &[f32],
+        metadata: Option<&str>,
+        created_at: &str,
+        updated_at: &str,
+    ) -> Result<String> {
+        self.insert_record(&NewMemory {
+            project_id,
+            content,
+            embedding,
+            metadata,
+            kind: MemoryKind::Other,
+            origin: &Origin::Api,
+            created_at,
+            updated_at,
+            owner: None,
+            visibility: Visibility::Shared,
+        })
+    }
+
+    /// Insert a fully specified memory row.
+    ///
+    /// Used by the other insert methods and by import to preserve source
+    /// timestamps and kinds. With stable IDs, a memory that already has the
+    /// record's ID is updated instead (see [`Database::replace_record`]),
+    /// and any trashed copy removed.
+    pub(crate) fn insert_record(&self, record: &NewMemory<'_>) -> Result<String> {
+        let tx = self.write_transaction()?;
+        let (id, exists) = self.new_id(&tx, record.project_id, record.content)?;
+        if self.stable_ids {
+            // A restore of the trashed copy would collide with the new memory
+            tx.execute(
+                "DELETE FROM memories_trash WHERE id = ?1 OR parent_id = ?1",
+                [&id],
+            )?;
+        }
+        if exists {
+            self.replace_record(&id, record)?;
+            tx.commit()?;
+            return Ok(id);
+        }
+
+        let blob = self.encode_embedding(record.embedding)?;
+        let (content, zstd) = self.encode_content(record.content)?;
+        let title = crate::title::derive(record.content);
+        let hash = content_hash::content_hash(record.content);
+        tx.execute(
+            r#"
+            INSERT INTO memories
+                (id, project_id, content, compressed, embedding, metadata, kind, origin,
+                 created_at, updated_at, title, content_hash, content_zstd, owner, visibility)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+            "#,
+            params![
+                &id,
+                record.project_id,
+                content,
+                zstd.is_some(),
+                &blob,
+                record.metadata,
+                record.kind,
+                record.origin,
+                record.created_at,
+                record.updated_at,
+                title,
+                hash,
+                zstd,
+                record.owner,
+                record.visibility
+            ],
+        )?;
+        audit::record_where(&tx, audit::OP_ADD, "id = ?1", [&id])?;
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    /// Replace memory `id` with `record`: its content, embedding, metadata,
+    /// kind and `updated_at` are replaced and its chunks removed, while its
+    /// project, creation time and origin are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the embedding has invalid dimensions, the memory is
+    /// not found or the query fails.
+    pub(crate) fn replace_record(&self, id: &str, record: &NewMemory<'_>) -> Result<()> {
+        let blob = self.encode_embedding(record.embedding)?;
+        let (content, zstd) = self.encode_content(record.content)?;
+        let title = crate::title::derive(record.content);
+        let hash = content_hash::content_hash(record.content);
+
+        let tx = self.write_transaction()?;
+        history::record(&tx, id, record.content, record.updated_at)?;
+        tx.execute("DELETE FROM memories WHERE parent_id = ?1", [id])?;
+        let rows = tx.execute(
+            r#"
+            UPDATE memories
+            SET content = ?1, compressed = ?2, embedding = ?3, metadata = ?4, kind = ?5,
+                updated_at = ?6,
+                title = CASE WHEN title IS vipune_title(vipune_content(content, content_zstd)) THEN ?7
+                        ELSE title END,
+                content_hash = ?8, content_zstd = ?10
+            WHERE id = ?9
+            "#,
+            params![
+                content,
+                zstd.is_some(),
+                &blob,
+                record.metadata,
+                record.kind,
+                record.updated_at,
+                title,
+                hash,
+                id,
+                zstd
+            ],
+        )?;
+        if rows == 0 {
+            return Err(Error::Sqlite("No memory found".to_string()));
+        }
+        audit::record_where(&tx, audit::OP_UPDATE, "id = ?1", [id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Retrieve a single memory by ID.
+    ///
+    /// Returns None if the memory does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database query fails.
+    pub fn get(&self, id: &str) -> Result<Option<Memory>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, project_id, vipune_content(content, content_zstd), metadata, created_at, updated_at,
+                   kind, origin, title
+            FROM memories
+            WHERE id = ?1
+            "#,
+        )?;
+
+        let result = stmt.query_row([id], memory_from_row).optional()?;
+
+        Ok(result)
+    }
+
+    /// Retrieve the stored embedding of a memory (or chunk) by ID, decoded
+    /// to `f32`s whatever its [`EmbeddingFormat`](super::EmbeddingFormat).
+    ///
+    /// Returns None if the memory does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails or the stored embedding is malformed.
+    pub fn get_embedding(&self, id: &str) -> Result<Option<Vec<f32>>> {
+        let blob: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT embedding FROM memories WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        blob.map(|blob| embedding::blob_to_vec(&blob)).transpose()
+    }
+
+    /// List memories for a project, ordered by creation time (newest first).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the limit is invalid or the query fails.
+    #[allow(dead_code)] // Used in tests
+    pub fn list(&self, project_id: &str, limit: usize) -> Result<Vec<Memory>> {
+        self.list_filtered(project_id, limit, None, None, None)
+    }
+
+    /// List memories for a project, optionally only those of one kind and
+    /// origin (see [`Origin::matches`]) and those visible to `viewer` (their
+    /// own and shared ones), ordered by creation time (newest first).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the limit is invalid or the query fails.
+    pub fn list_filtered(
+        &self,
+        project_id: &str,
+        limit: usize,
+        kind: Option<MemoryKind>,
+        origin: Option<&Origin>,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>> {
+        let options = ListOptions {
+            limit,
+            kind,
+            origin: origin.cloned(),
+            ..ListOptions::default()
+        };
+        self.list_with(project_id, &options, viewer)
+    }
+
+    /// List memories for a project as `options` says, also limited to those
+    /// visible to `viewer`, ordered by creation time (newest first). The
+    /// `since`/`before` range applies to `updated_at`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the limit is invalid or the query fails.
+    pub fn list_with(
+        &self,
+        project_id: &str,
+        options: &ListOptions,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>> {
+        search::validate_limit(options.limit)?;
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, project_id, vipune_content(content, content_zstd), metadata, created_at, updated_at,
+                   kind, origin, title
+            FROM memories
+            WHERE project_id = ?1 AND parent_id IS NULL AND (?3 IS NULL OR kind = ?3)
+                  AND (?4 IS NULL OR origin = ?4 OR (?4 = 'import' AND origin LIKE 'import:%'))
+                  AND (?5 IS NULL OR visibility = 'shared' OR owner = ?5)
+                  AND (?6 IS NULL OR updated_at >= ?6)
+                  AND (?7 IS NULL OR updated_at < ?7)
+            ORDER BY created_at DESC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let memories: SqliteResult<Vec<Memory>> = stmt
+            .query_map(
+                params![
+                    project_id,
+                    options.limit as i64,
+                    options.kind,
+                    options.origin,
+                    viewer,
+                    options.since.map(|t| t.to_rfc3339()),
+                    options.before.map(|t| t.to_rfc3339()),
+                ],
+                memory_from_row,
+            )?
+            .collect();
+
+        Ok(memories?)
+    }
+
+    /// Update a memory's content and embedding.
+    ///
+    /// A title derived from the old content is derived again from the new
+    /// one; a title that was set explicitly is kept.
+    ///
+    /// Returns an error if the memory does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the embedding has invalid dimensions, memory not found, or query fails.
+    pub fn update(&self, id: &str, content: &str, embedding: &[f32]) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        let blob = self.encode_embedding(embedding)?;
+        let title = crate::title::derive(content);
+        let hash = content_hash::content_hash(content);
+        let (encoded, zstd) = self.encode_content(content)?;
+
+        let tx = self.write_transaction()?;
+        history::record(&tx, id, content, &now)?;
+        let rows = tx.execute(
+            r#"
+            UPDATE memories
+            SET content = ?1, compressed = ?2, embedding = ?3, updated_at = ?4,
+                title = CASE WHEN title IS vipune_title(vipune_content(content, content_zstd)) THEN ?6
+                        ELSE title END,
+                content_hash = ?7, content_zstd = ?8
+            WHERE id = ?5
+            "#,
+            params![encoded, zstd.is_some(), &blob, &now, id, title, hash, zstd],
+        )?;
+
+        if rows == 0 {
+            return Err(Error::Sqlite("No memory found".to_string()));
+        }
+
+        audit::record_where(&tx, audit::OP_UPDATE, "id = ?1", [id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Replace a memory's metadata (`None` clears it), bumping `updated_at`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory is not found or the query fails.
+    pub fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+
+        let tx = self.write_transaction()?;
+        let rows = tx.execute(
+            "UPDATE memories SET metadata = ?1, updated_at = ?2 WHERE id = ?3",
+            params![metadata, &now, id],
+        )?;
+
+        if rows == 0 {
+            return Err(Error::Sqlite("No memory found".to_string()));
+        }
+
+        audit::record_where(&tx, audit::OP_UPDATE, "id = ?1", [id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Set a memory's `updated_at` to now, leaving content, embedding and
+    /// metadata alone. Recorded in the audit log as an update.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the memory is not found or the query fails.
+    pub fn touch(&self, id: &str) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+
+        let tx = self.write_transaction()?;
+        let rows = tx.execute(
+            "UPDATE memories SET updated_at = ?1 WHERE id = ?2 AND parent_id IS NULL",
+            params![&now, id],
+        )?;
+
+        if rows == 0 {
+            return Err(Error::Sqlite(format!("No memory found with id: {}", id)));
+        }
+
+        audit::record_where(&tx, audit::OP_UPDATE, "id = ?1", [id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete a memory by ID, along with its chunks.
+    ///
+    /// Returns true if a memory was deleted, false if it didn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database query fails.
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let tx = self.write_transaction()?;
+        audit::record_where(&tx, audit::OP_DELETE, "id = ?1", [id])?;
+        tx.execute("DELETE FROM memories WHERE parent_id = ?1", [id])?;
+        let rows = tx.execute("DELETE FROM memories WHERE id = ?1", [id])?;
+        tx.commit()?;
+        Ok(rows > 0)
+    }
+}
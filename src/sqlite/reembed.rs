@@ -0,0 +1,239 @@
+//! Embedding rewrites for `vipune reembed` and `vipune quantize`.
+//!
+//! Rows whose embedding blob is neither [`EMBEDDING_BLOB_SIZE`] nor
+//! [`INT8_BLOB_SIZE`] bytes (e.g. left behind by a model with other
+//! dimensions) are skipped by searches until they are embedded again. Like
+//! language detection, replacing an embedding leaves `updated_at` alone and
+//! is not written to the audit log.
+//!
+//! Rows whose embedding holds NaN or infinite values (written before
+//! embeddings were validated, e.g. from a corrupted model cache) make
+//! searches fail; `vipune doctor --scan-embeddings` finds and replaces them.
+
+use rusqlite::{Result as SqliteResult, params};
+
+use super::embedding::{
+    EMBEDDING_BLOB_SIZE, EmbeddingFormat, INT8_BLOB_SIZE, blob_to_vec, decode_blob, encode_blob,
+};
+use super::{Database, Result};
+use crate::embedding::EMBEDDING_DIMS;
+
+impl Database {
+    /// IDs and contents of the rows (memories and chunks, all projects) to
+    /// embed again: only those whose embedding has the wrong dimensions with
+    /// `mismatched_only`, otherwise every row.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn reembed_candidates(&self, mismatched_only: bool) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, vipune_content(content, content_zstd) FROM memories
+             WHERE NOT ?1 OR length(embedding) NOT IN (?2, ?3)
+             ORDER BY rowid",
+        )?;
+        let rows: SqliteResult<Vec<(String, String)>> = stmt
+            .query_map(
+                params![
+                    mismatched_only,
+                    EMBEDDING_BLOB_SIZE as i64,
+                    INT8_BLOB_SIZE as i64
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?
+            .collect();
+        Ok(rows?)
+    }
+
+    /// IDs and contents of the rows (memories and chunks, all projects) whose
+    /// stored embedding holds NaN or infinite values. Rows with mismatched
+    /// dimensions are not decoded and not reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a query fails.
+    pub fn non_finite_embeddings(&self) -> Result<Vec<(String, String)>> {
+        let ids: Vec<String> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, embedding FROM memories ORDER BY rowid")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?;
+            let mut ids = Vec::new();
+            for row in rows {
+                let (id, blob) = row?;
+                if decode_blob(&blob, EMBEDDING_DIMS)
+                    .is_some_and(|vec| vec.iter().any(|x| !x.is_finite()))
+                {
+                    ids.push(id);
+                }
+            }
+            ids
+        };
+        let mut stmt = self
+            .conn
+            .prepare("SELECT vipune_content(content, content_zstd) FROM memories WHERE id = ?1")?;
+        let rows: SqliteResult<Vec<(String, String)>> = ids
+            .into_iter()
+            .map(|id| {
+                let content = stmt.query_row([&id], |row| row.get(0))?;
+                Ok((id, content))
+            })
+            .collect();
+        Ok(rows?)
+    }
+
+    /// Replace the stored embedding of a memory or chunk row. Returns
+    /// `false` if the row doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the embedding has invalid dimensions or the update
+    /// fails.
+    pub fn set_embedding(&self, id: &str, embedding: &[f32]) -> Result<bool> {
+        let blob = self.encode_embedding(embedding)?;
+        let updated = self.conn.execute(
+            "UPDATE memories SET embedding = ?2 WHERE id = ?1",
+            params![id, blob],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Rewrite every stored embedding (memories and chunks, all projects)
+    /// that is not in `format`, in one transaction. Returns the number of
+    /// rows rewritten; rows with mismatched dimensions are left alone.
+    ///
+    /// Converting int8 embeddings back to f32 does not restore the precision
+    /// lost to quantization; `vipune reembed` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query or an update fails.
+    pub fn convert_embeddings(&self, format: EmbeddingFormat) -> Result<usize> {
+        let other = match format {
+            EmbeddingFormat::F32 => INT8_BLOB_SIZE,
+            EmbeddingFormat::Int8 => EMBEDDING_BLOB_SIZE,
+        };
+        let tx = self.write_transaction()?;
+        let rows: Vec<(String, Vec<u8>)> = {
+            let mut stmt =
+                tx.prepare("SELECT id, embedding FROM memories WHERE length(embedding) = ?1")?;
+            stmt.query_map([other as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<SqliteResult<_>>()?
+        };
+        for (id, blob) in &rows {
+            let converted = encode_blob(&blob_to_vec(blob)?, format)?;
+            tx.execute(
+                "UPDATE memories SET embedding = ?2 WHERE id = ?1",
+                params![id, converted],
+            )?;
+        }
+        tx.commit()?;
+        Ok(rows.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mismatched_rows_are_found_and_replaced() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; EMBEDDING_DIMS];
+        let good = db
+            .insert("proj", "current model", &embedding, None)
+            .unwrap();
+        let stale = db.insert("other", "older model", &embedding, None).unwrap();
+        db.conn
+            .execute(
+                "UPDATE memories SET embedding = ?2 WHERE id = ?1",
+                params![stale, vec![0u8; 768 * 4]],
+            )
+            .unwrap();
+        let before = db.get(&stale).unwrap().unwrap();
+
+        assert_eq!(
+            db.reembed_candidates(true).unwrap(),
+            vec![(stale.clone(), "older model".to_string())]
+        );
+        let all: Vec<String> = db
+            .reembed_candidates(false)
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(all, vec![good, stale.clone()]);
+
+        assert!(db.set_embedding(&stale, &embedding).unwrap());
+        assert!(!db.set_embedding("missing", &embedding).unwrap());
+        assert!(db.set_embedding(&stale, &[0.1; 768]).is_err());
+        assert!(db.reembed_candidates(true).unwrap().is_empty());
+        assert_eq!(
+            db.get(&stale).unwrap().unwrap().updated_at,
+            before.updated_at
+        );
+    }
+
+    #[test]
+    fn test_non_finite_embeddings_are_found() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; EMBEDDING_DIMS];
+        db.insert("proj", "healthy", &embedding, None).unwrap();
+        let bad = db
+            .insert("other", "contaminated", &embedding, None)
+            .unwrap();
+        let mut nan = embedding.clone();
+        nan[5] = f32::NAN;
+        // Insert and update reject it, so write the blob directly
+        assert!(db.set_embedding(&bad, &nan).is_err());
+        let blob: Vec<u8> = nan.iter().flat_map(|x| x.to_le_bytes()).collect();
+        db.conn
+            .execute(
+                "UPDATE memories SET embedding = ?2 WHERE id = ?1",
+                params![bad, blob],
+            )
+            .unwrap();
+
+        assert_eq!(
+            db.non_finite_embeddings().unwrap(),
+            vec![(bad.clone(), "contaminated".to_string())]
+        );
+        db.set_embedding(&bad, &embedding).unwrap();
+        assert!(db.non_finite_embeddings().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_convert_embeddings_between_formats() {
+        let mut db = Database::open_in_memory().unwrap();
+        let embedding: Vec<f32> = (0..EMBEDDING_DIMS).map(|i| i as f32 / 384.0).collect();
+        let id = db
+            .insert("proj", "full precision", &embedding, None)
+            .unwrap();
+        db.set_embedding_format(EmbeddingFormat::Int8);
+        db.insert("proj", "quantized", &embedding, None).unwrap();
+        let blob_size = |id: &str| -> i64 {
+            db.conn
+                .query_row(
+                    "SELECT length(embedding) FROM memories WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        assert_eq!(blob_size(&id), EMBEDDING_BLOB_SIZE as i64);
+
+        assert_eq!(db.convert_embeddings(EmbeddingFormat::Int8).unwrap(), 1);
+        assert_eq!(blob_size(&id), INT8_BLOB_SIZE as i64);
+        assert_eq!(db.convert_embeddings(EmbeddingFormat::Int8).unwrap(), 0);
+        // Quantized rows are not mismatched and stay searchable
+        assert!(db.reembed_candidates(true).unwrap().is_empty());
+        let results = db.search("proj", &embedding, 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].similarity.unwrap() > 0.999);
+
+        assert_eq!(db.convert_embeddings(EmbeddingFormat::F32).unwrap(), 2);
+        assert_eq!(blob_size(&id), EMBEDDING_BLOB_SIZE as i64);
+    }
+}
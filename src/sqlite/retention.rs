@@ -0,0 +1,130 @@
+//! TTL-based pruning and archiving of expired memories.
+
+use chrono::Utc;
+
+use super::{Database, Result, audit};
+
+impl Database {
+    /// List the distinct project IDs that have stored memories.
+    pub fn project_ids(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT project_id FROM memories ORDER BY project_id")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Remove memories in a project last updated before `cutoff` (RFC3339).
+    ///
+    /// With `archive` set, rows are copied to `memories_archive` before being
+    /// deleted, in a single transaction. Chunks expire with their parent (they
+    /// share its timestamps) and are not archived. Returns the number of
+    /// memories removed, not counting chunks.
+    pub fn prune_project(&self, project_id: &str, cutoff: &str, archive: bool) -> Result<usize> {
+        let tx = self.write_transaction()?;
+        if archive {
+            tx.execute(
+                r#"
+                INSERT OR REPLACE INTO memories_archive
+                    (id, project_id, content, compressed, content_zstd, embedding, metadata,
+                     kind, origin, created_at, updated_at, archived_at)
+                SELECT id, project_id, content, compressed, content_zstd, embedding, metadata,
+                       kind, origin, created_at, updated_at, ?3
+                FROM memories
+                WHERE project_id = ?1 AND julianday(updated_at) < julianday(?2)
+                    AND parent_id IS NULL
+                "#,
+                rusqlite::params![project_id, cutoff, Utc::now().to_rfc3339()],
+            )?;
+        }
+        let condition = "project_id = ?1 AND julianday(updated_at) < julianday(?2)";
+        let parents = format!("{condition} AND parent_id IS NULL");
+        audit::record_where(
+            &tx,
+            audit::OP_DELETE,
+            &parents,
+            rusqlite::params![project_id, cutoff],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM memories WHERE {condition} AND parent_id IS NOT NULL"),
+            rusqlite::params![project_id, cutoff],
+        )?;
+        let removed = tx.execute(
+            &format!("DELETE FROM memories WHERE {parents}"),
+            rusqlite::params![project_id, cutoff],
+        )?;
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Count archived memories for a project.
+    #[allow(dead_code)] // Used in tests
+    pub(crate) fn count_archived(&self, project_id: &str) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM memories_archive WHERE project_id = ?1",
+            [project_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_db() -> Database {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let db = Database::open(&path).unwrap();
+        std::mem::forget(dir);
+        db
+    }
+
+    fn insert_at(db: &Database, project: &str, ts: &str) -> String {
+        db.insert_with_time(project, "content", &vec![0.1; 384], None, ts, ts)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_project_ids() {
+        let db = create_test_db();
+        insert_at(&db, "b", "2024-01-01T00:00:00Z");
+        insert_at(&db, "a", "2024-01-01T00:00:00Z");
+        insert_at(&db, "a", "2024-01-02T00:00:00Z");
+        assert_eq!(db.project_ids().unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_prune_project_deletes_only_expired() {
+        let db = create_test_db();
+        let old = insert_at(&db, "a", "2024-01-01T00:00:00+00:00");
+        let fresh = insert_at(&db, "a", "2024-03-01T00:00:00Z");
+        let other = insert_at(&db, "b", "2024-01-01T00:00:00Z");
+
+        let removed = db
+            .prune_project("a", "2024-02-01T00:00:00+00:00", false)
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(db.get(&old).unwrap().is_none());
+        assert!(db.get(&fresh).unwrap().is_some());
+        assert!(db.get(&other).unwrap().is_some());
+        assert_eq!(db.count_archived("a").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_prune_project_archives() {
+        let db = create_test_db();
+        let old = insert_at(&db, "a", "2024-01-01T00:00:00Z");
+
+        let removed = db.prune_project("a", "2024-02-01T00:00:00Z", true).unwrap();
+        assert_eq!(removed, 1);
+        assert!(db.get(&old).unwrap().is_none());
+        assert_eq!(db.count_archived("a").unwrap(), 1);
+
+        // Archived memories are no longer searchable
+        assert!(db.search_bm25("content", "a", 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+//! Idle memories for the review queue, and the keep and archive decisions
+//! made on them.
+//!
+//! Like accesses, reviews are bookkeeping: marking a memory reviewed leaves
+//! `updated_at` alone and is not written to the audit log.
+
+use chrono::Utc;
+use rusqlite::params;
+
+use super::{Database, Memory, Result, audit, memory_from_row};
+
+/// A memory that has been idle (not updated, accessed or reviewed) for a
+/// while, with what the review queue ranks it by.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdleMemory {
+    /// The memory.
+    pub memory: Memory,
+    /// Numeric `importance` metadata, if any.
+    pub importance: Option<f64>,
+    /// When the memory was last returned by a get or search (RFC3339), if ever.
+    pub last_accessed_at: Option<String>,
+    /// When the memory was last kept in a review (RFC3339), if ever.
+    pub reviewed_at: Option<String>,
+    /// Days since the latest of its update, access and review.
+    pub idle_days: f64,
+}
+
+impl Database {
+    /// Memories of a project (chunk rows and pinned memories excluded) idle
+    /// for at least `min_idle_days` at `now` (RFC3339), longest idle first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn idle_memories(
+        &self,
+        project_id: &str,
+        now: &str,
+        min_idle_days: f64,
+    ) -> Result<Vec<IdleMemory>> {
+        // json_extract fails on malformed metadata, so only look inside valid JSON
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, project_id, vipune_content(content, content_zstd), metadata, created_at, updated_at, kind, origin, title,
+                   importance, last_accessed_at, reviewed_at, idle_days
+            FROM (
+                SELECT *,
+                    CASE WHEN json_valid(metadata)
+                        THEN json_extract(metadata, '$.pinned') IS 1 ELSE 0 END AS pinned,
+                    CASE WHEN json_valid(metadata)
+                        AND json_type(metadata, '$.importance') IN ('integer', 'real')
+                        THEN json_extract(metadata, '$.importance') END AS importance,
+                    julianday(?2) - max(
+                        julianday(updated_at),
+                        coalesce(julianday(last_accessed_at), 0),
+                        coalesce(julianday(reviewed_at), 0)
+                    ) AS idle_days
+                FROM memories
+                WHERE project_id = ?1 AND parent_id IS NULL
+            )
+            WHERE NOT pinned AND idle_days >= ?3
+            ORDER BY idle_days DESC, id ASC
+            "#,
+        )?;
+        let rows = stmt.query_map(params![project_id, now, min_idle_days], |row| {
+            Ok(IdleMemory {
+                memory: memory_from_row(row)?,
+                importance: row.get(9)?,
+                last_accessed_at: row.get(10)?,
+                reviewed_at: row.get(11)?,
+                idle_days: row.get(12)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Record that each memory in `ids` was kept in a review at `now`
+    /// (RFC3339), restarting its idle time. Returns the number of memories
+    /// marked (unknown IDs are skipped).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the update fails.
+    pub fn mark_reviewed(&self, ids: &[&str], now: &str) -> Result<usize> {
+        let tx = self.write_transaction()?;
+        let mut marked = 0;
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE memories SET reviewed_at = ?2 WHERE id = ?1 AND parent_id IS NULL",
+            )?;
+            for id in ids {
+                marked += stmt.execute([id, &now])?;
+            }
+        }
+        tx.commit()?;
+        Ok(marked)
+    }
+
+    /// Move each memory in `ids` to `memories_archive` and remove it and its
+    /// chunks from the store, in a single transaction. Returns the number of
+    /// memories archived (unknown IDs are skipped).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a copy or delete fails.
+    pub fn archive_memories(&self, ids: &[&str]) -> Result<usize> {
+        let archived_at = Utc::now().to_rfc3339();
+        let tx = self.write_transaction()?;
+        let mut archived = 0;
+        for id in ids {
+            tx.execute(
+                r#"
+                INSERT OR REPLACE INTO memories_archive
+                    (id, project_id, content, compressed, content_zstd, embedding, metadata,
+                     kind, origin, created_at, updated_at, archived_at)
+                SELECT id, project_id, content, compressed, content_zstd, embedding, metadata,
+                       kind, origin, created_at, updated_at, ?2
+                FROM memories
+                WHERE id = ?1 AND parent_id IS NULL
+                "#,
+                params![id, archived_at],
+            )?;
+            let condition = "id = ?1 AND parent_id IS NULL";
+            audit::record_where(&tx, audit::OP_DELETE, condition, [id])?;
+            tx.execute("DELETE FROM memories WHERE parent_id = ?1", [id])?;
+            archived += tx.execute(&format!("DELETE FROM memories WHERE {condition}"), [id])?;
+        }
+        tx.commit()?;
+        Ok(archived)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert_at(db: &Database, metadata: Option<&str>, ts: &str) -> String {
+        db.insert_with_time("p", "content", &vec![0.1; 384], metadata, ts, ts)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_idle_memories_counts_accesses_and_reviews() {
+        let db = Database::open_in_memory().unwrap();
+        let now = "2024-04-01T00:00:00Z";
+        let idle = insert_at(&db, Some(r#"{"importance":2}"#), "2024-01-01T00:00:00Z");
+        let fresh = insert_at(&db, None, "2024-03-31T00:00:00Z");
+        insert_at(&db, Some(r#"{"pinned":true}"#), "2024-01-01T00:00:00Z");
+        insert_at(&db, Some("not json"), "2024-02-01T00:00:00Z");
+
+        let found = db.idle_memories("p", now, 30.0).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].memory.id, idle);
+        assert_eq!(found[0].importance, Some(2.0));
+        assert_eq!(found[0].idle_days, 91.0);
+        assert_eq!(found[1].importance, None);
+        assert!(found.iter().all(|m| m.memory.id != fresh));
+
+        db.mark_reviewed(&[&idle], "2024-03-20T00:00:00Z").unwrap();
+        let found = db.idle_memories("p", now, 30.0).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_ne!(found[0].memory.id, idle);
+    }
+
+    #[test]
+    fn test_archive_memories() {
+        let db = Database::open_in_memory().unwrap();
+        let id = insert_at(&db, None, "2024-01-01T00:00:00Z");
+        let kept = insert_at(&db, None, "2024-01-01T00:00:00Z");
+
+        assert_eq!(db.archive_memories(&[&id, "missing"]).unwrap(), 1);
+        assert!(db.get(&id).unwrap().is_none());
+        assert!(db.get(&kept).unwrap().is_some());
+        assert_eq!(db.count_archived("p").unwrap(), 1);
+    }
+}
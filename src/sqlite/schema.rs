@@ -0,0 +1,184 @@
+//! Schema creation and migrations of older databases.
+
+use rusqlite::Connection;
+
+use super::{
+    FtsTokenizer, Result, SCHEMA_VERSION, activity, audit, content_hash, fts, history,
+    import_progress, store_meta, sync, synonyms, title, token_embeddings, trash, vector_index,
+};
+
+/// Initialize database schema and create necessary tables and triggers.
+///
+/// A new FTS5 index uses `tokenizer`; an existing one keeps its own. Without
+/// a tokenizer the FTS5 index, its view and triggers are dropped instead.
+pub(super) fn create_schema(conn: &mut Connection, tokenizer: Option<FtsTokenizer>) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS memories (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            content TEXT NOT NULL,
+            embedding BLOB NOT NULL,
+            metadata TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            parent_id TEXT,
+            kind TEXT NOT NULL DEFAULT 'other',
+            last_accessed_at TEXT,
+            access_count INTEGER NOT NULL DEFAULT 0,
+            language TEXT,
+            reviewed_at TEXT,
+            model_revision TEXT,
+            origin TEXT NOT NULL DEFAULT 'api',
+            compressed INTEGER NOT NULL DEFAULT 0,
+            content_zstd BLOB,
+            title TEXT,
+            owner TEXT,
+            visibility TEXT NOT NULL DEFAULT 'shared',
+            active INTEGER NOT NULL DEFAULT 1,
+            content_hash TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_memories_project ON memories(project_id);
+
+        CREATE TABLE IF NOT EXISTS memories_archive (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            content TEXT NOT NULL,
+            embedding BLOB NOT NULL,
+            metadata TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            archived_at TEXT NOT NULL,
+            kind TEXT NOT NULL DEFAULT 'other',
+            origin TEXT NOT NULL DEFAULT 'api',
+            compressed INTEGER NOT NULL DEFAULT 0,
+            content_zstd BLOB
+        );
+        "#,
+    )?;
+    // Before the FTS5 index, whose source view and triggers read titles;
+    // existing rows are titled once the other columns are migrated
+    let titles_added = add_column_if_missing(conn, "memories", "title", "TEXT")?;
+    match tokenizer {
+        Some(tokenizer) => fts::create_fts(conn, tokenizer)?,
+        None => fts::drop_fts(conn)?,
+    }
+    conn.execute_batch(audit::AUDIT_SCHEMA)?;
+    conn.execute_batch(import_progress::IMPORT_PROGRESS_SCHEMA)?;
+    conn.execute_batch(sync::SYNC_STATE_SCHEMA)?;
+    conn.execute_batch(synonyms::SYNONYMS_SCHEMA)?;
+    conn.execute_batch(trash::TRASH_SCHEMA)?;
+    migrate_schema(conn, tokenizer.is_some())?;
+    if titles_added {
+        title::derive_titles(conn)?;
+    }
+    Ok(())
+}
+
+/// Bring databases created by older versions up to the current schema
+/// (including the FTS5 index, if `fts` is set).
+fn migrate_schema(conn: &Connection, fts: bool) -> Result<()> {
+    add_column_if_missing(conn, "memories", "parent_id", "TEXT")?;
+    add_column_if_missing(conn, "memories", "kind", "TEXT NOT NULL DEFAULT 'other'")?;
+    add_column_if_missing(conn, "memories", "last_accessed_at", "TEXT")?;
+    add_column_if_missing(
+        conn,
+        "memories",
+        "access_count",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(conn, "memories", "language", "TEXT")?;
+    add_column_if_missing(conn, "memories", "reviewed_at", "TEXT")?;
+    add_column_if_missing(conn, "memories", "model_revision", "TEXT")?;
+    add_column_if_missing(conn, "memories", "origin", "TEXT NOT NULL DEFAULT 'api'")?;
+    add_column_if_missing(conn, "memories", "compressed", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "memories", "content_zstd", "BLOB")?;
+    add_column_if_missing(conn, "memories", "owner", "TEXT")?;
+    add_column_if_missing(
+        conn,
+        "memories",
+        "visibility",
+        "TEXT NOT NULL DEFAULT 'shared'",
+    )?;
+    add_column_if_missing(conn, "memories", "active", "INTEGER NOT NULL DEFAULT 1")?;
+    if add_column_if_missing(conn, "memories", "content_hash", "TEXT")? {
+        content_hash::hash_contents(conn)?;
+    }
+    add_column_if_missing(conn, "memories_trash", "title", "TEXT")?;
+    add_column_if_missing(conn, "memories_trash", "owner", "TEXT")?;
+    add_column_if_missing(
+        conn,
+        "memories_trash",
+        "visibility",
+        "TEXT NOT NULL DEFAULT 'shared'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "memories_archive",
+        "kind",
+        "TEXT NOT NULL DEFAULT 'other'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "memories_archive",
+        "origin",
+        "TEXT NOT NULL DEFAULT 'api'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "memories_archive",
+        "compressed",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(conn, "memories_archive", "content_zstd", "BLOB")?;
+    conn.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_memories_parent ON memories(parent_id);
+        CREATE INDEX IF NOT EXISTS idx_memories_kind ON memories(project_id, kind);
+        CREATE INDEX IF NOT EXISTS idx_memories_origin ON memories(project_id, origin);
+        CREATE INDEX IF NOT EXISTS idx_memories_updated ON memories(project_id, updated_at);
+        CREATE INDEX IF NOT EXISTS idx_memories_owner ON memories(project_id, owner);
+        CREATE INDEX IF NOT EXISTS idx_memories_compressed ON memories(compressed)
+            WHERE compressed = 1;
+        "#,
+    )?;
+    conn.execute_batch(activity::ACTIVITY_SCHEMA)?;
+    conn.execute_batch(content_hash::CONTENT_HASH_SCHEMA)?;
+    if fts {
+        fts::add_metadata_fields(conn)?;
+        fts::index_titles(conn)?;
+    }
+    conn.execute_batch(vector_index::VECTOR_CHANGES_SCHEMA)?;
+    conn.execute_batch(token_embeddings::TOKEN_EMBEDDINGS_SCHEMA)?;
+    conn.execute_batch(store_meta::STORE_META_SCHEMA)?;
+    conn.execute_batch(history::HISTORY_SCHEMA)?;
+    store_meta::record_versions(conn)?;
+    let previous: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if previous != SCHEMA_VERSION {
+        log::debug!("Schema version {} -> {}", previous, SCHEMA_VERSION);
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    }
+    Ok(())
+}
+
+/// Add `column` to `table` unless it already exists (table and column names
+/// are trusted). Returns true if the column was added.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    declaration: &str,
+) -> Result<bool> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2")?
+        .exists([table, column])?;
+    if !exists {
+        log::info!("Migrating schema: adding {}.{}", table, column);
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {};",
+            table, column, declaration
+        ))?;
+    }
+    Ok(!exists)
+}
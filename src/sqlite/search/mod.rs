@@ -0,0 +1,333 @@
+//! Semantic search and similarity operations.
+//!
+//! The structured part of a search ([`SearchFilter`]) becomes SQL conditions
+//! on the scanned rows, so only the embeddings of memories that pass are
+//! decoded and scored. Metadata filters run in SQL through the
+//! `vipune_metadata_matches(metadata, filters)` function, which applies
+//! [`MetadataFilter`] semantics.
+
+use std::collections::HashMap;
+
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OptionalExtension, params_from_iter};
+
+use super::{Database, Error, Memory, MemoryKind, Origin, embedding};
+use crate::memory::store::{MAX_SEARCH_LIMIT, SEARCH_LIMIT_CAP};
+use crate::search_options::{MetadataFilter, SearchFilter, metadata_matches};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Validate search limit is within acceptable bounds.
+///
+/// `MemoryStore` enforces the configured `max_search_limit`; the database
+/// only rejects limits above the highest value a config may set.
+pub fn validate_limit(limit: usize) -> Result<()> {
+    if limit == 0 {
+        return Err(Error::InvalidLimit(
+            "Limit must be greater than 0".to_string(),
+        ));
+    }
+    if limit > i64::MAX as usize || limit > SEARCH_LIMIT_CAP {
+        return Err(Error::InvalidLimit(format!(
+            "Limit {} exceeds maximum allowed ({})",
+            limit, SEARCH_LIMIT_CAP
+        )));
+    }
+    Ok(())
+}
+
+/// Register the `vipune_metadata_matches(metadata, filters)` SQL function,
+/// where `filters` is a JSON array of serialized [`MetadataFilter`]s.
+pub(super) fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    conn.create_scalar_function(
+        "vipune_metadata_matches",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            // Parsed once per statement rather than once per row
+            let filters = ctx.get_or_create_aux(1, |value| {
+                serde_json::from_str::<Vec<MetadataFilter>>(value.as_str()?)
+                    .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+            })?;
+            Ok(metadata_matches(
+                &filters,
+                ctx.get::<Option<String>>(0)?.as_deref(),
+            ))
+        },
+    )
+}
+
+/// Append the conditions of `filter` on the memories row aliased `m` to
+/// `sql` (each as ` AND ...`), binding their values to `params`.
+///
+/// Chunk rows carry their parent's kind, origin and timestamps but no
+/// metadata, so metadata filters judge them by their parent's.
+pub(super) fn push_filter_conditions(
+    sql: &mut String,
+    filter: &SearchFilter,
+    params: &mut Vec<Value>,
+) {
+    let bind = |params: &mut Vec<Value>, value: String| {
+        params.push(Value::Text(value));
+        params.len()
+    };
+    if !filter.metadata.is_empty() {
+        let n = bind(params, serde_json::json!(filter.metadata).to_string());
+        sql.push_str(&format!(
+            " AND vipune_metadata_matches(CASE WHEN m.parent_id IS NULL THEN m.metadata
+                  ELSE (SELECT p.metadata FROM memories p WHERE p.id = m.parent_id) END, ?{n})"
+        ));
+    }
+    if let Some(kind) = filter.kind {
+        let n = bind(params, kind.as_str().to_string());
+        sql.push_str(&format!(" AND m.kind = ?{n}"));
+    }
+    if let Some(origin) = &filter.origin {
+        let n = bind(params, origin.to_string());
+        sql.push_str(&format!(
+            " AND (m.origin = ?{n} OR (?{n} = 'import' AND m.origin LIKE 'import:%'))"
+        ));
+    }
+    if let Some(since) = &filter.since {
+        let n = bind(params, since.clone());
+        sql.push_str(&format!(" AND m.updated_at >= ?{n}"));
+    }
+    if let Some(as_of) = &filter.as_of {
+        let n = bind(params, as_of.clone());
+        sql.push_str(&format!(" AND m.updated_at <= ?{n}"));
+    }
+    if let Some(viewer) = &filter.viewer {
+        let n = bind(params, viewer.clone());
+        sql.push_str(&format!(" AND (m.visibility = 'shared' OR m.owner = ?{n})"));
+    }
+    if filter.active_only {
+        sql.push_str(" AND m.active = 1");
+    }
+}
+
+impl Database {
+    /// Search for similar memories using semantic (cosine) similarity.
+    ///
+    /// Retrieves all memories for a project, computes cosine similarity with the query
+    /// embedding, sorts by similarity (highest first), and returns the top `limit` results.
+    ///
+    /// Chunked memories are scored by their best-matching chunk: the parent is
+    /// returned with that chunk's similarity and the chunk text in `snippet`.
+    /// Rows whose stored embedding has other dimensions than the query are
+    /// skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query embedding is invalid or if the database
+    /// query fails.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn search(
+        &self,
+        project_id: &str,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<Memory>> {
+        self.search_filtered(project_id, query_embedding, limit, &SearchFilter::default())
+    }
+
+    /// Semantic search restricted to memories that pass `filter`.
+    ///
+    /// The filter is applied in SQL before any embedding is decoded, so the
+    /// scan shrinks to the matching rows (see [`Database::semantic_scan_size`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query embedding has invalid dimensions or if the database
+    /// query fails.
+    pub fn search_filtered(
+        &self,
+        project_id: &str,
+        query_embedding: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Memory>> {
+        validate_limit(limit)?;
+        if let Some(memories) =
+            self.search_vector_index(project_id, query_embedding, limit, filter)?
+        {
+            return Ok(memories);
+        }
+
+        let mut sql = String::from(
+            "SELECT m.id, m.project_id, vipune_content(m.content, m.content_zstd), m.metadata, m.created_at,
+                    m.updated_at, m.embedding, m.parent_id, m.kind, m.origin, m.title
+             FROM memories m
+             WHERE m.project_id = ?1",
+        );
+        let mut params = vec![Value::Text(project_id.to_string())];
+        push_filter_conditions(&mut sql, filter, &mut params);
+        let mut stmt = self.conn.prepare(&sql)?;
+
+        let mut memories: Vec<Memory> = Vec::new();
+        // Best (similarity, chunk content) per chunked parent
+        let mut best_chunks: HashMap<String, (f64, String)> = HashMap::new();
+
+        let rows = stmt.query_map(params_from_iter(&params), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, Vec<u8>>(6)?,
+                row.get::<_, Option<String>>(7)?,
+                row.get::<_, MemoryKind>(8)?,
+                row.get::<_, Origin>(9)?,
+                row.get::<_, Option<String>>(10)?,
+            ))
+        })?;
+
+        // Rows embedded with other dimensions (e.g. by an earlier model)
+        // can't be compared, so they are skipped instead of failing the query
+        let mut mismatched = 0usize;
+        for row_result in rows {
+            let (
+                id,
+                pid,
+                content,
+                metadata,
+                created_at,
+                updated_at,
+                blob,
+                parent_id,
+                kind,
+                origin,
+                title,
+            ) = row_result?;
+            let Some(stored_embedding) = embedding::decode_blob(&blob, query_embedding.len())
+            else {
+                mismatched += 1;
+                continue;
+            };
+            let score = embedding::cosine_similarity(query_embedding, &stored_embedding)?;
+
+            if let Some(parent_id) = parent_id {
+                let best = best_chunks
+                    .entry(parent_id)
+                    .or_insert((f64::NEG_INFINITY, String::new()));
+                if score > best.0 {
+                    *best = (score, content);
+                }
+                continue;
+            }
+
+            let similarity = Some(score);
+            memories.push(Memory {
+                id,
+                project_id: pid,
+                content,
+                metadata,
+                kind,
+                origin,
+                title,
+                similarity,
+                snippet: None,
+                explanation: None,
+                created_at,
+                updated_at,
+            });
+        }
+
+        if mismatched > 0 {
+            log::warn!(
+                "Skipped {} memories whose embeddings don't have the query's {} dimensions; \
+                 run `vipune reembed --mismatched-only` to make them searchable again",
+                mismatched,
+                query_embedding.len()
+            );
+        }
+
+        // A memory scores as its best match, whole or chunk; the snippet
+        // shows the chunk only when the chunk matched better
+        for memory in memories.iter_mut() {
+            if let Some((score, chunk)) = best_chunks.remove(&memory.id) {
+                if memory.similarity.is_none_or(|parent| score > parent) {
+                    memory.similarity = Some(score);
+                    memory.snippet = Some(chunk);
+                }
+            }
+        }
+
+        memories.sort_by(|a, b| {
+            b.similarity
+                .unwrap_or(0.0)
+                .partial_cmp(&a.similarity.unwrap_or(0.0))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        memories.truncate(limit);
+        Ok(memories)
+    }
+
+    /// Number of rows (memories and chunks) a semantic search of the
+    /// project with `filter` decodes and scores.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn semantic_scan_size(&self, project_id: &str, filter: &SearchFilter) -> Result<usize> {
+        let mut sql = String::from("SELECT count(*) FROM memories m WHERE m.project_id = ?1");
+        let mut params = vec![Value::Text(project_id.to_string())];
+        push_filter_conditions(&mut sql, filter, &mut params);
+        let count: i64 = self
+            .conn
+            .query_row(&sql, params_from_iter(&params), |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Find memories similar to the given embedding above a threshold.
+    ///
+    /// Uses semantic search to find all memories with cosine similarity >= threshold
+    /// that pass `filter`, so a viewer only conflicts with memories it can see.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the search fails.
+    pub fn find_similar(
+        &self,
+        project_id: &str,
+        embedding: &[f32],
+        threshold: f64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Memory>> {
+        let all_results = self.search_filtered(project_id, embedding, MAX_SEARCH_LIMIT, filter)?;
+        Ok(all_results
+            .into_iter()
+            .filter(|m| m.similarity.unwrap_or(0.0) >= threshold)
+            .collect())
+    }
+
+    /// Load stored embeddings for the given memory IDs.
+    ///
+    /// IDs that do not exist, or whose embedding has the wrong dimensions,
+    /// are absent from the returned map.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn embeddings_for(&self, ids: &[&str]) -> Result<HashMap<String, Vec<f32>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT embedding FROM memories WHERE id = ?1")?;
+        let mut embeddings = HashMap::with_capacity(ids.len());
+        for id in ids {
+            let blob: Option<Vec<u8>> = stmt.query_row([id], |row| row.get(0)).optional()?;
+            if let Some(blob) = blob {
+                if let Ok(stored) = embedding::blob_to_vec(&blob) {
+                    embeddings.insert(id.to_string(), stored);
+                }
+            }
+        }
+        Ok(embeddings)
+    }
+}
+
+#[cfg(test)]
+mod tests;
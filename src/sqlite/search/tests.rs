@@ -0,0 +1,473 @@
+//! Tests for semantic search, its filters and similarity lookups.
+
+use super::*;
+use crate::search_options::FieldWeights;
+use crate::sqlite::{NewMemory, Visibility};
+use tempfile::TempDir;
+
+fn create_test_db() -> Database {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let db = Database::open(&path).unwrap();
+    std::mem::forget(dir);
+    db
+}
+
+#[test]
+fn test_validate_limit_zero() {
+    assert!(validate_limit(0).is_err());
+}
+
+#[test]
+fn test_validate_limit_too_large() {
+    assert!(validate_limit(SEARCH_LIMIT_CAP + 1).is_err());
+}
+
+#[test]
+fn test_validate_limit_valid() {
+    assert!(validate_limit(10).is_ok());
+    assert!(validate_limit(5000).is_ok());
+}
+
+#[test]
+fn test_search_basic() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "rust programming", &embedding, None)
+        .unwrap();
+    db.insert("proj1", "python data science", &embedding, None)
+        .unwrap();
+
+    let results = db.search("proj1", &embedding, 10).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].similarity.unwrap() >= 0.9);
+}
+
+#[test]
+fn test_search_limit() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    for i in 0..5 {
+        db.insert("proj1", &format!("content {}", i), &embedding, None)
+            .unwrap();
+    }
+
+    let results = db.search("proj1", &embedding, 2).unwrap();
+    assert_eq!(results.len(), 2);
+}
+
+#[test]
+fn test_search_project_isolation() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "project 1 memory", &embedding, None)
+        .unwrap();
+    db.insert("proj2", "project 2 memory", &embedding, None)
+        .unwrap();
+
+    let results = db.search("proj1", &embedding, 10).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].project_id, "proj1");
+}
+
+#[test]
+fn test_search_skips_mismatched_dimensions() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let current = db.insert("proj1", "current", &embedding, None).unwrap();
+    let stale = db.insert("proj1", "stale", &embedding, None).unwrap();
+    db.conn
+        .execute(
+            "UPDATE memories SET embedding = ?2 WHERE id = ?1",
+            rusqlite::params![stale, vec![0u8; 768 * 4]],
+        )
+        .unwrap();
+
+    let results = db.search("proj1", &embedding, 10).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, current);
+
+    let embeddings = db.embeddings_for(&[current.as_str(), &stale]).unwrap();
+    assert_eq!(embeddings.len(), 1);
+    assert!(embeddings.contains_key(&current));
+}
+
+#[test]
+fn test_int8_search_recall_within_tolerance() {
+    // Deterministic pseudo-random unit-scale vectors
+    let mut state = 42u64;
+    let mut next_vector = || -> Vec<f32> {
+        (0..384)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) as f32 / (1u64 << 31) as f32) * 2.0 - 1.0
+            })
+            .collect()
+    };
+    let exact = create_test_db();
+    let mut quantized = create_test_db();
+    quantized.set_embedding_format(embedding::EmbeddingFormat::Int8);
+    for i in 0..200 {
+        let vector = next_vector();
+        let content = format!("memory {}", i);
+        exact.insert("proj1", &content, &vector, None).unwrap();
+        quantized.insert("proj1", &content, &vector, None).unwrap();
+    }
+
+    let mut overlap = 0;
+    for _ in 0..10 {
+        let query = next_vector();
+        let top = |db: &Database| -> Vec<String> {
+            db.search("proj1", &query, 10)
+                .unwrap()
+                .into_iter()
+                .map(|m| m.content)
+                .collect()
+        };
+        let expected = top(&exact);
+        overlap += top(&quantized)
+            .iter()
+            .filter(|c| expected.contains(c))
+            .count();
+    }
+    // Recall@10 of the quantized store against the exact one
+    assert!(overlap >= 90, "recall {}/100", overlap);
+}
+
+#[test]
+fn test_embeddings_for_skips_missing_ids() {
+    let db = create_test_db();
+    let id = db
+        .insert("proj1", "memory", &vec![0.5f32; 384], None)
+        .unwrap();
+
+    let embeddings = db.embeddings_for(&[id.as_str(), "missing"]).unwrap();
+    assert_eq!(embeddings.len(), 1);
+    assert_eq!(embeddings[&id], vec![0.5f32; 384]);
+}
+
+#[test]
+fn test_search_filtered_by_metadata() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let auth = db
+        .insert(
+            "proj1",
+            "auth",
+            &embedding,
+            Some(r#"{"topic": "auth", "priority": 3}"#),
+        )
+        .unwrap();
+    db.insert("proj1", "db", &embedding, Some(r#"{"topic": "db"}"#))
+        .unwrap();
+    db.insert("proj1", "none", &embedding, None).unwrap();
+
+    let filter = SearchFilter {
+        metadata: vec![MetadataFilter::parse("topic=auth").unwrap()],
+        ..SearchFilter::default()
+    };
+    let results = db
+        .search_filtered("proj1", &embedding, 10, &filter)
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, auth);
+
+    let results = db
+        .search_bm25_filtered("auth", "proj1", 10, &filter, &[], FieldWeights::default())
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, auth);
+
+    let range = |spec| SearchFilter {
+        metadata: vec![MetadataFilter::parse(spec).unwrap()],
+        ..SearchFilter::default()
+    };
+    let results = db
+        .search_filtered("proj1", &embedding, 10, &range("priority>2.5"))
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(
+        db.search_filtered("proj1", &embedding, 10, &range("priority<3"))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_search_filter_shrinks_the_scan() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let record = |content: &'static str, metadata, updated_at| NewMemory {
+        project_id: "proj1",
+        content,
+        embedding: &embedding,
+        metadata,
+        kind: MemoryKind::Other,
+        origin: &Origin::Api,
+        created_at: "2024-01-01T00:00:00+00:00",
+        updated_at,
+        owner: None,
+        visibility: Visibility::Shared,
+    };
+    let rust = Some(r#"{"tags": ["rust", "db"]}"#);
+    db.insert_record(&record("old rust note", rust, "2024-01-01T00:00:00+00:00"))
+        .unwrap();
+    let recent = db
+        .insert_record(&record(
+            "recent rust note",
+            rust,
+            "2024-05-01T00:00:00+00:00",
+        ))
+        .unwrap();
+    db.insert_record(&record("recent go note", None, "2024-05-01T00:00:00+00:00"))
+        .unwrap();
+    // Chunks have no metadata of their own and follow their parent's
+    let mut focused = embedding.clone();
+    focused[0] = 0.9;
+    db.insert_chunks(&recent, &[("rust".to_string(), focused.clone())])
+        .unwrap();
+
+    let filter = SearchFilter {
+        metadata: vec![MetadataFilter::parse("tags=rust").unwrap()],
+        since: Some("2024-03-01T00:00:00+00:00".to_string()),
+        ..SearchFilter::default()
+    };
+    let everything = SearchFilter::default();
+    assert_eq!(db.semantic_scan_size("proj1", &everything).unwrap(), 4);
+    assert_eq!(db.semantic_scan_size("proj1", &filter).unwrap(), 2);
+
+    let results = db.search_filtered("proj1", &focused, 10, &filter).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, recent);
+    assert_eq!(results[0].snippet.as_deref(), Some("rust"));
+    assert!(filter.matches(&results[0]));
+
+    let results = db
+        .search_bm25_filtered("note", "proj1", 10, &filter, &[], FieldWeights::default())
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, recent);
+}
+
+#[test]
+fn test_search_as_of_skips_later_memories() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let record = |content: &'static str, updated_at: &'static str| NewMemory {
+        project_id: "proj1",
+        content,
+        embedding: &embedding,
+        metadata: None,
+        kind: MemoryKind::Other,
+        origin: &Origin::Api,
+        created_at: "2024-01-01T00:00:00+00:00",
+        updated_at,
+        owner: None,
+        visibility: Visibility::Shared,
+    };
+    let old = db
+        .insert_record(&record("postgres in january", "2024-01-01T00:00:00+00:00"))
+        .unwrap();
+    db.insert_record(&record("postgres in july", "2024-07-01T00:00:00+00:00"))
+        .unwrap();
+
+    let filter = SearchFilter {
+        as_of: Some("2024-06-01T00:00:00+00:00".to_string()),
+        ..SearchFilter::default()
+    };
+    let results = db
+        .search_filtered("proj1", &embedding, 10, &filter)
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, old);
+
+    let results = db
+        .search_bm25_filtered(
+            "postgres",
+            "proj1",
+            10,
+            &filter,
+            &[],
+            FieldWeights::default(),
+        )
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, old);
+
+    let results = db
+        .search_filtered("proj1", &embedding, 10, &SearchFilter::default())
+        .unwrap();
+    assert_eq!(results.len(), 2);
+}
+
+#[test]
+fn test_search_and_list_filtered_by_kind() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let decision = db
+        .insert_with_kind(
+            "proj1",
+            "use postgres",
+            &embedding,
+            None,
+            MemoryKind::Decision,
+        )
+        .unwrap();
+    db.insert("proj1", "postgres runs on port 5432", &embedding, None)
+        .unwrap();
+    let mut focused = embedding.clone();
+    focused[0] = 0.9;
+    db.insert_chunks(&decision, &[("use".to_string(), focused.clone())])
+        .unwrap();
+    let decisions = SearchFilter {
+        kind: Some(MemoryKind::Decision),
+        ..SearchFilter::default()
+    };
+
+    let results = db
+        .search_filtered("proj1", &focused, 10, &decisions)
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, decision);
+    assert_eq!(results[0].kind, MemoryKind::Decision);
+    assert_eq!(results[0].snippet.as_deref(), Some("use"));
+
+    let results = db
+        .search_bm25_filtered(
+            "postgres",
+            "proj1",
+            10,
+            &decisions,
+            &[],
+            FieldWeights::default(),
+        )
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].kind, MemoryKind::Decision);
+
+    let listed = db
+        .list_filtered("proj1", 10, Some(MemoryKind::Other), None, None)
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].kind, MemoryKind::Other);
+    assert!(
+        db.list_filtered("proj1", 10, Some(MemoryKind::Task), None, None)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        db.get(&decision).unwrap().unwrap().kind,
+        MemoryKind::Decision
+    );
+}
+
+#[test]
+fn test_search_and_list_filtered_by_origin() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let notes = Origin::import("notes.jsonl");
+    let record = NewMemory {
+        project_id: "proj1",
+        content: "postgres by hand",
+        embedding: &embedding,
+        metadata: None,
+        kind: MemoryKind::Other,
+        origin: &Origin::Cli,
+        created_at: "2024-01-01T00:00:00+00:00",
+        updated_at: "2024-01-01T00:00:00+00:00",
+        owner: None,
+        visibility: Visibility::Shared,
+    };
+    let typed = db.insert_record(&record).unwrap();
+    let imported = db
+        .insert_record(&NewMemory {
+            content: "postgres imported",
+            origin: &notes,
+            ..record
+        })
+        .unwrap();
+    db.insert_chunks(&imported, &[("imported".to_string(), embedding.clone())])
+        .unwrap();
+
+    let search = |origin: &Origin| {
+        let filter = SearchFilter {
+            origin: Some(origin.clone()),
+            ..SearchFilter::default()
+        };
+        let semantic = db
+            .search_filtered("proj1", &embedding, 10, &filter)
+            .unwrap();
+        let keyword = db
+            .search_bm25_filtered(
+                "postgres",
+                "proj1",
+                10,
+                &filter,
+                &[],
+                FieldWeights::default(),
+            )
+            .unwrap();
+        let listed = db
+            .list_filtered("proj1", 10, None, Some(origin), None)
+            .unwrap();
+        let ids =
+            |memories: Vec<Memory>| -> Vec<String> { memories.into_iter().map(|m| m.id).collect() };
+        (ids(semantic), ids(keyword), ids(listed))
+    };
+    let only = |id: &String| (vec![id.clone()], vec![id.clone()], vec![id.clone()]);
+    assert_eq!(search(&Origin::Cli), only(&typed));
+    assert_eq!(search(&Origin::import("")), only(&imported));
+    assert_eq!(search(&notes), only(&imported));
+    assert_eq!(
+        search(&Origin::import("other.jsonl")).2,
+        Vec::<String>::new()
+    );
+    assert_eq!(search(&Origin::Hook).0, Vec::<String>::new());
+
+    let memory = db.get(&imported).unwrap().unwrap();
+    assert_eq!(memory.origin, notes);
+    assert_eq!(db.get(&typed).unwrap().unwrap().origin, Origin::Cli);
+}
+
+#[test]
+fn test_find_similar_with_threshold() {
+    let db = create_test_db();
+    let embedding1 = vec![1.0f32; 384];
+    let mut embedding2 = vec![1.0f32; 384];
+    embedding2[0] = 0.0; // Slightly different
+
+    db.insert("proj1", "memory 1", &embedding1, None).unwrap();
+    db.insert("proj1", "memory 2", &embedding2, None).unwrap();
+
+    let results = db
+        .find_similar("proj1", &embedding1, 0.99, &SearchFilter::default())
+        .unwrap();
+    assert!(!results.is_empty());
+}
+
+#[test]
+fn test_find_similar_hides_private_memories_from_other_viewers() {
+    let db = create_test_db();
+    let embedding = vec![1.0f32; 384];
+    let id = db.insert("proj1", "bob's note", &embedding, None).unwrap();
+    db.set_access(&id, Some("bob"), Visibility::Private)
+        .unwrap();
+
+    let viewer = |name: &str| SearchFilter {
+        viewer: Some(name.to_string()),
+        ..SearchFilter::default()
+    };
+    assert!(
+        db.find_similar("proj1", &embedding, 0.9, &viewer("alice"))
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        db.find_similar("proj1", &embedding, 0.9, &viewer("bob"))
+            .unwrap()
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,76 @@
+//! Whole-database snapshots and restores, for `vipune archive`.
+
+use std::path::Path;
+
+use rusqlite::MAIN_DB;
+
+use super::{Database, Result};
+
+impl Database {
+    /// Write a consistent, compacted copy of the database to `path`, which
+    /// must not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `path` exists or the copy cannot be written.
+    pub fn snapshot_to(&self, path: &Path) -> Result<()> {
+        self.conn
+            .execute("VACUUM INTO ?1", [path.to_string_lossy().as_ref()])?;
+        Ok(())
+    }
+
+    /// Replace the whole database with the contents of the database file at
+    /// `path`, through SQLite's backup API so journal files stay consistent.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `path` cannot be read or the copy fails.
+    pub fn restore_from(&mut self, path: &Path) -> Result<()> {
+        self.conn
+            .restore(MAIN_DB, path, None::<fn(rusqlite::backup::Progress)>)?;
+        Ok(())
+    }
+
+    /// Count every memory in the database (chunks are not counted separately).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn count_all(&self) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM memories WHERE parent_id IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_then_restore() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let snapshot = dir.path().join("snapshot.db");
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+
+        let source = Database::open(&dir.path().join("source.db")).unwrap();
+        source
+            .insert("a", "alpha memory", &embedding, None)
+            .unwrap();
+        source.insert("b", "beta memory", &embedding, None).unwrap();
+        source.snapshot_to(&snapshot).unwrap();
+        assert!(source.snapshot_to(&snapshot).is_err());
+
+        let mut target = Database::open(&dir.path().join("target.db")).unwrap();
+        target
+            .insert("c", "gamma memory", &embedding, None)
+            .unwrap();
+        target.restore_from(&snapshot).unwrap();
+        assert_eq!(target.count_all().unwrap(), 2);
+        assert_eq!(target.count_project("c").unwrap(), 0);
+        assert_eq!(target.search_bm25("beta", "b", 10).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,150 @@
+//! Deterministic memory IDs.
+//!
+//! With `stable_ids` set, a new memory's ID is derived from its project and
+//! content instead of drawn at random, so the same text added to the same
+//! project on any machine gets the same ID. Adding it again updates the
+//! existing memory in place (an upsert), which makes re-imports idempotent
+//! and lets stores be deduplicated by ID.
+//!
+//! IDs are standard name-based UUIDs (version 5, RFC 9562) in [`NAMESPACE`],
+//! so any UUID library can reproduce them.
+
+use rusqlite::{Connection, OptionalExtension};
+use uuid::Uuid;
+
+use super::{Database, Result};
+
+/// Namespace of stable memory IDs; changing it changes every stable ID.
+const NAMESPACE: Uuid = Uuid::from_u128(0xef59_35ce_e3b9_4ff9_8818_f352_e9ef_76a7);
+
+/// Stable ID of a memory in `project_id` with `content`.
+///
+/// The name is the project ID and the content separated by a newline, which
+/// project IDs never contain.
+pub(crate) fn stable_id(project_id: &str, content: &str) -> String {
+    Uuid::new_v5(&NAMESPACE, format!("{project_id}\n{content}").as_bytes()).to_string()
+}
+
+impl Database {
+    /// Derive the IDs of memories inserted from now on from their project
+    /// and content (see [`stable_id`]), upserting on a repeated insert, or
+    /// draw them at random (the default).
+    pub fn set_stable_ids(&mut self, enabled: bool) {
+        self.stable_ids = enabled;
+    }
+
+    /// ID for a new memory in `project_id` with `content`, and whether a
+    /// memory of that project already has it and must be updated instead.
+    ///
+    /// A stable ID held by another project (after a project rename) is not
+    /// reused; the memory gets a random ID.
+    pub(super) fn new_id(
+        &self,
+        conn: &Connection,
+        project_id: &str,
+        content: &str,
+    ) -> Result<(String, bool)> {
+        if !self.stable_ids {
+            return Ok((Uuid::new_v4().to_string(), false));
+        }
+        let id = stable_id(project_id, content);
+        let owner: Option<String> = conn
+            .query_row(
+                "SELECT project_id FROM memories WHERE id = ?1",
+                [&id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match owner {
+            None => Ok((id, false)),
+            Some(owner) if owner == project_id => Ok((id, true)),
+            Some(_) => Ok((Uuid::new_v4().to_string(), false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_db() -> Database {
+        let mut db = Database::open_in_memory().unwrap();
+        db.set_stable_ids(true);
+        db
+    }
+
+    #[test]
+    fn test_stable_id_is_deterministic() {
+        let id = stable_id("proj", "Alice works at Microsoft");
+        assert_eq!(id, stable_id("proj", "Alice works at Microsoft"));
+        assert_ne!(id, stable_id("proj", "alice works at microsoft"));
+        assert_ne!(id, stable_id("other", "Alice works at Microsoft"));
+        assert_ne!(id, stable_id("proj", "Alice works at Google"));
+        let uuid = Uuid::parse_str(&id).unwrap();
+        assert_eq!(uuid.get_version_num(), 5);
+        assert_eq!(
+            uuid,
+            Uuid::new_v5(&NAMESPACE, b"proj\nAlice works at Microsoft")
+        );
+    }
+
+    #[test]
+    fn test_repeated_insert_upserts() {
+        let db = create_test_db();
+        let embedding = vec![0.1f32; 384];
+        let id = db
+            .insert("proj", "use postgres", &embedding, Some(r#"{"v": 1}"#))
+            .unwrap();
+        assert_eq!(id, stable_id("proj", "use postgres"));
+        db.insert_chunks(&id, &[("use".to_string(), embedding.clone())])
+            .unwrap();
+
+        let again = db
+            .insert("proj", "use postgres", &embedding, Some(r#"{"v": 2}"#))
+            .unwrap();
+        assert_eq!(again, id);
+        assert_eq!(db.list("proj", 10).unwrap().len(), 1);
+        let memory = db.get(&id).unwrap().unwrap();
+        assert_eq!(memory.content, "use postgres");
+        assert_eq!(memory.metadata.as_deref(), Some(r#"{"v": 2}"#));
+        // The caller stores the chunks of the new content
+        assert_eq!(db.count_chunks(&id).unwrap(), 0);
+
+        // Random IDs when disabled
+        let mut db = db;
+        db.set_stable_ids(false);
+        let random = db.insert("proj", "use postgres", &embedding, None).unwrap();
+        assert_ne!(random, id);
+        assert_eq!(db.list("proj", 10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_stable_id_of_another_project_is_not_reused() {
+        let db = create_test_db();
+        let embedding = vec![0.1f32; 384];
+        let id = db.insert("old", "use postgres", &embedding, None).unwrap();
+        db.conn
+            .execute("UPDATE memories SET project_id = 'new'", [])
+            .unwrap();
+
+        let other = db.insert("old", "use postgres", &embedding, None).unwrap();
+        assert_ne!(other, id);
+        assert_eq!(db.get(&id).unwrap().unwrap().project_id, "new");
+    }
+
+    #[test]
+    fn test_insert_replaces_trashed_copy() {
+        let db = create_test_db();
+        let embedding = vec![0.1f32; 384];
+        let id = db.insert("proj", "use postgres", &embedding, None).unwrap();
+        assert!(db.trash(&id).unwrap());
+        assert_eq!(db.trashed("proj").unwrap().len(), 1);
+
+        assert_eq!(
+            db.insert("proj", "use postgres", &embedding, None).unwrap(),
+            id
+        );
+        assert!(db.trashed("proj").unwrap().is_empty());
+        assert!(!db.restore(&id).unwrap());
+    }
+}
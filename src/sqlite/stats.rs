@@ -0,0 +1,130 @@
+//! Per-project aggregates for `vipune stats`.
+
+use rusqlite::OptionalExtension;
+
+use super::{Database, MemoryKind, Result};
+
+impl Database {
+    /// Number of memories of each kind in a project (chunk rows excluded),
+    /// most common first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn kind_counts(&self, project_id: &str) -> Result<Vec<(MemoryKind, usize)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT kind, COUNT(*) AS n
+            FROM memories
+            WHERE project_id = ?1 AND parent_id IS NULL
+            GROUP BY kind
+            ORDER BY n DESC, kind ASC
+            "#,
+        )?;
+        let counts = stmt
+            .query_map([project_id], |row| {
+                Ok((row.get::<_, MemoryKind>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+
+    /// Number of memories in a project per detected language (ISO 639-3),
+    /// most common first; memories without a detected language are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn language_counts(&self, project_id: &str) -> Result<Vec<(String, usize)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT language, COUNT(*) AS n
+            FROM memories
+            WHERE project_id = ?1 AND parent_id IS NULL AND language IS NOT NULL
+            GROUP BY language
+            ORDER BY n DESC, language ASC
+            "#,
+        )?;
+        let counts = stmt
+            .query_map([project_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+
+    /// Content of every memory in a project (chunk rows excluded).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn contents(&self, project_id: &str) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT vipune_content(content, content_zstd) FROM memories WHERE project_id = ?1 AND parent_id IS NULL")?;
+        let contents = stmt
+            .query_map([project_id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(contents)
+    }
+
+    /// Earliest `created_at` and latest `updated_at` of a project's memories,
+    /// or `None` if it has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn time_range(&self, project_id: &str) -> Result<Option<(String, String)>> {
+        let range = self
+            .conn
+            .query_row(
+                r#"
+                SELECT MIN(created_at), MAX(updated_at)
+                FROM memories
+                WHERE project_id = ?1 AND parent_id IS NULL
+                HAVING COUNT(*) > 0
+                "#,
+                [project_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_counts_and_time_range() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1f32; 384];
+        assert!(db.kind_counts("proj").unwrap().is_empty());
+        assert_eq!(db.time_range("proj").unwrap(), None);
+
+        let first = db
+            .insert_with_kind("proj", "a", &embedding, None, MemoryKind::Fact)
+            .unwrap();
+        db.insert_with_kind("proj", "b", &embedding, None, MemoryKind::Task)
+            .unwrap();
+        let last = db
+            .insert_with_kind("proj", "c", &embedding, None, MemoryKind::Fact)
+            .unwrap();
+        db.insert("other", "d", &embedding, None).unwrap();
+        db.insert_chunks(&first, &[("chunk".to_string(), embedding.clone())])
+            .unwrap();
+
+        assert_eq!(
+            db.kind_counts("proj").unwrap(),
+            vec![(MemoryKind::Fact, 2), (MemoryKind::Task, 1)]
+        );
+        let (oldest, newest) = db.time_range("proj").unwrap().unwrap();
+        assert_eq!(oldest, db.get(&first).unwrap().unwrap().created_at);
+        assert_eq!(newest, db.get(&last).unwrap().unwrap().updated_at);
+
+        let mut contents = db.contents("proj").unwrap();
+        contents.sort();
+        assert_eq!(contents, vec!["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,165 @@
+//! Store-level facts kept in the `store_meta` key/value table: when the
+//! store was created, which schema and vipune version last opened it, and
+//! which embedding model its vectors come from.
+//!
+//! The versions are written on open. The embedding model is recorded by
+//! [`MemoryStore`](crate::MemoryStore), which refuses to open a store built
+//! with another model, so vectors of incompatible models are never mixed.
+
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+
+use super::{Database, Result, SCHEMA_VERSION};
+
+/// Key/value table of store-level facts.
+pub(super) const STORE_META_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS store_meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+"#;
+
+const CREATED_AT: &str = "created_at";
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+const CRATE_VERSION: &str = "crate_version";
+const EMBEDDING_MODEL: &str = "embedding_model";
+const EMBEDDING_DIMS: &str = "embedding_dims";
+
+/// Store-level facts from the `store_meta` table.
+///
+/// Returned by `Database::store_meta()` and reported by `vipune stats`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoreMeta {
+    /// HuggingFace ID of the model the stored embeddings come from, if
+    /// recorded (stores are stamped the first time a `MemoryStore` with an
+    /// embedding model opens them).
+    pub embedding_model: Option<String>,
+    /// Dimensions of the stored embeddings, if recorded.
+    pub embedding_dims: Option<usize>,
+    /// Schema version of the database.
+    pub schema_version: u32,
+    /// vipune version that last opened the database.
+    pub crate_version: Option<String>,
+    /// When the store was created (RFC3339). For stores older than the
+    /// table, the creation time of their oldest memory.
+    pub created_at: Option<String>,
+}
+
+/// Record the creation time (once) and the current schema and crate
+/// versions, writing only what changed.
+pub(super) fn record_versions(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO store_meta (key, value)
+         VALUES (?1, COALESCE((SELECT MIN(created_at) FROM memories), ?2))",
+        params![CREATED_AT, Utc::now().to_rfc3339()],
+    )?;
+    set(conn, SCHEMA_VERSION_KEY, &SCHEMA_VERSION.to_string())?;
+    set(conn, CRATE_VERSION, env!("CARGO_PKG_VERSION"))
+}
+
+fn get(conn: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT value FROM store_meta WHERE key = ?1",
+            [key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Set `key` to `value` unless it already has that value.
+fn set(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    if get(conn, key)?.as_deref() != Some(value) {
+        conn.execute(
+            "INSERT OR REPLACE INTO store_meta (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+    }
+    Ok(())
+}
+
+impl Database {
+    /// Store-level facts: embedding model, schema and vipune versions and
+    /// creation time.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn store_meta(&self) -> Result<StoreMeta> {
+        Ok(StoreMeta {
+            embedding_model: get(&self.conn, EMBEDDING_MODEL)?,
+            embedding_dims: get(&self.conn, EMBEDDING_DIMS)?.and_then(|d| d.parse().ok()),
+            schema_version: get(&self.conn, SCHEMA_VERSION_KEY)?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(SCHEMA_VERSION),
+            crate_version: get(&self.conn, CRATE_VERSION)?,
+            created_at: get(&self.conn, CREATED_AT)?,
+        })
+    }
+
+    /// Record the model the stored embeddings come from and their dimensions.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write fails.
+    pub fn set_embedding_model(&self, model_id: &str, dims: usize) -> Result<()> {
+        let tx = self.write_transaction()?;
+        set(&tx, EMBEDDING_MODEL, model_id)?;
+        set(&tx, EMBEDDING_DIMS, &dims.to_string())?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_meta_records_versions_and_model() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("meta.db");
+        let db = Database::open(&path).unwrap();
+        let meta = db.store_meta().unwrap();
+        assert_eq!(meta.schema_version, SCHEMA_VERSION);
+        assert_eq!(
+            meta.crate_version.as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert!(meta.created_at.is_some());
+        assert_eq!(meta.embedding_model, None);
+
+        db.set_embedding_model("BAAI/bge-small-en-v1.5", 384)
+            .unwrap();
+        drop(db);
+
+        // Reopening keeps the creation time and model
+        let db = Database::open(&path).unwrap();
+        let reopened = db.store_meta().unwrap();
+        assert_eq!(reopened.created_at, meta.created_at);
+        assert_eq!(
+            reopened.embedding_model.as_deref(),
+            Some("BAAI/bge-small-en-v1.5")
+        );
+        assert_eq!(reopened.embedding_dims, Some(384));
+    }
+
+    #[test]
+    fn test_existing_store_dates_from_its_oldest_memory() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert("p", "old", &[0.1; crate::embedding::EMBEDDING_DIMS], None)
+            .unwrap();
+        db.conn
+            .execute_batch(
+                "UPDATE memories SET created_at = '2023-05-01T00:00:00+00:00';
+                 DELETE FROM store_meta;",
+            )
+            .unwrap();
+        record_versions(&db.conn).unwrap();
+        assert_eq!(
+            db.store_meta().unwrap().created_at.as_deref(),
+            Some("2023-05-01T00:00:00+00:00")
+        );
+    }
+}
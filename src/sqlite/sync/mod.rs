@@ -0,0 +1,429 @@
+//! Change exchange between two stores for `vipune sync`.
+//!
+//! Every mutation already has a sequence number in the audit log, so the
+//! changes of a store since a sync are the memories whose latest audit entry
+//! is newer than the sequence it reached. [`Database::changes_since`] reads
+//! their current state (or a tombstone, for memories no longer there) and
+//! [`Database::apply_changes`] writes them into the other store in one
+//! transaction:
+//!
+//! - A memory with the same ID is overwritten if the incoming copy was
+//!   updated later, and kept otherwise.
+//! - A memory without a counterpart is matched to a similar one of its
+//!   project (similarity at least the threshold, as conflict detection
+//!   does), which the incoming copy overwrites if it was updated later.
+//!   Otherwise it is added with its own ID.
+//! - A deleted memory is moved to the trash unless it was updated after the
+//!   delete.
+//!
+//! Incoming copies keep their owner, visibility, language and model revision,
+//! so a private memory stays private to its owner in the receiving store.
+//! They also keep their `updated_at`, so a change sent back to the
+//! store it came from is recognized as not newer and writes nothing.
+//!
+//! `sync_state` records, per peer database, the sequences reached by the
+//! last push (in this store) and pull (in the peer).
+
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, params};
+use serde::Serialize;
+
+use super::embedding::blob_to_vec;
+use super::{Database, Memory, NewMemory, Result, Visibility, audit, content_hash};
+use crate::search_options::SearchFilter;
+
+/// Schema for the per-peer sync positions.
+pub(super) const SYNC_STATE_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS sync_state (
+        peer TEXT PRIMARY KEY,
+        pushed_seq INTEGER NOT NULL DEFAULT 0,
+        pulled_seq INTEGER NOT NULL DEFAULT 0,
+        synced_at TEXT
+    );
+"#;
+
+/// The state of a memory changed since a sync.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncChange {
+    /// The memory as it is now, with its embedding and chunks.
+    Upsert(Box<SyncedMemory>),
+    /// The memory was deleted, trashed or archived.
+    Delete {
+        /// ID of the removed memory.
+        id: String,
+        /// When it was removed (RFC3339).
+        deleted_at: String,
+    },
+}
+
+/// A memory carried to another store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncedMemory {
+    /// The memory row.
+    pub memory: Memory,
+    /// Its stored embedding.
+    pub embedding: Vec<f32>,
+    /// Content and embedding of its chunks, in order.
+    pub chunks: Vec<(String, Vec<f32>)>,
+    /// Owner of the memory (`None` if unlabeled).
+    pub owner: Option<String>,
+    /// Who besides the owner can see it.
+    pub visibility: Visibility,
+    /// Detected language of its content.
+    pub language: Option<String>,
+    /// Revision of the model that embedded it.
+    pub model_revision: Option<String>,
+}
+
+/// What a sync changed in the receiving store.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SyncStats {
+    /// Changed memories sent.
+    pub changes: usize,
+    /// Memories added with their own ID.
+    pub added: usize,
+    /// Memories overwritten by a copy updated later.
+    pub updated: usize,
+    /// Similar memories overwritten by a copy updated later.
+    pub merged: usize,
+    /// Memories moved to the trash.
+    pub deleted: usize,
+    /// Changes ignored because the receiving store had the same or a newer
+    /// version.
+    pub skipped: usize,
+}
+
+/// Sequences reached by the last sync with a peer (0 before the first).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncState {
+    /// Sequence of this store the last push sent changes up to.
+    pub pushed_seq: i64,
+    /// Sequence of the peer the last pull received changes up to.
+    pub pulled_seq: i64,
+    /// When the last sync finished (RFC3339).
+    pub synced_at: Option<String>,
+}
+
+/// Parent ID, embedding, owner, visibility, language and model revision of a
+/// stored row.
+type StoredRow = (
+    Option<String>,
+    Vec<u8>,
+    Option<String>,
+    Visibility,
+    Option<String>,
+    Option<String>,
+);
+
+/// Whether timestamp `a` is later than `b` (compared as text if either is
+/// not RFC3339).
+fn later(a: &str, b: &str) -> bool {
+    match (
+        DateTime::parse_from_rfc3339(a),
+        DateTime::parse_from_rfc3339(b),
+    ) {
+        (Ok(a), Ok(b)) => a > b,
+        _ => a > b,
+    }
+}
+
+impl Database {
+    /// Sync positions recorded for `peer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn sync_state(&self, peer: &str) -> Result<SyncState> {
+        let state = self
+            .conn
+            .query_row(
+                "SELECT pushed_seq, pulled_seq, synced_at FROM sync_state WHERE peer = ?1",
+                [peer],
+                |row| {
+                    Ok(SyncState {
+                        pushed_seq: row.get(0)?,
+                        pulled_seq: row.get(1)?,
+                        synced_at: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(state.unwrap_or_default())
+    }
+
+    /// Record the sync positions reached with `peer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the write fails.
+    pub fn set_sync_state(&self, peer: &str, pushed_seq: i64, pulled_seq: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO sync_state (peer, pushed_seq, pulled_seq, synced_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![peer, pushed_seq, pulled_seq, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Memories changed after audit sequence `since`, in the order of their
+    /// latest change, and the sequence they reach.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a query fails or a stored embedding is malformed.
+    pub fn changes_since(&self, since: i64) -> Result<(Vec<SyncChange>, i64)> {
+        let mut stmt = self.conn.prepare(
+            "SELECT memory_id, timestamp, MAX(seq) FROM audit_log
+             WHERE seq > ?1 GROUP BY memory_id ORDER BY MAX(seq)",
+        )?;
+        let latest = stmt
+            .query_map([since], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut reached = since;
+        let mut changes = Vec::with_capacity(latest.len());
+        for (id, timestamp, seq) in latest {
+            reached = reached.max(seq);
+            let row: Option<StoredRow> = self
+                .conn
+                .query_row(
+                    "SELECT parent_id, embedding, owner, visibility, language, model_revision
+                     FROM memories WHERE id = ?1",
+                    [&id],
+                    |row| {
+                        Ok((
+                            row.get(0)?,
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                            row.get(5)?,
+                        ))
+                    },
+                )
+                .optional()?;
+            match row {
+                // Chunks travel with their parent
+                Some((Some(_), ..)) => {}
+                Some((None, blob, owner, visibility, language, model_revision)) => {
+                    let Some(memory) = self.get(&id)? else {
+                        continue;
+                    };
+                    changes.push(SyncChange::Upsert(Box::new(SyncedMemory {
+                        memory,
+                        embedding: blob_to_vec(&blob)?,
+                        chunks: self.chunk_rows(&id)?,
+                        owner,
+                        visibility,
+                        language,
+                        model_revision,
+                    })));
+                }
+                None => changes.push(SyncChange::Delete {
+                    id,
+                    deleted_at: timestamp,
+                }),
+            }
+        }
+        Ok((changes, reached))
+    }
+
+    /// Content and embedding of the chunks of `parent_id`, in order.
+    fn chunk_rows(&self, parent_id: &str) -> Result<Vec<(String, Vec<f32>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT vipune_content(content, content_zstd), embedding FROM memories
+             WHERE parent_id = ?1 ORDER BY rowid",
+        )?;
+        let rows = stmt
+            .query_map([parent_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        rows.into_iter()
+            .map(|(content, blob)| Ok((content, blob_to_vec(&blob)?)))
+            .collect()
+    }
+
+    /// Apply `changes` from another store in one transaction, matching
+    /// memories without a counterpart to ones with similarity at least
+    /// `threshold`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a write fails; nothing is applied then.
+    pub fn apply_changes(&self, changes: &[SyncChange], threshold: f64) -> Result<SyncStats> {
+        let mut stats = SyncStats {
+            changes: changes.len(),
+            ..Default::default()
+        };
+        let tx = self.write_transaction()?;
+        for change in changes {
+            match change {
+                SyncChange::Upsert(incoming) => {
+                    self.apply_upsert(incoming, threshold, &mut stats)?
+                }
+                SyncChange::Delete { id, deleted_at } => match self.get(id)? {
+                    Some(local) if !later(&local.updated_at, deleted_at) => {
+                        self.trash(id)?;
+                        stats.deleted += 1;
+                    }
+                    _ => stats.skipped += 1,
+                },
+            }
+        }
+        tx.commit()?;
+        Ok(stats)
+    }
+
+    fn apply_upsert(
+        &self,
+        incoming: &SyncedMemory,
+        threshold: f64,
+        stats: &mut SyncStats,
+    ) -> Result<()> {
+        let memory = &incoming.memory;
+        if let Some(local) = self.get(&memory.id)? {
+            if later(&memory.updated_at, &local.updated_at) {
+                self.overwrite(&local.id, incoming)?;
+                stats.updated += 1;
+            } else {
+                stats.skipped += 1;
+            }
+            return Ok(());
+        }
+
+        let trashed_at: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT deleted_at FROM memories_trash WHERE id = ?1 AND parent_id IS NULL",
+                [&memory.id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(trashed_at) = trashed_at {
+            if !later(&memory.updated_at, &trashed_at) {
+                stats.skipped += 1;
+                return Ok(());
+            }
+            // The copy updated after the delete wins; a restore would collide
+            self.conn.execute(
+                "DELETE FROM memories_trash WHERE id = ?1 OR parent_id = ?1",
+                [&memory.id],
+            )?;
+        }
+
+        let similar = self.find_similar(
+            &memory.project_id,
+            &incoming.embedding,
+            threshold,
+            &SearchFilter::default(),
+        )?;
+        if let Some(local) = similar.first() {
+            if later(&memory.updated_at, &local.updated_at) {
+                self.overwrite(&local.id, incoming)?;
+                stats.merged += 1;
+            } else {
+                stats.skipped += 1;
+            }
+            return Ok(());
+        }
+
+        self.insert_synced(incoming)?;
+        stats.added += 1;
+        Ok(())
+    }
+
+    /// Replace memory `id` with the incoming copy, keeping its ID, origin and
+    /// creation time. Unlike [`Database::replace_record`], the copy's project,
+    /// title, owner, visibility, language and model revision win.
+    fn overwrite(&self, id: &str, incoming: &SyncedMemory) -> Result<()> {
+        let memory = &incoming.memory;
+        let tx = self.write_transaction()?;
+        // Moved first so the audit entry records the new project
+        tx.execute(
+            "UPDATE memories SET project_id = ?1 WHERE id = ?2",
+            params![memory.project_id, id],
+        )?;
+        self.replace_record(
+            id,
+            &NewMemory {
+                project_id: &memory.project_id,
+                content: &memory.content,
+                embedding: &incoming.embedding,
+                metadata: memory.metadata.as_deref(),
+                kind: memory.kind,
+                origin: &memory.origin,
+                created_at: &memory.created_at,
+                updated_at: &memory.updated_at,
+                owner: None,
+                visibility: Visibility::Shared,
+            },
+        )?;
+        // Chunks inserted below inherit the owner and visibility
+        tx.execute(
+            "UPDATE memories SET title = ?1, owner = ?2, visibility = ?3, language = ?4,
+                 model_revision = ?5
+             WHERE id = ?6",
+            params![
+                memory.title,
+                incoming.owner,
+                incoming.visibility,
+                incoming.language,
+                incoming.model_revision,
+                id
+            ],
+        )?;
+        self.insert_chunks(id, &incoming.chunks)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Insert the incoming copy under its own ID.
+    fn insert_synced(&self, incoming: &SyncedMemory) -> Result<()> {
+        let memory = &incoming.memory;
+        let blob = self.encode_embedding(&incoming.embedding)?;
+        let (content, zstd) = self.encode_content(&memory.content)?;
+        let tx = self.write_transaction()?;
+        tx.execute(
+            r#"
+            INSERT INTO memories
+                (id, project_id, content, compressed, embedding, metadata, kind, origin,
+                 created_at, updated_at, title, content_hash, content_zstd, owner, visibility,
+                 language, model_revision)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+            "#,
+            params![
+                memory.id,
+                memory.project_id,
+                content,
+                zstd.is_some(),
+                &blob,
+                memory.metadata,
+                memory.kind,
+                memory.origin,
+                memory.created_at,
+                memory.updated_at,
+                memory.title,
+                content_hash::content_hash(&memory.content),
+                zstd,
+                incoming.owner,
+                incoming.visibility,
+                incoming.language,
+                incoming.model_revision
+            ],
+        )?;
+        audit::record_where(&tx, audit::OP_ADD, "id = ?1", [&memory.id])?;
+        self.insert_chunks(&memory.id, &incoming.chunks)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,193 @@
+//! Tests for reading changes and applying them to another store.
+
+use super::*;
+use crate::fixtures::axis;
+
+fn set_updated_at(db: &Database, id: &str, updated_at: &str) {
+    db.conn
+        .execute(
+            "UPDATE memories SET updated_at = ?1 WHERE id = ?2",
+            [updated_at, id],
+        )
+        .unwrap();
+}
+
+fn sync(from: &Database, to: &Database, since: i64) -> (SyncStats, i64) {
+    let (changes, reached) = from.changes_since(since).unwrap();
+    (to.apply_changes(&changes, 0.9).unwrap(), reached)
+}
+
+#[test]
+fn test_changes_are_applied_once() {
+    let laptop = Database::open_in_memory().unwrap();
+    let desktop = Database::open_in_memory().unwrap();
+    let id = laptop
+        .insert(
+            "proj",
+            "Alice works at Microsoft",
+            &axis(0),
+            Some(r#"{"a": 1}"#),
+        )
+        .unwrap();
+    laptop
+        .insert_chunks(&id, &[("Alice".to_string(), axis(0))])
+        .unwrap();
+
+    let (stats, reached) = sync(&laptop, &desktop, 0);
+    assert_eq!((stats.added, stats.skipped), (1, 0));
+    assert_eq!(reached, laptop.write_seq().unwrap());
+    let copy = desktop.get(&id).unwrap().unwrap();
+    assert_eq!(copy, laptop.get(&id).unwrap().unwrap());
+    assert_eq!(desktop.count_chunks(&id).unwrap(), 1);
+
+    // Nothing new on the laptop, and the echo from the desktop is no-op
+    assert!(laptop.changes_since(reached).unwrap().0.is_empty());
+    let (stats, _) = sync(&desktop, &laptop, 0);
+    assert_eq!((stats.changes, stats.skipped), (1, 1));
+    assert_eq!(laptop.write_seq().unwrap(), reached);
+}
+
+#[test]
+fn test_later_update_wins() {
+    let laptop = Database::open_in_memory().unwrap();
+    let desktop = Database::open_in_memory().unwrap();
+    let id = laptop
+        .insert("proj", "use postgres", &axis(0), None)
+        .unwrap();
+    let (_, reached) = sync(&laptop, &desktop, 0);
+
+    laptop.update(&id, "use postgres 16", &axis(0)).unwrap();
+    set_updated_at(&laptop, &id, "2030-01-01T00:00:00+00:00");
+    desktop.update(&id, "use postgres 15", &axis(0)).unwrap();
+    set_updated_at(&desktop, &id, "2029-01-01T00:00:00+00:00");
+
+    // The desktop's older edit does not overwrite the laptop's
+    let (stats, _) = sync(&desktop, &laptop, 0);
+    assert_eq!(stats.skipped, 1);
+    assert_eq!(laptop.get(&id).unwrap().unwrap().content, "use postgres 16");
+
+    let (stats, _) = sync(&laptop, &desktop, reached);
+    assert_eq!(stats.updated, 1);
+    let copy = desktop.get(&id).unwrap().unwrap();
+    assert_eq!(copy.content, "use postgres 16");
+    assert_eq!(copy.updated_at, "2030-01-01T00:00:00+00:00");
+}
+
+#[test]
+fn test_similar_memory_is_merged() {
+    let laptop = Database::open_in_memory().unwrap();
+    let desktop = Database::open_in_memory().unwrap();
+    let local = desktop
+        .insert("proj", "Alice works at Microsoft", &axis(0), None)
+        .unwrap();
+    set_updated_at(&desktop, &local, "2020-01-01T00:00:00+00:00");
+    laptop
+        .insert("proj", "Alice works at Microsoft now", &axis(0), None)
+        .unwrap();
+
+    let (stats, _) = sync(&laptop, &desktop, 0);
+    assert_eq!(stats.merged, 1);
+    let memories = desktop.list("proj", 10).unwrap();
+    assert_eq!(memories.len(), 1);
+    assert_eq!(memories[0].id, local);
+    assert_eq!(memories[0].content, "Alice works at Microsoft now");
+
+    // A dissimilar memory is added
+    laptop
+        .insert("proj", "Bob uses vim", &axis(1), None)
+        .unwrap();
+    let (stats, _) = sync(&laptop, &desktop, 0);
+    assert_eq!((stats.added, stats.skipped), (1, 1));
+}
+
+#[test]
+fn test_deletes_move_to_trash_unless_updated_later() {
+    let laptop = Database::open_in_memory().unwrap();
+    let desktop = Database::open_in_memory().unwrap();
+    let kept = laptop.insert("proj", "kept", &axis(0), None).unwrap();
+    let gone = laptop.insert("proj", "gone", &axis(1), None).unwrap();
+    let (_, reached) = sync(&laptop, &desktop, 0);
+
+    laptop.delete(&gone).unwrap();
+    laptop.delete(&kept).unwrap();
+    set_updated_at(&desktop, &kept, "2999-01-01T00:00:00+00:00");
+    let (stats, reached) = sync(&laptop, &desktop, reached);
+    assert_eq!((stats.deleted, stats.skipped), (1, 1));
+    assert!(desktop.get(&gone).unwrap().is_none());
+    assert_eq!(desktop.trashed("proj").unwrap().len(), 1);
+    assert!(desktop.get(&kept).unwrap().is_some());
+
+    // The kept memory comes back to the laptop, replacing nothing
+    let (stats, _) = sync(&desktop, &laptop, 0);
+    assert_eq!(stats.added, 1);
+    assert!(laptop.get(&kept).unwrap().is_some());
+    assert!(laptop.get(&gone).unwrap().is_none());
+    assert_eq!(laptop.changes_since(reached).unwrap().0.len(), 1);
+}
+
+#[test]
+fn test_private_memory_stays_private() {
+    let laptop = Database::open_in_memory().unwrap();
+    let desktop = Database::open_in_memory().unwrap();
+    let id = laptop
+        .insert("proj", "alice's salary", &axis(0), None)
+        .unwrap();
+    laptop
+        .insert_chunks(&id, &[("salary".to_string(), axis(0))])
+        .unwrap();
+    laptop
+        .set_access(&id, Some("alice"), Visibility::Private)
+        .unwrap();
+    laptop.set_language(&id, Some("eng")).unwrap();
+    laptop.set_model_revision(&id, Some("abc123")).unwrap();
+    let (_, reached) = sync(&laptop, &desktop, 0);
+
+    let private = Some((Some("alice".to_string()), Visibility::Private));
+    assert_eq!(desktop.access(&id).unwrap(), private);
+    let chunk: (Option<String>, Visibility) = desktop
+        .conn
+        .query_row(
+            "SELECT owner, visibility FROM memories WHERE parent_id = ?1",
+            [&id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(Some(chunk), private);
+    let (language, revision): (Option<String>, Option<String>) = desktop
+        .conn
+        .query_row(
+            "SELECT language, model_revision FROM memories WHERE id = ?1",
+            [&id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(
+        (language.as_deref(), revision.as_deref()),
+        (Some("eng"), Some("abc123"))
+    );
+    assert!(
+        desktop
+            .list_filtered("proj", 10, None, None, Some("bob"))
+            .unwrap()
+            .is_empty()
+    );
+
+    // A later update restores the labels changed on the receiving side
+    desktop.set_access(&id, None, Visibility::Shared).unwrap();
+    laptop.update(&id, "alice's new salary", &axis(0)).unwrap();
+    set_updated_at(&laptop, &id, "2999-01-01T00:00:00+00:00");
+    let (stats, _) = sync(&laptop, &desktop, reached);
+    assert_eq!(stats.updated, 1);
+    assert_eq!(desktop.access(&id).unwrap(), private);
+}
+
+#[test]
+fn test_sync_state_per_peer() {
+    let db = Database::open_in_memory().unwrap();
+    assert_eq!(db.sync_state("/peer.db").unwrap(), SyncState::default());
+    db.set_sync_state("/peer.db", 4, 7).unwrap();
+    let state = db.sync_state("/peer.db").unwrap();
+    assert_eq!((state.pushed_seq, state.pulled_seq), (4, 7));
+    assert!(state.synced_at.is_some());
+    assert_eq!(db.sync_state("/other.db").unwrap(), SyncState::default());
+}
@@ -0,0 +1,157 @@
+//! Per-project synonyms expanding search queries.
+//!
+//! `vipune synonym add k8s kubernetes` stores the pair for the project; BM25
+//! search then matches any of `k8s`, `kubernetes` wherever the query has one
+//! of them (FTS5 `OR`). Pairs apply in both directions. Terms are single
+//! lowercase words; a synonym may be a phrase (`ml` → `machine learning`).
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::Utc;
+use rusqlite::{Result as SqliteResult, params};
+
+use super::{Database, Result};
+
+/// Schema for the synonyms table.
+pub(super) const SYNONYMS_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS synonyms (
+        project_id TEXT NOT NULL,
+        term TEXT NOT NULL,
+        synonym TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        PRIMARY KEY (project_id, term, synonym)
+    );
+"#;
+
+/// Key a query word is looked up under: lowercased, without surrounding
+/// punctuation (so `K8s?` finds the synonyms of `k8s`).
+pub(crate) fn lookup_key(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+impl Database {
+    /// Store `synonyms` of `term` for a project, returning how many pairs
+    /// were new.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the insert fails.
+    pub fn add_synonyms(&self, project_id: &str, term: &str, synonyms: &[&str]) -> Result<usize> {
+        let tx = self.write_transaction()?;
+        let now = Utc::now().to_rfc3339();
+        let mut added = 0;
+        for synonym in synonyms {
+            added += tx.execute(
+                "INSERT OR IGNORE INTO synonyms (project_id, term, synonym, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![project_id, term, synonym, now],
+            )?;
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Remove `synonym` of `term` (every synonym of `term` with `None`) from
+    /// a project, returning how many pairs were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the delete fails.
+    pub fn remove_synonyms(
+        &self,
+        project_id: &str,
+        term: &str,
+        synonym: Option<&str>,
+    ) -> Result<usize> {
+        let removed = self.conn.execute(
+            "DELETE FROM synonyms
+             WHERE project_id = ?1 AND term = ?2 AND (?3 IS NULL OR synonym = ?3)",
+            params![project_id, term, synonym],
+        )?;
+        Ok(removed)
+    }
+
+    /// A project's synonym pairs as `(term, synonym)`, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn list_synonyms(&self, project_id: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT term, synonym FROM synonyms WHERE project_id = ?1 ORDER BY term, synonym",
+        )?;
+        let pairs: SqliteResult<Vec<(String, String)>> = stmt
+            .query_map([project_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect();
+        Ok(pairs?)
+    }
+
+    /// Synonyms of each of `words` in a project, in both directions, keyed
+    /// by [`lookup_key`]. Words without synonyms are absent.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn synonyms_for(
+        &self,
+        project_id: &str,
+        words: &[&str],
+    ) -> Result<HashMap<String, Vec<String>>> {
+        let keys: BTreeSet<String> = words.iter().map(|w| lookup_key(w)).collect();
+        let mut found: HashMap<String, BTreeSet<String>> = HashMap::new();
+        for (term, synonym) in self.list_synonyms(project_id)? {
+            if keys.contains(&term) {
+                found
+                    .entry(term.clone())
+                    .or_default()
+                    .insert(synonym.clone());
+            }
+            if keys.contains(&synonym) {
+                found.entry(synonym).or_default().insert(term);
+            }
+        }
+        Ok(found
+            .into_iter()
+            .map(|(word, synonyms)| (word, synonyms.into_iter().collect()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synonyms_apply_in_both_directions() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(
+            db.add_synonyms("proj", "k8s", &["kubernetes", "kube"])
+                .unwrap(),
+            2
+        );
+        assert_eq!(db.add_synonyms("proj", "k8s", &["kube"]).unwrap(), 0);
+        db.add_synonyms("proj", "ml", &["machine learning"])
+            .unwrap();
+        db.add_synonyms("other", "k8s", &["kayts"]).unwrap();
+
+        let found = db
+            .synonyms_for("proj", &["K8s?", "kubernetes", "learning", "ml"])
+            .unwrap();
+        assert_eq!(found["k8s"], vec!["kube", "kubernetes"]);
+        assert_eq!(found["kubernetes"], vec!["k8s"]);
+        assert_eq!(found["ml"], vec!["machine learning"]);
+        assert!(!found.contains_key("learning"));
+
+        assert_eq!(db.remove_synonyms("proj", "k8s", Some("kube")).unwrap(), 1);
+        assert_eq!(
+            db.list_synonyms("proj").unwrap(),
+            vec![
+                ("k8s".to_string(), "kubernetes".to_string()),
+                ("ml".to_string(), "machine learning".to_string())
+            ]
+        );
+        assert_eq!(db.remove_synonyms("proj", "k8s", None).unwrap(), 1);
+        assert_eq!(db.list_synonyms("other").unwrap().len(), 1);
+    }
+}
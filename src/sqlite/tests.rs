@@ -0,0 +1,239 @@
+//! Tests for opening databases and the basic memory operations.
+
+use super::*;
+use tempfile::TempDir;
+
+fn create_test_db() -> Database {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.db");
+    let db = Database::open(&path).unwrap();
+    std::mem::forget(dir);
+    db
+}
+
+#[test]
+fn test_insert_and_get() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let id = db
+        .insert("proj1", "test content", &embedding, None)
+        .unwrap();
+
+    let memory = db.get(&id).unwrap();
+    assert!(memory.is_some());
+    let m = memory.unwrap();
+    assert_eq!(m.content, "test content");
+    assert_eq!(m.project_id, "proj1");
+}
+
+#[test]
+fn test_insert_with_metadata() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let id = db
+        .insert(
+            "proj1",
+            "test content",
+            &embedding,
+            Some(r#"{"key": "value"}"#),
+        )
+        .unwrap();
+
+    let m = db.get(&id).unwrap().unwrap();
+    assert_eq!(m.metadata, Some(r#"{"key": "value"}"#.to_string()));
+}
+
+#[test]
+fn test_insert_invalid_embedding() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 256];
+    let result = db.insert("proj1", "test", &embedding, None);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_get_nonexistent() {
+    let db = create_test_db();
+    let memory = db.get("nonexistent").unwrap();
+    assert!(memory.is_none());
+}
+
+#[test]
+fn test_list_ordering() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let id1 = db
+        .insert_with_time(
+            "proj1",
+            "first",
+            &embedding,
+            None,
+            "2024-01-01T00:00:00Z",
+            "2024-01-01T00:00:00Z",
+        )
+        .unwrap();
+    let id2 = db
+        .insert_with_time(
+            "proj1",
+            "second",
+            &embedding,
+            None,
+            "2024-01-02T00:00:00Z",
+            "2024-01-02T00:00:00Z",
+        )
+        .unwrap();
+
+    let memories = db.list("proj1", 10).unwrap();
+    assert_eq!(memories.len(), 2);
+    assert_eq!(memories[0].id, id2); // Newest first
+    assert_eq!(memories[1].id, id1);
+}
+
+#[test]
+fn test_list_limit() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    for i in 0..5 {
+        db.insert("proj1", &format!("content {}", i), &embedding, None)
+            .unwrap();
+    }
+
+    let memories = db.list("proj1", 2).unwrap();
+    assert_eq!(memories.len(), 2);
+}
+
+#[test]
+fn test_update() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let id = db.insert("proj1", "original", &embedding, None).unwrap();
+
+    db.update(&id, "updated", &embedding).unwrap();
+
+    let m = db.get(&id).unwrap().unwrap();
+    assert_eq!(m.content, "updated");
+}
+
+#[test]
+fn test_update_nonexistent() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let result = db.update("nonexistent", "content", &embedding);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_update_metadata() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let id = db
+        .insert("proj1", "content", &embedding, Some(r#"{"a": 1}"#))
+        .unwrap();
+
+    db.update_metadata(&id, Some(r#"{"b": 2}"#)).unwrap();
+    assert_eq!(
+        db.get(&id).unwrap().unwrap().metadata.as_deref(),
+        Some(r#"{"b": 2}"#)
+    );
+
+    db.update_metadata(&id, None).unwrap();
+    let m = db.get(&id).unwrap().unwrap();
+    assert_eq!(m.metadata, None);
+    assert_eq!(m.content, "content");
+    assert!(db.update_metadata("nonexistent", None).is_err());
+}
+
+#[test]
+fn test_touch() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let id = db
+        .insert_with_time(
+            "proj1",
+            "content",
+            &embedding,
+            Some(r#"{"a": 1}"#),
+            "2024-01-01T00:00:00Z",
+            "2024-01-01T00:00:00Z",
+        )
+        .unwrap();
+
+    db.touch(&id).unwrap();
+    let m = db.get(&id).unwrap().unwrap();
+    assert!(m.updated_at.as_str() > "2024-01-02");
+    assert_eq!(m.created_at, "2024-01-01T00:00:00Z");
+    assert_eq!(m.metadata.as_deref(), Some(r#"{"a": 1}"#));
+    assert_eq!(db.audit_log("proj1", None, 10).unwrap().len(), 2);
+    assert!(matches!(
+        crate::errors::Error::from(db.touch("nonexistent").unwrap_err()),
+        crate::errors::Error::NotFound(_)
+    ));
+}
+
+#[test]
+fn test_delete() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    let id = db.insert("proj1", "content", &embedding, None).unwrap();
+
+    let deleted = db.delete(&id).unwrap();
+    assert!(deleted);
+
+    let memory = db.get(&id).unwrap();
+    assert!(memory.is_none());
+}
+
+#[test]
+fn test_delete_nonexistent() {
+    let db = create_test_db();
+    let deleted = db.delete("nonexistent").unwrap();
+    assert!(!deleted);
+}
+
+#[test]
+fn test_project_isolation() {
+    let db = create_test_db();
+    let embedding = vec![0.1f32; 384];
+    db.insert("proj1", "proj1 content", &embedding, None)
+        .unwrap();
+    db.insert("proj2", "proj2 content", &embedding, None)
+        .unwrap();
+
+    let list1 = db.list("proj1", 10).unwrap();
+    let list2 = db.list("proj2", 10).unwrap();
+
+    assert_eq!(list1.len(), 1);
+    assert_eq!(list2.len(), 1);
+    assert_eq!(list1[0].project_id, "proj1");
+    assert_eq!(list2[0].project_id, "proj2");
+}
+
+#[test]
+fn test_write_transaction_joins_enclosing_one() {
+    let db = Database::open_in_memory().unwrap();
+    let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+    {
+        let outer = db.write_transaction().unwrap();
+        // insert commits its own (joined) transaction, not the outer one
+        db.insert("proj", "rolled back", &embedding, None).unwrap();
+        assert_eq!(
+            outer
+                .query_row("SELECT COUNT(*) FROM memories", [], |row| row
+                    .get::<_, i64>(0))
+                .unwrap(),
+            1
+        );
+    }
+    assert_eq!(db.count_project("proj").unwrap(), 0);
+
+    let outer = db.write_transaction().unwrap();
+    db.insert("proj", "kept", &embedding, None).unwrap();
+    outer.commit().unwrap();
+    assert_eq!(db.count_project("proj").unwrap(), 1);
+}
+
+#[test]
+fn test_schema_version_is_recorded() {
+    let db = Database::open_in_memory().unwrap();
+    assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+}
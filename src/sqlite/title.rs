@@ -0,0 +1,144 @@
+//! Memory titles (see [`crate::title`]).
+//!
+//! The `title` column holds the title given when the memory was added, or
+//! else one derived from the content. Derived titles follow content updates;
+//! given ones are kept. The `vipune_title(content)` SQL function derives a
+//! title, so existing rows can be titled in place when the column is added.
+
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{Connection, params};
+
+use super::{Database, Result};
+use crate::title;
+
+/// Register the `vipune_title(content)` SQL function.
+pub(super) fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "vipune_title",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            Ok(ctx
+                .get::<Option<String>>(0)?
+                .and_then(|c| title::derive(&c)))
+        },
+    )?;
+    Ok(())
+}
+
+/// Title every memory (chunk rows excluded) from its content; used when the
+/// `title` column is added to an existing database.
+pub(super) fn derive_titles(conn: &Connection) -> Result<()> {
+    log::info!("Migrating schema: deriving memory titles");
+    conn.execute(
+        "UPDATE memories SET title = vipune_title(vipune_content(content, content_zstd))
+         WHERE parent_id IS NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+impl Database {
+    /// Set a memory's title, or with `None` derive it from the content again.
+    ///
+    /// Leaves `updated_at` alone. Returns true if the memory exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the update fails.
+    pub fn set_title(&self, id: &str, title: Option<&str>) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE memories SET title = coalesce(?2, vipune_title(vipune_content(content, content_zstd)))
+             WHERE id = ?1 AND parent_id IS NULL",
+            params![id, title],
+        )?;
+        Ok(updated > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_titles_follow_content_unless_set() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; 384];
+        let id = db
+            .insert(
+                "p",
+                "Deploy on Fridays. Never on Mondays.",
+                &embedding,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            db.get(&id).unwrap().unwrap().title.as_deref(),
+            Some("Deploy on Fridays")
+        );
+
+        db.update(&id, "Deploy on Thursdays. Rollback in an hour.", &embedding)
+            .unwrap();
+        assert_eq!(
+            db.get(&id).unwrap().unwrap().title.as_deref(),
+            Some("Deploy on Thursdays")
+        );
+
+        assert!(db.set_title(&id, Some("Release schedule")).unwrap());
+        assert!(!db.set_title("missing", Some("x")).unwrap());
+        db.update(&id, "Deploy whenever CI is green.", &embedding)
+            .unwrap();
+        let memory = db.get(&id).unwrap().unwrap();
+        assert_eq!(memory.title.as_deref(), Some("Release schedule"));
+
+        // The title is indexed with the metadata fields
+        assert_eq!(db.search_bm25("schedule", "p", 10).unwrap().len(), 1);
+        db.set_title(&id, None).unwrap();
+        assert_eq!(
+            db.get(&id).unwrap().unwrap().title.as_deref(),
+            Some("Deploy whenever CI is green")
+        );
+        assert!(db.search_bm25("schedule", "p", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migration_titles_existing_memories() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let embedding = vec![0.1; 384];
+        {
+            let db = Database::open(&path).unwrap();
+            db.insert("p", "Legacy memory. With details.", &embedding, None)
+                .unwrap();
+            db.conn
+                .execute_batch(&format!(
+                    "DROP TRIGGER memories_fts_insert;
+                     DROP TRIGGER memories_fts_delete;
+                     DROP TRIGGER memories_fts_update;
+                     DROP TABLE memories_fts;
+                     DROP VIEW memories_fts_source;
+                     ALTER TABLE memories DROP COLUMN title;
+                     {}
+                     INSERT INTO memories_fts(memories_fts) VALUES('rebuild');",
+                    super::super::fts::schema::fts_schema(
+                        Default::default(),
+                        Default::default(),
+                        false
+                    )
+                    .replace("coalesce(title, '')", "''")
+                    .replace("coalesce(new.title, '')", "''")
+                    .replace("coalesce(old.title, '')", "''")
+                ))
+                .unwrap();
+        }
+
+        let db = Database::open(&path).unwrap();
+        let memory = &db.list("p", 10).unwrap()[0];
+        assert_eq!(memory.title.as_deref(), Some("Legacy memory"));
+        db.update(&memory.id, "Rewritten. Entirely.", &embedding)
+            .unwrap();
+        assert_eq!(db.search_bm25("rewritten", "p", 10).unwrap().len(), 1);
+        assert!(db.search_bm25("legacy", "p", 10).unwrap().is_empty());
+    }
+}
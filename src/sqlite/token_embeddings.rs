@@ -0,0 +1,140 @@
+//! Token embeddings kept for late-interaction scoring.
+//!
+//! With `[late_interaction] enabled`, the store samples the token embeddings
+//! of each memory's content and keeps them, int8-quantized, in
+//! `memory_tokens`. Triggers drop them when the memory is deleted or its
+//! content changes, so searches never score stale tokens.
+
+use std::collections::HashMap;
+
+use rusqlite::{OptionalExtension, params};
+
+use super::embedding::{self, INT8_BLOB_SIZE};
+use super::{Database, Result};
+use crate::embedding::EMBEDDING_DIMS;
+
+/// Table of token embeddings and the triggers that keep it current.
+pub(super) const TOKEN_EMBEDDINGS_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS memory_tokens (
+        memory_id TEXT PRIMARY KEY,
+        tokens BLOB NOT NULL
+    );
+
+    CREATE TRIGGER IF NOT EXISTS memory_tokens_delete AFTER DELETE ON memories BEGIN
+        DELETE FROM memory_tokens WHERE memory_id = old.id;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS memory_tokens_content AFTER UPDATE OF content ON memories BEGIN
+        DELETE FROM memory_tokens WHERE memory_id = new.id;
+    END;
+"#;
+
+impl Database {
+    /// Store the token embeddings of memory `id` (each int8-quantized), or
+    /// remove them with `None` or an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a token embedding does not have the model's
+    /// dimensions or holds NaN or infinite values, or the write fails.
+    pub fn set_token_embeddings(&self, id: &str, tokens: Option<&[Vec<f32>]>) -> Result<()> {
+        let tokens = tokens.unwrap_or_default();
+        if tokens.is_empty() {
+            self.conn
+                .execute("DELETE FROM memory_tokens WHERE memory_id = ?1", [id])?;
+            return Ok(());
+        }
+        let mut blob = Vec::with_capacity(tokens.len() * INT8_BLOB_SIZE);
+        for token in tokens {
+            blob.extend(embedding::vec_to_int8_blob(token)?);
+        }
+        self.conn.execute(
+            "INSERT OR REPLACE INTO memory_tokens (memory_id, tokens) VALUES (?1, ?2)",
+            params![id, blob],
+        )?;
+        Ok(())
+    }
+
+    /// IDs and contents of all memories (chunk rows excluded), for storing
+    /// their token embeddings on a full re-embed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn token_embedding_candidates(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, vipune_content(content, content_zstd) FROM memories
+             WHERE parent_id IS NULL ORDER BY rowid",
+        )?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Stored token embeddings of the given memories; memories without any
+    /// are absent from the map.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    #[allow(dead_code)] // Dead code justified: read by late-interaction searches only
+    pub fn token_embeddings(&self, ids: &[&str]) -> Result<HashMap<String, Vec<Vec<f32>>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tokens FROM memory_tokens WHERE memory_id = ?1")?;
+        let mut tokens = HashMap::new();
+        for id in ids {
+            let blob: Option<Vec<u8>> = stmt.query_row([id], |row| row.get(0)).optional()?;
+            let Some(blob) = blob else {
+                continue;
+            };
+            let decoded: Option<Vec<Vec<f32>>> = blob
+                .chunks(INT8_BLOB_SIZE)
+                .map(|chunk| embedding::decode_blob(chunk, EMBEDDING_DIMS))
+                .collect();
+            if let Some(decoded) = decoded {
+                tokens.insert(id.to_string(), decoded);
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(index: usize) -> Vec<f32> {
+        let mut token = vec![0.0; EMBEDDING_DIMS];
+        token[index] = 1.0;
+        token
+    }
+
+    #[test]
+    fn test_token_embeddings_roundtrip_and_cleanup() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; EMBEDDING_DIMS];
+        let id = db
+            .insert("p", "Deploys run on Fridays", &embedding, None)
+            .unwrap();
+        let other = db.insert("p", "Other", &embedding, None).unwrap();
+        db.set_token_embeddings(&id, Some(&[token(0), token(1)]))
+            .unwrap();
+        db.set_token_embeddings(&other, Some(&[token(2)])).unwrap();
+
+        let stored = db.token_embeddings(&[&id, "missing"]).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[&id], vec![token(0), token(1)]);
+
+        // New content makes the tokens stale, deleting the memory drops them
+        db.update(&id, "Deploys run on Mondays", &embedding)
+            .unwrap();
+        db.delete(&other).unwrap();
+        assert!(db.token_embeddings(&[&id, &other]).unwrap().is_empty());
+
+        db.set_token_embeddings(&id, Some(&[token(3)])).unwrap();
+        db.set_token_embeddings(&id, None).unwrap();
+        assert!(db.token_embeddings(&[&id]).unwrap().is_empty());
+    }
+}
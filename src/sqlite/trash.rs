@@ -0,0 +1,249 @@
+//! Trash of deleted memories, kept for a retention window.
+//!
+//! With `trash_retention_days` set, a delete moves the memory and its chunks
+//! to `memories_trash` (every column, so a restore brings them back as they
+//! were, embeddings and chunks included). `vipune trash restore` moves them
+//! back, and pruning permanently removes trash older than the window.
+
+use chrono::Utc;
+use rusqlite::params;
+
+use super::{Database, Memory, Result, audit, memory_from_row};
+
+/// Schema for the trash table: the columns of `memories` plus `deleted_at`.
+pub(super) const TRASH_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS memories_trash (
+        id TEXT PRIMARY KEY,
+        project_id TEXT NOT NULL,
+        content TEXT NOT NULL,
+        embedding BLOB NOT NULL,
+        metadata TEXT,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        parent_id TEXT,
+        kind TEXT NOT NULL DEFAULT 'other',
+        last_accessed_at TEXT,
+        access_count INTEGER NOT NULL DEFAULT 0,
+        language TEXT,
+        reviewed_at TEXT,
+        model_revision TEXT,
+        origin TEXT NOT NULL DEFAULT 'api',
+        compressed INTEGER NOT NULL DEFAULT 0,
+        content_zstd BLOB,
+        title TEXT,
+        owner TEXT,
+        visibility TEXT NOT NULL DEFAULT 'shared',
+        deleted_at TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_memories_trash_project ON memories_trash(project_id, deleted_at);
+"#;
+
+/// Columns copied between `memories` and `memories_trash`.
+const COLUMNS: &str = "id, project_id, content, embedding, metadata, created_at, updated_at, \
+    parent_id, kind, last_accessed_at, access_count, language, reviewed_at, model_revision, \
+    origin, compressed, content_zstd, title, owner, visibility";
+
+/// A deleted memory waiting in the trash.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrashedMemory {
+    /// The memory as it was when deleted.
+    pub memory: Memory,
+    /// When the memory was deleted (RFC3339).
+    pub deleted_at: String,
+}
+
+impl Database {
+    /// Move a memory and its chunks to the trash.
+    ///
+    /// Recorded in the audit log as a delete. Returns true if the memory was
+    /// trashed, false if it didn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the copy or delete fails.
+    pub fn trash(&self, id: &str) -> Result<bool> {
+        let tx = self.write_transaction()?;
+        tx.execute(
+            &format!(
+                "INSERT OR REPLACE INTO memories_trash ({COLUMNS}, deleted_at)
+                 SELECT {COLUMNS}, ?2 FROM memories WHERE id = ?1 OR parent_id = ?1"
+            ),
+            params![id, Utc::now().to_rfc3339()],
+        )?;
+        audit::record_where(&tx, audit::OP_DELETE, "id = ?1", [id])?;
+        tx.execute("DELETE FROM memories WHERE parent_id = ?1", [id])?;
+        let rows = tx.execute("DELETE FROM memories WHERE id = ?1", [id])?;
+        tx.commit()?;
+        Ok(rows > 0)
+    }
+
+    /// Trashed memories of a project (chunk rows excluded), most recently
+    /// deleted first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn trashed(&self, project_id: &str) -> Result<Vec<TrashedMemory>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, project_id, vipune_content(content, content_zstd), metadata, created_at, updated_at, kind, origin, title,
+                   deleted_at
+            FROM memories_trash
+            WHERE project_id = ?1 AND parent_id IS NULL
+            ORDER BY deleted_at DESC, id ASC
+            "#,
+        )?;
+        let rows = stmt.query_map([project_id], |row| {
+            Ok(TrashedMemory {
+                memory: memory_from_row(row)?,
+                deleted_at: row.get(9)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Move a trashed memory and its chunks back into the store.
+    ///
+    /// Recorded in the audit log as an add. Returns true if the memory was
+    /// restored, false if it is not in the trash.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the copy or delete fails.
+    pub fn restore(&self, id: &str) -> Result<bool> {
+        let tx = self.write_transaction()?;
+        let rows = tx.execute(
+            &format!(
+                "INSERT INTO memories ({COLUMNS})
+                 SELECT {COLUMNS} FROM memories_trash WHERE id = ?1 AND parent_id IS NULL"
+            ),
+            [id],
+        )?;
+        if rows > 0 {
+            tx.execute(
+                &format!(
+                    "INSERT INTO memories ({COLUMNS})
+                     SELECT {COLUMNS} FROM memories_trash WHERE parent_id = ?1"
+                ),
+                [id],
+            )?;
+            tx.execute(
+                "DELETE FROM memories_trash WHERE id = ?1 OR parent_id = ?1",
+                [id],
+            )?;
+            tx.execute(
+                "UPDATE memories SET content_hash = vipune_content_hash(vipune_content(content, content_zstd))
+                 WHERE id = ?1",
+                [id],
+            )?;
+            audit::record_where(&tx, audit::OP_ADD, "id = ?1", [id])?;
+        }
+        tx.commit()?;
+        Ok(rows > 0)
+    }
+
+    /// Permanently remove a project's trashed memories, returning how many
+    /// were removed (not counting chunks).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the delete fails.
+    pub fn empty_trash(&self, project_id: &str) -> Result<usize> {
+        self.purge_trash_where("project_id = ?1", [project_id])
+    }
+
+    /// Permanently remove memories of every project trashed before `cutoff`
+    /// (RFC3339), returning how many were removed (not counting chunks).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the delete fails.
+    pub fn purge_trash(&self, cutoff: &str) -> Result<usize> {
+        self.purge_trash_where("julianday(deleted_at) < julianday(?1)", [cutoff])
+    }
+
+    fn purge_trash_where<P: rusqlite::Params + Copy>(
+        &self,
+        condition: &str,
+        params: P,
+    ) -> Result<usize> {
+        let tx = self.write_transaction()?;
+        tx.execute(
+            &format!("DELETE FROM memories_trash WHERE {condition} AND parent_id IS NOT NULL"),
+            params,
+        )?;
+        let removed = tx.execute(
+            &format!("DELETE FROM memories_trash WHERE {condition} AND parent_id IS NULL"),
+            params,
+        )?;
+        tx.commit()?;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trash_and_restore_keeps_chunks_and_index() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; 384];
+        let id = db
+            .insert("p", "needle in the trash", &embedding, Some(r#"{"a":1}"#))
+            .unwrap();
+        db.insert_chunks(&id, &[("chunk text".to_string(), embedding.clone())])
+            .unwrap();
+        let before = db.get(&id).unwrap().unwrap();
+
+        assert!(db.trash(&id).unwrap());
+        assert!(!db.trash(&id).unwrap());
+        assert!(db.get(&id).unwrap().is_none());
+        assert!(db.search_bm25("needle", "p", 10).unwrap().is_empty());
+        let trashed = db.trashed("p").unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].memory, before);
+        assert!(db.trashed("other").unwrap().is_empty());
+
+        assert!(db.restore(&id).unwrap());
+        assert!(!db.restore(&id).unwrap());
+        assert_eq!(db.get(&id).unwrap().unwrap(), before);
+        assert_eq!(db.search_bm25("needle", "p", 10).unwrap().len(), 1);
+        let chunks: i64 = db
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM memories WHERE parent_id = ?1",
+                [&id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(chunks, 1);
+        assert!(db.trashed("p").unwrap().is_empty());
+
+        let operations: Vec<String> = db
+            .audit_log("p", None, 10)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.operation)
+            .collect();
+        assert_eq!(operations, vec!["add", "delete", "add"]);
+    }
+
+    #[test]
+    fn test_purge_and_empty_trash() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; 384];
+        let a = db.insert("a", "first", &embedding, None).unwrap();
+        let b = db.insert("b", "second", &embedding, None).unwrap();
+        db.trash(&a).unwrap();
+        db.trash(&b).unwrap();
+
+        assert_eq!(db.purge_trash("2000-01-01T00:00:00Z").unwrap(), 0);
+        assert_eq!(db.empty_trash("a").unwrap(), 1);
+        assert!(!db.restore(&a).unwrap());
+        let cutoff = (Utc::now() + chrono::Duration::minutes(1)).to_rfc3339();
+        assert_eq!(db.purge_trash(&cutoff).unwrap(), 1);
+        assert!(db.trashed("b").unwrap().is_empty());
+    }
+}
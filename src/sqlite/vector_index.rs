@@ -0,0 +1,298 @@
+//! Native vector search through the sqlite-vec extension.
+//!
+//! With the `sqlite-vec` feature and `sqlite_vec_path` configured, the
+//! extension is loaded on open and a `vec0` virtual table (`memories_vec`,
+//! partitioned by project) mirrors the embeddings of `memories` under the same
+//! rowids. Unfiltered semantic searches then run as a KNN query in SQLite
+//! instead of decoding every embedding of the project in Rust; searches with
+//! metadata, kind, origin or date filters, other embedding dimensions or very large
+//! limits keep using the scan in [`Database::search_filtered`].
+//!
+//! Writes go through plain triggers that log changed rowids to
+//! `memories_vec_changes` once the index exists, so connections without the
+//! extension (or older binaries) keep working and the index catches up on
+//! the next indexed search.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rusqlite::{OptionalExtension, params};
+
+use super::{Database, Memory, Result, embedding};
+use crate::embedding::EMBEDDING_DIMS;
+use crate::search_options::SearchFilter;
+
+/// Change log and triggers feeding the vector index. The triggers only log
+/// while `memories_vec` exists, so stores that never enable the index pay
+/// nothing but the existence check.
+pub(super) const VECTOR_CHANGES_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS memories_vec_changes (
+        memory_rowid INTEGER PRIMARY KEY
+    );
+
+    CREATE TRIGGER IF NOT EXISTS memories_vec_ai AFTER INSERT ON memories
+    WHEN EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'memories_vec')
+    BEGIN
+        INSERT OR IGNORE INTO memories_vec_changes VALUES (new.rowid);
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS memories_vec_au AFTER UPDATE OF embedding, project_id ON memories
+    WHEN EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'memories_vec')
+    BEGIN
+        INSERT OR IGNORE INTO memories_vec_changes VALUES (new.rowid);
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS memories_vec_ad AFTER DELETE ON memories
+    WHEN EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'memories_vec')
+    BEGIN
+        INSERT OR IGNORE INTO memories_vec_changes VALUES (old.rowid);
+    END;
+"#;
+
+/// Candidates fetched from the index per requested result, leaving room for
+/// chunk rows that collapse into their parent.
+const KNN_OVERFETCH: usize = 4;
+
+/// Largest `k` sqlite-vec accepts in a KNN query.
+const MAX_KNN: usize = 4096;
+
+impl Database {
+    /// Load the sqlite-vec extension from `path` and route semantic searches
+    /// through a `vec0` index, creating and filling it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the extension cannot be loaded or the index cannot be
+    /// created; the store then keeps searching by scanning.
+    #[cfg(feature = "sqlite-vec")]
+    pub fn enable_vector_index(&mut self, path: &Path) -> Result<()> {
+        // SAFETY: no SQL runs while extension loading is enabled, and the
+        // library is the one the user configured as sqlite-vec.
+        unsafe {
+            let _guard = rusqlite::LoadExtensionGuard::new(&self.conn)?;
+            self.conn.load_extension(path, None::<&str>)?;
+        }
+        self.conn.execute_batch(&format!(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS memories_vec USING vec0(
+                project_id text partition key,
+                embedding float[{EMBEDDING_DIMS}] distance_metric=cosine
+            );"
+        ))?;
+        // A new index, or one whose rowids no longer line up (e.g. after a
+        // restore), is rebuilt from scratch
+        let (indexed, stored): (i64, i64) = self.conn.query_row(
+            "SELECT (SELECT count(*) FROM memories_vec), (SELECT count(*) FROM memories)",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if indexed != stored {
+            log::info!("Rebuilding vector index ({} of {} rows)", indexed, stored);
+            self.conn.execute_batch(
+                "DELETE FROM memories_vec;
+                 INSERT OR IGNORE INTO memories_vec_changes SELECT rowid FROM memories;",
+            )?;
+        }
+        self.vector_index = true;
+        Ok(())
+    }
+
+    /// Load the sqlite-vec extension (unavailable: built without the
+    /// `sqlite-vec` feature).
+    ///
+    /// # Errors
+    ///
+    /// Always returns an error; the store keeps searching by scanning.
+    #[cfg(not(feature = "sqlite-vec"))]
+    pub fn enable_vector_index(&mut self, _path: &Path) -> Result<()> {
+        Err(super::Error::Sqlite(
+            "vipune was built without the `sqlite-vec` feature".to_string(),
+        ))
+    }
+
+    /// Whether semantic searches can use the sqlite-vec index.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn has_vector_index(&self) -> bool {
+        self.vector_index
+    }
+
+    /// Apply the changes logged since the last sync to `memories_vec`.
+    fn sync_vector_index(&self) -> Result<()> {
+        let tx = self.write_transaction()?;
+        let changes: Vec<(i64, Option<String>, Option<Vec<u8>>)> = tx
+            .prepare(
+                "SELECT c.memory_rowid, m.project_id, m.embedding
+                 FROM memories_vec_changes c LEFT JOIN memories m ON m.rowid = c.memory_rowid",
+            )?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        if changes.is_empty() {
+            return Ok(());
+        }
+        log::debug!(
+            "Syncing {} changed rows into the vector index",
+            changes.len()
+        );
+        for (rowid, project_id, blob) in changes {
+            tx.execute("DELETE FROM memories_vec WHERE rowid = ?1", [rowid])?;
+            // Rows of other dimensions stay out of the index, as they are
+            // skipped by scans too
+            let vector = blob.and_then(|blob| embedding::decode_blob(&blob, EMBEDDING_DIMS));
+            if let (Some(project_id), Some(vector)) = (project_id, vector) {
+                tx.execute(
+                    "INSERT INTO memories_vec(rowid, project_id, embedding) VALUES (?1, ?2, ?3)",
+                    params![rowid, project_id, embedding::vec_to_blob(&vector)?],
+                )?;
+            }
+        }
+        tx.execute("DELETE FROM memories_vec_changes", [])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Semantic search through the sqlite-vec index, with the same results
+    /// as a scan, or `None` if the index cannot answer this search.
+    pub(super) fn search_vector_index(
+        &self,
+        project_id: &str,
+        query_embedding: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Option<Vec<Memory>>> {
+        if !self.vector_index
+            || !filter.is_empty()
+            || query_embedding.len() != EMBEDDING_DIMS
+            || limit > MAX_KNN / KNN_OVERFETCH
+        {
+            return Ok(None);
+        }
+        self.sync_vector_index()?;
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            WITH knn AS (
+                SELECT rowid, distance FROM memories_vec
+                WHERE embedding MATCH ?1 AND k = ?2 AND project_id = ?3
+            )
+            SELECT m.id, m.project_id, vipune_content(m.content, m.content_zstd), m.metadata, m.created_at, m.updated_at,
+                   m.kind, m.origin, m.title, m.parent_id, knn.distance
+            FROM knn JOIN memories m ON m.rowid = knn.rowid
+            ORDER BY knn.distance
+            "#,
+        )?;
+        let k = (limit * KNN_OVERFETCH) as i64;
+        let rows = stmt.query_map(
+            params![embedding::vec_to_blob(query_embedding)?, k, project_id],
+            |row| {
+                let memory = super::memory_from_row(row)?;
+                let parent_id: Option<String> = row.get(9)?;
+                let distance: f64 = row.get(10)?;
+                Ok((memory, parent_id, 1.0 - distance))
+            },
+        )?;
+
+        let mut memories: Vec<Memory> = Vec::new();
+        // Best (similarity, chunk content) per chunked parent
+        let mut best_chunks: HashMap<String, (f64, String)> = HashMap::new();
+        for row in rows {
+            let (mut memory, parent_id, score) = row?;
+            match parent_id {
+                Some(parent_id) => {
+                    let best = best_chunks
+                        .entry(parent_id)
+                        .or_insert((f64::NEG_INFINITY, String::new()));
+                    if score > best.0 {
+                        *best = (score, memory.content);
+                    }
+                }
+                None => {
+                    memory.similarity = Some(score);
+                    memories.push(memory);
+                }
+            }
+        }
+
+        // As in the scan, a chunk replaces the score and snippet only when
+        // it matched better than its parent
+        for memory in memories.iter_mut() {
+            if let Some((score, chunk)) = best_chunks.remove(&memory.id) {
+                if memory.similarity.is_none_or(|parent| score > parent) {
+                    memory.similarity = Some(score);
+                    memory.snippet = Some(chunk);
+                }
+            }
+        }
+        // Parents found only through their chunks
+        for (parent_id, (score, chunk)) in best_chunks {
+            let parent = self
+                .conn
+                .query_row(
+                    "SELECT id, project_id, vipune_content(content, content_zstd), metadata, created_at, updated_at,
+                            kind, origin, title
+                     FROM memories WHERE id = ?1",
+                    [&parent_id],
+                    super::memory_from_row,
+                )
+                .optional()?;
+            if let Some(mut parent) = parent {
+                parent.similarity = Some(score);
+                parent.snippet = Some(chunk);
+                memories.push(parent);
+            }
+        }
+
+        memories.sort_by(|a, b| {
+            b.similarity
+                .unwrap_or(0.0)
+                .partial_cmp(&a.similarity.unwrap_or(0.0))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        memories.truncate(limit);
+        Ok(Some(memories))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_are_only_logged_once_the_index_exists() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert("p", "first", &[0.1; EMBEDDING_DIMS], None)
+            .unwrap();
+        let logged = |db: &Database| -> i64 {
+            db.conn
+                .query_row("SELECT count(*) FROM memories_vec_changes", [], |row| {
+                    row.get(0)
+                })
+                .unwrap()
+        };
+        assert_eq!(logged(&db), 0);
+
+        // A stand-in for the vec0 table turns logging on
+        db.conn
+            .execute_batch("CREATE TABLE memories_vec (rowid INTEGER PRIMARY KEY)")
+            .unwrap();
+        let id = db
+            .insert("p", "second", &[0.2; EMBEDDING_DIMS], None)
+            .unwrap();
+        db.delete(&id).unwrap();
+        assert_eq!(logged(&db), 1);
+    }
+
+    #[test]
+    fn test_missing_extension_falls_back_to_scan() {
+        let mut db = Database::open_in_memory().unwrap();
+        assert!(
+            db.enable_vector_index(Path::new("/nonexistent/vec0"))
+                .is_err()
+        );
+        assert!(!db.has_vector_index());
+
+        let id = db
+            .insert("p", "memory", &[0.1; EMBEDDING_DIMS], None)
+            .unwrap();
+        let results = db.search("p", &[0.1; EMBEDDING_DIMS], 5).unwrap();
+        assert_eq!(results[0].id, id);
+    }
+}
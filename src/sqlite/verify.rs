@@ -0,0 +1,132 @@
+//! Model revisions of stored embeddings, and reproducible samples of them
+//! for `vipune verify-embeddings`.
+//!
+//! Like the detected language, the model revision is bookkeeping about the
+//! embedding: setting it leaves `updated_at` alone and is not written to the
+//! audit log.
+
+use rusqlite::params;
+
+use super::embedding::blob_to_vec;
+use super::{Database, Result};
+
+/// A stored memory embedding picked for verification.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmbeddingSample {
+    /// Memory ID.
+    pub id: String,
+    /// Memory content, as embedded.
+    pub content: String,
+    /// Stored embedding (dequantized if stored as int8), or `None` if it has
+    /// the wrong dimensions.
+    pub embedding: Option<Vec<f32>>,
+    /// Hub commit hash of the model that embedded the memory, if recorded.
+    pub model_revision: Option<String>,
+}
+
+impl Database {
+    /// Record the model revision (Hub commit hash) that produced the stored
+    /// embedding of a memory or chunk row, or clear it with `None`. Returns
+    /// `false` if the row doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the update fails.
+    pub fn set_model_revision(&self, id: &str, revision: Option<&str>) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE memories SET model_revision = ?2 WHERE id = ?1",
+            params![id, revision],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Up to `limit` memories of every project (chunk rows excluded), picked
+    /// pseudo-randomly by `seed`: the same seed picks the same memories as
+    /// long as the store is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    pub fn embedding_sample(&self, limit: usize, seed: u64) -> Result<Vec<EmbeddingSample>> {
+        let mut rowids: Vec<i64> = self
+            .conn
+            .prepare("SELECT rowid FROM memories WHERE parent_id IS NULL")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        rowids.sort_by_key(|&rowid| (mix(seed ^ rowid as u64), rowid));
+        rowids.truncate(limit);
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, vipune_content(content, content_zstd), embedding, model_revision FROM memories WHERE rowid = ?1",
+        )?;
+        rowids
+            .into_iter()
+            .map(|rowid| {
+                let (id, content, blob, model_revision): (String, String, Vec<u8>, _) = stmt
+                    .query_row([rowid], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                    })?;
+                Ok(EmbeddingSample {
+                    id,
+                    content,
+                    embedding: blob_to_vec(&blob).ok(),
+                    model_revision,
+                })
+            })
+            .collect()
+    }
+}
+
+/// SplitMix64 finalizer: spreads nearby rowids over the whole `u64` range.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::EMBEDDING_DIMS;
+
+    #[test]
+    fn test_embedding_sample_is_reproducible() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; EMBEDDING_DIMS];
+        for i in 0..20 {
+            let project = if i % 2 == 0 { "a" } else { "b" };
+            db.insert(project, &format!("memory {i}"), &embedding, None)
+                .unwrap();
+        }
+        let ids = |seed| -> Vec<String> {
+            db.embedding_sample(5, seed)
+                .unwrap()
+                .into_iter()
+                .map(|sample| sample.id)
+                .collect()
+        };
+
+        assert_eq!(ids(7).len(), 5);
+        assert_eq!(ids(7), ids(7));
+        assert_ne!(ids(7), ids(8));
+        assert_eq!(db.embedding_sample(100, 7).unwrap().len(), 20);
+    }
+
+    #[test]
+    fn test_set_model_revision_without_editing() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; EMBEDDING_DIMS];
+        let id = db.insert("proj", "content", &embedding, None).unwrap();
+        let before = db.get(&id).unwrap().unwrap();
+
+        assert!(db.set_model_revision(&id, Some("abc123")).unwrap());
+        assert!(!db.set_model_revision("missing", Some("abc123")).unwrap());
+        let sample = db.embedding_sample(1, 0).unwrap();
+        assert_eq!(sample[0].model_revision.as_deref(), Some("abc123"));
+        assert_eq!(sample[0].embedding.as_deref(), Some(&embedding[..]));
+
+        assert_eq!(db.get(&id).unwrap().unwrap().updated_at, before.updated_at);
+        assert_eq!(db.audit_log("proj", None, 10).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,117 @@
+//! Owner and visibility labels of memories.
+//!
+//! Written with the row when a memory is added, from the adding store's
+//! configuration.
+//! Searches and lists pass the reading store's owner as a viewer, which
+//! leaves out the private memories of other owners.
+
+use rusqlite::{OptionalExtension, params};
+
+use super::{Database, Result, Visibility};
+
+impl Database {
+    /// Set the owner and visibility of a memory and its chunks. Returns
+    /// `false` if the memory doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the update fails.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn set_access(
+        &self,
+        id: &str,
+        owner: Option<&str>,
+        visibility: Visibility,
+    ) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE memories SET owner = ?2, visibility = ?3 WHERE id = ?1 OR parent_id = ?1",
+            params![id, owner, visibility],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Owner and visibility of a memory, or `None` if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the query fails.
+    #[allow(dead_code)] // Dead code justified: library API, unused by the CLI
+    pub fn access(&self, id: &str) -> Result<Option<(Option<String>, Visibility)>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT owner, visibility FROM memories WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_options::SearchFilter;
+
+    #[test]
+    fn test_viewer_sees_own_and_shared_memories() {
+        let db = Database::open_in_memory().unwrap();
+        let embedding = vec![0.1; crate::embedding::EMBEDDING_DIMS];
+        let unlabeled = db.insert("proj", "unlabeled", &embedding, None).unwrap();
+        let alice = db
+            .insert("proj", "alice private", &embedding, None)
+            .unwrap();
+        let bob = db.insert("proj", "bob private", &embedding, None).unwrap();
+        let shared = db.insert("proj", "bob shared", &embedding, None).unwrap();
+        assert!(
+            db.set_access(&alice, Some("alice"), Visibility::Private)
+                .unwrap()
+        );
+        assert!(
+            db.set_access(&bob, Some("bob"), Visibility::Private)
+                .unwrap()
+        );
+        assert!(
+            db.set_access(&shared, Some("bob"), Visibility::Shared)
+                .unwrap()
+        );
+        assert!(!db.set_access("missing", None, Visibility::Shared).unwrap());
+        assert_eq!(
+            db.access(&alice).unwrap(),
+            Some((Some("alice".to_string()), Visibility::Private))
+        );
+        assert_eq!(
+            db.access(&unlabeled).unwrap(),
+            Some((None, Visibility::Shared))
+        );
+
+        let ids = |memories: Vec<crate::sqlite::Memory>| {
+            let mut ids: Vec<String> = memories.into_iter().map(|m| m.id).collect();
+            ids.sort();
+            ids
+        };
+        let mut expected = vec![unlabeled.clone(), alice.clone(), shared.clone()];
+        expected.sort();
+        let filter = SearchFilter {
+            viewer: Some("alice".to_string()),
+            ..SearchFilter::default()
+        };
+        assert_eq!(
+            ids(db.search_filtered("proj", &embedding, 10, &filter).unwrap()),
+            expected
+        );
+        assert_eq!(
+            ids(db
+                .list_filtered("proj", 10, None, None, Some("alice"))
+                .unwrap()),
+            expected
+        );
+        // Without a viewer nothing is left out
+        assert_eq!(
+            db.list_filtered("proj", 10, None, None, None)
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+}
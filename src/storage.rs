@@ -0,0 +1,330 @@
+//! Storage backend abstraction behind [`MemoryStore`](crate::MemoryStore).
+//!
+//! `MemoryStore` handles embedding, conflict detection, ranking and policy
+//! (quotas, retention, chunking); a [`StorageBackend`] only persists memory
+//! records and answers vector queries. The SQLite [`Database`](crate::Database)
+//! is the default backend. Other backends (e.g. Postgres with pgvector, or a
+//! plain in-process store) implement the required methods and may leave the
+//! optional capabilities at their defaults.
+
+use std::collections::HashMap;
+
+use crate::errors::Error;
+use crate::list_options::ListOptions;
+use crate::memory::store::MAX_SEARCH_LIMIT;
+use crate::search_options::{FieldWeights, QuerySyntax, SearchFilter};
+use crate::sqlite::{AccessStats, Memory, MemoryKind, NewMemory, Origin};
+
+/// Persistence operations required by [`MemoryStore`](crate::MemoryStore).
+///
+/// Implementations report failures as [`Error::Storage`] (or a more specific
+/// variant) and must treat `project_id` as an isolation boundary: reads for one
+/// project never return memories of another.
+///
+/// Provided methods describe optional capabilities. Their defaults make the
+/// corresponding feature a no-op: no BM25 hits (hybrid search ranks by
+/// similarity only), no chunk rows, no size quota, no eviction, no retention,
+/// no trash (deletes are permanent), no access tracking (frecency ranking
+/// keeps the score order), no model revisions, no synonyms and no owner
+/// labels (every memory is visible to every viewer).
+pub trait StorageBackend {
+    /// Store a new memory and return its generated ID.
+    fn insert(&self, record: &NewMemory<'_>) -> Result<String, Error>;
+
+    /// Fetch a memory by ID (`None` if it does not exist).
+    fn get(&self, id: &str) -> Result<Option<Memory>, Error>;
+
+    /// List a project's memories, newest first, optionally only those of
+    /// `kind` and `origin` (see [`Origin::matches`]) and those visible to
+    /// `viewer` (the owner and visibility a memory was inserted with, see
+    /// [`NewMemory`]).
+    fn list(
+        &self,
+        project_id: &str,
+        limit: usize,
+        kind: Option<MemoryKind>,
+        origin: Option<&Origin>,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>, Error>;
+
+    /// List a project's memories as `options` says (see
+    /// [`StorageBackend::list`]), including its `since`/`before` range on
+    /// `updated_at`.
+    ///
+    /// Defaults to [`StorageBackend::list`] without a range, and to filtering
+    /// the newest [`MAX_SEARCH_LIMIT`] memories with one; backends that can
+    /// query by update time override it.
+    fn list_with(
+        &self,
+        project_id: &str,
+        options: &ListOptions,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>, Error> {
+        let kind = options.kind;
+        let origin = options.origin.as_ref();
+        if options.since.is_none() && options.before.is_none() {
+            return self.list(project_id, options.limit, kind, origin, viewer);
+        }
+        let memories = self.list(project_id, MAX_SEARCH_LIMIT, kind, origin, viewer)?;
+        Ok(memories
+            .into_iter()
+            .filter(|m| options.in_range(m))
+            .take(options.limit)
+            .collect())
+    }
+
+    /// A project's memories whose content equals `content` (ignoring case if
+    /// `ignore_case` is set) and that `viewer` may see, newest first.
+    ///
+    /// Defaults to filtering the newest [`MAX_SEARCH_LIMIT`] memories from
+    /// [`StorageBackend::list`]; backends with an index on content override
+    /// it.
+    fn find_by_content(
+        &self,
+        project_id: &str,
+        content: &str,
+        ignore_case: bool,
+        viewer: Option<&str>,
+    ) -> Result<Vec<Memory>, Error> {
+        let memories = self.list(project_id, MAX_SEARCH_LIMIT, None, None, viewer)?;
+        Ok(memories
+            .into_iter()
+            .filter(|m| {
+                m.content == content
+                    || (ignore_case && m.content.to_lowercase() == content.to_lowercase())
+            })
+            .collect())
+    }
+
+    /// Replace a memory's content and embedding, bumping `updated_at`.
+    ///
+    /// Returns [`Error::NotFound`] if the memory does not exist.
+    fn update(&self, id: &str, content: &str, embedding: &[f32]) -> Result<(), Error>;
+
+    /// Replace a memory's metadata (`None` clears it), bumping `updated_at`.
+    ///
+    /// Returns [`Error::NotFound`] if the memory does not exist.
+    fn update_metadata(&self, id: &str, metadata: Option<&str>) -> Result<(), Error>;
+
+    /// Bump a memory's `updated_at` without changing anything else.
+    ///
+    /// Returns [`Error::NotFound`] if the memory does not exist. Defaults to
+    /// writing the memory's metadata back with
+    /// [`StorageBackend::update_metadata`].
+    fn touch(&self, id: &str) -> Result<(), Error> {
+        let memory = self
+            .get(id)?
+            .ok_or_else(|| Error::NotFound("memory not found".to_string()))?;
+        self.update_metadata(id, memory.metadata.as_deref())
+    }
+
+    /// Delete a memory (and any chunk rows); `false` if it did not exist.
+    fn delete(&self, id: &str) -> Result<bool, Error>;
+
+    /// Up to `limit` memories of a project ranked by cosine similarity to
+    /// `query_embedding`, with `Memory::similarity` set.
+    ///
+    /// Only memories that pass `filter` are returned; backends should apply
+    /// it before scoring (see [`SearchFilter::matches`]).
+    fn search(
+        &self,
+        project_id: &str,
+        query_embedding: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Memory>, Error>;
+
+    /// Stored embeddings for the given IDs; unknown IDs are absent from the map.
+    fn embeddings_for(&self, ids: &[&str]) -> Result<HashMap<String, Vec<f32>>, Error>;
+
+    /// Number of memories stored for a project (chunk rows excluded).
+    fn count_project(&self, project_id: &str) -> Result<usize, Error>;
+
+    /// Up to `limit` memories of a project ranked by keyword relevance (best
+    /// first), with the same filtering as [`StorageBackend::search`].
+    ///
+    /// `Memory::similarity` holds the relevance score (higher is better); it is
+    /// only compared within one result list, so any scale works. Memories
+    /// containing every word of any `exclude` term are left out. Backends
+    /// that index metadata separately from content weigh matches in each with
+    /// `field_weights`. Backends that cannot read `syntax` other than
+    /// [`QuerySyntax::Plain`] match its words literally.
+    ///
+    /// Defaults to no results for backends without a full-text index.
+    #[allow(clippy::too_many_arguments)]
+    fn search_keyword(
+        &self,
+        _query: &str,
+        _syntax: QuerySyntax,
+        _project_id: &str,
+        _limit: usize,
+        _filter: &SearchFilter,
+        _exclude: &[String],
+        _field_weights: FieldWeights,
+    ) -> Result<Vec<Memory>, Error> {
+        Ok(Vec::new())
+    }
+
+    /// Number of rows a [`StorageBackend::search`] of the project with
+    /// `filter` scores, reported by `--explain`.
+    ///
+    /// Defaults to `None` (unknown).
+    fn semantic_scan_size(
+        &self,
+        _project_id: &str,
+        _filter: &SearchFilter,
+    ) -> Result<Option<usize>, Error> {
+        Ok(None)
+    }
+
+    /// Memories of a project passing `filter` with cosine similarity to
+    /// `embedding` of at least `threshold`, used for conflict detection.
+    fn find_similar(
+        &self,
+        project_id: &str,
+        embedding: &[f32],
+        threshold: f64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<Memory>, Error> {
+        let results = self.search(project_id, embedding, MAX_SEARCH_LIMIT, filter)?;
+        Ok(results
+            .into_iter()
+            .filter(|m| m.similarity.unwrap_or(0.0) >= threshold)
+            .collect())
+    }
+
+    /// Store chunks of the memory `parent_id`, returning how many were stored.
+    ///
+    /// Defaults to storing nothing, so long memories are searchable by their
+    /// full-content embedding only.
+    fn insert_chunks(
+        &self,
+        _parent_id: &str,
+        _chunks: &[(String, Vec<f32>)],
+    ) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    /// Delete the chunks of the memory `parent_id`, returning how many were removed.
+    fn delete_chunks(&self, _parent_id: &str) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    /// Bytes used by stored data, or `None` if unknown (the size quota is then
+    /// not enforced).
+    fn used_size_bytes(&self) -> Result<Option<u64>, Error> {
+        Ok(None)
+    }
+
+    /// Delete the `count` oldest memories, optionally only from one project.
+    ///
+    /// Returns the number deleted; the default deletes nothing, so quotas
+    /// reject adds instead of evicting.
+    fn evict_oldest(&self, _project_id: Option<&str>, _count: usize) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    /// Delete the oldest memories of `project_id` whose payloads add up to at
+    /// least `bytes`, at most `limit` of them, or none if `limit` is not enough.
+    ///
+    /// Returns the number deleted; the default deletes nothing.
+    fn evict_bytes(&self, _project_id: &str, _bytes: u64, _limit: usize) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    /// Every project that has stored memories.
+    fn project_ids(&self) -> Result<Vec<String>, Error> {
+        Ok(Vec::new())
+    }
+
+    /// Remove (or archive, if `archive` is set) a project's memories last
+    /// updated before `cutoff` (RFC3339), returning how many were removed.
+    fn prune_project(
+        &self,
+        _project_id: &str,
+        _cutoff: &str,
+        _archive: bool,
+    ) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    /// Move a memory (and any chunk rows) to a trash it can be restored
+    /// from; `false` if it did not exist.
+    ///
+    /// Defaults to deleting it with [`StorageBackend::delete`], so deletes
+    /// are permanent.
+    fn trash(&self, id: &str) -> Result<bool, Error> {
+        self.delete(id)
+    }
+
+    /// Permanently remove memories trashed before `cutoff` (RFC3339),
+    /// returning how many were removed.
+    fn purge_trash(&self, _cutoff: &str) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    /// Mark memories last updated or accessed before `cutoff` (RFC3339)
+    /// inactive, returning how many were marked. Searches with
+    /// [`SearchFilter::active_only`] skip them until they are touched again.
+    fn deactivate_idle(&self, _cutoff: &str) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    /// Record that the memories in `ids` were returned to a caller, bumping
+    /// their access count and last access time without touching `updated_at`.
+    fn record_access(&self, _ids: &[&str]) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Access counts and last access times for the given IDs; unknown (or
+    /// never tracked) IDs may be absent from the map.
+    fn access_stats(&self, _ids: &[&str]) -> Result<HashMap<String, AccessStats>, Error> {
+        Ok(HashMap::new())
+    }
+
+    /// Store the detected language (ISO 639-3 code) of a memory's content,
+    /// or clear it with `None`.
+    fn set_language(&self, _id: &str, _language: Option<&str>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Set a memory's title, or with `None` derive it from the content again.
+    fn set_title(&self, _id: &str, _title: Option<&str>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Store the model revision (Hub commit hash) that produced a memory's
+    /// embedding, or clear it with `None` when unknown.
+    fn set_model_revision(&self, _id: &str, _revision: Option<&str>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Store the token embeddings of memory `id` for late-interaction
+    /// scoring, or remove them with `None`.
+    fn set_token_embeddings(&self, _id: &str, _tokens: Option<&[Vec<f32>]>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Stored token embeddings of the given memories; memories without any
+    /// are absent from the map.
+    ///
+    /// Defaults to none, so late-interaction searches score every candidate
+    /// by its sentence embedding.
+    #[allow(dead_code)] // Dead code justified: read by late-interaction searches only
+    fn token_embeddings(&self, _ids: &[&str]) -> Result<HashMap<String, Vec<Vec<f32>>>, Error> {
+        Ok(HashMap::new())
+    }
+
+    /// Stored synonyms of each of `words` in a project (pairs apply in both
+    /// directions), keyed by the lowercased word; words without synonyms are
+    /// absent.
+    ///
+    /// Defaults to none, so queries are searched as written.
+    fn synonyms_for(
+        &self,
+        _project_id: &str,
+        _words: &[&str],
+    ) -> Result<HashMap<String, Vec<String>>, Error> {
+        Ok(HashMap::new())
+    }
+}
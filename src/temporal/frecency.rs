@@ -0,0 +1,37 @@
+//! Frecency: how often and how recently a memory was accessed.
+
+use chrono::{DateTime, Utc};
+
+/// Days after which the last access counts half as much toward frecency.
+pub const FRECENCY_HALF_LIFE_DAYS: f64 = 14.0;
+
+/// Access count at which frequency reaches half of its maximum.
+const FRECENCY_HALF_COUNT: f64 = 5.0;
+
+/// Frecency of a memory in [0.0, 1.0): how often it was accessed, decayed by
+/// how long ago the last access was.
+///
+/// Formula: count / (count + 5) × 0.5^(days since last access / 14)
+///
+/// Never-accessed memories score 0.0. An access after `now` (e.g. for an
+/// as-of search) counts as just now.
+pub fn frecency(
+    access_count: u64,
+    last_accessed_at: Option<&DateTime<Utc>>,
+    now: &DateTime<Utc>,
+) -> f64 {
+    let Some(last_accessed_at) = last_accessed_at else {
+        return 0.0;
+    };
+    if access_count == 0 {
+        return 0.0;
+    }
+    let count = access_count as f64;
+    let frequency = count / (count + FRECENCY_HALF_COUNT);
+    let age_days = now
+        .signed_duration_since(*last_accessed_at)
+        .num_seconds()
+        .max(0) as f64
+        / 86400.0;
+    frequency * 0.5f64.powf(age_days / FRECENCY_HALF_LIFE_DAYS)
+}
@@ -0,0 +1,21 @@
+//! Temporal decay scoring for search result recency weighting and frecency.
+
+mod decay;
+mod frecency;
+
+pub use decay::{DecayConfig, apply_recency_weight};
+pub use frecency::frecency;
+
+/// Validate recency weight is in valid range [0.0, 1.0].
+pub fn validate_recency_weight(recency_weight: f64) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&recency_weight) {
+        return Err(format!(
+            "Invalid recency weight: {} (must be between 0.0 and 1.0)",
+            recency_weight
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
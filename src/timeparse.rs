@@ -0,0 +1,186 @@
+//! Absolute and relative time specifiers, and humanized display of times.
+//!
+//! Every CLI flag that takes a time (`--since`, `--before`, `--as-of`,
+//! `--older-than`) accepts:
+//!
+//! - an RFC3339 timestamp (`2024-06-01T12:00:00Z`) or a date (`2024-06-01`,
+//!   midnight UTC)
+//! - `now`, `today` or `yesterday` (midnight UTC)
+//! - a duration before now, compact (`24h`, `2w`) or in words
+//!   (`3 days`, `an hour`), optionally followed by `ago`
+//!
+//! Months count as 30 days and years as 365 days.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+
+use crate::config::retention::parse_ttl;
+
+/// Parse `value` as a time, with relative values measured from now.
+///
+/// Returns `None` if `value` is not a time specifier.
+pub fn parse(value: &str) -> Option<DateTime<Utc>> {
+    parse_at(value, Utc::now())
+}
+
+/// Parse `value` as a time, with relative values measured from `now`.
+///
+/// Returns `None` if `value` is not a time specifier.
+pub fn parse_at(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date.and_time(NaiveTime::MIN).and_utc());
+    }
+
+    let value = value.to_ascii_lowercase();
+    let midnight = now.date_naive().and_time(NaiveTime::MIN).and_utc();
+    match value.as_str() {
+        "now" => return Some(now),
+        "today" => return Some(midnight),
+        "yesterday" => return Some(midnight - Duration::days(1)),
+        _ => {}
+    }
+
+    let duration = value.strip_suffix("ago").unwrap_or(&value).trim_end();
+    parse_duration(duration).and_then(|d| now.checked_sub_signed(d))
+}
+
+/// Duration such as `2w`, `2 weeks` or `a day`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    if let Ok(Some(duration)) = parse_ttl(value) {
+        return Some(duration);
+    }
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .filter(|&i| i > 0)
+        .unwrap_or_else(|| value.find(' ').unwrap_or(value.len()));
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = match amount.trim() {
+        "a" | "an" => 1,
+        n => n.parse().ok().filter(|&n| n > 0)?,
+    };
+    let days = |n: i64| amount.checked_mul(n).and_then(Duration::try_days);
+    match unit.trim().trim_end_matches('s') {
+        "sec" | "second" => Duration::try_seconds(amount),
+        "min" | "minute" => Duration::try_minutes(amount),
+        "hr" | "hour" => Duration::try_hours(amount),
+        "day" => Duration::try_days(amount),
+        "week" => Duration::try_weeks(amount),
+        "month" => days(30),
+        "year" => days(365),
+        _ => None,
+    }
+}
+
+/// `time` relative to `now` in words: "just now", "3 days ago" or
+/// "in 2 hours".
+pub fn humanize(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let delta = now.signed_duration_since(time);
+    let seconds = delta.num_seconds().unsigned_abs();
+    if seconds < 60 {
+        return "just now".to_string();
+    }
+
+    let (amount, unit) = match seconds / 60 {
+        minutes if minutes < 60 => (minutes, "minute"),
+        minutes if minutes < 60 * 24 => (minutes / 60, "hour"),
+        minutes => match minutes / (60 * 24) {
+            days if days < 30 => (days, "day"),
+            days if days < 365 => (days / 30, "month"),
+            days => (days / 365, "year"),
+        },
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    if delta.num_seconds() < 0 {
+        format!("in {} {}{}", amount, unit, plural)
+    } else {
+        format!("{} {}{} ago", amount, unit, plural)
+    }
+}
+
+/// [`humanize`] for an RFC3339 timestamp; other text is returned unchanged.
+pub fn humanize_rfc3339(timestamp: &str, now: DateTime<Utc>) -> String {
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(time) => humanize(time.with_timezone(&Utc), now),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-15T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn at(value: &str) -> String {
+        parse_at(value, now()).unwrap().to_rfc3339()
+    }
+
+    #[test]
+    fn test_absolute_times() {
+        assert_eq!(at("2024-06-01T12:00:00+02:00"), "2024-06-01T10:00:00+00:00");
+        assert_eq!(at("2024-06-01"), "2024-06-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_named_days() {
+        assert_eq!(at("now"), "2024-06-15T12:30:00+00:00");
+        assert_eq!(at("Today"), "2024-06-15T00:00:00+00:00");
+        assert_eq!(at("yesterday"), "2024-06-14T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_relative_durations() {
+        assert_eq!(at("24h"), "2024-06-14T12:30:00+00:00");
+        assert_eq!(at("2w"), "2024-06-01T12:30:00+00:00");
+        assert_eq!(at("2w ago"), "2024-06-01T12:30:00+00:00");
+        assert_eq!(at("3 days ago"), "2024-06-12T12:30:00+00:00");
+        assert_eq!(at("3days"), "2024-06-12T12:30:00+00:00");
+        assert_eq!(at("an hour ago"), "2024-06-15T11:30:00+00:00");
+        assert_eq!(at("1 month"), "2024-05-16T12:30:00+00:00");
+        assert_eq!(at("1 year ago"), "2023-06-16T12:30:00+00:00");
+    }
+
+    #[test]
+    fn test_invalid_specifiers() {
+        for value in [
+            "",
+            "never",
+            "0d",
+            "-3 days",
+            "3 fortnights",
+            "ago",
+            "days ago",
+        ] {
+            assert_eq!(parse_at(value, now()), None, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_humanize() {
+        let ago = |d: Duration| humanize(now() - d, now());
+        assert_eq!(ago(Duration::seconds(20)), "just now");
+        assert_eq!(ago(Duration::minutes(1)), "1 minute ago");
+        assert_eq!(ago(Duration::hours(5)), "5 hours ago");
+        assert_eq!(ago(Duration::days(3)), "3 days ago");
+        assert_eq!(ago(Duration::days(65)), "2 months ago");
+        assert_eq!(ago(Duration::days(800)), "2 years ago");
+        assert_eq!(ago(Duration::hours(-2)), "in 2 hours");
+    }
+
+    #[test]
+    fn test_humanize_rfc3339_keeps_other_text() {
+        assert_eq!(
+            humanize_rfc3339("2024-06-12T12:30:00+00:00", now()),
+            "3 days ago"
+        );
+        assert_eq!(humanize_rfc3339("unknown", now()), "unknown");
+    }
+}
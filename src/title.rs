@@ -0,0 +1,86 @@
+//! Titles derived from memory content.
+//!
+//! Every memory gets a short title so listings stay scannable when the
+//! content is long: the one given with `--title`, or else the first
+//! sentence of the content. Titles are indexed for keyword search alongside
+//! the indexed metadata fields, which rank above the content.
+
+/// Longest derived title, in characters (before the trailing ellipsis).
+pub const MAX_TITLE_CHARS: usize = 80;
+
+/// Title for `content`: its first sentence, without a Markdown heading
+/// marker or a trailing period, shortened at a word boundary to
+/// [`MAX_TITLE_CHARS`]. `None` if the content is blank.
+pub fn derive(content: &str) -> Option<String> {
+    let line = content
+        .lines()
+        .map(|line| line.trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())?;
+    let sentence = first_sentence(line).trim_end_matches(['.', ':', ';', ',']);
+    if sentence.chars().count() <= MAX_TITLE_CHARS {
+        return Some(sentence.to_string());
+    }
+    let cut = sentence
+        .char_indices()
+        .nth(MAX_TITLE_CHARS)
+        .map_or(sentence.len(), |(index, _)| index);
+    let head = &sentence[..cut];
+    let head = match head.rfind(char::is_whitespace) {
+        Some(space) if space > 0 => &head[..space],
+        _ => head,
+    };
+    Some(format!(
+        "{}…",
+        head.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+    ))
+}
+
+/// `line` up to and including the first `.`, `!` or `?` that ends a
+/// sentence (followed by whitespace), or all of it.
+fn first_sentence(line: &str) -> &str {
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if matches!(c, '.' | '!' | '?')
+            && chars.peek().is_some_and(|&(_, next)| next.is_whitespace())
+        {
+            return &line[..index + c.len_utf8()];
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_takes_first_sentence() {
+        assert_eq!(
+            derive("Use SQLite for storage. It needs no server.").as_deref(),
+            Some("Use SQLite for storage")
+        );
+        assert_eq!(
+            derive("Why does v1.2 fail? The cache is stale.").as_deref(),
+            Some("Why does v1.2 fail?")
+        );
+        assert_eq!(
+            derive("\n# Deployment notes\n\nDeploy on Fridays.").as_deref(),
+            Some("Deployment notes")
+        );
+        assert_eq!(derive("  \n\t"), None);
+    }
+
+    #[test]
+    fn test_derive_shortens_long_sentences() {
+        let content = "word ".repeat(40);
+        let title = derive(&content).unwrap();
+        assert!(title.ends_with("word…"));
+        assert!(title.chars().count() <= MAX_TITLE_CHARS + 1);
+
+        let unbroken = "x".repeat(100);
+        assert_eq!(
+            derive(&unbroken).unwrap(),
+            format!("{}…", "x".repeat(MAX_TITLE_CHARS))
+        );
+    }
+}
@@ -0,0 +1,453 @@
+//! Watch mode: ingest memories from a growing JSONL file or a notes directory.
+//!
+//! The watcher polls its source on a fixed interval (no filesystem event
+//! backend, matching the synchronous design):
+//! - JSONL file: new complete lines since the last poll are parsed as memory
+//!   records (same shape as `vipune import`). If the file shrinks, it is
+//!   treated as rotated and read again from the start.
+//! - Directory: new `.md`/`.markdown` files are each added as one memory,
+//!   with the file path recorded in metadata. The watcher remembers each
+//!   file's memory, so a modified file updates it in place.
+//!
+//! Every new entry goes through conflict detection; near-duplicates are
+//! skipped.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::errors::Error;
+use crate::import::JsonMemory;
+use crate::import::records::metadata_to_string;
+use crate::memory::MemoryStore;
+use crate::memory_types::AddResult;
+use crate::sqlite::MemoryKind;
+
+/// Counts accumulated by a watcher.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WatchStats {
+    /// Entries stored as new memories.
+    pub ingested: usize,
+    /// Modified files whose memories were updated.
+    pub updated: usize,
+    /// Entries skipped as duplicates or invalid.
+    pub skipped: usize,
+}
+
+/// A single entry discovered in the watched source.
+#[derive(Debug, PartialEq)]
+struct WatchEntry {
+    content: String,
+    project_id: Option<String>,
+    metadata: Option<String>,
+    kind: MemoryKind,
+    /// File the entry was read from, in directory mode.
+    path: Option<PathBuf>,
+    /// Memory stored from that file by an earlier poll.
+    memory_id: Option<String>,
+}
+
+/// A markdown file the watcher has read.
+#[derive(Debug)]
+struct SeenFile {
+    modified: SystemTime,
+    /// Memory stored from the file, if it was stored.
+    memory_id: Option<String>,
+}
+
+enum Source {
+    JsonlFile {
+        path: PathBuf,
+        offset: u64,
+    },
+    Directory {
+        path: PathBuf,
+        seen: HashMap<PathBuf, SeenFile>,
+    },
+}
+
+/// Polling watcher over a JSONL file or a directory of markdown notes.
+pub struct Watcher {
+    source: Source,
+}
+
+impl Watcher {
+    /// Create a watcher for `path`, choosing directory or JSONL mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the path does not exist.
+    pub fn new(path: &Path) -> Result<Self, Error> {
+        if !path.exists() {
+            return Err(Error::InvalidInput(format!(
+                "Watch path does not exist: {}",
+                path.display()
+            )));
+        }
+        let source = if path.is_dir() {
+            Source::Directory {
+                path: path.to_path_buf(),
+                seen: HashMap::new(),
+            }
+        } else {
+            Source::JsonlFile {
+                path: path.to_path_buf(),
+                offset: 0,
+            }
+        };
+        Ok(Self { source })
+    }
+
+    /// Ingest everything that appeared since the previous poll.
+    ///
+    /// Returns counts for this poll only.
+    ///
+    /// Entries that cannot be stored (rejected content, or an embedding or
+    /// database failure) are logged and counted as skipped, since the source
+    /// has already moved past them.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the source cannot be read.
+    pub fn poll(&mut self, store: &mut MemoryStore, project_id: &str) -> Result<WatchStats, Error> {
+        let (entries, mut stats) = match &mut self.source {
+            Source::JsonlFile { path, offset } => read_jsonl_entries(path, offset)?,
+            Source::Directory { path, seen } => {
+                (read_markdown_entries(path, seen)?, WatchStats::default())
+            }
+        };
+
+        for entry in entries {
+            if let Err(e) = store.prepare_content(&entry.content) {
+                log::warn!("Skipping entry: {}", e);
+                stats.skipped += 1;
+                continue;
+            }
+            // A file whose memory still exists updates it; the rest are added
+            if let Some(id) = &entry.memory_id
+                && store.get(id).is_ok_and(|memory| memory.is_some())
+            {
+                match store.update(id, &entry.content) {
+                    Ok(()) => stats.updated += 1,
+                    Err(e) => {
+                        log::warn!("Skipping entry: {}", e);
+                        stats.skipped += 1;
+                    }
+                }
+                continue;
+            }
+            let project = entry.project_id.as_deref().unwrap_or(project_id);
+            match store.add_with_kind(
+                project,
+                &entry.content,
+                entry.metadata.as_deref(),
+                entry.kind,
+                false,
+            ) {
+                Ok(AddResult::Added { id }) => {
+                    stats.ingested += 1;
+                    if let Some(path) = &entry.path {
+                        self.remember(path, id);
+                    }
+                }
+                Ok(AddResult::Conflicts { .. }) => stats.skipped += 1,
+                Err(e) => {
+                    log::warn!("Skipping entry: {}", e);
+                    stats.skipped += 1;
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Record that the markdown file `path` was stored as memory `id`.
+    fn remember(&mut self, path: &Path, id: String) {
+        if let Source::Directory { seen, .. } = &mut self.source
+            && let Some(file) = seen.get_mut(path)
+        {
+            file.memory_id = Some(id);
+        }
+    }
+}
+
+/// Read complete lines appended since `offset`, advancing it past them.
+///
+/// Invalid lines are reported and counted as skipped. Records name their
+/// project with `project_id`, or `user_id` as in `vipune import`.
+fn read_jsonl_entries(
+    path: &Path,
+    offset: &mut u64,
+) -> Result<(Vec<WatchEntry>, WatchStats), Error> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    if len < *offset {
+        // File was truncated or rotated; start over
+        *offset = 0;
+    }
+    file.seek(SeekFrom::Start(*offset))?;
+    let mut buf = String::new();
+    file.read_to_string(&mut buf)?;
+
+    // Only consume up to the last newline so partially written lines are retried
+    let complete = match buf.rfind('\n') {
+        Some(pos) => &buf[..=pos],
+        None => "",
+    };
+    *offset += complete.len() as u64;
+
+    let mut entries = Vec::new();
+    let mut stats = WatchStats::default();
+    for line in complete.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str::<JsonMemory>(line) {
+            Ok(record) => {
+                let project_id = record.source_project().map(str::to_string);
+                let kind = record.memory_kind();
+                match metadata_to_string(record.metadata) {
+                    Ok(metadata) => entries.push(WatchEntry {
+                        content: record.content,
+                        project_id,
+                        metadata,
+                        kind,
+                        path: None,
+                        memory_id: None,
+                    }),
+                    Err(e) => {
+                        log::warn!("Skipping line with invalid metadata: {}", e);
+                        stats.skipped += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                log::warn!("Skipping invalid JSON line: {}", e);
+                stats.skipped += 1;
+            }
+        }
+    }
+    Ok((entries, stats))
+}
+
+/// Collect markdown files that are new or modified since they were last
+/// seen, with the memory stored from a modified one.
+fn read_markdown_entries(
+    dir: &Path,
+    seen: &mut HashMap<PathBuf, SeenFile>,
+) -> Result<Vec<WatchEntry>, Error> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && is_markdown(p))
+        .collect();
+    paths.sort();
+
+    let mut entries = Vec::new();
+    for path in paths {
+        let modified = std::fs::metadata(&path)?.modified()?;
+        let memory_id = match seen.get(&path) {
+            Some(file) if file.modified == modified => continue,
+            Some(file) => file.memory_id.clone(),
+            None => None,
+        };
+        seen.insert(
+            path.clone(),
+            SeenFile {
+                modified,
+                memory_id: memory_id.clone(),
+            },
+        );
+
+        let content = std::fs::read_to_string(&path)?;
+        let metadata = serde_json::json!({ "path": path.display().to_string() });
+        entries.push(WatchEntry {
+            content,
+            project_id: None,
+            metadata: Some(metadata.to_string()),
+            kind: MemoryKind::Other,
+            path: Some(path),
+            memory_id,
+        });
+    }
+    Ok(entries)
+}
+
+fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("md") | Some("markdown")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_jsonl_reads_only_new_complete_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("log.jsonl");
+        std::fs::write(&path, "{\"content\": \"first\"}\n{\"content\": \"partial").unwrap();
+
+        let mut offset = 0;
+        let (entries, stats) = read_jsonl_entries(&path, &mut offset).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "first");
+        assert_eq!(stats.skipped, 0);
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        writeln!(file, "\"}}").unwrap();
+        let (entries, _) = read_jsonl_entries(&path, &mut offset).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "partial");
+
+        let (entries, _) = read_jsonl_entries(&path, &mut offset).unwrap();
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn test_jsonl_counts_invalid_lines_as_skipped() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("log.jsonl");
+        std::fs::write(
+            &path,
+            "not json\n{\"content\": \"ok\", \"metadata\": {\"k\": 1}}\n",
+        )
+        .unwrap();
+
+        let mut offset = 0;
+        let (entries, stats) = read_jsonl_entries(&path, &mut offset).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].metadata.as_deref(), Some(r#"{"k":1}"#));
+        assert_eq!(stats.skipped, 1);
+    }
+
+    #[test]
+    fn test_jsonl_project_falls_back_to_user_id() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("log.jsonl");
+        std::fs::write(
+            &path,
+            concat!(
+                "{\"content\": \"a\", \"user_id\": \"alice\"}\n",
+                "{\"content\": \"b\", \"project_id\": \"p\", \"user_id\": \"alice\"}\n",
+                "{\"content\": \"c\", \"project_id\": \" \"}\n",
+            ),
+        )
+        .unwrap();
+
+        let mut offset = 0;
+        let (entries, _) = read_jsonl_entries(&path, &mut offset).unwrap();
+        let projects: Vec<Option<&str>> = entries.iter().map(|e| e.project_id.as_deref()).collect();
+        assert_eq!(projects, vec![Some("alice"), Some("p"), None]);
+    }
+
+    #[test]
+    fn test_poll_skips_entries_that_fail_to_store() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("log.jsonl");
+        std::fs::write(&path, "{\"content\": \"one\"}\n{\"content\": \"two\"}\n").unwrap();
+        // Offline without a cached model, so every add fails to embed
+        let config = crate::config::Config {
+            offline: true,
+            ..Default::default()
+        };
+        let mut store = MemoryStore::new_in_memory("missing/model", config).unwrap();
+
+        let mut watcher = Watcher::new(&path).unwrap();
+        let stats = watcher.poll(&mut store, "p").unwrap();
+        assert_eq!(
+            stats,
+            WatchStats {
+                ingested: 0,
+                updated: 0,
+                skipped: 2
+            }
+        );
+        assert_eq!(
+            watcher.poll(&mut store, "p").unwrap(),
+            WatchStats::default()
+        );
+    }
+
+    #[test]
+    fn test_jsonl_restarts_after_truncation() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("log.jsonl");
+        std::fs::write(&path, "{\"content\": \"a long first entry\"}\n").unwrap();
+
+        let mut offset = 0;
+        read_jsonl_entries(&path, &mut offset).unwrap();
+        std::fs::write(&path, "{\"content\": \"b\"}\n").unwrap();
+        let (entries, _) = read_jsonl_entries(&path, &mut offset).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "b");
+    }
+
+    #[test]
+    fn test_markdown_directory_detects_new_files_once() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.md"), "# Note A").unwrap();
+        std::fs::write(dir.path().join("ignored.txt"), "not markdown").unwrap();
+
+        let mut seen = HashMap::new();
+        let entries = read_markdown_entries(dir.path(), &mut seen).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "# Note A");
+        assert!(entries[0].metadata.as_deref().unwrap().contains("a.md"));
+
+        assert!(
+            read_markdown_entries(dir.path(), &mut seen)
+                .unwrap()
+                .is_empty()
+        );
+
+        std::fs::write(dir.path().join("b.markdown"), "Note B").unwrap();
+        let entries = read_markdown_entries(dir.path(), &mut seen).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "Note B");
+        assert!(entries[0].memory_id.is_none());
+    }
+
+    #[test]
+    fn test_modified_markdown_file_carries_its_memory() {
+        let dir = TempDir::new().unwrap();
+        let note = dir.path().join("a.md");
+        std::fs::write(&note, "# Note A").unwrap();
+        let mut store = MemoryStore::open_without_embedder(
+            Path::new(crate::memory::store::IN_MEMORY_DB_PATH),
+            crate::config::Config::default(),
+        )
+        .unwrap();
+        let id = store.db.insert("p", "# Note A", &[0.1; 384], None).unwrap();
+
+        // The first poll cannot embed the note; pretend it stored it as `id`
+        let mut watcher = Watcher::new(dir.path()).unwrap();
+        assert_eq!(watcher.poll(&mut store, "p").unwrap().skipped, 1);
+        watcher.remember(&note, id.clone());
+
+        let Source::Directory { seen, .. } = &mut watcher.source else {
+            panic!("expected directory mode");
+        };
+        let touch = |seen: &mut HashMap<PathBuf, SeenFile>| {
+            seen.get_mut(&note).unwrap().modified = SystemTime::UNIX_EPOCH;
+        };
+        touch(seen);
+        let entries = read_markdown_entries(dir.path(), seen).unwrap();
+        assert_eq!(entries[0].memory_id.as_deref(), Some(id.as_str()));
+
+        // The update fails without a model, but no second memory is added
+        touch(seen);
+        let stats = watcher.poll(&mut store, "p").unwrap();
+        assert_eq!((stats.ingested, stats.updated, stats.skipped), (0, 0, 1));
+        assert_eq!(store.db.list("p", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_watcher_rejects_missing_path() {
+        assert!(Watcher::new(Path::new("/nonexistent/vipune/watch")).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 //! Integration tests testing vipune library API from external crate perspective.
 
+// Exercises the deprecated search/search_hybrid wrappers until they are removed
+#![allow(deprecated)]
+
 use std::env;
 use std::path::PathBuf;
 